@@ -0,0 +1,821 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! Blocking-to-async adapter
+//!
+//! [`BlockingAdapter`] wraps any implementation of the blocking crypto traits
+//! and exposes it through the async traits of this crate. Each async method
+//! runs the blocking call to completion and returns a ready future, so the
+//! adapter never yields. It exists so that software backends and drivers that
+//! have not been ported yet can be plugged into async callers unchanged.
+//!
+//! Contexts returned by an adapted controller are themselves wrapped, and the
+//! owned API recovers the wrapped controller:
+//!
+//! ```rust,ignore
+//! let controller = BlockingAdapter::new(RustCryptoController::new());
+//! let context = controller.init(Sha2_256).await?;
+//! let context = context.update(b"abc").await?;
+//! let (digest, controller) = context.finalize().await?;
+//! let controller: RustCryptoController = controller.into_inner();
+//! ```
+
+use openprot_hal_blocking as blocking;
+use openprot_hal_blocking::digest::DigestAlgorithm;
+
+use crate::{cipher, digest, ecdsa, mac};
+
+/// Adapter exposing a blocking implementation through the async HAL traits
+#[derive(Debug, Default)]
+pub struct BlockingAdapter<T>(T);
+
+impl<T> BlockingAdapter<T> {
+    /// Wrap a blocking implementation
+    pub const fn new(inner: T) -> Self {
+        Self(inner)
+    }
+
+    /// Borrow the wrapped implementation
+    pub fn inner(&self) -> &T {
+        &self.0
+    }
+
+    /// Mutably borrow the wrapped implementation
+    pub fn inner_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+
+    /// Unwrap the adapter, returning the blocking implementation
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+// Digest
+
+impl<T: blocking::digest::ErrorType> blocking::digest::ErrorType for BlockingAdapter<T> {
+    type Error = T::Error;
+}
+
+impl<A, T> digest::DigestInit<A> for BlockingAdapter<T>
+where
+    A: DigestAlgorithm,
+    T: blocking::digest::DigestInit<A>,
+{
+    type OpContext<'a>
+        = BlockingAdapter<T::OpContext<'a>>
+    where
+        Self: 'a;
+    type Output = T::Output;
+
+    async fn init(&mut self, init_params: A) -> Result<Self::OpContext<'_>, Self::Error> {
+        self.0.init(init_params).map(BlockingAdapter)
+    }
+}
+
+impl<T: blocking::digest::DigestCtrlReset> digest::DigestCtrlReset for BlockingAdapter<T> {
+    async fn reset(&mut self) -> Result<(), Self::Error> {
+        self.0.reset()
+    }
+}
+
+impl<T: blocking::digest::DigestOp> digest::DigestOp for BlockingAdapter<T> {
+    type Output = T::Output;
+
+    async fn update(&mut self, input: &[u8]) -> Result<(), Self::Error> {
+        self.0.update(input)
+    }
+
+    async fn finalize(self) -> Result<Self::Output, Self::Error> {
+        self.0.finalize()
+    }
+}
+
+impl<A, T> digest::owned::DigestInit<A> for BlockingAdapter<T>
+where
+    A: DigestAlgorithm,
+    T: blocking::digest::owned::DigestInit<A>,
+{
+    type Context = BlockingAdapter<T::Context>;
+    type Output = T::Output;
+
+    async fn init(self, init_params: A) -> Result<Self::Context, Self::Error> {
+        self.0.init(init_params).map(BlockingAdapter)
+    }
+}
+
+impl<T: blocking::digest::owned::DigestOp> digest::owned::DigestOp for BlockingAdapter<T> {
+    type Output = T::Output;
+    type Controller = BlockingAdapter<T::Controller>;
+
+    async fn update(self, data: &[u8]) -> Result<Self, Self::Error> {
+        self.0.update(data).map(BlockingAdapter)
+    }
+
+    async fn finalize(self) -> Result<(Self::Output, Self::Controller), Self::Error> {
+        let (output, controller) = self.0.finalize()?;
+        Ok((output, BlockingAdapter(controller)))
+    }
+
+    async fn cancel(self) -> Self::Controller {
+        BlockingAdapter(self.0.cancel())
+    }
+}
+
+// MAC
+
+impl<T: blocking::mac::ErrorType> blocking::mac::ErrorType for BlockingAdapter<T> {
+    type Error = T::Error;
+}
+
+impl<A, T> mac::MacInit<A> for BlockingAdapter<T>
+where
+    A: blocking::mac::MacAlgorithm,
+    T: blocking::mac::MacInit<A>,
+{
+    type Key = T::Key;
+    type OpContext<'a>
+        = BlockingAdapter<T::OpContext<'a>>
+    where
+        Self: 'a;
+
+    async fn init<'a>(
+        &'a mut self,
+        algo: A,
+        key: Self::Key,
+    ) -> Result<Self::OpContext<'a>, Self::Error> {
+        self.0.init(algo, key).map(BlockingAdapter)
+    }
+}
+
+impl<T: blocking::mac::MacCtrlReset> mac::MacCtrlReset for BlockingAdapter<T> {
+    async fn reset(&mut self) -> Result<(), Self::Error> {
+        self.0.reset()
+    }
+}
+
+impl<T: blocking::mac::MacOp> mac::MacOp for BlockingAdapter<T> {
+    type Output = T::Output;
+
+    async fn update(&mut self, input: &[u8]) -> Result<(), Self::Error> {
+        self.0.update(input)
+    }
+
+    async fn finalize(self) -> Result<Self::Output, Self::Error> {
+        self.0.finalize()
+    }
+}
+
+impl<A, T> mac::owned::MacInit<A> for BlockingAdapter<T>
+where
+    A: blocking::mac::MacAlgorithm,
+    T: blocking::mac::owned::MacInit<A>,
+{
+    type Key = T::Key;
+    type Context = BlockingAdapter<T::Context>;
+    type Output = T::Output;
+
+    async fn init(self, algorithm: A, key: Self::Key) -> Result<Self::Context, Self::Error> {
+        self.0.init(algorithm, key).map(BlockingAdapter)
+    }
+}
+
+impl<T: blocking::mac::owned::MacOp> mac::owned::MacOp for BlockingAdapter<T> {
+    type Output = T::Output;
+    type Controller = BlockingAdapter<T::Controller>;
+
+    async fn update(self, data: &[u8]) -> Result<Self, Self::Error> {
+        self.0.update(data).map(BlockingAdapter)
+    }
+
+    async fn finalize(self) -> Result<(Self::Output, Self::Controller), Self::Error> {
+        let (output, controller) = self.0.finalize()?;
+        Ok((output, BlockingAdapter(controller)))
+    }
+
+    async fn cancel(self) -> Self::Controller {
+        BlockingAdapter(self.0.cancel())
+    }
+}
+
+// Cipher
+
+impl<T: blocking::cipher::ErrorType> blocking::cipher::ErrorType for BlockingAdapter<T> {
+    type Error = T::Error;
+}
+
+impl<T: blocking::cipher::SymmetricCipher> blocking::cipher::SymmetricCipher
+    for BlockingAdapter<T>
+{
+    type Key = T::Key;
+    type Nonce = T::Nonce;
+    type PlainText = T::PlainText;
+    type CipherText = T::CipherText;
+}
+
+impl<M, T> cipher::CipherInit<M> for BlockingAdapter<T>
+where
+    M: blocking::cipher::CipherMode,
+    T: blocking::cipher::CipherInit<M>,
+{
+    type CipherContext<'a>
+        = BlockingAdapter<T::CipherContext<'a>>
+    where
+        Self: 'a;
+
+    async fn init<'a>(
+        &'a mut self,
+        key: &Self::Key,
+        nonce: &Self::Nonce,
+        mode: M,
+    ) -> Result<Self::CipherContext<'a>, Self::Error> {
+        self.0.init(key, nonce, mode).map(BlockingAdapter)
+    }
+}
+
+impl<M, T> cipher::CipherOp<M> for BlockingAdapter<T>
+where
+    M: blocking::cipher::CipherMode,
+    T: blocking::cipher::CipherOp<M>,
+{
+    async fn encrypt(
+        &mut self,
+        plaintext: Self::PlainText,
+    ) -> Result<Self::CipherText, Self::Error> {
+        self.0.encrypt(plaintext)
+    }
+
+    async fn decrypt(
+        &mut self,
+        ciphertext: Self::CipherText,
+    ) -> Result<Self::PlainText, Self::Error> {
+        self.0.decrypt(ciphertext)
+    }
+}
+
+impl<T: blocking::cipher::ResettableCipherOp> cipher::ResettableCipherOp for BlockingAdapter<T> {
+    async fn reset(&mut self) -> Result<(), Self::Error> {
+        self.0.reset()
+    }
+}
+
+impl<T: blocking::cipher::AeadCipherOp> cipher::AeadCipherOp for BlockingAdapter<T> {
+    type AssociatedData = T::AssociatedData;
+    type Tag = T::Tag;
+
+    async fn encrypt_aead(
+        &mut self,
+        plaintext: Self::PlainText,
+        associated_data: Self::AssociatedData,
+    ) -> Result<(Self::CipherText, Self::Tag), Self::Error> {
+        self.0.encrypt_aead(plaintext, associated_data)
+    }
+
+    async fn decrypt_aead(
+        &mut self,
+        ciphertext: Self::CipherText,
+        associated_data: Self::AssociatedData,
+        tag: Self::Tag,
+    ) -> Result<Self::PlainText, Self::Error> {
+        self.0.decrypt_aead(ciphertext, associated_data, tag)
+    }
+}
+
+// ECDSA
+
+impl<T: blocking::ecdsa::ErrorType> blocking::ecdsa::ErrorType for BlockingAdapter<T> {
+    type Error = T::Error;
+}
+
+impl<C, T> ecdsa::EcdsaKeyGen<C> for BlockingAdapter<T>
+where
+    C: blocking::ecdsa::Curve,
+    T: blocking::ecdsa::EcdsaKeyGen<C>,
+{
+    type PrivateKey = T::PrivateKey;
+    type PublicKey = T::PublicKey;
+
+    async fn generate_keypair<R>(
+        &mut self,
+        rng: &mut R,
+    ) -> Result<(Self::PrivateKey, Self::PublicKey), Self::Error>
+    where
+        R: rand_core::RngCore + rand_core::CryptoRng,
+    {
+        self.0.generate_keypair(rng)
+    }
+}
+
+impl<C, T> ecdsa::EcdsaSign<C> for BlockingAdapter<T>
+where
+    C: blocking::ecdsa::Curve,
+    T: blocking::ecdsa::EcdsaSign<C>,
+{
+    type PrivateKey = T::PrivateKey;
+    type Signature = T::Signature;
+
+    async fn sign<R>(
+        &mut self,
+        private_key: &Self::PrivateKey,
+        digest: <<C as blocking::ecdsa::Curve>::DigestType as DigestAlgorithm>::Digest,
+        rng: &mut R,
+    ) -> Result<Self::Signature, Self::Error>
+    where
+        R: rand_core::RngCore + rand_core::CryptoRng,
+    {
+        self.0.sign(private_key, digest, rng)
+    }
+}
+
+impl<C, T> ecdsa::EcdsaVerify<C> for BlockingAdapter<T>
+where
+    C: blocking::ecdsa::Curve,
+    T: blocking::ecdsa::EcdsaVerify<C>,
+{
+    type PublicKey = T::PublicKey;
+    type Signature = T::Signature;
+
+    async fn verify(
+        &mut self,
+        public_key: &Self::PublicKey,
+        digest: <<C as blocking::ecdsa::Curve>::DigestType as DigestAlgorithm>::Digest,
+        signature: &Self::Signature,
+    ) -> Result<(), Self::Error> {
+        self.0.verify(public_key, digest, signature)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use core::convert::Infallible;
+    use futures::executor::block_on;
+    use openprot_hal_blocking::digest::{Digest, Sha2_256};
+    use openprot_hal_blocking::ecdsa::{P256PublicKey, P256Signature, PublicKey, P256};
+    use openprot_hal_blocking::mac::HmacSha2_256;
+
+    /// Deterministic counter RNG, good enough to drive key generation in tests
+    struct CounterRng(u8);
+
+    impl rand_core::RngCore for CounterRng {
+        fn next_u32(&mut self) -> u32 {
+            let mut buf = [0u8; 4];
+            self.fill_bytes(&mut buf);
+            u32::from_le_bytes(buf)
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            let mut buf = [0u8; 8];
+            self.fill_bytes(&mut buf);
+            u64::from_le_bytes(buf)
+        }
+
+        fn fill_bytes(&mut self, dst: &mut [u8]) {
+            for byte in dst.iter_mut() {
+                self.0 = self.0.wrapping_add(1);
+                *byte = self.0;
+            }
+        }
+    }
+
+    impl rand_core::CryptoRng for CounterRng {}
+
+    /// Error reported by the fake cipher and ECDSA implementations
+    #[derive(Debug, PartialEq)]
+    struct FakeError;
+
+    impl blocking::cipher::Error for FakeError {
+        fn kind(&self) -> blocking::cipher::ErrorKind {
+            blocking::cipher::ErrorKind::InvalidInput
+        }
+    }
+
+    impl blocking::ecdsa::Error for FakeError {
+        fn kind(&self) -> blocking::ecdsa::ErrorKind {
+            blocking::ecdsa::ErrorKind::InvalidSignature
+        }
+    }
+
+    /// Running state of the toy fold hash shared by the fake digest and MAC
+    #[derive(Clone, Copy, Default)]
+    struct Fold {
+        words: [u32; 8],
+        count: usize,
+    }
+
+    impl Fold {
+        fn absorb(&mut self, data: &[u8]) {
+            for &byte in data {
+                let word = &mut self.words[self.count % 8];
+                *word = word.wrapping_mul(31).wrapping_add(u32::from(byte));
+                self.count += 1;
+            }
+        }
+
+        fn digest(data: &[u8]) -> Digest<8> {
+            let mut fold = Self::default();
+            fold.absorb(data);
+            Digest::new(fold.words)
+        }
+    }
+
+    /// Blocking controller implementing the digest and MAC traits with [`Fold`]
+    ///
+    /// It counts finalized operations so tests can tell that the controller
+    /// recovered through the adapter is the one that did the work.
+    #[derive(Debug, Default)]
+    struct FakeController {
+        finalized: usize,
+    }
+
+    impl blocking::digest::ErrorType for FakeController {
+        type Error = Infallible;
+    }
+
+    impl blocking::digest::DigestInit<Sha2_256> for FakeController {
+        type OpContext<'a> = FakeScopedContext<'a>;
+        type Output = Digest<8>;
+
+        fn init(&mut self, _init_params: Sha2_256) -> Result<Self::OpContext<'_>, Self::Error> {
+            Ok(FakeScopedContext {
+                controller: self,
+                fold: Fold::default(),
+            })
+        }
+    }
+
+    struct FakeScopedContext<'a> {
+        controller: &'a mut FakeController,
+        fold: Fold,
+    }
+
+    impl blocking::digest::ErrorType for FakeScopedContext<'_> {
+        type Error = Infallible;
+    }
+
+    impl blocking::digest::DigestOp for FakeScopedContext<'_> {
+        type Output = Digest<8>;
+
+        fn update(&mut self, input: &[u8]) -> Result<(), Self::Error> {
+            self.fold.absorb(input);
+            Ok(())
+        }
+
+        fn finalize(self) -> Result<Self::Output, Self::Error> {
+            self.controller.finalized += 1;
+            Ok(Digest::new(self.fold.words))
+        }
+    }
+
+    impl blocking::digest::owned::DigestInit<Sha2_256> for FakeController {
+        type Context = FakeOwnedContext;
+        type Output = Digest<8>;
+
+        fn init(self, _init_params: Sha2_256) -> Result<Self::Context, Self::Error> {
+            Ok(FakeOwnedContext {
+                controller: self,
+                fold: Fold::default(),
+            })
+        }
+    }
+
+    impl blocking::mac::ErrorType for FakeController {
+        type Error = Infallible;
+    }
+
+    /// Key handle accepted by the fake MAC
+    struct FakeKey(&'static [u8]);
+
+    impl blocking::mac::KeyHandle for FakeKey {}
+
+    impl blocking::mac::owned::MacInit<HmacSha2_256> for FakeController {
+        type Key = FakeKey;
+        type Context = FakeOwnedContext;
+        type Output = Digest<8>;
+
+        fn init(
+            self,
+            _algorithm: HmacSha2_256,
+            key: Self::Key,
+        ) -> Result<Self::Context, Self::Error> {
+            let mut fold = Fold::default();
+            fold.absorb(key.0);
+            Ok(FakeOwnedContext {
+                controller: self,
+                fold,
+            })
+        }
+    }
+
+    struct FakeOwnedContext {
+        controller: FakeController,
+        fold: Fold,
+    }
+
+    impl blocking::digest::ErrorType for FakeOwnedContext {
+        type Error = Infallible;
+    }
+
+    impl blocking::digest::owned::DigestOp for FakeOwnedContext {
+        type Output = Digest<8>;
+        type Controller = FakeController;
+
+        fn update(mut self, data: &[u8]) -> Result<Self, Self::Error> {
+            self.fold.absorb(data);
+            Ok(self)
+        }
+
+        fn finalize(mut self) -> Result<(Self::Output, Self::Controller), Self::Error> {
+            self.controller.finalized += 1;
+            Ok((Digest::new(self.fold.words), self.controller))
+        }
+
+        fn cancel(self) -> Self::Controller {
+            self.controller
+        }
+    }
+
+    impl blocking::mac::ErrorType for FakeOwnedContext {
+        type Error = Infallible;
+    }
+
+    impl blocking::mac::owned::MacOp for FakeOwnedContext {
+        type Output = Digest<8>;
+        type Controller = FakeController;
+
+        fn update(self, data: &[u8]) -> Result<Self, Self::Error> {
+            blocking::digest::owned::DigestOp::update(self, data)
+        }
+
+        fn finalize(self) -> Result<(Self::Output, Self::Controller), Self::Error> {
+            blocking::digest::owned::DigestOp::finalize(self)
+        }
+
+        fn cancel(self) -> Self::Controller {
+            blocking::digest::owned::DigestOp::cancel(self)
+        }
+    }
+
+    /// Stream mode marker for the fake XOR cipher
+    #[derive(Debug, Clone, Copy)]
+    struct XorMode;
+
+    impl blocking::cipher::CipherMode for XorMode {}
+    impl blocking::cipher::StreamCipherMode for XorMode {}
+
+    /// Blocking cipher XORing data with a keystream of `key ^ nonce`
+    struct FakeCipher {
+        keystream: [u8; 4],
+    }
+
+    impl blocking::cipher::ErrorType for FakeCipher {
+        type Error = FakeError;
+    }
+
+    impl blocking::cipher::SymmetricCipher for FakeCipher {
+        type Key = [u8; 4];
+        type Nonce = [u8; 4];
+        type PlainText = [u8; 16];
+        type CipherText = [u8; 16];
+    }
+
+    impl blocking::cipher::CipherInit<XorMode> for FakeCipher {
+        type CipherContext<'a> = &'a mut FakeCipher;
+
+        fn init<'a>(
+            &'a mut self,
+            key: &Self::Key,
+            nonce: &Self::Nonce,
+            _mode: XorMode,
+        ) -> Result<Self::CipherContext<'a>, Self::Error> {
+            if key == nonce {
+                return Err(FakeError);
+            }
+            for (out, (k, n)) in self.keystream.iter_mut().zip(key.iter().zip(nonce)) {
+                *out = k ^ n;
+            }
+            Ok(self)
+        }
+    }
+
+    impl blocking::cipher::ErrorType for &mut FakeCipher {
+        type Error = FakeError;
+    }
+
+    impl blocking::cipher::SymmetricCipher for &mut FakeCipher {
+        type Key = [u8; 4];
+        type Nonce = [u8; 4];
+        type PlainText = [u8; 16];
+        type CipherText = [u8; 16];
+    }
+
+    impl blocking::cipher::CipherOp<XorMode> for &mut FakeCipher {
+        fn encrypt(&mut self, plaintext: Self::PlainText) -> Result<Self::CipherText, Self::Error> {
+            let mut out = plaintext;
+            for (i, byte) in out.iter_mut().enumerate() {
+                *byte ^= self.keystream[i % 4];
+            }
+            Ok(out)
+        }
+
+        fn decrypt(
+            &mut self,
+            ciphertext: Self::CipherText,
+        ) -> Result<Self::PlainText, Self::Error> {
+            self.encrypt(ciphertext)
+        }
+    }
+
+    /// Private key of the fake ECDSA implementation
+    struct FakePrivateKey([u8; 32]);
+
+    impl zeroize::Zeroize for FakePrivateKey {
+        fn zeroize(&mut self) {
+            self.0.zeroize();
+        }
+    }
+
+    impl blocking::ecdsa::PrivateKey<P256> for FakePrivateKey {
+        fn validate(&self, _curve: &P256) -> Result<(), blocking::ecdsa::ErrorKind> {
+            Ok(())
+        }
+    }
+
+    /// Blocking ECDSA fake whose "signature" is the digest and the key itself
+    struct FakeEcdsa;
+
+    impl blocking::ecdsa::ErrorType for FakeEcdsa {
+        type Error = FakeError;
+    }
+
+    impl blocking::ecdsa::EcdsaKeyGen<P256> for FakeEcdsa {
+        type PrivateKey = FakePrivateKey;
+        type PublicKey = P256PublicKey;
+
+        fn generate_keypair<R>(
+            &mut self,
+            rng: &mut R,
+        ) -> Result<(Self::PrivateKey, Self::PublicKey), Self::Error>
+        where
+            R: rand_core::RngCore + rand_core::CryptoRng,
+        {
+            let mut key = [0u8; 32];
+            rng.fill_bytes(&mut key);
+            Ok((FakePrivateKey(key), P256PublicKey::new(key, key)))
+        }
+    }
+
+    impl blocking::ecdsa::EcdsaSign<P256> for FakeEcdsa {
+        type PrivateKey = FakePrivateKey;
+        type Signature = P256Signature;
+
+        fn sign<R>(
+            &mut self,
+            private_key: &Self::PrivateKey,
+            digest: Digest<8>,
+            _rng: &mut R,
+        ) -> Result<Self::Signature, Self::Error>
+        where
+            R: rand_core::RngCore + rand_core::CryptoRng,
+        {
+            let mut r = [0u8; 32];
+            r.copy_from_slice(digest.as_bytes());
+            Ok(P256Signature::new(r, private_key.0))
+        }
+    }
+
+    impl blocking::ecdsa::EcdsaVerify<P256> for FakeEcdsa {
+        type PublicKey = P256PublicKey;
+        type Signature = P256Signature;
+
+        fn verify(
+            &mut self,
+            public_key: &Self::PublicKey,
+            digest: Digest<8>,
+            signature: &Self::Signature,
+        ) -> Result<(), Self::Error> {
+            let (mut x, mut y) = ([0u8; 32], [0u8; 32]);
+            public_key.coordinates(&mut x, &mut y);
+            let (mut r, mut s) = ([0u8; 32], [0u8; 32]);
+            blocking::ecdsa::Signature::<P256>::coordinates(signature, &mut r, &mut s);
+            if r != digest.as_bytes() || s != x {
+                return Err(FakeError);
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_owned_digest_through_adapter() {
+        use crate::digest::owned::{DigestInit, DigestOp};
+
+        let controller = BlockingAdapter::new(FakeController::default());
+        let (digest, controller) = block_on(async {
+            let context = controller.init(Sha2_256).await?;
+            let context = context.update(b"a").await?;
+            let context = context.update(b"bc").await?;
+            context.finalize().await
+        })
+        .unwrap();
+        assert_eq!(digest.into_array(), Fold::digest(b"abc").into_array());
+        assert_eq!(controller.inner().finalized, 1);
+
+        // The recovered controller is still usable through the adapter
+        let controller = block_on(async {
+            let context = DigestInit::init(controller, Sha2_256).await?;
+            Ok::<_, Infallible>(context.cancel().await)
+        })
+        .unwrap();
+        let blocking: FakeController = controller.into_inner();
+        assert_eq!(blocking.finalized, 1);
+    }
+
+    #[test]
+    fn test_scoped_digest_through_adapter() {
+        use crate::digest::scoped::{DigestInit, DigestOp};
+
+        let mut controller = BlockingAdapter::new(FakeController::default());
+        let digest = block_on(async {
+            let mut context = DigestInit::<Sha2_256>::init(&mut controller, Sha2_256).await?;
+            context.update(b"ab").await?;
+            context.update(b"c").await?;
+            context.finalize().await
+        })
+        .unwrap();
+        assert_eq!(digest.into_array(), Fold::digest(b"abc").into_array());
+        assert_eq!(controller.inner().finalized, 1);
+    }
+
+    #[test]
+    fn test_owned_mac_through_adapter() {
+        use crate::mac::owned::{MacInit, MacOp};
+
+        let controller = BlockingAdapter::new(FakeController::default());
+        let (mac, controller) = block_on(async {
+            let context = controller.init(HmacSha2_256, FakeKey(b"Jefe")).await?;
+            let context = context.update(b"what do ya want ").await?;
+            let context = context.update(b"for nothing?").await?;
+            context.finalize().await
+        })
+        .unwrap();
+        assert_eq!(
+            mac.into_array(),
+            Fold::digest(b"Jefewhat do ya want for nothing?").into_array()
+        );
+        assert_eq!(controller.into_inner().finalized, 1);
+    }
+
+    #[test]
+    fn test_cipher_roundtrip_through_adapter() {
+        use crate::cipher::{CipherInit, CipherOp};
+
+        let key = [0x11u8; 4];
+        let nonce = [0x22u8; 4];
+        let plaintext = [0x42u8; 16];
+
+        let mut cipher = BlockingAdapter::new(FakeCipher { keystream: [0; 4] });
+        let ciphertext = block_on(async {
+            let mut context = cipher.init(&key, &nonce, XorMode).await?;
+            context.encrypt(plaintext).await
+        })
+        .unwrap();
+        assert_eq!(ciphertext, [0x42 ^ 0x11 ^ 0x22; 16]);
+
+        let decrypted = block_on(async {
+            let mut context = cipher.init(&key, &nonce, XorMode).await?;
+            context.decrypt(ciphertext).await
+        })
+        .unwrap();
+        assert_eq!(decrypted, plaintext);
+
+        // Errors from the blocking implementation are passed through
+        let result = block_on(async { cipher.init(&key, &key, XorMode).await.map(|_| ()) });
+        assert_eq!(result, Err(FakeError));
+    }
+
+    #[test]
+    fn test_ecdsa_through_adapter() {
+        use crate::ecdsa::{EcdsaKeyGen, EcdsaSign, EcdsaVerify};
+
+        let mut controller = BlockingAdapter::new(FakeEcdsa);
+        let mut rng = CounterRng(0);
+        let digest = Digest::<8>::new([0x0102_0304; 8]);
+
+        block_on(async {
+            let (private_key, public_key) =
+                EcdsaKeyGen::<P256>::generate_keypair(&mut controller, &mut rng).await?;
+            assert_eq!(private_key.0[0], 1);
+            let signature =
+                EcdsaSign::<P256>::sign(&mut controller, &private_key, digest, &mut rng).await?;
+            EcdsaVerify::<P256>::verify(&mut controller, &public_key, digest, &signature).await?;
+
+            let other = Digest::<8>::new([0x0506_0708; 8]);
+            let result =
+                EcdsaVerify::<P256>::verify(&mut controller, &public_key, other, &signature).await;
+            assert_eq!(result, Err(FakeError));
+            Ok::<_, FakeError>(())
+        })
+        .unwrap();
+    }
+}
@@ -0,0 +1,85 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! Async symmetric cipher operations
+//!
+//! Async mirrors of the operation traits in [`openprot_hal_blocking::cipher`].
+//! Mode markers, [`SymmetricCipher`] associated types and the error model are
+//! shared with the blocking HAL; only the operations that touch the hardware
+//! are async.
+
+pub use openprot_hal_blocking::cipher::{
+    AeadCipherMode, BlockCipherMode, CipherMode, Error, ErrorKind, ErrorType, StreamCipherMode,
+    SymmetricCipher,
+};
+
+/// Trait for initializing an async cipher context.
+///
+/// Async counterpart of [`openprot_hal_blocking::cipher::CipherInit`].
+#[allow(async_fn_in_trait)]
+pub trait CipherInit<M: CipherMode>: SymmetricCipher {
+    /// The cipher context type returned by [`init`](Self::init).
+    type CipherContext<'a>: CipherOp<M>
+    where
+        Self: 'a;
+
+    /// Initialize a cipher context with the given key, nonce and mode.
+    async fn init<'a>(
+        &'a mut self,
+        key: &Self::Key,
+        nonce: &Self::Nonce,
+        mode: M,
+    ) -> Result<Self::CipherContext<'a>, Self::Error>;
+}
+
+/// Trait for performing async cipher operations.
+///
+/// Async counterpart of [`openprot_hal_blocking::cipher::CipherOp`].
+#[allow(async_fn_in_trait)]
+pub trait CipherOp<M: CipherMode>: SymmetricCipher + ErrorType {
+    /// Encrypt a plaintext buffer.
+    async fn encrypt(
+        &mut self,
+        plaintext: Self::PlainText,
+    ) -> Result<Self::CipherText, Self::Error>;
+
+    /// Decrypt a ciphertext buffer.
+    async fn decrypt(
+        &mut self,
+        ciphertext: Self::CipherText,
+    ) -> Result<Self::PlainText, Self::Error>;
+}
+
+/// Trait for resetting an async cipher context.
+#[allow(async_fn_in_trait)]
+pub trait ResettableCipherOp: ErrorType {
+    /// Reset the cipher context to its initial state.
+    async fn reset(&mut self) -> Result<(), Self::Error>;
+}
+
+/// Trait for async authenticated encryption with associated data.
+///
+/// Async counterpart of [`openprot_hal_blocking::cipher::AeadCipherOp`].
+#[allow(async_fn_in_trait)]
+pub trait AeadCipherOp: SymmetricCipher + ErrorType {
+    /// Associated data type, authenticated but not encrypted.
+    type AssociatedData: zerocopy::FromBytes + zerocopy::IntoBytes;
+
+    /// Authentication tag type.
+    type Tag: zerocopy::FromBytes + zerocopy::IntoBytes;
+
+    /// Encrypt `plaintext` and authenticate it together with `associated_data`.
+    async fn encrypt_aead(
+        &mut self,
+        plaintext: Self::PlainText,
+        associated_data: Self::AssociatedData,
+    ) -> Result<(Self::CipherText, Self::Tag), Self::Error>;
+
+    /// Verify `tag` and decrypt `ciphertext`.
+    async fn decrypt_aead(
+        &mut self,
+        ciphertext: Self::CipherText,
+        associated_data: Self::AssociatedData,
+        tag: Self::Tag,
+    ) -> Result<Self::PlainText, Self::Error>;
+}
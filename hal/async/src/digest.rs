@@ -0,0 +1,133 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! Async cryptographic digest operations
+//!
+//! Async mirrors of the traits in [`openprot_hal_blocking::digest`]. Hardware
+//! hash engines typically signal completion by interrupt; these traits let a
+//! driver yield while the engine is busy instead of spinning on a status bit.
+//!
+//! Algorithm markers, output types and the error model are shared with the
+//! blocking HAL so that both flavours of a driver can be used interchangeably.
+
+use zerocopy::IntoBytes;
+
+pub use openprot_hal_blocking::digest::{
+    Digest, DigestAlgorithm, Error, ErrorKind, ErrorType, Sha2_256, Sha2_384, Sha2_512, Sha3_224,
+    Sha3_256, Sha3_384, Sha3_512,
+};
+
+/// Trait for initializing async digest operations.
+///
+/// Async counterpart of [`openprot_hal_blocking::digest::DigestInit`].
+///
+/// # Type Parameters
+///
+/// * `T` - The digest algorithm type that implements [`DigestAlgorithm`]
+#[allow(async_fn_in_trait)]
+pub trait DigestInit<T: DigestAlgorithm>: ErrorType {
+    /// The operation context type that will handle the digest computation.
+    ///
+    /// The lifetime parameter ensures the context cannot outlive the device that created it.
+    type OpContext<'a>: DigestOp<Output = Self::Output>
+    where
+        Self: 'a;
+
+    /// The output type produced by this digest implementation.
+    type Output: IntoBytes;
+
+    /// Init instance of the crypto function with the given context.
+    ///
+    /// # Parameters
+    ///
+    /// - `init_params`: The context or configuration parameters for the crypto function.
+    async fn init(&mut self, init_params: T) -> Result<Self::OpContext<'_>, Self::Error>;
+}
+
+/// Trait for resetting async digest devices.
+#[allow(async_fn_in_trait)]
+pub trait DigestCtrlReset: ErrorType {
+    /// Reset the digest device to its initial state.
+    async fn reset(&mut self) -> Result<(), Self::Error>;
+}
+
+/// Trait for performing async digest operations.
+///
+/// Async counterpart of [`openprot_hal_blocking::digest::DigestOp`].
+#[allow(async_fn_in_trait)]
+pub trait DigestOp: ErrorType {
+    /// The digest output type.
+    type Output: IntoBytes;
+
+    /// Update the digest state with input data.
+    ///
+    /// Completes once the engine has absorbed `input`; the buffer may be
+    /// reused afterwards.
+    async fn update(&mut self, input: &[u8]) -> Result<(), Self::Error>;
+
+    /// Finalize the digest computation and return the result.
+    async fn finalize(self) -> Result<Self::Output, Self::Error>;
+}
+
+pub mod scoped {
+    //! Scoped async digest API with borrowed contexts
+    //!
+    //! Mirrors [`openprot_hal_blocking::digest::scoped`]. Contexts borrow the
+    //! device and cannot outlive the call site that created them.
+
+    pub use super::{DigestAlgorithm, DigestCtrlReset, DigestInit, DigestOp, ErrorType};
+}
+
+pub mod owned {
+    //! Owned async digest API with move-based resource management
+    //!
+    //! Mirrors [`openprot_hal_blocking::digest::owned`]. Contexts own the
+    //! controller, so they can be stored in server session tables while an
+    //! operation is pending, and the controller is recovered on completion.
+
+    use super::{DigestAlgorithm, ErrorType, IntoBytes};
+    use core::result::Result;
+
+    /// Trait for initializing async digest operations with owned contexts.
+    ///
+    /// Takes ownership of the controller and returns an owned context.
+    #[allow(async_fn_in_trait)]
+    pub trait DigestInit<T: DigestAlgorithm>: ErrorType + Sized {
+        /// The owned context type that will handle the digest computation.
+        type Context: DigestOp<Output = Self::Output, Controller = Self>;
+
+        /// The output type produced by this digest implementation.
+        type Output: IntoBytes;
+
+        /// Initialize a new digest computation context.
+        ///
+        /// The controller is returned when the context is finalized or cancelled.
+        async fn init(self, init_params: T) -> Result<Self::Context, Self::Error>;
+    }
+
+    /// Trait for performing async digest operations with owned contexts.
+    ///
+    /// Each operation consumes the context and returns a new context (for
+    /// `update`) or the final result with a recovered controller (for
+    /// `finalize`/`cancel`).
+    #[allow(async_fn_in_trait)]
+    pub trait DigestOp: ErrorType + Sized {
+        /// The digest output type.
+        type Output: IntoBytes;
+
+        /// The controller type that will be recovered after finalization or cancellation.
+        type Controller;
+
+        /// Update the digest state with input data.
+        async fn update(self, data: &[u8]) -> Result<Self, Self::Error>;
+
+        /// Finalize the digest computation and recover the controller.
+        async fn finalize(self) -> Result<(Self::Output, Self::Controller), Self::Error>;
+
+        /// Cancel the digest computation and recover the controller.
+        ///
+        /// This may need to wait for an in-flight hardware operation to drain,
+        /// but cannot fail.
+        async fn cancel(self) -> Self::Controller;
+    }
+}
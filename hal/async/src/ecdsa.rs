@@ -0,0 +1,86 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! Async ECDSA digital signature operations
+//!
+//! Async mirrors of the operation traits in [`openprot_hal_blocking::ecdsa`].
+//! Curves, key and signature types and the error model are shared with the
+//! blocking HAL.
+
+use openprot_hal_blocking::digest::DigestAlgorithm;
+
+pub use openprot_hal_blocking::ecdsa::{
+    Curve, Error, ErrorKind, ErrorType, PrivateKey, PublicKey, Signature, P256, P384,
+};
+
+/// Trait for async ECDSA key generation.
+///
+/// Async counterpart of [`openprot_hal_blocking::ecdsa::EcdsaKeyGen`].
+#[allow(async_fn_in_trait)]
+pub trait EcdsaKeyGen<C: Curve>: ErrorType {
+    /// The type representing the private key for the curve.
+    type PrivateKey: PrivateKey<C>;
+    /// The type representing the public key for the curve.
+    type PublicKey: PublicKey<C>;
+
+    /// Generates an ECDSA key pair.
+    ///
+    /// # Parameters
+    /// - `rng`: A cryptographically secure random number generator.
+    async fn generate_keypair<R>(
+        &mut self,
+        rng: &mut R,
+    ) -> Result<(Self::PrivateKey, Self::PublicKey), Self::Error>
+    where
+        R: rand_core::RngCore + rand_core::CryptoRng;
+}
+
+/// Trait for async ECDSA signing.
+///
+/// Async counterpart of [`openprot_hal_blocking::ecdsa::EcdsaSign`].
+#[allow(async_fn_in_trait)]
+pub trait EcdsaSign<C: Curve>: ErrorType {
+    /// The type representing the private key for the curve.
+    type PrivateKey: PrivateKey<C>;
+    /// The type representing the signature for the curve.
+    type Signature: Signature<C>;
+
+    /// Signs a message digest.
+    ///
+    /// # Parameters
+    /// - `private_key`: The private key used for signing.
+    /// - `digest`: The message digest to sign.
+    /// - `rng`: A cryptographically secure random number generator.
+    async fn sign<R>(
+        &mut self,
+        private_key: &Self::PrivateKey,
+        digest: <<C as Curve>::DigestType as DigestAlgorithm>::Digest,
+        rng: &mut R,
+    ) -> Result<Self::Signature, Self::Error>
+    where
+        R: rand_core::RngCore + rand_core::CryptoRng;
+}
+
+/// Trait for async ECDSA signature verification.
+///
+/// Async counterpart of [`openprot_hal_blocking::ecdsa::EcdsaVerify`].
+#[allow(async_fn_in_trait)]
+pub trait EcdsaVerify<C: Curve>: ErrorType {
+    /// The type representing the public key for the curve.
+    type PublicKey: PublicKey<C>;
+    /// The type representing the signature for the curve.
+    type Signature: Signature<C>;
+
+    /// Verifies a signature against a message digest.
+    ///
+    /// # Parameters
+    /// - `public_key`: The public key used for verification.
+    /// - `digest`: The message digest that was signed.
+    /// - `signature`: The signature to verify.
+    async fn verify(
+        &mut self,
+        public_key: &Self::PublicKey,
+        digest: <<C as Curve>::DigestType as DigestAlgorithm>::Digest,
+        signature: &Self::Signature,
+    ) -> Result<(), Self::Error>;
+}
@@ -5,10 +5,29 @@
 //!
 //! This crate re-exports embedded-hal-async 1.0 traits for async/await-based
 //! hardware abstraction layer operations compatible with modern async runtimes.
+//!
+//! It also provides async mirrors of the OpenPRoT blocking crypto traits, for
+//! accelerators that signal completion by interrupt rather than requiring the
+//! caller to poll. The scoped/owned split of `openprot-hal-blocking` is kept,
+//! and [`adapter::BlockingAdapter`] lifts any blocking implementation into the
+//! async traits.
 
 #![no_std]
 #![forbid(unsafe_code)]
 #![deny(missing_docs)]
 
+/// Blocking-to-async adapter for crypto implementations
+pub mod adapter;
+/// Async symmetric cipher operations
+pub mod cipher;
+/// Async cryptographic digest operations (hashing)
+pub mod digest;
+/// Async ECDSA digital signature operations
+pub mod ecdsa;
+/// Async Message Authentication Code (MAC) operations
+pub mod mac;
+
+pub use adapter::BlockingAdapter;
+
 // Re-export embedded-hal-async 1.0 traits
 pub use embedded_hal_async::*;
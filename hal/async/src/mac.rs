@@ -0,0 +1,115 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! Async Message Authentication Code (MAC) operations
+//!
+//! Async mirrors of the traits in [`openprot_hal_blocking::mac`]. Algorithm
+//! markers, key handles and the error model are shared with the blocking HAL.
+
+use zerocopy::IntoBytes;
+
+pub use openprot_hal_blocking::mac::{
+    Error, ErrorKind, ErrorType, HmacSha2_256, HmacSha2_384, HmacSha2_512, KeyHandle, MacAlgorithm,
+};
+
+/// Trait for initializing an async MAC operation for a specific algorithm.
+///
+/// Async counterpart of [`openprot_hal_blocking::mac::MacInit`].
+#[allow(async_fn_in_trait)]
+pub trait MacInit<A: MacAlgorithm>: ErrorType {
+    /// The key type this implementation accepts.
+    type Key: KeyHandle;
+
+    /// The type representing the operational context for the MAC.
+    type OpContext<'a>: MacOp<Output = A::MacOutput>
+    where
+        Self: 'a;
+
+    /// Initializes the MAC operation with the specified algorithm and key.
+    ///
+    /// # Parameters
+    ///
+    /// - `algo`: A zero-sized type representing the MAC algorithm to use.
+    /// - `key`: The key handle.
+    async fn init<'a>(
+        &'a mut self,
+        algo: A,
+        key: Self::Key,
+    ) -> Result<Self::OpContext<'a>, Self::Error>;
+}
+
+/// Optional trait for resetting an async MAC device to its initial state.
+#[allow(async_fn_in_trait)]
+pub trait MacCtrlReset: ErrorType {
+    /// Resets the MAC device.
+    async fn reset(&mut self) -> Result<(), Self::Error>;
+}
+
+/// Trait for performing async MAC operations.
+///
+/// Async counterpart of [`openprot_hal_blocking::mac::MacOp`].
+#[allow(async_fn_in_trait)]
+pub trait MacOp: ErrorType {
+    /// The type of the MAC output.
+    type Output: IntoBytes;
+
+    /// Updates the MAC state with the provided input data.
+    async fn update(&mut self, input: &[u8]) -> Result<(), Self::Error>;
+
+    /// Finalizes the MAC computation and returns the result.
+    async fn finalize(self) -> Result<Self::Output, Self::Error>;
+}
+
+pub mod scoped {
+    //! Scoped async MAC API with borrowed contexts
+    //!
+    //! Mirrors [`openprot_hal_blocking::mac::scoped`].
+
+    pub use super::{ErrorType, MacAlgorithm, MacCtrlReset, MacInit, MacOp};
+}
+
+pub mod owned {
+    //! Owned async MAC API with move-based resource management
+    //!
+    //! Mirrors [`openprot_hal_blocking::mac::owned`].
+
+    use super::{ErrorType, IntoBytes, KeyHandle, MacAlgorithm};
+    use core::result::Result;
+
+    /// Trait for initializing async MAC operations with owned contexts.
+    #[allow(async_fn_in_trait)]
+    pub trait MacInit<A: MacAlgorithm>: ErrorType + Sized {
+        /// The key type this implementation accepts.
+        type Key: KeyHandle;
+
+        /// The owned context type that will handle the MAC computation.
+        type Context: MacOp<Output = Self::Output, Controller = Self>;
+
+        /// The output type produced by this MAC implementation.
+        type Output: IntoBytes;
+
+        /// Initialize a new MAC computation context.
+        ///
+        /// The controller is returned when the context is finalized or cancelled.
+        async fn init(self, algorithm: A, key: Self::Key) -> Result<Self::Context, Self::Error>;
+    }
+
+    /// Trait for performing async MAC operations with owned contexts.
+    #[allow(async_fn_in_trait)]
+    pub trait MacOp: ErrorType + Sized {
+        /// The MAC output type.
+        type Output: IntoBytes;
+
+        /// The controller type that will be recovered after finalization or cancellation.
+        type Controller;
+
+        /// Update the MAC state with input data.
+        async fn update(self, data: &[u8]) -> Result<Self, Self::Error>;
+
+        /// Finalize the MAC computation and recover the controller.
+        async fn finalize(self) -> Result<(Self::Output, Self::Controller), Self::Error>;
+
+        /// Cancel the MAC computation and recover the controller.
+        async fn cancel(self) -> Self::Controller;
+    }
+}
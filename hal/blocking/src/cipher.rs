@@ -40,6 +40,9 @@ pub enum ErrorKind {
 
     /// Key or IV is invalid or missing.
     KeyError,
+
+    /// AEAD authentication tag verification failed.
+    AuthenticationFailed,
 }
 
 /// Trait for converting implementation-specific errors into a generic [`ErrorKind`].
@@ -0,0 +1,136 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! Elliptic Curve Diffie-Hellman (ECDH) key agreement
+//!
+//! Traits for ephemeral key generation and shared secret computation, as used
+//! by SPDM `KEY_EXCHANGE`. Curves and key types are shared with the
+//! [`ecdsa`](crate::ecdsa) module, so the same [`P256`]/[`P384`] markers and
+//! public key encodings work for both signing and key agreement.
+//!
+//! The shared secret is the x-coordinate of the shared point (SEC 1, 3.3.1)
+//! and is zeroized when dropped.
+
+use core::fmt::Debug;
+use zerocopy::{Immutable, IntoBytes};
+use zeroize::Zeroize;
+
+pub use crate::ecdsa::{Curve, PrivateKey, PublicKey, P256, P384};
+
+/// Common error kinds for ECDH operations.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// The hardware is busy and cannot perform the operation.
+    Busy,
+    /// The peer public key is not a valid point on the curve.
+    ///
+    /// Implementations must reject such keys: invalid-curve attacks can
+    /// otherwise recover the private key.
+    InvalidPoint,
+    /// The private key is out of range for the curve.
+    InvalidPrivateKey,
+    /// Key generation failed, e.g. because the RNG did not produce a usable scalar.
+    KeyGenError,
+    /// The requested curve is not supported by the implementation.
+    UnsupportedCurve,
+    /// General hardware failure during the operation.
+    HardwareFailure,
+    /// Other implementation-specific error.
+    Other,
+}
+
+/// Trait for converting implementation-specific errors into a common error kind.
+pub trait Error: Debug {
+    /// Returns a generic error kind corresponding to the specific error.
+    fn kind(&self) -> ErrorKind;
+}
+
+impl Error for core::convert::Infallible {
+    fn kind(&self) -> ErrorKind {
+        match *self {}
+    }
+}
+
+/// Trait for types that associate with a specific error type.
+pub trait ErrorType {
+    /// The associated error type.
+    type Error: Error;
+}
+
+/// ECDH shared secret for curve `C`.
+///
+/// Holds the big-endian x-coordinate of the shared point. The bytes are
+/// zeroized on drop and the type is deliberately not `Clone`.
+pub struct SharedSecret<C: Curve> {
+    secret: C::Scalar,
+}
+
+impl<C: Curve> SharedSecret<C> {
+    /// Wraps the x-coordinate of a shared point.
+    pub fn new(secret: C::Scalar) -> Self {
+        Self { secret }
+    }
+
+    /// The shared secret bytes.
+    pub fn as_bytes(&self) -> &[u8]
+    where
+        C::Scalar: Immutable,
+    {
+        self.secret.as_bytes()
+    }
+}
+
+impl<C: Curve> Debug for SharedSecret<C> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SharedSecret")
+            .field("len", &core::mem::size_of::<C::Scalar>())
+            .field("bytes", &"[REDACTED]")
+            .finish()
+    }
+}
+
+impl<C: Curve> Drop for SharedSecret<C> {
+    fn drop(&mut self) {
+        self.secret.as_mut_bytes().zeroize();
+    }
+}
+
+/// Trait for generating ephemeral ECDH key pairs.
+pub trait EcdhKeyGen<C: Curve>: ErrorType {
+    /// The type representing the private key for the curve.
+    type PrivateKey: PrivateKey<C>;
+    /// The type representing the public key for the curve.
+    type PublicKey: PublicKey<C>;
+
+    /// Generates an ephemeral key pair.
+    ///
+    /// # Parameters
+    /// - `rng`: A cryptographically secure random number generator.
+    fn generate_ephemeral<R>(
+        &mut self,
+        rng: &mut R,
+    ) -> Result<(Self::PrivateKey, Self::PublicKey), Self::Error>
+    where
+        R: rand_core::RngCore + rand_core::CryptoRng;
+}
+
+/// Trait for computing an ECDH shared secret.
+pub trait EcdhKeyAgreement<C: Curve>: ErrorType {
+    /// The type representing the private key for the curve.
+    type PrivateKey: PrivateKey<C>;
+    /// The type representing the public key for the curve.
+    type PublicKey: PublicKey<C>;
+
+    /// Computes the shared secret between `private_key` and `peer_public_key`.
+    ///
+    /// # Errors
+    /// - `ErrorKind::InvalidPoint`: `peer_public_key` is not on the curve or
+    ///   is the point at infinity
+    /// - `ErrorKind::InvalidPrivateKey`: `private_key` is out of range
+    fn shared_secret(
+        &mut self,
+        private_key: &Self::PrivateKey,
+        peer_public_key: &Self::PublicKey,
+    ) -> Result<SharedSecret<C>, Self::Error>;
+}
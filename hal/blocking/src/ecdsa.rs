@@ -272,6 +272,12 @@ pub enum ErrorKind {
     /// - Other mathematically weak keys
     WeakKey,
 
+    /// Use of the key is not permitted
+    ///
+    /// Returned by vault-backed operations when the key is use-locked or its
+    /// usage permissions do not allow signing.
+    PermissionDenied,
+
     /// Other unspecified error
     ///
     /// A catch-all for errors that don't fit into the specific categories above.
@@ -774,6 +780,53 @@ pub trait EcdsaSign<C: Curve>: ErrorType {
         R: rand_core::RngCore + rand_core::CryptoRng;
 }
 
+/// Trait for ECDSA signing with a private key held in a key vault.
+///
+/// Unlike [`EcdsaSign`], the caller never holds the private key. It names the
+/// key by its vault identifier and the implementation signs with it in place,
+/// so key material is never exported.
+///
+/// # Policy Enforcement
+///
+/// Implementations must check on every call that the key exists, is not
+/// use-locked, and that its usage permissions allow signing, failing with
+/// [`ErrorKind::PermissionDenied`] otherwise. See
+/// [`check_key_use`](crate::key_vault::check_key_use).
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use openprot_hal_blocking::ecdsa::{EcdsaVaultSign, P384};
+///
+/// let signature = EcdsaVaultSign::<P384>::sign_with_key_id(
+///     &mut vault,
+///     KeyId::new(7),
+///     digest,
+///     &mut rng,
+/// )?;
+/// ```
+pub trait EcdsaVaultSign<C: Curve>: ErrorType {
+    /// The type identifying keys in the backing vault.
+    type KeyId: Copy + Clone + PartialEq + Eq;
+    /// The type representing the signature for the curve.
+    type Signature: Signature<C>;
+
+    /// Signs a message digest with the vault key identified by `key_id`.
+    ///
+    /// # Parameters
+    /// - `key_id`: Identifier of the private key in the vault.
+    /// - `digest`: The message digest to sign.
+    /// - `rng`: A cryptographically secure random number generator.
+    fn sign_with_key_id<R>(
+        &mut self,
+        key_id: Self::KeyId,
+        digest: <<C as Curve>::DigestType as DigestAlgorithm>::Digest,
+        rng: &mut R,
+    ) -> Result<Self::Signature, Self::Error>
+    where
+        R: rand_core::RngCore + rand_core::CryptoRng;
+}
+
 /// Trait for ECDSA signature verification using a digest algorithm.
 ///
 /// This trait provides ECDSA signature verification against message digests
@@ -879,6 +932,113 @@ impl Curve for P256 {
     type Scalar = [u8; 32];
 }
 
+/// A serializable public key for the P256 elliptic curve.
+///
+/// This implementation provides both coordinate access and serialization
+/// capabilities for P256 public keys, supporting the standard 64-byte
+/// uncompressed format (32 bytes each for x and y coordinates).
+#[derive(Clone, Debug, IntoBytes, FromBytes, Immutable)]
+#[repr(C)]
+pub struct P256PublicKey {
+    /// X coordinate (32 bytes for P256)
+    x: [u8; 32],
+    /// Y coordinate (32 bytes for P256)
+    y: [u8; 32],
+}
+
+impl P256PublicKey {
+    /// Create a new P256 public key from raw coordinates
+    pub fn new(x: [u8; 32], y: [u8; 32]) -> Self {
+        Self { x, y }
+    }
+}
+
+impl PublicKey<P256> for P256PublicKey {
+    fn coordinates(
+        &self,
+        x_out: &mut <P256 as Curve>::Scalar,
+        y_out: &mut <P256 as Curve>::Scalar,
+    ) {
+        // P256::Scalar is [u8; 32], so we can copy directly
+        *x_out = self.x;
+        *y_out = self.y;
+    }
+
+    fn from_coordinates(
+        x: <P256 as Curve>::Scalar,
+        y: <P256 as Curve>::Scalar,
+    ) -> Result<Self, ErrorKind> {
+        Ok(Self::new(x, y))
+    }
+}
+
+impl SerializablePublicKey<P256> for P256PublicKey {}
+
+/// Order `n` of the P-256 base point, big-endian.
+const P256_ORDER: [u8; 32] = [
+    0xff, 0xff, 0xff, 0xff, 0x00, 0x00, 0x00, 0x00, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0xbc, 0xe6, 0xfa, 0xad, 0xa7, 0x17, 0x9e, 0x84, 0xf3, 0xb9, 0xca, 0xc2, 0xfc, 0x63, 0x25, 0x51,
+];
+
+/// Order `n` of the P-384 base point, big-endian.
+const P384_ORDER: [u8; 48] = [
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xc7, 0x63, 0x4d, 0x81, 0xf4, 0x37, 0x2d, 0xdf,
+    0x58, 0x1a, 0x0d, 0xb2, 0x48, 0xb0, 0xa7, 0x7a, 0xec, 0xec, 0x19, 0x6a, 0xcc, 0xc5, 0x29, 0x73,
+];
+
+/// Check `0 < value < order` for big-endian scalars of equal length.
+///
+/// Signature components are public, so the comparison need not be constant time.
+fn scalar_in_range(value: &[u8], order: &[u8]) -> bool {
+    value.iter().any(|&b| b != 0) && value < order
+}
+
+/// A serializable signature for the P256 elliptic curve.
+///
+/// This implementation provides both signature validation and serialization
+/// capabilities for P256 ECDSA signatures, supporting the standard 64-byte
+/// format (32 bytes each for r and s components).
+#[derive(Clone, Debug, IntoBytes, FromBytes, Immutable)]
+#[repr(C)]
+pub struct P256Signature {
+    /// R component (32 bytes for P256)
+    r: [u8; 32],
+    /// S component (32 bytes for P256)
+    s: [u8; 32],
+}
+
+impl P256Signature {
+    /// Create a new P256 signature from r and s components
+    pub fn new(r: [u8; 32], s: [u8; 32]) -> Self {
+        Self { r, s }
+    }
+}
+
+impl Signature<P256> for P256Signature {
+    fn from_coordinates(
+        r: <P256 as Curve>::Scalar,
+        s: <P256 as Curve>::Scalar,
+    ) -> Result<Self, ErrorKind> {
+        if !scalar_in_range(&r, &P256_ORDER) || !scalar_in_range(&s, &P256_ORDER) {
+            return Err(ErrorKind::InvalidSignature);
+        }
+        Ok(Self::new(r, s))
+    }
+
+    fn coordinates(
+        &self,
+        r_out: &mut <P256 as Curve>::Scalar,
+        s_out: &mut <P256 as Curve>::Scalar,
+    ) {
+        // P256::Scalar is [u8; 32], so we can copy directly
+        *r_out = self.r;
+        *s_out = self.s;
+    }
+}
+
+impl SerializableSignature<P256> for P256Signature {}
+
 /// NIST P-384 elliptic curve marker type.
 ///
 /// This zero-sized type represents the NIST P-384 elliptic curve (secp384r1).
@@ -974,9 +1134,9 @@ impl Signature<P384> for P384Signature {
         r: <P384 as Curve>::Scalar,
         s: <P384 as Curve>::Scalar,
     ) -> Result<Self, ErrorKind> {
-        // TODO: Add proper signature validation here
-        // For now, we accept any r,s values but in a real implementation
-        // we should validate that 1 ≤ r,s < curve_order
+        if !scalar_in_range(&r, &P384_ORDER) || !scalar_in_range(&s, &P384_ORDER) {
+            return Err(ErrorKind::InvalidSignature);
+        }
         Ok(Self::new(r, s))
     }
 
@@ -1100,3 +1260,47 @@ impl Curve for BrainpoolP512r1 {
     type DigestType = crate::digest::Sha2_512;
     type Scalar = [u8; 64];
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_p256_signature_range_checked() {
+        let one = {
+            let mut v = [0u8; 32];
+            v[31] = 1;
+            v
+        };
+        let mut n_minus_one = P256_ORDER;
+        n_minus_one[31] -= 1;
+
+        assert!(P256Signature::from_coordinates(one, n_minus_one).is_ok());
+        for bad in [[0u8; 32], P256_ORDER, [0xff; 32]] {
+            assert_eq!(
+                P256Signature::from_coordinates(bad, one).err(),
+                Some(ErrorKind::InvalidSignature)
+            );
+            assert_eq!(
+                P256Signature::from_coordinates(one, bad).err(),
+                Some(ErrorKind::InvalidSignature)
+            );
+        }
+    }
+
+    #[test]
+    fn test_p384_signature_range_checked() {
+        let mut n_minus_one = P384_ORDER;
+        n_minus_one[47] -= 1;
+
+        assert!(P384Signature::from_coordinates(n_minus_one, n_minus_one).is_ok());
+        assert_eq!(
+            P384Signature::from_coordinates(P384_ORDER, n_minus_one).err(),
+            Some(ErrorKind::InvalidSignature)
+        );
+        assert_eq!(
+            P384Signature::from_coordinates(n_minus_one, [0u8; 48]).err(),
+            Some(ErrorKind::InvalidSignature)
+        );
+    }
+}
@@ -0,0 +1,88 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! # I3C Controller and Target Traits
+//!
+//! This module defines a minimal blocking interface to I3C private
+//! transfers, enough to carry byte-oriented protocols such as MCTP over I3C
+//! (DMTF DSP0233).
+//!
+//! ## Scope
+//!
+//! - **Controller**: private writes to a target's dynamic address
+//! - **Target**: its assigned dynamic address, maximum write length and the
+//!   private writes received from the active controller
+//!
+//! Dynamic address assignment, CCCs, private reads and in-band interrupts
+//! are left to the hardware driver and are not part of these traits yet.
+//!
+//! ## Trait Hierarchy
+//!
+//! ```text
+//! I3cErrorType (error type)
+//!     ├── I3cController (private writes)
+//!     └── I3cTarget (dynamic address, received private writes)
+//! ```
+
+/// Common I3C operation errors
+///
+/// Implementations are free to define more specific error types, and map
+/// them to these kinds so generic code can react to them.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[non_exhaustive]
+pub enum I3cErrorKind {
+    /// No target acknowledged the dynamic address
+    AddressNack,
+    /// The transfer exceeded the target's maximum write length
+    Overrun,
+    /// A parity or CRC error was detected on the bus
+    Parity,
+    /// The target has no dynamic address assigned
+    NoDynamicAddress,
+    /// Bus error or hardware failure during the transfer
+    Bus,
+    /// A different error occurred
+    Other,
+}
+
+/// Trait for I3C errors
+pub trait I3cError: core::fmt::Debug {
+    /// Convert error to a generic error kind
+    fn kind(&self) -> I3cErrorKind;
+}
+
+impl I3cError for core::convert::Infallible {
+    fn kind(&self) -> I3cErrorKind {
+        match *self {}
+    }
+}
+
+/// Trait for types that define an error type for I3C operations
+pub trait I3cErrorType {
+    /// Error type for I3C operations
+    type Error: I3cError;
+}
+
+/// I3C controller-role operations
+pub trait I3cController: I3cErrorType {
+    /// Send `data` to the target at dynamic address `addr` as one private
+    /// write transfer
+    ///
+    /// `addr` is the 7-bit dynamic address.
+    fn private_write(&mut self, addr: u8, data: &[u8]) -> Result<(), Self::Error>;
+}
+
+/// I3C target-role operations
+pub trait I3cTarget: I3cErrorType {
+    /// Dynamic address assigned by the active controller, if any
+    fn dynamic_address(&self) -> Option<u8>;
+
+    /// Maximum write length (MWL) this target accepts in one private write
+    fn max_write_length(&self) -> usize;
+
+    /// Take the oldest private write received from the controller
+    ///
+    /// Copies the transfer into `buffer` and returns its length, or `None`
+    /// if no write is pending. A transfer longer than `buffer` is truncated.
+    fn take_private_write(&mut self, buffer: &mut [u8]) -> Result<Option<usize>, Self::Error>;
+}
@@ -0,0 +1,240 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! Key derivation functions (KDF)
+//!
+//! Traits for HKDF (RFC 5869) and the NIST SP 800-108 KDF in counter mode.
+//! Both are keyed by a PRF, which is selected with the existing
+//! [`MacAlgorithm`] markers (e.g. [`HmacSha2_384`](crate::mac::HmacSha2_384)).
+//!
+//! Derived keys can either be written to a caller-provided buffer or stored
+//! directly into a key vault slot with [`hkdf_expand_to_slot`] and
+//! [`counter_kdf_to_slot`].
+
+use crate::key_vault::{self, KeyLifecycle, KeyLocking, KeyStore};
+use crate::mac::MacAlgorithm;
+use core::fmt::Debug;
+use zeroize::Zeroize;
+
+/// Common error kinds for key derivation operations.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// The key or input keying material is not valid for the PRF.
+    InvalidKeyLength,
+    /// The requested output length is zero, exceeds the KDF limit, or does
+    /// not match the key type of the destination slot.
+    InvalidOutputLength,
+    /// The specified PRF is not supported by the implementation.
+    UnsupportedAlgorithm,
+    /// The hardware accelerator is busy and cannot perform the derivation.
+    Busy,
+    /// General hardware failure during key derivation.
+    HardwareFailure,
+    /// Insufficient permissions to use the key or the destination slot,
+    /// e.g. because the slot is write-locked.
+    PermissionDenied,
+    /// The key store rejected the derived key.
+    KeyStore(key_vault::ErrorKind),
+}
+
+/// Trait for converting implementation-specific errors into a common error kind.
+pub trait Error: Debug {
+    /// Returns a generic error kind corresponding to the specific error.
+    fn kind(&self) -> ErrorKind;
+}
+
+impl Error for core::convert::Infallible {
+    fn kind(&self) -> ErrorKind {
+        match *self {}
+    }
+}
+
+/// Trait for types that associate with a specific error type.
+pub trait ErrorType {
+    /// The associated error type.
+    type Error: Error;
+}
+
+/// HKDF-Extract (RFC 5869, Section 2.2).
+///
+/// Condenses input keying material into a pseudorandom key (PRK) of the
+/// PRF output size. The PRK is passed to [`HkdfExpand::expand`] as bytes.
+pub trait HkdfExtract<A: MacAlgorithm>: ErrorType {
+    /// Extracts a PRK from `ikm` using `salt`.
+    ///
+    /// # Parameters
+    ///
+    /// - `algo`: The PRF used by HKDF.
+    /// - `salt`: Optional salt; an empty slice is treated as `HashLen` zero bytes.
+    /// - `ikm`: Input keying material.
+    fn extract(&mut self, algo: A, salt: &[u8], ikm: &[u8]) -> Result<A::MacOutput, Self::Error>;
+}
+
+/// HKDF-Expand (RFC 5869, Section 2.3).
+pub trait HkdfExpand<A: MacAlgorithm>: ErrorType {
+    /// Expands `prk` into `okm.len()` bytes of output keying material.
+    ///
+    /// # Parameters
+    ///
+    /// - `algo`: The PRF used by HKDF.
+    /// - `prk`: A pseudorandom key of at least `HashLen` bytes.
+    /// - `info`: Context and application specific information.
+    /// - `okm`: Output buffer; at most `255 * HashLen` bytes.
+    ///
+    /// # Errors
+    ///
+    /// - `ErrorKind::InvalidKeyLength`: `prk` is shorter than `HashLen`
+    /// - `ErrorKind::InvalidOutputLength`: `okm` is longer than `255 * HashLen`
+    fn expand(
+        &mut self,
+        algo: A,
+        prk: &[u8],
+        info: &[u8],
+        okm: &mut [u8],
+    ) -> Result<(), Self::Error>;
+}
+
+/// NIST SP 800-108 KDF in counter mode.
+///
+/// Each PRF block is computed over
+/// `[i]_32 || Label || 0x00 || Context || [L]_32`, where the counter `i`
+/// starts at 1 and `L` is the output length in bits, both big-endian.
+pub trait CounterModeKdf<A: MacAlgorithm>: ErrorType {
+    /// Derives `okm.len()` bytes from the key derivation key `key`.
+    ///
+    /// # Errors
+    ///
+    /// - `ErrorKind::InvalidOutputLength`: `okm` is empty or its length in
+    ///   bits does not fit in 32 bits
+    fn derive(
+        &mut self,
+        algo: A,
+        key: &[u8],
+        label: &[u8],
+        context: &[u8],
+        okm: &mut [u8],
+    ) -> Result<(), Self::Error>;
+
+    /// Derives `okm.len()` bytes using caller-formatted fixed input data.
+    ///
+    /// Each PRF block is computed over `[i]_32 || fixed_input`. This is the
+    /// form used by the NIST CAVP KBKDF vectors, and by protocols that
+    /// encode `Label`, `Context` and `L` differently from [`derive`].
+    ///
+    /// [`derive`]: CounterModeKdf::derive
+    ///
+    /// # Errors
+    ///
+    /// - `ErrorKind::InvalidOutputLength`: `okm` is empty or its length in
+    ///   bits does not fit in 32 bits
+    fn derive_with_fixed_input(
+        &mut self,
+        algo: A,
+        key: &[u8],
+        fixed_input: &[u8],
+        okm: &mut [u8],
+    ) -> Result<(), Self::Error>;
+}
+
+/// Refuse to derive into a slot that could not be written afterwards
+fn check_slot_writable<V>(vault: &V, id: <V as KeyStore>::KeyId) -> Result<(), ErrorKind>
+where
+    V: KeyStore + KeyLocking<KeyId = <V as KeyStore>::KeyId>,
+{
+    if vault
+        .is_write_locked(id)
+        .map_err(|e| ErrorKind::KeyStore(key_vault::Error::kind(&e)))?
+    {
+        return Err(ErrorKind::PermissionDenied);
+    }
+    Ok(())
+}
+
+fn store_derived<V>(
+    vault: &mut V,
+    id: <V as KeyStore>::KeyId,
+    okm: &[u8],
+    metadata: <V as KeyLifecycle>::KeyMetadata,
+) -> Result<(), ErrorKind>
+where
+    V: KeyStore + KeyLifecycle<KeyId = <V as KeyStore>::KeyId>,
+    for<'a> <V as KeyLifecycle>::KeyData: TryFrom<&'a [u8]>,
+{
+    let data =
+        <V as KeyLifecycle>::KeyData::try_from(okm).map_err(|_| ErrorKind::InvalidOutputLength)?;
+    vault
+        .store_key(id, data, metadata)
+        .map_err(|e| ErrorKind::KeyStore(key_vault::Error::kind(&e)))
+}
+
+/// Runs HKDF-Expand and stores the `L`-byte result into vault slot `id`.
+///
+/// The slot is checked before any key material is derived. The intermediate
+/// buffer is zeroized before returning, whether or not the vault accepted
+/// the key.
+///
+/// # Errors
+///
+/// - `ErrorKind::PermissionDenied`: slot `id` is write-locked
+/// - `ErrorKind::InvalidOutputLength`: `L` does not match the vault's key type
+/// - `ErrorKind::KeyStore`: the vault failed to report or store the key
+pub fn hkdf_expand_to_slot<const L: usize, K, A, V>(
+    kdf: &mut K,
+    algo: A,
+    prk: &[u8],
+    info: &[u8],
+    vault: &mut V,
+    id: <V as KeyStore>::KeyId,
+    metadata: <V as KeyLifecycle>::KeyMetadata,
+) -> Result<(), ErrorKind>
+where
+    A: MacAlgorithm,
+    K: HkdfExpand<A>,
+    V: KeyStore
+        + KeyLocking<KeyId = <V as KeyStore>::KeyId>
+        + KeyLifecycle<KeyId = <V as KeyStore>::KeyId>,
+    for<'a> <V as KeyLifecycle>::KeyData: TryFrom<&'a [u8]>,
+{
+    check_slot_writable(vault, id)?;
+    let mut okm = [0u8; L];
+    let result = kdf
+        .expand(algo, prk, info, &mut okm)
+        .map_err(|e| e.kind())
+        .and_then(|()| store_derived(vault, id, &okm, metadata));
+    okm.zeroize();
+    result
+}
+
+/// Runs the SP 800-108 counter-mode KDF and stores the `L`-byte result into
+/// vault slot `id`.
+///
+/// Checks and errors are as for [`hkdf_expand_to_slot`].
+#[allow(clippy::too_many_arguments)]
+pub fn counter_kdf_to_slot<const L: usize, K, A, V>(
+    kdf: &mut K,
+    algo: A,
+    key: &[u8],
+    label: &[u8],
+    context: &[u8],
+    vault: &mut V,
+    id: <V as KeyStore>::KeyId,
+    metadata: <V as KeyLifecycle>::KeyMetadata,
+) -> Result<(), ErrorKind>
+where
+    A: MacAlgorithm,
+    K: CounterModeKdf<A>,
+    V: KeyStore
+        + KeyLocking<KeyId = <V as KeyStore>::KeyId>
+        + KeyLifecycle<KeyId = <V as KeyStore>::KeyId>,
+    for<'a> <V as KeyLifecycle>::KeyData: TryFrom<&'a [u8]>,
+{
+    check_slot_writable(vault, id)?;
+    let mut okm = [0u8; L];
+    let result = kdf
+        .derive(algo, key, label, context, &mut okm)
+        .map_err(|e| e.kind())
+        .and_then(|()| store_derived(vault, id, &okm, metadata));
+    okm.zeroize();
+    result
+}
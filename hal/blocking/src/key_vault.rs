@@ -37,6 +37,12 @@ pub enum ErrorKind {
     /// or incompatible with the key or storage backend.
     InvalidUsage,
 
+    /// Key material has an unsupported size
+    ///
+    /// Returned when the key is larger than the slot can hold, or its
+    /// length does not match what the key type requires.
+    InvalidKeySize,
+
     /// Hardware fault or failure
     ///
     /// Indicates a hardware-level error in secure storage elements,
@@ -56,6 +62,7 @@ impl core::fmt::Display for ErrorKind {
             Self::KeyNotFound => write!(f, "specified key was not found"),
             Self::AccessDenied => write!(f, "access to key was denied"),
             Self::InvalidUsage => write!(f, "invalid key usage specification"),
+            Self::InvalidKeySize => write!(f, "unsupported key material size"),
             Self::HardwareFault => write!(f, "hardware fault during key operation"),
             Self::Other => write!(f, "key management operation failed"),
         }
@@ -171,6 +178,52 @@ pub trait KeyLocking: ErrorType {
     fn clear_use_lock(&mut self, id: Self::KeyId) -> Result<(), Self::Error>;
 }
 
+/// Usage permissions that can be checked against the usage an operation requires
+///
+/// Implemented by a vault's [`KeyStore::KeyUsage`] type so that generic code
+/// can enforce usage policy without knowing its encoding.
+pub trait KeyUsagePermissions: Copy {
+    /// Returns `true` if these permissions allow every use in `required`
+    fn permits(&self, required: Self) -> bool;
+}
+
+/// Check that a vault key may be used for an operation
+///
+/// Vault-backed operations call this before touching key material. Use is
+/// refused if the key does not exist, is use-locked, or its usage permissions
+/// do not include `required`.
+///
+/// # Errors
+///
+/// - `ErrorKind::KeyNotFound`: No key is stored under `id`
+/// - `ErrorKind::AccessDenied`: The key is use-locked
+/// - `ErrorKind::InvalidUsage`: The key's usage does not permit `required`
+/// - Any error kind reported by the vault while querying key state
+pub fn check_key_use<V>(
+    vault: &V,
+    id: <V as KeyStore>::KeyId,
+    required: <V as KeyStore>::KeyUsage,
+) -> Result<(), ErrorKind>
+where
+    V: KeyStore + KeyLocking<KeyId = <V as KeyStore>::KeyId>,
+    <V as KeyStore>::KeyUsage: KeyUsagePermissions,
+{
+    if !vault.key_exists(id).map_err(|e| e.kind())? {
+        return Err(ErrorKind::KeyNotFound);
+    }
+    if vault.is_use_locked(id).map_err(|e| e.kind())? {
+        return Err(ErrorKind::AccessDenied);
+    }
+    if !vault
+        .get_key_usage(id)
+        .map_err(|e| e.kind())?
+        .permits(required)
+    {
+        return Err(ErrorKind::InvalidUsage);
+    }
+    Ok(())
+}
+
 /// Key lifecycle management
 /// Complete key lifecycle from creation to retrieval
 pub trait KeyLifecycle: ErrorType {
@@ -17,6 +17,8 @@
 
 /// Cryptographic digest operations (hashing)
 pub mod digest;
+/// ECDH key agreement
+pub mod ecdh;
 /// ECDSA digital signature operations
 pub mod ecdsa;
 /// Gpio port module
@@ -25,8 +27,14 @@ pub mod gpio_port;
 pub mod i2c_device;
 /// I2C hardware controller traits (hardware abstraction layer)
 pub mod i2c_hardware;
+/// I3C controller and target private transfer traits
+pub mod i3c;
+/// Key derivation functions (HKDF, SP 800-108)
+pub mod kdf;
 /// Message Authentication Code (MAC) traits and implementations
 pub mod mac;
+/// Random number generation (entropy sources and DRBGs)
+pub mod rng;
 /// Reset and clocking traits for OpenPRoT HAL
 pub mod system_control;
 
@@ -199,6 +199,40 @@ pub trait MacInit<A: MacAlgorithm>: ErrorType {
     fn init<'a>(&'a mut self, algo: A, key: Self::Key) -> Result<Self::OpContext<'a>, Self::Error>;
 }
 
+/// Trait for initializing a MAC operation with a key held in a key vault.
+///
+/// Unlike [`MacInit`], the caller passes a vault key identifier instead of a
+/// key handle. The implementation keys the MAC engine from the vault slot
+/// directly, so key material is never exported.
+///
+/// # Policy Enforcement
+///
+/// Implementations must check on every call that the key exists, is not
+/// use-locked, and that its usage permissions allow MAC computation, failing
+/// with [`ErrorKind::PermissionDenied`] otherwise. See
+/// [`check_key_use`](crate::key_vault::check_key_use).
+pub trait VaultMacInit<A: MacAlgorithm>: ErrorType {
+    /// The type identifying keys in the backing vault.
+    type KeyId: Copy + Clone + PartialEq + Eq;
+
+    /// The type representing the operational context for the MAC.
+    type OpContext<'a>: MacOp<Output = A::MacOutput>
+    where
+        Self: 'a;
+
+    /// Initializes the MAC operation with the vault key identified by `key_id`.
+    ///
+    /// # Parameters
+    ///
+    /// - `algo`: A zero-sized type representing the MAC algorithm to use.
+    /// - `key_id`: Identifier of the MAC key in the vault.
+    fn init_with_key_id(
+        &mut self,
+        algo: A,
+        key_id: Self::KeyId,
+    ) -> Result<Self::OpContext<'_>, Self::Error>;
+}
+
 /// Optional trait for resetting a MAC context to its initial state.
 pub trait MacCtrlReset: ErrorType {
     /// Resets the MAC context.
@@ -0,0 +1,211 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! Random number generation
+//!
+//! Traits for the two halves of a NIST SP 800-90 random bit generator:
+//!
+//! - [`EntropySource`]: a (possibly slow) source of full-entropy bits such
+//!   as a hardware TRNG, subject to SP 800-90B health tests.
+//! - [`Drbg`]: a deterministic random bit generator (SP 800-90A) seeded from
+//!   an entropy source, used for all bulk randomness.
+//!
+//! Other HAL traits, such as [`EcdsaKeyGen`](crate::ecdsa::EcdsaKeyGen),
+//! take a `rand_core` RNG. [`DrbgRng`] exposes any [`Drbg`] through
+//! `rand_core::TryRngCore`, so it can be passed to them via
+//! `TryRngCore::unwrap_err`.
+
+use core::fmt::Debug;
+
+/// Common error kinds for random number generation.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// The entropy source or DRBG is busy with another request.
+    Busy,
+    /// The DRBG has not been instantiated.
+    NotInstantiated,
+    /// The DRBG reached its reseed interval and must be reseeded before
+    /// it can generate more output.
+    ReseedRequired,
+    /// The entropy source could not deliver enough entropy.
+    InsufficientEntropy,
+    /// SP 800-90B repetition count test failure: the noise source
+    /// produced the same sample too many times in a row.
+    RepetitionCountFailure,
+    /// SP 800-90B adaptive proportion test failure: one sample value
+    /// occurred too often within a window.
+    AdaptiveProportionFailure,
+    /// The requested output, additional input or personalization string
+    /// exceeds the limits of the implementation.
+    RequestTooLarge,
+    /// General hardware failure of the entropy source.
+    HardwareFailure,
+    /// Other implementation-specific error.
+    Other,
+}
+
+impl ErrorKind {
+    /// Returns `true` if the error is a failed entropy health test.
+    ///
+    /// Health test failures indicate that the noise source can no longer be
+    /// trusted; callers must not retry with the same source.
+    pub fn is_health_test_failure(&self) -> bool {
+        matches!(
+            self,
+            Self::RepetitionCountFailure | Self::AdaptiveProportionFailure
+        )
+    }
+}
+
+impl core::fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Busy => write!(f, "random number generator is busy"),
+            Self::NotInstantiated => write!(f, "DRBG is not instantiated"),
+            Self::ReseedRequired => write!(f, "DRBG must be reseeded"),
+            Self::InsufficientEntropy => write!(f, "insufficient entropy available"),
+            Self::RepetitionCountFailure => write!(f, "repetition count health test failed"),
+            Self::AdaptiveProportionFailure => {
+                write!(f, "adaptive proportion health test failed")
+            }
+            Self::RequestTooLarge => write!(f, "request exceeds generator limits"),
+            Self::HardwareFailure => write!(f, "entropy source hardware failure"),
+            Self::Other => write!(f, "random number generation failed"),
+        }
+    }
+}
+
+impl core::error::Error for ErrorKind {}
+
+/// Trait for converting implementation-specific errors into a common error kind.
+pub trait Error: Debug {
+    /// Returns a generic error kind corresponding to the specific error.
+    fn kind(&self) -> ErrorKind;
+}
+
+impl Error for core::convert::Infallible {
+    fn kind(&self) -> ErrorKind {
+        match *self {}
+    }
+}
+
+impl Error for ErrorKind {
+    fn kind(&self) -> ErrorKind {
+        *self
+    }
+}
+
+/// Trait for types that associate with a specific error type.
+pub trait ErrorType {
+    /// The associated error type.
+    type Error: Error;
+}
+
+/// A source of full-entropy bits (SP 800-90B).
+///
+/// Implementations run their continuous health tests on every request and
+/// report failures as [`ErrorKind::RepetitionCountFailure`] or
+/// [`ErrorKind::AdaptiveProportionFailure`] instead of returning output.
+pub trait EntropySource: ErrorType {
+    /// Fills `dest` with entropy.
+    ///
+    /// # Errors
+    ///
+    /// - `ErrorKind::InsufficientEntropy`: Not enough entropy is available
+    /// - `ErrorKind::RepetitionCountFailure`: Repetition count test failed
+    /// - `ErrorKind::AdaptiveProportionFailure`: Adaptive proportion test failed
+    fn fill_entropy(&mut self, dest: &mut [u8]) -> Result<(), Self::Error>;
+}
+
+/// A deterministic random bit generator (SP 800-90A).
+///
+/// Instantiation is implementation-specific, since it depends on the
+/// entropy source and mechanism. Once instantiated, a DRBG generates output
+/// until its reseed interval is reached and can be reseeded at any time.
+pub trait Drbg: ErrorType {
+    /// Generates `dest.len()` pseudorandom bytes.
+    ///
+    /// # Parameters
+    ///
+    /// - `dest`: Output buffer
+    /// - `additional_input`: Optional additional input; may be empty
+    ///
+    /// # Errors
+    ///
+    /// - `ErrorKind::ReseedRequired`: The reseed interval was reached
+    /// - `ErrorKind::RequestTooLarge`: `dest` or `additional_input` is too long
+    fn generate(&mut self, dest: &mut [u8], additional_input: &[u8]) -> Result<(), Self::Error>;
+
+    /// Reseeds the DRBG with fresh entropy from its entropy source.
+    ///
+    /// # Errors
+    ///
+    /// - Any entropy source error, including health test failures
+    /// - `ErrorKind::RequestTooLarge`: `additional_input` is too long
+    fn reseed(&mut self, additional_input: &[u8]) -> Result<(), Self::Error>;
+
+    /// Fills `dest` with pseudorandom bytes, without additional input.
+    fn fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Self::Error> {
+        self.generate(dest, &[])
+    }
+}
+
+/// Adapter exposing a [`Drbg`] as a `rand_core` RNG.
+///
+/// Errors are reported through `rand_core::TryRngCore`. APIs that require an
+/// infallible `RngCore` can use `TryRngCore::unwrap_err`, which panics if
+/// the DRBG fails.
+#[derive(Debug)]
+pub struct DrbgRng<D>(D);
+
+impl<D: Drbg> DrbgRng<D> {
+    /// Wraps a DRBG.
+    pub const fn new(drbg: D) -> Self {
+        Self(drbg)
+    }
+
+    /// Mutably borrows the wrapped DRBG.
+    pub fn drbg_mut(&mut self) -> &mut D {
+        &mut self.0
+    }
+
+    /// Unwraps the adapter, returning the DRBG.
+    pub fn into_inner(self) -> D {
+        self.0
+    }
+}
+
+impl<D: Drbg> rand_core::TryRngCore for DrbgRng<D> {
+    type Error = ErrorKind;
+
+    fn try_next_u32(&mut self) -> Result<u32, Self::Error> {
+        let mut buf = [0u8; 4];
+        self.try_fill_bytes(&mut buf)?;
+        Ok(u32::from_le_bytes(buf))
+    }
+
+    fn try_next_u64(&mut self) -> Result<u64, Self::Error> {
+        let mut buf = [0u8; 8];
+        self.try_fill_bytes(&mut buf)?;
+        Ok(u64::from_le_bytes(buf))
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Self::Error> {
+        self.0.fill_bytes(dest).map_err(|e| e.kind())
+    }
+}
+
+impl<D: Drbg> rand_core::TryCryptoRng for DrbgRng<D> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_health_test_failure_kinds() {
+        assert!(ErrorKind::RepetitionCountFailure.is_health_test_failure());
+        assert!(ErrorKind::AdaptiveProportionFailure.is_health_test_failure());
+        assert!(!ErrorKind::ReseedRequired.is_health_test_failure());
+    }
+}
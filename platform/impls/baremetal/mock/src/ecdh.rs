@@ -0,0 +1,261 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! Mock ECDH Implementation
+//!
+//! Provides a stub implementation of the ECDH traits for exercising key
+//! agreement flows without a real elliptic-curve backend.
+//!
+//! The "curve" is the additive group of integers modulo the Mersenne prime
+//! 2^61 - 1 with generator 5, so a public key is `d * 5 mod p` and the shared
+//! secret is `d_a * d_b * 5 mod p`. Agreement is symmetric like real ECDH, but
+//! it offers NO security: the private key is trivially recoverable.
+//!
+//! Public keys use the P-256/P-384 coordinate types. A point is valid only if
+//! `x` encodes a non-zero group element (big-endian in the last 8 bytes, all
+//! other bytes zero) and `y` equals the check value derived from `x`. Anything
+//! else is rejected with [`ErrorKind::InvalidPoint`], so tests can exercise
+//! invalid-point handling.
+
+use openprot_hal_blocking::ecdh::{
+    Curve, EcdhKeyAgreement, EcdhKeyGen, ErrorKind, ErrorType, PrivateKey, PublicKey, SharedSecret,
+    P256, P384,
+};
+use openprot_hal_blocking::ecdsa::{self, P256PublicKey, P384PublicKey};
+use zeroize::Zeroize;
+
+/// Group modulus (2^61 - 1)
+const MODULUS: u64 = (1 << 61) - 1;
+/// Group generator
+const GENERATOR: u64 = 5;
+/// Multiplier used to derive the `y` check value from `x`
+const Y_CHECK: u64 = 0x9e37_79b9_7f4a_7c15;
+
+fn mul_mod(a: u64, b: u64) -> u64 {
+    ((u128::from(a) * u128::from(b)) % u128::from(MODULUS)) as u64
+}
+
+/// Mock ECDH error type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MockEcdhError(pub ErrorKind);
+
+impl openprot_hal_blocking::ecdh::Error for MockEcdhError {
+    fn kind(&self) -> ErrorKind {
+        self.0
+    }
+}
+
+/// Mock ECDH private key
+///
+/// Valid keys hold a scalar in `[1, 2^61 - 1)`. Zeroized on drop.
+#[derive(Debug)]
+pub struct MockEcdhPrivateKey {
+    scalar: u64,
+}
+
+impl MockEcdhPrivateKey {
+    /// Create a private key from a raw scalar
+    pub fn new(scalar: u64) -> Self {
+        Self { scalar }
+    }
+
+    fn checked_scalar(&self) -> Result<u64, MockEcdhError> {
+        if self.scalar == 0 || self.scalar >= MODULUS {
+            return Err(MockEcdhError(ErrorKind::InvalidPrivateKey));
+        }
+        Ok(self.scalar)
+    }
+}
+
+impl Zeroize for MockEcdhPrivateKey {
+    fn zeroize(&mut self) {
+        self.scalar.zeroize();
+    }
+}
+
+impl Drop for MockEcdhPrivateKey {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl<C: Curve> PrivateKey<C> for MockEcdhPrivateKey {
+    fn validate(&self, _curve: &C) -> Result<(), ecdsa::ErrorKind> {
+        self.checked_scalar()
+            .map(|_| ())
+            .map_err(|_| ecdsa::ErrorKind::WeakKey)
+    }
+}
+
+/// Encode a group element as a mock curve coordinate
+fn encode<const N: usize>(value: u64) -> [u8; N] {
+    let mut out = [0u8; N];
+    out[N - 8..].copy_from_slice(&value.to_be_bytes());
+    out
+}
+
+/// Decode a coordinate, returning `None` unless it is a canonical group element
+fn decode<const N: usize>(bytes: &[u8; N]) -> Option<u64> {
+    let (high, low) = bytes.split_at(N - 8);
+    if high.iter().any(|&b| b != 0) {
+        return None;
+    }
+    let value = u64::from_be_bytes(low.try_into().ok()?);
+    (value < MODULUS).then_some(value)
+}
+
+/// Mock ECDH device
+#[derive(Default)]
+pub struct MockEcdhDevice;
+
+impl MockEcdhDevice {
+    /// Create a new mock ECDH device
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Build the mock public key coordinates for `scalar`
+    pub fn public_coordinates<const N: usize>(scalar: u64) -> ([u8; N], [u8; N]) {
+        let x = mul_mod(scalar, GENERATOR);
+        (encode(x), encode(mul_mod(x, Y_CHECK)))
+    }
+
+    fn validate_point<const N: usize>(x: &[u8; N], y: &[u8; N]) -> Result<u64, MockEcdhError> {
+        let invalid = MockEcdhError(ErrorKind::InvalidPoint);
+        let x_value = decode(x).ok_or(invalid)?;
+        // Zero plays the role of the point at infinity
+        if x_value == 0 || decode(y) != Some(mul_mod(x_value, Y_CHECK)) {
+            return Err(invalid);
+        }
+        Ok(x_value)
+    }
+}
+
+impl ErrorType for MockEcdhDevice {
+    type Error = MockEcdhError;
+}
+
+macro_rules! impl_mock_ecdh {
+    ($curve:ident, $public:ident, $len:expr) => {
+        impl EcdhKeyGen<$curve> for MockEcdhDevice {
+            type PrivateKey = MockEcdhPrivateKey;
+            type PublicKey = $public;
+
+            fn generate_ephemeral<R>(
+                &mut self,
+                rng: &mut R,
+            ) -> Result<(Self::PrivateKey, Self::PublicKey), Self::Error>
+            where
+                R: rand_core::RngCore + rand_core::CryptoRng,
+            {
+                let private = MockEcdhPrivateKey::new(rng.next_u64() % MODULUS);
+                let scalar = private
+                    .checked_scalar()
+                    .map_err(|_| MockEcdhError(ErrorKind::KeyGenError))?;
+                let (x, y) = Self::public_coordinates::<$len>(scalar);
+                Ok((private, $public::new(x, y)))
+            }
+        }
+
+        impl EcdhKeyAgreement<$curve> for MockEcdhDevice {
+            type PrivateKey = MockEcdhPrivateKey;
+            type PublicKey = $public;
+
+            fn shared_secret(
+                &mut self,
+                private_key: &Self::PrivateKey,
+                peer_public_key: &Self::PublicKey,
+            ) -> Result<SharedSecret<$curve>, Self::Error> {
+                let mut x = [0u8; $len];
+                let mut y = [0u8; $len];
+                peer_public_key.coordinates(&mut x, &mut y);
+                let peer = Self::validate_point(&x, &y)?;
+                let scalar = private_key.checked_scalar()?;
+                Ok(SharedSecret::new(encode(mul_mod(scalar, peer))))
+            }
+        }
+    };
+}
+
+impl_mock_ecdh!(P256, P256PublicKey, 32);
+impl_mock_ecdh!(P384, P384PublicKey, 48);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct CountingRng(u64);
+
+    impl rand_core::RngCore for CountingRng {
+        fn next_u32(&mut self) -> u32 {
+            self.next_u64() as u32
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            self.0 = self.0.wrapping_add(0x1234_5678_9abc_def1);
+            self.0
+        }
+
+        fn fill_bytes(&mut self, dst: &mut [u8]) {
+            for chunk in dst.chunks_mut(8) {
+                let bytes = self.next_u64().to_le_bytes();
+                chunk.copy_from_slice(&bytes[..chunk.len()]);
+            }
+        }
+    }
+
+    impl rand_core::CryptoRng for CountingRng {}
+
+    #[test]
+    fn test_agreement_is_symmetric() {
+        let mut device = MockEcdhDevice::new();
+        let mut rng = CountingRng(0);
+        let (a, ga) = EcdhKeyGen::<P384>::generate_ephemeral(&mut device, &mut rng).unwrap();
+        let (b, gb) = EcdhKeyGen::<P384>::generate_ephemeral(&mut device, &mut rng).unwrap();
+
+        let z_a = EcdhKeyAgreement::<P384>::shared_secret(&mut device, &a, &gb).unwrap();
+        let z_b = EcdhKeyAgreement::<P384>::shared_secret(&mut device, &b, &ga).unwrap();
+        assert_eq!(z_a.as_bytes(), z_b.as_bytes());
+        assert_eq!(z_a.as_bytes().len(), 48);
+    }
+
+    #[test]
+    fn test_invalid_points_rejected() {
+        let mut device = MockEcdhDevice::new();
+        let key = MockEcdhPrivateKey::new(42);
+        let (x, y) = MockEcdhDevice::public_coordinates::<32>(7);
+
+        let mut bad_y = y;
+        bad_y[31] ^= 0x01;
+        let mut high_x = x;
+        high_x[0] = 0x01;
+        let invalid = [
+            P256PublicKey::new(x, bad_y),
+            P256PublicKey::new(high_x, y),
+            P256PublicKey::new([0u8; 32], [0u8; 32]),
+            P256PublicKey::new(encode(MODULUS), encode(0)),
+        ];
+        for peer in &invalid {
+            let err = EcdhKeyAgreement::<P256>::shared_secret(&mut device, &key, peer).unwrap_err();
+            assert_eq!(err.0, ErrorKind::InvalidPoint);
+        }
+
+        let valid = P256PublicKey::new(x, y);
+        assert!(EcdhKeyAgreement::<P256>::shared_secret(&mut device, &key, &valid).is_ok());
+    }
+
+    #[test]
+    fn test_invalid_private_key_rejected() {
+        let mut device = MockEcdhDevice::new();
+        let (x, y) = MockEcdhDevice::public_coordinates::<32>(7);
+        let peer = P256PublicKey::new(x, y);
+
+        for scalar in [0, MODULUS] {
+            let key = MockEcdhPrivateKey::new(scalar);
+            assert!(PrivateKey::<P256>::validate(&key, &P256).is_err());
+            let err =
+                EcdhKeyAgreement::<P256>::shared_secret(&mut device, &key, &peer).unwrap_err();
+            assert_eq!(err.0, ErrorKind::InvalidPrivateKey);
+        }
+    }
+}
@@ -0,0 +1,634 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! Mock GPIO port implementation for testing and development
+//!
+//! This module provides a 32-pin mock of the `GpioPort` and `GpioInterrupt`
+//! traits. Tests drive the wire level of input pins, and the mock latches
+//! edge and level interrupts the way a GPIO controller's status register
+//! would, invoking registered handlers when an enabled interrupt fires.
+//!
+//! # Features
+//!
+//! - **Pin masks**: [`MockPinMask`] bit `n` selects pin `n`
+//! - **Polarity**: Masks carry logical (active = 1) levels; active-low pins
+//!   are inverted on the wire
+//! - **Input stimulus**: Set the wire level of input pins from the test
+//! - **Interrupt simulation**: Rising/falling/both-edge and high/low-level
+//!   sensitivity, enable, clear and pending queries
+//! - **Handler invocation**: Registered handlers run when an enabled pin's
+//!   interrupt becomes pending
+//! - **Configurable behavior**: Success/failure modes for testing error paths
+//!
+//! # Interrupt Model
+//!
+//! - Sensitivity applies to wire levels, as on the hardware controllers
+//! - A pin's status latches when its configured condition occurs, whether or
+//!   not its interrupt is enabled; enabling clears stale status first
+//! - Level-sensitive status re-latches after `Clear` while the level holds
+//! - Handlers run when an enabled pin's status goes from clear to pending,
+//!   with the mask of the pins that fired under the handler's own mask
+//!
+//! Handlers are boxed, so this module needs `alloc`.
+//!
+//! # Examples
+//!
+//! ```text
+//! use openprot_platform_mock::gpio::{MockGpioPort, MockPinMask};
+//! use openprot_hal_blocking::gpio_port::{
+//!     ActivePolarity, EdgeSensitivity, GpioInterrupt, GpioPort, InterruptOperation, PinConfig,
+//! };
+//!
+//! let mut port = MockGpioPort::new();
+//! let button = MockPinMask::pin(3);
+//! port.configure(button, PinConfig::input(ActivePolarity::ActiveLow))?;
+//! port.irq_configure(button, EdgeSensitivity::FallingEdge)?;
+//! port.irq_control(button, InterruptOperation::Enable)?;
+//! port.register_interrupt_handler(button, |fired| {
+//!     // Called with MockPinMask(1 << 3)
+//! })?;
+//!
+//! // Wire idles high, then the button is pressed
+//! port.set_input_levels(button, button);
+//! port.set_input_levels(button, MockPinMask(0));
+//! assert!(port.irq_control(button, InterruptOperation::IsPending)?);
+//! assert_eq!(port.read_input()?, button); // active-low: pressed reads 1
+//! ```
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+use openprot_hal_blocking::gpio_port::{
+    ActivePolarity, EdgeSensitivity, GpioError, GpioErrorKind, GpioErrorType, GpioInterrupt,
+    GpioPort, InterruptOperation, PinConfig, PinDirection, PinMask,
+};
+
+/// Number of pins on a mock port
+pub const MOCK_GPIO_PINS: u32 = 32;
+
+/// Set of pins on a [`MockGpioPort`]; bit `n` is pin `n`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct MockPinMask(pub u32);
+
+impl MockPinMask {
+    /// Mask selecting the single pin `pin`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pin` is not below [`MOCK_GPIO_PINS`].
+    pub const fn pin(pin: u32) -> Self {
+        assert!(pin < MOCK_GPIO_PINS);
+        Self(1 << pin)
+    }
+
+    /// Pin numbers in the mask, lowest first
+    pub fn pins(self) -> impl Iterator<Item = u32> {
+        (0..MOCK_GPIO_PINS).filter(move |pin| self.0 & (1 << pin) != 0)
+    }
+}
+
+impl PinMask for MockPinMask {
+    fn empty() -> Self {
+        Self(0)
+    }
+
+    fn all() -> Self {
+        Self(u32::MAX)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    fn union(&self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    fn intersection(&self, other: Self) -> Self {
+        Self(self.0 & other.0)
+    }
+
+    fn toggle(&self) -> Self {
+        Self(!self.0)
+    }
+}
+
+/// Mock error type for GPIO operations
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MockGpioError {
+    /// A pin is not an output (driving or toggling an input)
+    InvalidMode,
+    /// The same pin is in both the set and the reset mask
+    ConflictingMasks,
+    /// Simulated hardware failure (failing mock)
+    HardwareFailure,
+}
+
+impl GpioError for MockGpioError {
+    fn kind(&self) -> GpioErrorKind {
+        match self {
+            MockGpioError::InvalidMode => GpioErrorKind::InvalidMode,
+            MockGpioError::ConflictingMasks => GpioErrorKind::UnsupportedConfiguration,
+            MockGpioError::HardwareFailure => GpioErrorKind::HardwareFailure,
+        }
+    }
+}
+
+/// Boxed interrupt handler and the pins it listens to
+type Handler = (MockPinMask, Box<dyn FnMut(MockPinMask) + Send>);
+
+/// Mock GPIO port
+///
+/// All pin state is kept as 32-bit masks. Output pins put their latched
+/// level on the wire; every other pin reads the level set with
+/// [`set_input_levels`](Self::set_input_levels), which defaults to low.
+pub struct MockGpioPort {
+    /// Whether operations succeed
+    success: bool,
+    /// Pins configured as outputs
+    outputs: u32,
+    /// Pins configured as inputs
+    inputs: u32,
+    /// Pins declared active-low
+    active_low: u32,
+    /// Logical output latch
+    driven: u32,
+    /// Wire level applied to non-output pins by the test
+    external: u32,
+    /// Pins sensitive to a rising wire edge
+    rising: u32,
+    /// Pins sensitive to a falling wire edge
+    falling: u32,
+    /// Pins sensitive to a high wire level
+    high_level: u32,
+    /// Pins sensitive to a low wire level
+    low_level: u32,
+    /// Pins with interrupts enabled
+    enabled: u32,
+    /// Latched interrupt status
+    pending: u32,
+    /// Registered interrupt handlers
+    handlers: Vec<Handler>,
+}
+
+impl MockGpioPort {
+    /// Create a new mock GPIO port
+    ///
+    /// All pins start unconfigured, with the wire low and no interrupts.
+    pub fn new() -> Self {
+        Self {
+            success: true,
+            outputs: 0,
+            inputs: 0,
+            active_low: 0,
+            driven: 0,
+            external: 0,
+            rising: 0,
+            falling: 0,
+            high_level: 0,
+            low_level: 0,
+            enabled: 0,
+            pending: 0,
+            handlers: Vec::new(),
+        }
+    }
+
+    /// Create a new mock that will fail operations
+    ///
+    /// All trait operations return `MockGpioError::HardwareFailure`.
+    pub fn new_failing() -> Self {
+        Self {
+            success: false,
+            ..Self::new()
+        }
+    }
+
+    /// Set the wire level of non-output pins, as an external driver would
+    ///
+    /// Pins in `pins` go high where `levels` has a 1 and low elsewhere.
+    /// Edges and levels are evaluated against interrupt sensitivity, and
+    /// handlers run for newly pending, enabled pins. Output pins keep
+    /// driving their latched level.
+    pub fn set_input_levels(&mut self, pins: MockPinMask, levels: MockPinMask) {
+        let before = self.wire_levels().0;
+        self.external = (self.external & !pins.0) | (levels.0 & pins.0);
+        self.evaluate(before);
+    }
+
+    /// Current wire level of every pin
+    pub fn wire_levels(&self) -> MockPinMask {
+        let from_outputs = self.outputs & (self.driven ^ self.active_low);
+        MockPinMask(from_outputs | (!self.outputs & self.external))
+    }
+
+    /// Logical level latched for output pins
+    pub fn output_levels(&self) -> MockPinMask {
+        MockPinMask(self.driven & self.outputs)
+    }
+
+    /// Number of registered interrupt handlers
+    pub fn handler_count(&self) -> usize {
+        self.handlers.len()
+    }
+
+    fn check_success(&self) -> Result<(), MockGpioError> {
+        if self.success {
+            Ok(())
+        } else {
+            Err(MockGpioError::HardwareFailure)
+        }
+    }
+
+    fn check_outputs(&self, pins: MockPinMask) -> Result<(), MockGpioError> {
+        if pins.0 & !self.outputs == 0 {
+            Ok(())
+        } else {
+            Err(MockGpioError::InvalidMode)
+        }
+    }
+
+    /// Latch interrupt status for the wire change from `before`, then run
+    /// handlers for enabled pins that became pending
+    fn evaluate(&mut self, before: u32) {
+        let after = self.wire_levels().0;
+        let triggered = (!before & after & self.rising)
+            | (before & !after & self.falling)
+            | (after & self.high_level)
+            | (!after & self.low_level);
+        let fired = triggered & !self.pending & self.enabled;
+        self.pending |= triggered;
+
+        if fired != 0 {
+            for (mask, handler) in self.handlers.iter_mut() {
+                let pins = fired & mask.0;
+                if pins != 0 {
+                    handler(MockPinMask(pins));
+                }
+            }
+        }
+    }
+}
+
+impl Default for MockGpioPort {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl core::fmt::Debug for MockGpioPort {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("MockGpioPort")
+            .field("success", &self.success)
+            .field("outputs", &MockPinMask(self.outputs))
+            .field("inputs", &MockPinMask(self.inputs))
+            .field("wire", &self.wire_levels())
+            .field("enabled", &MockPinMask(self.enabled))
+            .field("pending", &MockPinMask(self.pending))
+            .field("handlers", &self.handlers.len())
+            .finish()
+    }
+}
+
+impl GpioErrorType for MockGpioPort {
+    type Error = MockGpioError;
+}
+
+impl GpioPort for MockGpioPort {
+    type Config = PinConfig;
+    type Mask = MockPinMask;
+
+    fn configure(&mut self, pins: Self::Mask, config: Self::Config) -> Result<(), Self::Error> {
+        self.check_success()?;
+        let before = self.wire_levels().0;
+        match config.polarity {
+            ActivePolarity::ActiveLow => self.active_low |= pins.0,
+            ActivePolarity::ActiveHigh => self.active_low &= !pins.0,
+        }
+        match config.direction {
+            PinDirection::Output => {
+                self.outputs |= pins.0;
+                self.inputs &= !pins.0;
+                if config.initial_output.unwrap_or(false) {
+                    self.driven |= pins.0;
+                } else {
+                    self.driven &= !pins.0;
+                }
+            }
+            PinDirection::Input => {
+                self.inputs |= pins.0;
+                self.outputs &= !pins.0;
+                self.driven &= !pins.0;
+            }
+        }
+        self.evaluate(before);
+        Ok(())
+    }
+
+    fn set_reset(
+        &mut self,
+        set_mask: Self::Mask,
+        reset_mask: Self::Mask,
+    ) -> Result<(), Self::Error> {
+        self.check_success()?;
+        if !set_mask.intersection(reset_mask).is_empty() {
+            return Err(MockGpioError::ConflictingMasks);
+        }
+        self.check_outputs(set_mask.union(reset_mask))?;
+        let before = self.wire_levels().0;
+        self.driven = (self.driven | set_mask.0) & !reset_mask.0;
+        self.evaluate(before);
+        Ok(())
+    }
+
+    /// Logical level of every pin; unconfigured pins read 0
+    fn read_input(&self) -> Result<Self::Mask, Self::Error> {
+        self.check_success()?;
+        let configured = self.inputs | self.outputs;
+        Ok(MockPinMask(
+            (self.wire_levels().0 ^ self.active_low) & configured,
+        ))
+    }
+
+    fn toggle(&mut self, pins: Self::Mask) -> Result<(), Self::Error> {
+        self.check_success()?;
+        self.check_outputs(pins)?;
+        let before = self.wire_levels().0;
+        self.driven ^= pins.0;
+        self.evaluate(before);
+        Ok(())
+    }
+}
+
+impl GpioInterrupt for MockGpioPort {
+    type Mask = MockPinMask;
+
+    fn irq_configure(
+        &mut self,
+        mask: Self::Mask,
+        sensitivity: EdgeSensitivity,
+    ) -> Result<(), Self::Error> {
+        self.check_success()?;
+        self.rising &= !mask.0;
+        self.falling &= !mask.0;
+        self.high_level &= !mask.0;
+        self.low_level &= !mask.0;
+        match sensitivity {
+            EdgeSensitivity::RisingEdge => self.rising |= mask.0,
+            EdgeSensitivity::FallingEdge => self.falling |= mask.0,
+            EdgeSensitivity::BothEdges => {
+                self.rising |= mask.0;
+                self.falling |= mask.0;
+            }
+            EdgeSensitivity::HighLevel => self.high_level |= mask.0,
+            EdgeSensitivity::LowLevel => self.low_level |= mask.0,
+        }
+        let levels = self.wire_levels().0;
+        self.evaluate(levels);
+        Ok(())
+    }
+
+    fn irq_control(
+        &mut self,
+        mask: Self::Mask,
+        operation: InterruptOperation,
+    ) -> Result<bool, Self::Error> {
+        self.check_success()?;
+        let levels = self.wire_levels().0;
+        match operation {
+            InterruptOperation::Enable => {
+                // Clear stale status first to avoid a spurious immediate fire
+                self.pending &= !mask.0;
+                self.enabled |= mask.0;
+                self.evaluate(levels);
+                Ok(true)
+            }
+            InterruptOperation::Disable => {
+                self.enabled &= !mask.0;
+                Ok(true)
+            }
+            InterruptOperation::Clear => {
+                self.pending &= !mask.0;
+                self.evaluate(levels);
+                Ok(true)
+            }
+            InterruptOperation::IsPending => Ok(self.pending & mask.0 != 0),
+        }
+    }
+
+    fn register_interrupt_handler<F>(
+        &mut self,
+        mask: Self::Mask,
+        handler: F,
+    ) -> Result<(), Self::Error>
+    where
+        F: FnMut(Self::Mask) + Send + 'static,
+    {
+        self.check_success()?;
+        self.handlers.push((mask, Box::new(handler)));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    extern crate std;
+    use std::sync::Arc;
+    use std::sync::Mutex;
+    use std::vec;
+
+    const LED: MockPinMask = MockPinMask::pin(0);
+    const BUTTON: MockPinMask = MockPinMask::pin(3);
+
+    /// Register a handler on `mask` that records every call
+    fn record(port: &mut MockGpioPort, mask: MockPinMask) -> Arc<Mutex<Vec<MockPinMask>>> {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&calls);
+        port.register_interrupt_handler(mask, move |fired| sink.lock().unwrap().push(fired))
+            .unwrap();
+        calls
+    }
+
+    #[test]
+    fn test_pin_mask() {
+        let mask = MockPinMask::pin(1).union(MockPinMask::pin(4));
+        assert_eq!(mask, MockPinMask(0b1_0010));
+        assert!(mask.contains(MockPinMask::pin(4)));
+        assert!(!mask.contains(MockPinMask::pin(2)));
+        assert_eq!(mask.intersection(MockPinMask::pin(1)), MockPinMask::pin(1));
+        assert_eq!(mask.pins().collect::<Vec<_>>(), vec![1, 4]);
+        assert!(MockPinMask::empty().is_empty());
+        assert_eq!(MockPinMask::all().toggle(), MockPinMask::empty());
+    }
+
+    #[test]
+    fn test_outputs() {
+        let mut port = MockGpioPort::new();
+        port.configure(LED, PinConfig::output_active_high(false))
+            .unwrap();
+        assert_eq!(port.read_input(), Ok(MockPinMask(0)));
+
+        port.set_reset(LED, MockPinMask(0)).unwrap();
+        assert_eq!(port.read_input(), Ok(LED));
+        assert_eq!(port.wire_levels(), LED);
+
+        port.toggle(LED).unwrap();
+        assert_eq!(port.output_levels(), MockPinMask(0));
+
+        assert_eq!(port.toggle(BUTTON), Err(MockGpioError::InvalidMode));
+        assert_eq!(
+            port.set_reset(LED, LED),
+            Err(MockGpioError::ConflictingMasks)
+        );
+    }
+
+    #[test]
+    fn test_active_low() {
+        let mut port = MockGpioPort::new();
+        port.configure(LED, PinConfig::output_active_low(true))
+            .unwrap();
+        // Active output drives the wire low
+        assert_eq!(port.wire_levels(), MockPinMask(0));
+        assert_eq!(port.read_input(), Ok(LED));
+
+        port.configure(BUTTON, PinConfig::input(ActivePolarity::ActiveLow))
+            .unwrap();
+        port.set_input_levels(BUTTON, BUTTON);
+        assert_eq!(port.read_input(), Ok(LED));
+        port.set_input_levels(BUTTON, MockPinMask(0));
+        assert_eq!(port.read_input(), Ok(LED.union(BUTTON)));
+    }
+
+    #[test]
+    fn test_edge_interrupts() {
+        let mut port = MockGpioPort::new();
+        port.configure(BUTTON, PinConfig::input(ActivePolarity::ActiveHigh))
+            .unwrap();
+        port.irq_configure(BUTTON, EdgeSensitivity::RisingEdge)
+            .unwrap();
+        let calls = record(&mut port, BUTTON);
+
+        // Status latches while disabled, but no handler runs
+        port.set_input_levels(BUTTON, BUTTON);
+        assert_eq!(
+            port.irq_control(BUTTON, InterruptOperation::IsPending),
+            Ok(true)
+        );
+        assert!(calls.lock().unwrap().is_empty());
+
+        // Enabling clears the stale status
+        port.irq_control(BUTTON, InterruptOperation::Enable)
+            .unwrap();
+        assert_eq!(
+            port.irq_control(BUTTON, InterruptOperation::IsPending),
+            Ok(false)
+        );
+
+        // Falling edge is ignored, rising edge fires
+        port.set_input_levels(BUTTON, MockPinMask(0));
+        assert!(calls.lock().unwrap().is_empty());
+        port.set_input_levels(BUTTON, BUTTON);
+        assert_eq!(*calls.lock().unwrap(), vec![BUTTON]);
+
+        // A second edge before Clear does not fire again
+        port.set_input_levels(BUTTON, MockPinMask(0));
+        port.set_input_levels(BUTTON, BUTTON);
+        assert_eq!(calls.lock().unwrap().len(), 1);
+
+        port.irq_control(BUTTON, InterruptOperation::Clear).unwrap();
+        port.set_input_levels(BUTTON, MockPinMask(0));
+        port.set_input_levels(BUTTON, BUTTON);
+        assert_eq!(calls.lock().unwrap().len(), 2);
+
+        // Disabled pins do not fire
+        port.irq_control(BUTTON, InterruptOperation::Clear).unwrap();
+        port.irq_control(BUTTON, InterruptOperation::Disable)
+            .unwrap();
+        port.set_input_levels(BUTTON, MockPinMask(0));
+        port.set_input_levels(BUTTON, BUTTON);
+        assert_eq!(calls.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_both_edges_on_outputs() {
+        let mut port = MockGpioPort::new();
+        port.configure(LED, PinConfig::output_active_high(false))
+            .unwrap();
+        port.irq_configure(LED, EdgeSensitivity::BothEdges).unwrap();
+        port.irq_control(LED, InterruptOperation::Enable).unwrap();
+        let calls = record(&mut port, LED);
+
+        port.toggle(LED).unwrap();
+        port.irq_control(LED, InterruptOperation::Clear).unwrap();
+        port.toggle(LED).unwrap();
+        assert_eq!(*calls.lock().unwrap(), vec![LED, LED]);
+    }
+
+    #[test]
+    fn test_level_interrupts() {
+        let mut port = MockGpioPort::new();
+        port.configure(BUTTON, PinConfig::input(ActivePolarity::ActiveHigh))
+            .unwrap();
+        port.irq_configure(BUTTON, EdgeSensitivity::LowLevel)
+            .unwrap();
+        let calls = record(&mut port, BUTTON);
+
+        // Wire is low, so enabling fires immediately
+        port.irq_control(BUTTON, InterruptOperation::Enable)
+            .unwrap();
+        assert_eq!(calls.lock().unwrap().len(), 1);
+
+        // Clear re-latches while the level holds
+        port.irq_control(BUTTON, InterruptOperation::Clear).unwrap();
+        assert_eq!(calls.lock().unwrap().len(), 2);
+
+        port.set_input_levels(BUTTON, BUTTON);
+        port.irq_control(BUTTON, InterruptOperation::Clear).unwrap();
+        assert_eq!(
+            port.irq_control(BUTTON, InterruptOperation::IsPending),
+            Ok(false)
+        );
+        assert_eq!(calls.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_handler_masks() {
+        let mut port = MockGpioPort::new();
+        let pins = LED.union(BUTTON);
+        port.configure(pins, PinConfig::input(ActivePolarity::ActiveHigh))
+            .unwrap();
+        port.irq_configure(pins, EdgeSensitivity::RisingEdge)
+            .unwrap();
+        port.irq_control(pins, InterruptOperation::Enable).unwrap();
+        let led_calls = record(&mut port, LED);
+        let all_calls = record(&mut port, pins);
+        assert_eq!(port.handler_count(), 2);
+
+        port.set_input_levels(pins, pins);
+        assert_eq!(*led_calls.lock().unwrap(), vec![LED]);
+        assert_eq!(*all_calls.lock().unwrap(), vec![pins]);
+    }
+
+    #[test]
+    fn test_failing_port() {
+        let mut port = MockGpioPort::new_failing();
+        assert_eq!(
+            port.configure(LED, PinConfig::output_active_high(false)),
+            Err(MockGpioError::HardwareFailure)
+        );
+        assert_eq!(port.read_input(), Err(MockGpioError::HardwareFailure));
+        assert_eq!(
+            port.irq_control(LED, InterruptOperation::Enable),
+            Err(MockGpioError::HardwareFailure)
+        );
+        assert_eq!(
+            MockGpioError::HardwareFailure.kind(),
+            GpioErrorKind::HardwareFailure
+        );
+    }
+}
@@ -0,0 +1,496 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! Deterministic virtual I2C bus
+//!
+//! This module connects several [`MockI2cHardware`] instances on one shared,
+//! in-memory bus so that a master transaction on one instance is delivered to
+//! the slave side of another. It lets transport code such as MCTP-over-I2C be
+//! exercised end-to-end on the host, with real framing, instead of shuttling
+//! packets between endpoints by hand.
+//!
+//! # Features
+//!
+//! - **Address routing**: Each attached instance answers at its own 7-bit address
+//! - **Slave delivery**: Master writes land in the target's slave receive buffer
+//!   and master reads drain its transmit buffer
+//! - **Event simulation**: Targets raise `I2cIsrEvent`s in bus order
+//! - **Fault injection**: Each master's own script and faults apply before
+//!   routing, so bus failures can be combined with real traffic
+//! - **Deterministic**: Transactions complete synchronously, in call order
+//! - **No allocation**: The bus is a fixed array of `N` nodes
+//!
+//! # Bus Behavior
+//!
+//! - A transaction is first run through the master's own
+//!   [`MockI2cHardware`] (success mode, stuck bus, script and faults); an
+//!   error there ends the transaction before any byte reaches the target
+//! - A transaction to an address no other node answers is NACKed
+//! - Each transaction replaces the target's receive buffer; a write that
+//!   does not fit its 64 bytes is NACKed
+//! - Read bytes come from the target's transmit buffer, padded with `0xFF`
+//! - The target raises `SlaveWrReq`/`SlaveWrRecvd` per write operation,
+//!   `SlaveRdReq`/`SlaveRdProc` per read operation and `SlaveStop` at the
+//!   end; as the mock keeps only the most recent event, `SlaveStop` is the
+//!   one left to poll
+//!
+//! # Examples
+//!
+//! ```text
+//! use openprot_platform_mock::i2c_bus::VirtualI2cBus;
+//! use openprot_platform_mock::i2c_hardware::MockI2cHardware;
+//! use openprot_hal_blocking::i2c_hardware::I2cMaster;
+//! use openprot_hal_blocking::i2c_hardware::slave::I2cSlaveBuffer;
+//!
+//! let bus: VirtualI2cBus<2> = VirtualI2cBus::new();
+//! let mut controller = bus.attach(MockI2cHardware::new(), 0x10)?;
+//! let mut target = bus.attach(MockI2cHardware::new(), 0x42)?;
+//!
+//! // Master write from 0x10 lands in the slave buffer of 0x42
+//! controller.write(0x42, &[0x0F, 0x01, 0x02])?;
+//!
+//! let mut buffer = [0u8; 8];
+//! let count = target.read_slave_buffer(&mut buffer)?;
+//! assert_eq!(&buffer[..count], &[0x0F, 0x01, 0x02]);
+//! ```
+
+use core::cell::{RefCell, RefMut};
+
+use embedded_hal::i2c::{ErrorType, Operation, SevenBitAddress};
+use openprot_hal_blocking::i2c_hardware::slave::{
+    I2cIsrEvent, I2cSlaveBuffer, I2cSlaveCore, I2cSlaveInterrupts, SlaveStatus,
+};
+use openprot_hal_blocking::i2c_hardware::{I2cBusRecovery, I2cHardwareCore, I2cMaster};
+
+use crate::i2c_hardware::{MockI2cConfig, MockI2cError, MockI2cHardware};
+
+/// Shared in-memory I2C bus with room for `N` mock controllers
+///
+/// Attached controllers are reached through [`VirtualI2cPort`] handles that
+/// borrow the bus, so every port sees the same wires.
+#[derive(Debug)]
+pub struct VirtualI2cBus<const N: usize> {
+    nodes: [RefCell<Option<MockI2cHardware>>; N],
+}
+
+impl<const N: usize> VirtualI2cBus<N> {
+    /// Create an empty bus
+    pub fn new() -> Self {
+        Self {
+            nodes: core::array::from_fn(|_| RefCell::new(None)),
+        }
+    }
+
+    /// Attach `hardware` to the bus as a slave at `address`
+    ///
+    /// Configures the slave address and enables slave mode on `hardware`.
+    /// The returned port drives it as both master and slave.
+    ///
+    /// # Errors
+    ///
+    /// - `MockI2cError::Other` if the bus is full or `address` is taken
+    /// - Any error from configuring slave mode on `hardware`
+    pub fn attach(
+        &self,
+        mut hardware: MockI2cHardware,
+        address: SevenBitAddress,
+    ) -> Result<VirtualI2cPort<'_, N>, MockI2cError> {
+        let taken = self.nodes.iter().any(|node| {
+            node.borrow()
+                .as_ref()
+                .is_some_and(|attached| attached.answers(address))
+        });
+        if taken {
+            return Err(MockI2cError::Other);
+        }
+        let index = self
+            .nodes
+            .iter()
+            .position(|node| node.borrow().is_none())
+            .ok_or(MockI2cError::Other)?;
+
+        hardware.configure_slave_address(address)?;
+        hardware.enable_slave_mode()?;
+        if let Some(node) = self.nodes.get(index) {
+            *node.borrow_mut() = Some(hardware);
+        }
+        Ok(VirtualI2cPort { bus: self, index })
+    }
+
+    /// Number of attached controllers
+    pub fn attached(&self) -> usize {
+        self.nodes
+            .iter()
+            .filter(|node| node.borrow().is_some())
+            .count()
+    }
+
+    fn node(&self, index: usize) -> RefMut<'_, MockI2cHardware> {
+        let node = self.nodes.get(index).expect("port index out of range");
+        RefMut::map(node.borrow_mut(), |slot| {
+            slot.as_mut().expect("port refers to a detached node")
+        })
+    }
+
+    /// Run a master transaction from node `from` and deliver it
+    fn transfer(
+        &self,
+        from: usize,
+        addr: SevenBitAddress,
+        ops: &mut [Operation<'_>],
+    ) -> Result<(), MockI2cError> {
+        self.node(from).transfer(addr, ops)?;
+
+        let target = self
+            .nodes
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| *index != from)
+            .map(|(_, node)| node.borrow_mut())
+            .find(|slot| slot.as_ref().is_some_and(|hw| hw.answers(addr)))
+            .ok_or(MockI2cError::NoAcknowledge)?;
+
+        RefMut::map(target, |slot| slot.as_mut().expect("matched an empty node")).serve_master(ops)
+    }
+}
+
+impl<const N: usize> Default for VirtualI2cBus<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Handle to one controller attached to a [`VirtualI2cBus`]
+///
+/// Implements the same hardware traits as [`MockI2cHardware`], plus
+/// `embedded_hal::i2c::I2c` so it can stand in for a bus client. Master
+/// operations go out on the shared bus; slave operations act on this
+/// controller's own buffers.
+///
+/// Clones refer to the same controller, so one clone can be handed to a
+/// bus client while another serves the slave side.
+#[derive(Debug, Clone)]
+pub struct VirtualI2cPort<'a, const N: usize> {
+    bus: &'a VirtualI2cBus<N>,
+    index: usize,
+}
+
+impl<'a, const N: usize> VirtualI2cPort<'a, N> {
+    /// Borrow the underlying mock, e.g. to inject faults or a script
+    ///
+    /// # Panics
+    ///
+    /// Panics if the mock is already borrowed through this port.
+    pub fn hardware(&self) -> RefMut<'a, MockI2cHardware> {
+        self.bus.node(self.index)
+    }
+
+    /// Remove the controller from the bus and return it
+    ///
+    /// Its slot can be reused by a later [`VirtualI2cBus::attach`].
+    pub fn detach(self) -> MockI2cHardware {
+        self.bus
+            .nodes
+            .get(self.index)
+            .and_then(|node| node.borrow_mut().take())
+            .expect("port refers to a detached node")
+    }
+}
+
+impl<const N: usize> ErrorType for VirtualI2cPort<'_, N> {
+    type Error = MockI2cError;
+}
+
+impl<const N: usize> I2cHardwareCore for VirtualI2cPort<'_, N> {
+    type Config = MockI2cConfig;
+    type I2cSpeed = u32;
+    type TimingConfig = ();
+
+    fn init(&mut self, config: &mut Self::Config) -> Result<(), Self::Error> {
+        self.hardware().init(config)
+    }
+
+    fn configure_timing(
+        &mut self,
+        speed: Self::I2cSpeed,
+        timing: &Self::TimingConfig,
+    ) -> Result<u32, Self::Error> {
+        self.hardware().configure_timing(speed, timing)
+    }
+
+    fn enable_interrupts(&mut self, mask: u32) {
+        self.hardware().enable_interrupts(mask);
+    }
+
+    fn clear_interrupts(&mut self, mask: u32) {
+        self.hardware().clear_interrupts(mask);
+    }
+
+    fn handle_interrupt(&mut self) {
+        self.hardware().handle_interrupt();
+    }
+}
+
+impl<const N: usize> I2cBusRecovery for VirtualI2cPort<'_, N> {
+    fn recover_bus(&mut self) -> Result<(), Self::Error> {
+        self.hardware().recover_bus()
+    }
+}
+
+// Master operations go out on the shared bus
+impl<const N: usize> I2cMaster<SevenBitAddress> for VirtualI2cPort<'_, N> {
+    fn write(&mut self, addr: SevenBitAddress, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.bus
+            .transfer(self.index, addr, &mut [Operation::Write(bytes)])
+    }
+
+    fn read(&mut self, addr: SevenBitAddress, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        self.bus
+            .transfer(self.index, addr, &mut [Operation::Read(buffer)])
+    }
+
+    fn write_read(
+        &mut self,
+        addr: SevenBitAddress,
+        bytes: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.bus.transfer(
+            self.index,
+            addr,
+            &mut [Operation::Write(bytes), Operation::Read(buffer)],
+        )
+    }
+
+    fn transaction_slice(
+        &mut self,
+        addr: SevenBitAddress,
+        ops_slice: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        self.bus.transfer(self.index, addr, ops_slice)
+    }
+}
+
+impl<const N: usize> embedded_hal::i2c::I2c<SevenBitAddress> for VirtualI2cPort<'_, N> {
+    fn transaction(
+        &mut self,
+        address: SevenBitAddress,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        self.bus.transfer(self.index, address, operations)
+    }
+}
+
+// Slave operations act on this controller's own buffers
+impl<const N: usize> I2cSlaveCore<SevenBitAddress> for VirtualI2cPort<'_, N> {
+    fn configure_slave_address(&mut self, addr: SevenBitAddress) -> Result<(), Self::Error> {
+        self.hardware().configure_slave_address(addr)
+    }
+
+    fn enable_slave_mode(&mut self) -> Result<(), Self::Error> {
+        self.hardware().enable_slave_mode()
+    }
+
+    fn disable_slave_mode(&mut self) -> Result<(), Self::Error> {
+        self.hardware().disable_slave_mode()
+    }
+
+    fn is_slave_mode_enabled(&self) -> bool {
+        self.hardware().is_slave_mode_enabled()
+    }
+
+    fn slave_address(&self) -> Option<SevenBitAddress> {
+        self.hardware().slave_address()
+    }
+}
+
+impl<const N: usize> I2cSlaveBuffer<SevenBitAddress> for VirtualI2cPort<'_, N> {
+    fn read_slave_buffer(&mut self, buffer: &mut [u8]) -> Result<usize, Self::Error> {
+        self.hardware().read_slave_buffer(buffer)
+    }
+
+    fn write_slave_response(&mut self, data: &[u8]) -> Result<(), Self::Error> {
+        self.hardware().write_slave_response(data)
+    }
+
+    fn poll_slave_data(&mut self) -> Result<Option<usize>, Self::Error> {
+        self.hardware().poll_slave_data()
+    }
+
+    fn clear_slave_buffer(&mut self) -> Result<(), Self::Error> {
+        self.hardware().clear_slave_buffer()
+    }
+
+    fn tx_buffer_space(&self) -> Result<usize, Self::Error> {
+        self.hardware().tx_buffer_space()
+    }
+
+    fn rx_buffer_count(&self) -> Result<usize, Self::Error> {
+        self.hardware().rx_buffer_count()
+    }
+}
+
+impl<const N: usize> I2cSlaveInterrupts<SevenBitAddress> for VirtualI2cPort<'_, N> {
+    fn enable_slave_interrupts(&mut self, mask: u32) {
+        self.hardware().enable_slave_interrupts(mask);
+    }
+
+    fn clear_slave_interrupts(&mut self, mask: u32) {
+        self.hardware().clear_slave_interrupts(mask);
+    }
+
+    fn slave_status(&self) -> Result<SlaveStatus, Self::Error> {
+        self.hardware().slave_status()
+    }
+
+    fn last_slave_event(&self) -> Option<I2cIsrEvent> {
+        self.hardware().last_slave_event()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i2c_hardware::{I2cExpectation, I2cFault};
+
+    #[test]
+    fn test_attach() {
+        let bus: VirtualI2cBus<2> = VirtualI2cBus::new();
+        let port = bus.attach(MockI2cHardware::new(), 0x10).unwrap();
+        assert_eq!(port.slave_address(), Some(0x10));
+        assert!(port.is_slave_mode_enabled());
+
+        // Duplicate address
+        assert_eq!(
+            bus.attach(MockI2cHardware::new(), 0x10).unwrap_err(),
+            MockI2cError::Other
+        );
+        let _second = bus.attach(MockI2cHardware::new(), 0x11).unwrap();
+        assert_eq!(bus.attached(), 2);
+
+        // Full bus
+        assert_eq!(
+            bus.attach(MockI2cHardware::new(), 0x12).unwrap_err(),
+            MockI2cError::Other
+        );
+
+        // Detaching frees the slot
+        let hardware = port.detach();
+        assert_eq!(hardware.slave_address(), Some(0x10));
+        assert!(bus.attach(MockI2cHardware::new(), 0x12).is_ok());
+    }
+
+    #[test]
+    fn test_write_delivered_to_slave() {
+        let bus: VirtualI2cBus<3> = VirtualI2cBus::new();
+        let mut controller = bus.attach(MockI2cHardware::new(), 0x10).unwrap();
+        let mut target = bus.attach(MockI2cHardware::new(), 0x42).unwrap();
+        let mut bystander = bus.attach(MockI2cHardware::new(), 0x43).unwrap();
+
+        I2cMaster::write(&mut controller, 0x42, &[0x0F, 0x01, 0x02]).unwrap();
+
+        assert_eq!(target.poll_slave_data(), Ok(Some(3)));
+        assert_eq!(target.last_slave_event(), Some(I2cIsrEvent::SlaveStop));
+        let mut buffer = [0u8; 8];
+        assert_eq!(target.read_slave_buffer(&mut buffer), Ok(3));
+        assert_eq!(&buffer[..3], &[0x0F, 0x01, 0x02]);
+
+        assert_eq!(bystander.poll_slave_data(), Ok(None));
+        assert_eq!(bystander.last_slave_event(), None);
+        assert_eq!(controller.poll_slave_data(), Ok(None));
+    }
+
+    #[test]
+    fn test_read_from_slave() {
+        let bus: VirtualI2cBus<2> = VirtualI2cBus::new();
+        let mut controller = bus.attach(MockI2cHardware::new(), 0x10).unwrap();
+        let mut target = bus.attach(MockI2cHardware::new(), 0x42).unwrap();
+
+        target.write_slave_response(&[0xA1, 0xA2]).unwrap();
+
+        let mut buffer = [0u8; 4];
+        I2cMaster::write_read(&mut controller, 0x42, &[0x05], &mut buffer).unwrap();
+        assert_eq!(buffer, [0xA1, 0xA2, 0xFF, 0xFF]);
+
+        // The register byte was received; the response was drained
+        let mut received = [0u8; 4];
+        assert_eq!(target.read_slave_buffer(&mut received), Ok(1));
+        assert_eq!(received[0], 0x05);
+        assert_eq!(target.tx_buffer_space(), Ok(64));
+    }
+
+    #[test]
+    fn test_unanswered_address_nacks() {
+        let bus: VirtualI2cBus<2> = VirtualI2cBus::new();
+        let mut controller = bus.attach(MockI2cHardware::new(), 0x10).unwrap();
+        let mut target = bus.attach(MockI2cHardware::new(), 0x42).unwrap();
+
+        assert_eq!(
+            I2cMaster::write(&mut controller, 0x50, &[0x01]),
+            Err(MockI2cError::NoAcknowledge)
+        );
+        // A controller does not answer its own transactions
+        assert_eq!(
+            I2cMaster::write(&mut controller, 0x10, &[0x01]),
+            Err(MockI2cError::NoAcknowledge)
+        );
+
+        target.disable_slave_mode().unwrap();
+        assert_eq!(
+            I2cMaster::write(&mut controller, 0x42, &[0x01]),
+            Err(MockI2cError::NoAcknowledge)
+        );
+    }
+
+    #[test]
+    fn test_oversized_write_nacks() {
+        let bus: VirtualI2cBus<2> = VirtualI2cBus::new();
+        let mut controller = bus.attach(MockI2cHardware::new(), 0x10).unwrap();
+        let _target = bus.attach(MockI2cHardware::new(), 0x42).unwrap();
+
+        assert_eq!(
+            I2cMaster::write(&mut controller, 0x42, &[0u8; 65]),
+            Err(MockI2cError::NoAcknowledge)
+        );
+        assert!(I2cMaster::write(&mut controller, 0x42, &[0u8; 64]).is_ok());
+    }
+
+    #[test]
+    fn test_master_faults_stop_delivery() {
+        let bus: VirtualI2cBus<2> = VirtualI2cBus::new();
+        let mut controller = bus.attach(MockI2cHardware::new(), 0x10).unwrap();
+        let mut target = bus.attach(MockI2cHardware::new(), 0x42).unwrap();
+
+        controller.hardware().inject_fault(I2cFault::BusStuck);
+        assert_eq!(
+            I2cMaster::write(&mut controller, 0x42, &[0x01]),
+            Err(MockI2cError::Bus)
+        );
+        assert_eq!(target.poll_slave_data(), Ok(None));
+
+        controller.recover_bus().unwrap();
+        controller
+            .hardware()
+            .expect(I2cExpectation::write(0x42, &[0x02]))
+            .unwrap();
+        I2cMaster::write(&mut controller, 0x42, &[0x02]).unwrap();
+        assert_eq!(controller.hardware().verify_script(), Ok(()));
+        assert_eq!(target.poll_slave_data(), Ok(Some(1)));
+    }
+
+    #[test]
+    fn test_embedded_hal_transaction() {
+        use embedded_hal::i2c::I2c;
+
+        let bus: VirtualI2cBus<2> = VirtualI2cBus::new();
+        let mut controller = bus.attach(MockI2cHardware::new(), 0x10).unwrap();
+        let mut target = bus.attach(MockI2cHardware::new(), 0x42).unwrap();
+
+        let mut ops = [Operation::Write(&[0x0F]), Operation::Write(&[0x02, 0x21])];
+        controller.transaction(0x42, &mut ops).unwrap();
+
+        let mut buffer = [0u8; 4];
+        assert_eq!(target.read_slave_buffer(&mut buffer), Ok(3));
+        assert_eq!(&buffer[..3], &[0x0F, 0x02, 0x21]);
+    }
+}
@@ -13,6 +13,10 @@
 //! - **Complete trait coverage**: Implements all OpenPRoT I2C hardware traits
 //! - **Configurable behavior**: Success/failure modes for testing error paths
 //! - **Event simulation**: Inject and poll I2C slave events for testing
+//! - **Fault injection**: Per-address NACKs, arbitration loss, clock-stretch
+//!   timeouts and stuck buses that only `recover_bus()` clears
+//! - **Transaction scripts**: Expected master transactions with canned
+//!   responses, checked in order with mismatch reporting
 //! - **Buffer management**: Realistic slave receive/transmit buffer simulation
 //! - **No external dependencies**: Uses only core Rust and OpenPRoT traits
 //! - **Production testing**: Comprehensive test suite with 20+ test cases
@@ -96,6 +100,37 @@
 //! }
 //! ```
 //!
+//! ## Scripted Transactions and Bus Faults
+//!
+//! ```text
+//! use openprot_platform_mock::i2c_hardware::{
+//!     I2cExpectation, I2cFault, I2cScriptMismatch, MockI2cError, MockI2cHardware,
+//! };
+//! use openprot_hal_blocking::i2c_hardware::{I2cBusRecovery, I2cMaster};
+//!
+//! let mut mock = MockI2cHardware::new();
+//!
+//! // Register read that succeeds, then a write that wedges the bus
+//! mock.expect(I2cExpectation::write_read(0x50, &[0x00], &[0x12, 0x34]))?;
+//! mock.expect(I2cExpectation::write(0x50, &[0x01, 0xAA]).with_fault(I2cFault::BusStuck))?;
+//!
+//! let mut buffer = [0u8; 2];
+//! mock.write_read(0x50, &[0x00], &mut buffer)?;
+//! assert_eq!(buffer, [0x12, 0x34]);
+//!
+//! assert_eq!(mock.write(0x50, &[0x01, 0xAA]), Err(MockI2cError::Bus));
+//! assert!(mock.is_bus_stuck());
+//! mock.recover_bus()?;
+//!
+//! // Every scripted transaction was issued as expected
+//! assert_eq!(mock.verify_script(), Ok(()));
+//!
+//! // Addresses can also be made to NACK outside the script
+//! mock.clear_script();
+//! mock.nack_address(0x51);
+//! assert_eq!(mock.write(0x51, &[0x00]), Err(MockI2cError::NoAcknowledge));
+//! ```
+//!
 //! ## SystemControl Integration
 //!
 //! ```text
@@ -260,6 +295,244 @@ impl Default for MockI2cConfig {
     }
 }
 
+/// Maximum number of transactions in a script
+pub const MAX_SCRIPT_STEPS: usize = 8;
+
+/// Maximum bytes written or read by one scripted transaction
+///
+/// Large enough for a baseline-MTU MCTP-over-SMBus packet (DSP0237).
+pub const MAX_SCRIPT_DATA: usize = 80;
+
+/// Bus fault injected into a master transaction
+///
+/// Faults are applied after the transaction has been checked against the
+/// script, so a faulted transaction still consumes its expectation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum I2cFault {
+    /// The target does not acknowledge its address
+    ///
+    /// Reported as `MockI2cError::NoAcknowledge`; no data is transferred.
+    AddressNack,
+    /// Another master wins arbitration at the given byte of the transaction
+    ///
+    /// Bytes are counted across all operations in order, writes and reads
+    /// alike; read bytes before the lost one are delivered. Reported as
+    /// `MockI2cError::ArbitrationLoss`.
+    ArbitrationLoss {
+        /// Zero-based index of the first byte that is not transferred
+        byte: usize,
+    },
+    /// The target stretches SCL past the controller's timeout
+    ///
+    /// Reported as `MockI2cError::Bus`.
+    ClockStretchTimeout,
+    /// The target holds SDA low
+    ///
+    /// Reported as `MockI2cError::Bus`, and every later master operation
+    /// fails the same way until `recover_bus()` clears the condition.
+    BusStuck,
+}
+
+/// One expected master transaction and the mock's response to it
+///
+/// The written bytes are the concatenation of all write operations in the
+/// transaction; the canned read bytes fill its read operations in order.
+///
+/// # Examples
+///
+/// ```text
+/// use openprot_platform_mock::i2c_hardware::{I2cExpectation, I2cFault};
+///
+/// // Register read: write the register index, read two bytes back
+/// let read_id = I2cExpectation::write_read(0x50, &[0x00], &[0x12, 0x34]);
+///
+/// // Write that loses arbitration on its second byte
+/// let lost = I2cExpectation::write(0x50, &[0x01, 0x02])
+///     .with_fault(I2cFault::ArbitrationLoss { byte: 1 });
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct I2cExpectation {
+    address: SevenBitAddress,
+    write: [u8; MAX_SCRIPT_DATA],
+    write_len: usize,
+    read: [u8; MAX_SCRIPT_DATA],
+    read_len: usize,
+    fault: Option<I2cFault>,
+}
+
+impl I2cExpectation {
+    /// Expect a transaction that writes `write` and then reads back `read`
+    ///
+    /// # Panics
+    ///
+    /// Panics if either slice is longer than [`MAX_SCRIPT_DATA`].
+    pub fn write_read(address: SevenBitAddress, write: &[u8], read: &[u8]) -> Self {
+        assert!(
+            write.len() <= MAX_SCRIPT_DATA && read.len() <= MAX_SCRIPT_DATA,
+            "scripted transaction exceeds MAX_SCRIPT_DATA"
+        );
+        let mut expectation = Self {
+            address,
+            write: [0; MAX_SCRIPT_DATA],
+            write_len: write.len(),
+            read: [0; MAX_SCRIPT_DATA],
+            read_len: read.len(),
+            fault: None,
+        };
+        expectation.write[..write.len()].copy_from_slice(write);
+        expectation.read[..read.len()].copy_from_slice(read);
+        expectation
+    }
+
+    /// Expect a transaction that only writes `write`
+    pub fn write(address: SevenBitAddress, write: &[u8]) -> Self {
+        Self::write_read(address, write, &[])
+    }
+
+    /// Expect a transaction that only reads, answering with `read`
+    pub fn read(address: SevenBitAddress, read: &[u8]) -> Self {
+        Self::write_read(address, &[], read)
+    }
+
+    /// Inject `fault` into the transaction
+    pub fn with_fault(mut self, fault: I2cFault) -> Self {
+        self.fault = Some(fault);
+        self
+    }
+
+    fn written(&self) -> &[u8] {
+        &self.write[..self.write_len]
+    }
+
+    fn canned(&self) -> &[u8] {
+        &self.read[..self.read_len]
+    }
+}
+
+/// How a master transaction departed from the script
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum I2cScriptMismatch {
+    /// A transaction arrived after the script was exhausted
+    Unexpected {
+        /// Index the transaction would have had in the script
+        step: usize,
+        /// Address of the unexpected transaction
+        address: SevenBitAddress,
+    },
+    /// The transaction addressed a different target
+    Address {
+        /// Index of the expectation in the script
+        step: usize,
+        /// Address the script expected
+        expected: SevenBitAddress,
+        /// Address the master used
+        actual: SevenBitAddress,
+    },
+    /// The written bytes differ from the expectation
+    WriteData {
+        /// Index of the expectation in the script
+        step: usize,
+        /// Offset of the first differing byte, or the length of the
+        /// shorter sequence if one is a prefix of the other
+        offset: usize,
+    },
+    /// The transaction reads a different number of bytes than scripted
+    ReadLength {
+        /// Index of the expectation in the script
+        step: usize,
+        /// Number of canned bytes in the script
+        expected: usize,
+        /// Number of bytes the master read
+        actual: usize,
+    },
+    /// Scripted transactions were never issued
+    Incomplete {
+        /// Number of expectations left
+        remaining: usize,
+    },
+}
+
+/// Transaction script and its progress
+#[derive(Debug)]
+struct I2cScript {
+    steps: [Option<I2cExpectation>; MAX_SCRIPT_STEPS],
+    len: usize,
+    next: usize,
+    mismatch: Option<I2cScriptMismatch>,
+}
+
+impl I2cScript {
+    const fn new() -> Self {
+        Self {
+            steps: [None; MAX_SCRIPT_STEPS],
+            len: 0,
+            next: 0,
+            mismatch: None,
+        }
+    }
+
+    fn is_active(&self) -> bool {
+        self.len > 0
+    }
+
+    /// Consume the next expectation, checking the transaction against it
+    fn check(
+        &mut self,
+        address: SevenBitAddress,
+        ops: &[Operation<'_>],
+    ) -> Result<I2cExpectation, I2cScriptMismatch> {
+        let step = self.next;
+        let expectation = self
+            .steps
+            .get(step)
+            .copied()
+            .flatten()
+            .ok_or(I2cScriptMismatch::Unexpected { step, address })?;
+        self.next += 1;
+
+        if address != expectation.address {
+            return Err(I2cScriptMismatch::Address {
+                step,
+                expected: expectation.address,
+                actual: address,
+            });
+        }
+
+        let expected = expectation.written();
+        let mut offset = 0;
+        for op in ops {
+            if let Operation::Write(bytes) = op {
+                for &byte in bytes.iter() {
+                    if expected.get(offset) != Some(&byte) {
+                        return Err(I2cScriptMismatch::WriteData { step, offset });
+                    }
+                    offset += 1;
+                }
+            }
+        }
+        if offset != expected.len() {
+            return Err(I2cScriptMismatch::WriteData { step, offset });
+        }
+
+        let actual = ops
+            .iter()
+            .map(|op| match op {
+                Operation::Read(buffer) => buffer.len(),
+                Operation::Write(_) => 0,
+            })
+            .sum();
+        if actual != expectation.read_len {
+            return Err(I2cScriptMismatch::ReadLength {
+                step,
+                expected: expectation.read_len,
+                actual,
+            });
+        }
+
+        Ok(expectation)
+    }
+}
+
 /// Mock I2C hardware implementation
 ///
 /// This implementation provides the bare minimum functionality needed to satisfy
@@ -285,10 +558,19 @@ impl Default for MockI2cConfig {
 /// last_slave_event         |     1        |    1
 /// [padding]                |     7        |    -
 /// -------------------------|--------------|----------
-/// TOTAL                    |   168 bytes  |    8
+/// Subtotal (base + slave)  |   168 bytes  |    8
+///
+/// nack_addresses           |    16        |   16
+/// pending_fault            |    16        |    8
+/// bus_stuck                |     1        |    1
+/// bus_recoveries           |     8        |    8
+/// script                   |  1648        |    8
+/// -------------------------|--------------|----------
+/// TOTAL                    |  1856 bytes  |   16
 /// ```
 ///
-/// **Final Size**: 168 bytes per instance (72% reduction from original 608 bytes)
+/// **Final Size**: 1856 bytes per instance on 64-bit targets (the compiler
+/// packs `bus_stuck` into existing padding)
 ///
 /// **Memory Breakdown**:
 /// - Base fields: 16 bytes (config, flags, addresses)
@@ -296,6 +578,9 @@ impl Default for MockI2cConfig {
 /// - Counters: 16 bytes (2x usize = 2x 8 bytes on 64-bit)
 /// - Event storage: 1 byte (enum discriminant)
 /// - Padding: 7 bytes (for alignment)
+/// - Transaction script: 1648 bytes (8x 200-byte expectations + 3 counters
+///   and the first mismatch), see [`MAX_SCRIPT_STEPS`] and [`MAX_SCRIPT_DATA`]
+/// - Fault state: 40 bytes (NACK bitmap, pending fault, recovery counter)
 ///
 /// # Examples
 ///
@@ -341,6 +626,18 @@ pub struct MockI2cHardware {
     slave_tx_count: usize,
     /// Most recent slave event that occurred (1 byte: `Option<enum>`)
     last_slave_event: Option<openprot_hal_blocking::i2c_hardware::slave::I2cIsrEvent>,
+
+    // Fault injection fields
+    /// Addresses that never acknowledge, one bit per 7-bit address
+    nack_addresses: u128,
+    /// Fault applied to the next unscripted master transaction
+    pending_fault: Option<I2cFault>,
+    /// Whether a `BusStuck` fault is waiting for `recover_bus()`
+    bus_stuck: bool,
+    /// Number of successful `recover_bus()` calls
+    bus_recoveries: usize,
+    /// Scripted master transactions
+    script: I2cScript,
 }
 
 impl MockI2cHardware {
@@ -368,6 +665,11 @@ impl MockI2cHardware {
             slave_tx_buffer: [0; 64],
             slave_tx_count: 0,
             last_slave_event: None,
+            nack_addresses: 0,
+            pending_fault: None,
+            bus_stuck: false,
+            bus_recoveries: 0,
+            script: I2cScript::new(),
         }
     }
 
@@ -381,14 +683,7 @@ impl MockI2cHardware {
                 success: false,
                 frequency: 100_000,
             },
-            initialized: false,
-            slave_enabled: false,
-            slave_address: None,
-            slave_rx_buffer: [0; 64],
-            slave_rx_count: 0,
-            slave_tx_buffer: [0; 64],
-            slave_tx_count: 0,
-            last_slave_event: None,
+            ..Self::new()
         }
     }
 
@@ -432,6 +727,171 @@ impl Default for MockI2cHardware {
     }
 }
 
+// Fault injection and transaction scripting
+impl MockI2cHardware {
+    /// Append an expected master transaction to the script
+    ///
+    /// Once a script holds at least one expectation, every master
+    /// transaction is checked against the next one in order. A transaction
+    /// that does not match fails with `MockI2cError::Other` and the first
+    /// mismatch is kept for [`verify_script`](Self::verify_script).
+    ///
+    /// # Errors
+    ///
+    /// Returns `MockI2cError::Other` if the script already holds
+    /// [`MAX_SCRIPT_STEPS`] expectations.
+    ///
+    /// # Examples
+    ///
+    /// ```text
+    /// use openprot_platform_mock::i2c_hardware::{I2cExpectation, MockI2cHardware};
+    /// use openprot_hal_blocking::i2c_hardware::I2cMaster;
+    ///
+    /// let mut mock = MockI2cHardware::new();
+    /// mock.expect(I2cExpectation::write_read(0x50, &[0x00], &[0x12, 0x34]))?;
+    ///
+    /// let mut buffer = [0u8; 2];
+    /// mock.write_read(0x50, &[0x00], &mut buffer)?;
+    /// assert_eq!(buffer, [0x12, 0x34]);
+    /// assert_eq!(mock.verify_script(), Ok(()));
+    /// ```
+    pub fn expect(&mut self, expectation: I2cExpectation) -> Result<(), MockI2cError> {
+        let slot = self
+            .script
+            .steps
+            .get_mut(self.script.len)
+            .ok_or(MockI2cError::Other)?;
+        *slot = Some(expectation);
+        self.script.len += 1;
+        Ok(())
+    }
+
+    /// Check that the script was followed to the end
+    ///
+    /// # Errors
+    ///
+    /// Returns the first mismatch seen, or `I2cScriptMismatch::Incomplete`
+    /// if expectations remain unconsumed.
+    pub fn verify_script(&self) -> Result<(), I2cScriptMismatch> {
+        if let Some(mismatch) = self.script.mismatch {
+            return Err(mismatch);
+        }
+        match self.script.len - self.script.next {
+            0 => Ok(()),
+            remaining => Err(I2cScriptMismatch::Incomplete { remaining }),
+        }
+    }
+
+    /// Discard the script and any recorded mismatch
+    ///
+    /// Master transactions are unchecked again afterwards.
+    pub fn clear_script(&mut self) {
+        self.script = I2cScript::new();
+    }
+
+    /// Make `addr` NACK its address on every master transaction
+    ///
+    /// Takes precedence over scripted and injected faults.
+    pub fn nack_address(&mut self, addr: SevenBitAddress) {
+        self.nack_addresses |= 1u128 << (addr & 0x7F);
+    }
+
+    /// Inject a fault into the next master transaction
+    ///
+    /// The fault is one-shot. A scripted transaction that carries its own
+    /// fault leaves this one pending for the transaction after it.
+    pub fn inject_fault(&mut self, fault: I2cFault) {
+        self.pending_fault = Some(fault);
+    }
+
+    /// Remove NACKed addresses and any pending injected fault
+    ///
+    /// A stuck bus stays stuck; only `recover_bus()` clears it.
+    pub fn clear_faults(&mut self) {
+        self.nack_addresses = 0;
+        self.pending_fault = None;
+    }
+
+    /// Check if a `BusStuck` fault is waiting for `recover_bus()`
+    pub fn is_bus_stuck(&self) -> bool {
+        self.bus_stuck
+    }
+
+    /// Number of times `recover_bus()` has succeeded
+    pub fn bus_recoveries(&self) -> usize {
+        self.bus_recoveries
+    }
+
+    /// Run one master transaction against the script and fault state
+    ///
+    /// Read operations are filled from the scripted response, or with
+    /// `0xFF` when no script is active.
+    pub(crate) fn transfer(
+        &mut self,
+        addr: SevenBitAddress,
+        ops: &mut [Operation<'_>],
+    ) -> Result<(), MockI2cError> {
+        self.check_success()?;
+        if self.bus_stuck {
+            return Err(MockI2cError::Bus);
+        }
+
+        let expectation = if self.script.is_active() {
+            match self.script.check(addr, ops) {
+                Ok(expectation) => Some(expectation),
+                Err(mismatch) => {
+                    self.script.mismatch.get_or_insert(mismatch);
+                    return Err(MockI2cError::Other);
+                }
+            }
+        } else {
+            None
+        };
+
+        let fault = if self.nack_addresses & (1u128 << (addr & 0x7F)) != 0 {
+            Some(I2cFault::AddressNack)
+        } else {
+            expectation
+                .and_then(|expectation| expectation.fault)
+                .or_else(|| self.pending_fault.take())
+        };
+        let lost_at = match fault {
+            Some(I2cFault::AddressNack) => return Err(MockI2cError::NoAcknowledge),
+            Some(I2cFault::ClockStretchTimeout) => return Err(MockI2cError::Bus),
+            Some(I2cFault::BusStuck) => {
+                self.bus_stuck = true;
+                return Err(MockI2cError::Bus);
+            }
+            Some(I2cFault::ArbitrationLoss { byte }) => Some(byte),
+            None => None,
+        };
+
+        let canned = expectation.as_ref().map_or(&[][..], I2cExpectation::canned);
+        let mut position = 0;
+        let mut read_offset = 0;
+        for op in ops.iter_mut() {
+            match op {
+                Operation::Write(bytes) => position += bytes.len(),
+                Operation::Read(buffer) => {
+                    for slot in buffer.iter_mut() {
+                        if lost_at.is_some_and(|byte| position >= byte) {
+                            return Err(MockI2cError::ArbitrationLoss);
+                        }
+                        *slot = canned.get(read_offset).copied().unwrap_or(0xFF);
+                        read_offset += 1;
+                        position += 1;
+                    }
+                }
+            }
+        }
+
+        match lost_at {
+            Some(_) => Err(MockI2cError::ArbitrationLoss),
+            None => Ok(()),
+        }
+    }
+}
+
 impl ErrorType for MockI2cHardware {
     type Error = MockI2cError;
 }
@@ -625,60 +1085,40 @@ impl I2cBusRecovery for MockI2cHardware {
     /// assert!(failing_mock.recover_bus().is_err());
     /// ```
     fn recover_bus(&mut self) -> Result<(), Self::Error> {
-        self.check_success()
+        self.check_success()?;
+        self.bus_stuck = false;
+        self.bus_recoveries += 1;
+        Ok(())
     }
 }
 
 impl I2cMaster<SevenBitAddress> for MockI2cHardware {
-    fn write(&mut self, _addr: SevenBitAddress, _bytes: &[u8]) -> Result<(), Self::Error> {
-        self.check_success()
+    fn write(&mut self, addr: SevenBitAddress, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.transfer(addr, &mut [Operation::Write(bytes)])
     }
 
-    fn read(&mut self, _addr: SevenBitAddress, buffer: &mut [u8]) -> Result<(), Self::Error> {
-        self.check_success()?;
-        // Fill buffer with dummy data
-        for byte in buffer.iter_mut() {
-            *byte = 0xFF;
-        }
-        Ok(())
+    fn read(&mut self, addr: SevenBitAddress, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        self.transfer(addr, &mut [Operation::Read(buffer)])
     }
 
     fn write_read(
         &mut self,
-        _addr: SevenBitAddress,
-        _bytes: &[u8],
+        addr: SevenBitAddress,
+        bytes: &[u8],
         buffer: &mut [u8],
     ) -> Result<(), Self::Error> {
-        self.check_success()?;
-        // Fill buffer with dummy data
-        for byte in buffer.iter_mut() {
-            *byte = 0xFF;
-        }
-        Ok(())
+        self.transfer(
+            addr,
+            &mut [Operation::Write(bytes), Operation::Read(buffer)],
+        )
     }
 
     fn transaction_slice(
         &mut self,
-        _addr: SevenBitAddress,
+        addr: SevenBitAddress,
         ops_slice: &mut [Operation<'_>],
     ) -> Result<(), Self::Error> {
-        self.check_success()?;
-
-        // Process each operation
-        for op in ops_slice.iter_mut() {
-            match op {
-                Operation::Read(buffer) => {
-                    // Fill read buffers with dummy data
-                    for byte in buffer.iter_mut() {
-                        *byte = 0xFF;
-                    }
-                }
-                Operation::Write(_) => {
-                    // Write operations are no-ops in mock
-                }
-            }
-        }
-        Ok(())
+        self.transfer(addr, ops_slice)
     }
 }
 
@@ -815,6 +1255,57 @@ impl openprot_hal_blocking::i2c_hardware::slave::I2cSlaveInterrupts<SevenBitAddr
     }
 }
 
+// Virtual bus support
+impl MockI2cHardware {
+    /// Check if this instance answers as a slave at `addr`
+    pub(crate) fn answers(&self, addr: SevenBitAddress) -> bool {
+        self.slave_enabled && self.slave_address == Some(addr)
+    }
+
+    /// Serve a master transaction addressed to this slave
+    ///
+    /// Written bytes replace the receive buffer; reads drain the transmit
+    /// buffer and are padded with `0xFF`. Events are raised in bus order and
+    /// the transaction ends with `SlaveStop`, which stays latched.
+    ///
+    /// A write that overflows the receive buffer is NACKed.
+    pub(crate) fn serve_master(&mut self, ops: &mut [Operation<'_>]) -> Result<(), MockI2cError> {
+        use openprot_hal_blocking::i2c_hardware::slave::I2cIsrEvent;
+
+        let mut received = 0;
+        for op in ops.iter_mut() {
+            match op {
+                Operation::Write(bytes) => {
+                    self.inject_slave_event(I2cIsrEvent::SlaveWrReq);
+                    let end = received + bytes.len();
+                    let dst_slice = self
+                        .slave_rx_buffer
+                        .get_mut(received..end)
+                        .ok_or(MockI2cError::NoAcknowledge)?;
+                    dst_slice.copy_from_slice(bytes);
+                    received = end;
+                    self.slave_rx_count = received;
+                    self.inject_slave_event(I2cIsrEvent::SlaveWrRecvd);
+                }
+                Operation::Read(buffer) => {
+                    self.inject_slave_event(I2cIsrEvent::SlaveRdReq);
+                    let response = self
+                        .slave_tx_buffer
+                        .get(..self.slave_tx_count)
+                        .unwrap_or(&[]);
+                    for (index, slot) in buffer.iter_mut().enumerate() {
+                        *slot = response.get(index).copied().unwrap_or(0xFF);
+                    }
+                    self.slave_tx_count = 0;
+                    self.inject_slave_event(I2cIsrEvent::SlaveRdProc);
+                }
+            }
+        }
+        self.inject_slave_event(I2cIsrEvent::SlaveStop);
+        Ok(())
+    }
+}
+
 // Non-blocking trait implementations
 impl MockI2cHardware {
     /// Inject data into the slave receive buffer for testing
@@ -1025,6 +1516,18 @@ where
     pub fn system_control_mut(&mut self) -> &mut S {
         &mut self.system_control
     }
+
+    /// Get a reference to the underlying mock I2C hardware
+    pub fn base_hardware(&self) -> &MockI2cHardware {
+        &self.base_hardware
+    }
+
+    /// Get a mutable reference to the underlying mock I2C hardware
+    ///
+    /// Gives access to the scripting and fault injection methods.
+    pub fn base_hardware_mut(&mut self) -> &mut MockI2cHardware {
+        &mut self.base_hardware
+    }
 }
 
 impl<S> ErrorType for MockI2cHardwareWithSystem<S> {
@@ -1777,4 +2280,201 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_scripted_transactions() {
+        let mut mock = MockI2cHardware::new();
+        mock.expect(I2cExpectation::write_read(0x50, &[0x00], &[0x12, 0x34]))
+            .unwrap();
+        mock.expect(I2cExpectation::write(0x51, &[0xAA, 0xBB]))
+            .unwrap();
+        mock.expect(I2cExpectation::read(0x52, &[0x01, 0x02, 0x03]))
+            .unwrap();
+        assert_eq!(
+            mock.verify_script(),
+            Err(I2cScriptMismatch::Incomplete { remaining: 3 })
+        );
+
+        let mut buffer = [0u8; 2];
+        mock.write_read(0x50, &[0x00], &mut buffer).unwrap();
+        assert_eq!(buffer, [0x12, 0x34]);
+
+        // Writes are matched across operations of one transaction
+        let mut ops = [Operation::Write(&[0xAA]), Operation::Write(&[0xBB])];
+        mock.transaction_slice(0x51, &mut ops).unwrap();
+
+        let mut buffer = [0u8; 3];
+        mock.read(0x52, &mut buffer).unwrap();
+        assert_eq!(buffer, [0x01, 0x02, 0x03]);
+        assert_eq!(mock.verify_script(), Ok(()));
+
+        assert_eq!(mock.write(0x50, &[0x00]), Err(MockI2cError::Other));
+        assert_eq!(
+            mock.verify_script(),
+            Err(I2cScriptMismatch::Unexpected {
+                step: 3,
+                address: 0x50
+            })
+        );
+
+        mock.clear_script();
+        assert!(mock.write(0x50, &[0x00]).is_ok());
+        assert_eq!(mock.verify_script(), Ok(()));
+    }
+
+    #[test]
+    fn test_script_mismatches() {
+        let mut mock = MockI2cHardware::new();
+        mock.expect(I2cExpectation::write(0x50, &[0x01])).unwrap();
+        assert_eq!(mock.write(0x51, &[0x01]), Err(MockI2cError::Other));
+        assert_eq!(
+            mock.verify_script(),
+            Err(I2cScriptMismatch::Address {
+                step: 0,
+                expected: 0x50,
+                actual: 0x51
+            })
+        );
+
+        let mut mock = MockI2cHardware::new();
+        mock.expect(I2cExpectation::write(0x50, &[0x01, 0x02]))
+            .unwrap();
+        mock.expect(I2cExpectation::write(0x50, &[0x01, 0x02]))
+            .unwrap();
+        assert_eq!(mock.write(0x50, &[0x01, 0x03]), Err(MockI2cError::Other));
+        // Only the first mismatch is kept
+        assert_eq!(mock.write(0x50, &[0x01]), Err(MockI2cError::Other));
+        assert_eq!(
+            mock.verify_script(),
+            Err(I2cScriptMismatch::WriteData { step: 0, offset: 1 })
+        );
+
+        let mut mock = MockI2cHardware::new();
+        mock.expect(I2cExpectation::write(0x50, &[0x01, 0x02]))
+            .unwrap();
+        assert_eq!(mock.write(0x50, &[0x01]), Err(MockI2cError::Other));
+        assert_eq!(
+            mock.verify_script(),
+            Err(I2cScriptMismatch::WriteData { step: 0, offset: 1 })
+        );
+
+        let mut mock = MockI2cHardware::new();
+        mock.expect(I2cExpectation::read(0x50, &[0x01, 0x02]))
+            .unwrap();
+        let mut buffer = [0u8; 4];
+        assert_eq!(mock.read(0x50, &mut buffer), Err(MockI2cError::Other));
+        assert_eq!(
+            mock.verify_script(),
+            Err(I2cScriptMismatch::ReadLength {
+                step: 0,
+                expected: 2,
+                actual: 4
+            })
+        );
+    }
+
+    #[test]
+    fn test_script_capacity() {
+        let mut mock = MockI2cHardware::new();
+        for _ in 0..MAX_SCRIPT_STEPS {
+            mock.expect(I2cExpectation::write(0x50, &[0x00])).unwrap();
+        }
+        assert_eq!(
+            mock.expect(I2cExpectation::write(0x50, &[0x00])),
+            Err(MockI2cError::Other)
+        );
+    }
+
+    #[test]
+    fn test_address_nack() {
+        let mut mock = MockI2cHardware::new();
+        mock.nack_address(0x50);
+
+        assert_eq!(mock.write(0x50, &[0x01]), Err(MockI2cError::NoAcknowledge));
+        assert_eq!(mock.write(0x50, &[0x01]), Err(MockI2cError::NoAcknowledge));
+        assert!(mock.write(0x51, &[0x01]).is_ok());
+
+        // A NACKed transaction still consumes its expectation
+        mock.expect(I2cExpectation::write(0x50, &[0x01])).unwrap();
+        assert_eq!(mock.write(0x50, &[0x01]), Err(MockI2cError::NoAcknowledge));
+        assert_eq!(mock.verify_script(), Ok(()));
+
+        mock.clear_faults();
+        mock.clear_script();
+        assert!(mock.write(0x50, &[0x01]).is_ok());
+    }
+
+    #[test]
+    fn test_arbitration_loss() {
+        let mut mock = MockI2cHardware::new();
+        mock.expect(
+            I2cExpectation::write_read(0x50, &[0x00], &[0x11, 0x22, 0x33])
+                .with_fault(I2cFault::ArbitrationLoss { byte: 3 }),
+        )
+        .unwrap();
+
+        // Byte 0 is the write; reads at bytes 1 and 2 complete
+        let mut buffer = [0u8; 3];
+        assert_eq!(
+            mock.write_read(0x50, &[0x00], &mut buffer),
+            Err(MockI2cError::ArbitrationLoss)
+        );
+        assert_eq!(buffer, [0x11, 0x22, 0x00]);
+        assert_eq!(mock.verify_script(), Ok(()));
+
+        // One-shot injection without a script
+        mock.clear_script();
+        mock.inject_fault(I2cFault::ArbitrationLoss { byte: 0 });
+        assert_eq!(
+            mock.write(0x50, &[0x01]),
+            Err(MockI2cError::ArbitrationLoss)
+        );
+        assert!(mock.write(0x50, &[0x01]).is_ok());
+    }
+
+    #[test]
+    fn test_clock_stretch_timeout() {
+        let mut mock = MockI2cHardware::new();
+        mock.inject_fault(I2cFault::ClockStretchTimeout);
+
+        let mut buffer = [0u8; 2];
+        assert_eq!(mock.read(0x50, &mut buffer), Err(MockI2cError::Bus));
+        assert!(!mock.is_bus_stuck());
+        assert!(mock.read(0x50, &mut buffer).is_ok());
+    }
+
+    #[test]
+    fn test_bus_stuck_until_recovery() {
+        let mut mock = MockI2cHardware::new();
+        mock.expect(I2cExpectation::write(0x50, &[0x01]).with_fault(I2cFault::BusStuck))
+            .unwrap();
+
+        assert_eq!(mock.write(0x50, &[0x01]), Err(MockI2cError::Bus));
+        assert!(mock.is_bus_stuck());
+        assert_eq!(mock.write(0x51, &[0x01]), Err(MockI2cError::Bus));
+        // Transactions on a stuck bus never reach the script
+        assert_eq!(mock.verify_script(), Ok(()));
+
+        assert!(mock.recover_bus().is_ok());
+        assert!(!mock.is_bus_stuck());
+        assert_eq!(mock.bus_recoveries(), 1);
+        mock.clear_script();
+        assert!(mock.write(0x51, &[0x01]).is_ok());
+    }
+
+    #[test]
+    fn test_fault_injection_through_system_wrapper() {
+        use crate::system_control::{MockClockId, MockResetId, MockSystemControl};
+
+        let system_control = MockSystemControl::new();
+        let mut i2c_with_system =
+            MockI2cHardwareWithSystem::new(system_control, MockClockId::I2c1, MockResetId::I2c1);
+
+        i2c_with_system.base_hardware_mut().nack_address(0x50);
+        assert_eq!(
+            i2c_with_system.write(0x50, &[0x01]),
+            Err(MockI2cError::NoAcknowledge)
+        );
+        assert!(!i2c_with_system.base_hardware().is_bus_stuck());
+    }
 }
@@ -0,0 +1,487 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! Mock I3C devices on a deterministic virtual bus
+//!
+//! This module provides [`MockI3cDevice`], a mock of the `I3cTarget` trait,
+//! and [`VirtualI3cBus`], which connects several devices so that a private
+//! write issued by one is delivered to the target holding the addressed
+//! dynamic address. Together they let transport code such as MCTP-over-I3C
+//! be exercised end-to-end on the host.
+//!
+//! # Features
+//!
+//! - **Dynamic addressing**: Tests assign dynamic addresses directly, standing
+//!   in for ENTDAA/SETDASA
+//! - **Private writes**: Delivered whole to the target's receive queue, in
+//!   bus order
+//! - **Maximum write length**: Writes longer than the target's MWL are
+//!   rejected with `Overrun`
+//! - **Configurable behavior**: Success/failure modes for testing error paths
+//! - **Deterministic**: Transfers complete synchronously, in call order
+//!
+//! Received writes are queued on the heap, so this module needs `alloc`.
+//!
+//! # Examples
+//!
+//! ```text
+//! use openprot_platform_mock::i3c::{MockI3cDevice, VirtualI3cBus};
+//! use openprot_hal_blocking::i3c::{I3cController, I3cTarget};
+//!
+//! let bus: VirtualI3cBus<2> = VirtualI3cBus::new();
+//! let mut controller = bus.attach(MockI3cDevice::new())?;
+//! let mut target = bus.attach(MockI3cDevice::with_dynamic_address(0x21))?;
+//!
+//! controller.private_write(0x21, &[0x01, 0x02, 0x03])?;
+//!
+//! let mut buffer = [0u8; 8];
+//! assert_eq!(target.take_private_write(&mut buffer)?, Some(3));
+//! assert_eq!(&buffer[..3], &[0x01, 0x02, 0x03]);
+//! ```
+
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+use core::cell::{RefCell, RefMut};
+
+use openprot_hal_blocking::i3c::{I3cController, I3cError, I3cErrorKind, I3cErrorType, I3cTarget};
+
+/// Maximum write length of a new mock device
+pub const DEFAULT_MAX_WRITE_LENGTH: usize = 256;
+
+/// Mock error type for I3C operations
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MockI3cError {
+    /// No device on the bus holds the dynamic address
+    AddressNack,
+    /// The write exceeds the target's maximum write length
+    Overrun,
+    /// The bus is full or the dynamic address is already taken
+    AddressInUse,
+    /// Simulated hardware failure (failing mock)
+    HardwareFailure,
+}
+
+impl I3cError for MockI3cError {
+    fn kind(&self) -> I3cErrorKind {
+        match self {
+            MockI3cError::AddressNack => I3cErrorKind::AddressNack,
+            MockI3cError::Overrun => I3cErrorKind::Overrun,
+            MockI3cError::AddressInUse => I3cErrorKind::Other,
+            MockI3cError::HardwareFailure => I3cErrorKind::Bus,
+        }
+    }
+}
+
+/// Mock I3C device
+///
+/// On its own it acts as a target whose receive queue is filled with
+/// [`inject_private_write`](Self::inject_private_write). Attached to a
+/// [`VirtualI3cBus`], it also receives the private writes other devices
+/// send to its dynamic address.
+#[derive(Debug)]
+pub struct MockI3cDevice {
+    /// Whether operations succeed
+    success: bool,
+    /// Assigned dynamic address
+    dynamic_address: Option<u8>,
+    /// Longest private write accepted
+    max_write_length: usize,
+    /// Private writes received, oldest first
+    received: VecDeque<Vec<u8>>,
+}
+
+impl MockI3cDevice {
+    /// Create a new mock device with no dynamic address
+    pub fn new() -> Self {
+        Self {
+            success: true,
+            dynamic_address: None,
+            max_write_length: DEFAULT_MAX_WRITE_LENGTH,
+            received: VecDeque::new(),
+        }
+    }
+
+    /// Create a new mock device already assigned dynamic address `addr`
+    pub fn with_dynamic_address(addr: u8) -> Self {
+        Self {
+            dynamic_address: Some(addr),
+            ..Self::new()
+        }
+    }
+
+    /// Create a new mock that will fail operations
+    ///
+    /// All trait operations return `MockI3cError::HardwareFailure`.
+    pub fn new_failing() -> Self {
+        Self {
+            success: false,
+            ..Self::new()
+        }
+    }
+
+    /// Set whether operations succeed
+    pub fn set_success(&mut self, success: bool) {
+        self.success = success;
+    }
+
+    /// Assign or clear the dynamic address, as ENTDAA or RSTDAA would
+    pub fn set_dynamic_address(&mut self, addr: Option<u8>) {
+        self.dynamic_address = addr;
+    }
+
+    /// Set the maximum write length, as SETMWL would
+    pub fn set_max_write_length(&mut self, length: usize) {
+        self.max_write_length = length;
+    }
+
+    /// Queue `data` as if the controller had written it
+    pub fn inject_private_write(&mut self, data: &[u8]) {
+        self.received.push_back(data.to_vec());
+    }
+
+    /// Number of received private writes not yet taken
+    pub fn pending_writes(&self) -> usize {
+        self.received.len()
+    }
+
+    fn check_success(&self) -> Result<(), MockI3cError> {
+        if self.success {
+            Ok(())
+        } else {
+            Err(MockI3cError::HardwareFailure)
+        }
+    }
+
+    /// Accept a private write addressed to this device
+    fn serve_private_write(&mut self, data: &[u8]) -> Result<(), MockI3cError> {
+        self.check_success()?;
+        if data.len() > self.max_write_length {
+            return Err(MockI3cError::Overrun);
+        }
+        self.received.push_back(data.to_vec());
+        Ok(())
+    }
+}
+
+impl Default for MockI3cDevice {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl I3cErrorType for MockI3cDevice {
+    type Error = MockI3cError;
+}
+
+impl I3cTarget for MockI3cDevice {
+    fn dynamic_address(&self) -> Option<u8> {
+        self.dynamic_address
+    }
+
+    fn max_write_length(&self) -> usize {
+        self.max_write_length
+    }
+
+    fn take_private_write(&mut self, buffer: &mut [u8]) -> Result<Option<usize>, Self::Error> {
+        self.check_success()?;
+        let Some(data) = self.received.pop_front() else {
+            return Ok(None);
+        };
+        let count = data.len().min(buffer.len());
+        buffer[..count].copy_from_slice(&data[..count]);
+        Ok(Some(count))
+    }
+}
+
+/// Shared in-memory I3C bus with room for `N` mock devices
+///
+/// Attached devices are reached through [`VirtualI3cPort`] handles that
+/// borrow the bus, so every port sees the same wires.
+#[derive(Debug)]
+pub struct VirtualI3cBus<const N: usize> {
+    nodes: [RefCell<Option<MockI3cDevice>>; N],
+}
+
+impl<const N: usize> VirtualI3cBus<N> {
+    /// Create an empty bus
+    pub fn new() -> Self {
+        Self {
+            nodes: core::array::from_fn(|_| RefCell::new(None)),
+        }
+    }
+
+    /// Attach `device` to the bus
+    ///
+    /// The returned port drives it as both controller and target.
+    ///
+    /// # Errors
+    ///
+    /// `MockI3cError::AddressInUse` if the bus is full or another device
+    /// holds the same dynamic address.
+    pub fn attach(&self, device: MockI3cDevice) -> Result<VirtualI3cPort<'_, N>, MockI3cError> {
+        if device
+            .dynamic_address
+            .is_some_and(|addr| self.find(addr).is_some())
+        {
+            return Err(MockI3cError::AddressInUse);
+        }
+        let index = self
+            .nodes
+            .iter()
+            .position(|node| node.borrow().is_none())
+            .ok_or(MockI3cError::AddressInUse)?;
+        if let Some(node) = self.nodes.get(index) {
+            *node.borrow_mut() = Some(device);
+        }
+        Ok(VirtualI3cPort { bus: self, index })
+    }
+
+    /// Number of attached devices
+    pub fn attached(&self) -> usize {
+        self.nodes
+            .iter()
+            .filter(|node| node.borrow().is_some())
+            .count()
+    }
+
+    fn node(&self, index: usize) -> RefMut<'_, MockI3cDevice> {
+        let node = self.nodes.get(index).expect("port index out of range");
+        RefMut::map(node.borrow_mut(), |slot| {
+            slot.as_mut().expect("port refers to a detached node")
+        })
+    }
+
+    /// Index of the device holding dynamic address `addr`
+    fn find(&self, addr: u8) -> Option<usize> {
+        self.nodes.iter().position(|node| {
+            node.borrow()
+                .as_ref()
+                .is_some_and(|device| device.dynamic_address == Some(addr))
+        })
+    }
+
+    /// Deliver a private write from node `from` to `addr`
+    fn private_write(&self, from: usize, addr: u8, data: &[u8]) -> Result<(), MockI3cError> {
+        self.node(from).check_success()?;
+        match self.find(addr) {
+            Some(index) if index != from => self.node(index).serve_private_write(data),
+            _ => Err(MockI3cError::AddressNack),
+        }
+    }
+}
+
+impl<const N: usize> Default for VirtualI3cBus<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Handle to one device attached to a [`VirtualI3cBus`]
+///
+/// Controller operations go out on the shared bus; target operations act on
+/// this device's own receive queue.
+///
+/// Clones refer to the same device, so one clone can be handed to a
+/// transport sender while another serves the target side.
+#[derive(Debug, Clone)]
+pub struct VirtualI3cPort<'a, const N: usize> {
+    bus: &'a VirtualI3cBus<N>,
+    index: usize,
+}
+
+impl<'a, const N: usize> VirtualI3cPort<'a, N> {
+    /// Borrow the underlying mock, e.g. to change its address or MWL
+    ///
+    /// # Panics
+    ///
+    /// Panics if the mock is already borrowed through this port.
+    pub fn device(&self) -> RefMut<'a, MockI3cDevice> {
+        self.bus.node(self.index)
+    }
+
+    /// Remove the device from the bus and return it
+    ///
+    /// Its slot can be reused by a later [`VirtualI3cBus::attach`].
+    pub fn detach(self) -> MockI3cDevice {
+        self.bus
+            .nodes
+            .get(self.index)
+            .and_then(|node| node.borrow_mut().take())
+            .expect("port refers to a detached node")
+    }
+}
+
+impl<const N: usize> I3cErrorType for VirtualI3cPort<'_, N> {
+    type Error = MockI3cError;
+}
+
+impl<const N: usize> I3cController for VirtualI3cPort<'_, N> {
+    fn private_write(&mut self, addr: u8, data: &[u8]) -> Result<(), Self::Error> {
+        self.bus.private_write(self.index, addr, data)
+    }
+}
+
+impl<const N: usize> I3cTarget for VirtualI3cPort<'_, N> {
+    fn dynamic_address(&self) -> Option<u8> {
+        self.device().dynamic_address()
+    }
+
+    fn max_write_length(&self) -> usize {
+        self.device().max_write_length()
+    }
+
+    fn take_private_write(&mut self, buffer: &mut [u8]) -> Result<Option<usize>, Self::Error> {
+        self.device().take_private_write(buffer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_standalone_target() {
+        let mut device = MockI3cDevice::with_dynamic_address(0x21);
+        assert_eq!(device.dynamic_address(), Some(0x21));
+        assert_eq!(device.max_write_length(), DEFAULT_MAX_WRITE_LENGTH);
+
+        let mut buffer = [0u8; 4];
+        assert_eq!(device.take_private_write(&mut buffer), Ok(None));
+
+        device.inject_private_write(&[1, 2]);
+        device.inject_private_write(&[3, 4, 5, 6, 7]);
+        assert_eq!(device.pending_writes(), 2);
+        assert_eq!(device.take_private_write(&mut buffer), Ok(Some(2)));
+        assert_eq!(&buffer[..2], &[1, 2]);
+        // Truncated to the buffer
+        assert_eq!(device.take_private_write(&mut buffer), Ok(Some(4)));
+        assert_eq!(buffer, [3, 4, 5, 6]);
+        assert_eq!(device.pending_writes(), 0);
+    }
+
+    #[test]
+    fn test_attach() {
+        let bus: VirtualI3cBus<2> = VirtualI3cBus::new();
+        let port = bus
+            .attach(MockI3cDevice::with_dynamic_address(0x21))
+            .unwrap();
+
+        // Duplicate dynamic address
+        assert_eq!(
+            bus.attach(MockI3cDevice::with_dynamic_address(0x21))
+                .unwrap_err(),
+            MockI3cError::AddressInUse
+        );
+        let _second = bus.attach(MockI3cDevice::new()).unwrap();
+        assert_eq!(bus.attached(), 2);
+
+        // Full bus
+        assert_eq!(
+            bus.attach(MockI3cDevice::new()).unwrap_err(),
+            MockI3cError::AddressInUse
+        );
+
+        // Detaching frees the slot
+        let device = port.detach();
+        assert_eq!(device.dynamic_address(), Some(0x21));
+        assert!(bus.attach(MockI3cDevice::new()).is_ok());
+    }
+
+    #[test]
+    fn test_private_write_delivered_to_target() {
+        let bus: VirtualI3cBus<3> = VirtualI3cBus::new();
+        let mut controller = bus.attach(MockI3cDevice::new()).unwrap();
+        let mut target = bus
+            .attach(MockI3cDevice::with_dynamic_address(0x21))
+            .unwrap();
+        let mut bystander = bus
+            .attach(MockI3cDevice::with_dynamic_address(0x22))
+            .unwrap();
+
+        controller.private_write(0x21, &[0x0F, 0x01]).unwrap();
+        controller.private_write(0x21, &[0x02]).unwrap();
+
+        let mut buffer = [0u8; 8];
+        assert_eq!(target.take_private_write(&mut buffer), Ok(Some(2)));
+        assert_eq!(&buffer[..2], &[0x0F, 0x01]);
+        assert_eq!(target.take_private_write(&mut buffer), Ok(Some(1)));
+        assert_eq!(buffer[0], 0x02);
+        assert_eq!(target.take_private_write(&mut buffer), Ok(None));
+
+        assert_eq!(bystander.take_private_write(&mut buffer), Ok(None));
+        assert_eq!(controller.take_private_write(&mut buffer), Ok(None));
+    }
+
+    #[test]
+    fn test_unassigned_address_nacks() {
+        let bus: VirtualI3cBus<2> = VirtualI3cBus::new();
+        let mut controller = bus
+            .attach(MockI3cDevice::with_dynamic_address(0x08))
+            .unwrap();
+        let target = bus.attach(MockI3cDevice::new()).unwrap();
+
+        assert_eq!(
+            controller.private_write(0x21, &[0x01]),
+            Err(MockI3cError::AddressNack)
+        );
+        // Writing to itself is not a bus transfer
+        assert_eq!(
+            controller.private_write(0x08, &[0x01]),
+            Err(MockI3cError::AddressNack)
+        );
+
+        // Once assigned, the target answers
+        target.device().set_dynamic_address(Some(0x21));
+        assert_eq!(controller.private_write(0x21, &[0x01]), Ok(()));
+        assert_eq!(target.device().pending_writes(), 1);
+    }
+
+    #[test]
+    fn test_max_write_length() {
+        let bus: VirtualI3cBus<2> = VirtualI3cBus::new();
+        let mut controller = bus.attach(MockI3cDevice::new()).unwrap();
+        let target = bus
+            .attach(MockI3cDevice::with_dynamic_address(0x21))
+            .unwrap();
+        target.device().set_max_write_length(4);
+
+        assert_eq!(target.max_write_length(), 4);
+        assert_eq!(controller.private_write(0x21, &[0; 4]), Ok(()));
+        assert_eq!(
+            controller.private_write(0x21, &[0; 5]),
+            Err(MockI3cError::Overrun)
+        );
+        assert_eq!(target.device().pending_writes(), 1);
+        assert_eq!(MockI3cError::Overrun.kind(), I3cErrorKind::Overrun);
+    }
+
+    #[test]
+    fn test_failing_mock() {
+        let bus: VirtualI3cBus<3> = VirtualI3cBus::new();
+        let mut broken = bus.attach(MockI3cDevice::new_failing()).unwrap();
+        let mut controller = bus.attach(MockI3cDevice::new()).unwrap();
+        let target = bus
+            .attach(MockI3cDevice::with_dynamic_address(0x21))
+            .unwrap();
+
+        // A failing controller puts nothing on the bus
+        assert_eq!(
+            broken.private_write(0x21, &[0x01]),
+            Err(MockI3cError::HardwareFailure)
+        );
+        let mut buffer = [0u8; 4];
+        assert_eq!(
+            broken.take_private_write(&mut buffer),
+            Err(MockI3cError::HardwareFailure)
+        );
+
+        // A failing target rejects the write
+        target.device().set_success(false);
+        assert_eq!(
+            controller.private_write(0x21, &[0x01]),
+            Err(MockI3cError::HardwareFailure)
+        );
+        target.device().set_success(true);
+        assert_eq!(target.device().pending_writes(), 0);
+    }
+}
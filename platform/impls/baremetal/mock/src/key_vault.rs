@@ -0,0 +1,1317 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! Mock Key Vault Implementation
+//!
+//! Provides a `no_std`, fixed-capacity software key vault implementing the
+//! full set of key management traits, for developing and testing key-handling
+//! services on the host before hardware vaults are available.
+//!
+//! - **Slots**: [`MOCK_VAULT_SLOTS`] slots of up to [`MOCK_VAULT_MAX_KEY_SIZE`]
+//!   bytes, each with [`MockKeyMetadata`] carrying a usage mask and label
+//! - **Policy**: keys can be used for MAC and ECDSA signing by key ID without
+//!   being exported; use locks and usage masks are checked on every call
+//! - **Lock-down**: after [`MockKeyVault::lock_down`], write and use locks can
+//!   no longer be cleared
+//! - **Erase**: key material is zeroized when a slot is erased or overwritten
+//! - **Persistence**: an optional [`KeyVaultStorage`] backend receives every
+//!   slot change and can be restored from on [`KeyVaultSetup::configure`]
+//!
+//! The MAC and signature outputs are deterministic stand-ins derived from the
+//! key and the input; they are not cryptographically meaningful.
+
+// Allow security lints for mock/test code
+#![allow(clippy::unwrap_used)]
+#![allow(clippy::expect_used)]
+#![allow(clippy::arithmetic_side_effects)]
+
+use core::marker::PhantomData;
+
+use openprot_hal_blocking::digest::{Digest, DigestAlgorithm};
+use openprot_hal_blocking::ecdsa::{self, Curve, P256Signature, P384Signature, P256, P384};
+use openprot_hal_blocking::key_vault::{
+    check_key_use, ErrorKind, ErrorType, KeyLifecycle, KeyLocking, KeyStore, KeyUsagePermissions,
+    KeyVaultSetup,
+};
+use openprot_hal_blocking::mac::{
+    self, HmacSha2_256, HmacSha2_384, HmacSha2_512, MacAlgorithm, MacOp, VaultMacInit,
+};
+use zeroize::Zeroize;
+
+/// Number of key slots in the mock vault
+pub const MOCK_VAULT_SLOTS: usize = 8;
+
+/// Largest key the mock vault can hold, in bytes
+pub const MOCK_VAULT_MAX_KEY_SIZE: usize = 64;
+
+/// Key slot identifier
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MockKeyId(pub u8);
+
+/// Key usage permissions as a bit mask
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MockKeyUsage(u8);
+
+impl MockKeyUsage {
+    /// No use permitted
+    pub const NONE: Self = Self(0);
+    /// Key may be used to compute MACs
+    pub const MAC: Self = Self(1 << 0);
+    /// Key may be used to produce signatures
+    pub const SIGN: Self = Self(1 << 1);
+    /// Key material may be read back with [`KeyLifecycle::retrieve_key`]
+    pub const EXPORT: Self = Self(1 << 2);
+
+    /// Combine two sets of permissions
+    pub const fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    /// Raw bit mask, for serialization
+    pub const fn bits(self) -> u8 {
+        self.0
+    }
+
+    /// Rebuild permissions from a raw bit mask
+    pub const fn from_bits(bits: u8) -> Self {
+        Self(bits)
+    }
+}
+
+impl KeyUsagePermissions for MockKeyUsage {
+    fn permits(&self, required: Self) -> bool {
+        self.0 & required.0 == required.0
+    }
+}
+
+/// Metadata stored alongside each key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MockKeyMetadata {
+    /// Operations the key may be used for
+    pub usage: MockKeyUsage,
+    /// Caller-defined label, e.g. a key type or derivation context
+    pub label: u32,
+}
+
+impl Default for MockKeyMetadata {
+    fn default() -> Self {
+        Self {
+            usage: MockKeyUsage::NONE,
+            label: 0,
+        }
+    }
+}
+
+/// Key material passed into and out of the vault
+///
+/// Zeroized when dropped.
+#[derive(Clone)]
+pub struct MockKeyData {
+    bytes: [u8; MOCK_VAULT_MAX_KEY_SIZE],
+    len: usize,
+}
+
+impl MockKeyData {
+    /// Copy key material into a new buffer
+    ///
+    /// Returns `None` if `bytes` is larger than [`MOCK_VAULT_MAX_KEY_SIZE`].
+    pub fn from_slice(bytes: &[u8]) -> Option<Self> {
+        let mut data = Self {
+            bytes: [0; MOCK_VAULT_MAX_KEY_SIZE],
+            len: bytes.len(),
+        };
+        data.bytes.get_mut(..bytes.len())?.copy_from_slice(bytes);
+        Some(data)
+    }
+
+    /// The key bytes
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes[..self.len]
+    }
+}
+
+impl TryFrom<&[u8]> for MockKeyData {
+    type Error = MockKeyVaultError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        Self::from_slice(bytes).ok_or(MockKeyVaultError(ErrorKind::InvalidKeySize))
+    }
+}
+
+impl core::fmt::Debug for MockKeyData {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("MockKeyData")
+            .field("len", &self.len)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for MockKeyData {
+    fn drop(&mut self) {
+        self.bytes.zeroize();
+    }
+}
+
+/// Mock key vault error
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MockKeyVaultError(pub ErrorKind);
+
+impl openprot_hal_blocking::key_vault::Error for MockKeyVaultError {
+    fn kind(&self) -> ErrorKind {
+        self.0
+    }
+}
+
+impl mac::Error for MockKeyVaultError {
+    fn kind(&self) -> mac::ErrorKind {
+        match self.0 {
+            ErrorKind::KeyNotFound | ErrorKind::AccessDenied | ErrorKind::InvalidUsage => {
+                mac::ErrorKind::PermissionDenied
+            }
+            ErrorKind::Busy => mac::ErrorKind::Busy,
+            _ => mac::ErrorKind::HardwareFailure,
+        }
+    }
+}
+
+impl ecdsa::Error for MockKeyVaultError {
+    fn kind(&self) -> ecdsa::ErrorKind {
+        match self.0 {
+            ErrorKind::KeyNotFound | ErrorKind::AccessDenied | ErrorKind::InvalidUsage => {
+                ecdsa::ErrorKind::PermissionDenied
+            }
+            ErrorKind::Busy => ecdsa::ErrorKind::Busy,
+            _ => ecdsa::ErrorKind::Other,
+        }
+    }
+}
+
+/// Persistent image of one key slot
+///
+/// Lock state is deliberately not part of the record: like hardware lock
+/// registers, locks are volatile and must be re-applied after every boot.
+#[derive(Clone)]
+pub struct SlotRecord {
+    /// Key material
+    pub key: MockKeyData,
+    /// Key metadata
+    pub metadata: MockKeyMetadata,
+}
+
+/// Abstract storage backend for persisting vault slots
+///
+/// Implementations might write to flash, a file on the host, or simply keep
+/// records in RAM for tests.
+pub trait KeyVaultStorage {
+    /// Backend-specific error type
+    type Error: core::fmt::Debug;
+
+    /// Load the record for `slot`, or `None` if nothing is stored
+    fn load(&mut self, slot: usize) -> Result<Option<SlotRecord>, Self::Error>;
+
+    /// Persist the record for `slot`, replacing any previous record
+    fn store(&mut self, slot: usize, record: &SlotRecord) -> Result<(), Self::Error>;
+
+    /// Remove the record for `slot`
+    fn erase(&mut self, slot: usize) -> Result<(), Self::Error>;
+}
+
+/// Storage backend that persists nothing
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoStorage;
+
+impl KeyVaultStorage for NoStorage {
+    type Error = core::convert::Infallible;
+
+    fn load(&mut self, _slot: usize) -> Result<Option<SlotRecord>, Self::Error> {
+        Ok(None)
+    }
+
+    fn store(&mut self, _slot: usize, _record: &SlotRecord) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn erase(&mut self, _slot: usize) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// RAM-backed storage that survives dropping and recreating the vault
+///
+/// Useful for exercising restore paths on the host.
+#[derive(Default)]
+pub struct MemoryStorage {
+    records: [Option<SlotRecord>; MOCK_VAULT_SLOTS],
+}
+
+impl MemoryStorage {
+    /// Create empty storage
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Check whether a record is stored for `slot`
+    pub fn has_record(&self, slot: usize) -> bool {
+        matches!(self.records.get(slot), Some(Some(_)))
+    }
+}
+
+impl KeyVaultStorage for MemoryStorage {
+    type Error = ErrorKind;
+
+    fn load(&mut self, slot: usize) -> Result<Option<SlotRecord>, Self::Error> {
+        self.records
+            .get(slot)
+            .cloned()
+            .ok_or(ErrorKind::KeyNotFound)
+    }
+
+    fn store(&mut self, slot: usize, record: &SlotRecord) -> Result<(), Self::Error> {
+        *self.records.get_mut(slot).ok_or(ErrorKind::KeyNotFound)? = Some(record.clone());
+        Ok(())
+    }
+
+    fn erase(&mut self, slot: usize) -> Result<(), Self::Error> {
+        *self.records.get_mut(slot).ok_or(ErrorKind::KeyNotFound)? = None;
+        Ok(())
+    }
+}
+
+/// Configuration applied by [`KeyVaultSetup::configure`]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct MockKeyVaultConfig {
+    /// Reload slot contents from the storage backend
+    pub restore_from_storage: bool,
+}
+
+#[derive(Clone)]
+struct Slot {
+    key: [u8; MOCK_VAULT_MAX_KEY_SIZE],
+    len: usize,
+    occupied: bool,
+    metadata: MockKeyMetadata,
+    write_locked: bool,
+    use_locked: bool,
+}
+
+impl Slot {
+    const EMPTY: Self = Self {
+        key: [0; MOCK_VAULT_MAX_KEY_SIZE],
+        len: 0,
+        occupied: false,
+        metadata: MockKeyMetadata {
+            usage: MockKeyUsage::NONE,
+            label: 0,
+        },
+        write_locked: false,
+        use_locked: false,
+    };
+
+    fn erase(&mut self) {
+        self.key.zeroize();
+        self.len = 0;
+        self.occupied = false;
+        self.metadata = MockKeyMetadata::default();
+    }
+
+    fn fill(&mut self, key: &[u8], metadata: MockKeyMetadata) {
+        self.erase();
+        self.key[..key.len()].copy_from_slice(key);
+        self.len = key.len();
+        self.occupied = true;
+        self.metadata = metadata;
+    }
+
+    fn record(&self) -> SlotRecord {
+        SlotRecord {
+            key: MockKeyData {
+                bytes: self.key,
+                len: self.len,
+            },
+            metadata: self.metadata,
+        }
+    }
+
+    /// Fold the key into a single word seeding the mock MAC and signature
+    fn seed(&self) -> u32 {
+        fold(0x811c_9dc5, &self.key[..self.len])
+    }
+}
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        self.key.zeroize();
+    }
+}
+
+/// FNV-1a style fold used to derive deterministic mock outputs
+fn fold(state: u32, data: &[u8]) -> u32 {
+    data.iter().fold(state, |acc, &b| {
+        (acc ^ u32::from(b)).wrapping_mul(0x0100_0193)
+    })
+}
+
+/// Software reference key vault
+///
+/// Keys are provisioned with [`KeyLifecycle::store_key`] (or the
+/// [`MockKeyVault::provision_key`] shorthand) and afterwards are named by
+/// [`MockKeyId`]. Key bytes can only be read back for keys whose usage
+/// includes [`MockKeyUsage::EXPORT`].
+///
+/// A vault created with [`MockKeyVault::with_storage`] rejects every change
+/// to slots or locks with `AccessDenied` until [`KeyVaultSetup::configure`]
+/// has run, so nothing can overwrite records that are about to be restored.
+/// `configure` itself succeeds only once, and never after lock-down.
+pub struct MockKeyVault<S: KeyVaultStorage = NoStorage> {
+    slots: [Slot; MOCK_VAULT_SLOTS],
+    storage: S,
+    configured: bool,
+    locked_down: bool,
+}
+
+impl Default for MockKeyVault {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockKeyVault {
+    /// Create an empty vault without persistent storage
+    ///
+    /// There is nothing to restore, so the vault is ready for use immediately.
+    pub const fn new() -> Self {
+        Self {
+            slots: [Slot::EMPTY; MOCK_VAULT_SLOTS],
+            storage: NoStorage,
+            configured: true,
+            locked_down: false,
+        }
+    }
+}
+
+impl<S: KeyVaultStorage> MockKeyVault<S> {
+    /// Create an empty vault backed by `storage`
+    ///
+    /// Call [`KeyVaultSetup::configure`] to restore previously stored keys.
+    pub fn with_storage(storage: S) -> Self {
+        Self {
+            slots: [Slot::EMPTY; MOCK_VAULT_SLOTS],
+            storage,
+            configured: false,
+            locked_down: false,
+        }
+    }
+
+    /// Borrow the storage backend
+    pub fn storage(&self) -> &S {
+        &self.storage
+    }
+
+    /// Import key material into a slot with the given usage permissions
+    ///
+    /// Shorthand for [`KeyLifecycle::store_key`] with an unlabelled key.
+    pub fn provision_key(
+        &mut self,
+        id: MockKeyId,
+        key: &[u8],
+        usage: MockKeyUsage,
+    ) -> Result<(), MockKeyVaultError> {
+        let data = MockKeyData::try_from(key)?;
+        self.store_key(id, data, MockKeyMetadata { usage, label: 0 })
+    }
+
+    /// Make all current and future locks permanent
+    ///
+    /// After this call `clear_write_lock` and `clear_use_lock` fail with
+    /// `AccessDenied`. There is no way to undo a lock-down short of dropping
+    /// the vault.
+    pub fn lock_down(&mut self) {
+        self.locked_down = true;
+    }
+
+    /// Check whether [`MockKeyVault::lock_down`] has been called
+    pub fn is_locked_down(&self) -> bool {
+        self.locked_down
+    }
+
+    fn slot(&self, id: MockKeyId) -> Result<&Slot, MockKeyVaultError> {
+        self.slots
+            .get(usize::from(id.0))
+            .ok_or(MockKeyVaultError(ErrorKind::KeyNotFound))
+    }
+
+    fn slot_mut(&mut self, id: MockKeyId) -> Result<&mut Slot, MockKeyVaultError> {
+        self.slots
+            .get_mut(usize::from(id.0))
+            .ok_or(MockKeyVaultError(ErrorKind::KeyNotFound))
+    }
+
+    /// Fail with `AccessDenied` until the vault has been configured
+    fn ensure_configured(&self) -> Result<(), MockKeyVaultError> {
+        if !self.configured {
+            return Err(MockKeyVaultError(ErrorKind::AccessDenied));
+        }
+        Ok(())
+    }
+
+    /// Return a copy of a slot that may be modified and then [`commit`]ted
+    ///
+    /// [`commit`]: MockKeyVault::commit
+    fn writable_slot(&self, id: MockKeyId) -> Result<Slot, MockKeyVaultError> {
+        self.ensure_configured()?;
+        let slot = self.slot(id)?;
+        if slot.write_locked {
+            return Err(MockKeyVaultError(ErrorKind::AccessDenied));
+        }
+        Ok(slot.clone())
+    }
+
+    /// Like [`MockKeyVault::writable_slot`], but the slot must hold a key
+    fn writable_key(&self, id: MockKeyId) -> Result<Slot, MockKeyVaultError> {
+        let slot = self.writable_slot(id)?;
+        if !slot.occupied {
+            return Err(MockKeyVaultError(ErrorKind::KeyNotFound));
+        }
+        Ok(slot)
+    }
+
+    /// Enforce policy and return the slot for an operation requiring `usage`
+    fn authorize(&self, id: MockKeyId, usage: MockKeyUsage) -> Result<&Slot, MockKeyVaultError> {
+        check_key_use(self, id, usage).map_err(MockKeyVaultError)?;
+        self.slot(id)
+    }
+
+    /// Replace slot `id` with `updated`, writing through to storage first
+    ///
+    /// If the storage backend fails, the slot in RAM is left untouched so the
+    /// two copies never disagree.
+    fn commit(&mut self, id: MockKeyId, updated: Slot) -> Result<(), MockKeyVaultError> {
+        let index = usize::from(id.0);
+        let result = if updated.occupied {
+            self.storage.store(index, &updated.record())
+        } else {
+            self.storage.erase(index)
+        };
+        result.map_err(|_| MockKeyVaultError(ErrorKind::HardwareFault))?;
+        // The previous slot contents are zeroized as they are dropped
+        *self.slot_mut(id)? = updated;
+        Ok(())
+    }
+}
+
+impl<S: KeyVaultStorage> ErrorType for MockKeyVault<S> {
+    type Error = MockKeyVaultError;
+}
+
+impl<S: KeyVaultStorage> KeyVaultSetup for MockKeyVault<S> {
+    type KeyVaultConfig = MockKeyVaultConfig;
+
+    fn configure(&mut self, config: Self::KeyVaultConfig) -> Result<(), Self::Error> {
+        if self.configured || self.locked_down {
+            return Err(MockKeyVaultError(ErrorKind::AccessDenied));
+        }
+        if config.restore_from_storage {
+            for (index, slot) in self.slots.iter_mut().enumerate() {
+                // Locked slots keep their contents; restore never overrides a lock
+                if slot.write_locked {
+                    continue;
+                }
+                let record = self
+                    .storage
+                    .load(index)
+                    .map_err(|_| MockKeyVaultError(ErrorKind::HardwareFault))?;
+                match record {
+                    Some(record) => slot.fill(record.key.as_bytes(), record.metadata),
+                    None => slot.erase(),
+                }
+            }
+        }
+        self.configured = true;
+        Ok(())
+    }
+
+    fn is_configured(&self) -> bool {
+        self.configured
+    }
+}
+
+impl<S: KeyVaultStorage> KeyStore for MockKeyVault<S> {
+    type KeyId = MockKeyId;
+    type KeyUsage = MockKeyUsage;
+
+    fn erase_key(&mut self, id: Self::KeyId) -> Result<(), Self::Error> {
+        let mut slot = self.writable_slot(id)?;
+        slot.erase();
+        self.commit(id, slot)
+    }
+
+    fn erase_all_keys(&mut self) -> Result<(), Self::Error> {
+        self.ensure_configured()?;
+        for index in 0..MOCK_VAULT_SLOTS {
+            let id = MockKeyId(index as u8);
+            if !self.is_write_locked(id)? {
+                self.erase_key(id)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn key_exists(&self, id: Self::KeyId) -> Result<bool, Self::Error> {
+        Ok(self.slot(id)?.occupied)
+    }
+
+    fn get_key_usage(&self, id: Self::KeyId) -> Result<Self::KeyUsage, Self::Error> {
+        Ok(self.get_key_metadata(id)?.usage)
+    }
+
+    fn set_key_usage(&mut self, id: Self::KeyId, usage: Self::KeyUsage) -> Result<(), Self::Error> {
+        let mut slot = self.writable_key(id)?;
+        slot.metadata.usage = usage;
+        self.commit(id, slot)
+    }
+}
+
+impl<S: KeyVaultStorage> KeyLocking for MockKeyVault<S> {
+    type KeyId = MockKeyId;
+
+    fn is_write_locked(&self, id: Self::KeyId) -> Result<bool, Self::Error> {
+        Ok(self.slot(id)?.write_locked)
+    }
+
+    fn set_write_lock(&mut self, id: Self::KeyId) -> Result<(), Self::Error> {
+        self.ensure_configured()?;
+        self.slot_mut(id)?.write_locked = true;
+        Ok(())
+    }
+
+    fn clear_write_lock(&mut self, id: Self::KeyId) -> Result<(), Self::Error> {
+        self.ensure_configured()?;
+        if self.locked_down {
+            return Err(MockKeyVaultError(ErrorKind::AccessDenied));
+        }
+        self.slot_mut(id)?.write_locked = false;
+        Ok(())
+    }
+
+    fn is_use_locked(&self, id: Self::KeyId) -> Result<bool, Self::Error> {
+        Ok(self.slot(id)?.use_locked)
+    }
+
+    fn set_use_lock(&mut self, id: Self::KeyId) -> Result<(), Self::Error> {
+        self.ensure_configured()?;
+        self.slot_mut(id)?.use_locked = true;
+        Ok(())
+    }
+
+    fn clear_use_lock(&mut self, id: Self::KeyId) -> Result<(), Self::Error> {
+        self.ensure_configured()?;
+        if self.locked_down {
+            return Err(MockKeyVaultError(ErrorKind::AccessDenied));
+        }
+        self.slot_mut(id)?.use_locked = false;
+        Ok(())
+    }
+}
+
+impl<S: KeyVaultStorage> KeyLifecycle for MockKeyVault<S> {
+    type KeyId = MockKeyId;
+    type KeyData = MockKeyData;
+    type KeyMetadata = MockKeyMetadata;
+
+    fn store_key(
+        &mut self,
+        id: Self::KeyId,
+        data: Self::KeyData,
+        metadata: Self::KeyMetadata,
+    ) -> Result<(), Self::Error> {
+        let mut slot = self.writable_slot(id)?;
+        slot.fill(data.as_bytes(), metadata);
+        self.commit(id, slot)
+    }
+
+    fn retrieve_key(&self, id: Self::KeyId) -> Result<Self::KeyData, Self::Error> {
+        Ok(self.authorize(id, MockKeyUsage::EXPORT)?.record().key)
+    }
+
+    fn get_key_metadata(&self, id: Self::KeyId) -> Result<Self::KeyMetadata, Self::Error> {
+        let slot = self.slot(id)?;
+        if !slot.occupied {
+            return Err(MockKeyVaultError(ErrorKind::KeyNotFound));
+        }
+        Ok(slot.metadata)
+    }
+
+    fn update_key_metadata(
+        &mut self,
+        id: Self::KeyId,
+        metadata: Self::KeyMetadata,
+    ) -> Result<(), Self::Error> {
+        let mut slot = self.writable_key(id)?;
+        slot.metadata = metadata;
+        self.commit(id, slot)
+    }
+}
+
+//
+// VAULT-BACKED MAC
+//
+
+impl<S: KeyVaultStorage> mac::ErrorType for MockKeyVault<S> {
+    type Error = MockKeyVaultError;
+}
+
+/// MAC context keyed from a vault slot
+///
+/// Holds only a value derived from the key, never the key itself. The
+/// lifetime ties the context to a borrow of the vault, like a hardware engine.
+pub struct MockVaultMac<'a, A> {
+    _vault: PhantomData<&'a mut ()>,
+    _alg: A,
+    state: u32,
+}
+
+impl<A> mac::ErrorType for MockVaultMac<'_, A> {
+    type Error = MockKeyVaultError;
+}
+
+/// Macro to implement vault-backed MAC traits for each algorithm
+macro_rules! impl_vault_mac {
+    ($algo:ident) => {
+        impl<S: KeyVaultStorage> VaultMacInit<$algo> for MockKeyVault<S> {
+            type KeyId = MockKeyId;
+            type OpContext<'a>
+                = MockVaultMac<'a, $algo>
+            where
+                S: 'a;
+
+            fn init_with_key_id(
+                &mut self,
+                algo: $algo,
+                key_id: Self::KeyId,
+            ) -> Result<Self::OpContext<'_>, Self::Error> {
+                let state = self.authorize(key_id, MockKeyUsage::MAC)?.seed();
+                Ok(MockVaultMac {
+                    _vault: PhantomData,
+                    _alg: algo,
+                    state,
+                })
+            }
+        }
+
+        impl MacOp for MockVaultMac<'_, $algo> {
+            type Output = <$algo as MacAlgorithm>::MacOutput;
+
+            fn update(&mut self, input: &[u8]) -> Result<(), Self::Error> {
+                self.state = fold(self.state, input);
+                Ok(())
+            }
+
+            fn finalize(self) -> Result<Self::Output, Self::Error> {
+                const OUTPUT_WORDS: usize = <$algo as MacAlgorithm>::OUTPUT_BITS / 32;
+                let mut value = [0u32; OUTPUT_WORDS];
+                for (i, word) in value.iter_mut().enumerate() {
+                    *word = self.state.wrapping_add(i as u32);
+                }
+                Ok(Digest { value })
+            }
+        }
+    };
+}
+
+impl_vault_mac!(HmacSha2_256);
+impl_vault_mac!(HmacSha2_384);
+impl_vault_mac!(HmacSha2_512);
+
+//
+// VAULT-BACKED ECDSA
+//
+
+impl<S: KeyVaultStorage> ecdsa::ErrorType for MockKeyVault<S> {
+    type Error = MockKeyVaultError;
+}
+
+/// Macro to implement vault-backed ECDSA signing for each curve
+macro_rules! impl_vault_sign {
+    ($curve:ident, $signature:ident, $len:expr) => {
+        impl<S: KeyVaultStorage> ecdsa::EcdsaVaultSign<$curve> for MockKeyVault<S> {
+            type KeyId = MockKeyId;
+            type Signature = $signature;
+
+            fn sign_with_key_id<R>(
+                &mut self,
+                key_id: Self::KeyId,
+                digest: <<$curve as Curve>::DigestType as DigestAlgorithm>::Digest,
+                _rng: &mut R,
+            ) -> Result<Self::Signature, Self::Error>
+            where
+                R: rand_core::RngCore + rand_core::CryptoRng,
+            {
+                let seed = self.authorize(key_id, MockKeyUsage::SIGN)?.seed();
+                let mixed = fold(seed, digest.as_bytes());
+
+                let mut r = [0u8; $len];
+                let mut s = [0u8; $len];
+                for (i, (r, s)) in r.iter_mut().zip(s.iter_mut()).enumerate() {
+                    *r = seed.wrapping_add(i as u32).to_le_bytes()[i % 4];
+                    *s = mixed.wrapping_add(i as u32).to_le_bytes()[i % 4];
+                }
+                Ok($signature::new(r, s))
+            }
+        }
+    };
+}
+
+impl_vault_sign!(P256, P256Signature, 32);
+impl_vault_sign!(P384, P384Signature, 48);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openprot_hal_blocking::ecdsa::{EcdsaVaultSign, Signature};
+    use openprot_hal_blocking::key_vault::KeyVaultSetup;
+
+    const MAC_KEY: MockKeyId = MockKeyId(0);
+    const SIGNING_KEY: MockKeyId = MockKeyId(1);
+
+    struct ZeroRng;
+
+    impl rand_core::RngCore for ZeroRng {
+        fn next_u32(&mut self) -> u32 {
+            0
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            0
+        }
+
+        fn fill_bytes(&mut self, dst: &mut [u8]) {
+            dst.fill(0);
+        }
+    }
+
+    impl rand_core::CryptoRng for ZeroRng {}
+
+    fn provisioned_vault() -> MockKeyVault {
+        let mut vault = MockKeyVault::new();
+        vault
+            .provision_key(MAC_KEY, b"mac key material", MockKeyUsage::MAC)
+            .unwrap();
+        vault
+            .provision_key(SIGNING_KEY, &[0x5a; 32], MockKeyUsage::SIGN)
+            .unwrap();
+        vault
+    }
+
+    fn mac<S: KeyVaultStorage>(
+        vault: &mut MockKeyVault<S>,
+        id: MockKeyId,
+        data: &[u8],
+    ) -> Result<[u32; 8], MockKeyVaultError> {
+        let mut ctx = vault.init_with_key_id(HmacSha2_256, id)?;
+        ctx.update(data)?;
+        ctx.finalize().map(Digest::into_array)
+    }
+
+    #[test]
+    fn test_mac_with_permitted_key() {
+        let mut vault = provisioned_vault();
+        let first = mac(&mut vault, MAC_KEY, b"payload").unwrap();
+        let second = mac(&mut vault, MAC_KEY, b"payload").unwrap();
+        let other = mac(&mut vault, MAC_KEY, b"other payload").unwrap();
+
+        assert_eq!(first, second);
+        assert_ne!(first, other);
+    }
+
+    #[test]
+    fn test_mac_depends_on_key() {
+        let mut vault = provisioned_vault();
+        let before = mac(&mut vault, MAC_KEY, b"payload").unwrap();
+        vault
+            .provision_key(MAC_KEY, b"rotated key", MockKeyUsage::MAC)
+            .unwrap();
+        let after = mac(&mut vault, MAC_KEY, b"payload").unwrap();
+        assert_ne!(before, after);
+    }
+
+    #[test]
+    fn test_mac_rejects_wrong_usage() {
+        let mut vault = provisioned_vault();
+        let err = mac(&mut vault, SIGNING_KEY, b"payload").unwrap_err();
+        assert_eq!(err, MockKeyVaultError(ErrorKind::InvalidUsage));
+        assert_eq!(mac::Error::kind(&err), mac::ErrorKind::PermissionDenied);
+    }
+
+    #[test]
+    fn test_mac_rejects_use_locked_key() {
+        let mut vault = provisioned_vault();
+        vault.set_use_lock(MAC_KEY).unwrap();
+        let err = mac(&mut vault, MAC_KEY, b"payload").unwrap_err();
+        assert_eq!(err.0, ErrorKind::AccessDenied);
+
+        vault.clear_use_lock(MAC_KEY).unwrap();
+        assert!(mac(&mut vault, MAC_KEY, b"payload").is_ok());
+    }
+
+    #[test]
+    fn test_mac_rejects_missing_key() {
+        let mut vault = provisioned_vault();
+        let err = mac(&mut vault, MockKeyId(5), b"payload").unwrap_err();
+        assert_eq!(err.0, ErrorKind::KeyNotFound);
+
+        let err = mac(&mut vault, MockKeyId(200), b"payload").unwrap_err();
+        assert_eq!(err.0, ErrorKind::KeyNotFound);
+    }
+
+    #[test]
+    fn test_usage_checked_on_every_call() {
+        let mut vault = provisioned_vault();
+        assert!(mac(&mut vault, MAC_KEY, b"payload").is_ok());
+
+        vault.set_key_usage(MAC_KEY, MockKeyUsage::SIGN).unwrap();
+        assert_eq!(
+            mac(&mut vault, MAC_KEY, b"payload").unwrap_err().0,
+            ErrorKind::InvalidUsage
+        );
+
+        vault
+            .set_key_usage(MAC_KEY, MockKeyUsage::MAC.union(MockKeyUsage::SIGN))
+            .unwrap();
+        assert!(mac(&mut vault, MAC_KEY, b"payload").is_ok());
+    }
+
+    #[test]
+    fn test_sign_with_permitted_key() {
+        let mut vault = provisioned_vault();
+        let digest = Digest::<8>::new([0x1111_1111; 8]);
+        let first =
+            EcdsaVaultSign::<P256>::sign_with_key_id(&mut vault, SIGNING_KEY, digest, &mut ZeroRng)
+                .unwrap();
+        let other = EcdsaVaultSign::<P256>::sign_with_key_id(
+            &mut vault,
+            SIGNING_KEY,
+            Digest::new([0x2222_2222; 8]),
+            &mut ZeroRng,
+        )
+        .unwrap();
+        let (mut r1, mut s1, mut r2, mut s2) = ([0u8; 32], [0u8; 32], [0u8; 32], [0u8; 32]);
+        first.coordinates(&mut r1, &mut s1);
+        other.coordinates(&mut r2, &mut s2);
+        assert_eq!(r1, r2);
+        assert_ne!(s1, s2);
+
+        let digest = Digest::<12>::new([0x3333_3333; 12]);
+        assert!(EcdsaVaultSign::<P384>::sign_with_key_id(
+            &mut vault,
+            SIGNING_KEY,
+            digest,
+            &mut ZeroRng
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn test_sign_policy_enforced() {
+        let mut vault = provisioned_vault();
+        let digest = Digest::<8>::new([0; 8]);
+
+        // MAC-only key cannot sign
+        let err =
+            EcdsaVaultSign::<P256>::sign_with_key_id(&mut vault, MAC_KEY, digest, &mut ZeroRng)
+                .unwrap_err();
+        assert_eq!(ecdsa::Error::kind(&err), ecdsa::ErrorKind::PermissionDenied);
+
+        // Use-locked key cannot sign
+        vault.set_use_lock(SIGNING_KEY).unwrap();
+        let err =
+            EcdsaVaultSign::<P256>::sign_with_key_id(&mut vault, SIGNING_KEY, digest, &mut ZeroRng)
+                .unwrap_err();
+        assert_eq!(err.0, ErrorKind::AccessDenied);
+    }
+
+    #[test]
+    fn test_write_lock_protects_slot() {
+        let mut vault = provisioned_vault();
+        vault.set_write_lock(MAC_KEY).unwrap();
+
+        assert_eq!(
+            vault
+                .provision_key(MAC_KEY, b"replacement", MockKeyUsage::MAC)
+                .unwrap_err()
+                .0,
+            ErrorKind::AccessDenied
+        );
+        assert_eq!(
+            vault
+                .set_key_usage(MAC_KEY, MockKeyUsage::SIGN)
+                .unwrap_err()
+                .0,
+            ErrorKind::AccessDenied
+        );
+        assert_eq!(
+            vault.erase_key(MAC_KEY).unwrap_err().0,
+            ErrorKind::AccessDenied
+        );
+
+        // Bulk erase skips locked slots
+        vault.erase_all_keys().unwrap();
+        assert!(vault.key_exists(MAC_KEY).unwrap());
+        assert!(!vault.key_exists(SIGNING_KEY).unwrap());
+    }
+
+    #[test]
+    fn test_erase_zeroizes_slot() {
+        let mut vault = provisioned_vault();
+        vault.erase_key(SIGNING_KEY).unwrap();
+
+        let slot = vault.slot(SIGNING_KEY).unwrap();
+        assert!(!slot.occupied);
+        assert!(slot.key.iter().all(|&b| b == 0));
+        assert_eq!(
+            vault.get_key_usage(SIGNING_KEY).unwrap_err().0,
+            ErrorKind::KeyNotFound
+        );
+    }
+
+    #[test]
+    fn test_lifecycle_store_and_metadata() {
+        let mut vault = MockKeyVault::new();
+        let metadata = MockKeyMetadata {
+            usage: MockKeyUsage::MAC,
+            label: 0xA5A5,
+        };
+        vault
+            .store_key(
+                MockKeyId(3),
+                MockKeyData::from_slice(b"k3").unwrap(),
+                metadata,
+            )
+            .unwrap();
+        assert_eq!(vault.get_key_metadata(MockKeyId(3)).unwrap(), metadata);
+        assert_eq!(
+            vault.get_key_usage(MockKeyId(3)).unwrap(),
+            MockKeyUsage::MAC
+        );
+
+        let updated = MockKeyMetadata {
+            usage: MockKeyUsage::SIGN,
+            label: 7,
+        };
+        vault.update_key_metadata(MockKeyId(3), updated).unwrap();
+        assert_eq!(vault.get_key_metadata(MockKeyId(3)).unwrap(), updated);
+
+        vault.set_write_lock(MockKeyId(3)).unwrap();
+        assert_eq!(
+            vault
+                .update_key_metadata(MockKeyId(3), metadata)
+                .unwrap_err()
+                .0,
+            ErrorKind::AccessDenied
+        );
+    }
+
+    #[test]
+    fn test_retrieve_requires_export_usage() {
+        let mut vault = provisioned_vault();
+        assert_eq!(
+            vault.retrieve_key(MAC_KEY).unwrap_err().0,
+            ErrorKind::InvalidUsage
+        );
+
+        vault
+            .provision_key(
+                MockKeyId(2),
+                b"wrapped",
+                MockKeyUsage::MAC.union(MockKeyUsage::EXPORT),
+            )
+            .unwrap();
+        assert_eq!(
+            vault.retrieve_key(MockKeyId(2)).unwrap().as_bytes(),
+            b"wrapped"
+        );
+
+        vault.set_use_lock(MockKeyId(2)).unwrap();
+        assert_eq!(
+            vault.retrieve_key(MockKeyId(2)).unwrap_err().0,
+            ErrorKind::AccessDenied
+        );
+    }
+
+    #[test]
+    fn test_oversized_key_rejected() {
+        let mut vault = MockKeyVault::new();
+        assert!(MockKeyData::from_slice(&[0u8; MOCK_VAULT_MAX_KEY_SIZE + 1]).is_none());
+        assert_eq!(
+            vault
+                .provision_key(
+                    MAC_KEY,
+                    &[0u8; MOCK_VAULT_MAX_KEY_SIZE + 1],
+                    MockKeyUsage::MAC
+                )
+                .unwrap_err()
+                .0,
+            ErrorKind::InvalidKeySize
+        );
+        assert_eq!(
+            MockKeyData::try_from(&[0u8; MOCK_VAULT_MAX_KEY_SIZE + 1][..])
+                .unwrap_err()
+                .0,
+            ErrorKind::InvalidKeySize
+        );
+    }
+
+    #[test]
+    fn test_locks_clearable_before_lock_down() {
+        let mut vault = provisioned_vault();
+        vault.set_write_lock(MAC_KEY).unwrap();
+        vault.set_use_lock(MAC_KEY).unwrap();
+        vault.clear_write_lock(MAC_KEY).unwrap();
+        vault.clear_use_lock(MAC_KEY).unwrap();
+        assert!(!vault.is_write_locked(MAC_KEY).unwrap());
+        assert!(!vault.is_use_locked(MAC_KEY).unwrap());
+    }
+
+    #[test]
+    fn test_lock_down_makes_locks_permanent() {
+        let mut vault = provisioned_vault();
+        vault.set_write_lock(MAC_KEY).unwrap();
+        vault.set_use_lock(MAC_KEY).unwrap();
+        vault.lock_down();
+        assert!(vault.is_locked_down());
+
+        assert_eq!(
+            vault.clear_write_lock(MAC_KEY).unwrap_err().0,
+            ErrorKind::AccessDenied
+        );
+        assert_eq!(
+            vault.clear_use_lock(MAC_KEY).unwrap_err().0,
+            ErrorKind::AccessDenied
+        );
+        assert!(vault.is_write_locked(MAC_KEY).unwrap());
+        assert!(vault.is_use_locked(MAC_KEY).unwrap());
+
+        // New locks can still be applied, and are permanent too
+        vault.set_use_lock(SIGNING_KEY).unwrap();
+        assert_eq!(
+            vault.clear_use_lock(SIGNING_KEY).unwrap_err().0,
+            ErrorKind::AccessDenied
+        );
+    }
+
+    #[test]
+    fn test_storage_persists_and_restores() {
+        let mut vault = MockKeyVault::with_storage(MemoryStorage::new());
+        assert!(!vault.is_configured());
+        vault.configure(MockKeyVaultConfig::default()).unwrap();
+        assert!(vault.is_configured());
+
+        vault
+            .provision_key(MAC_KEY, b"persisted", MockKeyUsage::MAC)
+            .unwrap();
+        vault
+            .provision_key(SIGNING_KEY, b"transient", MockKeyUsage::SIGN)
+            .unwrap();
+        vault.set_key_usage(MAC_KEY, MockKeyUsage::MAC).unwrap();
+        vault.set_use_lock(MAC_KEY).unwrap();
+        vault.erase_key(SIGNING_KEY).unwrap();
+        assert!(vault.storage().has_record(0));
+        assert!(!vault.storage().has_record(1));
+
+        let expected = {
+            vault.clear_use_lock(MAC_KEY).unwrap();
+            let value = mac(&mut vault, MAC_KEY, b"payload").unwrap();
+            vault.set_use_lock(MAC_KEY).unwrap();
+            value
+        };
+
+        // Move the storage into a fresh vault, as if after a reboot
+        let storage = {
+            let mut storage = MemoryStorage::new();
+            core::mem::swap(&mut storage, &mut vault.storage);
+            storage
+        };
+        let mut restored = MockKeyVault::with_storage(storage);
+        assert!(!restored.key_exists(MAC_KEY).unwrap());
+        restored
+            .configure(MockKeyVaultConfig {
+                restore_from_storage: true,
+            })
+            .unwrap();
+
+        assert!(restored.key_exists(MAC_KEY).unwrap());
+        assert!(!restored.key_exists(SIGNING_KEY).unwrap());
+        assert_eq!(restored.get_key_usage(MAC_KEY).unwrap(), MockKeyUsage::MAC);
+        // Locks are volatile and not restored
+        assert!(!restored.is_use_locked(MAC_KEY).unwrap());
+        assert_eq!(mac(&mut restored, MAC_KEY, b"payload").unwrap(), expected);
+    }
+
+    /// Storage whose writes can be made to fail, seeded with fixed records
+    struct ScriptedStorage {
+        inner: MemoryStorage,
+        fail_writes: bool,
+    }
+
+    impl ScriptedStorage {
+        fn with_record(slot: usize, key: &[u8], usage: MockKeyUsage) -> Self {
+            let mut inner = MemoryStorage::new();
+            let record = SlotRecord {
+                key: MockKeyData::from_slice(key).unwrap(),
+                metadata: MockKeyMetadata { usage, label: 0 },
+            };
+            inner.store(slot, &record).unwrap();
+            Self {
+                inner,
+                fail_writes: false,
+            }
+        }
+    }
+
+    impl KeyVaultStorage for ScriptedStorage {
+        type Error = ErrorKind;
+
+        fn load(&mut self, slot: usize) -> Result<Option<SlotRecord>, Self::Error> {
+            self.inner.load(slot)
+        }
+
+        fn store(&mut self, slot: usize, record: &SlotRecord) -> Result<(), Self::Error> {
+            if self.fail_writes {
+                return Err(ErrorKind::HardwareFault);
+            }
+            self.inner.store(slot, record)
+        }
+
+        fn erase(&mut self, slot: usize) -> Result<(), Self::Error> {
+            if self.fail_writes {
+                return Err(ErrorKind::HardwareFault);
+            }
+            self.inner.erase(slot)
+        }
+    }
+
+    #[test]
+    fn test_mutations_rejected_before_configure() {
+        let storage = ScriptedStorage::with_record(0, b"stored", MockKeyUsage::MAC);
+        let mut vault = MockKeyVault::with_storage(storage);
+        let denied = MockKeyVaultError(ErrorKind::AccessDenied);
+
+        assert_eq!(
+            vault.provision_key(MAC_KEY, b"early", MockKeyUsage::EXPORT),
+            Err(denied)
+        );
+        assert_eq!(vault.erase_key(MAC_KEY), Err(denied));
+        assert_eq!(vault.erase_all_keys(), Err(denied));
+        assert_eq!(vault.set_write_lock(MAC_KEY), Err(denied));
+        assert_eq!(vault.set_use_lock(MAC_KEY), Err(denied));
+        assert_eq!(vault.clear_write_lock(MAC_KEY), Err(denied));
+        assert_eq!(vault.clear_use_lock(MAC_KEY), Err(denied));
+
+        // The stored record survived and is restored intact
+        vault
+            .configure(MockKeyVaultConfig {
+                restore_from_storage: true,
+            })
+            .unwrap();
+        assert_eq!(vault.get_key_usage(MAC_KEY).unwrap(), MockKeyUsage::MAC);
+        assert_eq!(vault.slot(MAC_KEY).unwrap().key[..6], *b"stored");
+    }
+
+    #[test]
+    fn test_configure_only_once() {
+        let mut vault = MockKeyVault::with_storage(MemoryStorage::new());
+        vault.configure(MockKeyVaultConfig::default()).unwrap();
+        assert_eq!(
+            vault
+                .configure(MockKeyVaultConfig::default())
+                .unwrap_err()
+                .0,
+            ErrorKind::AccessDenied
+        );
+
+        // A vault without storage starts out configured
+        let mut vault = MockKeyVault::new();
+        assert_eq!(
+            vault
+                .configure(MockKeyVaultConfig::default())
+                .unwrap_err()
+                .0,
+            ErrorKind::AccessDenied
+        );
+    }
+
+    #[test]
+    fn test_configure_cannot_override_lock_down() {
+        let storage = ScriptedStorage::with_record(0, b"attacker", MockKeyUsage::EXPORT);
+        let mut vault = MockKeyVault::with_storage(storage);
+        vault.lock_down();
+        assert_eq!(
+            vault
+                .configure(MockKeyVaultConfig {
+                    restore_from_storage: true,
+                })
+                .unwrap_err()
+                .0,
+            ErrorKind::AccessDenied
+        );
+        assert!(!vault.key_exists(MAC_KEY).unwrap());
+
+        // Once configured and locked, restoring again is refused as well
+        let mut vault = MockKeyVault::with_storage(MemoryStorage::new());
+        vault.configure(MockKeyVaultConfig::default()).unwrap();
+        vault
+            .provision_key(MAC_KEY, b"genuine", MockKeyUsage::MAC)
+            .unwrap();
+        vault.set_write_lock(MAC_KEY).unwrap();
+        vault.lock_down();
+        vault
+            .storage
+            .store(
+                0,
+                &SlotRecord {
+                    key: MockKeyData::from_slice(b"attacker").unwrap(),
+                    metadata: MockKeyMetadata {
+                        usage: MockKeyUsage::EXPORT,
+                        label: 0,
+                    },
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            vault
+                .configure(MockKeyVaultConfig {
+                    restore_from_storage: true,
+                })
+                .unwrap_err()
+                .0,
+            ErrorKind::AccessDenied
+        );
+        assert_eq!(vault.get_key_usage(MAC_KEY).unwrap(), MockKeyUsage::MAC);
+        assert_eq!(vault.slot(MAC_KEY).unwrap().key[..7], *b"genuine");
+    }
+
+    #[test]
+    fn test_failed_persist_leaves_slot_unchanged() {
+        let mut vault = MockKeyVault::with_storage(ScriptedStorage {
+            inner: MemoryStorage::new(),
+            fail_writes: false,
+        });
+        vault.configure(MockKeyVaultConfig::default()).unwrap();
+        vault
+            .provision_key(MAC_KEY, b"original", MockKeyUsage::MAC)
+            .unwrap();
+        let expected = mac(&mut vault, MAC_KEY, b"payload").unwrap();
+
+        vault.storage.fail_writes = true;
+        let fault = MockKeyVaultError(ErrorKind::HardwareFault);
+        assert_eq!(
+            vault.provision_key(MAC_KEY, b"replacement", MockKeyUsage::MAC),
+            Err(fault)
+        );
+        assert_eq!(vault.set_key_usage(MAC_KEY, MockKeyUsage::SIGN), Err(fault));
+        assert_eq!(vault.erase_key(MAC_KEY), Err(fault));
+
+        assert_eq!(vault.get_key_usage(MAC_KEY).unwrap(), MockKeyUsage::MAC);
+        assert_eq!(mac(&mut vault, MAC_KEY, b"payload").unwrap(), expected);
+        assert!(vault.storage().inner.has_record(0));
+    }
+}
@@ -12,6 +12,15 @@
 #![allow(clippy::expect_used)]
 #![allow(clippy::arithmetic_side_effects)]
 
+extern crate alloc;
+
+pub mod ecdh;
+pub mod gpio;
 pub mod hash;
+pub mod i2c_bus;
 pub mod i2c_hardware;
+pub mod i3c;
+pub mod key_vault;
+pub mod rng;
+pub mod spi_nor_flash;
 pub mod system_control;
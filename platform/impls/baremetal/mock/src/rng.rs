@@ -0,0 +1,106 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! Mock DRBG Implementation
+//!
+//! Provides a deterministic stand-in for the [`Drbg`] trait so that code
+//! consuming random bytes can be tested against predictable output.
+//!
+//! [`CountingDrbg`] emits consecutive byte values and enforces a reseed
+//! budget, so reseed handling can be exercised without a real DRBG. It
+//! offers NO randomness.
+
+use openprot_hal_blocking::rng::{Drbg, ErrorKind, ErrorType};
+
+/// Additional input that makes [`CountingDrbg::reseed`] report a health test
+/// failure
+pub const POISON: &[u8] = b"poison";
+
+/// Counting "DRBG"
+///
+/// Each generated byte is one more than the previous one, wrapping at 0xFF.
+/// Generating more than the remaining budget fails with
+/// [`ErrorKind::ReseedRequired`]; reseeding restores the full budget, unless
+/// the additional input is [`POISON`], which fails with
+/// [`ErrorKind::RepetitionCountFailure`].
+#[derive(Debug, Clone)]
+pub struct CountingDrbg {
+    next: u8,
+    budget: usize,
+    remaining: usize,
+}
+
+impl CountingDrbg {
+    /// Create a DRBG that starts counting at zero and allows `budget` bytes
+    /// between reseeds
+    pub const fn new(budget: usize) -> Self {
+        Self::starting_at(0, budget)
+    }
+
+    /// Create a DRBG whose first byte is `first`
+    pub const fn starting_at(first: u8, budget: usize) -> Self {
+        Self {
+            next: first,
+            budget,
+            remaining: budget,
+        }
+    }
+}
+
+impl ErrorType for CountingDrbg {
+    type Error = ErrorKind;
+}
+
+impl Drbg for CountingDrbg {
+    fn generate(&mut self, dest: &mut [u8], _additional_input: &[u8]) -> Result<(), ErrorKind> {
+        if dest.len() > self.remaining {
+            return Err(ErrorKind::ReseedRequired);
+        }
+        self.remaining -= dest.len();
+        for byte in dest.iter_mut() {
+            *byte = self.next;
+            self.next = self.next.wrapping_add(1);
+        }
+        Ok(())
+    }
+
+    fn reseed(&mut self, additional_input: &[u8]) -> Result<(), ErrorKind> {
+        if additional_input == POISON {
+            return Err(ErrorKind::RepetitionCountFailure);
+        }
+        self.remaining = self.budget;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openprot_hal_blocking::rng::DrbgRng;
+    use rand_core::{RngCore, TryRngCore};
+
+    #[test]
+    fn test_counting_drbg_enforces_budget() {
+        let mut drbg = CountingDrbg::new(4);
+        let mut buf = [0u8; 3];
+        drbg.generate(&mut buf, &[]).unwrap();
+        assert_eq!(buf, [0, 1, 2]);
+        assert_eq!(drbg.generate(&mut buf, &[]), Err(ErrorKind::ReseedRequired));
+
+        assert_eq!(drbg.reseed(POISON), Err(ErrorKind::RepetitionCountFailure));
+        drbg.reseed(b"fresh").unwrap();
+        drbg.generate(&mut buf, &[]).unwrap();
+        assert_eq!(buf, [3, 4, 5]);
+    }
+
+    #[test]
+    fn test_drbg_rng_reports_drbg_errors() {
+        let mut rng = DrbgRng::new(CountingDrbg::starting_at(1, 8));
+        assert_eq!(rng.try_next_u32().unwrap(), 0x0403_0201);
+        assert_eq!(rng.try_next_u32().unwrap(), 0x0807_0605);
+        assert_eq!(rng.try_next_u32(), Err(ErrorKind::ReseedRequired));
+
+        rng.drbg_mut().reseed(&[]).unwrap();
+        assert_eq!(rng.unwrap_err().next_u64(), 0x100f_0e0d_0c0b_0a09);
+    }
+}
@@ -0,0 +1,450 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! Mock SPI NOR flash device for testing and development
+//!
+//! This module provides an in-memory SPI NOR flash that mirrors the
+//! operations of the AST10x0 `SpiNorFlashDevice` trait. The trait lives in
+//! the target-only peripherals crate, so the mock exposes the same method
+//! names and signatures as inherent methods, with [`MockFlashError`] in
+//! place of `SmcError`.
+//!
+//! # Features
+//!
+//! - **NOR semantics**: Page program only clears bits (1 → 0); only a sector
+//!   erase sets them back to 1
+//! - **Page wrap**: A program that runs past the end of a page wraps to the
+//!   start of the same page, as real devices do
+//! - **Busy timing**: The status register reports WIP for a configurable
+//!   number of polls after each program or erase; other operations fail
+//!   with `WriteInProgress` until it clears
+//! - **JEDEC ID**: Configurable, defaulting to a Winbond-style ID whose
+//!   capacity byte matches the array size
+//! - **Fault injection**: Bit flips in the array and power loss partway
+//!   through the next program or erase
+//!
+//! # Examples
+//!
+//! ```text
+//! use openprot_platform_mock::spi_nor_flash::{MockSpiNorFlash, SECTOR_SIZE, STATUS_WIP};
+//!
+//! let mut flash = MockSpiNorFlash::<{ 4 * SECTOR_SIZE }>::new();
+//! flash.erase_sector(0)?;
+//! while flash.status()? & STATUS_WIP != 0 {}
+//!
+//! flash.program_page(0x10, &[0x0F, 0xAA])?;
+//! while flash.status()? & STATUS_WIP != 0 {}
+//!
+//! // Programming again can only clear more bits
+//! flash.program_page(0x10, &[0xF0])?;
+//! while flash.status()? & STATUS_WIP != 0 {}
+//! assert!(flash.verify(0x10, &[0x00, 0xAA])?);
+//! ```
+
+use core::cell::Cell;
+
+/// Program page size in bytes
+pub const PAGE_SIZE: usize = 256;
+
+/// Erase sector size in bytes
+pub const SECTOR_SIZE: usize = 4096;
+
+/// Status register write-in-progress bit
+pub const STATUS_WIP: u8 = 0x01;
+
+/// Mock error type for SPI NOR flash operations
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MockFlashError {
+    /// The access extends past the end of the device
+    OutOfBounds,
+    /// The program data is empty or longer than a page
+    InvalidLength,
+    /// A program or erase is still in progress
+    WriteInProgress,
+    /// Power was lost partway through the operation
+    PowerLoss,
+}
+
+/// Timing and identity settings for [`MockSpiNorFlash`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MockSpiNorConfig {
+    /// JEDEC manufacturer, memory type and capacity bytes
+    pub jedec_id: [u8; 3],
+    /// Status polls reporting WIP after a page program
+    pub program_polls: u32,
+    /// Status polls reporting WIP after a sector erase
+    pub erase_polls: u32,
+}
+
+/// Mock SPI NOR flash with `SIZE` bytes of storage
+///
+/// `SIZE` must be a non-zero multiple of [`SECTOR_SIZE`]. A new device is
+/// fully erased.
+#[derive(Debug, Clone)]
+pub struct MockSpiNorFlash<const SIZE: usize> {
+    /// Flash array
+    data: [u8; SIZE],
+    /// Device settings
+    config: MockSpiNorConfig,
+    /// Remaining status polls that report WIP
+    busy_polls: Cell<u32>,
+    /// Bytes the next program or erase completes before power is lost
+    power_loss_after: Option<usize>,
+}
+
+impl<const SIZE: usize> MockSpiNorFlash<SIZE> {
+    /// Default configuration for this capacity
+    ///
+    /// The JEDEC ID is `EF 40 nn`, where `nn` is log2 of the capacity. One
+    /// status poll reports WIP after a program and three after an erase.
+    pub const fn default_config() -> MockSpiNorConfig {
+        MockSpiNorConfig {
+            jedec_id: [0xEF, 0x40, SIZE.trailing_zeros() as u8],
+            program_polls: 1,
+            erase_polls: 3,
+        }
+    }
+
+    /// Create a new erased device with the default configuration
+    pub fn new() -> Self {
+        Self::with_config(Self::default_config())
+    }
+
+    /// Create a new erased device with the given configuration
+    ///
+    /// # Panics
+    ///
+    /// Panics if `SIZE` is zero or not a multiple of [`SECTOR_SIZE`].
+    pub fn with_config(config: MockSpiNorConfig) -> Self {
+        assert!(SIZE != 0 && SIZE.is_multiple_of(SECTOR_SIZE));
+        Self {
+            data: [0xFF; SIZE],
+            config,
+            busy_polls: Cell::new(0),
+            power_loss_after: None,
+        }
+    }
+
+    /// Read `buf.len()` bytes starting at `offset`
+    pub fn read(&self, offset: u32, buf: &mut [u8]) -> Result<usize, MockFlashError> {
+        self.check_ready()?;
+        let range = self.range(offset, buf.len())?;
+        buf.copy_from_slice(self.data.get(range).ok_or(MockFlashError::OutOfBounds)?);
+        Ok(buf.len())
+    }
+
+    /// Device capacity in bytes
+    pub fn capacity_bytes(&self) -> Result<usize, MockFlashError> {
+        Ok(SIZE)
+    }
+
+    /// Erase the sector containing `offset` to 0xFF
+    ///
+    /// The low address bits are ignored, as on real devices.
+    pub fn erase_sector(&mut self, offset: u32) -> Result<(), MockFlashError> {
+        self.check_ready()?;
+        let start = (offset as usize) & !(SECTOR_SIZE - 1);
+        let range = self.range(start as u32, SECTOR_SIZE)?;
+        let (count, result) = self.power_budget(SECTOR_SIZE);
+        let sector = self
+            .data
+            .get_mut(range)
+            .ok_or(MockFlashError::OutOfBounds)?;
+        for byte in sector.iter_mut().take(count) {
+            *byte = 0xFF;
+        }
+        if result.is_ok() {
+            self.busy_polls.set(self.config.erase_polls);
+        }
+        result
+    }
+
+    /// Program `data` into the page containing `offset`
+    ///
+    /// Each stored byte is ANDed with the new data, so bits only go from 1
+    /// to 0. Bytes past the end of the page wrap to its start. Returns the
+    /// number of bytes programmed.
+    pub fn program_page(&mut self, offset: u32, data: &[u8]) -> Result<usize, MockFlashError> {
+        self.check_ready()?;
+        if data.is_empty() || data.len() > PAGE_SIZE {
+            return Err(MockFlashError::InvalidLength);
+        }
+        let page_start = (offset as usize) & !(PAGE_SIZE - 1);
+        let range = self.range(page_start as u32, PAGE_SIZE)?;
+        let (count, result) = self.power_budget(data.len());
+        let page = self
+            .data
+            .get_mut(range)
+            .ok_or(MockFlashError::OutOfBounds)?;
+        let mut column = (offset as usize) % PAGE_SIZE;
+        for value in data.iter().take(count) {
+            if let Some(byte) = page.get_mut(column) {
+                *byte &= *value;
+            }
+            column = (column + 1) % PAGE_SIZE;
+        }
+        if result.is_ok() {
+            self.busy_polls.set(self.config.program_polls);
+        }
+        result.map(|()| data.len())
+    }
+
+    /// Compare the contents starting at `offset` with `expected`
+    pub fn verify(&self, offset: u32, expected: &[u8]) -> Result<bool, MockFlashError> {
+        self.check_ready()?;
+        let range = self.range(offset, expected.len())?;
+        Ok(self.data.get(range) == Some(expected))
+    }
+
+    /// Read the status register
+    ///
+    /// Reports [`STATUS_WIP`] for the configured number of polls after a
+    /// program or erase, then 0.
+    pub fn status(&self) -> Result<u8, MockFlashError> {
+        let remaining = self.busy_polls.get();
+        if remaining == 0 {
+            Ok(0)
+        } else {
+            self.busy_polls.set(remaining - 1);
+            Ok(STATUS_WIP)
+        }
+    }
+
+    /// Read the JEDEC ID
+    pub fn jedec_id(&self) -> Result<[u8; 3], MockFlashError> {
+        Ok(self.config.jedec_id)
+    }
+
+    // Fault injection and inspection
+
+    /// Flip bit `bit` (0-7) of the byte at `offset`
+    ///
+    /// Unlike a program, this can set a bit back to 1, simulating retention
+    /// loss or disturb.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `offset` is out of range or `bit` is above 7.
+    pub fn flip_bit(&mut self, offset: u32, bit: u8) {
+        assert!(bit < 8);
+        let byte = self
+            .data
+            .get_mut(offset as usize)
+            .expect("flip_bit offset out of range");
+        *byte ^= 1 << bit;
+    }
+
+    /// Lose power partway through the next program or erase
+    ///
+    /// That operation updates only its first `bytes` bytes, returns
+    /// `MockFlashError::PowerLoss` and leaves the device idle.
+    pub fn inject_power_loss(&mut self, bytes: usize) {
+        self.power_loss_after = Some(bytes);
+    }
+
+    /// Cancel a pending power-loss fault
+    pub fn clear_faults(&mut self) {
+        self.power_loss_after = None;
+    }
+
+    /// Whether a program or erase is still in progress
+    pub fn is_busy(&self) -> bool {
+        self.busy_polls.get() != 0
+    }
+
+    /// Raw view of the flash array
+    pub fn contents(&self) -> &[u8] {
+        &self.data
+    }
+
+    fn check_ready(&self) -> Result<(), MockFlashError> {
+        if self.is_busy() {
+            Err(MockFlashError::WriteInProgress)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn range(&self, offset: u32, len: usize) -> Result<core::ops::Range<usize>, MockFlashError> {
+        let start = offset as usize;
+        let end = start.checked_add(len).ok_or(MockFlashError::OutOfBounds)?;
+        if end > SIZE {
+            return Err(MockFlashError::OutOfBounds);
+        }
+        Ok(start..end)
+    }
+
+    /// Bytes an operation of `len` bytes completes, consuming any pending
+    /// power-loss fault
+    fn power_budget(&mut self, len: usize) -> (usize, Result<(), MockFlashError>) {
+        match self.power_loss_after.take() {
+            Some(bytes) => (bytes.min(len), Err(MockFlashError::PowerLoss)),
+            None => (len, Ok(())),
+        }
+    }
+}
+
+impl<const SIZE: usize> Default for MockSpiNorFlash<SIZE> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Flash = MockSpiNorFlash<{ 4 * SECTOR_SIZE }>;
+
+    fn wait(flash: &Flash) -> u32 {
+        let mut polls = 0;
+        while flash.status().unwrap() & STATUS_WIP != 0 {
+            polls += 1;
+        }
+        polls
+    }
+
+    #[test]
+    fn test_identity() {
+        let flash = Flash::new();
+        assert_eq!(flash.capacity_bytes(), Ok(4 * SECTOR_SIZE));
+        assert_eq!(flash.jedec_id(), Ok([0xEF, 0x40, 14]));
+        assert_eq!(flash.status(), Ok(0));
+        assert!(flash.contents().iter().all(|&b| b == 0xFF));
+    }
+
+    #[test]
+    fn test_program_only_clears_bits() {
+        let mut flash = Flash::new();
+        assert_eq!(flash.program_page(0x100, &[0x0F, 0xAA]), Ok(2));
+        wait(&flash);
+        flash.program_page(0x100, &[0xF0, 0xFF]).unwrap();
+        wait(&flash);
+
+        let mut buf = [0u8; 2];
+        assert_eq!(flash.read(0x100, &mut buf), Ok(2));
+        assert_eq!(buf, [0x00, 0xAA]);
+        assert_eq!(flash.verify(0x100, &[0x00, 0xAA]), Ok(true));
+        assert_eq!(flash.verify(0x100, &[0xFF, 0xAA]), Ok(false));
+    }
+
+    #[test]
+    fn test_program_wraps_within_page() {
+        let mut flash = Flash::new();
+        flash.program_page(0x1FE, &[1, 2, 3, 4]).unwrap();
+        wait(&flash);
+        assert_eq!(flash.verify(0x1FE, &[1, 2]), Ok(true));
+        assert_eq!(flash.verify(0x100, &[3, 4]), Ok(true));
+        assert_eq!(flash.verify(0x200, &[0xFF]), Ok(true));
+
+        assert_eq!(
+            flash.program_page(0, &[0; PAGE_SIZE + 1]),
+            Err(MockFlashError::InvalidLength)
+        );
+        assert_eq!(
+            flash.program_page(0, &[]),
+            Err(MockFlashError::InvalidLength)
+        );
+    }
+
+    #[test]
+    fn test_erase_sector() {
+        let mut flash = Flash::new();
+        flash.program_page(SECTOR_SIZE as u32, &[0; 4]).unwrap();
+        wait(&flash);
+        flash.program_page(2 * SECTOR_SIZE as u32, &[0; 4]).unwrap();
+        wait(&flash);
+
+        // Unaligned offset erases the containing sector
+        flash.erase_sector(SECTOR_SIZE as u32 + 0x123).unwrap();
+        wait(&flash);
+        assert_eq!(flash.verify(SECTOR_SIZE as u32, &[0xFF; 4]), Ok(true));
+        assert_eq!(flash.verify(2 * SECTOR_SIZE as u32, &[0; 4]), Ok(true));
+
+        assert_eq!(
+            flash.erase_sector(4 * SECTOR_SIZE as u32),
+            Err(MockFlashError::OutOfBounds)
+        );
+    }
+
+    #[test]
+    fn test_busy_timing() {
+        let mut flash = Flash::with_config(MockSpiNorConfig {
+            jedec_id: [0xC2, 0x20, 0x18],
+            program_polls: 2,
+            erase_polls: 5,
+        });
+        assert_eq!(flash.jedec_id(), Ok([0xC2, 0x20, 0x18]));
+
+        flash.program_page(0, &[0]).unwrap();
+        assert!(flash.is_busy());
+        let mut buf = [0u8; 1];
+        assert_eq!(
+            flash.read(0, &mut buf),
+            Err(MockFlashError::WriteInProgress)
+        );
+        assert_eq!(
+            flash.program_page(1, &[0]),
+            Err(MockFlashError::WriteInProgress)
+        );
+        assert_eq!(wait(&flash), 2);
+
+        flash.erase_sector(0).unwrap();
+        assert_eq!(flash.erase_sector(0), Err(MockFlashError::WriteInProgress));
+        assert_eq!(wait(&flash), 5);
+        assert_eq!(flash.verify(0, &[0xFF]), Ok(true));
+    }
+
+    #[test]
+    fn test_out_of_bounds() {
+        let flash = Flash::new();
+        let mut buf = [0u8; 4];
+        assert_eq!(
+            flash.read(4 * SECTOR_SIZE as u32 - 2, &mut buf),
+            Err(MockFlashError::OutOfBounds)
+        );
+        assert_eq!(
+            flash.verify(u32::MAX, &[0]),
+            Err(MockFlashError::OutOfBounds)
+        );
+    }
+
+    #[test]
+    fn test_bit_flip() {
+        let mut flash = Flash::new();
+        flash.program_page(0, &[0x00]).unwrap();
+        wait(&flash);
+        flash.flip_bit(0, 3);
+        assert_eq!(flash.verify(0, &[0x00]), Ok(false));
+        assert_eq!(flash.verify(0, &[0x08]), Ok(true));
+    }
+
+    #[test]
+    fn test_power_loss_during_program() {
+        let mut flash = Flash::new();
+        flash.inject_power_loss(2);
+        assert_eq!(
+            flash.program_page(0, &[0x11; 4]),
+            Err(MockFlashError::PowerLoss)
+        );
+        assert!(!flash.is_busy());
+        assert_eq!(flash.verify(0, &[0x11, 0x11, 0xFF, 0xFF]), Ok(true));
+
+        // Fault is one-shot
+        assert_eq!(flash.program_page(2, &[0x11; 2]), Ok(2));
+    }
+
+    #[test]
+    fn test_power_loss_during_erase() {
+        let mut flash = Flash::new();
+        flash.program_page(0, &[0; 4]).unwrap();
+        wait(&flash);
+        flash.inject_power_loss(1);
+        assert_eq!(flash.erase_sector(0), Err(MockFlashError::PowerLoss));
+        assert_eq!(flash.verify(0, &[0xFF, 0, 0, 0]), Ok(true));
+
+        flash.inject_power_loss(0);
+        flash.clear_faults();
+        assert_eq!(flash.erase_sector(0), Ok(()));
+    }
+}
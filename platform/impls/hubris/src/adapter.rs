@@ -0,0 +1,233 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! Hubris traits for any `hal/blocking` controller
+//!
+//! [`ControllerAdapter`] implements [`HubrisDigestDevice`] for a controller
+//! that provides the owned SHA-2 digest and HMAC APIs, so a server task can
+//! host any backend without that backend depending on the Hubris traits.
+//!
+//! Controller errors are reduced to [`HubrisCryptoError`] by their
+//! [`ErrorKind`](openprot_hal_blocking::digest::ErrorKind).
+
+use openprot_hal_blocking::digest::owned::{DigestInit, DigestOp};
+use openprot_hal_blocking::digest::{
+    Digest, Error as DigestError, ErrorKind as DigestErrorKind, ErrorType as DigestErrorType,
+    Sha2_256, Sha2_384, Sha2_512,
+};
+use openprot_hal_blocking::mac::owned::{MacInit, MacOp};
+use openprot_hal_blocking::mac::{
+    Error as MacError, ErrorKind as MacErrorKind, ErrorType as MacErrorType, HmacSha2_256,
+    HmacSha2_384, HmacSha2_512,
+};
+use openprot_platform_traits_hubris::{CryptoSession, HubrisCryptoError, HubrisDigestDevice};
+
+/// Maps a digest error onto the Hubris error set.
+pub fn digest_error<E: DigestError>(error: E) -> HubrisCryptoError {
+    match error.kind() {
+        DigestErrorKind::InvalidInputLength | DigestErrorKind::InvalidOutputSize => {
+            HubrisCryptoError::InvalidParameters
+        }
+        DigestErrorKind::UnsupportedAlgorithm => HubrisCryptoError::NotSupported,
+        DigestErrorKind::Busy => HubrisCryptoError::ResourceBusy,
+        _ => HubrisCryptoError::HardwareFailure,
+    }
+}
+
+/// Maps a MAC error onto the Hubris error set.
+pub fn mac_error<E: MacError>(error: E) -> HubrisCryptoError {
+    match error.kind() {
+        MacErrorKind::InvalidInputLength | MacErrorKind::InvalidOutputSize => {
+            HubrisCryptoError::InvalidParameters
+        }
+        MacErrorKind::UnsupportedAlgorithm => HubrisCryptoError::NotSupported,
+        MacErrorKind::Busy => HubrisCryptoError::ResourceBusy,
+        MacErrorKind::NotInitialized => HubrisCryptoError::SessionStateError,
+        MacErrorKind::VerificationFailed => HubrisCryptoError::AuthenticationFailed,
+        _ => HubrisCryptoError::HardwareFailure,
+    }
+}
+
+/// Exposes a `hal/blocking` controller through [`HubrisDigestDevice`].
+pub struct ControllerAdapter<C> {
+    controller: C,
+}
+
+impl<C> ControllerAdapter<C> {
+    /// Wraps `controller`.
+    pub fn new(controller: C) -> Self {
+        Self { controller }
+    }
+
+    /// Releases the controller.
+    pub fn into_inner(self) -> C {
+        self.controller
+    }
+}
+
+/// Digest context of an adapted controller.
+pub struct AdaptedDigest<Ctx> {
+    context: Ctx,
+}
+
+impl<Ctx: DigestOp> DigestErrorType for AdaptedDigest<Ctx> {
+    type Error = Ctx::Error;
+}
+
+impl<Ctx: DigestOp> DigestOp for AdaptedDigest<Ctx> {
+    type Output = Ctx::Output;
+    type Controller = ControllerAdapter<Ctx::Controller>;
+
+    fn update(self, data: &[u8]) -> Result<Self, Self::Error> {
+        Ok(Self {
+            context: self.context.update(data)?,
+        })
+    }
+
+    fn finalize(self) -> Result<(Self::Output, Self::Controller), Self::Error> {
+        let (output, controller) = self.context.finalize()?;
+        Ok((output, ControllerAdapter::new(controller)))
+    }
+
+    fn cancel(self) -> Self::Controller {
+        ControllerAdapter::new(self.context.cancel())
+    }
+}
+
+/// MAC context of an adapted controller.
+pub struct AdaptedMac<Ctx> {
+    context: Ctx,
+}
+
+impl<Ctx: MacOp> MacErrorType for AdaptedMac<Ctx> {
+    type Error = Ctx::Error;
+}
+
+impl<Ctx: MacOp> MacOp for AdaptedMac<Ctx> {
+    type Output = Ctx::Output;
+    type Controller = ControllerAdapter<Ctx::Controller>;
+
+    fn update(self, data: &[u8]) -> Result<Self, Self::Error> {
+        Ok(Self {
+            context: self.context.update(data)?,
+        })
+    }
+
+    fn finalize(self) -> Result<(Self::Output, Self::Controller), Self::Error> {
+        let (output, controller) = self.context.finalize()?;
+        Ok((output, ControllerAdapter::new(controller)))
+    }
+
+    fn cancel(self) -> Self::Controller {
+        ControllerAdapter::new(self.context.cancel())
+    }
+}
+
+/// Hubris platform integration for any owned SHA-2/HMAC controller
+///
+/// Session methods recover the device with `C::default()`, matching how
+/// software and singleton hardware controllers are constructed.
+impl<C, K> HubrisDigestDevice for ControllerAdapter<C>
+where
+    C: DigestInit<Sha2_256, Output = Digest<8>>
+        + DigestInit<Sha2_384, Output = Digest<12>>
+        + DigestInit<Sha2_512, Output = Digest<16>>
+        + MacInit<HmacSha2_256, Key = K, Output = [u8; 32]>
+        + MacInit<HmacSha2_384, Key = K, Output = [u8; 48]>
+        + MacInit<HmacSha2_512, Key = K, Output = [u8; 64]>
+        + Default,
+    K: for<'a> TryFrom<&'a [u8]>,
+{
+    type DigestContext256 = AdaptedDigest<<C as DigestInit<Sha2_256>>::Context>;
+    type DigestContext384 = AdaptedDigest<<C as DigestInit<Sha2_384>>::Context>;
+    type DigestContext512 = AdaptedDigest<<C as DigestInit<Sha2_512>>::Context>;
+
+    type HmacKey = K;
+    type HmacContext256 = AdaptedMac<<C as MacInit<HmacSha2_256>>::Context>;
+    type HmacContext384 = AdaptedMac<<C as MacInit<HmacSha2_384>>::Context>;
+    type HmacContext512 = AdaptedMac<<C as MacInit<HmacSha2_512>>::Context>;
+
+    fn init_digest_sha256(self) -> Result<Self::DigestContext256, HubrisCryptoError> {
+        let context = DigestInit::init(self.controller, Sha2_256).map_err(digest_error)?;
+        Ok(AdaptedDigest { context })
+    }
+
+    fn init_digest_sha384(self) -> Result<Self::DigestContext384, HubrisCryptoError> {
+        let context = DigestInit::init(self.controller, Sha2_384).map_err(digest_error)?;
+        Ok(AdaptedDigest { context })
+    }
+
+    fn init_digest_sha512(self) -> Result<Self::DigestContext512, HubrisCryptoError> {
+        let context = DigestInit::init(self.controller, Sha2_512).map_err(digest_error)?;
+        Ok(AdaptedDigest { context })
+    }
+
+    fn init_hmac_sha256(
+        self,
+        key: Self::HmacKey,
+    ) -> Result<Self::HmacContext256, HubrisCryptoError> {
+        let context = MacInit::init(self.controller, HmacSha2_256, key).map_err(mac_error)?;
+        Ok(AdaptedMac { context })
+    }
+
+    fn init_hmac_sha384(
+        self,
+        key: Self::HmacKey,
+    ) -> Result<Self::HmacContext384, HubrisCryptoError> {
+        let context = MacInit::init(self.controller, HmacSha2_384, key).map_err(mac_error)?;
+        Ok(AdaptedMac { context })
+    }
+
+    fn init_hmac_sha512(
+        self,
+        key: Self::HmacKey,
+    ) -> Result<Self::HmacContext512, HubrisCryptoError> {
+        let context = MacInit::init(self.controller, HmacSha2_512, key).map_err(mac_error)?;
+        Ok(AdaptedMac { context })
+    }
+
+    fn init_digest_session_sha256(
+        self,
+    ) -> Result<CryptoSession<Self::DigestContext256, Self>, HubrisCryptoError> {
+        let context = self.init_digest_sha256()?;
+        Ok(CryptoSession::new(context, Self::new(C::default())))
+    }
+
+    fn init_digest_session_sha384(
+        self,
+    ) -> Result<CryptoSession<Self::DigestContext384, Self>, HubrisCryptoError> {
+        let context = self.init_digest_sha384()?;
+        Ok(CryptoSession::new(context, Self::new(C::default())))
+    }
+
+    fn init_digest_session_sha512(
+        self,
+    ) -> Result<CryptoSession<Self::DigestContext512, Self>, HubrisCryptoError> {
+        let context = self.init_digest_sha512()?;
+        Ok(CryptoSession::new(context, Self::new(C::default())))
+    }
+
+    fn init_hmac_session_sha256(
+        self,
+        key: Self::HmacKey,
+    ) -> Result<CryptoSession<Self::HmacContext256, Self>, HubrisCryptoError> {
+        let context = self.init_hmac_sha256(key)?;
+        Ok(CryptoSession::new(context, Self::new(C::default())))
+    }
+
+    fn init_hmac_session_sha384(
+        self,
+        key: Self::HmacKey,
+    ) -> Result<CryptoSession<Self::HmacContext384, Self>, HubrisCryptoError> {
+        let context = self.init_hmac_sha384(key)?;
+        Ok(CryptoSession::new(context, Self::new(C::default())))
+    }
+
+    fn init_hmac_session_sha512(
+        self,
+        key: Self::HmacKey,
+    ) -> Result<CryptoSession<Self::HmacContext512, Self>, HubrisCryptoError> {
+        let context = self.init_hmac_sha512(key)?;
+        Ok(CryptoSession::new(context, Self::new(C::default())))
+    }
+}
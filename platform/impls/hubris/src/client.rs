@@ -0,0 +1,527 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! Digest server IPC client
+//!
+//! [`DigestClient`] implements [`HubrisDigestDevice`] by forwarding every
+//! operation to a digest server task. Its contexts hold the client, so the
+//! owned API's move semantics carry over unchanged: `finalize` and `cancel`
+//! hand the client back for the next session.
+
+use openprot_hal_blocking::digest::owned::DigestOp;
+use openprot_hal_blocking::digest::{
+    Digest, Error as DigestError, ErrorKind as DigestErrorKind, ErrorType as DigestErrorType,
+};
+use openprot_hal_blocking::mac::owned::MacOp;
+use openprot_hal_blocking::mac::{
+    Error as MacError, ErrorKind as MacErrorKind, ErrorType as MacErrorType,
+};
+use openprot_platform_traits_hubris::{CryptoSession, HubrisCryptoError, HubrisDigestDevice};
+use zeroize::Zeroize;
+
+use crate::idl::{error_from_code, Operation};
+use crate::ipc::{dead_generation, Lease, SysSend, TaskId};
+
+/// Largest result the server produces (SHA-512).
+const MAX_OUTPUT_SIZE: usize = 64;
+
+/// Error from a digest server call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IpcError {
+    /// The server rejected the request.
+    Server(HubrisCryptoError),
+    /// The server restarted, losing any open session.
+    ServerRestarted,
+    /// The server's reply did not match the interface.
+    BadResponse,
+}
+
+impl From<IpcError> for HubrisCryptoError {
+    fn from(error: IpcError) -> Self {
+        match error {
+            IpcError::Server(error) => error,
+            IpcError::ServerRestarted => HubrisCryptoError::SessionStateError,
+            IpcError::BadResponse => HubrisCryptoError::HardwareFailure,
+        }
+    }
+}
+
+impl DigestError for IpcError {
+    fn kind(&self) -> DigestErrorKind {
+        match HubrisCryptoError::from(*self) {
+            HubrisCryptoError::InvalidKeyLength | HubrisCryptoError::InvalidParameters => {
+                DigestErrorKind::InvalidInputLength
+            }
+            HubrisCryptoError::ResourceBusy => DigestErrorKind::Busy,
+            HubrisCryptoError::NotSupported => DigestErrorKind::UnsupportedAlgorithm,
+            HubrisCryptoError::SessionStateError => DigestErrorKind::InitializationError,
+            HubrisCryptoError::HardwareFailure | HubrisCryptoError::AuthenticationFailed => {
+                DigestErrorKind::HardwareFailure
+            }
+        }
+    }
+}
+
+impl MacError for IpcError {
+    fn kind(&self) -> MacErrorKind {
+        match HubrisCryptoError::from(*self) {
+            HubrisCryptoError::InvalidKeyLength | HubrisCryptoError::InvalidParameters => {
+                MacErrorKind::InvalidInputLength
+            }
+            HubrisCryptoError::ResourceBusy => MacErrorKind::Busy,
+            HubrisCryptoError::NotSupported => MacErrorKind::UnsupportedAlgorithm,
+            HubrisCryptoError::SessionStateError => MacErrorKind::NotInitialized,
+            HubrisCryptoError::HardwareFailure => MacErrorKind::HardwareFailure,
+            HubrisCryptoError::AuthenticationFailed => MacErrorKind::VerificationFailed,
+        }
+    }
+}
+
+/// HMAC key buffered on the client until it is lent to the server.
+///
+/// The bytes are zeroed on drop.
+pub struct HmacKey {
+    data: [u8; 128],
+    len: usize,
+}
+
+impl HmacKey {
+    fn as_bytes(&self) -> &[u8] {
+        &self.data[..self.len]
+    }
+}
+
+impl TryFrom<&[u8]> for HmacKey {
+    type Error = HubrisCryptoError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        let mut data = [0u8; 128];
+        data.get_mut(..bytes.len())
+            .ok_or(HubrisCryptoError::InvalidKeyLength)?
+            .copy_from_slice(bytes);
+        Ok(Self {
+            data,
+            len: bytes.len(),
+        })
+    }
+}
+
+impl Drop for HmacKey {
+    fn drop(&mut self) {
+        self.data.zeroize();
+    }
+}
+
+/// Client for a digest server task.
+pub struct DigestClient<K: SysSend> {
+    ipc: K,
+    server: TaskId,
+}
+
+impl<K: SysSend> DigestClient<K> {
+    /// Creates a client for the server at `server`.
+    pub fn new(ipc: K, server: TaskId) -> Self {
+        Self { ipc, server }
+    }
+
+    /// The server task id, including the generation last seen.
+    pub fn server(&self) -> TaskId {
+        self.server
+    }
+
+    /// Sends `operation` and decodes the response code.
+    ///
+    /// When the server has restarted, the client adopts the new generation
+    /// so the next call reaches it, and reports
+    /// [`IpcError::ServerRestarted`].
+    fn call(
+        &mut self,
+        operation: Operation,
+        args: &[u8],
+        reply: &mut [u8],
+        leases: &mut [Lease<'_>],
+    ) -> Result<usize, IpcError> {
+        let (code, len) = self
+            .ipc
+            .sys_send(self.server, operation as u16, args, reply, leases);
+        if code == 0 {
+            return Ok(len);
+        }
+        if let Some(generation) = dead_generation(code) {
+            self.server = TaskId::for_index_and_gen(self.server.index(), generation);
+            return Err(IpcError::ServerRestarted);
+        }
+        Err(error_from_code(code).map_or(IpcError::BadResponse, IpcError::Server))
+    }
+
+    fn init(&mut self, operation: Operation) -> Result<(), IpcError> {
+        self.call(operation, &[], &mut [], &mut []).map(drop)
+    }
+
+    fn init_keyed(&mut self, operation: Operation, key: &HmacKey) -> Result<(), IpcError> {
+        self.call(operation, &[], &mut [], &mut [Lease::Read(key.as_bytes())])
+            .map(drop)
+    }
+
+    fn update(&mut self, data: &[u8]) -> Result<(), IpcError> {
+        let len = u32::try_from(data.len())
+            .map_err(|_| IpcError::Server(HubrisCryptoError::InvalidParameters))?;
+        self.call(
+            Operation::Update,
+            &len.to_le_bytes(),
+            &mut [],
+            &mut [Lease::Read(data)],
+        )
+        .map(drop)
+    }
+
+    /// Finalizes the session into `output`, which must be filled exactly.
+    fn finalize(&mut self, output: &mut [u8]) -> Result<(), IpcError> {
+        let mut buffer = [0u8; MAX_OUTPUT_SIZE];
+        let mut reply = [0u8; 4];
+        let reply_len = self.call(
+            Operation::Finalize,
+            &[],
+            &mut reply,
+            &mut [Lease::Write(&mut buffer)],
+        )?;
+        if reply_len != reply.len() || u32::from_le_bytes(reply) as usize != output.len() {
+            return Err(IpcError::BadResponse);
+        }
+        output.copy_from_slice(&buffer[..output.len()]);
+        Ok(())
+    }
+
+    fn cancel(&mut self) {
+        // A failed cancel leaves nothing to clean up: the next init cancels
+        // any session the server still holds.
+        let _ = self.call(Operation::Cancel, &[], &mut [], &mut []);
+    }
+}
+
+/// Digest session on the server, producing `N` 32-bit words.
+pub struct ClientDigestContext<K: SysSend, const N: usize> {
+    client: DigestClient<K>,
+}
+
+impl<K: SysSend, const N: usize> DigestErrorType for ClientDigestContext<K, N> {
+    type Error = IpcError;
+}
+
+impl<K: SysSend, const N: usize> DigestOp for ClientDigestContext<K, N> {
+    type Output = Digest<N>;
+    type Controller = DigestClient<K>;
+
+    fn update(mut self, data: &[u8]) -> Result<Self, Self::Error> {
+        self.client.update(data)?;
+        Ok(self)
+    }
+
+    fn finalize(mut self) -> Result<(Self::Output, Self::Controller), Self::Error> {
+        let mut bytes = [0u8; MAX_OUTPUT_SIZE];
+        let bytes = bytes
+            .get_mut(..N.saturating_mul(4))
+            .ok_or(IpcError::BadResponse)?;
+        self.client.finalize(bytes)?;
+
+        // The server sends the digest's in-memory words; client and server
+        // share the target's byte order.
+        let mut words = [0u32; N];
+        for (word, chunk) in words.iter_mut().zip(bytes.chunks_exact(4)) {
+            *word = u32::from_ne_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        }
+        Ok((Digest::new(words), self.client))
+    }
+
+    fn cancel(mut self) -> Self::Controller {
+        self.client.cancel();
+        self.client
+    }
+}
+
+/// HMAC session on the server, producing `N` bytes.
+pub struct ClientHmacContext<K: SysSend, const N: usize> {
+    client: DigestClient<K>,
+}
+
+impl<K: SysSend, const N: usize> MacErrorType for ClientHmacContext<K, N> {
+    type Error = IpcError;
+}
+
+impl<K: SysSend, const N: usize> MacOp for ClientHmacContext<K, N> {
+    type Output = [u8; N];
+    type Controller = DigestClient<K>;
+
+    fn update(mut self, data: &[u8]) -> Result<Self, Self::Error> {
+        self.client.update(data)?;
+        Ok(self)
+    }
+
+    fn finalize(mut self) -> Result<(Self::Output, Self::Controller), Self::Error> {
+        let mut output = [0u8; N];
+        self.client.finalize(&mut output)?;
+        Ok((output, self.client))
+    }
+
+    fn cancel(mut self) -> Self::Controller {
+        self.client.cancel();
+        self.client
+    }
+}
+
+/// Hubris platform integration for the digest server client
+///
+/// Session methods hand a clone of the IPC handle to the session as its
+/// recovery device; on Hubris the handle is zero-sized.
+impl<K: SysSend + Clone> HubrisDigestDevice for DigestClient<K> {
+    type DigestContext256 = ClientDigestContext<K, 8>;
+    type DigestContext384 = ClientDigestContext<K, 12>;
+    type DigestContext512 = ClientDigestContext<K, 16>;
+
+    type HmacKey = HmacKey;
+    type HmacContext256 = ClientHmacContext<K, 32>;
+    type HmacContext384 = ClientHmacContext<K, 48>;
+    type HmacContext512 = ClientHmacContext<K, 64>;
+
+    fn init_digest_sha256(mut self) -> Result<Self::DigestContext256, HubrisCryptoError> {
+        self.init(Operation::InitSha256)?;
+        Ok(ClientDigestContext { client: self })
+    }
+
+    fn init_digest_sha384(mut self) -> Result<Self::DigestContext384, HubrisCryptoError> {
+        self.init(Operation::InitSha384)?;
+        Ok(ClientDigestContext { client: self })
+    }
+
+    fn init_digest_sha512(mut self) -> Result<Self::DigestContext512, HubrisCryptoError> {
+        self.init(Operation::InitSha512)?;
+        Ok(ClientDigestContext { client: self })
+    }
+
+    fn init_hmac_sha256(
+        mut self,
+        key: Self::HmacKey,
+    ) -> Result<Self::HmacContext256, HubrisCryptoError> {
+        self.init_keyed(Operation::InitHmacSha256, &key)?;
+        Ok(ClientHmacContext { client: self })
+    }
+
+    fn init_hmac_sha384(
+        mut self,
+        key: Self::HmacKey,
+    ) -> Result<Self::HmacContext384, HubrisCryptoError> {
+        self.init_keyed(Operation::InitHmacSha384, &key)?;
+        Ok(ClientHmacContext { client: self })
+    }
+
+    fn init_hmac_sha512(
+        mut self,
+        key: Self::HmacKey,
+    ) -> Result<Self::HmacContext512, HubrisCryptoError> {
+        self.init_keyed(Operation::InitHmacSha512, &key)?;
+        Ok(ClientHmacContext { client: self })
+    }
+
+    fn init_digest_session_sha256(
+        self,
+    ) -> Result<CryptoSession<Self::DigestContext256, Self>, HubrisCryptoError> {
+        let recovered_device = DigestClient::new(self.ipc.clone(), self.server);
+        Ok(CryptoSession::new(
+            self.init_digest_sha256()?,
+            recovered_device,
+        ))
+    }
+
+    fn init_digest_session_sha384(
+        self,
+    ) -> Result<CryptoSession<Self::DigestContext384, Self>, HubrisCryptoError> {
+        let recovered_device = DigestClient::new(self.ipc.clone(), self.server);
+        Ok(CryptoSession::new(
+            self.init_digest_sha384()?,
+            recovered_device,
+        ))
+    }
+
+    fn init_digest_session_sha512(
+        self,
+    ) -> Result<CryptoSession<Self::DigestContext512, Self>, HubrisCryptoError> {
+        let recovered_device = DigestClient::new(self.ipc.clone(), self.server);
+        Ok(CryptoSession::new(
+            self.init_digest_sha512()?,
+            recovered_device,
+        ))
+    }
+
+    fn init_hmac_session_sha256(
+        self,
+        key: Self::HmacKey,
+    ) -> Result<CryptoSession<Self::HmacContext256, Self>, HubrisCryptoError> {
+        let recovered_device = DigestClient::new(self.ipc.clone(), self.server);
+        Ok(CryptoSession::new(
+            self.init_hmac_sha256(key)?,
+            recovered_device,
+        ))
+    }
+
+    fn init_hmac_session_sha384(
+        self,
+        key: Self::HmacKey,
+    ) -> Result<CryptoSession<Self::HmacContext384, Self>, HubrisCryptoError> {
+        let recovered_device = DigestClient::new(self.ipc.clone(), self.server);
+        Ok(CryptoSession::new(
+            self.init_hmac_sha384(key)?,
+            recovered_device,
+        ))
+    }
+
+    fn init_hmac_session_sha512(
+        self,
+        key: Self::HmacKey,
+    ) -> Result<CryptoSession<Self::HmacContext512, Self>, HubrisCryptoError> {
+        let recovered_device = DigestClient::new(self.ipc.clone(), self.server);
+        Ok(CryptoSession::new(
+            self.init_hmac_sha512(key)?,
+            recovered_device,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{FakeKernel, SERVER_INDEX};
+    use hex_literal::hex;
+    use openprot_platform_traits_hubris::HubrisDigestOneShot;
+
+    // RFC 4231 test case 2
+    const KEY: &[u8] = b"Jefe";
+    const DATA: &[u8] = b"what do ya want for nothing?";
+
+    fn client(kernel: &FakeKernel) -> DigestClient<FakeKernel> {
+        DigestClient::new(kernel.clone(), kernel.server_id())
+    }
+
+    #[test]
+    fn test_oneshot_digests() {
+        let kernel = FakeKernel::new();
+
+        assert_eq!(
+            client(&kernel)
+                .digest_sha256_oneshot(b"abc")
+                .unwrap()
+                .as_bytes(),
+            hex!("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+        );
+        assert_eq!(
+            client(&kernel)
+                .digest_sha384_oneshot(b"abc")
+                .unwrap()
+                .as_bytes(),
+            hex!(
+                "cb00753f45a35e8bb5a03d699ac65007272c32ab0eded1631a8b605a43ff5bed"
+                "8086072ba1e7cc2358baeca134c825a7"
+            )
+        );
+        assert_eq!(
+            client(&kernel)
+                .digest_sha512_oneshot(b"abc")
+                .unwrap()
+                .as_bytes(),
+            hex!(
+                "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a"
+                "2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f"
+            )
+        );
+        assert!(kernel.server_idle());
+    }
+
+    #[test]
+    fn test_oneshot_hmac() {
+        let kernel = FakeKernel::new();
+
+        assert_eq!(
+            client(&kernel).hmac_sha256_oneshot(KEY, DATA).unwrap(),
+            hex!("5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843")
+        );
+        assert_eq!(
+            client(&kernel).hmac_sha512_oneshot(KEY, DATA).unwrap(),
+            hex!(
+                "164b7a7bfcf819e2e395fbe73b56e0a387bd64222e831fd610270cd7ea250554"
+                "9758bf75c05a994a6d034f65f8f0e6fdcaeab1a34d4a6b4b636e070a38bce737"
+            )
+        );
+    }
+
+    #[test]
+    fn test_session_streams_and_recovers_client() {
+        let kernel = FakeKernel::new();
+        let key = DigestClient::<FakeKernel>::create_hmac_key(KEY).unwrap();
+
+        let mut session = client(&kernel).init_hmac_session_sha384(key).unwrap();
+        for chunk in DATA.chunks(7) {
+            session = session.update_mac(chunk).unwrap();
+        }
+        let (mac, client) = session.finalize_mac().unwrap();
+        assert_eq!(
+            mac,
+            hex!(
+                "af45d2e376484031617f78d2b58a6b1b9c7ef464f5a01b47e42ec3736322445e"
+                "8e2240ca5e69e2c78b3239ecfab21649"
+            )
+        );
+
+        // The recovered client starts the next session on the same server
+        let context = client.init_digest_sha256().unwrap();
+        assert!(!kernel.server_idle());
+        context.cancel();
+        assert!(kernel.server_idle());
+    }
+
+    #[test]
+    fn test_server_errors_surface() {
+        let kernel = FakeKernel::new();
+
+        let oversized = [0u8; 129];
+        assert_eq!(
+            DigestClient::<FakeKernel>::create_hmac_key(&oversized).err(),
+            Some(HubrisCryptoError::InvalidKeyLength)
+        );
+
+        // Updating without a session is rejected by the server
+        let mut client = client(&kernel);
+        let err = client.update(b"abc").unwrap_err();
+        assert_eq!(err, IpcError::Server(HubrisCryptoError::SessionStateError));
+        assert_eq!(
+            DigestError::kind(&err),
+            DigestErrorKind::InitializationError
+        );
+        assert_eq!(MacError::kind(&err), MacErrorKind::NotInitialized);
+    }
+
+    #[test]
+    fn test_server_restart_is_reported_once() {
+        let kernel = FakeKernel::new();
+        let context = client(&kernel).init_digest_sha256().unwrap();
+
+        kernel.restart_server();
+        let err = context.update(b"abc").err().unwrap();
+        assert_eq!(err, IpcError::ServerRestarted);
+        assert_eq!(
+            HubrisCryptoError::from(err),
+            HubrisCryptoError::SessionStateError
+        );
+
+        // A fresh client on the stale id learns the new generation and then
+        // reaches the restarted server
+        let mut client =
+            DigestClient::new(kernel.clone(), TaskId::for_index_and_gen(SERVER_INDEX, 0));
+        assert_eq!(
+            client.init(Operation::InitSha256),
+            Err(IpcError::ServerRestarted)
+        );
+        assert_eq!(client.server(), kernel.server_id());
+        let sends = kernel.sends();
+        assert!(client.digest_sha256_oneshot(b"abc").is_ok());
+        assert_eq!(kernel.sends(), sends + 3);
+    }
+}
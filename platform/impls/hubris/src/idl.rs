@@ -0,0 +1,143 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! Digest server interface
+//!
+//! The wire contract between [`DigestClient`](crate::client::DigestClient) and
+//! [`DigestServer`](crate::server::DigestServer), laid out the way Idol
+//! generates it: operations are numbered from 1 in declaration order,
+//! arguments are little-endian, and a zero response code means success.
+//!
+//! | Operation          | Arguments  | Leases             | Reply        |
+//! |--------------------|------------|--------------------|--------------|
+//! | `init_sha256`      |            |                    |              |
+//! | `init_sha384`      |            |                    |              |
+//! | `init_sha512`      |            |                    |              |
+//! | `init_hmac_sha256` |            | key (read)         |              |
+//! | `init_hmac_sha384` |            | key (read)         |              |
+//! | `init_hmac_sha512` |            | key (read)         |              |
+//! | `update`           | `len: u32` | data (read)        |              |
+//! | `finalize`         |            | output (write)     | `len: u32`   |
+//! | `cancel`           |            |                    |              |
+//!
+//! The server holds one session; an `init_*` while a session is open
+//! cancels it first.
+
+use openprot_platform_traits_hubris::HubrisCryptoError;
+
+/// Digest server operations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u16)]
+pub enum Operation {
+    /// Start a SHA-256 session.
+    InitSha256 = 1,
+    /// Start a SHA-384 session.
+    InitSha384 = 2,
+    /// Start a SHA-512 session.
+    InitSha512 = 3,
+    /// Start an HMAC-SHA256 session keyed from lease 0.
+    InitHmacSha256 = 4,
+    /// Start an HMAC-SHA384 session keyed from lease 0.
+    InitHmacSha384 = 5,
+    /// Start an HMAC-SHA512 session keyed from lease 0.
+    InitHmacSha512 = 6,
+    /// Absorb lease 0 into the open session.
+    Update = 7,
+    /// Write the session result into lease 0 and close the session.
+    Finalize = 8,
+    /// Close the open session, if any.
+    Cancel = 9,
+}
+
+impl TryFrom<u16> for Operation {
+    type Error = HubrisCryptoError;
+
+    fn try_from(value: u16) -> Result<Self, Self::Error> {
+        Ok(match value {
+            1 => Operation::InitSha256,
+            2 => Operation::InitSha384,
+            3 => Operation::InitSha512,
+            4 => Operation::InitHmacSha256,
+            5 => Operation::InitHmacSha384,
+            6 => Operation::InitHmacSha512,
+            7 => Operation::Update,
+            8 => Operation::Finalize,
+            9 => Operation::Cancel,
+            _ => return Err(HubrisCryptoError::NotSupported),
+        })
+    }
+}
+
+/// Encodes an error as a response code.
+///
+/// Codes start at 1, in declaration order of [`HubrisCryptoError`].
+#[must_use]
+pub const fn error_code(error: HubrisCryptoError) -> u32 {
+    match error {
+        HubrisCryptoError::InvalidKeyLength => 1,
+        HubrisCryptoError::HardwareFailure => 2,
+        HubrisCryptoError::InvalidParameters => 3,
+        HubrisCryptoError::ResourceBusy => 4,
+        HubrisCryptoError::NotSupported => 5,
+        HubrisCryptoError::SessionStateError => 6,
+        HubrisCryptoError::AuthenticationFailed => 7,
+    }
+}
+
+/// Decodes a non-zero, non-dead response code.
+#[must_use]
+pub const fn error_from_code(code: u32) -> Option<HubrisCryptoError> {
+    match code {
+        1 => Some(HubrisCryptoError::InvalidKeyLength),
+        2 => Some(HubrisCryptoError::HardwareFailure),
+        3 => Some(HubrisCryptoError::InvalidParameters),
+        4 => Some(HubrisCryptoError::ResourceBusy),
+        5 => Some(HubrisCryptoError::NotSupported),
+        6 => Some(HubrisCryptoError::SessionStateError),
+        7 => Some(HubrisCryptoError::AuthenticationFailed),
+        _ => None,
+    }
+}
+
+/// Encodes a server result as a response code.
+#[must_use]
+pub const fn response_code<T>(result: &Result<T, HubrisCryptoError>) -> u32 {
+    match result {
+        Ok(_) => 0,
+        Err(error) => error_code(*error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_codes_round_trip() {
+        for error in [
+            HubrisCryptoError::InvalidKeyLength,
+            HubrisCryptoError::HardwareFailure,
+            HubrisCryptoError::InvalidParameters,
+            HubrisCryptoError::ResourceBusy,
+            HubrisCryptoError::NotSupported,
+            HubrisCryptoError::SessionStateError,
+            HubrisCryptoError::AuthenticationFailed,
+        ] {
+            assert_eq!(error_from_code(error_code(error)), Some(error));
+        }
+        assert_eq!(error_from_code(0), None);
+        assert_eq!(error_from_code(8), None);
+    }
+
+    #[test]
+    fn test_operations_round_trip() {
+        for raw in 1..=9 {
+            assert_eq!(Operation::try_from(raw).unwrap() as u16, raw);
+        }
+        assert_eq!(Operation::try_from(0), Err(HubrisCryptoError::NotSupported));
+        assert_eq!(
+            Operation::try_from(10),
+            Err(HubrisCryptoError::NotSupported)
+        );
+    }
+}
@@ -0,0 +1,80 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! Hubris IPC seam
+//!
+//! Mirrors the shape of `userlib::sys_send` so that the client can be driven
+//! by the kernel on target and by an in-process fake on the host. On target,
+//! implement [`SysSend`] for a zero-sized handle that forwards to
+//! `userlib::sys_send`, converting [`Lease`]s into `userlib::Lease`s.
+
+/// Identifies a task and the generation it is expected to be in.
+///
+/// Same layout as the Hubris `TaskId`: the low 10 bits are the task index and
+/// the high 6 bits are the generation, which the kernel bumps on restart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TaskId(pub u16);
+
+impl TaskId {
+    const INDEX_BITS: u32 = 10;
+    const INDEX_MASK: u16 = (1 << Self::INDEX_BITS) - 1;
+
+    /// Builds a task id from its index and generation.
+    #[must_use]
+    pub const fn for_index_and_gen(index: u16, generation: u8) -> Self {
+        Self((index & Self::INDEX_MASK) | ((generation as u16) << Self::INDEX_BITS))
+    }
+
+    /// The task index.
+    #[must_use]
+    pub const fn index(self) -> u16 {
+        self.0 & Self::INDEX_MASK
+    }
+
+    /// The generation the caller expects the task to be in.
+    #[must_use]
+    pub const fn generation(self) -> u8 {
+        (self.0 >> Self::INDEX_BITS) as u8
+    }
+}
+
+/// Response code marking a send to a task that has restarted.
+///
+/// The kernel reports `DEAD_CODE_BASE | new_generation`.
+pub const DEAD_CODE_BASE: u32 = 0xffff_ff00;
+
+/// If `code` reports a dead peer, returns the peer's new generation.
+#[must_use]
+pub const fn dead_generation(code: u32) -> Option<u8> {
+    if code & DEAD_CODE_BASE == DEAD_CODE_BASE {
+        Some(code as u8)
+    } else {
+        None
+    }
+}
+
+/// Memory lent to the server for the duration of a send.
+pub enum Lease<'a> {
+    /// The server may read this buffer.
+    Read(&'a [u8]),
+    /// The server may write this buffer.
+    Write(&'a mut [u8]),
+}
+
+/// The Hubris send system call.
+pub trait SysSend {
+    /// Sends `operation` with `outgoing` arguments to `target`, blocking
+    /// until it replies into `incoming`.
+    ///
+    /// Returns the response code and reply length. A response code with
+    /// [`DEAD_CODE_BASE`] set means `target` restarted; see
+    /// [`dead_generation`].
+    fn sys_send(
+        &mut self,
+        target: TaskId,
+        operation: u16,
+        outgoing: &[u8],
+        incoming: &mut [u8],
+        leases: &mut [Lease<'_>],
+    ) -> (u32, usize);
+}
@@ -4,5 +4,31 @@
 //! Hubris OS platform implementation for OpenPRoT
 //!
 //! This crate provides Hubris OS-specific implementations of platform abstraction traits.
+//!
+//! | Module | Contents |
+//! |--------|----------|
+//! | [`idl`] | Digest server operation numbers and error codes |
+//! | [`ipc`] | `sys_send` seam, task ids and leases |
+//! | [`client`] | [`HubrisDigestDevice`](openprot_platform_traits_hubris::HubrisDigestDevice) over IPC to a digest server |
+//! | [`server`] | Digest server dispatch for any `HubrisDigestDevice` |
+//! | [`adapter`] | `HubrisDigestDevice` for any owned `hal/blocking` digest/MAC controller |
+//!
+//! A digest server task wraps its backend in an
+//! [`adapter::ControllerAdapter`] (or uses a backend that implements the
+//! Hubris traits itself) and feeds received messages to
+//! [`server::DigestServer::dispatch`]; client tasks use
+//! [`client::DigestClient`] exactly as they would a local device.
 
 #![no_std]
+
+#[cfg(test)]
+extern crate std;
+
+pub mod adapter;
+pub mod client;
+pub mod idl;
+pub mod ipc;
+pub mod server;
+
+#[cfg(test)]
+mod test_util;
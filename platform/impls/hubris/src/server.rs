@@ -0,0 +1,365 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! Digest server dispatch
+//!
+//! [`DigestServer`] decodes the operations in [`idl`](crate::idl) and runs
+//! them against a [`HubrisDigestDevice`]. It holds at most one session; the
+//! server task's receive loop passes each message to
+//! [`DigestServer::dispatch`] and replies with the encoded result.
+//!
+//! The owned API consumes the device or context on failure, so a device
+//! that fails to initialize, update or finalize is lost and every later
+//! operation reports [`HubrisCryptoError::HardwareFailure`] until the task
+//! restarts.
+
+use core::mem;
+
+use openprot_hal_blocking::digest::owned::DigestOp;
+use openprot_hal_blocking::mac::owned::MacOp;
+use openprot_platform_traits_hubris::{HubrisCryptoError, HubrisDigestDevice};
+
+use crate::adapter::{digest_error, mac_error};
+use crate::idl::Operation;
+use crate::ipc::Lease;
+
+enum Session<D: HubrisDigestDevice> {
+    Idle(D),
+    Sha256(D::DigestContext256),
+    Sha384(D::DigestContext384),
+    Sha512(D::DigestContext512),
+    HmacSha256(D::HmacContext256),
+    HmacSha384(D::HmacContext384),
+    HmacSha512(D::HmacContext512),
+    Lost,
+}
+
+impl<D: HubrisDigestDevice> Session<D> {
+    /// Result size of the open session, in bytes.
+    fn output_len(&self) -> Option<usize> {
+        match self {
+            Session::Sha256(_) | Session::HmacSha256(_) => Some(32),
+            Session::Sha384(_) | Session::HmacSha384(_) => Some(48),
+            Session::Sha512(_) | Session::HmacSha512(_) => Some(64),
+            Session::Idle(_) | Session::Lost => None,
+        }
+    }
+}
+
+/// Serves digest and HMAC sessions from a single device.
+pub struct DigestServer<D: HubrisDigestDevice> {
+    session: Session<D>,
+}
+
+impl<D: HubrisDigestDevice> DigestServer<D> {
+    /// Creates a server with no open session.
+    pub fn new(device: D) -> Self {
+        Self {
+            session: Session::Idle(device),
+        }
+    }
+
+    /// Whether the device is available for a new session.
+    pub fn is_idle(&self) -> bool {
+        matches!(self.session, Session::Idle(_))
+    }
+
+    /// Handles one message.
+    ///
+    /// `operation` and `incoming` are the received operation and arguments;
+    /// the reply arguments are written to `reply` and their length is
+    /// returned.
+    pub fn dispatch(
+        &mut self,
+        operation: u16,
+        incoming: &[u8],
+        reply: &mut [u8],
+        leases: &mut [Lease<'_>],
+    ) -> Result<usize, HubrisCryptoError> {
+        match Operation::try_from(operation)? {
+            Operation::InitSha256 => {
+                self.session = Session::Sha256(self.take_device()?.init_digest_sha256()?);
+            }
+            Operation::InitSha384 => {
+                self.session = Session::Sha384(self.take_device()?.init_digest_sha384()?);
+            }
+            Operation::InitSha512 => {
+                self.session = Session::Sha512(self.take_device()?.init_digest_sha512()?);
+            }
+            Operation::InitHmacSha256 => {
+                let key = D::create_hmac_key(read_lease(leases)?)?;
+                self.session = Session::HmacSha256(self.take_device()?.init_hmac_sha256(key)?);
+            }
+            Operation::InitHmacSha384 => {
+                let key = D::create_hmac_key(read_lease(leases)?)?;
+                self.session = Session::HmacSha384(self.take_device()?.init_hmac_sha384(key)?);
+            }
+            Operation::InitHmacSha512 => {
+                let key = D::create_hmac_key(read_lease(leases)?)?;
+                self.session = Session::HmacSha512(self.take_device()?.init_hmac_sha512(key)?);
+            }
+            Operation::Update => self.update(incoming, leases)?,
+            Operation::Finalize => return self.finalize(reply, leases),
+            Operation::Cancel => self.session = Session::Idle(self.take_device()?),
+        }
+        Ok(0)
+    }
+
+    /// Takes the device, cancelling any open session.
+    fn take_device(&mut self) -> Result<D, HubrisCryptoError> {
+        match mem::replace(&mut self.session, Session::Lost) {
+            Session::Idle(device) => Ok(device),
+            Session::Sha256(context) => Ok(context.cancel()),
+            Session::Sha384(context) => Ok(context.cancel()),
+            Session::Sha512(context) => Ok(context.cancel()),
+            Session::HmacSha256(context) => Ok(context.cancel()),
+            Session::HmacSha384(context) => Ok(context.cancel()),
+            Session::HmacSha512(context) => Ok(context.cancel()),
+            Session::Lost => Err(HubrisCryptoError::HardwareFailure),
+        }
+    }
+
+    fn update(&mut self, incoming: &[u8], leases: &[Lease<'_>]) -> Result<(), HubrisCryptoError> {
+        let len = incoming
+            .try_into()
+            .map(u32::from_le_bytes)
+            .map_err(|_| HubrisCryptoError::InvalidParameters)?;
+        let data = read_lease(leases)?;
+        if data.len() != len as usize {
+            return Err(HubrisCryptoError::InvalidParameters);
+        }
+
+        self.session = match mem::replace(&mut self.session, Session::Lost) {
+            Session::Sha256(context) => {
+                Session::Sha256(context.update(data).map_err(digest_error)?)
+            }
+            Session::Sha384(context) => {
+                Session::Sha384(context.update(data).map_err(digest_error)?)
+            }
+            Session::Sha512(context) => {
+                Session::Sha512(context.update(data).map_err(digest_error)?)
+            }
+            Session::HmacSha256(context) => {
+                Session::HmacSha256(context.update(data).map_err(mac_error)?)
+            }
+            Session::HmacSha384(context) => {
+                Session::HmacSha384(context.update(data).map_err(mac_error)?)
+            }
+            Session::HmacSha512(context) => {
+                Session::HmacSha512(context.update(data).map_err(mac_error)?)
+            }
+            Session::Idle(device) => {
+                self.session = Session::Idle(device);
+                return Err(HubrisCryptoError::SessionStateError);
+            }
+            Session::Lost => return Err(HubrisCryptoError::HardwareFailure),
+        };
+        Ok(())
+    }
+
+    fn finalize(
+        &mut self,
+        reply: &mut [u8],
+        leases: &mut [Lease<'_>],
+    ) -> Result<usize, HubrisCryptoError> {
+        let Some(len) = self.session.output_len() else {
+            return Err(match self.session {
+                Session::Lost => HubrisCryptoError::HardwareFailure,
+                _ => HubrisCryptoError::SessionStateError,
+            });
+        };
+        let reply = reply
+            .get_mut(..4)
+            .ok_or(HubrisCryptoError::InvalidParameters)?;
+        let output = match leases.first_mut() {
+            Some(Lease::Write(buffer)) => buffer
+                .get_mut(..len)
+                .ok_or(HubrisCryptoError::InvalidParameters)?,
+            _ => return Err(HubrisCryptoError::InvalidParameters),
+        };
+
+        let device = match mem::replace(&mut self.session, Session::Lost) {
+            Session::Sha256(context) => {
+                let (digest, device) = context.finalize().map_err(digest_error)?;
+                output.copy_from_slice(digest.as_bytes());
+                device
+            }
+            Session::Sha384(context) => {
+                let (digest, device) = context.finalize().map_err(digest_error)?;
+                output.copy_from_slice(digest.as_bytes());
+                device
+            }
+            Session::Sha512(context) => {
+                let (digest, device) = context.finalize().map_err(digest_error)?;
+                output.copy_from_slice(digest.as_bytes());
+                device
+            }
+            Session::HmacSha256(context) => {
+                let (mac, device) = context.finalize().map_err(mac_error)?;
+                output.copy_from_slice(&mac);
+                device
+            }
+            Session::HmacSha384(context) => {
+                let (mac, device) = context.finalize().map_err(mac_error)?;
+                output.copy_from_slice(&mac);
+                device
+            }
+            Session::HmacSha512(context) => {
+                let (mac, device) = context.finalize().map_err(mac_error)?;
+                output.copy_from_slice(&mac);
+                device
+            }
+            Session::Idle(_) | Session::Lost => return Err(HubrisCryptoError::SessionStateError),
+        };
+        self.session = Session::Idle(device);
+
+        reply.copy_from_slice(&(len as u32).to_le_bytes());
+        Ok(reply.len())
+    }
+}
+
+/// Returns lease 0, which must be readable.
+fn read_lease<'a>(leases: &'a [Lease<'_>]) -> Result<&'a [u8], HubrisCryptoError> {
+    match leases.first() {
+        Some(Lease::Read(data)) => Ok(data),
+        _ => Err(HubrisCryptoError::InvalidParameters),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::idl::Operation;
+    use crate::test_util::{fresh_server, SoftServer};
+    use hex_literal::hex;
+
+    fn send(
+        server: &mut SoftServer,
+        operation: Operation,
+        incoming: &[u8],
+        leases: &mut [Lease<'_>],
+    ) -> Result<usize, HubrisCryptoError> {
+        server.dispatch(operation as u16, incoming, &mut [0u8; 4], leases)
+    }
+
+    fn update(server: &mut SoftServer, data: &[u8]) -> Result<usize, HubrisCryptoError> {
+        let len = (data.len() as u32).to_le_bytes();
+        send(server, Operation::Update, &len, &mut [Lease::Read(data)])
+    }
+
+    #[test]
+    fn test_update_and_finalize_need_a_session() {
+        let mut server = fresh_server();
+
+        assert_eq!(
+            update(&mut server, b"abc"),
+            Err(HubrisCryptoError::SessionStateError)
+        );
+        let mut output = [0u8; 64];
+        assert_eq!(
+            send(
+                &mut server,
+                Operation::Finalize,
+                &[],
+                &mut [Lease::Write(&mut output)]
+            ),
+            Err(HubrisCryptoError::SessionStateError)
+        );
+        assert!(server.is_idle());
+    }
+
+    #[test]
+    fn test_malformed_requests_keep_the_session() {
+        let mut server = fresh_server();
+        send(&mut server, Operation::InitSha256, &[], &mut []).unwrap();
+
+        // Declared length disagrees with the lease
+        assert_eq!(
+            send(
+                &mut server,
+                Operation::Update,
+                &4u32.to_le_bytes(),
+                &mut [Lease::Read(b"abc")]
+            ),
+            Err(HubrisCryptoError::InvalidParameters)
+        );
+        // Output lease too small for SHA-256
+        let mut short = [0u8; 31];
+        assert_eq!(
+            send(
+                &mut server,
+                Operation::Finalize,
+                &[],
+                &mut [Lease::Write(&mut short)]
+            ),
+            Err(HubrisCryptoError::InvalidParameters)
+        );
+        assert_eq!(
+            server.dispatch(0x7f, &[], &mut [], &mut []),
+            Err(HubrisCryptoError::NotSupported)
+        );
+
+        update(&mut server, b"abc").unwrap();
+        let mut output = [0u8; 32];
+        let mut reply = [0u8; 4];
+        assert_eq!(
+            server.dispatch(
+                Operation::Finalize as u16,
+                &[],
+                &mut reply,
+                &mut [Lease::Write(&mut output)]
+            ),
+            Ok(4)
+        );
+        assert_eq!(u32::from_le_bytes(reply), 32);
+        assert_eq!(
+            output,
+            hex!("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+        );
+        assert!(server.is_idle());
+    }
+
+    #[test]
+    fn test_init_replaces_open_session() {
+        let mut server = fresh_server();
+        send(&mut server, Operation::InitSha512, &[], &mut []).unwrap();
+        update(&mut server, b"discarded").unwrap();
+
+        send(&mut server, Operation::InitSha256, &[], &mut []).unwrap();
+        update(&mut server, b"abc").unwrap();
+        let mut output = [0u8; 64];
+        send(
+            &mut server,
+            Operation::Finalize,
+            &[],
+            &mut [Lease::Write(&mut output)],
+        )
+        .unwrap();
+        assert_eq!(
+            output[..32],
+            hex!("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+        );
+    }
+
+    #[test]
+    fn test_hmac_key_must_be_lent() {
+        let mut server = fresh_server();
+        assert_eq!(
+            send(&mut server, Operation::InitHmacSha256, &[], &mut []),
+            Err(HubrisCryptoError::InvalidParameters)
+        );
+        assert_eq!(
+            send(
+                &mut server,
+                Operation::InitHmacSha256,
+                &[],
+                &mut [Lease::Read(&[0u8; 129])]
+            ),
+            Err(HubrisCryptoError::InvalidKeyLength)
+        );
+        assert!(server.is_idle());
+
+        send(&mut server, Operation::Cancel, &[], &mut []).unwrap();
+        assert!(server.is_idle());
+    }
+}
@@ -0,0 +1,94 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! Fixtures shared by the unit tests of this crate
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use platform_rustcrypto_impl::controller::RustCryptoController;
+
+use crate::adapter::ControllerAdapter;
+use crate::idl::response_code;
+use crate::ipc::{Lease, SysSend, TaskId, DEAD_CODE_BASE};
+use crate::server::DigestServer;
+
+/// Task index the fake kernel gives the digest server.
+pub(crate) const SERVER_INDEX: u16 = 3;
+
+pub(crate) type SoftServer = DigestServer<ControllerAdapter<RustCryptoController>>;
+
+struct ServerTask {
+    server: SoftServer,
+    generation: u8,
+}
+
+/// In-process `sys_send` that delivers to a [`DigestServer`] backed by the
+/// RustCrypto controller.
+///
+/// Clones share the server task, as clients of one task would. A restart
+/// replaces the server and bumps its generation; sends addressed to the old
+/// generation come back dead, as they do from the kernel.
+#[derive(Clone)]
+pub(crate) struct FakeKernel {
+    task: Rc<RefCell<ServerTask>>,
+    sends: Rc<RefCell<usize>>,
+}
+
+impl FakeKernel {
+    pub(crate) fn new() -> Self {
+        Self {
+            task: Rc::new(RefCell::new(ServerTask {
+                server: fresh_server(),
+                generation: 0,
+            })),
+            sends: Rc::new(RefCell::new(0)),
+        }
+    }
+
+    /// The current task id of the server.
+    pub(crate) fn server_id(&self) -> TaskId {
+        TaskId::for_index_and_gen(SERVER_INDEX, self.task.borrow().generation)
+    }
+
+    /// Restarts the server task, dropping any open session.
+    pub(crate) fn restart_server(&self) {
+        let mut task = self.task.borrow_mut();
+        task.server = fresh_server();
+        task.generation += 1;
+    }
+
+    /// Whether the server has no open session.
+    pub(crate) fn server_idle(&self) -> bool {
+        self.task.borrow().server.is_idle()
+    }
+
+    /// Number of messages sent so far.
+    pub(crate) fn sends(&self) -> usize {
+        *self.sends.borrow()
+    }
+}
+
+impl SysSend for FakeKernel {
+    fn sys_send(
+        &mut self,
+        target: TaskId,
+        operation: u16,
+        outgoing: &[u8],
+        incoming: &mut [u8],
+        leases: &mut [Lease<'_>],
+    ) -> (u32, usize) {
+        *self.sends.borrow_mut() += 1;
+        let mut task = self.task.borrow_mut();
+        assert_eq!(target.index(), SERVER_INDEX, "send to unknown task");
+        if target.generation() != task.generation {
+            return (DEAD_CODE_BASE | u32::from(task.generation), 0);
+        }
+        let result = task.server.dispatch(operation, outgoing, incoming, leases);
+        (response_code(&result), result.unwrap_or(0))
+    }
+}
+
+pub(crate) fn fresh_server() -> SoftServer {
+    DigestServer::new(ControllerAdapter::new(RustCryptoController::new()))
+}
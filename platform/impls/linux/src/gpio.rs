@@ -0,0 +1,804 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! GPIO and system control over the Linux GPIO character device
+//!
+//! [`LinuxGpioPort`] implements `GpioPort` for up to 64 lines of one GPIO
+//! chip, with [`LineMask`] bit `n` selecting line offset `n`. Polarity is
+//! applied here, so masks always carry logical (active = 1) levels.
+//!
+//! [`GpioSystemControl`] implements `SystemControl` on top of a port for
+//! boards where resets and clock gates are GPIO lines: a reset line is driven
+//! active while asserted, a clock line enables an oscillator with a fixed
+//! frequency. Clock and reset IDs are line offsets.
+//!
+//! Line access goes through [`GpioLines`]. [`CdevLines`] is the real
+//! implementation on `/dev/gpiochipN` via `gpio-cdev`; [`SimulatedLines`] is
+//! an in-memory chip for tests and the PRoT emulator.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use openprot_hal_blocking::gpio_port::{GpioPort, PinConfig};
+//! use openprot_platform_linux::gpio::{CdevLines, LineMask, LinuxGpioPort};
+//!
+//! let lines = CdevLines::open("/dev/gpiochip0", "openprot")?;
+//! let mut port = LinuxGpioPort::new(lines);
+//! let led = LineMask::line(17);
+//! port.configure(led, PinConfig::output_active_high(false))?;
+//! port.toggle(led)?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+use gpio_cdev::{Chip, LineHandle, LineRequestFlags};
+use openprot_hal_blocking::gpio_port::{
+    ActivePolarity, GpioError, GpioErrorKind, GpioErrorType, GpioPort, PinConfig, PinDirection,
+    PinMask,
+};
+use openprot_hal_blocking::system_control::{self, ClockControl, ErrorType, ResetControl};
+
+/// Most lines a [`LineMask`] can address.
+pub const MAX_LINES: u32 = 64;
+
+/// Set of line offsets on one chip; bit `n` is offset `n`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct LineMask(pub u64);
+
+impl LineMask {
+    /// Mask selecting the single line `offset`.
+    ///
+    /// # Panics
+    ///
+    /// If `offset` is not below [`MAX_LINES`].
+    pub const fn line(offset: u32) -> Self {
+        assert!(offset < MAX_LINES);
+        Self(1 << offset)
+    }
+
+    /// Offsets of the selected lines, lowest first.
+    pub fn offsets(self) -> impl Iterator<Item = u32> {
+        (0..MAX_LINES).filter(move |offset| self.0 & (1 << offset) != 0)
+    }
+}
+
+impl PinMask for LineMask {
+    fn empty() -> Self {
+        Self(0)
+    }
+
+    fn all() -> Self {
+        Self(u64::MAX)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    fn union(&self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    fn intersection(&self, other: Self) -> Self {
+        Self(self.0 & other.0)
+    }
+
+    fn toggle(&self) -> Self {
+        Self(!self.0)
+    }
+}
+
+/// Error from the Linux GPIO backends.
+#[derive(Debug)]
+pub enum LinuxGpioError {
+    /// A line offset is beyond the chip.
+    InvalidLine(u32),
+    /// The line is not configured for the operation (e.g. driving an input).
+    InvalidMode(u32),
+    /// The same line is in both the set and the reset mask.
+    ConflictingMasks,
+    /// No clock is registered with this ID.
+    ClockNotFound(u32),
+    /// The clock runs at a fixed, different frequency.
+    InvalidFrequency(u64),
+    /// No reset line is registered with this ID.
+    InvalidResetId(u32),
+    /// The chip rejected the request.
+    Io(io::Error),
+}
+
+impl From<io::Error> for LinuxGpioError {
+    fn from(error: io::Error) -> Self {
+        LinuxGpioError::Io(error)
+    }
+}
+
+impl GpioError for LinuxGpioError {
+    fn kind(&self) -> GpioErrorKind {
+        match self {
+            LinuxGpioError::InvalidLine(_)
+            | LinuxGpioError::ClockNotFound(_)
+            | LinuxGpioError::InvalidResetId(_) => GpioErrorKind::InvalidPin,
+            LinuxGpioError::InvalidMode(_) => GpioErrorKind::InvalidMode,
+            LinuxGpioError::ConflictingMasks | LinuxGpioError::InvalidFrequency(_) => {
+                GpioErrorKind::UnsupportedConfiguration
+            }
+            LinuxGpioError::Io(error) => match error.raw_os_error() {
+                Some(libc::EBUSY) => GpioErrorKind::PinInUse,
+                Some(libc::EPERM) | Some(libc::EACCES) => GpioErrorKind::PermissionDenied,
+                _ => GpioErrorKind::HardwareFailure,
+            },
+        }
+    }
+}
+
+impl system_control::Error for LinuxGpioError {
+    fn kind(&self) -> system_control::ErrorKind {
+        match self {
+            LinuxGpioError::ClockNotFound(_) => system_control::ErrorKind::ClockNotFound,
+            LinuxGpioError::InvalidFrequency(_) => system_control::ErrorKind::InvalidClockFrequency,
+            LinuxGpioError::InvalidResetId(_) => system_control::ErrorKind::InvalidResetId,
+            other => match GpioError::kind(other) {
+                GpioErrorKind::PermissionDenied => system_control::ErrorKind::PermissionDenied,
+                _ => system_control::ErrorKind::HardwareFailure,
+            },
+        }
+    }
+}
+
+impl core::fmt::Display for LinuxGpioError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            LinuxGpioError::InvalidLine(offset) => write!(f, "no GPIO line {offset}"),
+            LinuxGpioError::InvalidMode(offset) => {
+                write!(f, "GPIO line {offset} is not configured for this")
+            }
+            LinuxGpioError::ConflictingMasks => write!(f, "line in both set and reset masks"),
+            LinuxGpioError::ClockNotFound(id) => write!(f, "no clock {id}"),
+            LinuxGpioError::InvalidFrequency(hz) => write!(f, "unsupported frequency {hz} Hz"),
+            LinuxGpioError::InvalidResetId(id) => write!(f, "no reset {id}"),
+            LinuxGpioError::Io(error) => write!(f, "GPIO chip: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for LinuxGpioError {}
+
+/// Physical line access on one GPIO chip.
+///
+/// Values are electrical levels; polarity is handled by the caller.
+pub trait GpioLines {
+    /// Number of lines on the chip.
+    fn num_lines(&self) -> u32;
+
+    /// Claim `offset` as an output driven to `level`, releasing any
+    /// previous claim.
+    fn request_output(&mut self, offset: u32, level: bool) -> io::Result<()>;
+
+    /// Claim `offset` as an input, releasing any previous claim.
+    fn request_input(&mut self, offset: u32) -> io::Result<()>;
+
+    /// Drive a claimed output.
+    fn set_level(&mut self, offset: u32, level: bool) -> io::Result<()>;
+
+    /// Sample a claimed line.
+    fn level(&self, offset: u32) -> io::Result<bool>;
+}
+
+/// Lines of a `/dev/gpiochipN` device.
+#[derive(Debug)]
+pub struct CdevLines {
+    chip: Chip,
+    consumer: String,
+    handles: HashMap<u32, LineHandle>,
+}
+
+impl CdevLines {
+    /// Open a GPIO chip; claimed lines show `consumer` as their user.
+    pub fn open(path: impl AsRef<Path>, consumer: &str) -> io::Result<Self> {
+        let chip = Chip::new(path).map_err(cdev_error)?;
+        Ok(Self {
+            chip,
+            consumer: consumer.into(),
+            handles: HashMap::new(),
+        })
+    }
+
+    fn request(&mut self, offset: u32, flags: LineRequestFlags, level: bool) -> io::Result<()> {
+        // Release our own claim first: the kernel refuses a second one.
+        self.handles.remove(&offset);
+        let line = self.chip.get_line(offset).map_err(cdev_error)?;
+        if line.info().map_err(cdev_error)?.is_used() {
+            return Err(io::Error::from_raw_os_error(libc::EBUSY));
+        }
+        let handle = line
+            .request(flags, u8::from(level), &self.consumer)
+            .map_err(cdev_error)?;
+        self.handles.insert(offset, handle);
+        Ok(())
+    }
+
+    fn handle(&self, offset: u32) -> io::Result<&LineHandle> {
+        self.handles
+            .get(&offset)
+            .ok_or_else(|| io::Error::from(io::ErrorKind::NotFound))
+    }
+}
+
+impl GpioLines for CdevLines {
+    fn num_lines(&self) -> u32 {
+        self.chip.num_lines()
+    }
+
+    fn request_output(&mut self, offset: u32, level: bool) -> io::Result<()> {
+        self.request(offset, LineRequestFlags::OUTPUT, level)
+    }
+
+    fn request_input(&mut self, offset: u32) -> io::Result<()> {
+        self.request(offset, LineRequestFlags::INPUT, false)
+    }
+
+    fn set_level(&mut self, offset: u32, level: bool) -> io::Result<()> {
+        self.handle(offset)?
+            .set_value(u8::from(level))
+            .map_err(cdev_error)
+    }
+
+    fn level(&self, offset: u32) -> io::Result<bool> {
+        Ok(self.handle(offset)?.get_value().map_err(cdev_error)? != 0)
+    }
+}
+
+fn cdev_error(error: gpio_cdev::Error) -> io::Error {
+    let io_error = std::error::Error::source(&error)
+        .and_then(|source| source.downcast_ref::<io::Error>())
+        .and_then(io::Error::raw_os_error);
+    match io_error {
+        Some(code) => io::Error::from_raw_os_error(code),
+        None => io::Error::other(error),
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SimLine {
+    Free,
+    Input,
+    Output,
+}
+
+/// In-memory GPIO chip.
+///
+/// Outputs hold the level last driven. Inputs read the level set by
+/// [`SimulatedLines::drive`], which stands in for the outside world.
+#[derive(Clone, Debug)]
+pub struct SimulatedLines {
+    lines: Vec<SimLine>,
+    levels: Vec<bool>,
+}
+
+impl SimulatedLines {
+    /// A chip with `num_lines` free lines, all low.
+    pub fn new(num_lines: u32) -> Self {
+        Self {
+            lines: vec![SimLine::Free; num_lines as usize],
+            levels: vec![false; num_lines as usize],
+        }
+    }
+
+    /// Set the external level seen by input line `offset`.
+    pub fn drive(&mut self, offset: u32, level: bool) {
+        self.levels[offset as usize] = level;
+    }
+
+    /// Current electrical level of `offset`, whatever its direction.
+    pub fn probe(&self, offset: u32) -> bool {
+        self.levels[offset as usize]
+    }
+
+    fn claimed(&self, offset: u32, expected: SimLine) -> io::Result<usize> {
+        match self.lines.get(offset as usize) {
+            Some(line) if *line == expected => Ok(offset as usize),
+            Some(_) => Err(io::Error::from(io::ErrorKind::NotFound)),
+            None => Err(io::Error::from_raw_os_error(libc::EINVAL)),
+        }
+    }
+}
+
+impl GpioLines for SimulatedLines {
+    fn num_lines(&self) -> u32 {
+        self.lines.len() as u32
+    }
+
+    fn request_output(&mut self, offset: u32, level: bool) -> io::Result<()> {
+        let line = self
+            .lines
+            .get_mut(offset as usize)
+            .ok_or(io::Error::from_raw_os_error(libc::EINVAL))?;
+        *line = SimLine::Output;
+        self.levels[offset as usize] = level;
+        Ok(())
+    }
+
+    fn request_input(&mut self, offset: u32) -> io::Result<()> {
+        let line = self
+            .lines
+            .get_mut(offset as usize)
+            .ok_or(io::Error::from_raw_os_error(libc::EINVAL))?;
+        *line = SimLine::Input;
+        Ok(())
+    }
+
+    fn set_level(&mut self, offset: u32, level: bool) -> io::Result<()> {
+        let index = self.claimed(offset, SimLine::Output)?;
+        self.levels[index] = level;
+        Ok(())
+    }
+
+    fn level(&self, offset: u32) -> io::Result<bool> {
+        match self.lines.get(offset as usize) {
+            Some(SimLine::Free) => Err(io::Error::from(io::ErrorKind::NotFound)),
+            Some(_) => Ok(self.levels[offset as usize]),
+            None => Err(io::Error::from_raw_os_error(libc::EINVAL)),
+        }
+    }
+}
+
+/// `GpioPort` over the lines of one chip.
+#[derive(Debug)]
+pub struct LinuxGpioPort<L: GpioLines = CdevLines> {
+    lines: L,
+    /// Lines configured as inputs.
+    inputs: LineMask,
+    /// Lines configured as outputs.
+    outputs: LineMask,
+    /// Configured lines that are active-low.
+    active_low: LineMask,
+    /// Logical level last driven on each output.
+    driven: LineMask,
+}
+
+impl<L: GpioLines> LinuxGpioPort<L> {
+    /// Port on `lines`, with no line configured yet.
+    pub fn new(lines: L) -> Self {
+        Self {
+            lines,
+            inputs: LineMask(0),
+            outputs: LineMask(0),
+            active_low: LineMask(0),
+            driven: LineMask(0),
+        }
+    }
+
+    /// The underlying lines.
+    pub fn lines(&self) -> &L {
+        &self.lines
+    }
+
+    /// The underlying lines, mutably.
+    pub fn lines_mut(&mut self) -> &mut L {
+        &mut self.lines
+    }
+
+    /// Give back the underlying lines.
+    pub fn into_inner(self) -> L {
+        self.lines
+    }
+
+    fn check_lines(&self, pins: LineMask) -> Result<(), LinuxGpioError> {
+        let num_lines = self.lines.num_lines().min(MAX_LINES);
+        match pins.offsets().find(|offset| *offset >= num_lines) {
+            Some(offset) => Err(LinuxGpioError::InvalidLine(offset)),
+            None => Ok(()),
+        }
+    }
+
+    fn check_outputs(&self, pins: LineMask) -> Result<(), LinuxGpioError> {
+        self.check_lines(pins)?;
+        match LineMask(pins.0 & !self.outputs.0).offsets().next() {
+            Some(offset) => Err(LinuxGpioError::InvalidMode(offset)),
+            None => Ok(()),
+        }
+    }
+
+    /// Drive `pins` to the logical levels in `levels`.
+    fn drive(&mut self, pins: LineMask, levels: LineMask) -> Result<(), LinuxGpioError> {
+        for offset in pins.offsets() {
+            let bit = 1 << offset;
+            let logical = levels.0 & bit != 0;
+            let electrical = logical != (self.active_low.0 & bit != 0);
+            self.lines.set_level(offset, electrical)?;
+            self.driven.0 = (self.driven.0 & !bit) | (levels.0 & bit);
+        }
+        Ok(())
+    }
+}
+
+impl<L: GpioLines> GpioErrorType for LinuxGpioPort<L> {
+    type Error = LinuxGpioError;
+}
+
+impl<L: GpioLines> GpioPort for LinuxGpioPort<L> {
+    type Config = PinConfig;
+    type Mask = LineMask;
+
+    fn configure(&mut self, pins: LineMask, config: PinConfig) -> Result<(), LinuxGpioError> {
+        self.check_lines(pins)?;
+        let active_low = config.polarity == ActivePolarity::ActiveLow;
+        for offset in pins.offsets() {
+            let bit = 1 << offset;
+            match config.direction {
+                PinDirection::Output => {
+                    let logical = config.initial_output.unwrap_or(false);
+                    self.lines.request_output(offset, logical != active_low)?;
+                    self.outputs.0 |= bit;
+                    self.inputs.0 &= !bit;
+                    if logical {
+                        self.driven.0 |= bit;
+                    } else {
+                        self.driven.0 &= !bit;
+                    }
+                }
+                PinDirection::Input => {
+                    self.lines.request_input(offset)?;
+                    self.inputs.0 |= bit;
+                    self.outputs.0 &= !bit;
+                    self.driven.0 &= !bit;
+                }
+            }
+            if active_low {
+                self.active_low.0 |= bit;
+            } else {
+                self.active_low.0 &= !bit;
+            }
+        }
+        Ok(())
+    }
+
+    fn set_reset(
+        &mut self,
+        set_mask: LineMask,
+        reset_mask: LineMask,
+    ) -> Result<(), LinuxGpioError> {
+        if !set_mask.intersection(reset_mask).is_empty() {
+            return Err(LinuxGpioError::ConflictingMasks);
+        }
+        let pins = set_mask.union(reset_mask);
+        self.check_outputs(pins)?;
+        self.drive(pins, set_mask)
+    }
+
+    /// Logical level of every configured line; unconfigured lines read 0.
+    fn read_input(&self) -> Result<LineMask, LinuxGpioError> {
+        let configured = self.inputs.union(self.outputs);
+        let mut levels = 0;
+        for offset in configured.offsets() {
+            let bit = 1 << offset;
+            let electrical = self.lines.level(offset)?;
+            if electrical != (self.active_low.0 & bit != 0) {
+                levels |= bit;
+            }
+        }
+        Ok(LineMask(levels))
+    }
+
+    fn toggle(&mut self, pins: LineMask) -> Result<(), LinuxGpioError> {
+        self.check_outputs(pins)?;
+        let levels = LineMask(!self.driven.0);
+        self.drive(pins, levels)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct GpioClock {
+    line: u32,
+    frequency_hz: u64,
+}
+
+/// `SystemControl` for resets and clock gates wired to GPIO lines.
+#[derive(Debug)]
+pub struct GpioSystemControl<L: GpioLines = CdevLines> {
+    port: LinuxGpioPort<L>,
+    clocks: Vec<GpioClock>,
+    resets: LineMask,
+}
+
+impl<L: GpioLines> GpioSystemControl<L> {
+    /// Controller with no clocks or resets registered.
+    pub fn new(lines: L) -> Self {
+        Self {
+            port: LinuxGpioPort::new(lines),
+            clocks: Vec::new(),
+            resets: LineMask(0),
+        }
+    }
+
+    /// Register `line` as a clock gate for an oscillator at `frequency_hz`.
+    /// The clock starts disabled.
+    pub fn with_clock(
+        mut self,
+        line: u32,
+        polarity: ActivePolarity,
+        frequency_hz: u64,
+    ) -> Result<Self, LinuxGpioError> {
+        self.claim_output(line, polarity)?;
+        self.clocks.retain(|clock| clock.line != line);
+        self.clocks.push(GpioClock { line, frequency_hz });
+        Ok(self)
+    }
+
+    /// Register `line` as a reset. The reset starts deasserted.
+    pub fn with_reset(
+        mut self,
+        line: u32,
+        polarity: ActivePolarity,
+    ) -> Result<Self, LinuxGpioError> {
+        self.claim_output(line, polarity)?;
+        self.resets.0 |= 1 << line;
+        Ok(self)
+    }
+
+    /// The GPIO port holding the clock and reset lines.
+    pub fn port(&self) -> &LinuxGpioPort<L> {
+        &self.port
+    }
+
+    fn claim_output(&mut self, line: u32, polarity: ActivePolarity) -> Result<(), LinuxGpioError> {
+        if line >= MAX_LINES {
+            return Err(LinuxGpioError::InvalidLine(line));
+        }
+        let config = PinConfig {
+            direction: PinDirection::Output,
+            polarity,
+            initial_output: Some(false),
+        };
+        self.port.configure(LineMask::line(line), config)
+    }
+
+    fn clock(&self, id: u32) -> Result<GpioClock, LinuxGpioError> {
+        self.clocks
+            .iter()
+            .find(|clock| clock.line == id)
+            .copied()
+            .ok_or(LinuxGpioError::ClockNotFound(id))
+    }
+
+    fn reset(&self, id: u32) -> Result<LineMask, LinuxGpioError> {
+        if id < MAX_LINES && self.resets.contains(LineMask::line(id)) {
+            Ok(LineMask::line(id))
+        } else {
+            Err(LinuxGpioError::InvalidResetId(id))
+        }
+    }
+
+    fn set_line(&mut self, line: LineMask, active: bool) -> Result<(), LinuxGpioError> {
+        if active {
+            self.port.set_reset(line, LineMask(0))
+        } else {
+            self.port.set_reset(LineMask(0), line)
+        }
+    }
+
+    fn is_active(&self, line: LineMask) -> Result<bool, LinuxGpioError> {
+        Ok(self.port.read_input()?.contains(line))
+    }
+}
+
+impl<L: GpioLines> ErrorType for GpioSystemControl<L> {
+    type Error = LinuxGpioError;
+}
+
+impl<L: GpioLines> ClockControl for GpioSystemControl<L> {
+    type ClockId = u32;
+    /// Gated oscillators have nothing to configure.
+    type ClockConfig = ();
+
+    fn enable(&mut self, clock_id: &u32) -> Result<(), LinuxGpioError> {
+        let clock = self.clock(*clock_id)?;
+        self.set_line(LineMask::line(clock.line), true)
+    }
+
+    fn disable(&mut self, clock_id: &u32) -> Result<(), LinuxGpioError> {
+        let clock = self.clock(*clock_id)?;
+        self.set_line(LineMask::line(clock.line), false)
+    }
+
+    /// Only the oscillator's own frequency is accepted.
+    fn set_frequency(&mut self, clock_id: &u32, frequency_hz: u64) -> Result<(), LinuxGpioError> {
+        let clock = self.clock(*clock_id)?;
+        if frequency_hz != clock.frequency_hz {
+            return Err(LinuxGpioError::InvalidFrequency(frequency_hz));
+        }
+        Ok(())
+    }
+
+    fn get_frequency(&self, clock_id: &u32) -> Result<u64, LinuxGpioError> {
+        Ok(self.clock(*clock_id)?.frequency_hz)
+    }
+
+    fn configure(&mut self, clock_id: &u32, _config: ()) -> Result<(), LinuxGpioError> {
+        self.clock(*clock_id).map(|_| ())
+    }
+
+    fn get_config(&self, clock_id: &u32) -> Result<(), LinuxGpioError> {
+        self.clock(*clock_id).map(|_| ())
+    }
+}
+
+impl<L: GpioLines> ResetControl for GpioSystemControl<L> {
+    type ResetId = u32;
+
+    fn reset_assert(&mut self, reset_id: &u32) -> Result<(), LinuxGpioError> {
+        let line = self.reset(*reset_id)?;
+        self.set_line(line, true)
+    }
+
+    fn reset_deassert(&mut self, reset_id: &u32) -> Result<(), LinuxGpioError> {
+        let line = self.reset(*reset_id)?;
+        self.set_line(line, false)
+    }
+
+    fn reset_pulse(&mut self, reset_id: &u32, duration: Duration) -> Result<(), LinuxGpioError> {
+        let line = self.reset(*reset_id)?;
+        self.set_line(line, true)?;
+        std::thread::sleep(duration);
+        self.set_line(line, false)
+    }
+
+    fn reset_is_asserted(&self, reset_id: &u32) -> Result<bool, LinuxGpioError> {
+        let line = self.reset(*reset_id)?;
+        self.is_active(line)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gpio_kind(error: LinuxGpioError) -> GpioErrorKind {
+        GpioError::kind(&error)
+    }
+
+    fn sysctl_kind(error: LinuxGpioError) -> system_control::ErrorKind {
+        system_control::Error::kind(&error)
+    }
+
+    fn port() -> LinuxGpioPort<SimulatedLines> {
+        LinuxGpioPort::new(SimulatedLines::new(8))
+    }
+
+    #[test]
+    fn line_mask_set_operations() {
+        let a = LineMask::line(0).union(LineMask::line(5));
+        assert!(a.contains(LineMask::line(5)));
+        assert!(!a.contains(LineMask::line(1)));
+        assert_eq!(a.intersection(LineMask::line(5)), LineMask::line(5));
+        assert_eq!(a.offsets().collect::<Vec<_>>(), [0, 5]);
+        assert!(LineMask::empty().is_empty());
+        assert_eq!(LineMask::all().toggle(), LineMask::empty());
+    }
+
+    #[test]
+    fn outputs_apply_polarity() {
+        let mut port = port();
+        let high = LineMask::line(1);
+        let low = LineMask::line(2);
+        port.configure(high, PinConfig::output_active_high(true))
+            .unwrap();
+        port.configure(low, PinConfig::output_active_low(false))
+            .unwrap();
+        assert!(port.lines().probe(1));
+        // Inactive active-low output sits high.
+        assert!(port.lines().probe(2));
+
+        port.set_reset(low, high).unwrap();
+        assert!(!port.lines().probe(1));
+        assert!(!port.lines().probe(2));
+        assert_eq!(port.read_input().unwrap(), low);
+
+        port.toggle(high.union(low)).unwrap();
+        assert_eq!(port.read_input().unwrap(), high);
+        assert!(port.lines().probe(1));
+        assert!(port.lines().probe(2));
+    }
+
+    #[test]
+    fn inputs_read_external_levels() {
+        let mut port = port();
+        let pins = LineMask::line(3).union(LineMask::line(4));
+        port.configure(
+            LineMask::line(3),
+            PinConfig::input(ActivePolarity::ActiveHigh),
+        )
+        .unwrap();
+        port.configure(
+            LineMask::line(4),
+            PinConfig::input(ActivePolarity::ActiveLow),
+        )
+        .unwrap();
+        assert_eq!(port.read_input().unwrap(), LineMask::line(4));
+
+        port.lines_mut().drive(3, true);
+        port.lines_mut().drive(4, true);
+        assert_eq!(port.read_input().unwrap(), LineMask::line(3));
+
+        assert_eq!(
+            gpio_kind(port.toggle(pins).unwrap_err()),
+            GpioErrorKind::InvalidMode
+        );
+    }
+
+    #[test]
+    fn invalid_requests_are_rejected() {
+        let mut port = port();
+        let err = port
+            .configure(
+                LineMask::line(8),
+                PinConfig::input(ActivePolarity::ActiveHigh),
+            )
+            .unwrap_err();
+        assert_eq!(gpio_kind(err), GpioErrorKind::InvalidPin);
+        let pin = LineMask::line(0);
+        port.configure(pin, PinConfig::output_active_high(false))
+            .unwrap();
+        assert_eq!(
+            gpio_kind(port.set_reset(pin, pin).unwrap_err()),
+            GpioErrorKind::UnsupportedConfiguration
+        );
+    }
+
+    #[test]
+    fn missing_chip_is_an_io_error() {
+        let err = CdevLines::open("/dev/gpiochip-does-not-exist", "test").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn system_control_drives_reset_and_clock_lines() {
+        const RESET: u32 = 0;
+        const CLOCK: u32 = 1;
+        let mut sysctl = GpioSystemControl::new(SimulatedLines::new(4))
+            .with_reset(RESET, ActivePolarity::ActiveLow)
+            .unwrap()
+            .with_clock(CLOCK, ActivePolarity::ActiveHigh, 25_000_000)
+            .unwrap();
+        // Deasserted active-low reset is high; disabled clock gate is low.
+        assert!(sysctl.port().lines().probe(RESET));
+        assert!(!sysctl.port().lines().probe(CLOCK));
+
+        sysctl.reset_assert(&RESET).unwrap();
+        assert!(sysctl.reset_is_asserted(&RESET).unwrap());
+        assert!(!sysctl.port().lines().probe(RESET));
+        sysctl
+            .reset_pulse(&RESET, Duration::from_micros(10))
+            .unwrap();
+        assert!(!sysctl.reset_is_asserted(&RESET).unwrap());
+
+        sysctl.enable(&CLOCK).unwrap();
+        assert!(sysctl.port().lines().probe(CLOCK));
+        assert_eq!(sysctl.get_frequency(&CLOCK).unwrap(), 25_000_000);
+        sysctl.set_frequency(&CLOCK, 25_000_000).unwrap();
+        assert_eq!(
+            sysctl_kind(sysctl.set_frequency(&CLOCK, 1).unwrap_err()),
+            system_control::ErrorKind::InvalidClockFrequency
+        );
+        sysctl.disable(&CLOCK).unwrap();
+        assert!(!sysctl.port().lines().probe(CLOCK));
+
+        assert_eq!(
+            sysctl_kind(sysctl.reset_assert(&CLOCK).unwrap_err()),
+            system_control::ErrorKind::InvalidResetId
+        );
+        assert_eq!(
+            sysctl_kind(sysctl.enable(&RESET).unwrap_err()),
+            system_control::ErrorKind::ClockNotFound
+        );
+    }
+}
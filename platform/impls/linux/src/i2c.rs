@@ -0,0 +1,432 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! I2C controllers for Linux
+//!
+//! - [`LinuxI2c`] drives a real bus through the i2c-dev character device
+//!   (`/dev/i2c-N`). Every `embedded_hal` transaction becomes a single
+//!   `I2C_RDWR` ioctl, so the kernel issues it as one bus transaction with
+//!   repeated starts between direction changes.
+//! - [`FileI2c`] is a pure-file stand-in: a single target at a fixed address
+//!   whose register space is a regular file, addressed like a small EEPROM
+//!   (the first byte written sets the register pointer). It needs no kernel
+//!   support and no privileges, which makes it suitable for tests and for
+//!   the PRoT emulator.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use embedded_hal::i2c::I2c;
+//! use openprot_platform_linux::i2c::LinuxI2c;
+//!
+//! let mut bus = LinuxI2c::open("/dev/i2c-1")?;
+//! let mut id = [0u8; 2];
+//! bus.write_read(0x50, &[0x00], &mut id)?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::os::fd::AsRawFd;
+use std::path::Path;
+
+use embedded_hal::i2c::{
+    ErrorKind, ErrorType, I2c, NoAcknowledgeSource, Operation, SevenBitAddress,
+};
+
+/// `I2C_RDWR` request from `<linux/i2c-dev.h>`.
+const I2C_RDWR: libc::c_ulong = 0x0707;
+/// `i2c_msg` flag: this message reads from the target.
+const I2C_M_RD: u16 = 0x0001;
+/// Kernel limit on messages per `I2C_RDWR` (`I2C_RDWR_IOCTL_MAX_MSGS`).
+///
+/// Each run of adjacent operations in the same direction is one message.
+pub const MAX_OPERATIONS: usize = 42;
+
+/// `struct i2c_msg` from `<linux/i2c.h>`.
+#[repr(C)]
+struct I2cMsg {
+    addr: u16,
+    flags: u16,
+    len: u16,
+    buf: *mut u8,
+}
+
+/// `struct i2c_rdwr_ioctl_data` from `<linux/i2c-dev.h>`.
+#[repr(C)]
+struct I2cRdwrData {
+    msgs: *mut I2cMsg,
+    nmsgs: u32,
+}
+
+/// Error from a Linux I2C controller.
+#[derive(Debug)]
+pub enum I2cError {
+    /// The target did not acknowledge.
+    NoAcknowledge(NoAcknowledgeSource),
+    /// The transaction changes direction more often than one `I2C_RDWR` can
+    /// carry, or a run of same-direction operations is longer than 65535 bytes.
+    TooLarge,
+    /// The device file or ioctl failed.
+    Io(io::Error),
+}
+
+impl From<io::Error> for I2cError {
+    fn from(error: io::Error) -> Self {
+        match error.raw_os_error() {
+            // i2c-dev adapters report a missing ACK as ENXIO or EREMOTEIO.
+            Some(libc::ENXIO) | Some(libc::EREMOTEIO) => {
+                I2cError::NoAcknowledge(NoAcknowledgeSource::Unknown)
+            }
+            _ => I2cError::Io(error),
+        }
+    }
+}
+
+impl embedded_hal::i2c::Error for I2cError {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            I2cError::NoAcknowledge(source) => ErrorKind::NoAcknowledge(*source),
+            I2cError::TooLarge => ErrorKind::Other,
+            I2cError::Io(error) => match error.raw_os_error() {
+                Some(libc::EAGAIN) => ErrorKind::ArbitrationLoss,
+                Some(libc::EIO) => ErrorKind::Bus,
+                _ => ErrorKind::Other,
+            },
+        }
+    }
+}
+
+impl core::fmt::Display for I2cError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            I2cError::NoAcknowledge(source) => write!(f, "no acknowledge: {source}"),
+            I2cError::TooLarge => write!(f, "transaction too large for I2C_RDWR"),
+            I2cError::Io(error) => write!(f, "i2c-dev: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for I2cError {}
+
+/// I2C controller backed by a Linux i2c-dev node.
+#[derive(Debug)]
+pub struct LinuxI2c {
+    device: File,
+}
+
+impl LinuxI2c {
+    /// Open an i2c-dev node such as `/dev/i2c-1`.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let device = OpenOptions::new().read(true).write(true).open(path)?;
+        Ok(Self { device })
+    }
+}
+
+impl ErrorType for LinuxI2c {
+    type Error = I2cError;
+}
+
+impl I2c<SevenBitAddress> for LinuxI2c {
+    fn transaction(
+        &mut self,
+        address: SevenBitAddress,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        if operations.is_empty() {
+            return Ok(());
+        }
+        let mut segments = segments(operations)?;
+        rdwr(&self.device, i2c_msgs(address, &mut segments)?)?;
+        scatter_reads(&segments, operations);
+        Ok(())
+    }
+}
+
+/// A run of adjacent operations in the same direction, sent as one message.
+struct Segment {
+    read: bool,
+    buf: Vec<u8>,
+}
+
+/// Coalesce a transaction into one buffer per direction change.
+///
+/// `embedded_hal` requires adjacent operations in the same direction to be
+/// joined without a repeated start. `I2C_M_NOSTART` would express that, but
+/// few adapters advertise `I2C_FUNC_NOSTART`, so the operations are merged
+/// into a single message instead.
+fn segments(operations: &[Operation<'_>]) -> Result<Vec<Segment>, I2cError> {
+    let mut segments: Vec<Segment> = Vec::new();
+    for operation in operations {
+        let read = matches!(operation, Operation::Read(_));
+        let segment = match segments.last_mut() {
+            Some(segment) if segment.read == read => segment,
+            _ => {
+                segments.push(Segment {
+                    read,
+                    buf: Vec::new(),
+                });
+                let last = segments.len() - 1;
+                &mut segments[last]
+            }
+        };
+        match operation {
+            Operation::Read(buf) => segment.buf.resize(segment.buf.len() + buf.len(), 0),
+            Operation::Write(data) => segment.buf.extend_from_slice(data),
+        }
+    }
+    if segments.len() > MAX_OPERATIONS {
+        return Err(I2cError::TooLarge);
+    }
+    Ok(segments)
+}
+
+/// Build the `i2c_msg` array for a transaction's segments.
+fn i2c_msgs(address: SevenBitAddress, segments: &mut [Segment]) -> Result<Vec<I2cMsg>, I2cError> {
+    segments
+        .iter_mut()
+        .map(|segment| {
+            Ok(I2cMsg {
+                addr: u16::from(address),
+                flags: if segment.read { I2C_M_RD } else { 0 },
+                len: u16::try_from(segment.buf.len()).map_err(|_| I2cError::TooLarge)?,
+                buf: segment.buf.as_mut_ptr(),
+            })
+        })
+        .collect()
+}
+
+/// Copy the bytes read into the segments back to the read operations.
+fn scatter_reads(segments: &[Segment], operations: &mut [Operation<'_>]) {
+    let mut bytes = segments
+        .iter()
+        .filter(|segment| segment.read)
+        .flat_map(|segment| segment.buf.iter().copied());
+    for operation in operations {
+        if let Operation::Read(buf) = operation {
+            for (dest, byte) in buf.iter_mut().zip(&mut bytes) {
+                *dest = byte;
+            }
+        }
+    }
+}
+
+fn rdwr(device: &File, mut msgs: Vec<I2cMsg>) -> Result<(), I2cError> {
+    let mut data = I2cRdwrData {
+        msgs: msgs.as_mut_ptr(),
+        nmsgs: msgs.len() as u32,
+    };
+    // SAFETY: `data` points at `msgs`, whose buffers borrow the caller's
+    // segments; all of them outlive the ioctl.
+    let rc = unsafe { libc::ioctl(device.as_raw_fd(), I2C_RDWR, &mut data) };
+    if rc < 0 {
+        return Err(io::Error::last_os_error().into());
+    }
+    Ok(())
+}
+
+/// Pure-file I2C target: one device at `address` whose registers are the
+/// bytes of `file`.
+///
+/// Within a transaction, the first byte of the first write sets the register
+/// pointer; further written bytes are stored at the pointer and reads return
+/// bytes from it, both advancing it. Reads past the end of the file return
+/// `0xFF`, like an erased EEPROM. Any other address is not acknowledged.
+#[derive(Debug)]
+pub struct FileI2c {
+    file: File,
+    address: SevenBitAddress,
+    pointer: u64,
+}
+
+impl FileI2c {
+    /// Serve `file` as the register space of a target at `address`.
+    pub fn new(file: File, address: SevenBitAddress) -> Self {
+        Self {
+            file,
+            address,
+            pointer: 0,
+        }
+    }
+
+    /// Open (creating if needed) the file at `path` as the register space.
+    pub fn open(path: impl AsRef<Path>, address: SevenBitAddress) -> io::Result<Self> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?;
+        Ok(Self::new(file, address))
+    }
+
+    /// Give back the backing file.
+    pub fn into_inner(self) -> File {
+        self.file
+    }
+
+    fn read_at_pointer(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        self.file.seek(SeekFrom::Start(self.pointer))?;
+        let mut filled = 0;
+        while filled < buf.len() {
+            match self.file.read(&mut buf[filled..])? {
+                0 => break,
+                n => filled += n,
+            }
+        }
+        buf[filled..].fill(0xFF);
+        self.pointer += buf.len() as u64;
+        Ok(())
+    }
+
+    fn write_at_pointer(&mut self, data: &[u8]) -> io::Result<()> {
+        self.file.seek(SeekFrom::Start(self.pointer))?;
+        self.file.write_all(data)?;
+        self.pointer += data.len() as u64;
+        Ok(())
+    }
+}
+
+impl ErrorType for FileI2c {
+    type Error = I2cError;
+}
+
+impl I2c<SevenBitAddress> for FileI2c {
+    fn transaction(
+        &mut self,
+        address: SevenBitAddress,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        if address != self.address {
+            return Err(I2cError::NoAcknowledge(NoAcknowledgeSource::Address));
+        }
+        let mut pointer_set = false;
+        for operation in operations {
+            match operation {
+                Operation::Write(data) => {
+                    let data = if pointer_set {
+                        &data[..]
+                    } else if let Some((&pointer, rest)) = data.split_first() {
+                        self.pointer = u64::from(pointer);
+                        pointer_set = true;
+                        rest
+                    } else {
+                        continue;
+                    };
+                    self.write_at_pointer(data)?;
+                }
+                Operation::Read(buf) => self.read_at_pointer(buf)?,
+            }
+        }
+        self.file.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::scratch_path;
+    use embedded_hal::i2c::Error;
+
+    #[test]
+    fn file_target_writes_and_reads_registers() {
+        let path = scratch_path("i2c-eeprom");
+        let mut target = FileI2c::open(&path, 0x50).unwrap();
+
+        target.write(0x50, &[0x10, 0xde, 0xad, 0xbe, 0xef]).unwrap();
+        let mut out = [0u8; 4];
+        target.write_read(0x50, &[0x10], &mut out).unwrap();
+        assert_eq!(out, [0xde, 0xad, 0xbe, 0xef]);
+
+        // Sequential read continues from the pointer.
+        let mut next = [0u8; 2];
+        target.write_read(0x50, &[0x0e], &mut next).unwrap();
+        target.read(0x50, &mut next).unwrap();
+        assert_eq!(next, [0xde, 0xad]);
+
+        // Unwritten space reads as erased.
+        target.write_read(0x50, &[0x40], &mut next).unwrap();
+        assert_eq!(next, [0xff, 0xff]);
+
+        // Contents persist in the file.
+        drop(target);
+        let bytes = std::fs::read(&path).unwrap();
+        assert_eq!(bytes[0x10..0x14], [0xde, 0xad, 0xbe, 0xef]);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn file_target_nacks_other_addresses() {
+        let path = scratch_path("i2c-nack");
+        let mut target = FileI2c::open(&path, 0x50).unwrap();
+        let err = target.write(0x51, &[0x00]).unwrap_err();
+        assert_eq!(
+            err.kind(),
+            ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address)
+        );
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn messages_merge_same_direction_operations() {
+        let (a, b, mut c) = ([1u8], [2u8, 3], [0u8; 3]);
+        let operations = [
+            Operation::Write(&a),
+            Operation::Write(&b),
+            Operation::Read(&mut c),
+        ];
+        let mut segments = segments(&operations).unwrap();
+        assert_eq!(segments[0].buf, [1, 2, 3]);
+        let msgs = i2c_msgs(0x42, &mut segments).unwrap();
+        let flags: Vec<u16> = msgs.iter().map(|m| m.flags).collect();
+        assert_eq!(flags, [0, I2C_M_RD]);
+        assert!(msgs.iter().all(|m| m.addr == 0x42));
+        assert_eq!(msgs[0].len, 3);
+        assert_eq!(msgs[1].len, 3);
+    }
+
+    #[test]
+    fn merged_reads_are_scattered_back() {
+        let (mut a, mut b) = ([0u8; 2], [0u8; 3]);
+        let mut operations = [Operation::Read(&mut a), Operation::Read(&mut b)];
+        let mut segments = segments(&operations).unwrap();
+        assert_eq!(segments.len(), 1);
+        segments[0].buf.copy_from_slice(&[1, 2, 3, 4, 5]);
+        scatter_reads(&segments, &mut operations);
+        assert_eq!(a, [1, 2]);
+        assert_eq!(b, [3, 4, 5]);
+    }
+
+    #[test]
+    fn oversized_transactions_are_rejected() {
+        let mut reads = [[0u8; 1]; MAX_OPERATIONS / 2 + 1];
+        let writes = [[0u8; 1]; MAX_OPERATIONS / 2];
+        let mut operations: Vec<Operation<'_>> = Vec::new();
+        let mut reads = reads.iter_mut();
+        for write in &writes {
+            operations.extend(reads.next().map(|r| Operation::Read(&mut r[..])));
+            operations.push(Operation::Write(&write[..]));
+        }
+        operations.extend(reads.next().map(|r| Operation::Read(&mut r[..])));
+        assert!(matches!(segments(&operations), Err(I2cError::TooLarge)));
+
+        let long = vec![0u8; usize::from(u16::MAX) + 1];
+        let mut segments = segments(&[Operation::Write(&long)]).unwrap();
+        assert!(matches!(
+            i2c_msgs(0x42, &mut segments),
+            Err(I2cError::TooLarge)
+        ));
+    }
+
+    #[test]
+    fn i2c_dev_ioctl_on_a_regular_file_fails_cleanly() {
+        let path = scratch_path("i2c-notadev");
+        std::fs::write(&path, []).unwrap();
+        let mut bus = LinuxI2c::open(&path).unwrap();
+        let err = bus.write(0x50, &[0x00]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Other);
+        assert!(matches!(err, I2cError::Io(_)));
+        std::fs::remove_file(path).unwrap();
+    }
+}
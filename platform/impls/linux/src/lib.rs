@@ -3,4 +3,33 @@
 
 //! Linux platform implementation for OpenPRoT
 //!
-//! This crate provides Linux-specific implementations of platform abstraction traits.
+//! This crate provides Linux-specific implementations of platform abstraction traits,
+//! for integration tests and the PRoT emulator running as ordinary processes.
+//!
+//! | Module | Implements | Linux interface | Local stand-in |
+//! |--------|------------|-----------------|----------------|
+//! | [`i2c`] | `embedded_hal::i2c::I2c` | i2c-dev (`/dev/i2c-N`, `I2C_RDWR`) | [`i2c::FileI2c`], a register file |
+//! | [`usart`] | `UsartBackend` | tty / serial port (termios) | [`usart::LinuxUsart::pty_pair`] |
+//! | [`mctp`] | `MctpClient` | `AF_MCTP` sockets | [`mctp::LocalNetwork`] |
+//! | [`gpio`] | `GpioPort`, `SystemControl` | GPIO character device (`gpio-cdev`) | [`gpio::SimulatedLines`] |
+//!
+//! Each backend works against its stand-in without privileges or special
+//! kernel support, so the crate's tests run on any Linux host.
+
+pub mod gpio;
+pub mod i2c;
+pub mod mctp;
+pub mod usart;
+
+#[cfg(test)]
+mod test_util {
+    use std::path::PathBuf;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// A path in the temp directory no other test uses.
+    pub fn scratch_path(name: &str) -> PathBuf {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+        let n = NEXT.fetch_add(1, Ordering::Relaxed);
+        std::env::temp_dir().join(format!("openprot-{name}-{}-{n}", std::process::id()))
+    }
+}
@@ -0,0 +1,788 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! MCTP client over Linux `AF_MCTP` sockets
+//!
+//! [`LinuxMctp`] implements `openprot_mctp_api::MctpClient` on the kernel's
+//! MCTP stack (Linux 5.15+, `CONFIG_MCTP`). Each listener or request handle
+//! is one datagram socket:
+//!
+//! - a listener socket is bound to its message type, so the kernel delivers
+//!   matching requests to it;
+//! - a request socket sends with the tag-owner bit set, so the kernel
+//!   allocates a tag and routes the response back to the same socket;
+//! - responses go out through a shared unbound socket with the request's
+//!   tag and the tag-owner bit clear.
+//!
+//! Endpoint IDs belong to the kernel's interfaces (`mctp addr add`), so
+//! [`MctpClient::set_eid`] cannot assign one. It records the local EID that
+//! new listeners bind to; the default, 0, binds to any local EID.
+//!
+//! When the kernel has no `AF_MCTP` support, [`LinuxMctp::new`] still
+//! succeeds but returns a disabled client: [`LinuxMctp::is_enabled`] is
+//! `false` and every operation fails with `ResponseCode::InternalError`.
+//!
+//! The socket layer is behind [`MctpNetwork`]. [`KernelMctp`] is the real
+//! one; [`LocalNetwork`] is an in-process stand-in with the same tag routing
+//! for tests and the PRoT emulator.
+
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, VecDeque};
+use std::io;
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+use std::rc::Rc;
+use std::time::Duration;
+
+use openprot_mctp_api::{Handle, MctpClient, MctpError, RecvMetadata, ResponseCode};
+
+/// Address family number of MCTP (`AF_MCTP` in `<linux/socket.h>`).
+pub const AF_MCTP: libc::c_int = 45;
+/// Any MCTP network.
+pub const MCTP_NET_ANY: u32 = 0;
+/// Any (bind) or broadcast (send) endpoint ID.
+pub const MCTP_ADDR_ANY: u8 = 0xff;
+/// Tag-owner bit of `smctp_tag`.
+pub const MCTP_TAG_OWNER: u8 = 0x08;
+/// Tag value bits of `smctp_tag`.
+pub const MCTP_TAG_MASK: u8 = 0x07;
+/// Integrity-check bit of the message type byte.
+const MCTP_TYPE_IC: u8 = 0x80;
+
+/// `struct sockaddr_mctp` from `<linux/mctp.h>`.
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+struct SockaddrMctp {
+    smctp_family: libc::sa_family_t,
+    smctp_pad0: u16,
+    smctp_network: u32,
+    smctp_addr: u8,
+    smctp_type: u8,
+    smctp_tag: u8,
+    smctp_pad1: u8,
+}
+
+/// An MCTP socket address: network, endpoint, message type and tag.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MctpAddr {
+    /// Network ID, or [`MCTP_NET_ANY`].
+    pub network: u32,
+    /// Endpoint ID.
+    pub eid: u8,
+    /// Message type byte, including the integrity-check bit.
+    pub msg_type: u8,
+    /// Tag value plus [`MCTP_TAG_OWNER`] for requests.
+    pub tag: u8,
+}
+
+impl From<MctpAddr> for SockaddrMctp {
+    fn from(addr: MctpAddr) -> Self {
+        SockaddrMctp {
+            smctp_family: AF_MCTP as libc::sa_family_t,
+            smctp_network: addr.network,
+            smctp_addr: addr.eid,
+            smctp_type: addr.msg_type,
+            smctp_tag: addr.tag,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<SockaddrMctp> for MctpAddr {
+    fn from(addr: SockaddrMctp) -> Self {
+        MctpAddr {
+            network: addr.smctp_network,
+            eid: addr.smctp_addr,
+            msg_type: addr.smctp_type,
+            tag: addr.smctp_tag,
+        }
+    }
+}
+
+/// A datagram socket carrying whole MCTP messages.
+pub trait MctpSocket {
+    /// Receive messages of `addr.msg_type` sent to `addr.eid`.
+    fn bind(&self, addr: &MctpAddr) -> io::Result<()>;
+
+    /// Send one message to `addr`.
+    fn send_to(&self, buf: &[u8], addr: &MctpAddr) -> io::Result<()>;
+
+    /// Receive one message, waiting at most `timeout` (`None` waits forever).
+    ///
+    /// Returns the message length and its source address. A message longer
+    /// than `buf` is an error of kind `InvalidInput`.
+    fn recv_from(&self, buf: &mut [u8], timeout: Option<Duration>)
+        -> io::Result<(usize, MctpAddr)>;
+}
+
+/// A source of MCTP sockets.
+pub trait MctpNetwork {
+    /// The socket type.
+    type Socket: MctpSocket;
+
+    /// Open a new, unbound socket.
+    fn socket(&self) -> io::Result<Self::Socket>;
+}
+
+/// The kernel MCTP stack.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct KernelMctp;
+
+impl MctpNetwork for KernelMctp {
+    type Socket = AfMctpSocket;
+
+    fn socket(&self) -> io::Result<AfMctpSocket> {
+        // SAFETY: plain socket(2) call; the result is checked below.
+        let fd = unsafe { libc::socket(AF_MCTP, libc::SOCK_DGRAM | libc::SOCK_CLOEXEC, 0) };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        // SAFETY: `fd` is a freshly created descriptor owned by nobody else.
+        Ok(AfMctpSocket(unsafe { OwnedFd::from_raw_fd(fd) }))
+    }
+}
+
+/// An `AF_MCTP` datagram socket.
+#[derive(Debug)]
+pub struct AfMctpSocket(OwnedFd);
+
+impl AsRawFd for AfMctpSocket {
+    fn as_raw_fd(&self) -> libc::c_int {
+        self.0.as_raw_fd()
+    }
+}
+
+const SOCKADDR_MCTP_LEN: libc::socklen_t = core::mem::size_of::<SockaddrMctp>() as libc::socklen_t;
+
+impl MctpSocket for AfMctpSocket {
+    fn bind(&self, addr: &MctpAddr) -> io::Result<()> {
+        let sockaddr = SockaddrMctp::from(*addr);
+        // SAFETY: `sockaddr` is a valid sockaddr_mctp of the given length.
+        let rc = unsafe {
+            libc::bind(
+                self.as_raw_fd(),
+                (&sockaddr as *const SockaddrMctp).cast(),
+                SOCKADDR_MCTP_LEN,
+            )
+        };
+        if rc < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    fn send_to(&self, buf: &[u8], addr: &MctpAddr) -> io::Result<()> {
+        let sockaddr = SockaddrMctp::from(*addr);
+        // SAFETY: `buf` and `sockaddr` are valid for the lengths passed.
+        let rc = unsafe {
+            libc::sendto(
+                self.as_raw_fd(),
+                buf.as_ptr().cast(),
+                buf.len(),
+                0,
+                (&sockaddr as *const SockaddrMctp).cast(),
+                SOCKADDR_MCTP_LEN,
+            )
+        };
+        if rc < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    fn recv_from(
+        &self,
+        buf: &mut [u8],
+        timeout: Option<Duration>,
+    ) -> io::Result<(usize, MctpAddr)> {
+        let timeout_ms = match timeout {
+            Some(timeout) => libc::c_int::try_from(timeout.as_millis()).unwrap_or(libc::c_int::MAX),
+            None => -1,
+        };
+        let mut pollfd = libc::pollfd {
+            fd: self.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        loop {
+            // SAFETY: one valid pollfd.
+            match unsafe { libc::poll(&mut pollfd, 1, timeout_ms) } {
+                0 => return Err(io::ErrorKind::TimedOut.into()),
+                rc if rc > 0 => break,
+                _ => {
+                    let error = io::Error::last_os_error();
+                    if error.kind() != io::ErrorKind::Interrupted {
+                        return Err(error);
+                    }
+                }
+            }
+        }
+
+        let mut sockaddr = SockaddrMctp::default();
+        let mut addrlen = SOCKADDR_MCTP_LEN;
+        // SAFETY: `buf` and `sockaddr` are valid for the lengths passed.
+        // MSG_TRUNC makes the kernel report the full message length.
+        let len = unsafe {
+            libc::recvfrom(
+                self.as_raw_fd(),
+                buf.as_mut_ptr().cast(),
+                buf.len(),
+                libc::MSG_TRUNC,
+                (&mut sockaddr as *mut SockaddrMctp).cast(),
+                &mut addrlen,
+            )
+        };
+        if len < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let len = len as usize;
+        if len > buf.len() {
+            return Err(io::ErrorKind::InvalidInput.into());
+        }
+        Ok((len, sockaddr.into()))
+    }
+}
+
+/// In-process MCTP network with a single local endpoint.
+///
+/// Every message is addressed to the local EID and routed the way the
+/// kernel routes it on a loopback interface: requests (tag-owner bit set) go
+/// to the socket bound to their message type and get a fresh tag; responses
+/// go to the socket that sent the request with that tag. Messages nobody
+/// receives are dropped. Sockets never block: receiving with nothing queued
+/// times out immediately.
+#[derive(Clone, Debug)]
+pub struct LocalNetwork {
+    state: Rc<RefCell<LocalState>>,
+}
+
+#[derive(Debug)]
+struct LocalState {
+    eid: u8,
+    next_socket: usize,
+    next_tag: u8,
+    /// Socket id → bound message type.
+    bindings: HashMap<usize, u8>,
+    queues: HashMap<usize, VecDeque<(Vec<u8>, MctpAddr)>>,
+    /// (peer EID, tag) → socket waiting for the response.
+    tags: HashMap<(u8, u8), usize>,
+}
+
+impl LocalNetwork {
+    /// Create a network whose only endpoint is `eid`.
+    pub fn new(eid: u8) -> Self {
+        Self {
+            state: Rc::new(RefCell::new(LocalState {
+                eid,
+                next_socket: 0,
+                next_tag: 0,
+                bindings: HashMap::new(),
+                queues: HashMap::new(),
+                tags: HashMap::new(),
+            })),
+        }
+    }
+}
+
+impl MctpNetwork for LocalNetwork {
+    type Socket = LocalSocket;
+
+    fn socket(&self) -> io::Result<LocalSocket> {
+        let mut state = self.state.borrow_mut();
+        let id = state.next_socket;
+        state.next_socket += 1;
+        state.queues.insert(id, VecDeque::new());
+        Ok(LocalSocket {
+            id,
+            state: Rc::clone(&self.state),
+        })
+    }
+}
+
+/// A socket on a [`LocalNetwork`].
+#[derive(Debug)]
+pub struct LocalSocket {
+    id: usize,
+    state: Rc<RefCell<LocalState>>,
+}
+
+impl MctpSocket for LocalSocket {
+    fn bind(&self, addr: &MctpAddr) -> io::Result<()> {
+        let mut state = self.state.borrow_mut();
+        if addr.eid != MCTP_ADDR_ANY && addr.eid != state.eid {
+            return Err(io::ErrorKind::AddrNotAvailable.into());
+        }
+        let msg_type = addr.msg_type & !MCTP_TYPE_IC;
+        if state.bindings.values().any(|&bound| bound == msg_type) {
+            return Err(io::ErrorKind::AddrInUse.into());
+        }
+        state.bindings.insert(self.id, msg_type);
+        Ok(())
+    }
+
+    fn send_to(&self, buf: &[u8], addr: &MctpAddr) -> io::Result<()> {
+        let mut state = self.state.borrow_mut();
+        if addr.eid != state.eid {
+            return Err(io::Error::from_raw_os_error(libc::EHOSTUNREACH));
+        }
+        let local = state.eid;
+        let (target, tag) = if addr.tag & MCTP_TAG_OWNER != 0 {
+            let tag = (0..=MCTP_TAG_MASK)
+                .map(|i| (state.next_tag + i) & MCTP_TAG_MASK)
+                .find(|tag| !state.tags.contains_key(&(addr.eid, *tag)))
+                .ok_or(io::Error::from_raw_os_error(libc::EBUSY))?;
+            state.next_tag = (tag + 1) & MCTP_TAG_MASK;
+            state.tags.insert((addr.eid, tag), self.id);
+            let msg_type = addr.msg_type & !MCTP_TYPE_IC;
+            let target = state
+                .bindings
+                .iter()
+                .find(|(_, bound)| **bound == msg_type)
+                .map(|(id, _)| *id);
+            (target, tag | MCTP_TAG_OWNER)
+        } else {
+            let tag = addr.tag & MCTP_TAG_MASK;
+            (state.tags.remove(&(addr.eid, tag)), tag)
+        };
+        if let Some(queue) = target.and_then(|id| state.queues.get_mut(&id)) {
+            let source = MctpAddr {
+                network: addr.network,
+                eid: local,
+                msg_type: addr.msg_type,
+                tag,
+            };
+            queue.push_back((buf.to_vec(), source));
+        }
+        Ok(())
+    }
+
+    fn recv_from(
+        &self,
+        buf: &mut [u8],
+        _timeout: Option<Duration>,
+    ) -> io::Result<(usize, MctpAddr)> {
+        let mut state = self.state.borrow_mut();
+        let queue = state
+            .queues
+            .get_mut(&self.id)
+            .ok_or(io::ErrorKind::NotConnected)?;
+        let (message, source) = queue.pop_front().ok_or(io::ErrorKind::TimedOut)?;
+        let dest = buf
+            .get_mut(..message.len())
+            .ok_or(io::ErrorKind::InvalidInput)?;
+        dest.copy_from_slice(&message);
+        Ok((message.len(), source))
+    }
+}
+
+impl Drop for LocalSocket {
+    fn drop(&mut self) {
+        let mut state = self.state.borrow_mut();
+        state.bindings.remove(&self.id);
+        state.queues.remove(&self.id);
+        state.tags.retain(|_, owner| *owner != self.id);
+    }
+}
+
+enum Slot<S> {
+    Listener(S),
+    Request { socket: S, eid: u8 },
+}
+
+/// `MctpClient` on Linux MCTP sockets.
+pub struct LinuxMctp<N: MctpNetwork = KernelMctp> {
+    /// `None` when the network is unavailable.
+    network: Option<N>,
+    eid: Cell<u8>,
+    slots: RefCell<Vec<Option<Slot<N::Socket>>>>,
+    reply: RefCell<Option<N::Socket>>,
+}
+
+impl LinuxMctp<KernelMctp> {
+    /// Client on the kernel MCTP stack, disabled if the kernel lacks
+    /// `AF_MCTP`.
+    pub fn new() -> Self {
+        let available = KernelMctp.socket().is_ok();
+        Self::build(available.then_some(KernelMctp))
+    }
+}
+
+impl Default for LinuxMctp<KernelMctp> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N: MctpNetwork> LinuxMctp<N> {
+    /// Client on `network`.
+    pub fn with_network(network: N) -> Self {
+        Self::build(Some(network))
+    }
+
+    fn build(network: Option<N>) -> Self {
+        Self {
+            network,
+            eid: Cell::new(0),
+            slots: RefCell::new(Vec::new()),
+            reply: RefCell::new(None),
+        }
+    }
+
+    /// Whether the client has a network to talk to.
+    pub fn is_enabled(&self) -> bool {
+        self.network.is_some()
+    }
+
+    fn socket(&self) -> Result<N::Socket, MctpError> {
+        let network = self.network.as_ref().ok_or(disabled())?;
+        network.socket().map_err(io_error)
+    }
+
+    fn insert(&self, slot: Slot<N::Socket>) -> Handle {
+        let mut slots = self.slots.borrow_mut();
+        let index = match slots.iter().position(Option::is_none) {
+            Some(index) => {
+                slots[index] = Some(slot);
+                index
+            }
+            None => {
+                slots.push(Some(slot));
+                slots.len() - 1
+            }
+        };
+        // Handle 0 is never issued.
+        Handle(index as u32 + 1)
+    }
+
+    fn with_slot<T>(
+        &self,
+        handle: Handle,
+        f: impl FnOnce(&Slot<N::Socket>) -> Result<T, MctpError>,
+    ) -> Result<T, MctpError> {
+        let slots = self.slots.borrow();
+        let slot = (handle.0 as usize)
+            .checked_sub(1)
+            .and_then(|index| slots.get(index))
+            .and_then(Option::as_ref)
+            .ok_or(MctpError::from_code(ResponseCode::BadArgument))?;
+        f(slot)
+    }
+
+    fn send_reply(&self, buf: &[u8], addr: &MctpAddr) -> Result<(), MctpError> {
+        let mut reply = self.reply.borrow_mut();
+        if reply.is_none() {
+            *reply = Some(self.socket()?);
+        }
+        let socket = reply.as_ref().ok_or(disabled())?;
+        socket.send_to(buf, addr).map_err(io_error)
+    }
+}
+
+impl<N: MctpNetwork> MctpClient for LinuxMctp<N> {
+    fn req(&self, eid: u8) -> Result<Handle, MctpError> {
+        let socket = self.socket()?;
+        Ok(self.insert(Slot::Request { socket, eid }))
+    }
+
+    fn listener(&self, msg_type: u8) -> Result<Handle, MctpError> {
+        let socket = self.socket()?;
+        let local = match self.eid.get() {
+            0 => MCTP_ADDR_ANY,
+            eid => eid,
+        };
+        socket
+            .bind(&MctpAddr {
+                network: MCTP_NET_ANY,
+                eid: local,
+                msg_type,
+                tag: 0,
+            })
+            .map_err(io_error)?;
+        Ok(self.insert(Slot::Listener(socket)))
+    }
+
+    fn get_eid(&self) -> u8 {
+        self.eid.get()
+    }
+
+    fn set_eid(&self, eid: u8) -> Result<(), MctpError> {
+        if !self.is_enabled() {
+            return Err(disabled());
+        }
+        if eid == MCTP_ADDR_ANY {
+            return Err(MctpError::from_code(ResponseCode::BadArgument));
+        }
+        self.eid.set(eid);
+        Ok(())
+    }
+
+    fn recv(
+        &self,
+        handle: Handle,
+        timeout_millis: u32,
+        buf: &mut [u8],
+    ) -> Result<RecvMetadata, MctpError> {
+        let timeout = (timeout_millis != 0).then(|| Duration::from_millis(timeout_millis.into()));
+        let (len, source) = self.with_slot(handle, |slot| {
+            let socket = match slot {
+                Slot::Listener(socket) | Slot::Request { socket, .. } => socket,
+            };
+            socket.recv_from(buf, timeout).map_err(io_error)
+        })?;
+        Ok(RecvMetadata {
+            msg_type: source.msg_type & !MCTP_TYPE_IC,
+            msg_ic: source.msg_type & MCTP_TYPE_IC != 0,
+            msg_tag: source.tag & MCTP_TAG_MASK,
+            remote_eid: source.eid,
+            payload_size: len,
+        })
+    }
+
+    /// Requests ignore `eid` and `tag`: the kernel allocates the tag and does
+    /// not report it, and routes the response back to the request handle.
+    /// The returned tag is then just [`MCTP_TAG_OWNER`]. Responses return
+    /// the tag they were sent with.
+    fn send(
+        &self,
+        handle: Option<Handle>,
+        msg_type: u8,
+        eid: Option<u8>,
+        tag: Option<u8>,
+        integrity_check: bool,
+        buf: &[u8],
+    ) -> Result<u8, MctpError> {
+        let msg_type = if integrity_check {
+            msg_type | MCTP_TYPE_IC
+        } else {
+            msg_type & !MCTP_TYPE_IC
+        };
+        match handle {
+            Some(handle) => self.with_slot(handle, |slot| match slot {
+                Slot::Request { socket, eid } => {
+                    let addr = MctpAddr {
+                        network: MCTP_NET_ANY,
+                        eid: *eid,
+                        msg_type,
+                        tag: MCTP_TAG_OWNER,
+                    };
+                    socket.send_to(buf, &addr).map_err(io_error)?;
+                    Ok(MCTP_TAG_OWNER)
+                }
+                Slot::Listener(_) => Err(MctpError::from_code(ResponseCode::BadArgument)),
+            }),
+            None => {
+                let (Some(eid), Some(tag)) = (eid, tag) else {
+                    return Err(MctpError::from_code(ResponseCode::BadArgument));
+                };
+                let tag = tag & MCTP_TAG_MASK;
+                let addr = MctpAddr {
+                    network: MCTP_NET_ANY,
+                    eid,
+                    msg_type,
+                    tag,
+                };
+                self.send_reply(buf, &addr)?;
+                Ok(tag)
+            }
+        }
+    }
+
+    fn drop_handle(&self, handle: Handle) {
+        let mut slots = self.slots.borrow_mut();
+        if let Some(slot) = (handle.0 as usize)
+            .checked_sub(1)
+            .and_then(|index| slots.get_mut(index))
+        {
+            *slot = None;
+        }
+    }
+}
+
+fn disabled() -> MctpError {
+    MctpError::from_code(ResponseCode::InternalError)
+}
+
+fn io_error(error: io::Error) -> MctpError {
+    let code = match error.kind() {
+        io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock => ResponseCode::TimedOut,
+        io::ErrorKind::AddrInUse => ResponseCode::AddrInUse,
+        io::ErrorKind::InvalidInput | io::ErrorKind::AddrNotAvailable => ResponseCode::BadArgument,
+        io::ErrorKind::OutOfMemory => ResponseCode::NoSpace,
+        _ => match error.raw_os_error() {
+            Some(libc::ENOBUFS) | Some(libc::EBUSY) => ResponseCode::NoSpace,
+            Some(libc::EINVAL) => ResponseCode::BadArgument,
+            _ => ResponseCode::InternalError,
+        },
+    };
+    MctpError::from_code(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openprot_mctp_api::{MctpListener, MctpReqChannel, MctpRespChannel, Stack};
+
+    const SPDM: u8 = 0x05;
+    const LOCAL_EID: u8 = 8;
+
+    fn client() -> LinuxMctp<LocalNetwork> {
+        LinuxMctp::with_network(LocalNetwork::new(LOCAL_EID))
+    }
+
+    #[test]
+    fn sockaddr_matches_the_kernel_layout() {
+        assert_eq!(SOCKADDR_MCTP_LEN, 12);
+        let raw = SockaddrMctp::from(MctpAddr {
+            network: 1,
+            eid: 9,
+            msg_type: SPDM,
+            tag: MCTP_TAG_OWNER,
+        });
+        assert_eq!(raw.smctp_family, AF_MCTP as libc::sa_family_t);
+        assert_eq!(MctpAddr::from(raw).eid, 9);
+    }
+
+    #[test]
+    fn request_and_response_round_trip() {
+        let mctp = client();
+        let listener = mctp.listener(SPDM).unwrap();
+        let req = mctp.req(LOCAL_EID).unwrap();
+
+        assert_eq!(
+            mctp.send(Some(req), SPDM, None, None, true, b"GET_VERSION")
+                .unwrap(),
+            MCTP_TAG_OWNER
+        );
+        let mut buf = [0u8; 32];
+        let meta = mctp.recv(listener, 0, &mut buf).unwrap();
+        assert_eq!(&buf[..meta.payload_size], b"GET_VERSION");
+        assert_eq!(meta.msg_type, SPDM);
+        assert!(meta.msg_ic);
+        assert_eq!(meta.remote_eid, LOCAL_EID);
+
+        let tag = mctp
+            .send(
+                None,
+                SPDM,
+                Some(meta.remote_eid),
+                Some(meta.msg_tag),
+                false,
+                b"VERSION",
+            )
+            .unwrap();
+        assert_eq!(tag, meta.msg_tag);
+        let meta = mctp.recv(req, 0, &mut buf).unwrap();
+        assert_eq!(&buf[..meta.payload_size], b"VERSION");
+        assert!(!meta.msg_ic);
+
+        // Nothing else is queued.
+        assert!(mctp.recv(req, 10, &mut buf).unwrap_err().is_timeout());
+        mctp.drop_handle(req);
+        mctp.drop_handle(listener);
+    }
+
+    #[test]
+    fn works_through_the_stack_helpers() {
+        let stack = Stack::new(client());
+        let mut listener = stack.listener(SPDM, 0).unwrap();
+        let mut req = stack.req(LOCAL_EID, 0).unwrap();
+
+        req.send(SPDM, b"ping").unwrap();
+        let mut buf = [0u8; 16];
+        let (_, payload, mut resp) = listener.recv(&mut buf).unwrap();
+        assert_eq!(payload, b"ping");
+        resp.send(b"pong").unwrap();
+
+        let mut buf = [0u8; 16];
+        let (_, payload) = req.recv(&mut buf).unwrap();
+        assert_eq!(payload, b"pong");
+    }
+
+    #[test]
+    fn handles_and_arguments_are_checked() {
+        let mctp = client();
+        let mut buf = [0u8; 4];
+        assert_eq!(
+            mctp.recv(Handle(0), 0, &mut buf).unwrap_err().code,
+            ResponseCode::BadArgument
+        );
+        let listener = mctp.listener(SPDM).unwrap();
+        assert_eq!(
+            mctp.listener(SPDM).unwrap_err().code,
+            ResponseCode::AddrInUse
+        );
+        assert_eq!(
+            mctp.send(Some(listener), SPDM, None, None, false, b"x")
+                .unwrap_err()
+                .code,
+            ResponseCode::BadArgument
+        );
+        assert_eq!(
+            mctp.send(None, SPDM, Some(LOCAL_EID), None, false, b"x")
+                .unwrap_err()
+                .code,
+            ResponseCode::BadArgument
+        );
+
+        // A message too long for the buffer is reported, not truncated.
+        let req = mctp.req(LOCAL_EID).unwrap();
+        mctp.send(Some(req), SPDM, None, None, false, b"too long")
+            .unwrap();
+        assert_eq!(
+            mctp.recv(listener, 0, &mut buf).unwrap_err().code,
+            ResponseCode::BadArgument
+        );
+
+        // Dropped handles are rejected and their slot is reused.
+        mctp.drop_handle(listener);
+        assert_eq!(
+            mctp.recv(listener, 0, &mut buf).unwrap_err().code,
+            ResponseCode::BadArgument
+        );
+        assert_eq!(mctp.listener(SPDM).unwrap(), listener);
+    }
+
+    #[test]
+    fn set_eid_records_the_listener_address() {
+        let mctp = client();
+        assert_eq!(mctp.get_eid(), 0);
+        mctp.set_eid(LOCAL_EID).unwrap();
+        assert_eq!(mctp.get_eid(), LOCAL_EID);
+        mctp.listener(SPDM).unwrap();
+
+        mctp.set_eid(LOCAL_EID + 1).unwrap();
+        assert_eq!(
+            mctp.listener(0x7e).unwrap_err().code,
+            ResponseCode::BadArgument
+        );
+        assert_eq!(
+            mctp.set_eid(MCTP_ADDR_ANY).unwrap_err().code,
+            ResponseCode::BadArgument
+        );
+    }
+
+    #[test]
+    fn kernel_client_is_disabled_without_af_mctp() {
+        let mctp = LinuxMctp::new();
+        match KernelMctp.socket() {
+            Ok(_) => assert!(mctp.is_enabled()),
+            Err(_) => {
+                assert!(!mctp.is_enabled());
+                assert_eq!(mctp.req(9).unwrap_err().code, ResponseCode::InternalError);
+                assert_eq!(
+                    mctp.listener(SPDM).unwrap_err().code,
+                    ResponseCode::InternalError
+                );
+                assert_eq!(
+                    mctp.set_eid(9).unwrap_err().code,
+                    ResponseCode::InternalError
+                );
+                assert_eq!(
+                    mctp.send(None, SPDM, Some(9), Some(0), false, b"x")
+                        .unwrap_err()
+                        .code,
+                    ResponseCode::InternalError
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,348 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! USART backend over a Linux tty
+//!
+//! [`LinuxUsart`] implements `usart_api::backend::UsartBackend` on any
+//! terminal file descriptor: a real serial port (`/dev/ttyS*`, `/dev/ttyUSB*`)
+//! or one side of a pseudo-terminal. [`LinuxUsart::pty_pair`] opens a
+//! connected pty pair, so a test or the PRoT emulator can play the far end of
+//! the line without hardware.
+//!
+//! The line is put in raw mode. Linux has no UART interrupts to hand out, so
+//! the interrupt mask is recorded and [`LinuxUsart::pending_interrupts`]
+//! reports which enabled sources are currently active; a server runtime can
+//! `poll(2)` the descriptor from [`AsRawFd`] and then call it.
+//!
+//! `line_status` follows the 16550 LSR layout: bit 0 (data ready) is set
+//! while input is queued, bits 5 and 6 (transmitter empty) while no output
+//! is queued.
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Write};
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd, RawFd};
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::Path;
+
+use usart_api::backend::{BackendError, IrqMask, LineStatus, Parity, UsartBackend, UsartConfig};
+
+/// LSR bit 0: received data is available.
+pub const LSR_DATA_READY: u8 = 0x01;
+/// LSR bit 5: the transmit holding register is empty.
+pub const LSR_THR_EMPTY: u8 = 0x20;
+/// LSR bit 6: the transmitter is idle.
+pub const LSR_TX_EMPTY: u8 = 0x40;
+
+/// USART backend on a Linux terminal device.
+#[derive(Debug)]
+pub struct LinuxUsart {
+    tty: File,
+    enabled: IrqMask,
+}
+
+impl LinuxUsart {
+    /// Open a serial device such as `/dev/ttyUSB0` and switch it to raw mode.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let tty = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .custom_flags(libc::O_NOCTTY)
+            .open(path)?;
+        Self::from_fd(tty.into())
+    }
+
+    /// Wrap an already open terminal descriptor and switch it to raw mode.
+    pub fn from_fd(fd: OwnedFd) -> io::Result<Self> {
+        let tty = File::from(fd);
+        let mut termios = get_termios(&tty)?;
+        // SAFETY: `termios` is a valid, initialised structure.
+        unsafe { libc::cfmakeraw(&mut termios) };
+        set_termios(&tty, &termios)?;
+        Ok(Self {
+            tty,
+            enabled: IrqMask::empty(),
+        })
+    }
+
+    /// Open a connected pseudo-terminal pair, both ends in raw mode.
+    ///
+    /// Bytes written to one end are read from the other.
+    pub fn pty_pair() -> io::Result<(Self, Self)> {
+        let mut controller: RawFd = -1;
+        let mut device: RawFd = -1;
+        // SAFETY: the out-pointers are valid; name, termios and winsize are
+        // optional and passed as null.
+        let rc = unsafe {
+            libc::openpty(
+                &mut controller,
+                &mut device,
+                core::ptr::null_mut(),
+                core::ptr::null(),
+                core::ptr::null(),
+            )
+        };
+        if rc < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        // SAFETY: openpty succeeded, so both descriptors are open and owned
+        // by nobody else.
+        let (controller, device) = unsafe {
+            (
+                OwnedFd::from_raw_fd(controller),
+                OwnedFd::from_raw_fd(device),
+            )
+        };
+        Ok((Self::from_fd(controller)?, Self::from_fd(device)?))
+    }
+
+    /// Enabled interrupt sources whose condition currently holds.
+    pub fn pending_interrupts(&self) -> Result<IrqMask, BackendError> {
+        let mut pending = IrqMask::empty();
+        if self.input_queued()? > 0 {
+            pending |= IrqMask::RX_DATA_AVAILABLE;
+        }
+        if self.output_queued()? == 0 {
+            pending |= IrqMask::TX_IDLE;
+        }
+        Ok(pending & self.enabled)
+    }
+
+    fn input_queued(&self) -> Result<usize, BackendError> {
+        queued(&self.tty, libc::FIONREAD)
+    }
+
+    fn output_queued(&self) -> Result<usize, BackendError> {
+        queued(&self.tty, libc::TIOCOUTQ)
+    }
+}
+
+impl AsRawFd for LinuxUsart {
+    fn as_raw_fd(&self) -> RawFd {
+        self.tty.as_raw_fd()
+    }
+}
+
+impl UsartBackend for LinuxUsart {
+    fn configure(&mut self, config: UsartConfig) -> Result<(), BackendError> {
+        let speed = baud_to_speed(config.baud_rate).ok_or(BackendError::InvalidConfiguration)?;
+        let mut termios = get_termios(&self.tty).map_err(io_error)?;
+        // SAFETY: `termios` is a valid, initialised structure.
+        let rc = unsafe { libc::cfsetspeed(&mut termios, speed) };
+        if rc < 0 {
+            return Err(BackendError::InvalidConfiguration);
+        }
+        termios.c_cflag &= !(libc::PARENB | libc::PARODD | libc::CSTOPB);
+        match config.parity {
+            Parity::None => {}
+            Parity::Even => termios.c_cflag |= libc::PARENB,
+            Parity::Odd => termios.c_cflag |= libc::PARENB | libc::PARODD,
+        }
+        match config.stop_bits {
+            1 => {}
+            2 => termios.c_cflag |= libc::CSTOPB,
+            _ => return Err(BackendError::InvalidConfiguration),
+        }
+        set_termios(&self.tty, &termios).map_err(io_error)
+    }
+
+    fn write(&mut self, data: &[u8]) -> Result<usize, BackendError> {
+        self.tty.write_all(data).map_err(io_error)?;
+        Ok(data.len())
+    }
+
+    fn read(&mut self, out: &mut [u8]) -> Result<usize, BackendError> {
+        if out.is_empty() {
+            return Ok(0);
+        }
+        loop {
+            match self.tty.read(out) {
+                Ok(n) => return Ok(n),
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(io_error(e)),
+            }
+        }
+    }
+
+    fn try_read(&mut self, out: &mut [u8]) -> Result<usize, BackendError> {
+        if self.input_queued()? == 0 {
+            return Err(BackendError::WouldBlock);
+        }
+        self.read(out)
+    }
+
+    fn line_status(&self) -> Result<LineStatus, BackendError> {
+        let mut lsr = 0;
+        if self.input_queued()? > 0 {
+            lsr |= LSR_DATA_READY;
+        }
+        if self.output_queued()? == 0 {
+            lsr |= LSR_THR_EMPTY | LSR_TX_EMPTY;
+        }
+        Ok(LineStatus(lsr))
+    }
+
+    fn enable_interrupts(&mut self, mask: IrqMask) -> Result<(), BackendError> {
+        self.enabled |= mask;
+        Ok(())
+    }
+
+    fn disable_interrupts(&mut self, mask: IrqMask) -> Result<(), BackendError> {
+        self.enabled &= !mask;
+        Ok(())
+    }
+}
+
+fn io_error(error: io::Error) -> BackendError {
+    match error.kind() {
+        io::ErrorKind::WouldBlock => BackendError::WouldBlock,
+        io::ErrorKind::TimedOut => BackendError::Timeout,
+        _ => BackendError::InternalError,
+    }
+}
+
+fn queued(tty: &File, request: libc::c_ulong) -> Result<usize, BackendError> {
+    let mut count: libc::c_int = 0;
+    // SAFETY: both requests write one `int` through the pointer.
+    let rc = unsafe { libc::ioctl(tty.as_raw_fd(), request, &mut count) };
+    if rc < 0 {
+        return Err(io_error(io::Error::last_os_error()));
+    }
+    Ok(count.max(0) as usize)
+}
+
+fn get_termios(tty: &File) -> io::Result<libc::termios> {
+    let mut termios = core::mem::MaybeUninit::uninit();
+    // SAFETY: tcgetattr fills the whole structure on success.
+    if unsafe { libc::tcgetattr(tty.as_raw_fd(), termios.as_mut_ptr()) } < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    // SAFETY: initialised by the successful tcgetattr above.
+    Ok(unsafe { termios.assume_init() })
+}
+
+fn set_termios(tty: &File, termios: &libc::termios) -> io::Result<()> {
+    // SAFETY: `termios` is a valid structure obtained from tcgetattr.
+    if unsafe { libc::tcsetattr(tty.as_raw_fd(), libc::TCSANOW, termios) } < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+fn baud_to_speed(baud_rate: u32) -> Option<libc::speed_t> {
+    Some(match baud_rate {
+        9_600 => libc::B9600,
+        19_200 => libc::B19200,
+        38_400 => libc::B38400,
+        57_600 => libc::B57600,
+        115_200 => libc::B115200,
+        230_400 => libc::B230400,
+        460_800 => libc::B460800,
+        921_600 => libc::B921600,
+        1_000_000 => libc::B1000000,
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(baud_rate: u32) -> UsartConfig {
+        UsartConfig {
+            baud_rate,
+            parity: Parity::None,
+            stop_bits: 1,
+        }
+    }
+
+    #[test]
+    fn bytes_cross_the_pty_pair() {
+        let (mut host, mut dut) = LinuxUsart::pty_pair().unwrap();
+        dut.configure(config(115_200)).unwrap();
+
+        assert_eq!(dut.write(b"hello").unwrap(), 5);
+        let mut buf = [0u8; 16];
+        let mut got = Vec::new();
+        while got.len() < 5 {
+            let n = host.read(&mut buf).unwrap();
+            got.extend_from_slice(&buf[..n]);
+        }
+        assert_eq!(got, b"hello");
+
+        host.write(&[0x00, 0xff, b'\n', 0x03]).unwrap();
+        let mut got = Vec::new();
+        while got.len() < 4 {
+            let n = dut.read(&mut buf).unwrap();
+            got.extend_from_slice(&buf[..n]);
+        }
+        // Raw mode: no newline translation and no signal characters.
+        assert_eq!(got, [0x00, 0xff, b'\n', 0x03]);
+    }
+
+    #[test]
+    fn try_read_and_line_status_follow_the_rx_queue() {
+        let (mut host, mut dut) = LinuxUsart::pty_pair().unwrap();
+        let mut buf = [0u8; 8];
+        assert_eq!(dut.try_read(&mut buf), Err(BackendError::WouldBlock));
+        assert_eq!(dut.line_status().unwrap().0 & LSR_DATA_READY, 0);
+
+        dut.enable_interrupts(IrqMask::RX_DATA_AVAILABLE).unwrap();
+        assert_eq!(dut.pending_interrupts().unwrap(), IrqMask::empty());
+
+        host.write(b"x").unwrap();
+        // The pty may take a moment to move the byte across.
+        let mut tries = 0;
+        while dut.line_status().unwrap().0 & LSR_DATA_READY == 0 {
+            tries += 1;
+            assert!(tries < 1000, "byte never arrived");
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+        assert_eq!(
+            dut.pending_interrupts().unwrap(),
+            IrqMask::RX_DATA_AVAILABLE
+        );
+        assert_eq!(dut.try_read(&mut buf), Ok(1));
+        assert_eq!(buf[0], b'x');
+
+        dut.disable_interrupts(IrqMask::RX_DATA_AVAILABLE).unwrap();
+        dut.enable_interrupts(IrqMask::TX_IDLE).unwrap();
+        assert_eq!(dut.pending_interrupts().unwrap(), IrqMask::TX_IDLE);
+    }
+
+    #[test]
+    fn configure_validates_settings() {
+        let (_host, mut dut) = LinuxUsart::pty_pair().unwrap();
+        dut.configure(UsartConfig {
+            baud_rate: 9_600,
+            parity: Parity::Even,
+            stop_bits: 2,
+        })
+        .unwrap();
+        // A pty keeps speed and stop bits but, unlike a real UART, drops
+        // the parity setting.
+        let termios = get_termios(&dut.tty).unwrap();
+        assert_ne!(termios.c_cflag & libc::CSTOPB, 0);
+        // SAFETY: `termios` was filled by tcgetattr.
+        assert_eq!(unsafe { libc::cfgetospeed(&termios) }, libc::B9600);
+
+        assert_eq!(
+            dut.configure(config(12_345)),
+            Err(BackendError::InvalidConfiguration)
+        );
+        assert_eq!(
+            dut.configure(UsartConfig {
+                stop_bits: 3,
+                ..config(9_600)
+            }),
+            Err(BackendError::InvalidConfiguration)
+        );
+    }
+
+    #[test]
+    fn open_rejects_non_terminals() {
+        let path = crate::test_util::scratch_path("usart-notatty");
+        std::fs::write(&path, []).unwrap();
+        assert!(LinuxUsart::open(&path).is_err());
+        std::fs::remove_file(path).unwrap();
+    }
+}
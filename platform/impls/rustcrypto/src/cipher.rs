@@ -4,15 +4,25 @@
 #![allow(deprecated)] // Allow deprecated GenericArray from cipher crate for compatibility
 
 use openprot_hal_blocking::cipher::{
-    AeadCipherMode, BlockCipherMode, CipherInit, CipherMode, CipherOp, CipherStatus, Error,
-    ErrorKind, ErrorType, SymmetricCipher,
+    AeadCipherMode, AeadCipherOp, BlockAligned, BlockCipherMode, CipherInit, CipherMode, CipherOp,
+    CipherStatus, Error, ErrorKind, ErrorType, SymmetricCipher,
 };
 
 // RustCrypto imports for AES-CTR implementation
-use aes::Aes256;
+use aes::{Aes128, Aes192, Aes256};
 use cipher::{generic_array::GenericArray, KeyIvInit, StreamCipher, StreamCipherSeek};
 use ctr::Ctr64BE;
 
+// RustCrypto imports for AES block modes and AEAD ciphers
+use aes_gcm::aead::{AeadInPlace, KeyInit};
+use aes_gcm::{Aes128Gcm, Aes256Gcm};
+use chacha20poly1305::ChaCha20Poly1305;
+use cipher::consts::{U12, U16};
+use cipher::{BlockDecrypt, BlockEncrypt, BlockSizeUser};
+
+/// AES block size in bytes
+pub const AES_BLOCK_SIZE: usize = 16;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum RustCryptoCipherError {
     InvalidKey,
@@ -23,6 +33,7 @@ pub enum RustCryptoCipherError {
     MessageTooLarge,
     InvalidState,
     HardwareFailure,
+    InvalidPadding,
 }
 
 impl core::fmt::Display for RustCryptoCipherError {
@@ -36,6 +47,7 @@ impl core::fmt::Display for RustCryptoCipherError {
             Self::MessageTooLarge => write!(f, "message too large for configured buffer size"),
             Self::InvalidState => write!(f, "cipher context is in an invalid state"),
             Self::HardwareFailure => write!(f, "hardware failure during cipher operation"),
+            Self::InvalidPadding => write!(f, "invalid PKCS#7 padding"),
         }
     }
 }
@@ -46,10 +58,11 @@ impl Error for RustCryptoCipherError {
             Self::InvalidKey => ErrorKind::KeyError,
             Self::InvalidNonce => ErrorKind::InvalidInput,
             Self::EncryptionFailed | Self::DecryptionFailed => ErrorKind::HardwareFailure,
-            Self::AuthenticationFailed => ErrorKind::InvalidInput,
+            Self::AuthenticationFailed => ErrorKind::AuthenticationFailed,
             Self::MessageTooLarge => ErrorKind::InvalidInput,
             Self::InvalidState => ErrorKind::InvalidState,
             Self::HardwareFailure => ErrorKind::HardwareFailure,
+            Self::InvalidPadding => ErrorKind::InvalidInput,
         }
     }
 }
@@ -72,6 +85,62 @@ pub struct Aes256GcmMode;
 impl CipherMode for Aes256GcmMode {}
 impl AeadCipherMode for Aes256GcmMode {}
 
+/// AES-128 in GCM mode marker
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Aes128GcmMode;
+
+impl CipherMode for Aes128GcmMode {}
+impl AeadCipherMode for Aes128GcmMode {}
+
+/// ChaCha20-Poly1305 (RFC 8439) marker
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChaCha20Poly1305Mode;
+
+impl CipherMode for ChaCha20Poly1305Mode {}
+impl AeadCipherMode for ChaCha20Poly1305Mode {}
+
+/// AES-128 in CBC mode marker
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Aes128CbcMode;
+
+impl CipherMode for Aes128CbcMode {}
+impl BlockCipherMode for Aes128CbcMode {}
+
+/// AES-192 in CBC mode marker
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Aes192CbcMode;
+
+impl CipherMode for Aes192CbcMode {}
+impl BlockCipherMode for Aes192CbcMode {}
+
+/// AES-256 in CBC mode marker
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Aes256CbcMode;
+
+impl CipherMode for Aes256CbcMode {}
+impl BlockCipherMode for Aes256CbcMode {}
+
+/// AES-128 in ECB mode marker
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Aes128EcbMode;
+
+impl CipherMode for Aes128EcbMode {}
+impl BlockCipherMode for Aes128EcbMode {}
+
+/// AES-192 in ECB mode marker
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Aes192EcbMode;
+
+impl CipherMode for Aes192EcbMode {}
+impl BlockCipherMode for Aes192EcbMode {}
+
+/// AES-256 in ECB mode marker
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Aes256EcbMode;
+
+impl CipherMode for Aes256EcbMode {}
+impl BlockCipherMode for Aes256EcbMode {}
+
 //
 // Basic cipher implementations for type system foundation
 //
@@ -82,6 +151,42 @@ pub struct Aes256CtrCipher;
 /// Basic AES-256-GCM AEAD cipher implementation
 pub struct Aes256GcmCipher;
 
+/// Basic AES-128-GCM AEAD cipher implementation
+pub struct Aes128GcmCipher;
+
+/// ChaCha20-Poly1305 AEAD cipher implementation
+pub struct ChaCha20Poly1305Cipher;
+
+/// AES-128-CBC cipher implementation
+///
+/// `BLOCKS` is the number of AES blocks processed per [`CipherOp`] call.
+pub struct Aes128CbcCipher<const BLOCKS: usize = 16>;
+
+/// AES-192-CBC cipher implementation
+///
+/// `BLOCKS` is the number of AES blocks processed per [`CipherOp`] call.
+pub struct Aes192CbcCipher<const BLOCKS: usize = 16>;
+
+/// AES-256-CBC cipher implementation
+///
+/// `BLOCKS` is the number of AES blocks processed per [`CipherOp`] call.
+pub struct Aes256CbcCipher<const BLOCKS: usize = 16>;
+
+/// AES-128-ECB cipher implementation
+///
+/// `BLOCKS` is the number of AES blocks processed per [`CipherOp`] call.
+pub struct Aes128EcbCipher<const BLOCKS: usize = 16>;
+
+/// AES-192-ECB cipher implementation
+///
+/// `BLOCKS` is the number of AES blocks processed per [`CipherOp`] call.
+pub struct Aes192EcbCipher<const BLOCKS: usize = 16>;
+
+/// AES-256-ECB cipher implementation
+///
+/// `BLOCKS` is the number of AES blocks processed per [`CipherOp`] call.
+pub struct Aes256EcbCipher<const BLOCKS: usize = 16>;
+
 //
 // ErrorType trait implementations
 //
@@ -94,6 +199,14 @@ impl ErrorType for Aes256GcmCipher {
     type Error = RustCryptoCipherError;
 }
 
+impl ErrorType for Aes128GcmCipher {
+    type Error = RustCryptoCipherError;
+}
+
+impl ErrorType for ChaCha20Poly1305Cipher {
+    type Error = RustCryptoCipherError;
+}
+
 //
 // SymmetricCipher trait implementations
 //
@@ -112,6 +225,20 @@ impl SymmetricCipher for Aes256GcmCipher {
     type CipherText = [u8; 272]; // 256 + 16 bytes for authentication tag
 }
 
+impl SymmetricCipher for Aes128GcmCipher {
+    type Key = [u8; 16]; // AES-128 key
+    type Nonce = [u8; 12]; // 96-bit nonce for GCM
+    type PlainText = [u8; 256]; // Fixed-size plaintext buffer
+    type CipherText = [u8; 272]; // 256 + 16 bytes for authentication tag
+}
+
+impl SymmetricCipher for ChaCha20Poly1305Cipher {
+    type Key = [u8; 32]; // 256-bit key
+    type Nonce = [u8; 12]; // 96-bit nonce
+    type PlainText = [u8; 256]; // Fixed-size plaintext buffer
+    type CipherText = [u8; 272]; // 256 + 16 bytes for authentication tag
+}
+
 //
 // AES-CTR Implementation using RustCrypto
 //
@@ -368,11 +495,584 @@ impl CipherStatus for AesCtrContext {
     }
 }
 
+//
+// AES block modes (CBC and ECB) using RustCrypto block primitives
+//
+
+/// Bound for the RustCrypto AES block ciphers usable by the block-mode contexts.
+pub trait AesBlockCipher:
+    KeyInit + BlockEncrypt + BlockDecrypt + BlockSizeUser<BlockSize = U16>
+{
+}
+
+impl<C> AesBlockCipher for C where
+    C: KeyInit + BlockEncrypt + BlockDecrypt + BlockSizeUser<BlockSize = U16>
+{
+}
+
+/// AES-CBC cipher context.
+///
+/// The chaining value is carried across calls, so a message can be processed
+/// as a sequence of block-aligned chunks. Use [`encrypt_padded`] and
+/// [`decrypt_padded`] for messages of arbitrary length with PKCS#7 padding.
+///
+/// `BLOCKS` is the number of AES blocks processed per [`CipherOp`] call.
+///
+/// [`encrypt_padded`]: AesCbcContext::encrypt_padded
+/// [`decrypt_padded`]: AesCbcContext::decrypt_padded
+pub struct AesCbcContext<C: AesBlockCipher, const BLOCKS: usize = 16> {
+    /// The underlying RustCrypto AES block cipher
+    cipher: C,
+    /// Chaining value: the IV, then the last ciphertext block processed
+    chain: [u8; AES_BLOCK_SIZE],
+}
+
+impl<C: AesBlockCipher, const BLOCKS: usize> AesCbcContext<C, BLOCKS> {
+    /// Create a new AES-CBC context with the given key and IV.
+    ///
+    /// # Errors
+    /// - `InvalidKey`: If the key length does not match the AES variant
+    pub fn new(key: &[u8], iv: &[u8; AES_BLOCK_SIZE]) -> Result<Self, RustCryptoCipherError> {
+        let cipher = C::new_from_slice(key).map_err(|_| RustCryptoCipherError::InvalidKey)?;
+        Ok(Self { cipher, chain: *iv })
+    }
+
+    /// Reset the cipher context with a new key and IV.
+    pub fn reset(
+        &mut self,
+        key: &[u8],
+        iv: &[u8; AES_BLOCK_SIZE],
+    ) -> Result<(), RustCryptoCipherError> {
+        *self = Self::new(key, iv)?;
+        Ok(())
+    }
+
+    fn encrypt_block(&mut self, block: &mut [u8; AES_BLOCK_SIZE]) {
+        for (b, c) in block.iter_mut().zip(self.chain.iter()) {
+            *b ^= c;
+        }
+        self.cipher
+            .encrypt_block(GenericArray::from_mut_slice(block));
+        self.chain.copy_from_slice(block);
+    }
+
+    fn decrypt_block(&mut self, block: &mut [u8; AES_BLOCK_SIZE]) {
+        let mut next_chain = [0u8; AES_BLOCK_SIZE];
+        next_chain.copy_from_slice(block);
+        self.cipher
+            .decrypt_block(GenericArray::from_mut_slice(block));
+        for (b, c) in block.iter_mut().zip(self.chain.iter()) {
+            *b ^= c;
+        }
+        self.chain = next_chain;
+    }
+
+    /// Encrypt a sequence of blocks without padding.
+    pub fn encrypt_blocks<const N: usize>(
+        &mut self,
+        input: &BlockAligned<AES_BLOCK_SIZE, N>,
+    ) -> Result<BlockAligned<AES_BLOCK_SIZE, N>, RustCryptoCipherError> {
+        let mut output = BlockAligned::new();
+        for block in input.iter_blocks() {
+            let mut block = *block;
+            self.encrypt_block(&mut block);
+            output
+                .push_block(block)
+                .map_err(|_| RustCryptoCipherError::MessageTooLarge)?;
+        }
+        Ok(output)
+    }
+
+    /// Decrypt a sequence of blocks without removing padding.
+    pub fn decrypt_blocks<const N: usize>(
+        &mut self,
+        input: &BlockAligned<AES_BLOCK_SIZE, N>,
+    ) -> Result<BlockAligned<AES_BLOCK_SIZE, N>, RustCryptoCipherError> {
+        let mut output = BlockAligned::new();
+        for block in input.iter_blocks() {
+            let mut block = *block;
+            self.decrypt_block(&mut block);
+            output
+                .push_block(block)
+                .map_err(|_| RustCryptoCipherError::MessageTooLarge)?;
+        }
+        Ok(output)
+    }
+
+    /// Apply PKCS#7 padding to `data` and encrypt it.
+    ///
+    /// A full padding block is appended when `data` is already block aligned.
+    ///
+    /// # Errors
+    /// - `MessageTooLarge`: If the padded message does not fit in `N` blocks
+    pub fn encrypt_padded<const N: usize>(
+        &mut self,
+        data: &[u8],
+    ) -> Result<BlockAligned<AES_BLOCK_SIZE, N>, RustCryptoCipherError> {
+        let padded = pkcs7_pad::<N>(data)?;
+        self.encrypt_blocks(&padded)
+    }
+
+    /// Decrypt `input`, strip its PKCS#7 padding and copy the message to `out`.
+    ///
+    /// Returns the slice of `out` holding the recovered message.
+    ///
+    /// # Errors
+    /// - `InvalidPadding`: If the input is empty or the padding is malformed
+    /// - `MessageTooLarge`: If `out` is too small for the recovered message
+    pub fn decrypt_padded<'b, const N: usize>(
+        &mut self,
+        input: &BlockAligned<AES_BLOCK_SIZE, N>,
+        out: &'b mut [u8],
+    ) -> Result<&'b [u8], RustCryptoCipherError> {
+        let decrypted = self.decrypt_blocks(input)?;
+        pkcs7_unpad(&decrypted, out)
+    }
+}
+
+/// AES-ECB cipher context.
+///
+/// ECB encrypts every block independently and leaks repeated plaintext
+/// blocks. Only use it for single-block primitives such as key wrapping.
+///
+/// `BLOCKS` is the number of AES blocks processed per [`CipherOp`] call.
+pub struct AesEcbContext<C: AesBlockCipher, const BLOCKS: usize = 16> {
+    /// The underlying RustCrypto AES block cipher
+    cipher: C,
+}
+
+impl<C: AesBlockCipher, const BLOCKS: usize> AesEcbContext<C, BLOCKS> {
+    /// Create a new AES-ECB context with the given key.
+    ///
+    /// # Errors
+    /// - `InvalidKey`: If the key length does not match the AES variant
+    pub fn new(key: &[u8]) -> Result<Self, RustCryptoCipherError> {
+        let cipher = C::new_from_slice(key).map_err(|_| RustCryptoCipherError::InvalidKey)?;
+        Ok(Self { cipher })
+    }
+
+    /// Encrypt a sequence of blocks.
+    pub fn encrypt_blocks<const N: usize>(
+        &mut self,
+        input: &BlockAligned<AES_BLOCK_SIZE, N>,
+    ) -> Result<BlockAligned<AES_BLOCK_SIZE, N>, RustCryptoCipherError> {
+        let mut output = BlockAligned::new();
+        for block in input.iter_blocks() {
+            let mut block = *block;
+            self.cipher
+                .encrypt_block(GenericArray::from_mut_slice(&mut block));
+            output
+                .push_block(block)
+                .map_err(|_| RustCryptoCipherError::MessageTooLarge)?;
+        }
+        Ok(output)
+    }
+
+    /// Decrypt a sequence of blocks.
+    pub fn decrypt_blocks<const N: usize>(
+        &mut self,
+        input: &BlockAligned<AES_BLOCK_SIZE, N>,
+    ) -> Result<BlockAligned<AES_BLOCK_SIZE, N>, RustCryptoCipherError> {
+        let mut output = BlockAligned::new();
+        for block in input.iter_blocks() {
+            let mut block = *block;
+            self.cipher
+                .decrypt_block(GenericArray::from_mut_slice(&mut block));
+            output
+                .push_block(block)
+                .map_err(|_| RustCryptoCipherError::MessageTooLarge)?;
+        }
+        Ok(output)
+    }
+}
+
+/// Pad `data` to a whole number of AES blocks using PKCS#7.
+///
+/// # Errors
+/// - `MessageTooLarge`: If the padded message does not fit in `N` blocks
+pub fn pkcs7_pad<const N: usize>(
+    data: &[u8],
+) -> Result<BlockAligned<AES_BLOCK_SIZE, N>, RustCryptoCipherError> {
+    let pad_len = AES_BLOCK_SIZE - (data.len() % AES_BLOCK_SIZE);
+    // pad_len is in 1..=16, so the cast cannot truncate
+    let pad_byte = pad_len as u8;
+    let mut blocks = BlockAligned::from_slice_padded(data, pad_byte)
+        .map_err(|_| RustCryptoCipherError::MessageTooLarge)?;
+    if pad_len == AES_BLOCK_SIZE {
+        blocks
+            .push_block([pad_byte; AES_BLOCK_SIZE])
+            .map_err(|_| RustCryptoCipherError::MessageTooLarge)?;
+    }
+    Ok(blocks)
+}
+
+/// Strip PKCS#7 padding from `blocks` and copy the message to `out`.
+///
+/// The padding bytes are checked without early exit so that the failure
+/// does not depend on the position of the first bad byte.
+///
+/// # Errors
+/// - `InvalidPadding`: If `blocks` is empty or the padding is malformed
+/// - `MessageTooLarge`: If `out` is too small for the message
+pub fn pkcs7_unpad<'b, const N: usize>(
+    blocks: &BlockAligned<AES_BLOCK_SIZE, N>,
+    out: &'b mut [u8],
+) -> Result<&'b [u8], RustCryptoCipherError> {
+    let last = blocks
+        .blocks()
+        .last()
+        .ok_or(RustCryptoCipherError::InvalidPadding)?;
+    let pad_byte = last[AES_BLOCK_SIZE - 1];
+    let pad_len = usize::from(pad_byte);
+    if pad_len == 0 || pad_len > AES_BLOCK_SIZE {
+        return Err(RustCryptoCipherError::InvalidPadding);
+    }
+    let mismatch = last[AES_BLOCK_SIZE - pad_len..]
+        .iter()
+        .fold(0u8, |acc, b| acc | (b ^ pad_byte));
+    if mismatch != 0 {
+        return Err(RustCryptoCipherError::InvalidPadding);
+    }
+
+    let msg_len = blocks.len() - pad_len;
+    let out = out
+        .get_mut(..msg_len)
+        .ok_or(RustCryptoCipherError::MessageTooLarge)?;
+    for (chunk, block) in out.chunks_mut(AES_BLOCK_SIZE).zip(blocks.iter_blocks()) {
+        chunk.copy_from_slice(&block[..chunk.len()]);
+    }
+    Ok(out)
+}
+
+macro_rules! impl_aes_block_mode {
+    ($cipher:ident, $context:ident, $aes:ty, $key_len:expr, $nonce:ty) => {
+        impl<const BLOCKS: usize> ErrorType for $cipher<BLOCKS> {
+            type Error = RustCryptoCipherError;
+        }
+
+        impl<const BLOCKS: usize> SymmetricCipher for $cipher<BLOCKS> {
+            type Key = [u8; $key_len];
+            type Nonce = $nonce;
+            type PlainText = [[u8; AES_BLOCK_SIZE]; BLOCKS]; // Whole blocks only
+            type CipherText = [[u8; AES_BLOCK_SIZE]; BLOCKS];
+        }
+
+        impl<const BLOCKS: usize> ErrorType for $context<$aes, BLOCKS> {
+            type Error = RustCryptoCipherError;
+        }
+
+        impl<const BLOCKS: usize> SymmetricCipher for $context<$aes, BLOCKS> {
+            type Key = [u8; $key_len];
+            type Nonce = $nonce;
+            type PlainText = [[u8; AES_BLOCK_SIZE]; BLOCKS];
+            type CipherText = [[u8; AES_BLOCK_SIZE]; BLOCKS];
+        }
+    };
+}
+
+impl_aes_block_mode!(Aes128CbcCipher, AesCbcContext, Aes128, 16, [u8; 16]);
+impl_aes_block_mode!(Aes192CbcCipher, AesCbcContext, Aes192, 24, [u8; 16]);
+impl_aes_block_mode!(Aes256CbcCipher, AesCbcContext, Aes256, 32, [u8; 16]);
+impl_aes_block_mode!(Aes128EcbCipher, AesEcbContext, Aes128, 16, [u8; 0]);
+impl_aes_block_mode!(Aes192EcbCipher, AesEcbContext, Aes192, 24, [u8; 0]);
+impl_aes_block_mode!(Aes256EcbCipher, AesEcbContext, Aes256, 32, [u8; 0]);
+
+macro_rules! impl_aes_cbc {
+    ($cipher:ident, $mode:ident, $aes:ty) => {
+        impl<const BLOCKS: usize> CipherInit<$mode> for $cipher<BLOCKS> {
+            type CipherContext<'a> = AesCbcContext<$aes, BLOCKS>;
+
+            fn init<'a>(
+                &'a mut self,
+                key: &Self::Key,
+                nonce: &Self::Nonce,
+                _mode: $mode,
+            ) -> Result<Self::CipherContext<'a>, Self::Error> {
+                AesCbcContext::new(key, nonce)
+            }
+        }
+
+        impl<const BLOCKS: usize> CipherOp<$mode> for AesCbcContext<$aes, BLOCKS> {
+            /// Encrypt whole blocks in CBC mode, without padding.
+            fn encrypt(
+                &mut self,
+                mut plaintext: Self::PlainText,
+            ) -> Result<Self::CipherText, Self::Error> {
+                for block in plaintext.iter_mut() {
+                    self.encrypt_block(block);
+                }
+                Ok(plaintext)
+            }
+
+            /// Decrypt whole blocks in CBC mode, without removing padding.
+            fn decrypt(
+                &mut self,
+                mut ciphertext: Self::CipherText,
+            ) -> Result<Self::PlainText, Self::Error> {
+                for block in ciphertext.iter_mut() {
+                    self.decrypt_block(block);
+                }
+                Ok(ciphertext)
+            }
+        }
+    };
+}
+
+impl_aes_cbc!(Aes128CbcCipher, Aes128CbcMode, Aes128);
+impl_aes_cbc!(Aes192CbcCipher, Aes192CbcMode, Aes192);
+impl_aes_cbc!(Aes256CbcCipher, Aes256CbcMode, Aes256);
+
+macro_rules! impl_aes_ecb {
+    ($cipher:ident, $mode:ident, $aes:ty) => {
+        impl<const BLOCKS: usize> CipherInit<$mode> for $cipher<BLOCKS> {
+            type CipherContext<'a> = AesEcbContext<$aes, BLOCKS>;
+
+            fn init<'a>(
+                &'a mut self,
+                key: &Self::Key,
+                _nonce: &Self::Nonce,
+                _mode: $mode,
+            ) -> Result<Self::CipherContext<'a>, Self::Error> {
+                AesEcbContext::new(key)
+            }
+        }
+
+        impl<const BLOCKS: usize> CipherOp<$mode> for AesEcbContext<$aes, BLOCKS> {
+            fn encrypt(
+                &mut self,
+                mut plaintext: Self::PlainText,
+            ) -> Result<Self::CipherText, Self::Error> {
+                for block in plaintext.iter_mut() {
+                    self.cipher
+                        .encrypt_block(GenericArray::from_mut_slice(block));
+                }
+                Ok(plaintext)
+            }
+
+            fn decrypt(
+                &mut self,
+                mut ciphertext: Self::CipherText,
+            ) -> Result<Self::PlainText, Self::Error> {
+                for block in ciphertext.iter_mut() {
+                    self.cipher
+                        .decrypt_block(GenericArray::from_mut_slice(block));
+                }
+                Ok(ciphertext)
+            }
+        }
+    };
+}
+
+impl_aes_ecb!(Aes128EcbCipher, Aes128EcbMode, Aes128);
+impl_aes_ecb!(Aes192EcbCipher, Aes192EcbMode, Aes192);
+impl_aes_ecb!(Aes256EcbCipher, Aes256EcbMode, Aes256);
+
+//
+// AEAD Implementation (AES-GCM, ChaCha20-Poly1305) using RustCrypto
+//
+
+/// AEAD context for RustCrypto ciphers with a 96-bit nonce and 128-bit tag.
+///
+/// Used as [`AesGcmContext`] (NIST SP 800-38D) and
+/// [`ChaCha20Poly1305Context`] (RFC 8439). `LEN` is the size of the plaintext
+/// and ciphertext buffers and `AAD_LEN` the size of the associated data.
+/// [`AeadCipherOp`] returns the 16-byte tag separately from the ciphertext.
+///
+/// The nonce is consumed by the first encryption; further encryptions fail
+/// with `InvalidState` until a fresh nonce is installed with [`set_nonce`].
+///
+/// [`CipherOp`], which is what [`CipherInit`] hands out, has no room for a
+/// tag or associated data. Through it the context authenticates an empty
+/// AAD, keeps the tag of the last encryption for [`tag`], and checks
+/// decryptions against the tag installed with [`set_tag`].
+///
+/// [`set_nonce`]: AeadContext::set_nonce
+/// [`tag`]: AeadContext::tag
+/// [`set_tag`]: AeadContext::set_tag
+pub struct AeadContext<C, const LEN: usize = 256, const AAD_LEN: usize = 0> {
+    /// The underlying RustCrypto AEAD cipher
+    cipher: C,
+    /// 96-bit nonce for the next operation
+    nonce: [u8; 12],
+    /// Whether the nonce has already been used for encryption
+    nonce_used: bool,
+    /// Tag produced by, or expected for, the last `CipherOp` call
+    tag: [u8; 16],
+}
+
+/// AES-GCM context; `C` selects the key size.
+pub type AesGcmContext<C, const LEN: usize = 256, const AAD_LEN: usize = 0> =
+    AeadContext<C, LEN, AAD_LEN>;
+
+/// ChaCha20-Poly1305 context (RFC 8439).
+pub type ChaCha20Poly1305Context<const LEN: usize = 256, const AAD_LEN: usize = 0> =
+    AeadContext<ChaCha20Poly1305, LEN, AAD_LEN>;
+
+impl<C, const LEN: usize, const AAD_LEN: usize> AeadContext<C, LEN, AAD_LEN>
+where
+    C: KeyInit + AeadInPlace<NonceSize = U12, TagSize = U16>,
+{
+    /// Create a new AEAD context with the given key and nonce.
+    ///
+    /// # Errors
+    /// - `InvalidKey`: If the key length does not match the cipher
+    pub fn new(key: &[u8], nonce: &[u8; 12]) -> Result<Self, RustCryptoCipherError> {
+        let cipher = C::new_from_slice(key).map_err(|_| RustCryptoCipherError::InvalidKey)?;
+        Ok(Self {
+            cipher,
+            nonce: *nonce,
+            nonce_used: false,
+            tag: [0; 16],
+        })
+    }
+
+    /// Install the nonce for the next operation.
+    ///
+    /// # Security Note
+    /// A nonce must never be reused with the same key.
+    pub fn set_nonce(&mut self, nonce: &[u8; 12]) {
+        self.nonce = *nonce;
+        self.nonce_used = false;
+    }
+
+    /// Tag produced by the last [`CipherOp::encrypt`] call.
+    pub fn tag(&self) -> [u8; 16] {
+        self.tag
+    }
+
+    /// Install the tag that the next [`CipherOp::decrypt`] call must verify.
+    pub fn set_tag(&mut self, tag: &[u8; 16]) {
+        self.tag = *tag;
+    }
+
+    fn seal(&mut self, data: &mut [u8], aad: &[u8]) -> Result<[u8; 16], RustCryptoCipherError> {
+        if self.nonce_used {
+            return Err(RustCryptoCipherError::InvalidState);
+        }
+        self.nonce_used = true;
+
+        let tag = self
+            .cipher
+            .encrypt_in_place_detached(GenericArray::from_slice(&self.nonce), aad, data)
+            .map_err(|_| RustCryptoCipherError::MessageTooLarge)?;
+        Ok(tag.into())
+    }
+
+    fn open(
+        &mut self,
+        data: &mut [u8],
+        aad: &[u8],
+        tag: &[u8; 16],
+    ) -> Result<(), RustCryptoCipherError> {
+        self.cipher
+            .decrypt_in_place_detached(
+                GenericArray::from_slice(&self.nonce),
+                aad,
+                data,
+                GenericArray::from_slice(tag),
+            )
+            .map_err(|_| RustCryptoCipherError::AuthenticationFailed)
+    }
+}
+
+macro_rules! impl_aead {
+    ($cipher:ident, $mode:ident, $aead:ty, $key_len:expr) => {
+        impl CipherInit<$mode> for $cipher {
+            type CipherContext<'a> = AeadContext<$aead>;
+
+            fn init<'a>(
+                &'a mut self,
+                key: &Self::Key,
+                nonce: &Self::Nonce,
+                _mode: $mode,
+            ) -> Result<Self::CipherContext<'a>, Self::Error> {
+                AeadContext::new(key, nonce)
+            }
+        }
+
+        impl<const LEN: usize, const AAD_LEN: usize> ErrorType
+            for AeadContext<$aead, LEN, AAD_LEN>
+        {
+            type Error = RustCryptoCipherError;
+        }
+
+        impl<const LEN: usize, const AAD_LEN: usize> SymmetricCipher
+            for AeadContext<$aead, LEN, AAD_LEN>
+        {
+            type Key = [u8; $key_len];
+            type Nonce = [u8; 12];
+            type PlainText = [u8; LEN];
+            type CipherText = [u8; LEN];
+        }
+
+        impl<const LEN: usize, const AAD_LEN: usize> CipherOp<$mode>
+            for AeadContext<$aead, LEN, AAD_LEN>
+        {
+            fn encrypt(
+                &mut self,
+                mut plaintext: Self::PlainText,
+            ) -> Result<Self::CipherText, Self::Error> {
+                self.tag = self.seal(&mut plaintext, &[])?;
+                Ok(plaintext)
+            }
+
+            fn decrypt(
+                &mut self,
+                mut ciphertext: Self::CipherText,
+            ) -> Result<Self::PlainText, Self::Error> {
+                let tag = self.tag;
+                self.open(&mut ciphertext, &[], &tag)?;
+                Ok(ciphertext)
+            }
+        }
+
+        impl<const LEN: usize, const AAD_LEN: usize> AeadCipherOp
+            for AeadContext<$aead, LEN, AAD_LEN>
+        {
+            type AssociatedData = [u8; AAD_LEN];
+            type Tag = [u8; 16];
+
+            fn encrypt_aead(
+                &mut self,
+                mut plaintext: Self::PlainText,
+                associated_data: Self::AssociatedData,
+            ) -> Result<(Self::CipherText, Self::Tag), Self::Error> {
+                let tag = self.seal(&mut plaintext, &associated_data)?;
+                Ok((plaintext, tag))
+            }
+
+            /// Verify `tag` and decrypt `ciphertext`.
+            ///
+            /// # Errors
+            /// - `AuthenticationFailed`: If the tag does not match the ciphertext
+            ///   and associated data. No plaintext is released in that case.
+            fn decrypt_aead(
+                &mut self,
+                mut ciphertext: Self::CipherText,
+                associated_data: Self::AssociatedData,
+                tag: Self::Tag,
+            ) -> Result<Self::PlainText, Self::Error> {
+                self.open(&mut ciphertext, &associated_data, &tag)?;
+                Ok(ciphertext)
+            }
+        }
+    };
+}
+
+impl_aead!(Aes128GcmCipher, Aes128GcmMode, Aes128Gcm, 16);
+impl_aead!(Aes256GcmCipher, Aes256GcmMode, Aes256Gcm, 32);
+impl_aead!(
+    ChaCha20Poly1305Cipher,
+    ChaCha20Poly1305Mode,
+    ChaCha20Poly1305,
+    32
+);
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used)] // Allow unwrap in tests for cleaner test code
 mod tests {
     use super::*;
-    use openprot_hal_blocking::cipher::BlockAligned;
+    use hex_literal::hex;
 
     // Test vectors for AES-256-CTR mode
     const TEST_KEY: [u8; 32] = [
@@ -581,4 +1281,497 @@ mod tests {
             "Container should have correct capacity"
         );
     }
+
+    // NIST SP 800-38A, Appendix F: plaintext shared by the ECB and CBC vectors
+    const SP800_38A_PLAINTEXT: [u8; 64] = hex!(
+        "6bc1bee22e409f96e93d7e117393172a"
+        "ae2d8a571e03ac9c9eb76fac45af8e51"
+        "30c81c46a35ce411e5fbc1191a0a52ef"
+        "f69f2445df4f9b17ad2b417be66c3710"
+    );
+    const SP800_38A_KEY_128: [u8; 16] = hex!("2b7e151628aed2a6abf7158809cf4f3c");
+    const SP800_38A_KEY_192: [u8; 24] = hex!("8e73b0f7da0e6452c810f32b809079e562f8ead2522c6b7b");
+    const SP800_38A_KEY_256: [u8; 32] =
+        hex!("603deb1015ca71be2b73aef0857d77811f352c073b6108d72d9810a30914dff4");
+    const SP800_38A_CBC_IV: [u8; 16] = hex!("000102030405060708090a0b0c0d0e0f");
+
+    fn sp800_38a_blocks(data: &[u8; 64]) -> BlockAligned<16, 4> {
+        BlockAligned::from_slice_padded(data, 0x00).unwrap()
+    }
+
+    fn flatten(blocks: &BlockAligned<16, 4>) -> [u8; 64] {
+        let mut out = [0u8; 64];
+        for (chunk, block) in out.chunks_mut(16).zip(blocks.iter_blocks()) {
+            chunk.copy_from_slice(block);
+        }
+        out
+    }
+
+    fn check_ecb<C: AesBlockCipher>(key: &[u8], expected: &[u8; 64]) {
+        let mut ctx = AesEcbContext::<C>::new(key).unwrap();
+        let ct = ctx
+            .encrypt_blocks(&sp800_38a_blocks(&SP800_38A_PLAINTEXT))
+            .unwrap();
+        assert_eq!(&flatten(&ct), expected);
+        let pt = ctx.decrypt_blocks(&ct).unwrap();
+        assert_eq!(flatten(&pt), SP800_38A_PLAINTEXT);
+    }
+
+    fn check_cbc<C: AesBlockCipher>(key: &[u8], expected: &[u8; 64]) {
+        let mut ctx = AesCbcContext::<C>::new(key, &SP800_38A_CBC_IV).unwrap();
+        let ct = ctx
+            .encrypt_blocks(&sp800_38a_blocks(&SP800_38A_PLAINTEXT))
+            .unwrap();
+        assert_eq!(&flatten(&ct), expected);
+
+        let mut ctx = AesCbcContext::<C>::new(key, &SP800_38A_CBC_IV).unwrap();
+        let pt = ctx.decrypt_blocks(&ct).unwrap();
+        assert_eq!(flatten(&pt), SP800_38A_PLAINTEXT);
+    }
+
+    #[test]
+    fn test_sp800_38a_ecb_vectors() {
+        // F.1.1 / F.1.3 / F.1.5
+        check_ecb::<Aes128>(
+            &SP800_38A_KEY_128,
+            &hex!(
+                "3ad77bb40d7a3660a89ecaf32466ef97"
+                "f5d3d58503b9699de785895a96fdbaaf"
+                "43b1cd7f598ece23881b00e3ed030688"
+                "7b0c785e27e8ad3f8223207104725dd4"
+            ),
+        );
+        check_ecb::<Aes192>(
+            &SP800_38A_KEY_192,
+            &hex!(
+                "bd334f1d6e45f25ff712a214571fa5cc"
+                "974104846d0ad3ad7734ecb3ecee4eef"
+                "ef7afd2270e2e60adce0ba2face6444e"
+                "9a4b41ba738d6c72fb16691603c18e0e"
+            ),
+        );
+        check_ecb::<Aes256>(
+            &SP800_38A_KEY_256,
+            &hex!(
+                "f3eed1bdb5d2a03c064b5a7e3db181f8"
+                "591ccb10d410ed26dc5ba74a31362870"
+                "b6ed21b99ca6f4f9f153e7b1beafed1d"
+                "23304b7a39f9f3ff067d8d8f9e24ecc7"
+            ),
+        );
+    }
+
+    #[test]
+    fn test_sp800_38a_cbc_vectors() {
+        // F.2.1 / F.2.3 / F.2.5
+        check_cbc::<Aes128>(
+            &SP800_38A_KEY_128,
+            &hex!(
+                "7649abac8119b246cee98e9b12e9197d"
+                "5086cb9b507219ee95db113a917678b2"
+                "73bed6b8e3c1743b7116e69e22229516"
+                "3ff1caa1681fac09120eca307586e1a7"
+            ),
+        );
+        check_cbc::<Aes192>(
+            &SP800_38A_KEY_192,
+            &hex!(
+                "4f021db243bc633d7178183a9fa071e8"
+                "b4d9ada9ad7dedf4e5e738763f69145a"
+                "571b242012fb7ae07fa9baac3df102e0"
+                "08b0e27988598881d920a9e64f5615cd"
+            ),
+        );
+        check_cbc::<Aes256>(
+            &SP800_38A_KEY_256,
+            &hex!(
+                "f58c4c04d6e5f1ba779eabfb5f7bfbd6"
+                "9cfc4e967edb808d679f777bc6702c7d"
+                "39f23369a9d9bacfa530e26304231461"
+                "b2eb05e2c39be9fcda6c19078c6a9d1b"
+            ),
+        );
+    }
+
+    /// Split the SP 800-38A plaintext into `N`-block chunks.
+    fn sp800_38a_chunks<const N: usize>() -> impl Iterator<Item = [[u8; 16]; N]> {
+        SP800_38A_PLAINTEXT.chunks(16 * N).map(|chunk| {
+            let mut blocks = [[0u8; 16]; N];
+            for (block, bytes) in blocks.iter_mut().zip(chunk.chunks(16)) {
+                block.copy_from_slice(bytes);
+            }
+            blocks
+        })
+    }
+
+    #[test]
+    fn test_cbc_chaining_across_cipher_op_calls() {
+        // Encrypting in two-block CipherOp calls must equal one continuous
+        // CBC pass: SP 800-38A F.2.1
+        let expected = hex!(
+            "7649abac8119b246cee98e9b12e9197d"
+            "5086cb9b507219ee95db113a917678b2"
+            "73bed6b8e3c1743b7116e69e22229516"
+            "3ff1caa1681fac09120eca307586e1a7"
+        );
+
+        let mut cipher = Aes128CbcCipher::<2>;
+        let mut ctx = cipher
+            .init(&SP800_38A_KEY_128, &SP800_38A_CBC_IV, Aes128CbcMode)
+            .unwrap();
+        let mut ciphertext = [[[0u8; 16]; 2]; 2];
+        for (out, chunk) in ciphertext.iter_mut().zip(sp800_38a_chunks::<2>()) {
+            *out = ctx.encrypt(chunk).unwrap();
+        }
+        assert_eq!(ciphertext.as_flattened().as_flattened(), expected);
+
+        let mut ctx = cipher
+            .init(&SP800_38A_KEY_128, &SP800_38A_CBC_IV, Aes128CbcMode)
+            .unwrap();
+        for (ct, pt) in ciphertext.into_iter().zip(sp800_38a_chunks::<2>()) {
+            assert_eq!(ctx.decrypt(ct).unwrap(), pt);
+        }
+    }
+
+    #[test]
+    fn test_cbc_pkcs7_roundtrip() {
+        for len in [0usize, 1, 15, 16, 17, 47] {
+            let message = &SP800_38A_PLAINTEXT[..len];
+            let mut ctx =
+                AesCbcContext::<Aes256>::new(&SP800_38A_KEY_256, &SP800_38A_CBC_IV).unwrap();
+            let ct = ctx.encrypt_padded::<4>(message).unwrap();
+            assert_eq!(ct.block_count(), len / 16 + 1, "len {len}");
+
+            let mut ctx =
+                AesCbcContext::<Aes256>::new(&SP800_38A_KEY_256, &SP800_38A_CBC_IV).unwrap();
+            let mut out = [0u8; 64];
+            let recovered = ctx.decrypt_padded(&ct, &mut out).unwrap();
+            assert_eq!(recovered, message, "len {len}");
+        }
+    }
+
+    #[test]
+    fn test_pkcs7_errors() {
+        // A block-aligned message needs a full extra padding block
+        assert_eq!(
+            pkcs7_pad::<4>(&[0u8; 64]).err(),
+            Some(RustCryptoCipherError::MessageTooLarge)
+        );
+
+        let mut out = [0u8; 64];
+        let empty = BlockAligned::<16, 4>::new();
+        assert_eq!(
+            pkcs7_unpad(&empty, &mut out).err(),
+            Some(RustCryptoCipherError::InvalidPadding)
+        );
+
+        let mut bad_block = [0x03u8; 16];
+        bad_block[15] = 0x00;
+        let mut bad = BlockAligned::<16, 4>::new();
+        bad.push_block(bad_block).unwrap();
+        assert_eq!(
+            pkcs7_unpad(&bad, &mut out).err(),
+            Some(RustCryptoCipherError::InvalidPadding)
+        );
+
+        let mut inconsistent = [0x04u8; 16];
+        inconsistent[13] = 0x05;
+        let mut bad = BlockAligned::<16, 4>::new();
+        bad.push_block(inconsistent).unwrap();
+        assert_eq!(
+            pkcs7_unpad(&bad, &mut out).err(),
+            Some(RustCryptoCipherError::InvalidPadding)
+        );
+
+        let padded = pkcs7_pad::<4>(&[0xAA; 20]).unwrap();
+        let mut small = [0u8; 19];
+        assert_eq!(
+            pkcs7_unpad(&padded, &mut small).err(),
+            Some(RustCryptoCipherError::MessageTooLarge)
+        );
+        assert_eq!(
+            RustCryptoCipherError::InvalidPadding.kind(),
+            ErrorKind::InvalidInput
+        );
+    }
+
+    #[test]
+    fn test_ecb_cipher_op_matches_blocks() {
+        let mut cipher = Aes192EcbCipher::<1>;
+        let mut ctx = cipher.init(&SP800_38A_KEY_192, &[], Aes192EcbMode).unwrap();
+        let plaintext = sp800_38a_chunks::<1>().next().unwrap();
+        let ct = ctx.encrypt(plaintext).unwrap();
+        assert_eq!(
+            ct,
+            [hex!("bd334f1d6e45f25ff712a214571fa5cc")],
+            "SP 800-38A F.1.3 block 1"
+        );
+        assert_eq!(ctx.decrypt(ct).unwrap(), plaintext);
+
+        // The default cipher processes 16 blocks per call
+        let mut cipher: Aes192EcbCipher = Aes192EcbCipher;
+        let mut ctx = cipher.init(&SP800_38A_KEY_192, &[], Aes192EcbMode).unwrap();
+        let blocks = [[0x5Au8; 16]; 16];
+        let ct = ctx.encrypt(blocks).unwrap();
+        assert_eq!(ctx.decrypt(ct).unwrap(), blocks);
+    }
+
+    #[test]
+    fn test_block_mode_key_length_validation() {
+        assert_eq!(
+            AesEcbContext::<Aes128>::new(&SP800_38A_KEY_256).err(),
+            Some(RustCryptoCipherError::InvalidKey)
+        );
+        assert!(AesCbcContext::<Aes192>::new(&SP800_38A_KEY_128, &SP800_38A_CBC_IV).is_err());
+    }
+
+    // GCM specification (McGrew/Viega), test cases 4 and 16: 60-byte
+    // plaintext with 20 bytes of associated data, as published in the NIST
+    // GCM validation material.
+    const GCM_KEY_128: [u8; 16] = hex!("feffe9928665731c6d6a8f9467308308");
+    const GCM_KEY_256: [u8; 32] =
+        hex!("feffe9928665731c6d6a8f9467308308feffe9928665731c6d6a8f9467308308");
+    const GCM_IV: [u8; 12] = hex!("cafebabefacedbaddecaf888");
+    const GCM_AAD: [u8; 20] = hex!("feedfacedeadbeeffeedfacedeadbeefabaddad2");
+    const GCM_PLAINTEXT: [u8; 60] = hex!(
+        "d9313225f88406e5a55909c5aff5269a"
+        "86a7a9531534f7da2e4c303d8a318a72"
+        "1c3c0c95956809532fcf0e2449a6b525"
+        "b16aedf5aa0de657ba637b39"
+    );
+    const GCM_TC4_CIPHERTEXT: [u8; 60] = hex!(
+        "42831ec2217774244b7221b784d0d49c"
+        "e3aa212f2c02a4e035c17e2329aca12e"
+        "21d514b25466931c7d8f6a5aac84aa05"
+        "1ba30b396a0aac973d58e091"
+    );
+    const GCM_TC4_TAG: [u8; 16] = hex!("5bc94fbc3221a5db94fae95ae7121a47");
+    const GCM_TC16_CIPHERTEXT: [u8; 60] = hex!(
+        "522dc1f099567d07f47f37a32a84427d"
+        "643a8cdcbfe5c0c97598a2bd2555d1aa"
+        "8cb08e48590dbb3da7b08b1056828838"
+        "c5f61e6393ba7a0abcc9f662"
+    );
+    const GCM_TC16_TAG: [u8; 16] = hex!("76fc6ece0f4e1768cddf8853bb2d551b");
+
+    type GcmKatContext<C> = AesGcmContext<C, 60, 20>;
+
+    #[test]
+    fn test_aes128_gcm_kat() {
+        let mut ctx = GcmKatContext::<Aes128Gcm>::new(&GCM_KEY_128, &GCM_IV).unwrap();
+        let (ct, tag) = ctx.encrypt_aead(GCM_PLAINTEXT, GCM_AAD).unwrap();
+        assert_eq!(ct, GCM_TC4_CIPHERTEXT);
+        assert_eq!(tag, GCM_TC4_TAG);
+
+        let pt = ctx
+            .decrypt_aead(GCM_TC4_CIPHERTEXT, GCM_AAD, GCM_TC4_TAG)
+            .unwrap();
+        assert_eq!(pt, GCM_PLAINTEXT);
+    }
+
+    #[test]
+    fn test_aes256_gcm_kat() {
+        let mut ctx = GcmKatContext::<Aes256Gcm>::new(&GCM_KEY_256, &GCM_IV).unwrap();
+        let (ct, tag) = ctx.encrypt_aead(GCM_PLAINTEXT, GCM_AAD).unwrap();
+        assert_eq!(ct, GCM_TC16_CIPHERTEXT);
+        assert_eq!(tag, GCM_TC16_TAG);
+
+        let pt = ctx
+            .decrypt_aead(GCM_TC16_CIPHERTEXT, GCM_AAD, GCM_TC16_TAG)
+            .unwrap();
+        assert_eq!(pt, GCM_PLAINTEXT);
+    }
+
+    #[test]
+    fn test_aes_gcm_rejects_tampering() {
+        let mut ctx = GcmKatContext::<Aes128Gcm>::new(&GCM_KEY_128, &GCM_IV).unwrap();
+
+        let mut tag = GCM_TC4_TAG;
+        tag[15] ^= 0x01;
+        assert_eq!(
+            ctx.decrypt_aead(GCM_TC4_CIPHERTEXT, GCM_AAD, tag).err(),
+            Some(RustCryptoCipherError::AuthenticationFailed)
+        );
+
+        let mut ct = GCM_TC4_CIPHERTEXT;
+        ct[0] ^= 0x80;
+        assert_eq!(
+            ctx.decrypt_aead(ct, GCM_AAD, GCM_TC4_TAG).err(),
+            Some(RustCryptoCipherError::AuthenticationFailed)
+        );
+
+        let mut aad = GCM_AAD;
+        aad[19] ^= 0x01;
+        assert_eq!(
+            ctx.decrypt_aead(GCM_TC4_CIPHERTEXT, aad, GCM_TC4_TAG).err(),
+            Some(RustCryptoCipherError::AuthenticationFailed)
+        );
+    }
+
+    #[test]
+    fn test_aes_gcm_tag_mismatch_error_kind() {
+        let mut ctx = GcmKatContext::<Aes256Gcm>::new(&GCM_KEY_256, &GCM_IV).unwrap();
+        let mut tag = GCM_TC16_TAG;
+        tag[0] ^= 0x01;
+        let err = ctx
+            .decrypt_aead(GCM_TC16_CIPHERTEXT, GCM_AAD, tag)
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::AuthenticationFailed);
+    }
+
+    #[test]
+    fn test_aes_gcm_nonce_single_use() {
+        let mut ctx = GcmKatContext::<Aes256Gcm>::new(&GCM_KEY_256, &GCM_IV).unwrap();
+        ctx.encrypt_aead(GCM_PLAINTEXT, GCM_AAD).unwrap();
+        assert_eq!(
+            ctx.encrypt_aead(GCM_PLAINTEXT, GCM_AAD).err(),
+            Some(RustCryptoCipherError::InvalidState)
+        );
+
+        ctx.set_nonce(&[0u8; 12]);
+        let (ct, tag) = ctx.encrypt_aead(GCM_PLAINTEXT, GCM_AAD).unwrap();
+        assert_ne!(ct, GCM_TC16_CIPHERTEXT);
+        assert_eq!(ctx.decrypt_aead(ct, GCM_AAD, tag).unwrap(), GCM_PLAINTEXT);
+    }
+
+    #[test]
+    fn test_aes_gcm_cipher_op_through_init() {
+        // GCM specification test case 2: zero key, IV and plaintext, no AAD
+        let mut cipher = Aes128GcmCipher;
+        let mut ctx = cipher.init(&[0u8; 16], &[0u8; 12], Aes128GcmMode).unwrap();
+        let mut plaintext = [0u8; 256];
+        plaintext[16..].fill(0x5A);
+        let ct = ctx.encrypt(plaintext).unwrap();
+        assert_eq!(ct[..16], hex!("0388dace60b6a392f328c2b971b2fe78"));
+        let tag = ctx.tag();
+
+        assert_eq!(ctx.decrypt(ct).unwrap(), plaintext);
+
+        let mut bad_tag = tag;
+        bad_tag[0] ^= 0x01;
+        ctx.set_tag(&bad_tag);
+        assert_eq!(
+            ctx.decrypt(ct).err(),
+            Some(RustCryptoCipherError::AuthenticationFailed)
+        );
+
+        let mut cipher = Aes256GcmCipher;
+        let mut ctx = cipher
+            .init(&SP800_38A_KEY_256, &[0x11; 12], Aes256GcmMode)
+            .unwrap();
+        let ct = ctx.encrypt([0x5Au8; 256]).unwrap();
+        assert_eq!(ctx.decrypt(ct).unwrap(), [0x5Au8; 256]);
+    }
+
+    // RFC 8439, Section 2.8.2: AEAD_CHACHA20_POLY1305 test vector
+    const RFC8439_KEY: [u8; 32] =
+        hex!("808182838485868788898a8b8c8d8e8f909192939495969798999a9b9c9d9e9f");
+    const RFC8439_NONCE: [u8; 12] = hex!("070000004041424344454647");
+    const RFC8439_AAD: [u8; 12] = hex!("50515253c0c1c2c3c4c5c6c7");
+    const RFC8439_PLAINTEXT: &[u8; 114] = b"Ladies and Gentlemen of the class of '99: \
+If I could offer you only one tip for the future, sunscreen would be it.";
+    const RFC8439_CIPHERTEXT: [u8; 114] = hex!(
+        "d31a8d34648e60db7b86afbc53ef7ec2"
+        "a4aded51296e08fea9e2b5a736ee62d6"
+        "3dbea45e8ca9671282fafb69da92728b"
+        "1a71de0a9e060b2905d6a5b67ecd3b36"
+        "92ddbd7f2d778b8c9803aee328091b58"
+        "fab324e4fad675945585808b4831d7bc"
+        "3ff4def08e4b7a9de576d26586cec64b"
+        "6116"
+    );
+    const RFC8439_TAG: [u8; 16] = hex!("1ae10b594f09e26a7e902ecbd0600691");
+
+    type Rfc8439Context = ChaCha20Poly1305Context<114, 12>;
+
+    #[test]
+    fn test_chacha20_poly1305_rfc8439_encrypt() {
+        let mut ctx = Rfc8439Context::new(&RFC8439_KEY, &RFC8439_NONCE).unwrap();
+        let (ct, tag) = ctx.encrypt_aead(*RFC8439_PLAINTEXT, RFC8439_AAD).unwrap();
+        assert_eq!(ct, RFC8439_CIPHERTEXT);
+        assert_eq!(tag, RFC8439_TAG);
+    }
+
+    #[test]
+    fn test_chacha20_poly1305_rfc8439_decrypt() {
+        let mut ctx = Rfc8439Context::new(&RFC8439_KEY, &RFC8439_NONCE).unwrap();
+        let pt = ctx
+            .decrypt_aead(RFC8439_CIPHERTEXT, RFC8439_AAD, RFC8439_TAG)
+            .unwrap();
+        assert_eq!(&pt, RFC8439_PLAINTEXT);
+    }
+
+    #[test]
+    fn test_chacha20_poly1305_tag_mismatch() {
+        let mut ctx = Rfc8439Context::new(&RFC8439_KEY, &RFC8439_NONCE).unwrap();
+
+        let mut tag = RFC8439_TAG;
+        tag[0] ^= 0x01;
+        let err = ctx
+            .decrypt_aead(RFC8439_CIPHERTEXT, RFC8439_AAD, tag)
+            .unwrap_err();
+        assert_eq!(err, RustCryptoCipherError::AuthenticationFailed);
+        assert_eq!(err.kind(), ErrorKind::AuthenticationFailed);
+
+        let mut ct = RFC8439_CIPHERTEXT;
+        ct[113] ^= 0x80;
+        assert_eq!(
+            ctx.decrypt_aead(ct, RFC8439_AAD, RFC8439_TAG).err(),
+            Some(RustCryptoCipherError::AuthenticationFailed)
+        );
+
+        let mut aad = RFC8439_AAD;
+        aad[0] ^= 0x01;
+        assert_eq!(
+            ctx.decrypt_aead(RFC8439_CIPHERTEXT, aad, RFC8439_TAG).err(),
+            Some(RustCryptoCipherError::AuthenticationFailed)
+        );
+    }
+
+    #[test]
+    fn test_chacha20_poly1305_nonce_single_use() {
+        let mut ctx = ChaCha20Poly1305Context::<256>::new(&RFC8439_KEY, &RFC8439_NONCE).unwrap();
+        let plaintext = [0x42u8; 256];
+        let (ct1, tag1) = ctx.encrypt_aead(plaintext, []).unwrap();
+        assert_eq!(
+            ctx.encrypt_aead(plaintext, []).err(),
+            Some(RustCryptoCipherError::InvalidState)
+        );
+
+        ctx.set_nonce(&[0u8; 12]);
+        let (ct2, tag2) = ctx.encrypt_aead(plaintext, []).unwrap();
+        assert_ne!(ct1, ct2);
+        assert_ne!(tag1, tag2);
+        assert_eq!(ctx.decrypt_aead(ct2, [], tag2).unwrap(), plaintext);
+    }
+
+    #[test]
+    fn test_chacha20_poly1305_cipher_init() {
+        let mut cipher = ChaCha20Poly1305Cipher;
+        let mut ctx = cipher
+            .init(&RFC8439_KEY, &RFC8439_NONCE, ChaCha20Poly1305Mode)
+            .unwrap();
+        let plaintext = [0x42u8; 256];
+        let ct = ctx.encrypt(plaintext).unwrap();
+        let tag = ctx.tag();
+
+        // CipherOp authenticates an empty AAD, so it agrees with AeadCipherOp
+        let mut aead = ChaCha20Poly1305Context::<256>::new(&RFC8439_KEY, &RFC8439_NONCE).unwrap();
+        assert_eq!(aead.encrypt_aead(plaintext, []).unwrap(), (ct, tag));
+
+        assert_eq!(ctx.decrypt(ct).unwrap(), plaintext);
+        ctx.set_tag(&[0u8; 16]);
+        assert_eq!(
+            ctx.decrypt(ct).unwrap_err().kind(),
+            ErrorKind::AuthenticationFailed
+        );
+    }
+
+    #[test]
+    fn test_chacha20_poly1305_key_length_validation() {
+        assert_eq!(
+            ChaCha20Poly1305Context::<256>::new(&[0u8; 16], &RFC8439_NONCE).err(),
+            Some(RustCryptoCipherError::InvalidKey)
+        );
+    }
 }
@@ -6,21 +6,34 @@
 //! A Hubris-compatible controller that can handle both digest and MAC requests using RustCrypto implementations.
 //! This serves as a drop-in backend for Hubris digest servers.
 
+use aes_gcm::aead::AeadInPlace;
+use aes_gcm::Aes256Gcm;
 use core::fmt;
 use hmac::{Hmac, Mac as HmacTrait};
 use openprot_hal_blocking::digest::owned::{DigestInit, DigestOp};
+use openprot_hal_blocking::digest::scoped::{
+    DigestInit as ScopedDigestInit, DigestOp as ScopedDigestOp,
+};
 use openprot_hal_blocking::digest::{Digest, Sha2_256, Sha2_384, Sha2_512};
 use openprot_hal_blocking::digest::{
     Error as DigestError, ErrorKind as DigestErrorKind, ErrorType as DigestErrorType,
 };
+use openprot_hal_blocking::digest::{Sha3_224, Sha3_256, Sha3_384, Sha3_512};
+use openprot_hal_blocking::ecdsa::{EcdsaVerify, P384PublicKey, P384Signature, Signature, P384};
 use openprot_hal_blocking::mac::owned::{MacInit, MacOp};
 use openprot_hal_blocking::mac::{
     Error as MacError, ErrorKind as MacErrorKind, ErrorType as MacErrorType, KeyHandle,
 };
 use openprot_hal_blocking::mac::{HmacSha2_256, HmacSha2_384, HmacSha2_512};
-use openprot_platform_traits_hubris::{CryptoSession, HubrisCryptoError, HubrisDigestDevice};
+use openprot_platform_traits_hubris::{
+    CryptoSession, HubrisAeadDevice, HubrisCryptoError, HubrisDigestDevice, HubrisEcdsaP384Device,
+    HubrisSha3DigestDevice, AES_GCM_NONCE_SIZE, AES_GCM_TAG_SIZE, P384_POINT_SIZE,
+    P384_SCALAR_SIZE,
+};
 use sha2::{Digest as Sha2Digest, Sha256, Sha384, Sha512};
 
+use crate::ecdsa::{EcdsaSignDeterministic, P384PrivateKey, RustCryptoEcdsaError};
+
 /// A type implementing RustCrypto-based hash/digest owned traits.
 /// Compatible with Hubris digest server requirements
 /// `RustCrypto`-based cryptographic controller
@@ -260,25 +273,7 @@ impl DigestOp for DigestContext256 {
     }
 
     fn finalize(self) -> Result<(Self::Output, Self::Controller), Self::Error> {
-        let result = self.0.finalize();
-
-        // Convert SHA-256 output (32 bytes) to Digest<8> (8 x 32-bit words)
-        let mut words = [0u32; 8];
-
-        // Safe iteration with proper bounds checking
-        for (i, chunk) in result.chunks_exact(4).enumerate().take(8) {
-            if let Ok(bytes) = chunk.try_into() {
-                if let Some(word) = words.get_mut(i) {
-                    *word = u32::from_le_bytes(bytes);
-                } else {
-                    return Err(CryptoError::OperationFailed);
-                }
-            } else {
-                return Err(CryptoError::OperationFailed);
-            }
-        }
-
-        let digest = Digest::new(words);
+        let digest = digest_from_bytes(&self.0.finalize())?;
         Ok((digest, RustCryptoController::new()))
     }
 
@@ -302,25 +297,7 @@ impl DigestOp for DigestContext384 {
     }
 
     fn finalize(self) -> Result<(Self::Output, Self::Controller), Self::Error> {
-        let result = self.0.finalize();
-
-        // Convert SHA-384 output (48 bytes) to Digest<12> (12 x 32-bit words)
-        let mut words = [0u32; 12];
-
-        // Safe iteration with proper bounds checking
-        for (i, chunk) in result.chunks_exact(4).enumerate().take(12) {
-            if let Ok(bytes) = chunk.try_into() {
-                if let Some(word) = words.get_mut(i) {
-                    *word = u32::from_le_bytes(bytes);
-                } else {
-                    return Err(CryptoError::OperationFailed);
-                }
-            } else {
-                return Err(CryptoError::OperationFailed);
-            }
-        }
-
-        let digest = Digest::new(words);
+        let digest = digest_from_bytes(&self.0.finalize())?;
         Ok((digest, RustCryptoController::new()))
     }
 
@@ -344,25 +321,7 @@ impl DigestOp for DigestContext512 {
     }
 
     fn finalize(self) -> Result<(Self::Output, Self::Controller), Self::Error> {
-        let result = self.0.finalize();
-
-        // Convert SHA-512 output (64 bytes) to Digest<16> (16 x 32-bit words)
-        let mut words = [0u32; 16];
-
-        // Safe iteration with proper bounds checking
-        for (i, chunk) in result.chunks_exact(4).enumerate().take(16) {
-            if let Ok(bytes) = chunk.try_into() {
-                if let Some(word) = words.get_mut(i) {
-                    *word = u32::from_le_bytes(bytes);
-                } else {
-                    return Err(CryptoError::OperationFailed);
-                }
-            } else {
-                return Err(CryptoError::OperationFailed);
-            }
-        }
-
-        let digest = Digest::new(words);
+        let digest = digest_from_bytes(&self.0.finalize())?;
         Ok((digest, RustCryptoController::new()))
     }
 
@@ -371,6 +330,126 @@ impl DigestOp for DigestContext512 {
     }
 }
 
+/// Packs digest bytes into the word layout of `Digest<N>`, preserving byte order
+pub(crate) fn digest_from_bytes<const N: usize>(bytes: &[u8]) -> Result<Digest<N>, CryptoError> {
+    if bytes.len() != N.saturating_mul(4) {
+        return Err(CryptoError::InvalidOutputLength);
+    }
+
+    let mut words = [0u32; N];
+    for (word, chunk) in words.iter_mut().zip(bytes.chunks_exact(4)) {
+        let chunk: [u8; 4] = chunk.try_into().map_err(|_| CryptoError::OperationFailed)?;
+        *word = u32::from_le_bytes(chunk);
+    }
+    Ok(Digest::new(words))
+}
+
+/// Macro to implement owned and scoped digest contexts for each SHA-3 variant
+macro_rules! impl_sha3_digest {
+    ($algo:ident, $hasher:ty, $context:ident, $scoped:ident, $words:expr) => {
+        /// SHA-3 digest context (owned API)
+        pub struct $context($hasher);
+
+        /// SHA-3 digest context (scoped API)
+        ///
+        /// Borrows the controller for the lifetime of the computation.
+        pub struct $scoped<'a> {
+            hasher: $hasher,
+            _controller: &'a mut RustCryptoController,
+        }
+
+        impl DigestInit<$algo> for RustCryptoController {
+            type Context = $context;
+            type Output = Digest<$words>;
+
+            fn init(self, _algorithm: $algo) -> Result<Self::Context, Self::Error> {
+                Ok($context(<$hasher>::new()))
+            }
+        }
+
+        impl DigestErrorType for $context {
+            type Error = CryptoError;
+        }
+
+        impl DigestOp for $context {
+            type Output = Digest<$words>;
+            type Controller = RustCryptoController;
+
+            fn update(mut self, data: &[u8]) -> Result<Self, Self::Error> {
+                self.0.update(data);
+                Ok(self)
+            }
+
+            fn finalize(self) -> Result<(Self::Output, Self::Controller), Self::Error> {
+                let digest = digest_from_bytes(&self.0.finalize())?;
+                Ok((digest, RustCryptoController::new()))
+            }
+
+            fn cancel(self) -> Self::Controller {
+                RustCryptoController::new()
+            }
+        }
+
+        impl ScopedDigestInit<$algo> for RustCryptoController {
+            type OpContext<'a> = $scoped<'a>;
+            type Output = Digest<$words>;
+
+            fn init(&mut self, _algorithm: $algo) -> Result<Self::OpContext<'_>, Self::Error> {
+                Ok($scoped {
+                    hasher: <$hasher>::new(),
+                    _controller: self,
+                })
+            }
+        }
+
+        impl DigestErrorType for $scoped<'_> {
+            type Error = CryptoError;
+        }
+
+        impl ScopedDigestOp for $scoped<'_> {
+            type Output = Digest<$words>;
+
+            fn update(&mut self, input: &[u8]) -> Result<(), Self::Error> {
+                self.hasher.update(input);
+                Ok(())
+            }
+
+            fn finalize(self) -> Result<Self::Output, Self::Error> {
+                digest_from_bytes(&self.hasher.finalize())
+            }
+        }
+    };
+}
+
+impl_sha3_digest!(
+    Sha3_224,
+    sha3::Sha3_224,
+    DigestContextSha3_224,
+    ScopedDigestContextSha3_224,
+    7
+);
+impl_sha3_digest!(
+    Sha3_256,
+    sha3::Sha3_256,
+    DigestContextSha3_256,
+    ScopedDigestContextSha3_256,
+    8
+);
+impl_sha3_digest!(
+    Sha3_384,
+    sha3::Sha3_384,
+    DigestContextSha3_384,
+    ScopedDigestContextSha3_384,
+    12
+);
+impl_sha3_digest!(
+    Sha3_512,
+    sha3::Sha3_512,
+    DigestContextSha3_512,
+    ScopedDigestContextSha3_512,
+    16
+);
+
 // MAC initialization - creates HMAC-SHA256 context
 impl MacInit<HmacSha2_256> for RustCryptoController {
     type Key = SecureOwnedKey;
@@ -600,6 +679,143 @@ impl HubrisDigestDevice for RustCryptoController {
     }
 }
 
+impl HubrisSha3DigestDevice for RustCryptoController {
+    type DigestContextSha3_224 = DigestContextSha3_224;
+    type DigestContextSha3_256 = DigestContextSha3_256;
+    type DigestContextSha3_384 = DigestContextSha3_384;
+    type DigestContextSha3_512 = DigestContextSha3_512;
+
+    fn init_digest_sha3_224(self) -> Result<Self::DigestContextSha3_224, HubrisCryptoError> {
+        DigestInit::init(self, Sha3_224).map_err(|_| HubrisCryptoError::HardwareFailure)
+    }
+
+    fn init_digest_sha3_256(self) -> Result<Self::DigestContextSha3_256, HubrisCryptoError> {
+        DigestInit::init(self, Sha3_256).map_err(|_| HubrisCryptoError::HardwareFailure)
+    }
+
+    fn init_digest_sha3_384(self) -> Result<Self::DigestContextSha3_384, HubrisCryptoError> {
+        DigestInit::init(self, Sha3_384).map_err(|_| HubrisCryptoError::HardwareFailure)
+    }
+
+    fn init_digest_sha3_512(self) -> Result<Self::DigestContextSha3_512, HubrisCryptoError> {
+        DigestInit::init(self, Sha3_512).map_err(|_| HubrisCryptoError::HardwareFailure)
+    }
+
+    fn init_digest_session_sha3_224(
+        self,
+    ) -> Result<CryptoSession<Self::DigestContextSha3_224, Self>, HubrisCryptoError> {
+        let context = self.init_digest_sha3_224()?;
+        Ok(CryptoSession::new(context, RustCryptoController::new()))
+    }
+
+    fn init_digest_session_sha3_256(
+        self,
+    ) -> Result<CryptoSession<Self::DigestContextSha3_256, Self>, HubrisCryptoError> {
+        let context = self.init_digest_sha3_256()?;
+        Ok(CryptoSession::new(context, RustCryptoController::new()))
+    }
+
+    fn init_digest_session_sha3_384(
+        self,
+    ) -> Result<CryptoSession<Self::DigestContextSha3_384, Self>, HubrisCryptoError> {
+        let context = self.init_digest_sha3_384()?;
+        Ok(CryptoSession::new(context, RustCryptoController::new()))
+    }
+
+    fn init_digest_session_sha3_512(
+        self,
+    ) -> Result<CryptoSession<Self::DigestContextSha3_512, Self>, HubrisCryptoError> {
+        let context = self.init_digest_sha3_512()?;
+        Ok(CryptoSession::new(context, RustCryptoController::new()))
+    }
+}
+
+impl From<RustCryptoEcdsaError> for HubrisCryptoError {
+    fn from(error: RustCryptoEcdsaError) -> Self {
+        match error {
+            RustCryptoEcdsaError::InvalidPrivateKey | RustCryptoEcdsaError::InvalidPublicKey => {
+                HubrisCryptoError::InvalidParameters
+            }
+            RustCryptoEcdsaError::InvalidSignature => HubrisCryptoError::AuthenticationFailed,
+            RustCryptoEcdsaError::KeyGenFailed | RustCryptoEcdsaError::SigningFailed => {
+                HubrisCryptoError::HardwareFailure
+            }
+        }
+    }
+}
+
+/// Hubris P-384 ECDSA; signatures use RFC 6979 deterministic nonces
+impl HubrisEcdsaP384Device for RustCryptoController {
+    fn ecdsa_p384_sign(
+        &mut self,
+        private_key: &[u8; P384_SCALAR_SIZE],
+        digest: &Digest<12>,
+    ) -> Result<[u8; P384_POINT_SIZE], HubrisCryptoError> {
+        let private_key = P384PrivateKey::from_bytes(*private_key)?;
+        let signature =
+            EcdsaSignDeterministic::<P384>::sign_deterministic(self, &private_key, *digest)?;
+
+        let (mut r, mut s) = ([0u8; P384_SCALAR_SIZE], [0u8; P384_SCALAR_SIZE]);
+        signature.coordinates(&mut r, &mut s);
+        let mut encoded = [0u8; P384_POINT_SIZE];
+        encoded[..P384_SCALAR_SIZE].copy_from_slice(&r);
+        encoded[P384_SCALAR_SIZE..].copy_from_slice(&s);
+        Ok(encoded)
+    }
+
+    fn ecdsa_p384_verify(
+        &mut self,
+        public_key: &[u8; P384_POINT_SIZE],
+        digest: &Digest<12>,
+        signature: &[u8; P384_POINT_SIZE],
+    ) -> Result<(), HubrisCryptoError> {
+        let (x, y) = split_p384(public_key);
+        let public_key = P384PublicKey::new(x, y);
+        let (r, s) = split_p384(signature);
+        let signature = P384Signature::new(r, s);
+        EcdsaVerify::<P384>::verify(self, &public_key, *digest, &signature).map_err(Into::into)
+    }
+}
+
+/// Splits `a || b` into its two P-384 scalars.
+fn split_p384(bytes: &[u8; P384_POINT_SIZE]) -> ([u8; P384_SCALAR_SIZE], [u8; P384_SCALAR_SIZE]) {
+    let (mut a, mut b) = ([0u8; P384_SCALAR_SIZE], [0u8; P384_SCALAR_SIZE]);
+    a.copy_from_slice(&bytes[..P384_SCALAR_SIZE]);
+    b.copy_from_slice(&bytes[P384_SCALAR_SIZE..]);
+    (a, b)
+}
+
+/// Hubris AES-256-GCM (NIST SP 800-38D)
+impl HubrisAeadDevice for RustCryptoController {
+    fn aes256_gcm_encrypt(
+        &mut self,
+        key: &[u8; 32],
+        nonce: &[u8; AES_GCM_NONCE_SIZE],
+        associated_data: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<[u8; AES_GCM_TAG_SIZE], HubrisCryptoError> {
+        let cipher: Aes256Gcm = aes_gcm::aead::KeyInit::new(key.into());
+        let tag = cipher
+            .encrypt_in_place_detached(nonce.into(), associated_data, buffer)
+            .map_err(|_| HubrisCryptoError::InvalidParameters)?;
+        Ok(tag.into())
+    }
+
+    fn aes256_gcm_decrypt(
+        &mut self,
+        key: &[u8; 32],
+        nonce: &[u8; AES_GCM_NONCE_SIZE],
+        associated_data: &[u8],
+        buffer: &mut [u8],
+        tag: &[u8; AES_GCM_TAG_SIZE],
+    ) -> Result<(), HubrisCryptoError> {
+        let cipher: Aes256Gcm = aes_gcm::aead::KeyInit::new(key.into());
+        cipher
+            .decrypt_in_place_detached(nonce.into(), associated_data, buffer, tag.into())
+            .map_err(|_| HubrisCryptoError::AuthenticationFailed)
+    }
+}
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
 mod tests {
@@ -1030,4 +1246,239 @@ mod tests {
         // ✅ Compatible with both digest and MAC operations
         // ✅ Simulates real hardware controller constraints
     }
+
+    #[test]
+    fn test_sha3_owned_known_answers() {
+        use hex_literal::hex;
+
+        let controller = RustCryptoController::new();
+        let context = DigestInit::<Sha3_224>::init(controller, Sha3_224).unwrap();
+        let (digest, controller) = context.update(b"abc").unwrap().finalize().unwrap();
+        assert_eq!(
+            digest.as_bytes(),
+            hex!("e642824c3f8cf24ad09234ee7d3c766fc9a3a5168d0c94ad73b46fdf")
+        );
+
+        let context = DigestInit::<Sha3_256>::init(controller, Sha3_256).unwrap();
+        let (digest, controller) = context.update(b"abc").unwrap().finalize().unwrap();
+        assert_eq!(
+            digest.as_bytes(),
+            hex!("3a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532")
+        );
+
+        let context = DigestInit::<Sha3_384>::init(controller, Sha3_384).unwrap();
+        let (digest, controller) = context.update(b"abc").unwrap().finalize().unwrap();
+        assert_eq!(
+            digest.as_bytes(),
+            hex!(
+                "ec01498288516fc926459f58e2c6ad8df9b473cb0fc08c2596da7cf0e49be4b2"
+                "98d88cea927ac7f539f1edf228376d25"
+            )
+        );
+
+        let context = DigestInit::<Sha3_512>::init(controller, Sha3_512).unwrap();
+        let (digest, _controller) = context.update(b"abc").unwrap().finalize().unwrap();
+        assert_eq!(
+            digest.as_bytes(),
+            hex!(
+                "b751850b1a57168a5693cd924b6b096e08f621827444f70d884f5d0240d2712e"
+                "10e116e9192af3c91a7ec57647e3934057340b4cf408d5a56592f8274eec53f0"
+            )
+        );
+    }
+
+    #[test]
+    fn test_sha3_scoped_known_answers() {
+        use hex_literal::hex;
+
+        let mut controller = RustCryptoController::new();
+
+        // Empty message, no updates
+        let context = ScopedDigestInit::<Sha3_224>::init(&mut controller, Sha3_224).unwrap();
+        assert_eq!(
+            context.finalize().unwrap().as_bytes(),
+            hex!("6b4e03423667dbb73b6e15454f0eb1abd4597f9a1b078e3f5b5a6bc7")
+        );
+
+        let context = ScopedDigestInit::<Sha3_256>::init(&mut controller, Sha3_256).unwrap();
+        assert_eq!(
+            context.finalize().unwrap().as_bytes(),
+            hex!("a7ffc6f8bf1ed76651c14756a061d662f580ff4de43b49fa82d80a4b80f8434a")
+        );
+
+        let context = ScopedDigestInit::<Sha3_384>::init(&mut controller, Sha3_384).unwrap();
+        assert_eq!(
+            context.finalize().unwrap().as_bytes(),
+            hex!(
+                "0c63a75b845e4f7d01107d852e4c2485c51a50aaaa94fc61995e71bbee983a2a"
+                "c3713831264adb47fb6bd1e058d5f004"
+            )
+        );
+
+        let context = ScopedDigestInit::<Sha3_512>::init(&mut controller, Sha3_512).unwrap();
+        assert_eq!(
+            context.finalize().unwrap().as_bytes(),
+            hex!(
+                "a69f73cca23a9ac5c8b567dc185a756e97c982164fe25859e0d1dcc1475c80a6"
+                "15b2123af1f5f94c11e3e9402c3ac558f500199d95b6d3e301758586281dcd26"
+            )
+        );
+
+        // 448-bit message split across several updates
+        let mut context = ScopedDigestInit::<Sha3_256>::init(&mut controller, Sha3_256).unwrap();
+        context.update(b"abcdbcdecdefdefgefghfghighij").unwrap();
+        context.update(b"hijkijkljklmklmnlmnomnopnopq").unwrap();
+        assert_eq!(
+            context.finalize().unwrap().as_bytes(),
+            hex!("41c0dba2a9d6240849100376a8235e2c82e1b9998a999e21db32dd97496d3376")
+        );
+    }
+
+    #[test]
+    fn test_hubris_sha3_oneshot_correctness() {
+        use hex_literal::hex;
+        use openprot_platform_traits_hubris::HubrisSha3DigestOneShot;
+
+        let result = RustCryptoController::new()
+            .digest_sha3_256_oneshot(b"abc")
+            .unwrap();
+        assert_eq!(
+            result.as_bytes(),
+            hex!("3a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532")
+        );
+
+        let result = RustCryptoController::new()
+            .digest_sha3_384_oneshot(b"")
+            .unwrap();
+        assert_eq!(
+            result.as_bytes(),
+            hex!(
+                "0c63a75b845e4f7d01107d852e4c2485c51a50aaaa94fc61995e71bbee983a2a"
+                "c3713831264adb47fb6bd1e058d5f004"
+            )
+        );
+
+        assert_eq!(
+            RustCryptoController::new()
+                .digest_sha3_224_oneshot(b"abc")
+                .unwrap()
+                .as_bytes()
+                .len(),
+            28
+        );
+        assert_eq!(
+            RustCryptoController::new()
+                .digest_sha3_512_oneshot(b"abc")
+                .unwrap()
+                .as_bytes()
+                .len(),
+            64
+        );
+    }
+
+    #[test]
+    fn test_hubris_sha3_384_session() {
+        use hex_literal::hex;
+
+        let session = RustCryptoController::new()
+            .init_digest_session_sha3_384()
+            .unwrap();
+        let session = session.update(b"a").unwrap().update(b"bc").unwrap();
+        let (digest, controller) = session.finalize().unwrap();
+        assert_eq!(
+            digest.as_bytes(),
+            hex!(
+                "ec01498288516fc926459f58e2c6ad8df9b473cb0fc08c2596da7cf0e49be4b2"
+                "98d88cea927ac7f539f1edf228376d25"
+            )
+        );
+
+        // The recovered controller starts another session
+        let session = controller.init_digest_session_sha3_256().unwrap();
+        assert!(session.finalize().is_ok());
+    }
+
+    #[test]
+    fn test_hubris_ecdsa_p384() {
+        use hex_literal::hex;
+        use openprot_platform_traits_hubris::HubrisDigestOneShot;
+
+        // RFC 6979 Appendix A.2.6, P-384 with SHA-384
+        let private_key = hex!(
+            "6b9d3dad2e1b8c1c05b19875b6659f4de23c3b667bf297ba9aa47740787137d8"
+            "96d5724e4c70a825f872c9ea60d2edf5"
+        );
+        let public_key = hex!(
+            "ec3a4e415b4e19a4568618029f427fa5da9a8bc4ae92e02e06aae5286b300c64"
+            "def8f0ea9055866064a254515480bc13"
+            "8015d9b72d7d57244ea8ef9ac0c621896708a59367f9dfb9f54ca84b3f1c9db1"
+            "288b231c3ae0d4fe7344fd2533264720"
+        );
+        let digest = RustCryptoController::new()
+            .digest_sha384_oneshot(b"sample")
+            .unwrap();
+
+        let mut controller = RustCryptoController::new();
+        let signature = controller.ecdsa_p384_sign(&private_key, &digest).unwrap();
+        assert_eq!(
+            signature,
+            hex!(
+                "94edbb92a5ecb8aad4736e56c691916b3f88140666ce9fa73d64c4ea95ad133c"
+                "81a648152e44acf96e36dd1e80fabe4699ef4aeb15f178cea1fe40db2603138f"
+                "130e740a19624526203b6351d0a3a94fa329c145786e679e7b82c71a38628ac8"
+            )
+        );
+        assert_eq!(
+            controller.ecdsa_p384_verify(&public_key, &digest, &signature),
+            Ok(())
+        );
+
+        let mut tampered = signature;
+        tampered[P384_POINT_SIZE - 1] ^= 1;
+        assert_eq!(
+            controller.ecdsa_p384_verify(&public_key, &digest, &tampered),
+            Err(HubrisCryptoError::AuthenticationFailed)
+        );
+        assert_eq!(
+            controller.ecdsa_p384_verify(&[0u8; P384_POINT_SIZE], &digest, &signature),
+            Err(HubrisCryptoError::InvalidParameters)
+        );
+        assert_eq!(
+            controller.ecdsa_p384_sign(&[0u8; P384_SCALAR_SIZE], &digest),
+            Err(HubrisCryptoError::InvalidParameters)
+        );
+    }
+
+    #[test]
+    fn test_hubris_aes256_gcm() {
+        use hex_literal::hex;
+
+        let mut controller = RustCryptoController::new();
+        let key = [0u8; 32];
+        let nonce = [0u8; AES_GCM_NONCE_SIZE];
+
+        // NIST GCM specification, test case 14
+        let mut buffer = [0u8; 16];
+        let tag = controller
+            .aes256_gcm_encrypt(&key, &nonce, &[], &mut buffer)
+            .unwrap();
+        assert_eq!(buffer, hex!("cea7403d4d606b6e074ec5d3baf39d18"));
+        assert_eq!(tag, hex!("d0d1c8a799996bf0265b98b5d48ab919"));
+
+        // Round trip with associated data
+        let mut buffer = *b"spdm secured message";
+        let tag = controller
+            .aes256_gcm_encrypt(&key, &nonce, b"header", &mut buffer)
+            .unwrap();
+        let ciphertext = buffer;
+        assert_eq!(
+            controller.aes256_gcm_decrypt(&key, &nonce, b"tampered", &mut buffer, &tag),
+            Err(HubrisCryptoError::AuthenticationFailed)
+        );
+        assert_eq!(buffer, ciphertext);
+        controller
+            .aes256_gcm_decrypt(&key, &nonce, b"header", &mut buffer, &tag)
+            .unwrap();
+        assert_eq!(&buffer, b"spdm secured message");
+    }
 }
@@ -0,0 +1,270 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! RustCrypto-based ECDH for NIST P-256 and P-384
+//!
+//! Implements the `openprot_hal_blocking::ecdh` traits for
+//! [`RustCryptoController`] on top of the `p256` and `p384` crates. Keys are
+//! the same [`P256PrivateKey`]/[`P384PrivateKey`] types used for ECDSA.
+//!
+//! Peer public keys are decoded through SEC 1 point validation, so points
+//! that are not on the curve (or the point at infinity) are rejected before
+//! any scalar multiplication takes place.
+
+use openprot_hal_blocking::ecdh::{
+    EcdhKeyAgreement, EcdhKeyGen, Error, ErrorKind, ErrorType, SharedSecret,
+};
+use openprot_hal_blocking::ecdsa::{
+    Curve, EcdsaKeyGen, P256PublicKey, P384PublicKey, PublicKey, P256, P384,
+};
+use p256::elliptic_curve::sec1::{FromEncodedPoint, ToEncodedPoint};
+use zerocopy::{FromZeros, IntoBytes};
+
+use crate::controller::RustCryptoController;
+use crate::ecdsa::{P256PrivateKey, P384PrivateKey};
+
+/// Error type for RustCrypto ECDH operations
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RustCryptoEcdhError {
+    /// Private key scalar is zero or not below the curve order
+    InvalidPrivateKey,
+    /// Peer public key coordinates do not describe a point on the curve
+    InvalidPublicKey,
+    /// The RNG did not produce a usable private key
+    KeyGenFailed,
+}
+
+impl core::fmt::Display for RustCryptoEcdhError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::InvalidPrivateKey => write!(f, "private key out of range for curve"),
+            Self::InvalidPublicKey => write!(f, "peer public key is not a valid curve point"),
+            Self::KeyGenFailed => write!(f, "key generation failed"),
+        }
+    }
+}
+
+impl Error for RustCryptoEcdhError {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::InvalidPrivateKey => ErrorKind::InvalidPrivateKey,
+            Self::InvalidPublicKey => ErrorKind::InvalidPoint,
+            Self::KeyGenFailed => ErrorKind::KeyGenError,
+        }
+    }
+}
+
+impl ErrorType for RustCryptoController {
+    type Error = RustCryptoEcdhError;
+}
+
+macro_rules! impl_ecdh {
+    ($curve:ident, $krate:ident, $private:ty, $public:ty) => {
+        impl EcdhKeyGen<$curve> for RustCryptoController {
+            type PrivateKey = $private;
+            type PublicKey = $public;
+
+            fn generate_ephemeral<R>(
+                &mut self,
+                rng: &mut R,
+            ) -> Result<(Self::PrivateKey, Self::PublicKey), Self::Error>
+            where
+                R: rand_core::RngCore + rand_core::CryptoRng,
+            {
+                EcdsaKeyGen::<$curve>::generate_keypair(self, rng)
+                    .map_err(|_| RustCryptoEcdhError::KeyGenFailed)
+            }
+        }
+
+        impl EcdhKeyAgreement<$curve> for RustCryptoController {
+            type PrivateKey = $private;
+            type PublicKey = $public;
+
+            fn shared_secret(
+                &mut self,
+                private_key: &Self::PrivateKey,
+                peer_public_key: &Self::PublicKey,
+            ) -> Result<SharedSecret<$curve>, Self::Error> {
+                let secret = $krate::SecretKey::from_slice(private_key.as_bytes())
+                    .map_err(|_| RustCryptoEcdhError::InvalidPrivateKey)?;
+
+                let mut x = <<$curve as Curve>::Scalar as FromZeros>::new_zeroed();
+                let mut y = <<$curve as Curve>::Scalar as FromZeros>::new_zeroed();
+                peer_public_key.coordinates(&mut x, &mut y);
+                let encoded =
+                    $krate::EncodedPoint::from_affine_coordinates(&x.into(), &y.into(), false);
+                let peer: $krate::PublicKey =
+                    Option::from($krate::PublicKey::from_encoded_point(&encoded))
+                        .ok_or(RustCryptoEcdhError::InvalidPublicKey)?;
+
+                let shared = (peer.to_projective() * *secret.to_nonzero_scalar()).to_affine();
+                let shared = shared.to_encoded_point(false);
+                let shared_x = shared.x().ok_or(RustCryptoEcdhError::InvalidPublicKey)?;
+                Ok(SharedSecret::new((*shared_x).into()))
+            }
+        }
+    };
+}
+
+impl_ecdh!(P256, p256, P256PrivateKey, P256PublicKey);
+impl_ecdh!(P384, p384, P384PrivateKey, P384PublicKey);
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)] // Allow unwrap in tests for cleaner test code
+mod tests {
+    use super::*;
+    use crate::test_util::ReplayRng;
+    use hex_literal::hex;
+
+    // RFC 5903, Section 8.1: 256-bit random ECP group
+    const P256_I: [u8; 32] =
+        hex!("c88f01f510d9ac3f70a292daa2316de544e9aab8afe84049c62a9c57862d1433");
+    const P256_GIX: [u8; 32] =
+        hex!("dad0b65394221cf9b051e1feca5787d098dfe637fc90b9ef945d0c3772581180");
+    const P256_GIY: [u8; 32] =
+        hex!("5271a0461cdb8252d61f1c456fa3e59ab1f45b33accf5f58389e0577b8990bb3");
+    const P256_R: [u8; 32] =
+        hex!("c6ef9c5d78ae012a011164acb397ce2088685d8f06bf9be0b283ab46476bee53");
+    const P256_GRX: [u8; 32] =
+        hex!("d12dfb5289c8d4f81208b70270398c342296970a0bccb74c736fc7554494bf63");
+    const P256_GRY: [u8; 32] =
+        hex!("56fbf3ca366cc23e8157854c13c58d6aac23f046ada30f8353e74f33039872ab");
+    const P256_GIRX: [u8; 32] =
+        hex!("d6840f6b42f6edafd13116e0e12565202fef8e9ece7dce03812464d04b9442de");
+
+    // RFC 5903, Section 8.2: 384-bit random ECP group
+    const P384_I: [u8; 48] = hex!(
+        "099f3c7034d4a2c699884d73a375a67f7624ef7c6b3c0f160647b67414dce655"
+        "e35b538041e649ee3faef896783ab194"
+    );
+    const P384_GIX: [u8; 48] = hex!(
+        "667842d7d180ac2cde6f74f37551f55755c7645c20ef73e31634fe72b4c55ee6"
+        "de3ac808acb4bdb4c88732aee95f41aa"
+    );
+    const P384_GIY: [u8; 48] = hex!(
+        "9482ed1fc0eeb9cafc4984625ccfc23f65032149e0e144ada024181535a0f38e"
+        "eb9fcff3c2c947dae69b4c634573a81c"
+    );
+    const P384_R: [u8; 48] = hex!(
+        "41cb0779b4bdb85d47846725fbec3c9430fab46cc8dc5060855cc9bda0aa2942"
+        "e0308312916b8ed2960e4bd55a7448fc"
+    );
+    const P384_GRX: [u8; 48] = hex!(
+        "e558dbef53eecde3d3fccfc1aea08a89a987475d12fd950d83cfa41732bc509d"
+        "0d1ac43a0336def96fda41d0774a3571"
+    );
+    const P384_GRY: [u8; 48] = hex!(
+        "dcfbec7aacf3196472169e838430367f66eebe3c6e70c416dd5f0c68759dd1ff"
+        "f83fa40142209dff5eaad96db9e6386c"
+    );
+    const P384_GIRX: [u8; 48] = hex!(
+        "11187331c279962d93d604243fd592cb9d0a926f422e47187521287e7156c5c4"
+        "d603135569b9e9d09cf5d4a270f59746"
+    );
+
+    #[test]
+    fn test_p256_rfc5903_shared_secret() {
+        let mut ctrl = RustCryptoController::new();
+
+        let (i, gi) =
+            EcdhKeyGen::<P256>::generate_ephemeral(&mut ctrl, &mut ReplayRng::new(&P256_I))
+                .unwrap();
+        let mut x = [0u8; 32];
+        let mut y = [0u8; 32];
+        gi.coordinates(&mut x, &mut y);
+        assert_eq!((x, y), (P256_GIX, P256_GIY));
+
+        let r = P256PrivateKey::from_bytes(P256_R).unwrap();
+        let gr = P256PublicKey::new(P256_GRX, P256_GRY);
+
+        let z_i = EcdhKeyAgreement::<P256>::shared_secret(&mut ctrl, &i, &gr).unwrap();
+        let z_r = EcdhKeyAgreement::<P256>::shared_secret(&mut ctrl, &r, &gi).unwrap();
+        assert_eq!(z_i.as_bytes(), P256_GIRX);
+        assert_eq!(z_r.as_bytes(), P256_GIRX);
+    }
+
+    #[test]
+    fn test_p384_rfc5903_shared_secret() {
+        let mut ctrl = RustCryptoController::new();
+
+        let (i, gi) =
+            EcdhKeyGen::<P384>::generate_ephemeral(&mut ctrl, &mut ReplayRng::new(&P384_I))
+                .unwrap();
+        let mut x = [0u8; 48];
+        let mut y = [0u8; 48];
+        gi.coordinates(&mut x, &mut y);
+        assert_eq!((x, y), (P384_GIX, P384_GIY));
+
+        let r = P384PrivateKey::from_bytes(P384_R).unwrap();
+        let gr = P384PublicKey::new(P384_GRX, P384_GRY);
+
+        let z_i = EcdhKeyAgreement::<P384>::shared_secret(&mut ctrl, &i, &gr).unwrap();
+        let z_r = EcdhKeyAgreement::<P384>::shared_secret(&mut ctrl, &r, &gi).unwrap();
+        assert_eq!(z_i.as_bytes(), P384_GIRX);
+        assert_eq!(z_r.as_bytes(), P384_GIRX);
+    }
+
+    #[test]
+    fn test_p256_rejects_invalid_points() {
+        let mut ctrl = RustCryptoController::new();
+        let i = P256PrivateKey::from_bytes(P256_I).unwrap();
+
+        let mut off_curve_y = P256_GRY;
+        off_curve_y[31] ^= 0x01;
+        let invalid = [
+            // Valid x with a y that does not satisfy the curve equation
+            P256PublicKey::new(P256_GRX, off_curve_y),
+            // (0, 0) is used as an encoding of infinity by some stacks
+            P256PublicKey::new([0u8; 32], [0u8; 32]),
+            // Coordinates not reduced modulo p
+            P256PublicKey::new([0xff; 32], [0xff; 32]),
+        ];
+        for peer in &invalid {
+            let err = EcdhKeyAgreement::<P256>::shared_secret(&mut ctrl, &i, peer).unwrap_err();
+            assert_eq!(err, RustCryptoEcdhError::InvalidPublicKey);
+            assert_eq!(err.kind(), ErrorKind::InvalidPoint);
+        }
+    }
+
+    #[test]
+    fn test_p384_rejects_invalid_points() {
+        let mut ctrl = RustCryptoController::new();
+        let i = P384PrivateKey::from_bytes(P384_I).unwrap();
+
+        let mut off_curve_x = P384_GRX;
+        off_curve_x[0] ^= 0x80;
+        let invalid = [
+            P384PublicKey::new(off_curve_x, P384_GRY),
+            P384PublicKey::new([0u8; 48], [0u8; 48]),
+            P384PublicKey::new([0xff; 48], [0xff; 48]),
+        ];
+        for peer in &invalid {
+            let err = EcdhKeyAgreement::<P384>::shared_secret(&mut ctrl, &i, peer).unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::InvalidPoint);
+        }
+    }
+
+    #[test]
+    fn test_ephemeral_agreement_roundtrip() {
+        let mut ctrl = RustCryptoController::new();
+        let (a, ga) =
+            EcdhKeyGen::<P256>::generate_ephemeral(&mut ctrl, &mut ReplayRng::new(&[0x11]))
+                .unwrap();
+        let (b, gb) =
+            EcdhKeyGen::<P256>::generate_ephemeral(&mut ctrl, &mut ReplayRng::new(&[0x22]))
+                .unwrap();
+
+        let z_a = EcdhKeyAgreement::<P256>::shared_secret(&mut ctrl, &a, &gb).unwrap();
+        let z_b = EcdhKeyAgreement::<P256>::shared_secret(&mut ctrl, &b, &ga).unwrap();
+        assert_eq!(z_a.as_bytes(), z_b.as_bytes());
+    }
+
+    #[test]
+    fn test_keygen_rejects_broken_rng() {
+        let mut ctrl = RustCryptoController::new();
+        // An all-zero scalar is never a valid private key
+        let err = EcdhKeyGen::<P384>::generate_ephemeral(&mut ctrl, &mut ReplayRng::new(&[0x00]))
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::KeyGenError);
+    }
+}
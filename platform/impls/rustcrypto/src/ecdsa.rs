@@ -0,0 +1,652 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! RustCrypto-based ECDSA for NIST P-256 and P-384
+//!
+//! Implements the `openprot_hal_blocking::ecdsa` key generation, signing and
+//! verification traits for [`RustCryptoController`] on top of the `p256` and
+//! `p384` crates.
+//!
+//! Signing through [`EcdsaSign`] computes the nonce with RFC 6979 and mixes
+//! bytes from the caller's RNG into it as additional data (RFC 6979 §3.6), so a
+//! weak RNG cannot leak the key. Fully deterministic RFC 6979 signing is
+//! available through [`EcdsaSignDeterministic`].
+
+use ecdsa::hazmat::{bits2field, SignPrimitive};
+use openprot_hal_blocking::digest::DigestAlgorithm;
+use openprot_hal_blocking::ecdsa::{
+    Curve, EcdsaKeyGen, EcdsaSign, EcdsaVerify, Error, ErrorKind, ErrorType, P256PublicKey,
+    P256Signature, P384PublicKey, P384Signature, PrivateKey, PublicKey, SerializablePrivateKey,
+    Signature, P256, P384,
+};
+use p256::ecdsa::signature::hazmat::{PrehashSigner, PrehashVerifier};
+use p256::elliptic_curve::sec1::ToEncodedPoint;
+use sha2::{Sha256, Sha384};
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use crate::controller::RustCryptoController;
+
+/// Number of RNG draws attempted before key generation gives up.
+///
+/// A uniformly random 256-bit string is a valid P-256 scalar with probability
+/// greater than `1 - 2^-32`, so hitting this limit indicates a broken RNG.
+const MAX_KEYGEN_ATTEMPTS: usize = 8;
+
+/// Error type for RustCrypto ECDSA operations
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RustCryptoEcdsaError {
+    /// Private key scalar is zero or not below the curve order
+    InvalidPrivateKey,
+    /// Public key coordinates do not describe a point on the curve
+    InvalidPublicKey,
+    /// Signature is malformed or does not verify
+    InvalidSignature,
+    /// The RNG did not produce a usable private key
+    KeyGenFailed,
+    /// The signing computation failed
+    SigningFailed,
+}
+
+impl core::fmt::Display for RustCryptoEcdsaError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::InvalidPrivateKey => write!(f, "private key out of range for curve"),
+            Self::InvalidPublicKey => write!(f, "public key is not a valid curve point"),
+            Self::InvalidSignature => write!(f, "signature verification failed"),
+            Self::KeyGenFailed => write!(f, "key generation failed"),
+            Self::SigningFailed => write!(f, "signing operation failed"),
+        }
+    }
+}
+
+impl Error for RustCryptoEcdsaError {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::InvalidPrivateKey => ErrorKind::WeakKey,
+            Self::InvalidPublicKey => ErrorKind::InvalidPoint,
+            Self::InvalidSignature => ErrorKind::InvalidSignature,
+            Self::KeyGenFailed => ErrorKind::KeyGenError,
+            Self::SigningFailed => ErrorKind::SigningError,
+        }
+    }
+}
+
+/// Software P-256 private key (big-endian scalar).
+///
+/// The key material is zeroized on drop.
+#[derive(Clone, IntoBytes, FromBytes, Immutable, Zeroize, ZeroizeOnDrop)]
+#[repr(transparent)]
+pub struct P256PrivateKey([u8; 32]);
+
+impl P256PrivateKey {
+    /// Create a private key from a big-endian scalar, validating its range.
+    pub fn from_bytes(bytes: [u8; 32]) -> Result<Self, RustCryptoEcdsaError> {
+        let key = Self(bytes);
+        key.validate(&P256)
+            .map_err(|_| RustCryptoEcdsaError::InvalidPrivateKey)?;
+        Ok(key)
+    }
+
+    fn signing_key(&self) -> Result<p256::ecdsa::SigningKey, RustCryptoEcdsaError> {
+        p256::ecdsa::SigningKey::from_bytes(&self.0.into())
+            .map_err(|_| RustCryptoEcdsaError::InvalidPrivateKey)
+    }
+}
+
+impl core::fmt::Debug for P256PrivateKey {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("P256PrivateKey([REDACTED])")
+    }
+}
+
+impl PrivateKey<P256> for P256PrivateKey {
+    fn validate(&self, _curve: &P256) -> Result<(), ErrorKind> {
+        p256::SecretKey::from_bytes(&self.0.into())
+            .map(|_| ())
+            .map_err(|_| ErrorKind::WeakKey)
+    }
+}
+
+impl SerializablePrivateKey<P256> for P256PrivateKey {}
+
+/// Software P-384 private key (big-endian scalar).
+///
+/// The key material is zeroized on drop.
+#[derive(Clone, IntoBytes, FromBytes, Immutable, Zeroize, ZeroizeOnDrop)]
+#[repr(transparent)]
+pub struct P384PrivateKey([u8; 48]);
+
+impl P384PrivateKey {
+    /// Create a private key from a big-endian scalar, validating its range.
+    pub fn from_bytes(bytes: [u8; 48]) -> Result<Self, RustCryptoEcdsaError> {
+        let key = Self(bytes);
+        key.validate(&P384)
+            .map_err(|_| RustCryptoEcdsaError::InvalidPrivateKey)?;
+        Ok(key)
+    }
+
+    fn signing_key(&self) -> Result<p384::ecdsa::SigningKey, RustCryptoEcdsaError> {
+        p384::ecdsa::SigningKey::from_bytes(&self.0.into())
+            .map_err(|_| RustCryptoEcdsaError::InvalidPrivateKey)
+    }
+}
+
+impl core::fmt::Debug for P384PrivateKey {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("P384PrivateKey([REDACTED])")
+    }
+}
+
+impl PrivateKey<P384> for P384PrivateKey {
+    fn validate(&self, _curve: &P384) -> Result<(), ErrorKind> {
+        p384::SecretKey::from_bytes(&self.0.into())
+            .map(|_| ())
+            .map_err(|_| ErrorKind::WeakKey)
+    }
+}
+
+impl SerializablePrivateKey<P384> for P384PrivateKey {}
+
+/// RFC 6979 deterministic ECDSA signing.
+///
+/// Unlike [`EcdsaSign::sign`], no RNG is involved: the same key and digest
+/// always yield the same signature. Useful for known-answer tests and for
+/// callers without an entropy source.
+pub trait EcdsaSignDeterministic<C: Curve>: EcdsaSign<C> {
+    /// Signs a digest with a nonce derived purely from the key and digest.
+    fn sign_deterministic(
+        &mut self,
+        private_key: &Self::PrivateKey,
+        digest: <<C as Curve>::DigestType as DigestAlgorithm>::Digest,
+    ) -> Result<Self::Signature, Self::Error>;
+}
+
+impl ErrorType for RustCryptoController {
+    type Error = RustCryptoEcdsaError;
+}
+
+//
+// P-256
+//
+
+impl EcdsaKeyGen<P256> for RustCryptoController {
+    type PrivateKey = P256PrivateKey;
+    type PublicKey = P256PublicKey;
+
+    fn generate_keypair<R>(
+        &mut self,
+        rng: &mut R,
+    ) -> Result<(Self::PrivateKey, Self::PublicKey), Self::Error>
+    where
+        R: rand_core::RngCore + rand_core::CryptoRng,
+    {
+        for _ in 0..MAX_KEYGEN_ATTEMPTS {
+            let mut candidate = P256PrivateKey([0u8; 32]);
+            rng.fill_bytes(&mut candidate.0);
+
+            // Rejection sampling keeps the scalar uniform over [1, n)
+            if let Ok(secret) = p256::SecretKey::from_bytes(&candidate.0.into()) {
+                let point = secret.public_key().to_encoded_point(false);
+                let (Some(x), Some(y)) = (point.x(), point.y()) else {
+                    return Err(RustCryptoEcdsaError::KeyGenFailed);
+                };
+                let public = P256PublicKey::new((*x).into(), (*y).into());
+                return Ok((candidate, public));
+            }
+        }
+        Err(RustCryptoEcdsaError::KeyGenFailed)
+    }
+}
+
+impl EcdsaSign<P256> for RustCryptoController {
+    type PrivateKey = P256PrivateKey;
+    type Signature = P256Signature;
+
+    fn sign<R>(
+        &mut self,
+        private_key: &Self::PrivateKey,
+        digest: <<P256 as Curve>::DigestType as DigestAlgorithm>::Digest,
+        rng: &mut R,
+    ) -> Result<Self::Signature, Self::Error>
+    where
+        R: rand_core::RngCore + rand_core::CryptoRng,
+    {
+        let signing_key = private_key.signing_key()?;
+        let z = bits2field::<p256::NistP256>(digest.as_bytes())
+            .map_err(|_| RustCryptoEcdsaError::SigningFailed)?;
+
+        let mut ad = p256::FieldBytes::default();
+        rng.fill_bytes(&mut ad);
+
+        let scalar: &p256::Scalar = signing_key.as_nonzero_scalar().as_ref();
+        let (signature, _) = scalar
+            .try_sign_prehashed_rfc6979::<Sha256>(&z, &ad)
+            .map_err(|_| RustCryptoEcdsaError::SigningFailed)?;
+        ad.zeroize();
+
+        let (r, s) = signature.split_bytes();
+        Ok(P256Signature::new(r.into(), s.into()))
+    }
+}
+
+impl EcdsaSignDeterministic<P256> for RustCryptoController {
+    fn sign_deterministic(
+        &mut self,
+        private_key: &Self::PrivateKey,
+        digest: <<P256 as Curve>::DigestType as DigestAlgorithm>::Digest,
+    ) -> Result<Self::Signature, Self::Error> {
+        let signing_key = private_key.signing_key()?;
+        let signature: p256::ecdsa::Signature = signing_key
+            .sign_prehash(digest.as_bytes())
+            .map_err(|_| RustCryptoEcdsaError::SigningFailed)?;
+
+        let (r, s) = signature.split_bytes();
+        Ok(P256Signature::new(r.into(), s.into()))
+    }
+}
+
+impl EcdsaVerify<P256> for RustCryptoController {
+    type PublicKey = P256PublicKey;
+    type Signature = P256Signature;
+
+    fn verify(
+        &mut self,
+        public_key: &Self::PublicKey,
+        digest: <<P256 as Curve>::DigestType as DigestAlgorithm>::Digest,
+        signature: &Self::Signature,
+    ) -> Result<(), Self::Error> {
+        let (mut x, mut y) = ([0u8; 32], [0u8; 32]);
+        public_key.coordinates(&mut x, &mut y);
+        let point = p256::EncodedPoint::from_affine_coordinates(&x.into(), &y.into(), false);
+        let verifying_key = p256::ecdsa::VerifyingKey::from_encoded_point(&point)
+            .map_err(|_| RustCryptoEcdsaError::InvalidPublicKey)?;
+
+        let (mut r, mut s) = ([0u8; 32], [0u8; 32]);
+        signature.coordinates(&mut r, &mut s);
+        let signature = p256::ecdsa::Signature::from_scalars(r, s)
+            .map_err(|_| RustCryptoEcdsaError::InvalidSignature)?;
+
+        verifying_key
+            .verify_prehash(digest.as_bytes(), &signature)
+            .map_err(|_| RustCryptoEcdsaError::InvalidSignature)
+    }
+}
+
+//
+// P-384
+//
+
+impl EcdsaKeyGen<P384> for RustCryptoController {
+    type PrivateKey = P384PrivateKey;
+    type PublicKey = P384PublicKey;
+
+    fn generate_keypair<R>(
+        &mut self,
+        rng: &mut R,
+    ) -> Result<(Self::PrivateKey, Self::PublicKey), Self::Error>
+    where
+        R: rand_core::RngCore + rand_core::CryptoRng,
+    {
+        for _ in 0..MAX_KEYGEN_ATTEMPTS {
+            let mut candidate = P384PrivateKey([0u8; 48]);
+            rng.fill_bytes(&mut candidate.0);
+
+            // Rejection sampling keeps the scalar uniform over [1, n)
+            if let Ok(secret) = p384::SecretKey::from_bytes(&candidate.0.into()) {
+                let point = secret.public_key().to_encoded_point(false);
+                let (Some(x), Some(y)) = (point.x(), point.y()) else {
+                    return Err(RustCryptoEcdsaError::KeyGenFailed);
+                };
+                let public = P384PublicKey::new((*x).into(), (*y).into());
+                return Ok((candidate, public));
+            }
+        }
+        Err(RustCryptoEcdsaError::KeyGenFailed)
+    }
+}
+
+impl EcdsaSign<P384> for RustCryptoController {
+    type PrivateKey = P384PrivateKey;
+    type Signature = P384Signature;
+
+    fn sign<R>(
+        &mut self,
+        private_key: &Self::PrivateKey,
+        digest: <<P384 as Curve>::DigestType as DigestAlgorithm>::Digest,
+        rng: &mut R,
+    ) -> Result<Self::Signature, Self::Error>
+    where
+        R: rand_core::RngCore + rand_core::CryptoRng,
+    {
+        let signing_key = private_key.signing_key()?;
+        let z = bits2field::<p384::NistP384>(digest.as_bytes())
+            .map_err(|_| RustCryptoEcdsaError::SigningFailed)?;
+
+        let mut ad = p384::FieldBytes::default();
+        rng.fill_bytes(&mut ad);
+
+        let scalar: &p384::Scalar = signing_key.as_nonzero_scalar().as_ref();
+        let (signature, _) = scalar
+            .try_sign_prehashed_rfc6979::<Sha384>(&z, &ad)
+            .map_err(|_| RustCryptoEcdsaError::SigningFailed)?;
+        ad.zeroize();
+
+        let (r, s) = signature.split_bytes();
+        Ok(P384Signature::new(r.into(), s.into()))
+    }
+}
+
+impl EcdsaSignDeterministic<P384> for RustCryptoController {
+    fn sign_deterministic(
+        &mut self,
+        private_key: &Self::PrivateKey,
+        digest: <<P384 as Curve>::DigestType as DigestAlgorithm>::Digest,
+    ) -> Result<Self::Signature, Self::Error> {
+        let signing_key = private_key.signing_key()?;
+        let signature: p384::ecdsa::Signature = signing_key
+            .sign_prehash(digest.as_bytes())
+            .map_err(|_| RustCryptoEcdsaError::SigningFailed)?;
+
+        let (r, s) = signature.split_bytes();
+        Ok(P384Signature::new(r.into(), s.into()))
+    }
+}
+
+impl EcdsaVerify<P384> for RustCryptoController {
+    type PublicKey = P384PublicKey;
+    type Signature = P384Signature;
+
+    fn verify(
+        &mut self,
+        public_key: &Self::PublicKey,
+        digest: <<P384 as Curve>::DigestType as DigestAlgorithm>::Digest,
+        signature: &Self::Signature,
+    ) -> Result<(), Self::Error> {
+        let (mut x, mut y) = ([0u8; 48], [0u8; 48]);
+        public_key.coordinates(&mut x, &mut y);
+        let point = p384::EncodedPoint::from_affine_coordinates(&x.into(), &y.into(), false);
+        let verifying_key = p384::ecdsa::VerifyingKey::from_encoded_point(&point)
+            .map_err(|_| RustCryptoEcdsaError::InvalidPublicKey)?;
+
+        let (mut r, mut s) = ([0u8; 48], [0u8; 48]);
+        signature.coordinates(&mut r, &mut s);
+        let signature = p384::ecdsa::Signature::from_scalars(r, s)
+            .map_err(|_| RustCryptoEcdsaError::InvalidSignature)?;
+
+        verifying_key
+            .verify_prehash(digest.as_bytes(), &signature)
+            .map_err(|_| RustCryptoEcdsaError::InvalidSignature)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::test_util::ReplayRng;
+    use hex_literal::hex;
+    use openprot_hal_blocking::digest::owned::{DigestInit, DigestOp};
+    use openprot_hal_blocking::digest::{Digest, Sha2_256, Sha2_384};
+
+    fn digest_from_bytes<const N: usize>(bytes: &[u8]) -> Digest<N> {
+        let mut words = [0u32; N];
+        for (word, chunk) in words.iter_mut().zip(bytes.chunks_exact(4)) {
+            *word = u32::from_le_bytes(chunk.try_into().unwrap());
+        }
+        Digest::new(words)
+    }
+
+    fn sha256(data: &[u8]) -> Digest<8> {
+        let ctx = DigestInit::init(RustCryptoController::new(), Sha2_256).unwrap();
+        ctx.update(data).unwrap().finalize().unwrap().0
+    }
+
+    fn sha384(data: &[u8]) -> Digest<12> {
+        let ctx = DigestInit::init(RustCryptoController::new(), Sha2_384).unwrap();
+        ctx.update(data).unwrap().finalize().unwrap().0
+    }
+
+    struct CavpVector {
+        d: &'static [u8],
+        q_x: &'static [u8],
+        q_y: &'static [u8],
+        m: &'static [u8],
+        r: &'static [u8],
+        s: &'static [u8],
+    }
+
+    // NIST CAVP FIPS 186-4 SigGen.txt, [P-256,SHA-256]; `m` is the SHA-256 of Msg.
+    const P256_CAVP: &[CavpVector] = &[
+        CavpVector {
+            d: &hex!("519b423d715f8b581f4fa8ee59f4771a5b44c8130b4e3eacca54a56dda72b464"),
+            q_x: &hex!("1ccbe91c075fc7f4f033bfa248db8fccd3565de94bbfb12f3c59ff46c271bf83"),
+            q_y: &hex!("ce4014c68811f9a21a1fdb2c0e6113e06db7ca93b7404e78dc7ccd5ca89a4ca9"),
+            m: &hex!("44acf6b7e36c1342c2c5897204fe09504e1e2efb1a900377dbc4e7a6a133ec56"),
+            r: &hex!("f3ac8061b514795b8843e3d6629527ed2afd6b1f6a555a7acabb5e6f79c8c2ac"),
+            s: &hex!("8bf77819ca05a6b2786c76262bf7371cef97b218e96f175a3ccdda2acc058903"),
+        },
+        CavpVector {
+            d: &hex!("0f56db78ca460b055c500064824bed999a25aaf48ebb519ac201537b85479813"),
+            q_x: &hex!("e266ddfdc12668db30d4ca3e8f7749432c416044f2d2b8c10bf3d4012aeffa8a"),
+            q_y: &hex!("bfa86404a2e9ffe67d47c587ef7a97a7f456b863b4d02cfc6928973ab5b1cb39"),
+            m: &hex!("9b2db89cb0e8fa3cc7608b4d6cc1dec0114e0b9ff4080bea12b134f489ab2bbc"),
+            r: &hex!("976d3a4e9d23326dc0baa9fa560b7c4e53f42864f508483a6473b6a11079b2db"),
+            s: &hex!("1b766e9ceb71ba6c01dcd46e0af462cd4cfa652ae5017d4555b8eeefe36e1932"),
+        },
+        CavpVector {
+            d: &hex!("e283871239837e13b95f789e6e1af63bf61c918c992e62bca040d64cad1fc2ef"),
+            q_x: &hex!("74ccd8a62fba0e667c50929a53f78c21b8ff0c3c737b0b40b1750b2302b0bde8"),
+            q_y: &hex!("29074e21f3a0ef88b9efdf10d06aa4c295cc1671f758ca0e4cd108803d0f2614"),
+            m: &hex!("b804cf88af0c2eff8bbbfb3660ebb3294138e9d3ebd458884e19818061dacff0"),
+            r: &hex!("35fb60f5ca0f3ca08542fb3cc641c8263a2cab7a90ee6a5e1583fac2bb6f6bd1"),
+            s: &hex!("ee59d81bc9db1055cc0ed97b159d8784af04e98511d0a9a407b99bb292572e96"),
+        },
+    ];
+
+    // NIST CAVP FIPS 186-4 SigGen.txt, [P-384,SHA-384]; `m` is the SHA-384 of Msg.
+    const P384_CAVP: &[CavpVector] = &[CavpVector {
+        d: &hex!(
+            "201b432d8df14324182d6261db3e4b3f46a8284482d52e370da41e6cbdf45ec2952f5db7ccbce3bc29449f4fb080ac97"
+        ),
+        q_x: &hex!(
+            "c2b47944fb5de342d03285880177ca5f7d0f2fcad7678cce4229d6e1932fcac11bfc3c3e97d942a3c56bf34123013dbf"
+        ),
+        q_y: &hex!(
+            "37257906a8223866eda0743c519616a76a758ae58aee81c5fd35fbf3a855b7754a36d4a0672df95d6c44a81cf7620c2d"
+        ),
+        m: &hex!(
+            "31a452d6164d904bb5724c878280231eae705c29ce9d4bc7d58e020e1085f17eebcc1a38f0ed0bf2b344d81fbd896825"
+        ),
+        r: &hex!(
+            "50835a9251bad008106177ef004b091a1e4235cd0da84fff54542b0ed755c1d6f251609d14ecf18f9e1ddfe69b946e32"
+        ),
+        s: &hex!(
+            "0475f3d30c6463b646e8d3bf2455830314611cbde404be518b14464fdb195fdcc92eb222e61f426a4a592c00a6a89721"
+        ),
+    }];
+
+    #[test]
+    fn test_p256_cavp_keygen_and_verify() {
+        let mut controller = RustCryptoController::new();
+
+        for v in P256_CAVP {
+            // Replaying `d` through the RNG makes keygen derive the vector's Q
+            let mut rng = ReplayRng::new(v.d);
+            let (_private, public) =
+                EcdsaKeyGen::<P256>::generate_keypair(&mut controller, &mut rng).unwrap();
+            let (mut x, mut y) = ([0u8; 32], [0u8; 32]);
+            public.coordinates(&mut x, &mut y);
+            assert_eq!(&x[..], v.q_x);
+            assert_eq!(&y[..], v.q_y);
+
+            let signature = P256Signature::new(v.r.try_into().unwrap(), v.s.try_into().unwrap());
+            EcdsaVerify::<P256>::verify(
+                &mut controller,
+                &public,
+                digest_from_bytes(v.m),
+                &signature,
+            )
+            .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_p384_cavp_keygen_and_verify() {
+        let mut controller = RustCryptoController::new();
+
+        for v in P384_CAVP {
+            let mut rng = ReplayRng::new(v.d);
+            let (_private, public) =
+                EcdsaKeyGen::<P384>::generate_keypair(&mut controller, &mut rng).unwrap();
+            let (mut x, mut y) = ([0u8; 48], [0u8; 48]);
+            public.coordinates(&mut x, &mut y);
+            assert_eq!(&x[..], v.q_x);
+            assert_eq!(&y[..], v.q_y);
+
+            let signature = P384Signature::new(v.r.try_into().unwrap(), v.s.try_into().unwrap());
+            EcdsaVerify::<P384>::verify(
+                &mut controller,
+                &public,
+                digest_from_bytes(v.m),
+                &signature,
+            )
+            .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_p256_cavp_rejects_tampered_digest() {
+        let mut controller = RustCryptoController::new();
+        let v = &P256_CAVP[0];
+
+        let public = P256PublicKey::new(v.q_x.try_into().unwrap(), v.q_y.try_into().unwrap());
+        let signature = P256Signature::new(v.r.try_into().unwrap(), v.s.try_into().unwrap());
+        let mut m: [u8; 32] = v.m.try_into().unwrap();
+        m[0] ^= 0x01;
+
+        let err = EcdsaVerify::<P256>::verify(
+            &mut controller,
+            &public,
+            digest_from_bytes(&m),
+            &signature,
+        )
+        .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidSignature);
+    }
+
+    #[test]
+    fn test_p256_rfc6979_deterministic() {
+        // RFC 6979 Appendix A.2.5, P-256 with SHA-256
+        let key = P256PrivateKey::from_bytes(hex!(
+            "c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721"
+        ))
+        .unwrap();
+        let mut controller = RustCryptoController::new();
+
+        let sig = EcdsaSignDeterministic::<P256>::sign_deterministic(
+            &mut controller,
+            &key,
+            sha256(b"sample"),
+        )
+        .unwrap();
+        assert_eq!(
+            sig.as_bytes(),
+            hex!(
+                "efd48b2aacb6a8fd1140dd9cd45e81d69d2c877b56aaf991c34d0ea84eaf3716"
+                "f7cb1c942d657c41d436c7a1b6e29f65f3e900dbb9aff4064dc4ab2f843acda8"
+            )
+        );
+
+        let sig = EcdsaSignDeterministic::<P256>::sign_deterministic(
+            &mut controller,
+            &key,
+            sha256(b"test"),
+        )
+        .unwrap();
+        assert_eq!(
+            sig.as_bytes(),
+            hex!(
+                "f1abb023518351cd71d881567b1ea663ed3efcf6c5132b354f28d3b0b7d38367"
+                "019f4113742a2b14bd25926b49c649155f267e60d3814b4c0cc84250e46f0083"
+            )
+        );
+    }
+
+    #[test]
+    fn test_p384_rfc6979_deterministic() {
+        // RFC 6979 Appendix A.2.6, P-384 with SHA-384
+        let key = P384PrivateKey::from_bytes(hex!(
+            "6b9d3dad2e1b8c1c05b19875b6659f4de23c3b667bf297ba9aa47740787137d8"
+            "96d5724e4c70a825f872c9ea60d2edf5"
+        ))
+        .unwrap();
+        let mut controller = RustCryptoController::new();
+
+        let sig = EcdsaSignDeterministic::<P384>::sign_deterministic(
+            &mut controller,
+            &key,
+            sha384(b"sample"),
+        )
+        .unwrap();
+        assert_eq!(
+            sig.as_bytes(),
+            hex!(
+                "94edbb92a5ecb8aad4736e56c691916b3f88140666ce9fa73d64c4ea95ad133c"
+                "81a648152e44acf96e36dd1e80fabe4699ef4aeb15f178cea1fe40db2603138f"
+                "130e740a19624526203b6351d0a3a94fa329c145786e679e7b82c71a38628ac8"
+            )
+        );
+    }
+
+    #[test]
+    fn test_sign_verify_roundtrip() {
+        let mut controller = RustCryptoController::new();
+        let mut rng = ReplayRng::new(&[0x5a, 0x17, 0xc3, 0x88]);
+
+        let (private, public) =
+            EcdsaKeyGen::<P256>::generate_keypair(&mut controller, &mut rng).unwrap();
+        let digest = sha256(b"firmware manifest");
+        let sig = EcdsaSign::<P256>::sign(&mut controller, &private, digest, &mut rng).unwrap();
+        EcdsaVerify::<P256>::verify(&mut controller, &public, digest, &sig).unwrap();
+
+        let (private, public) =
+            EcdsaKeyGen::<P384>::generate_keypair(&mut controller, &mut rng).unwrap();
+        let digest = sha384(b"firmware manifest");
+        let sig = EcdsaSign::<P384>::sign(&mut controller, &private, digest, &mut rng).unwrap();
+        EcdsaVerify::<P384>::verify(&mut controller, &public, digest, &sig).unwrap();
+
+        // Hedged signatures differ from the purely deterministic ones
+        let det =
+            EcdsaSignDeterministic::<P384>::sign_deterministic(&mut controller, &private, digest)
+                .unwrap();
+        assert_ne!(sig.as_bytes(), det.as_bytes());
+        EcdsaVerify::<P384>::verify(&mut controller, &public, digest, &det).unwrap();
+    }
+
+    #[test]
+    fn test_invalid_keys_rejected() {
+        // Zero and the group order are not valid scalars
+        assert_eq!(
+            P256PrivateKey::from_bytes([0u8; 32]).unwrap_err(),
+            RustCryptoEcdsaError::InvalidPrivateKey
+        );
+        let order = hex!("ffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632551");
+        assert!(P256PrivateKey::from_bytes(order).is_err());
+
+        // An all-zero RNG can never produce a key
+        let mut controller = RustCryptoController::new();
+        let mut rng = ReplayRng::new(&[0]);
+        let err = EcdsaKeyGen::<P256>::generate_keypair(&mut controller, &mut rng).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::KeyGenError);
+
+        // (1, 1) is not on P-256
+        let mut one = [0u8; 32];
+        one[31] = 1;
+        let bogus = P256PublicKey::new(one, one);
+        let sig = P256Signature::new(one, one);
+        let err =
+            EcdsaVerify::<P256>::verify(&mut controller, &bogus, sha256(b"x"), &sig).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidPoint);
+    }
+}
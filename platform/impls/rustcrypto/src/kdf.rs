@@ -0,0 +1,636 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! RustCrypto-based key derivation
+//!
+//! Implements the `openprot_hal_blocking::kdf` traits for
+//! [`RustCryptoController`]. HKDF is provided by the `hkdf` crate; the
+//! SP 800-108 counter-mode KDF is built directly on `hmac`.
+
+use hkdf::Hkdf;
+use hmac::digest::OutputSizeUser;
+use hmac::{Hmac, Mac};
+use openprot_hal_blocking::digest::Digest;
+use openprot_hal_blocking::kdf::{
+    CounterModeKdf, Error, ErrorKind, ErrorType, HkdfExpand, HkdfExtract,
+};
+use openprot_hal_blocking::mac::{HmacSha2_256, HmacSha2_384, HmacSha2_512};
+use sha2::{Sha256, Sha384, Sha512};
+use zeroize::Zeroize;
+
+use crate::controller::{digest_from_bytes, CryptoError, RustCryptoController};
+
+impl Error for CryptoError {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            CryptoError::InvalidKeyLength => ErrorKind::InvalidKeyLength,
+            CryptoError::InvalidOutputLength => ErrorKind::InvalidOutputLength,
+            CryptoError::OperationFailed => ErrorKind::HardwareFailure,
+        }
+    }
+}
+
+impl ErrorType for RustCryptoController {
+    type Error = CryptoError;
+}
+
+/// Output length in bits as encoded in `[L]_32`
+fn output_bits(len: usize) -> Result<u32, CryptoError> {
+    u32::try_from(len)
+        .ok()
+        .and_then(|len| len.checked_mul(8))
+        .filter(|&bits| bits != 0)
+        .ok_or(CryptoError::InvalidOutputLength)
+}
+
+/// SP 800-108 counter-mode KDF with a 32-bit counter placed before the fixed
+/// input data, which is passed in pieces to avoid a concatenation buffer.
+fn counter_kdf<M>(key: &[u8], fixed_input: &[&[u8]], okm: &mut [u8]) -> Result<(), CryptoError>
+where
+    M: Mac + hmac::digest::KeyInit + Clone,
+{
+    output_bits(okm.len())?;
+    let prf = <M as Mac>::new_from_slice(key).map_err(|_| CryptoError::InvalidKeyLength)?;
+
+    let mut counter: u32 = 0;
+    for chunk in okm.chunks_mut(<M as OutputSizeUser>::output_size()) {
+        // The block count is bounded by the output length, which fits in u32
+        counter = counter
+            .checked_add(1)
+            .ok_or(CryptoError::InvalidOutputLength)?;
+        let mut mac = prf.clone();
+        mac.update(&counter.to_be_bytes());
+        for part in fixed_input {
+            mac.update(part);
+        }
+        let mut block = mac.finalize().into_bytes();
+        chunk.copy_from_slice(&block[..chunk.len()]);
+        block.as_mut_slice().zeroize();
+    }
+    Ok(())
+}
+
+macro_rules! impl_kdf {
+    ($algo:ty, $hash:ty, $words:expr) => {
+        impl HkdfExtract<$algo> for RustCryptoController {
+            fn extract(
+                &mut self,
+                _algo: $algo,
+                salt: &[u8],
+                ikm: &[u8],
+            ) -> Result<Digest<$words>, Self::Error> {
+                let (mut prk, _) = Hkdf::<$hash>::extract(Some(salt), ikm);
+                let result = digest_from_bytes(&prk);
+                prk.as_mut_slice().zeroize();
+                result
+            }
+        }
+
+        impl HkdfExpand<$algo> for RustCryptoController {
+            fn expand(
+                &mut self,
+                _algo: $algo,
+                prk: &[u8],
+                info: &[u8],
+                okm: &mut [u8],
+            ) -> Result<(), Self::Error> {
+                Hkdf::<$hash>::from_prk(prk)
+                    .map_err(|_| CryptoError::InvalidKeyLength)?
+                    .expand(info, okm)
+                    .map_err(|_| CryptoError::InvalidOutputLength)
+            }
+        }
+
+        impl CounterModeKdf<$algo> for RustCryptoController {
+            fn derive(
+                &mut self,
+                _algo: $algo,
+                key: &[u8],
+                label: &[u8],
+                context: &[u8],
+                okm: &mut [u8],
+            ) -> Result<(), Self::Error> {
+                let out_bits = output_bits(okm.len())?.to_be_bytes();
+                counter_kdf::<Hmac<$hash>>(key, &[label, &[0x00], context, &out_bits], okm)
+            }
+
+            fn derive_with_fixed_input(
+                &mut self,
+                _algo: $algo,
+                key: &[u8],
+                fixed_input: &[u8],
+                okm: &mut [u8],
+            ) -> Result<(), Self::Error> {
+                counter_kdf::<Hmac<$hash>>(key, &[fixed_input], okm)
+            }
+        }
+    };
+}
+
+impl_kdf!(HmacSha2_256, Sha256, 8);
+impl_kdf!(HmacSha2_384, Sha384, 12);
+impl_kdf!(HmacSha2_512, Sha512, 16);
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)] // Allow unwrap in tests for cleaner test code
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+    use openprot_hal_blocking::kdf::{counter_kdf_to_slot, hkdf_expand_to_slot};
+    use openprot_hal_blocking::key_vault::{self, KeyLifecycle, KeyLocking, KeyStore};
+
+    struct Rfc5869Case {
+        ikm: &'static [u8],
+        salt: &'static [u8],
+        info: &'static [u8],
+        prk: &'static [u8],
+        okm: &'static [u8],
+    }
+
+    // RFC 5869, Appendix A.1 - A.3 (SHA-256)
+    const RFC5869_CASES: [Rfc5869Case; 3] = [
+        Rfc5869Case {
+            ikm: &hex!("0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b"),
+            salt: &hex!("000102030405060708090a0b0c"),
+            info: &hex!("f0f1f2f3f4f5f6f7f8f9"),
+            prk: &hex!("077709362c2e32df0ddc3f0dc47bba6390b6c73bb50f9c3122ec844ad7c2b3e5"),
+            okm: &hex!(
+                "3cb25f25faacd57a90434f64d0362f2a"
+                "2d2d0a90cf1a5a4c5db02d56ecc4c5bf"
+                "34007208d5b887185865"
+            ),
+        },
+        Rfc5869Case {
+            ikm: &hex!(
+                "000102030405060708090a0b0c0d0e0f"
+                "101112131415161718191a1b1c1d1e1f"
+                "202122232425262728292a2b2c2d2e2f"
+                "303132333435363738393a3b3c3d3e3f"
+                "404142434445464748494a4b4c4d4e4f"
+            ),
+            salt: &hex!(
+                "606162636465666768696a6b6c6d6e6f"
+                "707172737475767778797a7b7c7d7e7f"
+                "808182838485868788898a8b8c8d8e8f"
+                "909192939495969798999a9b9c9d9e9f"
+                "a0a1a2a3a4a5a6a7a8a9aaabacadaeaf"
+            ),
+            info: &hex!(
+                "b0b1b2b3b4b5b6b7b8b9babbbcbdbebf"
+                "c0c1c2c3c4c5c6c7c8c9cacbcccdcecf"
+                "d0d1d2d3d4d5d6d7d8d9dadbdcdddedf"
+                "e0e1e2e3e4e5e6e7e8e9eaebecedeeef"
+                "f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff"
+            ),
+            prk: &hex!("06a6b88c5853361a06104c9ceb35b45cef760014904671014a193f40c15fc244"),
+            okm: &hex!(
+                "b11e398dc80327a1c8e7f78c596a4934"
+                "4f012eda2d4efad8a050cc4c19afa97c"
+                "59045a99cac7827271cb41c65e590e09"
+                "da3275600c2f09b8367793a9aca3db71"
+                "cc30c58179ec3e87c14c01d5c1f3434f"
+                "1d87"
+            ),
+        },
+        Rfc5869Case {
+            ikm: &hex!("0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b"),
+            salt: &[],
+            info: &[],
+            prk: &hex!("19ef24a32c717b167f33a91d6f648bdf96596776afdb6377ac434c1c293ccb04"),
+            okm: &hex!(
+                "8da4e775a563c18f715f802a063c5a31"
+                "b8a11f5c5ee1879ec3454e5f3c738d2d"
+                "9d201395faa4b61a96c8"
+            ),
+        },
+    ];
+
+    #[test]
+    fn test_hkdf_sha256_rfc5869() {
+        let mut kdf = RustCryptoController::new();
+        for case in &RFC5869_CASES {
+            let prk = HkdfExtract::extract(&mut kdf, HmacSha2_256, case.salt, case.ikm).unwrap();
+            assert_eq!(prk.as_bytes(), case.prk);
+
+            let mut okm = [0u8; 82];
+            let okm = &mut okm[..case.okm.len()];
+            HkdfExpand::expand(&mut kdf, HmacSha2_256, prk.as_bytes(), case.info, okm).unwrap();
+            assert_eq!(okm, case.okm);
+        }
+    }
+
+    // RFC 5869 test case 1 and 3 inputs with SHA-384 and SHA-512, cross-checked
+    // against an independent HKDF implementation
+    const HKDF_SHA384_CASES: [Rfc5869Case; 2] = [
+        Rfc5869Case {
+            prk: &hex!(
+                "704b39990779ce1dc548052c7dc39f30"
+                "3570dd13fb39f7acc564680bef80e8de"
+                "c70ee9a7e1f3e293ef68eceb072a5ade"
+            ),
+            okm: &hex!(
+                "9b5097a86038b805309076a44b3a9f38"
+                "063e25b516dcbf369f394cfab43685f7"
+                "48b6457763e4f0204fc5"
+            ),
+            ..RFC5869_CASES[0]
+        },
+        Rfc5869Case {
+            prk: &hex!(
+                "10e40cf072a4c5626e43dd22c1cf727d"
+                "4bb140975c9ad0cbc8e45b40068f8f0b"
+                "a57cdb598af9dfa6963a96899af047e5"
+            ),
+            okm: &hex!(
+                "c8c96e710f89b0d7990bca68bcdec8cf"
+                "854062e54c73a7abc743fade9b242daa"
+                "cc1cea5670415b52849c"
+            ),
+            ..RFC5869_CASES[2]
+        },
+    ];
+
+    const HKDF_SHA512_CASES: [Rfc5869Case; 2] = [
+        Rfc5869Case {
+            prk: &hex!(
+                "665799823737ded04a88e47e54a5890b"
+                "b2c3d247c7a4254a8e61350723590a26"
+                "c36238127d8661b88cf80ef802d57e2f"
+                "7cebcf1e00e083848be19929c61b4237"
+            ),
+            okm: &hex!(
+                "832390086cda71fb47625bb5ceb168e4"
+                "c8e26a1a16ed34d9fc7fe92c14815793"
+                "38da362cb8d9f925d7cb"
+            ),
+            ..RFC5869_CASES[0]
+        },
+        Rfc5869Case {
+            prk: &hex!(
+                "fd200c4987ac491313bd4a2a13287121"
+                "247239e11c9ef82802044b66ef357e5b"
+                "194498d0682611382348572a7b1611de"
+                "54764094286320578a863f36562b0df6"
+            ),
+            okm: &hex!(
+                "f5fa02b18298a72a8c23898a8703472c"
+                "6eb179dc204c03425c970e3b164bf90f"
+                "ff22d04836d0e2343bac"
+            ),
+            ..RFC5869_CASES[2]
+        },
+    ];
+
+    #[test]
+    fn test_hkdf_sha384_sha512() {
+        let mut kdf = RustCryptoController::new();
+        for case in &HKDF_SHA384_CASES {
+            let prk = HkdfExtract::extract(&mut kdf, HmacSha2_384, case.salt, case.ikm).unwrap();
+            assert_eq!(prk.as_bytes(), case.prk);
+            let mut okm = [0u8; 42];
+            HkdfExpand::expand(&mut kdf, HmacSha2_384, prk.as_bytes(), case.info, &mut okm)
+                .unwrap();
+            assert_eq!(&okm[..], case.okm);
+        }
+        for case in &HKDF_SHA512_CASES {
+            let prk = HkdfExtract::extract(&mut kdf, HmacSha2_512, case.salt, case.ikm).unwrap();
+            assert_eq!(prk.as_bytes(), case.prk);
+            let mut okm = [0u8; 42];
+            HkdfExpand::expand(&mut kdf, HmacSha2_512, prk.as_bytes(), case.info, &mut okm)
+                .unwrap();
+            assert_eq!(&okm[..], case.okm);
+        }
+    }
+
+    #[test]
+    fn test_hkdf_expand_length_limits() {
+        let mut kdf = RustCryptoController::new();
+        let prk = [0x42u8; 48];
+
+        // 255 * HashLen is the longest permitted output
+        let mut okm = [0u8; 255 * 48 + 1];
+        assert_eq!(
+            HkdfExpand::expand(&mut kdf, HmacSha2_384, &prk, b"", &mut okm[..255 * 48]),
+            Ok(())
+        );
+        let err = HkdfExpand::expand(&mut kdf, HmacSha2_384, &prk, b"", &mut okm).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidOutputLength);
+
+        // The PRK must be at least HashLen bytes
+        let err = HkdfExpand::expand(&mut kdf, HmacSha2_384, &prk[..32], b"", &mut okm[..16])
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidKeyLength);
+    }
+
+    // NIST CAVP KBKDF (SP 800-108) counter mode, CTRLOCATION=BEFORE_FIXED,
+    // RLEN=32_BITS, L=128, COUNT=0 for each PRF
+    #[test]
+    fn test_counter_kdf_cavp_sha256() {
+        let mut kdf = RustCryptoController::new();
+        let mut okm = [0u8; 16];
+        CounterModeKdf::derive_with_fixed_input(
+            &mut kdf,
+            HmacSha2_256,
+            &hex!("dd1d91b7d90b2bd3138533ce92b272fbf8a369316aefe242e659cc0ae238afe0"),
+            &hex!(
+                "01322b96b30acd197979444e468e1c5c6859bf1b1cf951b7e725303e237e46b8"
+                "64a145fab25e517b08f8683d0315bb2911d80a0e8aba17f3b413faac"
+            ),
+            &mut okm,
+        )
+        .unwrap();
+        assert_eq!(okm, hex!("10621342bfb0fd40046c0e29f2cfdbf0"));
+    }
+
+    #[test]
+    fn test_counter_kdf_cavp_sha384() {
+        let mut kdf = RustCryptoController::new();
+        let mut okm = [0u8; 16];
+        CounterModeKdf::derive_with_fixed_input(
+            &mut kdf,
+            HmacSha2_384,
+            &hex!(
+                "216ed044769c4c3908188ece61601af8819c30f501d12995"
+                "df608e06f5e0e607ab54f542ee2da41906dfdb4971f20f9d"
+            ),
+            &hex!(
+                "638e9506a2c7be69ea346b84629a010c0e225b7548f508162c89f29c1ddbfd70"
+                "472c2b58e7dc8aa6a5b06602f1c8ed4948cda79c62708218e26ac0e2"
+            ),
+            &mut okm,
+        )
+        .unwrap();
+        assert_eq!(okm, hex!("d4b144bb40c7cabed13963d7d4318e72"));
+    }
+
+    #[test]
+    fn test_counter_kdf_cavp_sha512() {
+        let mut kdf = RustCryptoController::new();
+        let mut okm = [0u8; 16];
+        CounterModeKdf::derive_with_fixed_input(
+            &mut kdf,
+            HmacSha2_512,
+            &hex!(
+                "dd5dbd45593ee2ac139748e7645b450f223d2ff297b73fd71cbcebe71d41653c"
+                "950b88500de5322d99ef18dfdd30428294c4b3094f4c954334e593bd982ec614"
+            ),
+            &hex!(
+                "b50b0c963c6b3034b8cf19cd3f5c4ebe4f4985af0c03e575db62e6fdf1ecfe4f"
+                "28b95d7ce16df85843246e1557ce95bb26cc9a21974bbd2eb69e8355"
+            ),
+            &mut okm,
+        )
+        .unwrap();
+        assert_eq!(okm, hex!("e5993bf9bd2aa1c45746042e12598155"));
+    }
+
+    #[test]
+    fn test_counter_kdf_label_context_encoding() {
+        // derive() must equal derive_with_fixed_input() over
+        // Label || 0x00 || Context || [L]_32 (L = 640 bits here)
+        let mut kdf = RustCryptoController::new();
+        let key = [0x0bu8; 48];
+        let mut okm = [0u8; 80];
+        CounterModeKdf::derive(&mut kdf, HmacSha2_384, &key, b"label", b"ctx", &mut okm).unwrap();
+
+        let mut expected = [0u8; 80];
+        CounterModeKdf::derive_with_fixed_input(
+            &mut kdf,
+            HmacSha2_384,
+            &key,
+            b"label\x00ctx\x00\x00\x02\x80",
+            &mut expected,
+        )
+        .unwrap();
+        assert_eq!(okm, expected);
+    }
+
+    #[test]
+    fn test_counter_kdf_binds_output_length() {
+        let mut kdf = RustCryptoController::new();
+        let mut short = [0u8; 32];
+        let mut long = [0u8; 64];
+        CounterModeKdf::derive(&mut kdf, HmacSha2_256, b"key", b"l", b"c", &mut short).unwrap();
+        CounterModeKdf::derive(&mut kdf, HmacSha2_256, b"key", b"l", b"c", &mut long).unwrap();
+        assert_ne!(short, long[..32]);
+
+        let err = CounterModeKdf::derive(&mut kdf, HmacSha2_512, b"key", b"l", b"c", &mut [])
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidOutputLength);
+    }
+
+    #[derive(Debug)]
+    struct TestVaultError(key_vault::ErrorKind);
+
+    impl key_vault::Error for TestVaultError {
+        fn kind(&self) -> key_vault::ErrorKind {
+            self.0
+        }
+    }
+
+    /// Single-slot vault holding 32-byte keys
+    #[derive(Default)]
+    struct TestVault {
+        slot: Option<([u8; 32], u8)>,
+        write_locked: bool,
+    }
+
+    impl key_vault::ErrorType for TestVault {
+        type Error = TestVaultError;
+    }
+
+    impl KeyStore for TestVault {
+        type KeyId = u8;
+        type KeyUsage = u8;
+
+        fn erase_key(&mut self, _id: u8) -> Result<(), TestVaultError> {
+            self.slot = None;
+            Ok(())
+        }
+
+        fn erase_all_keys(&mut self) -> Result<(), TestVaultError> {
+            self.erase_key(0)
+        }
+
+        fn key_exists(&self, id: u8) -> Result<bool, TestVaultError> {
+            Ok(id == 0 && self.slot.is_some())
+        }
+
+        fn get_key_usage(&self, id: u8) -> Result<u8, TestVaultError> {
+            self.get_key_metadata(id)
+        }
+
+        fn set_key_usage(&mut self, _id: u8, _usage: u8) -> Result<(), TestVaultError> {
+            Err(TestVaultError(key_vault::ErrorKind::Other))
+        }
+    }
+
+    impl KeyLocking for TestVault {
+        type KeyId = u8;
+
+        fn is_write_locked(&self, id: u8) -> Result<bool, TestVaultError> {
+            if id != 0 {
+                return Err(TestVaultError(key_vault::ErrorKind::KeyNotFound));
+            }
+            Ok(self.write_locked)
+        }
+
+        fn set_write_lock(&mut self, _id: u8) -> Result<(), TestVaultError> {
+            self.write_locked = true;
+            Ok(())
+        }
+
+        fn clear_write_lock(&mut self, _id: u8) -> Result<(), TestVaultError> {
+            self.write_locked = false;
+            Ok(())
+        }
+
+        fn is_use_locked(&self, _id: u8) -> Result<bool, TestVaultError> {
+            Ok(false)
+        }
+
+        fn set_use_lock(&mut self, _id: u8) -> Result<(), TestVaultError> {
+            Err(TestVaultError(key_vault::ErrorKind::Other))
+        }
+
+        fn clear_use_lock(&mut self, _id: u8) -> Result<(), TestVaultError> {
+            Ok(())
+        }
+    }
+
+    impl KeyLifecycle for TestVault {
+        type KeyId = u8;
+        type KeyData = [u8; 32];
+        type KeyMetadata = u8;
+
+        fn store_key(
+            &mut self,
+            id: u8,
+            data: [u8; 32],
+            metadata: u8,
+        ) -> Result<(), TestVaultError> {
+            if id != 0 {
+                return Err(TestVaultError(key_vault::ErrorKind::KeyNotFound));
+            }
+            if self.write_locked {
+                return Err(TestVaultError(key_vault::ErrorKind::AccessDenied));
+            }
+            self.slot = Some((data, metadata));
+            Ok(())
+        }
+
+        fn retrieve_key(&self, _id: u8) -> Result<[u8; 32], TestVaultError> {
+            self.slot
+                .map(|(data, _)| data)
+                .ok_or(TestVaultError(key_vault::ErrorKind::KeyNotFound))
+        }
+
+        fn get_key_metadata(&self, _id: u8) -> Result<u8, TestVaultError> {
+            self.slot
+                .map(|(_, metadata)| metadata)
+                .ok_or(TestVaultError(key_vault::ErrorKind::KeyNotFound))
+        }
+
+        fn update_key_metadata(&mut self, _id: u8, _metadata: u8) -> Result<(), TestVaultError> {
+            Err(TestVaultError(key_vault::ErrorKind::Other))
+        }
+    }
+
+    #[test]
+    fn test_derive_into_key_store_slot() {
+        let mut kdf = RustCryptoController::new();
+        let mut vault = TestVault::default();
+        let case = &RFC5869_CASES[0];
+
+        hkdf_expand_to_slot::<32, _, _, _>(
+            &mut kdf,
+            HmacSha2_256,
+            case.prk,
+            case.info,
+            &mut vault,
+            0,
+            7,
+        )
+        .unwrap();
+        assert_eq!(&vault.retrieve_key(0).unwrap()[..], &case.okm[..32]);
+        assert_eq!(vault.get_key_metadata(0).unwrap(), 7);
+
+        counter_kdf_to_slot::<32, _, _, _>(
+            &mut kdf,
+            HmacSha2_256,
+            b"key",
+            b"label",
+            b"ctx",
+            &mut vault,
+            0,
+            8,
+        )
+        .unwrap();
+        let mut expected = [0u8; 32];
+        CounterModeKdf::derive(
+            &mut kdf,
+            HmacSha2_256,
+            b"key",
+            b"label",
+            b"ctx",
+            &mut expected,
+        )
+        .unwrap();
+        assert_eq!(vault.retrieve_key(0).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_derive_into_key_store_errors() {
+        let mut kdf = RustCryptoController::new();
+        let mut vault = TestVault {
+            write_locked: true,
+            ..TestVault::default()
+        };
+        let prk = [0u8; 32];
+
+        let err =
+            hkdf_expand_to_slot::<32, _, _, _>(&mut kdf, HmacSha2_256, &prk, b"", &mut vault, 0, 0)
+                .unwrap_err();
+        assert_eq!(err, ErrorKind::PermissionDenied);
+        let err = counter_kdf_to_slot::<32, _, _, _>(
+            &mut kdf,
+            HmacSha2_256,
+            b"key",
+            b"label",
+            b"ctx",
+            &mut vault,
+            0,
+            0,
+        )
+        .unwrap_err();
+        assert_eq!(err, ErrorKind::PermissionDenied);
+        assert!(vault.slot.is_none());
+
+        // Vault errors while checking the slot are passed through
+        vault.write_locked = false;
+        let err =
+            hkdf_expand_to_slot::<32, _, _, _>(&mut kdf, HmacSha2_256, &prk, b"", &mut vault, 1, 0)
+                .unwrap_err();
+        assert_eq!(err, ErrorKind::KeyStore(key_vault::ErrorKind::KeyNotFound));
+
+        // A short PRK fails before the vault is touched
+        let err = hkdf_expand_to_slot::<32, _, _, _>(
+            &mut kdf,
+            HmacSha2_256,
+            &prk[..16],
+            b"",
+            &mut vault,
+            0,
+            0,
+        )
+        .unwrap_err();
+        assert_eq!(err, ErrorKind::InvalidKeyLength);
+        assert!(vault.slot.is_none());
+
+        // Derived length must match the slot's key type
+        let err =
+            hkdf_expand_to_slot::<16, _, _, _>(&mut kdf, HmacSha2_256, &prk, b"", &mut vault, 0, 0)
+                .unwrap_err();
+        assert_eq!(err, ErrorKind::InvalidOutputLength);
+    }
+}
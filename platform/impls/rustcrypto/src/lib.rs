@@ -12,11 +12,29 @@
 #![no_std]
 
 pub mod cipher;
+pub mod ecdh;
+pub mod ecdsa;
+pub mod kdf;
+pub mod rng;
+
+#[cfg(test)]
+mod test_util;
+
+// Re-export commonly used ECDH types
+pub use ecdh::RustCryptoEcdhError;
 
 // Re-export commonly used ECDSA types
+pub use ecdsa::{EcdsaSignDeterministic, P256PrivateKey, P384PrivateKey, RustCryptoEcdsaError};
+
+// Re-export the software DRBG
+pub use rng::{HmacDrbg, HmacDrbgSha256, HmacDrbgSha384, HmacDrbgSha512};
 
 // Re-export commonly used cipher types
-pub use cipher::{Aes256CtrCipher, Aes256GcmCipher, RustCryptoCipherError};
+pub use cipher::{
+    Aes128CbcCipher, Aes128EcbCipher, Aes128GcmCipher, Aes192CbcCipher, Aes192EcbCipher,
+    Aes256CbcCipher, Aes256CtrCipher, Aes256EcbCipher, Aes256GcmCipher, ChaCha20Poly1305Cipher,
+    ChaCha20Poly1305Context, RustCryptoCipherError,
+};
 
 // Re-export RustCrypto-based controller
 pub mod controller;
@@ -0,0 +1,285 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! RustCrypto-based deterministic random bit generator
+//!
+//! [`HmacDrbg`] implements HMAC_DRBG from NIST SP 800-90A Rev. 1,
+//! Section 10.1.2, on top of the `hmac` crate. It draws its entropy input
+//! and nonce from any [`EntropySource`] and implements the
+//! `openprot_hal_blocking::rng::Drbg` trait.
+//!
+//! All hash functions are instantiated at a security strength of 256 bits,
+//! the highest strength SP 800-90A allows for HMAC_DRBG.
+
+use hmac::digest::{KeyInit, Output};
+use hmac::{Hmac, Mac};
+use openprot_hal_blocking::rng::{Drbg, EntropySource, Error, ErrorKind, ErrorType};
+use sha2::{Sha256, Sha384, Sha512};
+use zeroize::Zeroize;
+
+/// Security strength of every instantiation, in bytes
+const SECURITY_STRENGTH: usize = 32;
+
+/// Length of the nonce drawn at instantiation: half the security strength
+const NONCE_LEN: usize = SECURITY_STRENGTH / 2;
+
+/// Maximum number of bytes per generate request (2^19 bits)
+pub const MAX_REQUEST_BYTES: usize = 1 << 16;
+
+/// Maximum number of generate requests between reseeds
+pub const MAX_RESEED_INTERVAL: u64 = 1 << 48;
+
+/// HMAC_DRBG over the HMAC implementation `M`, seeded from `E`
+///
+/// The working state is zeroized when the DRBG is dropped.
+pub struct HmacDrbg<M: Mac, E> {
+    key: Output<M>,
+    value: Output<M>,
+    reseed_counter: u64,
+    reseed_interval: u64,
+    entropy: E,
+}
+
+/// HMAC_DRBG with HMAC-SHA-256
+pub type HmacDrbgSha256<E> = HmacDrbg<Hmac<Sha256>, E>;
+/// HMAC_DRBG with HMAC-SHA-384
+pub type HmacDrbgSha384<E> = HmacDrbg<Hmac<Sha384>, E>;
+/// HMAC_DRBG with HMAC-SHA-512
+pub type HmacDrbgSha512<E> = HmacDrbg<Hmac<Sha512>, E>;
+
+impl<M, E> HmacDrbg<M, E>
+where
+    M: Mac + KeyInit,
+    E: EntropySource,
+{
+    /// Instantiates the DRBG (SP 800-90A, Section 10.1.2.3).
+    ///
+    /// Draws the entropy input and the nonce from `entropy` in a single
+    /// request, then mixes in the optional `personalization` string.
+    ///
+    /// # Errors
+    ///
+    /// Any error reported by the entropy source, including health test
+    /// failures.
+    pub fn new(mut entropy: E, personalization: &[u8]) -> Result<Self, ErrorKind> {
+        let mut seed = [0u8; SECURITY_STRENGTH + NONCE_LEN];
+        let filled = entropy.fill_entropy(&mut seed).map_err(|e| e.kind());
+
+        let mut drbg = Self {
+            key: Output::<M>::default(),
+            value: Output::<M>::default(),
+            reseed_counter: 1,
+            reseed_interval: MAX_RESEED_INTERVAL,
+            entropy,
+        };
+        drbg.value.as_mut_slice().fill(0x01);
+        let result = filled.and_then(|()| drbg.update(&[&seed, personalization]));
+        seed.zeroize();
+        result.map(|()| drbg)
+    }
+
+    /// Lowers the number of generate requests allowed between reseeds.
+    ///
+    /// The interval is capped at [`MAX_RESEED_INTERVAL`].
+    pub fn with_reseed_interval(mut self, interval: u64) -> Self {
+        self.reseed_interval = interval.min(MAX_RESEED_INTERVAL);
+        self
+    }
+
+    /// HMAC_DRBG_Update (SP 800-90A, Section 10.1.2.2), with the provided
+    /// data passed in pieces to avoid a concatenation buffer.
+    fn update(&mut self, provided: &[&[u8]]) -> Result<(), ErrorKind> {
+        let rounds: &[u8] = if provided.iter().all(|part| part.is_empty()) {
+            &[0x00]
+        } else {
+            &[0x00, 0x01]
+        };
+        for &round in rounds {
+            let mut mac = self.prf()?;
+            mac.update(&self.value);
+            mac.update(&[round]);
+            for part in provided {
+                mac.update(part);
+            }
+            self.key = mac.finalize().into_bytes();
+            self.next_value()?;
+        }
+        Ok(())
+    }
+
+    /// `V = HMAC(Key, V)`
+    fn next_value(&mut self) -> Result<(), ErrorKind> {
+        let mut mac = self.prf()?;
+        mac.update(&self.value);
+        self.value = mac.finalize().into_bytes();
+        Ok(())
+    }
+
+    fn prf(&self) -> Result<M, ErrorKind> {
+        <M as Mac>::new_from_slice(&self.key).map_err(|_| ErrorKind::Other)
+    }
+}
+
+impl<M: Mac, E> Drop for HmacDrbg<M, E> {
+    fn drop(&mut self) {
+        self.key.as_mut_slice().zeroize();
+        self.value.as_mut_slice().zeroize();
+    }
+}
+
+impl<M: Mac, E> ErrorType for HmacDrbg<M, E> {
+    type Error = ErrorKind;
+}
+
+impl<M, E> Drbg for HmacDrbg<M, E>
+where
+    M: Mac + KeyInit,
+    E: EntropySource,
+{
+    fn generate(&mut self, dest: &mut [u8], additional_input: &[u8]) -> Result<(), Self::Error> {
+        if dest.len() > MAX_REQUEST_BYTES {
+            return Err(ErrorKind::RequestTooLarge);
+        }
+        if self.reseed_counter > self.reseed_interval {
+            return Err(ErrorKind::ReseedRequired);
+        }
+        if !additional_input.is_empty() {
+            self.update(&[additional_input])?;
+        }
+        for chunk in dest.chunks_mut(self.value.len()) {
+            self.next_value()?;
+            chunk.copy_from_slice(&self.value[..chunk.len()]);
+        }
+        self.update(&[additional_input])?;
+        self.reseed_counter += 1;
+        Ok(())
+    }
+
+    fn reseed(&mut self, additional_input: &[u8]) -> Result<(), Self::Error> {
+        let mut entropy_input = [0u8; SECURITY_STRENGTH];
+        let result = self
+            .entropy
+            .fill_entropy(&mut entropy_input)
+            .map_err(|e| e.kind())
+            .and_then(|()| self.update(&[&entropy_input, additional_input]));
+        entropy_input.zeroize();
+        result?;
+        self.reseed_counter = 1;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)] // Allow unwrap in tests for cleaner test code
+mod tests {
+    use super::*;
+    use crate::test_util::ReplayRng;
+    use hex_literal::hex;
+
+    struct CavpVector {
+        entropy_input: &'static [u8],
+        nonce: &'static [u8],
+        personalization: &'static [u8],
+        returned_bits: &'static [u8],
+    }
+
+    // NIST CAVP HMAC_DRBG.txt, [SHA-256], PredictionResistance = False,
+    // no reseed, COUNT = 0. Each vector generates 1024 bits twice and
+    // checks the second output.
+    const SHA256_CAVP: [CavpVector; 2] = [
+        CavpVector {
+            entropy_input: &hex!(
+                "ca851911349384bffe89de1cbdc46e6831e44d34a4fb935ee285dd14b71a7488"
+            ),
+            nonce: &hex!("659ba96c601dc69fc902940805ec0ca8"),
+            personalization: &[],
+            returned_bits: &hex!(
+                "e528e9abf2dece54d47c7e75e5fe302149f817ea9fb4bee6f4199697d04d5b89"
+                "d54fbb978a15b5c443c9ec21036d2460b6f73ebad0dc2aba6e624abf07745bc1"
+                "07694bb7547bb0995f70de25d6b29e2d3011bb19d27676c07162c8b5ccde0668"
+                "961df86803482cb37ed6d5c0bb8d50cf1f50d476aa0458bdaba806f48be9dcb8"
+            ),
+        },
+        CavpVector {
+            entropy_input: &hex!(
+                "5cacc68165a2e2ee20812f35ec73a79dbf30fd475476ac0c44fc6174cdac2b55"
+            ),
+            nonce: &hex!("6f885496c1e63af620becd9e71ecb824"),
+            personalization: &hex!(
+                "e72dd8590d4ed5295515c35ed6199e9d211b8f069b3058caa6670b96ef1208d0"
+            ),
+            returned_bits: &hex!(
+                "f1012cf543f94533df27fedfbf58e5b79a3dc517a9c402bdbfc9a0c0f721f9d5"
+                "3faf4aafdc4b8f7a1b580fcaa52338d4bd95f58966a243cdcd3f446ed4bc546d"
+                "9f607b190dd69954450d16cd0e2d6437067d8b44d19a6af7a7cfa8794e5fbd72"
+                "8e8fb2f2e8db5dd4ff1aa275f35886098e80ff844886060da8b1e7137846b23b"
+            ),
+        },
+    ];
+
+    /// Entropy source whose continuous health test has tripped
+    struct StuckEntropy;
+
+    impl ErrorType for StuckEntropy {
+        type Error = ErrorKind;
+    }
+
+    impl EntropySource for StuckEntropy {
+        fn fill_entropy(&mut self, _dest: &mut [u8]) -> Result<(), Self::Error> {
+            Err(ErrorKind::RepetitionCountFailure)
+        }
+    }
+
+    #[test]
+    fn test_hmac_drbg_sha256_cavp() {
+        for v in &SHA256_CAVP {
+            let mut seed = [0u8; 48];
+            seed[..32].copy_from_slice(v.entropy_input);
+            seed[32..].copy_from_slice(v.nonce);
+
+            let mut drbg = HmacDrbgSha256::new(ReplayRng::new(&seed), v.personalization).unwrap();
+            let mut out = [0u8; 128];
+            drbg.generate(&mut out, &[]).unwrap();
+            drbg.generate(&mut out, &[]).unwrap();
+            assert_eq!(out, v.returned_bits);
+        }
+    }
+
+    #[test]
+    fn test_hmac_drbg_reseed_interval() {
+        let mut drbg = HmacDrbgSha384::new(ReplayRng::new(&[0x5a]), b"openprot")
+            .unwrap()
+            .with_reseed_interval(2);
+        let mut first = [0u8; 70];
+        let mut second = [0u8; 70];
+        drbg.generate(&mut first, &[]).unwrap();
+        drbg.generate(&mut second, b"additional").unwrap();
+        assert_ne!(first, second);
+        assert_eq!(
+            drbg.generate(&mut first, &[]),
+            Err(ErrorKind::ReseedRequired)
+        );
+
+        drbg.reseed(&[]).unwrap();
+        drbg.generate(&mut first, &[]).unwrap();
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn test_hmac_drbg_request_limit() {
+        let mut drbg = HmacDrbgSha512::new(ReplayRng::new(&[0xa5]), &[]).unwrap();
+        let mut out = [0u8; MAX_REQUEST_BYTES + 1];
+        assert_eq!(
+            drbg.generate(&mut out, &[]),
+            Err(ErrorKind::RequestTooLarge)
+        );
+        drbg.generate(&mut out[..MAX_REQUEST_BYTES], &[]).unwrap();
+    }
+
+    #[test]
+    fn test_hmac_drbg_entropy_failure() {
+        let err = HmacDrbgSha256::new(StuckEntropy, &[]).err().unwrap();
+        assert_eq!(err, ErrorKind::RepetitionCountFailure);
+        assert!(err.is_health_test_failure());
+    }
+}
@@ -0,0 +1,52 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! Fixtures shared by the unit tests of this crate
+
+/// RNG that replays a fixed byte pattern; lets tests pin "random" keys.
+///
+/// It doubles as an entropy source for DRBG known-answer tests.
+pub(crate) struct ReplayRng<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> ReplayRng<'a> {
+    pub(crate) fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, pos: 0 }
+    }
+}
+
+impl rand_core::RngCore for ReplayRng<'_> {
+    fn next_u32(&mut self) -> u32 {
+        let mut buf = [0u8; 4];
+        self.fill_bytes(&mut buf);
+        u32::from_le_bytes(buf)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut buf = [0u8; 8];
+        self.fill_bytes(&mut buf);
+        u64::from_le_bytes(buf)
+    }
+
+    fn fill_bytes(&mut self, dst: &mut [u8]) {
+        for byte in dst.iter_mut() {
+            *byte = self.bytes[self.pos % self.bytes.len()];
+            self.pos += 1;
+        }
+    }
+}
+
+impl rand_core::CryptoRng for ReplayRng<'_> {}
+
+impl openprot_hal_blocking::rng::ErrorType for ReplayRng<'_> {
+    type Error = core::convert::Infallible;
+}
+
+impl openprot_hal_blocking::rng::EntropySource for ReplayRng<'_> {
+    fn fill_entropy(&mut self, dest: &mut [u8]) -> Result<(), Self::Error> {
+        rand_core::RngCore::fill_bytes(self, dest);
+        Ok(())
+    }
+}
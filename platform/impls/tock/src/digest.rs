@@ -0,0 +1,262 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! SHA-2 digests on the Tock SHA capsule
+//!
+//! [`TockDigest`] implements both the owned and the scoped digest APIs for
+//! SHA-256, SHA-384 and SHA-512. The owned contexts carry the controller and
+//! hand it back on `finalize`/`cancel`; the scoped contexts borrow it for the
+//! lifetime of the computation.
+
+use openprot_hal_blocking::digest::owned::{DigestInit, DigestOp};
+use openprot_hal_blocking::digest::scoped::{
+    DigestInit as ScopedDigestInit, DigestOp as ScopedDigestOp,
+};
+use openprot_hal_blocking::digest::{Digest, ErrorType, Sha2_256, Sha2_384, Sha2_512};
+
+use crate::engine::{self, ALGORITHM_SHA256, ALGORITHM_SHA384, ALGORITHM_SHA512, UPCALL_DONE};
+use crate::syscall::{open_driver, ErrorCode, Syscalls};
+
+/// Driver number of the SHA capsule.
+pub const DRIVER_NUM: u32 = 0x40005;
+
+/// Digest controller backed by the Tock SHA capsule.
+///
+/// Owns the system call handle, so only one computation can be in flight.
+pub struct TockDigest<S: Syscalls> {
+    kernel: S,
+}
+
+impl<S: Syscalls> TockDigest<S> {
+    /// Opens the SHA capsule, failing if the kernel does not provide it.
+    pub fn new(mut kernel: S) -> Result<Self, ErrorCode> {
+        open_driver(&mut kernel, DRIVER_NUM, UPCALL_DONE)?;
+        Ok(Self { kernel })
+    }
+
+    /// Releases the system call handle.
+    pub fn into_inner(self) -> S {
+        self.kernel
+    }
+}
+
+impl<S: Syscalls> ErrorType for TockDigest<S> {
+    type Error = ErrorCode;
+}
+
+/// Macro to implement owned and scoped digest contexts for each SHA-2 variant
+macro_rules! impl_tock_digest {
+    ($algo:ident, $id:expr, $context:ident, $scoped:ident, $words:expr) => {
+        /// SHA-2 digest context (owned API)
+        pub struct $context<S: Syscalls> {
+            controller: TockDigest<S>,
+        }
+
+        /// SHA-2 digest context (scoped API)
+        ///
+        /// Borrows the controller for the lifetime of the computation.
+        pub struct $scoped<'a, S: Syscalls> {
+            controller: &'a mut TockDigest<S>,
+        }
+
+        impl<S: Syscalls> DigestInit<$algo> for TockDigest<S> {
+            type Context = $context<S>;
+            type Output = Digest<$words>;
+
+            fn init(mut self, _algorithm: $algo) -> Result<Self::Context, Self::Error> {
+                engine::start(&mut self.kernel, DRIVER_NUM, $id)?;
+                Ok($context { controller: self })
+            }
+        }
+
+        impl<S: Syscalls> ErrorType for $context<S> {
+            type Error = ErrorCode;
+        }
+
+        impl<S: Syscalls> DigestOp for $context<S> {
+            type Output = Digest<$words>;
+            type Controller = TockDigest<S>;
+
+            fn update(mut self, data: &[u8]) -> Result<Self, Self::Error> {
+                engine::update(&mut self.controller.kernel, DRIVER_NUM, data)?;
+                Ok(self)
+            }
+
+            fn finalize(mut self) -> Result<(Self::Output, Self::Controller), Self::Error> {
+                let digest = engine::finish(&mut self.controller.kernel, DRIVER_NUM)?;
+                Ok((digest, self.controller))
+            }
+
+            fn cancel(self) -> Self::Controller {
+                // The next `init` selects an algorithm, which discards the
+                // capsule's partial state.
+                self.controller
+            }
+        }
+
+        impl<S: Syscalls> ScopedDigestInit<$algo> for TockDigest<S> {
+            type OpContext<'a>
+                = $scoped<'a, S>
+            where
+                Self: 'a;
+            type Output = Digest<$words>;
+
+            fn init(&mut self, _algorithm: $algo) -> Result<Self::OpContext<'_>, Self::Error> {
+                engine::start(&mut self.kernel, DRIVER_NUM, $id)?;
+                Ok($scoped { controller: self })
+            }
+        }
+
+        impl<S: Syscalls> ErrorType for $scoped<'_, S> {
+            type Error = ErrorCode;
+        }
+
+        impl<S: Syscalls> ScopedDigestOp for $scoped<'_, S> {
+            type Output = Digest<$words>;
+
+            fn update(&mut self, input: &[u8]) -> Result<(), Self::Error> {
+                engine::update(&mut self.controller.kernel, DRIVER_NUM, input)
+            }
+
+            fn finalize(self) -> Result<Self::Output, Self::Error> {
+                engine::finish(&mut self.controller.kernel, DRIVER_NUM)
+            }
+        }
+    };
+}
+
+impl_tock_digest!(
+    Sha2_256,
+    ALGORITHM_SHA256,
+    DigestContext256,
+    ScopedDigestContext256,
+    8
+);
+impl_tock_digest!(
+    Sha2_384,
+    ALGORITHM_SHA384,
+    DigestContext384,
+    ScopedDigestContext384,
+    12
+);
+impl_tock_digest!(
+    Sha2_512,
+    ALGORITHM_SHA512,
+    DigestContext512,
+    ScopedDigestContext512,
+    16
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{CMD_FINISH, CMD_SET_ALGORITHM, CMD_UPDATE, RO_DATA, RW_DEST};
+    use crate::fake::{Call, FakeKernel};
+    use hex_literal::hex;
+    use openprot_hal_blocking::digest::Error as _;
+    use openprot_hal_blocking::digest::ErrorKind;
+
+    fn controller() -> TockDigest<FakeKernel> {
+        TockDigest::new(FakeKernel::new()).unwrap()
+    }
+
+    #[test]
+    fn test_owned_known_answers() {
+        let context = DigestInit::<Sha2_256>::init(controller(), Sha2_256).unwrap();
+        let (digest, controller) = context.update(b"abc").unwrap().finalize().unwrap();
+        assert_eq!(
+            digest.as_bytes(),
+            hex!("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+        );
+
+        let context = DigestInit::<Sha2_384>::init(controller, Sha2_384).unwrap();
+        let (digest, controller) = context.update(b"abc").unwrap().finalize().unwrap();
+        assert_eq!(
+            digest.as_bytes(),
+            hex!(
+                "cb00753f45a35e8bb5a03d699ac65007272c32ab0eded1631a8b605a43ff5bed"
+                "8086072ba1e7cc2358baeca134c825a7"
+            )
+        );
+
+        let context = DigestInit::<Sha2_512>::init(controller, Sha2_512).unwrap();
+        let (digest, _) = context.update(b"abc").unwrap().finalize().unwrap();
+        assert_eq!(
+            digest.as_bytes(),
+            hex!(
+                "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a"
+                "2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f"
+            )
+        );
+    }
+
+    #[test]
+    fn test_scoped_streaming_matches_one_shot() {
+        let mut controller = controller();
+
+        let mut context = ScopedDigestInit::<Sha2_384>::init(&mut controller, Sha2_384).unwrap();
+        context.update(b"a").unwrap();
+        context.update(&[]).unwrap();
+        context.update(b"bc").unwrap();
+        let streamed = context.finalize().unwrap();
+
+        let mut context = ScopedDigestInit::<Sha2_384>::init(&mut controller, Sha2_384).unwrap();
+        context.update(b"abc").unwrap();
+        assert!(streamed == context.finalize().unwrap());
+    }
+
+    #[test]
+    fn test_cancel_discards_partial_state() {
+        let context = DigestInit::<Sha2_256>::init(controller(), Sha2_256).unwrap();
+        let controller = context.update(b"garbage").unwrap().cancel();
+
+        let context = DigestInit::<Sha2_256>::init(controller, Sha2_256).unwrap();
+        let (digest, _) = context.update(b"abc").unwrap().finalize().unwrap();
+        assert_eq!(
+            digest.as_bytes(),
+            hex!("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+        );
+    }
+
+    #[test]
+    fn test_syscall_marshalling() {
+        let context = DigestInit::<Sha2_256>::init(controller(), Sha2_256).unwrap();
+        let (_, controller) = context.update(b"abc").unwrap().finalize().unwrap();
+        let kernel = controller.into_inner();
+
+        let expected = [
+            Call::Command(DRIVER_NUM, 0, 0, 0),
+            Call::Subscribe(DRIVER_NUM, UPCALL_DONE),
+            Call::Command(DRIVER_NUM, CMD_SET_ALGORITHM, ALGORITHM_SHA256, 0),
+            Call::AllowRo(DRIVER_NUM, RO_DATA, 3),
+            Call::Command(DRIVER_NUM, CMD_UPDATE, 0, 0),
+            Call::Yield(DRIVER_NUM, UPCALL_DONE),
+            Call::AllowRo(DRIVER_NUM, RO_DATA, 0),
+            Call::AllowRw(DRIVER_NUM, RW_DEST, 32),
+            Call::Command(DRIVER_NUM, CMD_FINISH, 0, 0),
+            Call::Yield(DRIVER_NUM, UPCALL_DONE),
+            Call::AllowRw(DRIVER_NUM, RW_DEST, 0),
+        ];
+        assert_eq!(kernel.calls, expected);
+        assert!(kernel.nothing_shared());
+    }
+
+    #[test]
+    fn test_missing_driver() {
+        let kernel = FakeKernel::without_driver(DRIVER_NUM);
+        let err = TockDigest::new(kernel).err().unwrap();
+        assert_eq!(err, ErrorCode::NoDevice);
+        assert_eq!(err.kind(), ErrorKind::UnsupportedAlgorithm);
+    }
+
+    #[test]
+    fn test_engine_failure_revokes_buffer() {
+        let mut controller = controller();
+        controller.kernel.fail_next_upcall(ErrorCode::Fail);
+
+        let mut context = ScopedDigestInit::<Sha2_256>::init(&mut controller, Sha2_256).unwrap();
+        let err = context.update(b"abc").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::HardwareFailure);
+        assert!(controller.kernel.nothing_shared());
+    }
+}
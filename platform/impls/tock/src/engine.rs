@@ -0,0 +1,99 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! Streaming hash engine driver interface
+//!
+//! The SHA and HMAC capsules expose the same streaming interface, differing
+//! only in driver number and in whether a key is shared:
+//!
+//! | Kind      | Number | Meaning                                         |
+//! |-----------|--------|-------------------------------------------------|
+//! | Command   | 0      | Driver present                                  |
+//! | Command   | 1      | Select algorithm `arg0`, loading the shared key |
+//! | Command   | 2      | Absorb the shared data buffer                   |
+//! | Command   | 3      | Write the result into the shared destination    |
+//! | Allow RO  | 0      | Key (HMAC only)                                 |
+//! | Allow RO  | 1      | Data                                            |
+//! | Allow RW  | 0      | Destination                                     |
+//! | Subscribe | 0      | Operation complete, `arg0` = status             |
+//!
+//! Selecting an algorithm discards any computation in progress.
+
+use openprot_hal_blocking::digest::Digest;
+
+use crate::syscall::{command_and_wait, share_ro, share_rw, ErrorCode, Syscalls};
+
+/// Completion upcall of both engines.
+pub(crate) const UPCALL_DONE: u32 = 0;
+
+pub(crate) const CMD_SET_ALGORITHM: u32 = 1;
+pub(crate) const CMD_UPDATE: u32 = 2;
+pub(crate) const CMD_FINISH: u32 = 3;
+
+pub(crate) const RO_KEY: u32 = 0;
+pub(crate) const RO_DATA: u32 = 1;
+pub(crate) const RW_DEST: u32 = 0;
+
+pub(crate) const ALGORITHM_SHA256: u32 = 0;
+pub(crate) const ALGORITHM_SHA384: u32 = 1;
+pub(crate) const ALGORITHM_SHA512: u32 = 2;
+
+/// Largest result either engine produces (SHA-512).
+const MAX_OUTPUT_SIZE: usize = 64;
+
+/// Starts a keyless computation.
+pub(crate) fn start<S: Syscalls + ?Sized>(
+    kernel: &mut S,
+    driver: u32,
+    algorithm: u32,
+) -> Result<(), ErrorCode> {
+    kernel
+        .command(driver, CMD_SET_ALGORITHM, algorithm, 0)
+        .to_result()
+}
+
+/// Starts a keyed computation; the key is only shared while it is loaded.
+pub(crate) fn start_keyed<S: Syscalls + ?Sized>(
+    kernel: &mut S,
+    driver: u32,
+    algorithm: u32,
+    key: &[u8],
+) -> Result<(), ErrorCode> {
+    share_ro(kernel, driver, RO_KEY, key, |kernel| {
+        start(kernel, driver, algorithm)
+    })
+}
+
+/// Absorbs `data` into the running computation.
+pub(crate) fn update<S: Syscalls + ?Sized>(
+    kernel: &mut S,
+    driver: u32,
+    data: &[u8],
+) -> Result<(), ErrorCode> {
+    if data.is_empty() {
+        return Ok(());
+    }
+    share_ro(kernel, driver, RO_DATA, data, |kernel| {
+        let (status, _, _) = command_and_wait(kernel, driver, UPCALL_DONE, CMD_UPDATE, 0, 0)?;
+        ErrorCode::check(status)
+    })
+}
+
+/// Completes the running computation.
+pub(crate) fn finish<S: Syscalls + ?Sized, const N: usize>(
+    kernel: &mut S,
+    driver: u32,
+) -> Result<Digest<N>, ErrorCode> {
+    let mut dest = [0u8; MAX_OUTPUT_SIZE];
+    let dest = dest.get_mut(..N.saturating_mul(4)).ok_or(ErrorCode::Size)?;
+    share_rw(kernel, driver, RW_DEST, dest, |kernel| {
+        let (status, _, _) = command_and_wait(kernel, driver, UPCALL_DONE, CMD_FINISH, 0, 0)?;
+        ErrorCode::check(status)
+    })?;
+
+    let mut words = [0u32; N];
+    for (word, chunk) in words.iter_mut().zip(dest.chunks_exact(4)) {
+        *word = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+    }
+    Ok(Digest::new(words))
+}
@@ -0,0 +1,393 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! Fake Tock kernel for host tests
+//!
+//! Records every system call and emulates the SHA, HMAC and I2C capsules
+//! closely enough to check both the marshalling and the results: digests and
+//! MACs are computed with RustCrypto, the I2C master talks to an EEPROM-like
+//! register file at [`EEPROM_ADDRESS`], and slave transactions are scripted
+//! with [`FakeKernel::script_bus`].
+//!
+//! Shared buffers are accessed through the pointers passed to allow, exactly
+//! as the kernel would; a yield with no queued upcall panics instead of
+//! blocking forever.
+
+use std::collections::{HashMap, VecDeque};
+use std::vec::Vec;
+
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256, Sha384, Sha512};
+
+use crate::syscall::{CommandReturn, ErrorCode, Syscalls};
+use crate::{digest, engine, i2c, mac};
+
+/// Address the emulated EEPROM answers to.
+pub const EEPROM_ADDRESS: u8 = 0x50;
+
+/// A recorded system call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Call {
+    /// `(driver, command, arg0, arg1)`
+    Command(u32, u32, u32, u32),
+    /// `(driver, buffer, len)`; a length of zero is a revocation.
+    AllowRo(u32, u32, usize),
+    /// `(driver, buffer, len)`; a length of zero is a revocation.
+    AllowRw(u32, u32, usize),
+    /// `(driver, subscribe)`
+    Subscribe(u32, u32),
+    /// `(driver, subscribe)`
+    Yield(u32, u32),
+}
+
+/// Controller activity seen by a listening slave.
+pub enum BusEvent {
+    /// The controller writes these bytes.
+    Write(Vec<u8>),
+    /// The controller reads whatever the target sends.
+    Read,
+}
+
+enum Hasher {
+    Sha256(Sha256),
+    Sha384(Sha384),
+    Sha512(Sha512),
+    Hmac256(Hmac<Sha256>),
+    Hmac384(Hmac<Sha384>),
+    Hmac512(Hmac<Sha512>),
+}
+
+impl Hasher {
+    fn digest(algorithm: u32) -> Result<Self, ErrorCode> {
+        match algorithm {
+            engine::ALGORITHM_SHA256 => Ok(Hasher::Sha256(Sha256::new())),
+            engine::ALGORITHM_SHA384 => Ok(Hasher::Sha384(Sha384::new())),
+            engine::ALGORITHM_SHA512 => Ok(Hasher::Sha512(Sha512::new())),
+            _ => Err(ErrorCode::NoSupport),
+        }
+    }
+
+    fn hmac(algorithm: u32, key: &[u8]) -> Result<Self, ErrorCode> {
+        let invalid = |_| ErrorCode::Invalid;
+        match algorithm {
+            engine::ALGORITHM_SHA256 => {
+                Ok(Hasher::Hmac256(Hmac::new_from_slice(key).map_err(invalid)?))
+            }
+            engine::ALGORITHM_SHA384 => {
+                Ok(Hasher::Hmac384(Hmac::new_from_slice(key).map_err(invalid)?))
+            }
+            engine::ALGORITHM_SHA512 => {
+                Ok(Hasher::Hmac512(Hmac::new_from_slice(key).map_err(invalid)?))
+            }
+            _ => Err(ErrorCode::NoSupport),
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            Hasher::Sha256(h) => Digest::update(h, data),
+            Hasher::Sha384(h) => Digest::update(h, data),
+            Hasher::Sha512(h) => Digest::update(h, data),
+            Hasher::Hmac256(h) => Mac::update(h, data),
+            Hasher::Hmac384(h) => Mac::update(h, data),
+            Hasher::Hmac512(h) => Mac::update(h, data),
+        }
+    }
+
+    fn finish(self) -> Vec<u8> {
+        match self {
+            Hasher::Sha256(h) => h.finalize().to_vec(),
+            Hasher::Sha384(h) => h.finalize().to_vec(),
+            Hasher::Sha512(h) => h.finalize().to_vec(),
+            Hasher::Hmac256(h) => h.finalize().into_bytes().to_vec(),
+            Hasher::Hmac384(h) => h.finalize().into_bytes().to_vec(),
+            Hasher::Hmac512(h) => h.finalize().into_bytes().to_vec(),
+        }
+    }
+}
+
+/// An upcall waiting for Yield-WaitFor: `(driver, subscribe)` and its
+/// arguments.
+type QueuedUpcall = ((u32, u32), (u32, u32, u32));
+
+/// Recording kernel emulating the capsules this crate drives.
+pub struct FakeKernel {
+    /// Every system call, in order.
+    pub calls: Vec<Call>,
+    /// Slave address programmed through the I2C capsule.
+    pub slave_address: Option<u8>,
+    /// Bytes the slave sent in answer to controller reads.
+    pub sent_to_controller: Vec<u8>,
+    drivers: Vec<u32>,
+    ro: HashMap<(u32, u32), (*const u8, usize)>,
+    rw: HashMap<(u32, u32), (*mut u8, usize)>,
+    subscribed: Vec<(u32, u32)>,
+    pending: VecDeque<QueuedUpcall>,
+    fail_next: Option<ErrorCode>,
+    hashers: HashMap<u32, Hasher>,
+    eeprom: [u8; 256],
+    eeprom_pointer: u8,
+    bus: VecDeque<BusEvent>,
+}
+
+impl FakeKernel {
+    /// A kernel providing the SHA, HMAC and I2C capsules.
+    pub fn new() -> Self {
+        Self {
+            calls: Vec::new(),
+            slave_address: None,
+            sent_to_controller: Vec::new(),
+            drivers: std::vec![digest::DRIVER_NUM, mac::DRIVER_NUM, i2c::DRIVER_NUM],
+            ro: HashMap::new(),
+            rw: HashMap::new(),
+            subscribed: Vec::new(),
+            pending: VecDeque::new(),
+            fail_next: None,
+            hashers: HashMap::new(),
+            eeprom: [0; 256],
+            eeprom_pointer: 0,
+            bus: VecDeque::new(),
+        }
+    }
+
+    /// A kernel missing `driver`.
+    pub fn without_driver(driver: u32) -> Self {
+        let mut kernel = Self::new();
+        kernel.drivers.retain(|&d| d != driver);
+        kernel
+    }
+
+    /// Makes the next hash engine completion report `error`.
+    pub fn fail_next_upcall(&mut self, error: ErrorCode) {
+        self.fail_next = Some(error);
+    }
+
+    /// Queues controller activity for the next listen.
+    pub fn script_bus(&mut self, event: BusEvent) {
+        self.bus.push_back(event);
+    }
+
+    /// Whether every allow has been revoked.
+    pub fn nothing_shared(&self) -> bool {
+        self.ro.is_empty() && self.rw.is_empty()
+    }
+
+    fn ro_bytes(&self, driver: u32, buffer: u32) -> Vec<u8> {
+        match self.ro.get(&(driver, buffer)) {
+            // SAFETY: allow_ro's contract keeps the buffer valid until revoked.
+            Some(&(ptr, len)) => unsafe { core::slice::from_raw_parts(ptr, len) }.to_vec(),
+            None => Vec::new(),
+        }
+    }
+
+    fn rw_slice(&mut self, driver: u32, buffer: u32) -> &mut [u8] {
+        match self.rw.get(&(driver, buffer)) {
+            // SAFETY: allow_rw's contract keeps the buffer valid and
+            // untouched by the process until revoked.
+            Some(&(ptr, len)) => unsafe { core::slice::from_raw_parts_mut(ptr, len) },
+            None => &mut [],
+        }
+    }
+
+    fn complete(&mut self, driver: u32, subscribe: u32, args: (u32, u32, u32)) {
+        // Like the kernel, drop upcalls nobody subscribed to.
+        if self.subscribed.contains(&(driver, subscribe)) {
+            self.pending.push_back(((driver, subscribe), args));
+        }
+    }
+
+    fn engine_command(&mut self, driver: u32, command: u32, arg0: u32) -> CommandReturn {
+        match command {
+            engine::CMD_SET_ALGORITHM => {
+                let hasher = if driver == mac::DRIVER_NUM {
+                    Hasher::hmac(arg0, &self.ro_bytes(driver, engine::RO_KEY))
+                } else {
+                    Hasher::digest(arg0)
+                };
+                match hasher {
+                    Ok(hasher) => {
+                        self.hashers.insert(driver, hasher);
+                        CommandReturn::success()
+                    }
+                    Err(error) => CommandReturn::failure(error),
+                }
+            }
+            engine::CMD_UPDATE | engine::CMD_FINISH => {
+                if !self.hashers.contains_key(&driver) {
+                    return CommandReturn::failure(ErrorCode::Off);
+                }
+                let status = match self.fail_next.take() {
+                    Some(error) => error as u32,
+                    None if command == engine::CMD_UPDATE => {
+                        let data = self.ro_bytes(driver, engine::RO_DATA);
+                        if let Some(hasher) = self.hashers.get_mut(&driver) {
+                            hasher.update(&data);
+                        }
+                        0
+                    }
+                    None => {
+                        let result = self.hashers.remove(&driver).map(Hasher::finish);
+                        let dest = self.rw_slice(driver, engine::RW_DEST);
+                        match result {
+                            Some(result) if result.len() == dest.len() => {
+                                dest.copy_from_slice(&result);
+                                0
+                            }
+                            _ => ErrorCode::Size as u32,
+                        }
+                    }
+                };
+                self.complete(driver, engine::UPCALL_DONE, (status, 0, 0));
+                CommandReturn::success()
+            }
+            _ => CommandReturn::failure(ErrorCode::NoSupport),
+        }
+    }
+
+    fn eeprom_write(&mut self, address: u32, len: usize) -> u32 {
+        if address != u32::from(EEPROM_ADDRESS) {
+            return ErrorCode::NoAck as u32;
+        }
+        let data = self.rw_slice(i2c::DRIVER_NUM, i2c::RW_MASTER_TX)[..len].to_vec();
+        if let Some((&pointer, values)) = data.split_first() {
+            self.eeprom_pointer = pointer;
+            for value in values {
+                self.eeprom[usize::from(self.eeprom_pointer)] = *value;
+                self.eeprom_pointer = self.eeprom_pointer.wrapping_add(1);
+            }
+        }
+        0
+    }
+
+    fn eeprom_read(&mut self, address: u32, len: usize) -> u32 {
+        if address != u32::from(EEPROM_ADDRESS) {
+            return ErrorCode::NoAck as u32;
+        }
+        let mut data = std::vec![0u8; len];
+        for byte in &mut data {
+            *byte = self.eeprom[usize::from(self.eeprom_pointer)];
+            self.eeprom_pointer = self.eeprom_pointer.wrapping_add(1);
+        }
+        self.rw_slice(i2c::DRIVER_NUM, i2c::RW_MASTER_RX)[..len].copy_from_slice(&data);
+        0
+    }
+
+    fn i2c_command(&mut self, command: u32, arg0: u32, arg1: u32) -> CommandReturn {
+        let driver = i2c::DRIVER_NUM;
+        // Command numbers of the upstream `i2c_master_slave_driver` capsule,
+        // written out so that a wrong constant in the driver fails here.
+        let args = match command {
+            // Master write.
+            1 => {
+                let status = self.eeprom_write(arg0, arg1 as usize);
+                (i2c::EVENT_MASTER_WRITE, status, 0)
+            }
+            // Master read.
+            2 => {
+                let status = self.eeprom_read(arg0, arg1 as usize);
+                (i2c::EVENT_MASTER_READ, status, arg1)
+            }
+            // Master write-read.
+            7 => {
+                let (write_len, read_len) = (arg1 & 0xff, arg1 >> 8);
+                let status = match self.eeprom_write(arg0, write_len as usize) {
+                    0 => self.eeprom_read(arg0, read_len as usize),
+                    status => status,
+                };
+                (i2c::EVENT_MASTER_READ, status, read_len)
+            }
+            // Set slave address.
+            6 => {
+                self.slave_address = Some(arg0 as u8);
+                return CommandReturn::success();
+            }
+            // Listen.
+            3 => match self.bus.pop_front() {
+                Some(BusEvent::Write(bytes)) => {
+                    let rx = self.rw_slice(driver, i2c::RW_SLAVE_RX);
+                    let len = bytes.len().min(rx.len());
+                    rx[..len].copy_from_slice(&bytes[..len]);
+                    (i2c::EVENT_SLAVE_WRITE, 0, len as u32)
+                }
+                Some(BusEvent::Read) => (i2c::EVENT_SLAVE_READ_REQUEST, 0, 0),
+                None => panic!("no controller activity scripted"),
+            },
+            // Slave read send.
+            4 => {
+                let len = arg0 as usize;
+                let sent = self.rw_slice(driver, i2c::RW_SLAVE_TX)[..len].to_vec();
+                self.sent_to_controller.extend_from_slice(&sent);
+                (i2c::EVENT_SLAVE_READ_COMPLETE, 0, arg0)
+            }
+            _ => return CommandReturn::failure(ErrorCode::NoSupport),
+        };
+        self.complete(driver, i2c::UPCALL, args);
+        CommandReturn::success()
+    }
+}
+
+impl Syscalls for FakeKernel {
+    fn command(&mut self, driver: u32, command: u32, arg0: u32, arg1: u32) -> CommandReturn {
+        self.calls.push(Call::Command(driver, command, arg0, arg1));
+        if !self.drivers.contains(&driver) {
+            return CommandReturn::failure(ErrorCode::NoDevice);
+        }
+        match (driver, command) {
+            (_, 0) => CommandReturn::success(),
+            (digest::DRIVER_NUM | mac::DRIVER_NUM, _) => self.engine_command(driver, command, arg0),
+            (i2c::DRIVER_NUM, _) => self.i2c_command(command, arg0, arg1),
+            _ => CommandReturn::failure(ErrorCode::NoSupport),
+        }
+    }
+
+    unsafe fn allow_ro(
+        &mut self,
+        driver: u32,
+        buffer: u32,
+        ptr: *const u8,
+        len: usize,
+    ) -> Result<(), ErrorCode> {
+        self.calls.push(Call::AllowRo(driver, buffer, len));
+        if len == 0 {
+            self.ro.remove(&(driver, buffer));
+        } else {
+            self.ro.insert((driver, buffer), (ptr, len));
+        }
+        Ok(())
+    }
+
+    unsafe fn allow_rw(
+        &mut self,
+        driver: u32,
+        buffer: u32,
+        ptr: *mut u8,
+        len: usize,
+    ) -> Result<(), ErrorCode> {
+        self.calls.push(Call::AllowRw(driver, buffer, len));
+        if len == 0 {
+            self.rw.remove(&(driver, buffer));
+        } else {
+            self.rw.insert((driver, buffer), (ptr, len));
+        }
+        Ok(())
+    }
+
+    fn subscribe(&mut self, driver: u32, subscribe: u32) -> Result<(), ErrorCode> {
+        self.calls.push(Call::Subscribe(driver, subscribe));
+        if !self.drivers.contains(&driver) {
+            return Err(ErrorCode::NoDevice);
+        }
+        self.subscribed.push((driver, subscribe));
+        Ok(())
+    }
+
+    fn yield_wait_for(&mut self, driver: u32, subscribe: u32) -> (u32, u32, u32) {
+        self.calls.push(Call::Yield(driver, subscribe));
+        let index = self
+            .pending
+            .iter()
+            .position(|(upcall, _)| *upcall == (driver, subscribe))
+            .expect("yield would block forever: no upcall queued");
+        self.pending.remove(index).map(|(_, args)| args).unwrap()
+    }
+}
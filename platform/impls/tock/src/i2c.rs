@@ -0,0 +1,578 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! I2C on the Tock I2C master/slave capsule
+//!
+//! [`TockI2cMaster`] implements [`I2cMaster`] and [`TockI2cTarget`] serves an
+//! [`I2CTarget`](openprot_hal_blocking::i2c_device::I2CTarget) device model
+//! from the capsule's slave events. Both drive the same capsule interface:
+//!
+//! | Kind      | Number | Meaning                                              |
+//! |-----------|--------|------------------------------------------------------|
+//! | Command   | 1      | Master write of `arg1` bytes to `arg0`               |
+//! | Command   | 2      | Master read of `arg1` bytes from `arg0`              |
+//! | Command   | 3      | Listen for one slave transaction                     |
+//! | Command   | 4      | Send `arg0` bytes in answer to a slave read          |
+//! | Command   | 5      | Set the slave address to `arg0`                      |
+//! | Command   | 6      | Master write-read; `arg1` = `read_len << 8 \| write_len` |
+//! | Allow RW  | 0..=3  | Master TX, master RX, slave RX, slave TX             |
+//! | Subscribe | 0      | `(event, status, length)`                            |
+//!
+//! Transfers are limited to [`MAX_TRANSFER`] bytes in each direction.
+
+use embedded_hal::i2c::{ErrorType, Operation, SevenBitAddress};
+use openprot_hal_blocking::i2c_device::{
+    ReadTarget, TransactionDirection, TransactionResponse, WriteTarget,
+};
+use openprot_hal_blocking::i2c_hardware::{I2cHardwareCore, I2cMaster};
+
+use crate::syscall::{command_and_wait, open_driver, share_rw, ErrorCode, Syscalls};
+
+/// Driver number of the I2C master/slave capsule.
+pub const DRIVER_NUM: u32 = 0x20006;
+
+/// Largest transfer in either direction, bounded by the write-read packing.
+pub const MAX_TRANSFER: usize = 255;
+
+pub(crate) const UPCALL: u32 = 0;
+
+pub(crate) const CMD_MASTER_WRITE: u32 = 1;
+pub(crate) const CMD_MASTER_READ: u32 = 2;
+pub(crate) const CMD_LISTEN: u32 = 3;
+pub(crate) const CMD_SLAVE_READ_SEND: u32 = 4;
+pub(crate) const CMD_SET_SLAVE_ADDRESS: u32 = 6;
+pub(crate) const CMD_MASTER_WRITE_READ: u32 = 7;
+
+pub(crate) const RW_MASTER_TX: u32 = 0;
+pub(crate) const RW_MASTER_RX: u32 = 1;
+pub(crate) const RW_SLAVE_RX: u32 = 2;
+pub(crate) const RW_SLAVE_TX: u32 = 3;
+
+pub(crate) const EVENT_MASTER_WRITE: u32 = 0;
+pub(crate) const EVENT_MASTER_READ: u32 = 1;
+pub(crate) const EVENT_SLAVE_READ_REQUEST: u32 = 2;
+pub(crate) const EVENT_SLAVE_READ_COMPLETE: u32 = 3;
+pub(crate) const EVENT_SLAVE_WRITE: u32 = 4;
+
+/// Issues `command` and checks the completion reports `expected`.
+fn transfer<S: Syscalls + ?Sized>(
+    kernel: &mut S,
+    command: u32,
+    arg0: u32,
+    arg1: u32,
+    expected: u32,
+) -> Result<usize, ErrorCode> {
+    let (event, status, len) = command_and_wait(kernel, DRIVER_NUM, UPCALL, command, arg0, arg1)?;
+    ErrorCode::check(status)?;
+    if event != expected {
+        return Err(ErrorCode::BadRVal);
+    }
+    Ok(len as usize)
+}
+
+/// I2C controller backed by the Tock I2C master/slave capsule.
+///
+/// Bus timing, interrupts and recovery belong to the kernel, so the
+/// [`I2cHardwareCore`] hooks are no-ops apart from `configure_timing`, which
+/// reports [`ErrorCode::NoSupport`].
+pub struct TockI2cMaster<S: Syscalls> {
+    kernel: S,
+    tx: [u8; MAX_TRANSFER],
+    rx: [u8; MAX_TRANSFER],
+}
+
+impl<S: Syscalls> TockI2cMaster<S> {
+    /// Opens the I2C capsule, failing if the kernel does not provide it.
+    pub fn new(mut kernel: S) -> Result<Self, ErrorCode> {
+        open_driver(&mut kernel, DRIVER_NUM, UPCALL)?;
+        Ok(Self {
+            kernel,
+            tx: [0; MAX_TRANSFER],
+            rx: [0; MAX_TRANSFER],
+        })
+    }
+
+    /// Releases the system call handle.
+    pub fn into_inner(self) -> S {
+        self.kernel
+    }
+}
+
+impl<S: Syscalls> ErrorType for TockI2cMaster<S> {
+    type Error = ErrorCode;
+}
+
+impl<S: Syscalls> I2cHardwareCore for TockI2cMaster<S> {
+    type Config = ();
+    type I2cSpeed = ();
+    type TimingConfig = ();
+
+    fn init(&mut self, _config: &mut Self::Config) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn configure_timing(
+        &mut self,
+        _speed: Self::I2cSpeed,
+        _timing: &Self::TimingConfig,
+    ) -> Result<u32, Self::Error> {
+        Err(ErrorCode::NoSupport)
+    }
+
+    fn enable_interrupts(&mut self, _mask: u32) {}
+
+    fn clear_interrupts(&mut self, _mask: u32) {}
+
+    fn handle_interrupt(&mut self) {}
+}
+
+impl<S: Syscalls> I2cMaster for TockI2cMaster<S> {
+    fn write(&mut self, addr: SevenBitAddress, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.transaction_slice(addr, &mut [Operation::Write(bytes)])
+    }
+
+    fn read(&mut self, addr: SevenBitAddress, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        self.transaction_slice(addr, &mut [Operation::Read(buffer)])
+    }
+
+    fn write_read(
+        &mut self,
+        addr: SevenBitAddress,
+        bytes: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.transaction_slice(
+            addr,
+            &mut [Operation::Write(bytes), Operation::Read(buffer)],
+        )
+    }
+
+    /// Runs `ops_slice` as at most one capsule transfer.
+    ///
+    /// Leading writes are joined into one write phase and trailing reads into
+    /// one read phase, with a repeated start between the phases. A write
+    /// after a read cannot be expressed and fails with
+    /// [`ErrorCode::NoSupport`].
+    fn transaction_slice(
+        &mut self,
+        addr: SevenBitAddress,
+        ops_slice: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        let mut write_len = 0;
+        let mut read_len = 0;
+        let mut writes = false;
+        let mut reads = false;
+        for op in ops_slice.iter() {
+            match op {
+                Operation::Write(bytes) => {
+                    if reads {
+                        return Err(ErrorCode::NoSupport);
+                    }
+                    let end = write_len + bytes.len();
+                    self.tx
+                        .get_mut(write_len..end)
+                        .ok_or(ErrorCode::Size)?
+                        .copy_from_slice(bytes);
+                    write_len = end;
+                    writes = true;
+                }
+                Operation::Read(buffer) => {
+                    read_len += buffer.len();
+                    if read_len > MAX_TRANSFER {
+                        return Err(ErrorCode::Size);
+                    }
+                    reads = true;
+                }
+            }
+        }
+
+        let address = u32::from(addr);
+        let kernel = &mut self.kernel;
+        let tx = &mut self.tx[..write_len];
+        let rx = &mut self.rx[..read_len];
+        match (writes, reads) {
+            (false, false) => return Ok(()),
+            (true, false) => share_rw(kernel, DRIVER_NUM, RW_MASTER_TX, tx, |kernel| {
+                transfer(
+                    kernel,
+                    CMD_MASTER_WRITE,
+                    address,
+                    write_len as u32,
+                    EVENT_MASTER_WRITE,
+                )
+            })?,
+            (false, true) => share_rw(kernel, DRIVER_NUM, RW_MASTER_RX, rx, |kernel| {
+                transfer(
+                    kernel,
+                    CMD_MASTER_READ,
+                    address,
+                    read_len as u32,
+                    EVENT_MASTER_READ,
+                )
+            })?,
+            (true, true) => share_rw(kernel, DRIVER_NUM, RW_MASTER_TX, tx, |kernel| {
+                share_rw(kernel, DRIVER_NUM, RW_MASTER_RX, rx, |kernel| {
+                    transfer(
+                        kernel,
+                        CMD_MASTER_WRITE_READ,
+                        address,
+                        ((read_len as u32) << 8) | write_len as u32,
+                        EVENT_MASTER_READ,
+                    )
+                })
+            })?,
+        };
+
+        let mut received = self.rx[..read_len].iter();
+        for op in ops_slice.iter_mut() {
+            if let Operation::Read(buffer) = op {
+                for (dst, src) in buffer.iter_mut().zip(&mut received) {
+                    *dst = *src;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Error from [`TockI2cTarget::serve`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServeError<E> {
+    /// The capsule failed or reported an unexpected event.
+    Kernel(ErrorCode),
+    /// The device model rejected the transaction.
+    Device(E),
+}
+
+impl<E> From<ErrorCode> for ServeError<E> {
+    fn from(error: ErrorCode) -> Self {
+        ServeError::Kernel(error)
+    }
+}
+
+/// A slave transaction handled by [`TockI2cTarget::serve`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TargetEvent {
+    /// The controller wrote this many bytes to the device.
+    Write(usize),
+    /// The controller read this many bytes from the device.
+    Read(usize),
+    /// The device declined the address; nothing was exchanged.
+    Ignored,
+}
+
+/// I2C target runner backed by the Tock I2C master/slave capsule.
+///
+/// Each call to [`serve`](Self::serve) listens for one transaction and
+/// dispatches it to a device model through the `i2c_device` callbacks. The
+/// receive buffer is only shared while listening, so the capsule will not
+/// accept a write between calls.
+pub struct TockI2cTarget<S: Syscalls> {
+    kernel: S,
+    address: Option<u8>,
+    rx: [u8; MAX_TRANSFER],
+    tx: [u8; MAX_TRANSFER],
+}
+
+impl<S: Syscalls> TockI2cTarget<S> {
+    /// Opens the I2C capsule, failing if the kernel does not provide it.
+    pub fn new(mut kernel: S) -> Result<Self, ErrorCode> {
+        open_driver(&mut kernel, DRIVER_NUM, UPCALL)?;
+        Ok(Self {
+            kernel,
+            address: None,
+            rx: [0; MAX_TRANSFER],
+            tx: [0; MAX_TRANSFER],
+        })
+    }
+
+    /// Programs the slave address the capsule answers to.
+    pub fn set_address(&mut self, address: u8) -> Result<(), ErrorCode> {
+        self.kernel
+            .command(DRIVER_NUM, CMD_SET_SLAVE_ADDRESS, u32::from(address), 0)
+            .to_result()?;
+        self.address = Some(address);
+        Ok(())
+    }
+
+    /// Releases the system call handle.
+    pub fn into_inner(self) -> S {
+        self.kernel
+    }
+
+    /// Blocks until a controller addresses this target, then runs the
+    /// transaction against `device`.
+    ///
+    /// Fails with [`ErrorCode::Off`] if no address has been set.
+    pub fn serve<D>(&mut self, device: &mut D) -> Result<TargetEvent, ServeError<D::Error>>
+    where
+        D: ReadTarget + WriteTarget,
+    {
+        let address = self.address.ok_or(ErrorCode::Off)?;
+        let (event, status, len) = share_rw(
+            &mut self.kernel,
+            DRIVER_NUM,
+            RW_SLAVE_RX,
+            &mut self.rx,
+            |kernel| command_and_wait(kernel, DRIVER_NUM, UPCALL, CMD_LISTEN, 0, 0),
+        )?;
+        ErrorCode::check(status)?;
+
+        let matched = device.on_address_match(address);
+        match event {
+            EVENT_SLAVE_WRITE => {
+                if !matched {
+                    return Ok(TargetEvent::Ignored);
+                }
+                let len = len.min(MAX_TRANSFER as u32) as usize;
+                device
+                    .on_transaction_start(TransactionDirection::Write, false)
+                    .map_err(ServeError::Device)?;
+                device
+                    .on_write(&self.rx[..len])
+                    .map_err(ServeError::Device)?;
+                device.on_stop();
+                Ok(TargetEvent::Write(len))
+            }
+            EVENT_SLAVE_READ_REQUEST => {
+                let mut staged = 0;
+                if matched {
+                    if let TransactionResponse::ByteToSend(byte) = device
+                        .on_transaction_start(TransactionDirection::Read, false)
+                        .map_err(ServeError::Device)?
+                    {
+                        self.tx[0] = byte;
+                        staged = 1;
+                    }
+                    staged += device
+                        .on_read(&mut self.tx[staged..])
+                        .map_err(ServeError::Device)?;
+                    staged = staged.min(MAX_TRANSFER);
+                }
+
+                let sent = share_rw(
+                    &mut self.kernel,
+                    DRIVER_NUM,
+                    RW_SLAVE_TX,
+                    &mut self.tx[..staged],
+                    |kernel| {
+                        transfer(
+                            kernel,
+                            CMD_SLAVE_READ_SEND,
+                            staged as u32,
+                            0,
+                            EVENT_SLAVE_READ_COMPLETE,
+                        )
+                    },
+                )?;
+                if !matched {
+                    return Ok(TargetEvent::Ignored);
+                }
+                device.on_stop();
+                Ok(TargetEvent::Read(sent))
+            }
+            _ => Err(ServeError::Kernel(ErrorCode::BadRVal)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake::{BusEvent, Call, FakeKernel, EEPROM_ADDRESS};
+    use embedded_hal::i2c::{Error as _, ErrorKind, NoAcknowledgeSource};
+    use openprot_hal_blocking::i2c_device::I2CCoreTarget;
+    use std::vec;
+
+    fn master() -> TockI2cMaster<FakeKernel> {
+        TockI2cMaster::new(FakeKernel::new()).unwrap()
+    }
+
+    #[test]
+    fn test_master_write_then_write_read() {
+        let mut master = master();
+        master.write(EEPROM_ADDRESS, &[0x10, 1, 2, 3]).unwrap();
+
+        let mut buf = [0u8; 3];
+        master
+            .write_read(EEPROM_ADDRESS, &[0x10], &mut buf)
+            .unwrap();
+        assert_eq!(buf, [1, 2, 3]);
+
+        let mut buf = [0u8; 2];
+        master.read(EEPROM_ADDRESS, &mut buf).unwrap();
+        assert_eq!(buf, [0, 0]);
+    }
+
+    #[test]
+    fn test_master_nack() {
+        let mut master = master();
+        let err = master.write(0x22, &[0]).unwrap_err();
+        assert_eq!(
+            err.kind(),
+            ErrorKind::NoAcknowledge(NoAcknowledgeSource::Unknown)
+        );
+        assert!(master.into_inner().nothing_shared());
+    }
+
+    #[test]
+    fn test_transaction_joins_phases() {
+        let mut master = master();
+        master
+            .write(EEPROM_ADDRESS, &[0x20, 0xaa, 0xbb, 0xcc])
+            .unwrap();
+
+        let (mut first, mut second) = ([0u8; 1], [0u8; 2]);
+        master
+            .transaction_slice(
+                EEPROM_ADDRESS,
+                &mut [
+                    Operation::Write(&[]),
+                    Operation::Write(&[0x20]),
+                    Operation::Read(&mut first),
+                    Operation::Read(&mut second),
+                ],
+            )
+            .unwrap();
+        assert_eq!((first, second), ([0xaa], [0xbb, 0xcc]));
+
+        let kernel = master.into_inner();
+        assert_eq!(
+            kernel.calls[kernel.calls.len() - 6..],
+            [
+                Call::AllowRw(DRIVER_NUM, RW_MASTER_TX, 1),
+                Call::AllowRw(DRIVER_NUM, RW_MASTER_RX, 3),
+                Call::Command(
+                    DRIVER_NUM,
+                    CMD_MASTER_WRITE_READ,
+                    u32::from(EEPROM_ADDRESS),
+                    (3 << 8) | 1
+                ),
+                Call::Yield(DRIVER_NUM, UPCALL),
+                Call::AllowRw(DRIVER_NUM, RW_MASTER_RX, 0),
+                Call::AllowRw(DRIVER_NUM, RW_MASTER_TX, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_transaction_limits() {
+        let mut master = master();
+        let mut buf = [0u8; 1];
+        let err = master
+            .transaction_slice(
+                EEPROM_ADDRESS,
+                &mut [Operation::Read(&mut buf), Operation::Write(&[0])],
+            )
+            .unwrap_err();
+        assert_eq!(err, ErrorCode::NoSupport);
+
+        let err = master
+            .write(EEPROM_ADDRESS, &[0u8; MAX_TRANSFER + 1])
+            .unwrap_err();
+        assert_eq!(err, ErrorCode::Size);
+        assert_eq!(
+            master.configure_timing((), &()).unwrap_err(),
+            ErrorCode::NoSupport
+        );
+    }
+
+    /// Register file device model: a write sets the pointer and stores any
+    /// following bytes; a read streams from the pointer.
+    #[derive(Default)]
+    struct Registers {
+        regs: [u8; 16],
+        pointer: usize,
+        stops: usize,
+    }
+
+    impl ErrorType for Registers {
+        type Error = ErrorCode;
+    }
+
+    impl I2CCoreTarget for Registers {
+        fn init(&mut self, _address: u8) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn on_transaction_start(
+            &mut self,
+            _direction: TransactionDirection,
+            _repeated: bool,
+        ) -> Result<TransactionResponse, Self::Error> {
+            Ok(TransactionResponse::NoImmediateData)
+        }
+
+        fn on_stop(&mut self) {
+            self.stops += 1;
+        }
+
+        fn on_address_match(&mut self, address: u8) -> bool {
+            address == 0x42
+        }
+    }
+
+    impl WriteTarget for Registers {
+        fn on_write(&mut self, data: &[u8]) -> Result<(), Self::Error> {
+            let (&pointer, values) = data.split_first().ok_or(ErrorCode::Invalid)?;
+            self.pointer = usize::from(pointer) % self.regs.len();
+            for value in values {
+                self.regs[self.pointer] = *value;
+                self.pointer = (self.pointer + 1) % self.regs.len();
+            }
+            Ok(())
+        }
+    }
+
+    impl ReadTarget for Registers {
+        fn on_read(&mut self, buffer: &mut [u8]) -> Result<usize, Self::Error> {
+            let n = buffer.len().min(4);
+            for byte in &mut buffer[..n] {
+                *byte = self.regs[self.pointer];
+                self.pointer = (self.pointer + 1) % self.regs.len();
+            }
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn test_target_serves_write_and_read() {
+        let mut kernel = FakeKernel::new();
+        kernel.script_bus(BusEvent::Write(vec![0x02, 0xde, 0xad]));
+        kernel.script_bus(BusEvent::Write(vec![0x02]));
+        kernel.script_bus(BusEvent::Read);
+
+        let mut target = TockI2cTarget::new(kernel).unwrap();
+        target.set_address(0x42).unwrap();
+        let mut device = Registers::default();
+
+        assert_eq!(target.serve(&mut device), Ok(TargetEvent::Write(3)));
+        assert_eq!(target.serve(&mut device), Ok(TargetEvent::Write(1)));
+        assert_eq!(target.serve(&mut device), Ok(TargetEvent::Read(4)));
+        assert_eq!(device.stops, 3);
+
+        let kernel = target.into_inner();
+        assert_eq!(kernel.slave_address, Some(0x42));
+        assert_eq!(kernel.sent_to_controller, [0xde, 0xad, 0, 0]);
+        assert!(kernel.nothing_shared());
+    }
+
+    #[test]
+    fn test_target_requires_address() {
+        let mut target = TockI2cTarget::new(FakeKernel::new()).unwrap();
+        let err = target.serve(&mut Registers::default()).unwrap_err();
+        assert_eq!(err, ServeError::Kernel(ErrorCode::Off));
+    }
+
+    #[test]
+    fn test_target_device_error() {
+        let mut kernel = FakeKernel::new();
+        kernel.script_bus(BusEvent::Write(vec![]));
+        let mut target = TockI2cTarget::new(kernel).unwrap();
+        target.set_address(0x42).unwrap();
+
+        let err = target.serve(&mut Registers::default()).unwrap_err();
+        assert_eq!(err, ServeError::Device(ErrorCode::Invalid));
+        assert!(target.into_inner().nothing_shared());
+    }
+}
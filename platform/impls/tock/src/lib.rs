@@ -4,5 +4,29 @@
 //! Tock OS platform implementation for OpenPRoT
 //!
 //! This crate provides Tock OS-specific implementations of platform abstraction traits.
+//!
+//! | Module      | HAL traits                                   | Capsule        |
+//! |-------------|----------------------------------------------|----------------|
+//! | [`digest`]  | owned and scoped `DigestInit`/`DigestOp`     | SHA            |
+//! | [`mac`]     | owned and scoped `MacInit`/`MacOp`           | HMAC           |
+//! | [`i2c`]     | `I2cMaster`, `i2c_device` target callbacks   | I2C master/slave |
+//!
+//! Every driver is generic over [`syscall::Syscalls`], so it runs unchanged
+//! against the kernel (`riscv::TockKernel` on target) or a fake kernel in
+//! host tests.
 
 #![no_std]
+
+#[cfg(test)]
+extern crate std;
+
+pub mod digest;
+mod engine;
+pub mod i2c;
+pub mod mac;
+#[cfg(target_arch = "riscv32")]
+pub mod riscv;
+pub mod syscall;
+
+#[cfg(test)]
+mod fake;
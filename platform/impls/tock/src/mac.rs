@@ -0,0 +1,285 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! HMAC-SHA-2 on the Tock HMAC capsule
+//!
+//! [`TockHmac`] implements the owned and scoped MAC APIs for HMAC-SHA-256,
+//! HMAC-SHA-384 and HMAC-SHA-512, with the same context split as
+//! [`TockDigest`](crate::digest::TockDigest). The key is shared with the
+//! capsule only while it is loaded at `init`.
+
+use openprot_hal_blocking::digest::Digest;
+use openprot_hal_blocking::mac::owned::{MacInit, MacOp};
+use openprot_hal_blocking::mac::{
+    ErrorType, HmacSha2_256, HmacSha2_384, HmacSha2_512, KeyHandle, MacInit as ScopedMacInit,
+    MacOp as ScopedMacOp,
+};
+use zeroize::Zeroize;
+
+use crate::engine::{self, ALGORITHM_SHA256, ALGORITHM_SHA384, ALGORITHM_SHA512, UPCALL_DONE};
+use crate::syscall::{open_driver, ErrorCode, Syscalls};
+
+/// Driver number of the HMAC capsule.
+pub const DRIVER_NUM: u32 = 0x40003;
+
+/// Longest key accepted, one SHA-512 block.
+pub const MAX_KEY_LEN: usize = 128;
+
+/// HMAC key held in process memory until it is loaded into the capsule.
+///
+/// The bytes are zeroed on drop.
+pub struct HmacKey {
+    bytes: [u8; MAX_KEY_LEN],
+    len: usize,
+}
+
+impl HmacKey {
+    /// Copies `key`, failing with [`ErrorCode::Size`] if it exceeds
+    /// [`MAX_KEY_LEN`].
+    pub fn new(key: &[u8]) -> Result<Self, ErrorCode> {
+        let mut bytes = [0u8; MAX_KEY_LEN];
+        bytes
+            .get_mut(..key.len())
+            .ok_or(ErrorCode::Size)?
+            .copy_from_slice(key);
+        Ok(Self {
+            bytes,
+            len: key.len(),
+        })
+    }
+
+    fn as_bytes(&self) -> &[u8] {
+        &self.bytes[..self.len]
+    }
+}
+
+impl KeyHandle for HmacKey {}
+
+impl Drop for HmacKey {
+    fn drop(&mut self) {
+        self.bytes.zeroize();
+    }
+}
+
+/// MAC controller backed by the Tock HMAC capsule.
+///
+/// Owns the system call handle, so only one computation can be in flight.
+pub struct TockHmac<S: Syscalls> {
+    kernel: S,
+}
+
+impl<S: Syscalls> TockHmac<S> {
+    /// Opens the HMAC capsule, failing if the kernel does not provide it.
+    pub fn new(mut kernel: S) -> Result<Self, ErrorCode> {
+        open_driver(&mut kernel, DRIVER_NUM, UPCALL_DONE)?;
+        Ok(Self { kernel })
+    }
+
+    /// Releases the system call handle.
+    pub fn into_inner(self) -> S {
+        self.kernel
+    }
+}
+
+impl<S: Syscalls> ErrorType for TockHmac<S> {
+    type Error = ErrorCode;
+}
+
+/// Macro to implement owned and scoped MAC contexts for each HMAC variant
+macro_rules! impl_tock_hmac {
+    ($algo:ident, $id:expr, $context:ident, $scoped:ident, $words:expr) => {
+        /// HMAC context (owned API)
+        pub struct $context<S: Syscalls> {
+            controller: TockHmac<S>,
+        }
+
+        /// HMAC context (scoped API)
+        ///
+        /// Borrows the controller for the lifetime of the computation.
+        pub struct $scoped<'a, S: Syscalls> {
+            controller: &'a mut TockHmac<S>,
+        }
+
+        impl<S: Syscalls> MacInit<$algo> for TockHmac<S> {
+            type Key = HmacKey;
+            type Context = $context<S>;
+            type Output = Digest<$words>;
+
+            fn init(
+                mut self,
+                _algorithm: $algo,
+                key: Self::Key,
+            ) -> Result<Self::Context, Self::Error> {
+                engine::start_keyed(&mut self.kernel, DRIVER_NUM, $id, key.as_bytes())?;
+                Ok($context { controller: self })
+            }
+        }
+
+        impl<S: Syscalls> ErrorType for $context<S> {
+            type Error = ErrorCode;
+        }
+
+        impl<S: Syscalls> MacOp for $context<S> {
+            type Output = Digest<$words>;
+            type Controller = TockHmac<S>;
+
+            fn update(mut self, data: &[u8]) -> Result<Self, Self::Error> {
+                engine::update(&mut self.controller.kernel, DRIVER_NUM, data)?;
+                Ok(self)
+            }
+
+            fn finalize(mut self) -> Result<(Self::Output, Self::Controller), Self::Error> {
+                let mac = engine::finish(&mut self.controller.kernel, DRIVER_NUM)?;
+                Ok((mac, self.controller))
+            }
+
+            fn cancel(self) -> Self::Controller {
+                self.controller
+            }
+        }
+
+        impl<S: Syscalls> ScopedMacInit<$algo> for TockHmac<S> {
+            type Key = HmacKey;
+            type OpContext<'a>
+                = $scoped<'a, S>
+            where
+                Self: 'a;
+
+            fn init<'a>(
+                &'a mut self,
+                _algo: $algo,
+                key: Self::Key,
+            ) -> Result<Self::OpContext<'a>, Self::Error> {
+                engine::start_keyed(&mut self.kernel, DRIVER_NUM, $id, key.as_bytes())?;
+                Ok($scoped { controller: self })
+            }
+        }
+
+        impl<S: Syscalls> ErrorType for $scoped<'_, S> {
+            type Error = ErrorCode;
+        }
+
+        impl<S: Syscalls> ScopedMacOp for $scoped<'_, S> {
+            type Output = Digest<$words>;
+
+            fn update(&mut self, input: &[u8]) -> Result<(), Self::Error> {
+                engine::update(&mut self.controller.kernel, DRIVER_NUM, input)
+            }
+
+            fn finalize(self) -> Result<Self::Output, Self::Error> {
+                engine::finish(&mut self.controller.kernel, DRIVER_NUM)
+            }
+        }
+    };
+}
+
+impl_tock_hmac!(
+    HmacSha2_256,
+    ALGORITHM_SHA256,
+    MacContext256,
+    ScopedMacContext256,
+    8
+);
+impl_tock_hmac!(
+    HmacSha2_384,
+    ALGORITHM_SHA384,
+    MacContext384,
+    ScopedMacContext384,
+    12
+);
+impl_tock_hmac!(
+    HmacSha2_512,
+    ALGORITHM_SHA512,
+    MacContext512,
+    ScopedMacContext512,
+    16
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{CMD_SET_ALGORITHM, RO_KEY};
+    use crate::fake::{Call, FakeKernel};
+    use hex_literal::hex;
+    use openprot_hal_blocking::mac::Error as _;
+    use openprot_hal_blocking::mac::ErrorKind;
+
+    // RFC 4231 test case 2
+    const KEY: &[u8] = b"Jefe";
+    const DATA: &[u8] = b"what do ya want for nothing?";
+
+    fn controller() -> TockHmac<FakeKernel> {
+        TockHmac::new(FakeKernel::new()).unwrap()
+    }
+
+    fn key() -> HmacKey {
+        HmacKey::new(KEY).unwrap()
+    }
+
+    #[test]
+    fn test_owned_rfc4231_vectors() {
+        let context = MacInit::<HmacSha2_256>::init(controller(), HmacSha2_256, key()).unwrap();
+        let (mac, controller) = context.update(DATA).unwrap().finalize().unwrap();
+        assert_eq!(
+            mac.as_bytes(),
+            hex!("5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843")
+        );
+
+        let context = MacInit::<HmacSha2_384>::init(controller, HmacSha2_384, key()).unwrap();
+        let (mac, controller) = context.update(DATA).unwrap().finalize().unwrap();
+        assert_eq!(
+            mac.as_bytes(),
+            hex!(
+                "af45d2e376484031617f78d2b58a6b1b9c7ef464f5a01b47e42ec3736322445e"
+                "8e2240ca5e69e2c78b3239ecfab21649"
+            )
+        );
+
+        let context = MacInit::<HmacSha2_512>::init(controller, HmacSha2_512, key()).unwrap();
+        let (mac, _) = context.update(DATA).unwrap().finalize().unwrap();
+        assert_eq!(
+            mac.as_bytes(),
+            hex!(
+                "164b7a7bfcf819e2e395fbe73b56e0a387bd64222e831fd610270cd7ea250554"
+                "9758bf75c05a994a6d034f65f8f0e6fdcaeab1a34d4a6b4b636e070a38bce737"
+            )
+        );
+    }
+
+    #[test]
+    fn test_scoped_streaming() {
+        let mut controller = controller();
+        let mut context =
+            ScopedMacInit::<HmacSha2_256>::init(&mut controller, HmacSha2_256, key()).unwrap();
+        for chunk in DATA.chunks(5) {
+            context.update(chunk).unwrap();
+        }
+        assert_eq!(
+            context.finalize().unwrap().as_bytes(),
+            hex!("5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843")
+        );
+    }
+
+    #[test]
+    fn test_key_shared_only_while_loading() {
+        let context = MacInit::<HmacSha2_256>::init(controller(), HmacSha2_256, key()).unwrap();
+        let kernel = context.cancel().into_inner();
+
+        assert_eq!(
+            kernel.calls[2..],
+            [
+                Call::AllowRo(DRIVER_NUM, RO_KEY, KEY.len()),
+                Call::Command(DRIVER_NUM, CMD_SET_ALGORITHM, ALGORITHM_SHA256, 0),
+                Call::AllowRo(DRIVER_NUM, RO_KEY, 0),
+            ]
+        );
+        assert!(kernel.nothing_shared());
+    }
+
+    #[test]
+    fn test_oversized_key_rejected() {
+        let err = HmacKey::new(&[0u8; MAX_KEY_LEN + 1]).err().unwrap();
+        assert_eq!(err.kind(), ErrorKind::InvalidInputLength);
+        assert!(HmacKey::new(&[0u8; MAX_KEY_LEN]).is_ok());
+    }
+}
@@ -0,0 +1,132 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! System calls for Tock processes on RISC-V
+//!
+//! Marshals the [`Syscalls`] trait onto the `libtock_runtime` raw system
+//! call backend, using the `libtock_platform` class and yield identifiers.
+
+use libtock_platform::{syscall_class, yield_id, RawSyscalls, Register};
+use libtock_runtime::TockSyscalls;
+
+use crate::syscall::{CommandReturn, ErrorCode, Syscalls};
+
+/// Yield is class 0; `libtock_platform` only names the other classes because
+/// its own yield calls are dedicated functions.
+const CLASS_YIELD: usize = 0;
+
+/// Issues a four-argument system call of class `CLASS`.
+///
+/// # Safety
+///
+/// The arguments must be valid for the requested call; in particular any
+/// buffer passed to allow must satisfy the [`Syscalls`] allow contract.
+unsafe fn syscall4<const CLASS: usize>(args: [Register; 4]) -> CommandReturn {
+    // SAFETY: forwarded from the caller.
+    let [variant, r1, r2, r3] = unsafe { TockSyscalls::syscall4::<CLASS>(args) };
+    CommandReturn::new(variant.as_u32(), r1.as_u32(), r2.as_u32(), r3.as_u32())
+}
+
+/// The Tock kernel, reached through `libtock_runtime`.
+///
+/// Upcalls are never delivered to process code: [`Syscalls::subscribe`]
+/// registers a null upcall and completions are collected with Yield-WaitFor.
+pub struct TockKernel {
+    _private: (),
+}
+
+impl TockKernel {
+    /// Returns the handle to the kernel.
+    ///
+    /// # Safety
+    ///
+    /// The drivers in this crate assume they are the only users of the
+    /// upcalls and allow slots they touch; the caller must not create
+    /// handles that would drive the same capsule concurrently.
+    #[must_use]
+    pub const unsafe fn new() -> Self {
+        Self { _private: () }
+    }
+}
+
+impl Syscalls for TockKernel {
+    fn command(&mut self, driver: u32, command: u32, arg0: u32, arg1: u32) -> CommandReturn {
+        // SAFETY: commands pass no memory to the kernel.
+        unsafe {
+            syscall4::<{ syscall_class::COMMAND }>([
+                driver.into(),
+                command.into(),
+                arg0.into(),
+                arg1.into(),
+            ])
+        }
+    }
+
+    unsafe fn allow_ro(
+        &mut self,
+        driver: u32,
+        buffer: u32,
+        ptr: *const u8,
+        len: usize,
+    ) -> Result<(), ErrorCode> {
+        // SAFETY: forwarded from the caller.
+        unsafe {
+            syscall4::<{ syscall_class::ALLOW_RO }>([
+                driver.into(),
+                buffer.into(),
+                ptr.into(),
+                len.into(),
+            ])
+        }
+        .to_result()
+    }
+
+    unsafe fn allow_rw(
+        &mut self,
+        driver: u32,
+        buffer: u32,
+        ptr: *mut u8,
+        len: usize,
+    ) -> Result<(), ErrorCode> {
+        // SAFETY: forwarded from the caller.
+        unsafe {
+            syscall4::<{ syscall_class::ALLOW_RW }>([
+                driver.into(),
+                buffer.into(),
+                ptr.into(),
+                len.into(),
+            ])
+        }
+        .to_result()
+    }
+
+    fn subscribe(&mut self, driver: u32, subscribe: u32) -> Result<(), ErrorCode> {
+        // SAFETY: a null upcall is never invoked, so no code pointer is
+        // handed to the kernel.
+        unsafe {
+            syscall4::<{ syscall_class::SUBSCRIBE }>([
+                driver.into(),
+                subscribe.into(),
+                0u32.into(),
+                0u32.into(),
+            ])
+        }
+        .to_result()
+    }
+
+    fn yield_wait_for(&mut self, driver: u32, subscribe: u32) -> (u32, u32, u32) {
+        // SAFETY: Yield-WaitFor returns the upcall arguments in a0-a2
+        // without running any process code.
+        let CommandReturn {
+            variant, r1, r2, ..
+        } = unsafe {
+            syscall4::<CLASS_YIELD>([
+                yield_id::WAIT_FOR.into(),
+                driver.into(),
+                subscribe.into(),
+                0u32.into(),
+            ])
+        };
+        (variant, r1, r2)
+    }
+}
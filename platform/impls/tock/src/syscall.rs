@@ -0,0 +1,336 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! Tock system call seam
+//!
+//! The HAL implementations in this crate never issue system calls directly;
+//! they go through the [`Syscalls`] trait, which models the four Tock 2.x
+//! system call classes used by blocking drivers (TRD104):
+//!
+//! | Class     | Method                                      |
+//! |-----------|---------------------------------------------|
+//! | Yield     | [`Syscalls::yield_wait_for`]                |
+//! | Subscribe | [`Syscalls::subscribe`]                     |
+//! | Command   | [`Syscalls::command`]                       |
+//! | Allow     | [`Syscalls::allow_ro`], [`Syscalls::allow_rw`] |
+//!
+//! On target the trait is implemented on top of `libtock_runtime` in
+//! `riscv`; host tests substitute a fake kernel that emulates the
+//! capsules, so the command/allow/subscribe marshalling is checked without a
+//! board.
+//!
+//! Buffers are shared with the kernel through [`share_ro`] and [`share_rw`],
+//! which revoke the allow before the borrow ends.
+
+use core::fmt;
+use core::ptr;
+
+use embedded_hal::i2c::{Error as I2cError, ErrorKind as I2cErrorKind, NoAcknowledgeSource};
+use openprot_hal_blocking::digest::{Error as DigestError, ErrorKind as DigestErrorKind};
+use openprot_hal_blocking::mac::{Error as MacError, ErrorKind as MacErrorKind};
+
+/// Return variant of a successful system call with no payload.
+pub const SUCCESS: u32 = 128;
+/// Return variant of a successful system call carrying one `u32`.
+pub const SUCCESS_U32: u32 = 129;
+/// Return variant of a successful system call carrying two `u32`s.
+pub const SUCCESS_U32_U32: u32 = 130;
+/// Return variant of a failed system call carrying only an error code.
+pub const FAILURE: u32 = 0;
+/// Return variant of a failed system call carrying an error code and a `u32`.
+pub const FAILURE_U32: u32 = 1;
+/// Return variant of a failed system call carrying an error code and two `u32`s.
+pub const FAILURE_U32_U32: u32 = 2;
+
+/// Tock kernel error codes (TRD104 §3.2).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum ErrorCode {
+    /// Generic failure.
+    Fail = 1,
+    /// The underlying system is busy; retry.
+    Busy = 2,
+    /// The state requested is already set.
+    Already = 3,
+    /// The component is powered down.
+    Off = 4,
+    /// Reservation required before use.
+    Reserve = 5,
+    /// An invalid parameter was passed.
+    Invalid = 6,
+    /// The parameter passed was too large.
+    Size = 7,
+    /// The operation was cancelled.
+    Cancel = 8,
+    /// Memory required was not available.
+    NoMem = 9,
+    /// The operation is not supported.
+    NoSupport = 10,
+    /// The device is not available.
+    NoDevice = 11,
+    /// The device is not physically installed.
+    Uninstalled = 12,
+    /// The packet transmission was not acknowledged.
+    NoAck = 13,
+    /// The kernel returned a value this crate does not understand.
+    BadRVal = 1024,
+}
+
+impl ErrorCode {
+    /// Decodes a raw error code register value.
+    #[must_use]
+    pub const fn from_raw(raw: u32) -> Self {
+        match raw {
+            1 => Self::Fail,
+            2 => Self::Busy,
+            3 => Self::Already,
+            4 => Self::Off,
+            5 => Self::Reserve,
+            6 => Self::Invalid,
+            7 => Self::Size,
+            8 => Self::Cancel,
+            9 => Self::NoMem,
+            10 => Self::NoSupport,
+            11 => Self::NoDevice,
+            12 => Self::Uninstalled,
+            13 => Self::NoAck,
+            _ => Self::BadRVal,
+        }
+    }
+
+    /// Interprets a capsule status word, where `0` means success.
+    pub const fn check(status: u32) -> Result<(), Self> {
+        match status {
+            0 => Ok(()),
+            raw => Err(Self::from_raw(raw)),
+        }
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Tock error code {self:?}")
+    }
+}
+
+impl core::error::Error for ErrorCode {}
+
+impl DigestError for ErrorCode {
+    fn kind(&self) -> DigestErrorKind {
+        match self {
+            ErrorCode::Busy | ErrorCode::Reserve => DigestErrorKind::Busy,
+            ErrorCode::Invalid => DigestErrorKind::InvalidInputLength,
+            ErrorCode::Size => DigestErrorKind::InvalidOutputSize,
+            ErrorCode::NoMem => DigestErrorKind::MemoryAllocationFailure,
+            ErrorCode::NoSupport | ErrorCode::NoDevice | ErrorCode::Uninstalled => {
+                DigestErrorKind::UnsupportedAlgorithm
+            }
+            _ => DigestErrorKind::HardwareFailure,
+        }
+    }
+}
+
+impl MacError for ErrorCode {
+    fn kind(&self) -> MacErrorKind {
+        match self {
+            ErrorCode::Busy | ErrorCode::Reserve => MacErrorKind::Busy,
+            ErrorCode::Invalid | ErrorCode::Size => MacErrorKind::InvalidInputLength,
+            ErrorCode::NoSupport | ErrorCode::NoDevice | ErrorCode::Uninstalled => {
+                MacErrorKind::UnsupportedAlgorithm
+            }
+            _ => MacErrorKind::HardwareFailure,
+        }
+    }
+}
+
+impl I2cError for ErrorCode {
+    fn kind(&self) -> I2cErrorKind {
+        match self {
+            ErrorCode::NoAck => I2cErrorKind::NoAcknowledge(NoAcknowledgeSource::Unknown),
+            ErrorCode::Busy => I2cErrorKind::ArbitrationLoss,
+            ErrorCode::Size => I2cErrorKind::Overrun,
+            ErrorCode::Fail => I2cErrorKind::Bus,
+            _ => I2cErrorKind::Other,
+        }
+    }
+}
+
+/// Register values returned by a Command system call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommandReturn {
+    /// Return variant (`SUCCESS*` / `FAILURE*`).
+    pub variant: u32,
+    /// First payload register.
+    pub r1: u32,
+    /// Second payload register.
+    pub r2: u32,
+    /// Third payload register.
+    pub r3: u32,
+}
+
+impl CommandReturn {
+    /// Builds a `Success` return.
+    #[must_use]
+    pub const fn success() -> Self {
+        Self::new(SUCCESS, 0, 0, 0)
+    }
+
+    /// Builds a `Success with u32` return.
+    #[must_use]
+    pub const fn success_u32(value: u32) -> Self {
+        Self::new(SUCCESS_U32, value, 0, 0)
+    }
+
+    /// Builds a `Failure` return.
+    #[must_use]
+    pub const fn failure(error: ErrorCode) -> Self {
+        Self::new(FAILURE, error as u32, 0, 0)
+    }
+
+    /// Builds a return from raw register values.
+    #[must_use]
+    pub const fn new(variant: u32, r1: u32, r2: u32, r3: u32) -> Self {
+        Self {
+            variant,
+            r1,
+            r2,
+            r3,
+        }
+    }
+
+    /// Collapses the return into success or the reported error code.
+    ///
+    /// Every failure variant carries the error code in `r1`; a success
+    /// variant is accepted regardless of payload.
+    pub fn to_result(self) -> Result<(), ErrorCode> {
+        match self.variant {
+            FAILURE | FAILURE_U32 | FAILURE_U32_U32 => Err(ErrorCode::from_raw(self.r1)),
+            variant if variant >= SUCCESS => Ok(()),
+            _ => Err(ErrorCode::BadRVal),
+        }
+    }
+}
+
+/// The Tock system call interface used by this crate's drivers.
+///
+/// Implementations marshal each call into the kernel ABI; they do no
+/// buffering or retrying of their own.
+pub trait Syscalls {
+    /// Issues a Command system call to `driver`.
+    fn command(&mut self, driver: u32, command: u32, arg0: u32, arg1: u32) -> CommandReturn;
+
+    /// Shares a read-only buffer with `driver` in slot `buffer`.
+    ///
+    /// Passing a null pointer with length zero revokes the previous allow.
+    ///
+    /// # Safety
+    ///
+    /// `ptr..ptr + len` must stay valid for reads until the slot is revoked.
+    /// Prefer [`share_ro`], which upholds this.
+    unsafe fn allow_ro(
+        &mut self,
+        driver: u32,
+        buffer: u32,
+        ptr: *const u8,
+        len: usize,
+    ) -> Result<(), ErrorCode>;
+
+    /// Shares a read-write buffer with `driver` in slot `buffer`.
+    ///
+    /// Passing a null pointer with length zero revokes the previous allow.
+    ///
+    /// # Safety
+    ///
+    /// `ptr..ptr + len` must stay valid for reads and writes, and must not be
+    /// accessed by the process, until the slot is revoked. Prefer
+    /// [`share_rw`], which upholds this.
+    unsafe fn allow_rw(
+        &mut self,
+        driver: u32,
+        buffer: u32,
+        ptr: *mut u8,
+        len: usize,
+    ) -> Result<(), ErrorCode>;
+
+    /// Registers for upcall `subscribe` of `driver`, so that completions are
+    /// queued for [`Syscalls::yield_wait_for`].
+    fn subscribe(&mut self, driver: u32, subscribe: u32) -> Result<(), ErrorCode>;
+
+    /// Blocks until upcall `subscribe` of `driver` fires and returns its
+    /// three arguments.
+    fn yield_wait_for(&mut self, driver: u32, subscribe: u32) -> (u32, u32, u32);
+}
+
+/// Shares `data` read-only with `driver` for the duration of `f`.
+///
+/// The allow is revoked before returning, even if `f` fails; an error from
+/// `f` takes precedence over a revocation error.
+pub fn share_ro<S, R>(
+    kernel: &mut S,
+    driver: u32,
+    buffer: u32,
+    data: &[u8],
+    f: impl FnOnce(&mut S) -> Result<R, ErrorCode>,
+) -> Result<R, ErrorCode>
+where
+    S: Syscalls + ?Sized,
+{
+    // SAFETY: `data` outlives this call and the slot is revoked below.
+    unsafe { kernel.allow_ro(driver, buffer, data.as_ptr(), data.len())? };
+    let result = f(kernel);
+    // SAFETY: revoking with a null buffer shares no memory.
+    let revoked = unsafe { kernel.allow_ro(driver, buffer, ptr::null(), 0) };
+    let value = result?;
+    revoked.map(|()| value)
+}
+
+/// Shares `data` read-write with `driver` for the duration of `f`.
+///
+/// The allow is revoked before returning, even if `f` fails; an error from
+/// `f` takes precedence over a revocation error.
+pub fn share_rw<S, R>(
+    kernel: &mut S,
+    driver: u32,
+    buffer: u32,
+    data: &mut [u8],
+    f: impl FnOnce(&mut S) -> Result<R, ErrorCode>,
+) -> Result<R, ErrorCode>
+where
+    S: Syscalls + ?Sized,
+{
+    // SAFETY: `data` is exclusively borrowed for this call, so the process
+    // cannot touch it while shared, and the slot is revoked below.
+    unsafe { kernel.allow_rw(driver, buffer, data.as_mut_ptr(), data.len())? };
+    let result = f(kernel);
+    // SAFETY: revoking with a null buffer shares no memory.
+    let revoked = unsafe { kernel.allow_rw(driver, buffer, ptr::null_mut(), 0) };
+    let value = result?;
+    revoked.map(|()| value)
+}
+
+/// Issues `command` and blocks for the completion upcall on `subscribe`.
+///
+/// Returns the upcall arguments; interpreting them is up to the caller.
+pub fn command_and_wait<S>(
+    kernel: &mut S,
+    driver: u32,
+    subscribe: u32,
+    command: u32,
+    arg0: u32,
+    arg1: u32,
+) -> Result<(u32, u32, u32), ErrorCode>
+where
+    S: Syscalls + ?Sized,
+{
+    kernel.command(driver, command, arg0, arg1).to_result()?;
+    Ok(kernel.yield_wait_for(driver, subscribe))
+}
+
+/// Checks that `driver` is present and subscribes to its completion upcall.
+pub fn open_driver<S>(kernel: &mut S, driver: u32, subscribe: u32) -> Result<(), ErrorCode>
+where
+    S: Syscalls + ?Sized,
+{
+    kernel.command(driver, 0, 0, 0).to_result()?;
+    kernel.subscribe(driver, subscribe)
+}
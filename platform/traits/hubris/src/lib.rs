@@ -39,6 +39,8 @@ pub enum HubrisCryptoError {
     NotSupported,
     /// Session state error (context or device missing)
     SessionStateError,
+    /// Signature or authentication tag did not verify
+    AuthenticationFailed,
 }
 
 /// RAII-style crypto session management
@@ -373,3 +375,192 @@ pub trait HubrisDigestOneShot: HubrisDigestDevice + Sized {
 
 // Blanket implementation for one-shot operations
 impl<T: HubrisDigestDevice> HubrisDigestOneShot for T {}
+
+/// SHA-3 extension for [`HubrisDigestDevice`]
+///
+/// Kept out of the base trait so that digest devices without SHA-3 support
+/// do not have to change.
+pub trait HubrisSha3DigestDevice: HubrisDigestDevice {
+    /// Digest context for SHA3-224 operations
+    type DigestContextSha3_224: DigestOp<Controller = Self, Output = Digest<7>>;
+    /// Digest context for SHA3-256 operations
+    type DigestContextSha3_256: DigestOp<Controller = Self, Output = Digest<8>>;
+    /// Digest context for SHA3-384 operations
+    type DigestContextSha3_384: DigestOp<Controller = Self, Output = Digest<12>>;
+    /// Digest context for SHA3-512 operations
+    type DigestContextSha3_512: DigestOp<Controller = Self, Output = Digest<16>>;
+
+    /// Initialize a SHA3-224 digest operation
+    fn init_digest_sha3_224(self) -> Result<Self::DigestContextSha3_224, HubrisCryptoError>;
+
+    /// Initialize a SHA3-256 digest operation
+    fn init_digest_sha3_256(self) -> Result<Self::DigestContextSha3_256, HubrisCryptoError>;
+
+    /// Initialize a SHA3-384 digest operation
+    fn init_digest_sha3_384(self) -> Result<Self::DigestContextSha3_384, HubrisCryptoError>;
+
+    /// Initialize a SHA3-512 digest operation
+    fn init_digest_sha3_512(self) -> Result<Self::DigestContextSha3_512, HubrisCryptoError>;
+
+    /// Initialize SHA3-224 digest session with device recovery
+    fn init_digest_session_sha3_224(
+        self,
+    ) -> Result<CryptoSession<Self::DigestContextSha3_224, Self>, HubrisCryptoError>
+    where
+        Self: Sized;
+
+    /// Initialize SHA3-256 digest session with device recovery
+    fn init_digest_session_sha3_256(
+        self,
+    ) -> Result<CryptoSession<Self::DigestContextSha3_256, Self>, HubrisCryptoError>
+    where
+        Self: Sized;
+
+    /// Initialize SHA3-384 digest session with device recovery
+    fn init_digest_session_sha3_384(
+        self,
+    ) -> Result<CryptoSession<Self::DigestContextSha3_384, Self>, HubrisCryptoError>
+    where
+        Self: Sized;
+
+    /// Initialize SHA3-512 digest session with device recovery
+    fn init_digest_session_sha3_512(
+        self,
+    ) -> Result<CryptoSession<Self::DigestContextSha3_512, Self>, HubrisCryptoError>
+    where
+        Self: Sized;
+}
+
+/// One-shot SHA-3 operations, analogous to [`HubrisDigestOneShot`]
+pub trait HubrisSha3DigestOneShot: HubrisSha3DigestDevice + Sized {
+    /// Compute SHA3-224 digest in one operation
+    fn digest_sha3_224_oneshot(self, data: &[u8]) -> Result<Digest<7>, HubrisCryptoError> {
+        let ctx = self.init_digest_sha3_224()?;
+        let ctx = ctx
+            .update(data)
+            .map_err(|_| HubrisCryptoError::HardwareFailure)?;
+        let (result, _controller) = ctx
+            .finalize()
+            .map_err(|_| HubrisCryptoError::HardwareFailure)?;
+        Ok(result)
+    }
+
+    /// Compute SHA3-256 digest in one operation
+    fn digest_sha3_256_oneshot(self, data: &[u8]) -> Result<Digest<8>, HubrisCryptoError> {
+        let ctx = self.init_digest_sha3_256()?;
+        let ctx = ctx
+            .update(data)
+            .map_err(|_| HubrisCryptoError::HardwareFailure)?;
+        let (result, _controller) = ctx
+            .finalize()
+            .map_err(|_| HubrisCryptoError::HardwareFailure)?;
+        Ok(result)
+    }
+
+    /// Compute SHA3-384 digest in one operation
+    fn digest_sha3_384_oneshot(self, data: &[u8]) -> Result<Digest<12>, HubrisCryptoError> {
+        let ctx = self.init_digest_sha3_384()?;
+        let ctx = ctx
+            .update(data)
+            .map_err(|_| HubrisCryptoError::HardwareFailure)?;
+        let (result, _controller) = ctx
+            .finalize()
+            .map_err(|_| HubrisCryptoError::HardwareFailure)?;
+        Ok(result)
+    }
+
+    /// Compute SHA3-512 digest in one operation
+    fn digest_sha3_512_oneshot(self, data: &[u8]) -> Result<Digest<16>, HubrisCryptoError> {
+        let ctx = self.init_digest_sha3_512()?;
+        let ctx = ctx
+            .update(data)
+            .map_err(|_| HubrisCryptoError::HardwareFailure)?;
+        let (result, _controller) = ctx
+            .finalize()
+            .map_err(|_| HubrisCryptoError::HardwareFailure)?;
+        Ok(result)
+    }
+}
+
+// Blanket implementation for one-shot SHA-3 operations
+impl<T: HubrisSha3DigestDevice> HubrisSha3DigestOneShot for T {}
+
+/// Size in bytes of a P-384 scalar or coordinate
+pub const P384_SCALAR_SIZE: usize = 48;
+
+/// Size in bytes of a P-384 public key (`x || y`) or signature (`r || s`)
+pub const P384_POINT_SIZE: usize = 2 * P384_SCALAR_SIZE;
+
+/// P-384 ECDSA extension for Hubris
+///
+/// Keys and signatures are fixed-size big-endian byte arrays so they pass
+/// through IDL-generated interfaces unchanged:
+/// - Private keys are the 48-byte scalar
+/// - Public keys are the uncompressed affine coordinates `x || y`
+/// - Signatures are `r || s`
+///
+/// Both operations take a SHA-384 digest, so signing a message pairs this
+/// trait with a SHA-384 or SHA3-384 digest session. Neither consumes the
+/// device, so no session recovery is needed.
+pub trait HubrisEcdsaP384Device {
+    /// Sign a SHA-384 digest
+    ///
+    /// Fails with `InvalidParameters` if the private key is zero or not
+    /// below the curve order.
+    fn ecdsa_p384_sign(
+        &mut self,
+        private_key: &[u8; P384_SCALAR_SIZE],
+        digest: &Digest<12>,
+    ) -> Result<[u8; P384_POINT_SIZE], HubrisCryptoError>;
+
+    /// Verify a signature over a SHA-384 digest
+    ///
+    /// Fails with `InvalidParameters` if the public key is not a curve
+    /// point, and with `AuthenticationFailed` if the signature does not
+    /// verify.
+    fn ecdsa_p384_verify(
+        &mut self,
+        public_key: &[u8; P384_POINT_SIZE],
+        digest: &Digest<12>,
+        signature: &[u8; P384_POINT_SIZE],
+    ) -> Result<(), HubrisCryptoError>;
+}
+
+/// Size in bytes of an AES-GCM nonce
+pub const AES_GCM_NONCE_SIZE: usize = 12;
+
+/// Size in bytes of an AES-GCM authentication tag
+pub const AES_GCM_TAG_SIZE: usize = 16;
+
+/// AES-256-GCM extension for Hubris
+///
+/// Operations work in place on a caller buffer, which maps directly onto a
+/// read-write lease; the associated data maps onto a read lease. Each call
+/// is a complete seal or open, so the device is borrowed rather than
+/// consumed.
+///
+/// # Security
+/// A nonce must never be reused with the same key.
+pub trait HubrisAeadDevice {
+    /// Encrypt `buffer` in place and return the authentication tag
+    fn aes256_gcm_encrypt(
+        &mut self,
+        key: &[u8; 32],
+        nonce: &[u8; AES_GCM_NONCE_SIZE],
+        associated_data: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<[u8; AES_GCM_TAG_SIZE], HubrisCryptoError>;
+
+    /// Verify `tag` and decrypt `buffer` in place
+    ///
+    /// Fails with `AuthenticationFailed` if the tag does not match, in which
+    /// case `buffer` still holds the ciphertext.
+    fn aes256_gcm_decrypt(
+        &mut self,
+        key: &[u8; 32],
+        nonce: &[u8; AES_GCM_NONCE_SIZE],
+        associated_data: &[u8],
+        buffer: &mut [u8],
+        tag: &[u8; AES_GCM_TAG_SIZE],
+    ) -> Result<(), HubrisCryptoError>;
+}
@@ -0,0 +1,14 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+#![no_std]
+
+pub mod protocol;
+pub mod transport;
+
+#[doc(inline)]
+pub use protocol::{
+    CryptoAlgo, CryptoError, CryptoOp, CryptoRequestHeader, CryptoResponseHeader, MAX_BUF_SIZE,
+    MAX_PAYLOAD_SIZE,
+};
+pub use transport::{Transport, TransportError};
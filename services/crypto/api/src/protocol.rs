@@ -0,0 +1,349 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! Wire protocol for the crypto service.
+//!
+//! Every operation is one request and one response; the server runs it to
+//! completion before replying. Streaming hashes are split into
+//! `HashBegin` / `HashUpdate` / `HashFinish` round-trips that refer to a
+//! server-side session by the id returned from `HashBegin`.
+//!
+//! ```text
+//! request : [CryptoRequestHeader] [payload (payload_len bytes)]
+//! response: [CryptoResponseHeader] [payload (payload_len bytes)]
+//! ```
+//!
+//! Per-op meaning of the header fields and payloads:
+//!
+//! | Op            | `algo`       | `arg`        | request payload                | response payload |
+//! |---------------|--------------|--------------|--------------------------------|------------------|
+//! | `Hash`        | SHA-2        | —            | data                           | digest           |
+//! | `HashBegin`   | SHA-2        | —            | —                              | session id (u16 LE) |
+//! | `HashUpdate`  | —            | session id   | data                           | —                |
+//! | `HashFinish`  | —            | session id   | —                              | digest           |
+//! | `HashCancel`  | —            | session id   | —                              | —                |
+//! | `Hmac`        | SHA-2        | key length   | key ‖ data                     | MAC              |
+//! | `Random`      | —            | length       | additional input               | random bytes     |
+//! | `Reseed`      | —            | —            | additional input               | —                |
+//! | `EcdsaVerify` | P-256, P-384 | —            | Qx ‖ Qy ‖ r ‖ s ‖ digest       | —                |
+//!
+//! ECDSA scalars and coordinates are big-endian, each the curve's scalar
+//! size; the digest is the curve's hash output (SHA-256 for P-256, SHA-384
+//! for P-384). A failed verification is reported as
+//! [`CryptoError::VerificationFailed`].
+
+use zerocopy::{FromBytes, Immutable, IntoBytes, KnownLayout};
+
+/// Max payload bytes in one request or response.
+pub const MAX_PAYLOAD_SIZE: usize = 1024;
+
+/// One request/response buffer size: a header plus a full payload. The
+/// request header is the larger of the two.
+pub const MAX_BUF_SIZE: usize = CryptoRequestHeader::SIZE + MAX_PAYLOAD_SIZE;
+
+#[non_exhaustive]
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CryptoOp {
+    /// One-shot hash of the payload.
+    Hash = 0x01,
+    /// Open a streaming hash session.
+    HashBegin = 0x02,
+    /// Feed the payload into session `arg`.
+    HashUpdate = 0x03,
+    /// Close session `arg` and return its digest.
+    HashFinish = 0x04,
+    /// Close session `arg` without producing a digest.
+    HashCancel = 0x05,
+    /// One-shot HMAC; the first `arg` payload bytes are the key.
+    Hmac = 0x06,
+    /// Return `arg` bytes from the service DRBG, mixing in the payload as
+    /// additional input.
+    Random = 0x07,
+    /// Reseed the service DRBG, mixing in the payload as additional input.
+    Reseed = 0x08,
+    /// Verify an ECDSA signature over a prehashed digest.
+    EcdsaVerify = 0x09,
+}
+
+impl TryFrom<u8> for CryptoOp {
+    type Error = CryptoError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0x01 => Ok(Self::Hash),
+            0x02 => Ok(Self::HashBegin),
+            0x03 => Ok(Self::HashUpdate),
+            0x04 => Ok(Self::HashFinish),
+            0x05 => Ok(Self::HashCancel),
+            0x06 => Ok(Self::Hmac),
+            0x07 => Ok(Self::Random),
+            0x08 => Ok(Self::Reseed),
+            0x09 => Ok(Self::EcdsaVerify),
+            _ => Err(CryptoError::InvalidOperation),
+        }
+    }
+}
+
+/// Algorithm selector carried in `CryptoRequestHeader::algo`.
+#[non_exhaustive]
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CryptoAlgo {
+    /// Field unused by the operation.
+    None = 0x00,
+    Sha256 = 0x01,
+    Sha384 = 0x02,
+    Sha512 = 0x03,
+    P256 = 0x10,
+    P384 = 0x11,
+}
+
+impl CryptoAlgo {
+    /// Digest size for the SHA-2 selectors, `None` otherwise.
+    pub fn digest_len(self) -> Option<usize> {
+        match self {
+            Self::Sha256 => Some(32),
+            Self::Sha384 => Some(48),
+            Self::Sha512 => Some(64),
+            _ => None,
+        }
+    }
+
+    /// Scalar size for the curve selectors, `None` otherwise.
+    pub fn scalar_len(self) -> Option<usize> {
+        match self {
+            Self::P256 => Some(32),
+            Self::P384 => Some(48),
+            _ => None,
+        }
+    }
+}
+
+impl TryFrom<u8> for CryptoAlgo {
+    type Error = CryptoError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0x00 => Ok(Self::None),
+            0x01 => Ok(Self::Sha256),
+            0x02 => Ok(Self::Sha384),
+            0x03 => Ok(Self::Sha512),
+            0x10 => Ok(Self::P256),
+            0x11 => Ok(Self::P384),
+            _ => Err(CryptoError::UnsupportedAlgorithm),
+        }
+    }
+}
+
+/// Status / error code carried in `CryptoResponseHeader`.
+#[non_exhaustive]
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CryptoError {
+    InvalidOperation = 0x01,
+    UnsupportedAlgorithm = 0x02,
+    /// Request or response does not fit, or the payload is malformed.
+    BufferTooSmall = 0x03,
+    /// No open session has the requested id.
+    InvalidSession = 0x04,
+    /// Every backend is in use by an open session.
+    Busy = 0x05,
+    InvalidKey = 0x06,
+    /// The signature does not verify.
+    VerificationFailed = 0x07,
+    /// The DRBG must be reseeded before it returns more output.
+    ReseedRequired = 0x08,
+    /// The entropy source failed, including SP 800-90B health tests.
+    EntropyFailure = 0x09,
+    InternalError = 0xFF,
+}
+
+impl From<u8> for CryptoError {
+    fn from(value: u8) -> Self {
+        match value {
+            0x01 => Self::InvalidOperation,
+            0x02 => Self::UnsupportedAlgorithm,
+            0x03 => Self::BufferTooSmall,
+            0x04 => Self::InvalidSession,
+            0x05 => Self::Busy,
+            0x06 => Self::InvalidKey,
+            0x07 => Self::VerificationFailed,
+            0x08 => Self::ReseedRequired,
+            0x09 => Self::EntropyFailure,
+            _ => Self::InternalError,
+        }
+    }
+}
+
+impl core::fmt::Display for CryptoError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::InvalidOperation => f.write_str("invalid crypto operation"),
+            Self::UnsupportedAlgorithm => f.write_str("unsupported algorithm"),
+            Self::BufferTooSmall => f.write_str("buffer too small"),
+            Self::InvalidSession => f.write_str("no such hash session"),
+            Self::Busy => f.write_str("crypto backends busy"),
+            Self::InvalidKey => f.write_str("invalid key"),
+            Self::VerificationFailed => f.write_str("signature verification failed"),
+            Self::ReseedRequired => f.write_str("drbg reseed required"),
+            Self::EntropyFailure => f.write_str("entropy source failure"),
+            Self::InternalError => f.write_str("internal crypto server error"),
+        }
+    }
+}
+
+impl core::error::Error for CryptoError {}
+
+#[repr(C, packed)]
+#[derive(Debug, Clone, Copy, FromBytes, IntoBytes, Immutable, KnownLayout)]
+pub struct CryptoRequestHeader {
+    pub(crate) op_code: u8,
+    pub(crate) algo: u8,
+    /// Op-specific argument: session id, key length or output length.
+    pub(crate) arg: u16,
+    /// Payload bytes after the header.
+    pub(crate) payload_len: u16,
+}
+
+impl CryptoRequestHeader {
+    pub const SIZE: usize = core::mem::size_of::<Self>();
+
+    pub fn new(op: CryptoOp, algo: CryptoAlgo, arg: u16, payload_len: u16) -> Self {
+        Self {
+            op_code: op as u8,
+            algo: algo as u8,
+            arg: arg.to_le(),
+            payload_len: payload_len.to_le(),
+        }
+    }
+
+    pub fn operation(&self) -> Result<CryptoOp, CryptoError> {
+        CryptoOp::try_from(self.op_code)
+    }
+
+    pub fn algorithm(&self) -> Result<CryptoAlgo, CryptoError> {
+        CryptoAlgo::try_from(self.algo)
+    }
+
+    pub fn arg_value(&self) -> u16 {
+        u16::from_le(self.arg)
+    }
+
+    pub fn payload_length(&self) -> usize {
+        u16::from_le(self.payload_len) as usize
+    }
+}
+
+#[repr(C, packed)]
+#[derive(Debug, Clone, Copy, FromBytes, IntoBytes, Immutable, KnownLayout)]
+pub struct CryptoResponseHeader {
+    pub(crate) status: u8,
+    pub(crate) reserved: u8,
+    /// Payload bytes following this header.
+    pub(crate) payload_len: u16,
+}
+
+impl CryptoResponseHeader {
+    pub const SIZE: usize = core::mem::size_of::<Self>();
+
+    pub fn success(payload_len: u16) -> Self {
+        Self {
+            status: 0,
+            reserved: 0,
+            payload_len: payload_len.to_le(),
+        }
+    }
+
+    pub fn error(error: CryptoError) -> Self {
+        Self {
+            status: error as u8,
+            reserved: 0,
+            payload_len: 0,
+        }
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.status == 0
+    }
+
+    pub fn error_code(&self) -> Option<CryptoError> {
+        if self.is_success() {
+            None
+        } else {
+            Some(CryptoError::from(self.status))
+        }
+    }
+
+    pub fn payload_length(&self) -> usize {
+        u16::from_le(self.payload_len) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zerocopy::{FromBytes, IntoBytes};
+
+    #[test]
+    fn request_header_roundtrips_through_bytes() {
+        let h = CryptoRequestHeader::new(CryptoOp::Hmac, CryptoAlgo::Sha384, 20, 300);
+        let bytes = h.as_bytes();
+        assert_eq!(bytes.len(), CryptoRequestHeader::SIZE);
+        let decoded = CryptoRequestHeader::ref_from_bytes(bytes).unwrap();
+        assert_eq!(decoded.operation(), Ok(CryptoOp::Hmac));
+        assert_eq!(decoded.algorithm(), Ok(CryptoAlgo::Sha384));
+        assert_eq!(decoded.arg_value(), 20);
+        assert_eq!(decoded.payload_length(), 300);
+    }
+
+    #[test]
+    fn response_header_success_and_error() {
+        const { assert!(CryptoResponseHeader::SIZE <= CryptoRequestHeader::SIZE) };
+
+        let ok = CryptoResponseHeader::success(48);
+        let ok = CryptoResponseHeader::ref_from_bytes(ok.as_bytes()).unwrap();
+        assert!(ok.is_success());
+        assert_eq!(ok.payload_length(), 48);
+
+        let err = CryptoResponseHeader::error(CryptoError::InvalidSession);
+        let err = CryptoResponseHeader::ref_from_bytes(err.as_bytes()).unwrap();
+        assert!(!err.is_success());
+        assert_eq!(err.error_code(), Some(CryptoError::InvalidSession));
+        assert_eq!(err.payload_length(), 0);
+    }
+
+    #[test]
+    fn error_and_op_byte_mapping_is_stable() {
+        for raw in 0x01u8..=0x09 {
+            assert_eq!(CryptoError::from(raw) as u8, raw);
+            assert_eq!(CryptoOp::try_from(raw).unwrap() as u8, raw);
+        }
+        // 0x00 is the success sentinel on the wire — it is not a CryptoError variant.
+        assert_eq!(CryptoError::from(0x00), CryptoError::InternalError);
+        assert_eq!(CryptoError::from(0x42), CryptoError::InternalError);
+        assert_eq!(CryptoOp::try_from(0x00), Err(CryptoError::InvalidOperation));
+        assert_eq!(CryptoOp::try_from(0x0A), Err(CryptoError::InvalidOperation));
+    }
+
+    #[test]
+    fn algo_sizes() {
+        for (raw, algo, digest, scalar) in [
+            (0x00u8, CryptoAlgo::None, None, None),
+            (0x01, CryptoAlgo::Sha256, Some(32), None),
+            (0x02, CryptoAlgo::Sha384, Some(48), None),
+            (0x03, CryptoAlgo::Sha512, Some(64), None),
+            (0x10, CryptoAlgo::P256, None, Some(32)),
+            (0x11, CryptoAlgo::P384, None, Some(48)),
+        ] {
+            assert_eq!(CryptoAlgo::try_from(raw), Ok(algo));
+            assert_eq!(algo as u8, raw);
+            assert_eq!(algo.digest_len(), digest);
+            assert_eq!(algo.scalar_len(), scalar);
+        }
+        assert_eq!(
+            CryptoAlgo::try_from(0x04),
+            Err(CryptoError::UnsupportedAlgorithm)
+        );
+    }
+}
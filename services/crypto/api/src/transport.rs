@@ -0,0 +1,43 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! The transport seam.
+//!
+//! One whole serialized request goes out, one whole response comes back —
+//! bytes in → bytes out, one shot, for every impl. Same seam as
+//! `i2c_api::Transport`: the `CryptoClient` is generic over it and contains
+//! **all** the wire marshalling, so swapping the transport is a wiring choice:
+//!
+//! - `IpcTransport` (in `crypto-client-ipc`) — production cross-process path
+//!   (Pigweed `channel_transact`); the only IPC-coupled, kernel-tagged piece.
+//! - `LoopbackTransport` (in `crypto-server`) — calls the server dispatch
+//!   directly against in-process HAL backends. Host-buildable, so the *same*
+//!   client encoders/decoders are exercised with no kernel.
+
+/// Why a transport round-trip failed. Deliberately tiny and transport-neutral;
+/// crypto-level status travels inside the response payload, not here.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransportError {
+    /// The underlying channel/syscall/loopback call failed.
+    Failed,
+}
+
+impl core::fmt::Display for TransportError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Failed => f.write_str("crypto transport round-trip failed"),
+        }
+    }
+}
+
+impl core::error::Error for TransportError {}
+
+/// Bytes-in → bytes-out, exactly one round-trip.
+///
+/// `transact` writes the response into `resp` and returns its length. The
+/// request is one fully serialized `crypto_api` request; the response is one
+/// fully serialized reply. No fragmentation, no state between calls.
+pub trait Transport {
+    fn transact(&mut self, req: &[u8], resp: &mut [u8]) -> Result<usize, TransportError>;
+}
@@ -0,0 +1,43 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! Production IPC transport for `CryptoClient`.
+//!
+//! The **only** IPC-coupled, kernel-tagged piece of the client path. It
+//! implements `crypto_api::Transport` over a Pigweed channel; all wire
+//! marshalling stays in the host-buildable `crypto_client`. Wiring:
+//!
+//! ```rust,ignore
+//! use crypto_client::CryptoClient;
+//! use crypto_client_ipc::IpcTransport;
+//! let mut crypto = CryptoClient::new(IpcTransport::new(handle::CRYPTO));
+//! ```
+//!
+//! Swapping this for `crypto_server::loopback::LoopbackTransport` (host) exercises the same
+//! `CryptoClient` code with no kernel — that is the point of the seam.
+
+#![no_std]
+
+use crypto_api::{Transport, TransportError};
+use userspace::syscall;
+use userspace::time::Instant;
+
+/// Cross-process transport: one `channel_transact` per request.
+pub struct IpcTransport {
+    handle: u32,
+}
+
+impl IpcTransport {
+    /// Bind to the crypto service's IPC channel (handle from the app's
+    /// generated `handle` module).
+    pub const fn new(handle: u32) -> Self {
+        Self { handle }
+    }
+}
+
+impl Transport for IpcTransport {
+    fn transact(&mut self, req: &[u8], resp: &mut [u8]) -> Result<usize, TransportError> {
+        syscall::channel_transact(self.handle, req, resp, Instant::MAX)
+            .map_err(|_| TransportError::Failed)
+    }
+}
@@ -0,0 +1,381 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! Client for the crypto service.
+//!
+//! `CryptoClient<T>` marshals every operation of the `crypto_api` wire
+//! protocol and is generic over [`Transport`]: the *same* encode/decode code
+//! runs in production (`IpcTransport`, cross-process) and in host tests
+//! (`LoopbackTransport`, in-process against real HAL backends). Each call
+//! performs one `Transport::transact` per request, except where noted:
+//!
+//! - one-shot hashes whose input exceeds [`MAX_PAYLOAD_SIZE`] are streamed
+//!   through a server-side session, and
+//! - [`get_random_bytes`](CryptoClient::get_random_bytes) is split into
+//!   payload-sized requests.
+//!
+//! Streaming sessions are plain tokens ([`Sha256Session`], [`Sha384Session`],
+//! [`Sha512Session`]) holding the server-side session id; every step takes
+//! the client that opened them. A session that is dropped instead of
+//! finalized or cancelled keeps its server backend busy.
+//!
+//! The client also implements `openprot_hal_blocking::rng::Drbg`, so the
+//! service DRBG can be handed to anything generic over the HAL.
+//!
+//! This crate has **no kernel/IPC dependency** and builds on the host.
+
+#![no_std]
+
+use crypto_api::{
+    CryptoAlgo, CryptoError, CryptoOp, CryptoRequestHeader, CryptoResponseHeader, Transport,
+    TransportError, MAX_BUF_SIZE, MAX_PAYLOAD_SIZE,
+};
+use openprot_hal_blocking::rng;
+use zerocopy::{FromBytes, IntoBytes};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClientError {
+    Transport(TransportError),
+    ServerError(CryptoError),
+    InvalidResponse,
+    /// The request does not fit one round-trip buffer.
+    BufferTooSmall,
+}
+
+/// Result type for crypto client operations.
+pub type CryptoResult<T> = Result<T, ClientError>;
+
+impl core::fmt::Display for ClientError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Transport(e) => write!(f, "crypto transport error: {e}"),
+            Self::ServerError(e) => write!(f, "crypto server error: {e}"),
+            Self::InvalidResponse => f.write_str("malformed crypto response"),
+            Self::BufferTooSmall => f.write_str("request exceeds one round-trip buffer"),
+        }
+    }
+}
+
+impl core::error::Error for ClientError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::Transport(e) => Some(e),
+            Self::ServerError(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<TransportError> for ClientError {
+    fn from(e: TransportError) -> Self {
+        Self::Transport(e)
+    }
+}
+
+// Lets the client stand in for any `Drbg` without callers knowing the
+// bytes come from another process.
+impl rng::Error for ClientError {
+    fn kind(&self) -> rng::ErrorKind {
+        match self {
+            Self::ServerError(CryptoError::Busy) => rng::ErrorKind::Busy,
+            Self::ServerError(CryptoError::ReseedRequired) => rng::ErrorKind::ReseedRequired,
+            Self::ServerError(CryptoError::EntropyFailure) => rng::ErrorKind::InsufficientEntropy,
+            Self::ServerError(CryptoError::BufferTooSmall) | Self::BufferTooSmall => {
+                rng::ErrorKind::RequestTooLarge
+            }
+            _ => rng::ErrorKind::Other,
+        }
+    }
+}
+
+macro_rules! session {
+    ($(#[$doc:meta])* $name:ident, $len:literal) => {
+        $(#[$doc])*
+        #[derive(Debug)]
+        pub struct $name {
+            id: u16,
+        }
+
+        impl $name {
+            /// Feed `data` into the session.
+            ///
+            /// Input larger than one payload is sent in several requests.
+            pub fn update<T: Transport>(
+                &mut self,
+                client: &mut CryptoClient<T>,
+                data: &[u8],
+            ) -> CryptoResult<()> {
+                client.hash_update(self.id, data)
+            }
+
+            /// Close the session and return its digest.
+            pub fn finalize<T: Transport>(
+                self,
+                client: &mut CryptoClient<T>,
+            ) -> CryptoResult<[u8; $len]> {
+                let mut digest = [0u8; $len];
+                client.call_exact(
+                    CryptoOp::HashFinish,
+                    CryptoAlgo::None,
+                    self.id,
+                    &[],
+                    &mut digest,
+                )?;
+                Ok(digest)
+            }
+
+            /// Close the session without producing a digest, releasing its
+            /// server-side backend.
+            pub fn cancel<T: Transport>(self, client: &mut CryptoClient<T>) -> CryptoResult<()> {
+                client
+                    .call(CryptoOp::HashCancel, CryptoAlgo::None, self.id, &[], &mut [])
+                    .map(|_| ())
+            }
+        }
+    };
+}
+
+session!(
+    /// An open SHA-256 streaming session.
+    Sha256Session,
+    32
+);
+session!(
+    /// An open SHA-384 streaming session.
+    Sha384Session,
+    48
+);
+session!(
+    /// An open SHA-512 streaming session.
+    Sha512Session,
+    64
+);
+
+macro_rules! hash_fns {
+    ($($one_shot:ident, $begin:ident, $session:ident, $algo:ident, $len:literal;)*) => {$(
+        #[doc = concat!("One-shot ", stringify!($algo), " of `data`.")]
+        ///
+        /// Input larger than one payload is streamed through a session.
+        pub fn $one_shot(&mut self, data: &[u8]) -> CryptoResult<[u8; $len]> {
+            if data.len() > MAX_PAYLOAD_SIZE {
+                let mut session = self.$begin()?;
+                session.update(self, data)?;
+                return session.finalize(self);
+            }
+            let mut digest = [0u8; $len];
+            self.call_exact(CryptoOp::Hash, CryptoAlgo::$algo, 0, data, &mut digest)?;
+            Ok(digest)
+        }
+
+        #[doc = concat!("Open a ", stringify!($algo), " streaming session.")]
+        ///
+        /// # Errors
+        /// - [`ClientError::ServerError`] with `Busy` if every server backend
+        ///   is already inside a session.
+        pub fn $begin(&mut self) -> CryptoResult<$session> {
+            self.begin(CryptoAlgo::$algo).map(|id| $session { id })
+        }
+    )*};
+}
+
+/// A crypto service client speaking the `crypto_api` wire protocol over any
+/// [`Transport`].
+pub struct CryptoClient<T: Transport> {
+    transport: T,
+}
+
+impl<T: Transport> CryptoClient<T> {
+    /// Create a client bound to `transport`.
+    pub const fn new(transport: T) -> Self {
+        Self { transport }
+    }
+
+    hash_fns! {
+        sha256, sha256_begin, Sha256Session, Sha256, 32;
+        sha384, sha384_begin, Sha384Session, Sha384, 48;
+        sha512, sha512_begin, Sha512Session, Sha512, 64;
+    }
+
+    /// One-shot HMAC of `data` under `key`, using the SHA-2 variant `algo`.
+    /// Returns the MAC length written to `mac`.
+    ///
+    /// # Errors
+    /// - [`ClientError::ServerError`] with `UnsupportedAlgorithm` if `algo`
+    ///   is not a SHA-2 selector.
+    /// - [`ClientError::BufferTooSmall`] — `key ‖ data` exceeds one payload,
+    ///   or `mac` cannot hold the MAC.
+    pub fn hmac(
+        &mut self,
+        algo: CryptoAlgo,
+        key: &[u8],
+        data: &[u8],
+        mac: &mut [u8],
+    ) -> CryptoResult<usize> {
+        let len = algo
+            .digest_len()
+            .ok_or(ClientError::ServerError(CryptoError::UnsupportedAlgorithm))?;
+        let mac = mac.get_mut(..len).ok_or(ClientError::BufferTooSmall)?;
+        let key_len = u16::try_from(key.len()).map_err(|_| ClientError::BufferTooSmall)?;
+        let mut payload = [0u8; MAX_PAYLOAD_SIZE];
+        let payload = payload
+            .get_mut(..key.len() + data.len())
+            .ok_or(ClientError::BufferTooSmall)?;
+        let (k, d) = payload.split_at_mut(key.len());
+        k.copy_from_slice(key);
+        d.copy_from_slice(data);
+        self.call_exact(CryptoOp::Hmac, algo, key_len, payload, mac)?;
+        Ok(len)
+    }
+
+    /// Fill `buf` from the service DRBG.
+    pub fn get_random_bytes(&mut self, buf: &mut [u8]) -> CryptoResult<()> {
+        self.random(buf, &[])
+    }
+
+    /// Reseed the service DRBG, mixing in `additional_input`.
+    pub fn reseed(&mut self, additional_input: &[u8]) -> CryptoResult<()> {
+        self.call(
+            CryptoOp::Reseed,
+            CryptoAlgo::None,
+            0,
+            additional_input,
+            &mut [],
+        )
+        .map(|_| ())
+    }
+
+    /// Verify a P-256 signature `(r, s)` over a SHA-256 `digest` with the
+    /// public key `(qx, qy)`. All values are big-endian.
+    ///
+    /// # Errors
+    /// - [`ClientError::ServerError`] with `VerificationFailed` if the
+    ///   signature does not verify.
+    pub fn ecdsa_verify_p256(
+        &mut self,
+        qx: &[u8; 32],
+        qy: &[u8; 32],
+        r: &[u8; 32],
+        s: &[u8; 32],
+        digest: &[u8; 32],
+    ) -> CryptoResult<()> {
+        self.ecdsa_verify(CryptoAlgo::P256, &[qx, qy, r, s, digest])
+    }
+
+    /// Verify a P-384 signature `(r, s)` over a SHA-384 `digest` with the
+    /// public key `(qx, qy)`. All values are big-endian.
+    ///
+    /// # Errors
+    /// - [`ClientError::ServerError`] with `VerificationFailed` if the
+    ///   signature does not verify.
+    pub fn ecdsa_verify_p384(
+        &mut self,
+        qx: &[u8; 48],
+        qy: &[u8; 48],
+        r: &[u8; 48],
+        s: &[u8; 48],
+        digest: &[u8; 48],
+    ) -> CryptoResult<()> {
+        self.ecdsa_verify(CryptoAlgo::P384, &[qx, qy, r, s, digest])
+    }
+
+    fn ecdsa_verify(&mut self, algo: CryptoAlgo, fields: &[&[u8]]) -> CryptoResult<()> {
+        let mut payload = [0u8; MAX_PAYLOAD_SIZE];
+        let mut len = 0;
+        for field in fields {
+            payload[len..len + field.len()].copy_from_slice(field);
+            len += field.len();
+        }
+        self.call(CryptoOp::EcdsaVerify, algo, 0, &payload[..len], &mut [])
+            .map(|_| ())
+    }
+
+    fn random(&mut self, buf: &mut [u8], additional_input: &[u8]) -> CryptoResult<()> {
+        for chunk in buf.chunks_mut(MAX_PAYLOAD_SIZE) {
+            self.call_exact(
+                CryptoOp::Random,
+                CryptoAlgo::None,
+                chunk.len() as u16,
+                additional_input,
+                chunk,
+            )?;
+        }
+        Ok(())
+    }
+
+    fn begin(&mut self, algo: CryptoAlgo) -> CryptoResult<u16> {
+        let mut id = [0u8; 2];
+        self.call_exact(CryptoOp::HashBegin, algo, 0, &[], &mut id)?;
+        Ok(u16::from_le_bytes(id))
+    }
+
+    fn hash_update(&mut self, id: u16, data: &[u8]) -> CryptoResult<()> {
+        for chunk in data.chunks(MAX_PAYLOAD_SIZE) {
+            self.call(CryptoOp::HashUpdate, CryptoAlgo::None, id, chunk, &mut [])?;
+        }
+        Ok(())
+    }
+
+    /// [`call`](Self::call), requiring exactly `out.len()` payload bytes back.
+    fn call_exact(
+        &mut self,
+        op: CryptoOp,
+        algo: CryptoAlgo,
+        arg: u16,
+        payload: &[u8],
+        out: &mut [u8],
+    ) -> CryptoResult<()> {
+        if self.call(op, algo, arg, payload, out)? != out.len() {
+            return Err(ClientError::InvalidResponse);
+        }
+        Ok(())
+    }
+
+    /// One round-trip: encode the request, decode the response header and
+    /// copy its payload into `out`. Returns the payload length.
+    fn call(
+        &mut self,
+        op: CryptoOp,
+        algo: CryptoAlgo,
+        arg: u16,
+        payload: &[u8],
+        out: &mut [u8],
+    ) -> CryptoResult<usize> {
+        if payload.len() > MAX_PAYLOAD_SIZE {
+            return Err(ClientError::BufferTooSmall);
+        }
+        let hdr = CryptoRequestHeader::new(op, algo, arg, payload.len() as u16);
+        let req_len = CryptoRequestHeader::SIZE + payload.len();
+        let mut req = [0u8; MAX_BUF_SIZE];
+        req[..CryptoRequestHeader::SIZE].copy_from_slice(hdr.as_bytes());
+        req[CryptoRequestHeader::SIZE..req_len].copy_from_slice(payload);
+
+        let mut resp = [0u8; MAX_BUF_SIZE];
+        let resp_len = self.transport.transact(&req[..req_len], &mut resp)?;
+        let resp = resp.get(..resp_len).ok_or(ClientError::InvalidResponse)?;
+        let (rhdr, body) = CryptoResponseHeader::ref_from_prefix(resp)
+            .map_err(|_| ClientError::InvalidResponse)?;
+        if let Some(e) = rhdr.error_code() {
+            return Err(ClientError::ServerError(e));
+        }
+        let n = rhdr.payload_length();
+        if body.len() < n || out.len() < n {
+            return Err(ClientError::InvalidResponse);
+        }
+        out[..n].copy_from_slice(&body[..n]);
+        Ok(n)
+    }
+}
+
+impl<T: Transport> rng::ErrorType for CryptoClient<T> {
+    type Error = ClientError;
+}
+
+impl<T: Transport> rng::Drbg for CryptoClient<T> {
+    fn generate(&mut self, dest: &mut [u8], additional_input: &[u8]) -> CryptoResult<()> {
+        self.random(dest, additional_input)
+    }
+
+    fn reseed(&mut self, additional_input: &[u8]) -> CryptoResult<()> {
+        CryptoClient::reseed(self, additional_input)
+    }
+}
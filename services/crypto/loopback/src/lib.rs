@@ -0,0 +1,79 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! Host crypto service backed by RustCrypto.
+//!
+//! Wires `crypto_server::CryptoServer` to [`RustCryptoController`] backends
+//! and a RustCrypto HMAC_DRBG behind a [`LoopbackTransport`], so code written
+//! against `CryptoClient<T>` — the SPDM hash and RNG crates, for example —
+//! runs against real algorithms on the host, with no kernel:
+//!
+//! ```rust,ignore
+//! use crypto_client::CryptoClient;
+//! use crypto_loopback::{CountingEntropy, rustcrypto_loopback};
+//!
+//! let mut crypto = CryptoClient::new(rustcrypto_loopback(CountingEntropy::new(0), b"test")?);
+//! let digest = crypto.sha384(b"abc")?;
+//! ```
+//!
+//! [`CountingEntropy`] is a deterministic stand-in for a TRNG so tests are
+//! reproducible. It provides no entropy and must never seed a production
+//! DRBG.
+
+use crypto_server::CryptoServer;
+use openprot_hal_blocking::rng::{EntropySource, ErrorKind, ErrorType};
+use platform_rustcrypto_impl::{HmacDrbgSha256, RustCryptoController};
+
+pub use crypto_server::loopback::LoopbackTransport;
+
+/// Number of RustCrypto backends, and so of concurrently open hash sessions.
+pub const SESSIONS: usize = 4;
+
+/// In-process transport to a RustCrypto-backed crypto server.
+pub type RustCryptoLoopback<E> =
+    LoopbackTransport<RustCryptoController, HmacDrbgSha256<E>, SESSIONS>;
+
+/// Build a loopback transport whose server DRBG is instantiated from
+/// `entropy` and `personalization`.
+///
+/// # Errors
+///
+/// Any error reported by `entropy` while instantiating the DRBG.
+pub fn rustcrypto_loopback<E: EntropySource>(
+    entropy: E,
+    personalization: &[u8],
+) -> Result<RustCryptoLoopback<E>, ErrorKind> {
+    let drbg = HmacDrbgSha256::new(entropy, personalization)?;
+    let backends = core::array::from_fn(|_| RustCryptoController::new());
+    Ok(LoopbackTransport::new(CryptoServer::new(backends, drbg)))
+}
+
+/// Deterministic "entropy": consecutive byte values starting from a seed.
+///
+/// For host tests only. Two DRBGs instantiated from sources with the same
+/// seed produce the same output.
+#[derive(Debug, Clone)]
+pub struct CountingEntropy {
+    next: u8,
+}
+
+impl CountingEntropy {
+    /// Create a source whose first byte is `seed`.
+    pub const fn new(seed: u8) -> Self {
+        Self { next: seed }
+    }
+}
+
+impl ErrorType for CountingEntropy {
+    type Error = ErrorKind;
+}
+
+impl EntropySource for CountingEntropy {
+    fn fill_entropy(&mut self, dest: &mut [u8]) -> Result<(), ErrorKind> {
+        for byte in dest {
+            *byte = self.next;
+            self.next = self.next.wrapping_add(1);
+        }
+        Ok(())
+    }
+}
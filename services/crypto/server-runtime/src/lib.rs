@@ -0,0 +1,56 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! IPC dispatch loop for the crypto service.
+//!
+//! Every request runs to completion inside `CryptoServer::dispatch`, so the
+//! loop is stateless: wait, read, dispatch, respond. Streaming hash sessions
+//! live in the server, not in the loop, which means several client tasks can
+//! share one server as long as the backend pool is large enough for their
+//! open sessions.
+//!
+//! Same wait-group convention as the usart runtime: the binary registers
+//! each client channel with its own handle as `user_data`
+//! (`wait_group_add(wg, ch, Signals::READABLE, ch as usize)`), and the loop
+//! reads from and responds on the channel named by `user_data`.
+//!
+//! The **only** kernel-tagged server crate; it wraps the host-buildable
+//! `crypto_server::CryptoServer` in the Pigweed loop.
+
+#![no_std]
+
+use crypto_server::backend::CryptoBackend;
+use crypto_server::{CryptoServer, MAX_BUF_SIZE};
+use openprot_hal_blocking::rng::Drbg;
+use userspace::syscall::{self, Signals};
+use userspace::time::Instant;
+
+/// Run the crypto server forever on the channels registered with `wg`.
+pub fn run<C, R, const N: usize>(server: &mut CryptoServer<C, R, N>, wg: u32) -> !
+where
+    C: CryptoBackend,
+    R: Drbg,
+{
+    let mut request_buf = [0u8; MAX_BUF_SIZE];
+    let mut response_buf = [0u8; MAX_BUF_SIZE];
+
+    loop {
+        let Ok(w) = syscall::object_wait(wg, Signals::READABLE, Instant::MAX) else {
+            continue;
+        };
+        if !w.pending_signals.contains(Signals::READABLE) {
+            continue;
+        }
+
+        let channel = w.user_data as u32;
+        let Ok(req_len) = syscall::channel_read(channel, 0, &mut request_buf) else {
+            pw_log::error!("channel_read failed");
+            continue;
+        };
+
+        let resp_len = server.dispatch(&request_buf[..req_len], &mut response_buf);
+        if syscall::channel_respond(channel, &response_buf[..resp_len]).is_err() {
+            pw_log::error!("channel_respond failed");
+        }
+    }
+}
@@ -0,0 +1,103 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! The HAL seam on the server side.
+//!
+//! The dispatcher never names a concrete crypto implementation. It drives
+//! any type implementing the `openprot_hal_blocking` owned digest, owned MAC
+//! and ECDSA verify traits for the algorithms on the wire — the RustCrypto
+//! controller on the host, a hardware accelerator driver on target — and any
+//! `rng::Drbg`. HAL error kinds are mapped onto the wire status codes here.
+
+use crypto_api::CryptoError;
+use openprot_hal_blocking::digest::owned::DigestInit;
+use openprot_hal_blocking::digest::{self, Digest, Sha2_256, Sha2_384, Sha2_512};
+use openprot_hal_blocking::ecdsa::{self, EcdsaVerify, P256, P384};
+use openprot_hal_blocking::mac::owned::MacInit;
+use openprot_hal_blocking::mac::{self, HmacSha2_256, HmacSha2_384, HmacSha2_512};
+use openprot_hal_blocking::rng;
+use zerocopy::{Immutable, IntoBytes};
+
+/// Owned-API SHA-2 support for every digest on the wire.
+pub trait HashBackend:
+    DigestInit<Sha2_256, Output = Digest<8>>
+    + DigestInit<Sha2_384, Output = Digest<12>>
+    + DigestInit<Sha2_512, Output = Digest<16>>
+{
+}
+
+impl<T> HashBackend for T where
+    T: DigestInit<Sha2_256, Output = Digest<8>>
+        + DigestInit<Sha2_384, Output = Digest<12>>
+        + DigestInit<Sha2_512, Output = Digest<16>>
+{
+}
+
+/// Owned-API HMAC support for every HMAC on the wire. Keys arrive as bytes
+/// and are converted into the backend's key handle.
+pub trait MacBackend:
+    MacInit<HmacSha2_256, Key: for<'a> TryFrom<&'a [u8]>, Output: IntoBytes + Immutable>
+    + MacInit<HmacSha2_384, Key: for<'a> TryFrom<&'a [u8]>, Output: IntoBytes + Immutable>
+    + MacInit<HmacSha2_512, Key: for<'a> TryFrom<&'a [u8]>, Output: IntoBytes + Immutable>
+{
+}
+
+impl<T> MacBackend for T where
+    T: MacInit<HmacSha2_256, Key: for<'a> TryFrom<&'a [u8]>, Output: IntoBytes + Immutable>
+        + MacInit<HmacSha2_384, Key: for<'a> TryFrom<&'a [u8]>, Output: IntoBytes + Immutable>
+        + MacInit<HmacSha2_512, Key: for<'a> TryFrom<&'a [u8]>, Output: IntoBytes + Immutable>
+{
+}
+
+/// ECDSA verification for every curve on the wire.
+pub trait EcdsaBackend: EcdsaVerify<P256> + EcdsaVerify<P384> {}
+
+impl<T> EcdsaBackend for T where T: EcdsaVerify<P256> + EcdsaVerify<P384> {}
+
+/// Everything one crypto backend instance must provide.
+pub trait CryptoBackend: HashBackend + MacBackend + EcdsaBackend {}
+
+impl<T> CryptoBackend for T where T: HashBackend + MacBackend + EcdsaBackend {}
+
+pub(crate) fn digest_error(kind: digest::ErrorKind) -> CryptoError {
+    match kind {
+        digest::ErrorKind::Busy => CryptoError::Busy,
+        digest::ErrorKind::UnsupportedAlgorithm => CryptoError::UnsupportedAlgorithm,
+        digest::ErrorKind::InvalidInputLength => CryptoError::BufferTooSmall,
+        _ => CryptoError::InternalError,
+    }
+}
+
+pub(crate) fn mac_error(kind: mac::ErrorKind) -> CryptoError {
+    match kind {
+        mac::ErrorKind::Busy => CryptoError::Busy,
+        mac::ErrorKind::UnsupportedAlgorithm => CryptoError::UnsupportedAlgorithm,
+        mac::ErrorKind::InvalidInputLength => CryptoError::InvalidKey,
+        _ => CryptoError::InternalError,
+    }
+}
+
+pub(crate) fn ecdsa_error(kind: ecdsa::ErrorKind) -> CryptoError {
+    match kind {
+        ecdsa::ErrorKind::Busy => CryptoError::Busy,
+        ecdsa::ErrorKind::InvalidSignature => CryptoError::VerificationFailed,
+        ecdsa::ErrorKind::InvalidKeyFormat | ecdsa::ErrorKind::InvalidPoint => {
+            CryptoError::InvalidKey
+        }
+        ecdsa::ErrorKind::UnsupportedCurve => CryptoError::UnsupportedAlgorithm,
+        _ => CryptoError::InternalError,
+    }
+}
+
+pub(crate) fn rng_error(kind: rng::ErrorKind) -> CryptoError {
+    match kind {
+        rng::ErrorKind::Busy => CryptoError::Busy,
+        rng::ErrorKind::ReseedRequired => CryptoError::ReseedRequired,
+        rng::ErrorKind::RequestTooLarge => CryptoError::BufferTooSmall,
+        rng::ErrorKind::InsufficientEntropy
+        | rng::ErrorKind::RepetitionCountFailure
+        | rng::ErrorKind::AdaptiveProportionFailure
+        | rng::ErrorKind::HardwareFailure => CryptoError::EntropyFailure,
+        _ => CryptoError::InternalError,
+    }
+}
@@ -0,0 +1,1013 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! Server side of the crypto service.
+//!
+//! Mirror image of `crypto_client`: each request is decoded, run to
+//! completion against `openprot_hal_blocking` implementations, and answered
+//! with exactly one response. [`CryptoServer::dispatch`] is pure (no
+//! `userspace`/IPC) and generic over the backend, so it is unit-testable on
+//! the host; the Pigweed loop lives in `crypto_server_runtime`.
+//!
+//! ## Backends and sessions
+//!
+//! The server owns a fixed pool of `N` backend instances (see
+//! [`backend::CryptoBackend`]). The owned digest API moves a backend into a
+//! hash context for the lifetime of a streaming session, so at most `N`
+//! sessions can be open at once; one-shot operations borrow any free backend
+//! and fail with `Busy` while all of them are inside sessions.
+//!
+//! If a backend reports an error in the middle of an owned operation, the
+//! context — and with it the backend — is consumed. That pool entry is
+//! retired and the server carries on with the remaining backends.
+
+#![no_std]
+#![deny(
+    clippy::unwrap_used,
+    clippy::expect_used,
+    clippy::panic,
+    clippy::unreachable,
+    clippy::todo,
+    clippy::unimplemented
+)]
+#![cfg_attr(test, allow(clippy::unwrap_used))]
+
+pub mod backend;
+pub mod loopback;
+
+use backend::{digest_error, ecdsa_error, mac_error, rng_error, CryptoBackend, HashBackend};
+use crypto_api::{
+    CryptoAlgo, CryptoError, CryptoOp, CryptoRequestHeader, CryptoResponseHeader, MAX_PAYLOAD_SIZE,
+};
+use openprot_hal_blocking::digest::owned::{DigestInit, DigestOp};
+use openprot_hal_blocking::digest::{DigestAlgorithm, Error as _, Sha2_256, Sha2_384, Sha2_512};
+use openprot_hal_blocking::ecdsa::{
+    Curve, EcdsaVerify, Error as _, PublicKey, Signature, P256, P384,
+};
+use openprot_hal_blocking::mac::owned::{MacInit, MacOp};
+use openprot_hal_blocking::mac::{
+    Error as _, HmacSha2_256, HmacSha2_384, HmacSha2_512, MacAlgorithm,
+};
+use openprot_hal_blocking::rng::{Drbg, Error as _};
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+pub use crypto_api::MAX_BUF_SIZE;
+
+/// One pool entry: a free backend or the hash context it was moved into.
+enum Slot<C: HashBackend> {
+    Free(C),
+    Sha256(u16, <C as DigestInit<Sha2_256>>::Context),
+    Sha384(u16, <C as DigestInit<Sha2_384>>::Context),
+    Sha512(u16, <C as DigestInit<Sha2_512>>::Context),
+    /// The backend was consumed by a failed operation.
+    Retired,
+}
+
+impl<C: HashBackend> Slot<C> {
+    fn session_id(&self) -> Option<u16> {
+        match self {
+            Self::Sha256(id, _) | Self::Sha384(id, _) | Self::Sha512(id, _) => Some(*id),
+            Self::Free(_) | Self::Retired => None,
+        }
+    }
+}
+
+pub(crate) fn encode_error(response: &mut [u8], err: CryptoError) -> usize {
+    let hdr = CryptoResponseHeader::error(err);
+    response[..CryptoResponseHeader::SIZE].copy_from_slice(hdr.as_bytes());
+    CryptoResponseHeader::SIZE
+}
+
+/// Success header with `payload_len` bytes already placed at
+/// `response[CryptoResponseHeader::SIZE..]` by the caller (0 for ack-only).
+pub(crate) fn encode_ok(response: &mut [u8], payload_len: usize) -> usize {
+    let hdr = CryptoResponseHeader::success(payload_len as u16);
+    response[..CryptoResponseHeader::SIZE].copy_from_slice(hdr.as_bytes());
+    CryptoResponseHeader::SIZE + payload_len
+}
+
+/// Crypto service state: the backend pool, open sessions and the DRBG.
+pub struct CryptoServer<C: HashBackend, R, const N: usize> {
+    slots: [Slot<C>; N],
+    rng: R,
+    next_session: u16,
+}
+
+impl<C, R, const N: usize> CryptoServer<C, R, N>
+where
+    C: CryptoBackend,
+    R: Drbg,
+{
+    /// Create a server owning `backends` and the instantiated DRBG `rng`.
+    pub fn new(backends: [C; N], rng: R) -> Self {
+        Self {
+            slots: backends.map(Slot::Free),
+            rng,
+            next_session: 0,
+        }
+    }
+
+    /// Number of open hash sessions.
+    pub fn open_sessions(&self) -> usize {
+        self.slots
+            .iter()
+            .filter(|slot| slot.session_id().is_some())
+            .count()
+    }
+
+    /// Decode one wire request, run it, and encode the response into
+    /// `response`.
+    ///
+    /// Returns the number of bytes written to `response` (always `>=
+    /// CryptoResponseHeader::SIZE`, or 0 if `response` cannot even hold a
+    /// header). Never panics on malformed input — it answers with an error
+    /// header instead.
+    pub fn dispatch(&mut self, request: &[u8], response: &mut [u8]) -> usize {
+        if response.len() < CryptoResponseHeader::SIZE {
+            return 0;
+        }
+        let cap = (response.len() - CryptoResponseHeader::SIZE).min(MAX_PAYLOAD_SIZE);
+        let out = &mut response[CryptoResponseHeader::SIZE..][..cap];
+        match self.handle(request, out) {
+            Ok(n) => encode_ok(response, n),
+            Err(e) => encode_error(response, e),
+        }
+    }
+
+    fn handle(&mut self, request: &[u8], out: &mut [u8]) -> Result<usize, CryptoError> {
+        let Some((hdr, body)) = request.split_at_checked(CryptoRequestHeader::SIZE) else {
+            return Err(CryptoError::InvalidOperation);
+        };
+        let hdr =
+            CryptoRequestHeader::ref_from_bytes(hdr).map_err(|_| CryptoError::InvalidOperation)?;
+        let payload_len = hdr.payload_length();
+        if payload_len > MAX_PAYLOAD_SIZE {
+            return Err(CryptoError::BufferTooSmall);
+        }
+        let payload = body.get(..payload_len).ok_or(CryptoError::BufferTooSmall)?;
+        let algo = hdr.algorithm()?;
+        let arg = hdr.arg_value();
+
+        match hdr.operation()? {
+            CryptoOp::Hash => self.hash(algo, payload, out),
+            CryptoOp::HashBegin => self.hash_begin(algo, out),
+            CryptoOp::HashUpdate => self.hash_update(arg, payload).map(|()| 0),
+            CryptoOp::HashFinish => self.hash_finish(arg, out),
+            CryptoOp::HashCancel => self.hash_cancel(arg).map(|()| 0),
+            CryptoOp::Hmac => self.hmac(algo, usize::from(arg), payload, out),
+            CryptoOp::Random => {
+                let out = out
+                    .get_mut(..usize::from(arg))
+                    .ok_or(CryptoError::BufferTooSmall)?;
+                self.rng
+                    .generate(out, payload)
+                    .map_err(|e| rng_error(e.kind()))?;
+                Ok(out.len())
+            }
+            CryptoOp::Reseed => self
+                .rng
+                .reseed(payload)
+                .map(|()| 0)
+                .map_err(|e| rng_error(e.kind())),
+            CryptoOp::EcdsaVerify => self.ecdsa_verify(algo, payload).map(|()| 0),
+            _ => Err(CryptoError::InvalidOperation),
+        }
+    }
+
+    /// Take a free backend out of the pool, leaving its slot `Retired` until
+    /// the caller puts it back.
+    fn take_free(&mut self) -> Result<(usize, C), CryptoError> {
+        for (index, slot) in self.slots.iter_mut().enumerate() {
+            match core::mem::replace(slot, Slot::Retired) {
+                Slot::Free(backend) => return Ok((index, backend)),
+                other => *slot = other,
+            }
+        }
+        Err(CryptoError::Busy)
+    }
+
+    /// Take the session `id` out of the pool, leaving its slot `Retired`.
+    fn take_session(&mut self, id: u16) -> Result<(usize, Slot<C>), CryptoError> {
+        let index = self
+            .slots
+            .iter()
+            .position(|slot| slot.session_id() == Some(id))
+            .ok_or(CryptoError::InvalidSession)?;
+        Ok((
+            index,
+            core::mem::replace(&mut self.slots[index], Slot::Retired),
+        ))
+    }
+
+    fn hash(
+        &mut self,
+        algo: CryptoAlgo,
+        data: &[u8],
+        out: &mut [u8],
+    ) -> Result<usize, CryptoError> {
+        let (index, backend) = self.take_free()?;
+        let (result, backend) = match algo {
+            CryptoAlgo::Sha256 => one_shot_hash(backend, Sha2_256, data, out),
+            CryptoAlgo::Sha384 => one_shot_hash(backend, Sha2_384, data, out),
+            CryptoAlgo::Sha512 => one_shot_hash(backend, Sha2_512, data, out),
+            _ => (Err(CryptoError::UnsupportedAlgorithm), Some(backend)),
+        };
+        if let Some(backend) = backend {
+            self.slots[index] = Slot::Free(backend);
+        }
+        result
+    }
+
+    fn hash_begin(&mut self, algo: CryptoAlgo, out: &mut [u8]) -> Result<usize, CryptoError> {
+        if out.len() < 2 {
+            return Err(CryptoError::BufferTooSmall);
+        }
+        if algo.digest_len().is_none() {
+            return Err(CryptoError::UnsupportedAlgorithm);
+        }
+        let id = self.allocate_session_id();
+        let (index, backend) = self.take_free()?;
+        let slot = match algo {
+            CryptoAlgo::Sha256 => DigestInit::init(backend, Sha2_256).map(|c| Slot::Sha256(id, c)),
+            CryptoAlgo::Sha384 => DigestInit::init(backend, Sha2_384).map(|c| Slot::Sha384(id, c)),
+            _ => DigestInit::init(backend, Sha2_512).map(|c| Slot::Sha512(id, c)),
+        }
+        .map_err(|e| digest_error(e.kind()))?;
+        self.slots[index] = slot;
+        out[..2].copy_from_slice(&id.to_le_bytes());
+        Ok(2)
+    }
+
+    fn hash_update(&mut self, id: u16, data: &[u8]) -> Result<(), CryptoError> {
+        let (index, slot) = self.take_session(id)?;
+        self.slots[index] = match slot {
+            Slot::Sha256(id, ctx) => {
+                Slot::Sha256(id, ctx.update(data).map_err(|e| digest_error(e.kind()))?)
+            }
+            Slot::Sha384(id, ctx) => {
+                Slot::Sha384(id, ctx.update(data).map_err(|e| digest_error(e.kind()))?)
+            }
+            Slot::Sha512(id, ctx) => {
+                Slot::Sha512(id, ctx.update(data).map_err(|e| digest_error(e.kind()))?)
+            }
+            other => other,
+        };
+        Ok(())
+    }
+
+    fn hash_finish(&mut self, id: u16, out: &mut [u8]) -> Result<usize, CryptoError> {
+        let (index, slot) = self.take_session(id)?;
+        let (result, backend) = match slot {
+            Slot::Sha256(_, ctx) => finish_hash(ctx, out),
+            Slot::Sha384(_, ctx) => finish_hash(ctx, out),
+            Slot::Sha512(_, ctx) => finish_hash(ctx, out),
+            Slot::Free(_) | Slot::Retired => (Err(CryptoError::InvalidSession), None),
+        };
+        if let Some(backend) = backend {
+            self.slots[index] = Slot::Free(backend);
+        }
+        result
+    }
+
+    fn hash_cancel(&mut self, id: u16) -> Result<(), CryptoError> {
+        let (index, slot) = self.take_session(id)?;
+        self.slots[index] = match slot {
+            Slot::Sha256(_, ctx) => Slot::Free(ctx.cancel()),
+            Slot::Sha384(_, ctx) => Slot::Free(ctx.cancel()),
+            Slot::Sha512(_, ctx) => Slot::Free(ctx.cancel()),
+            other => other,
+        };
+        Ok(())
+    }
+
+    fn hmac(
+        &mut self,
+        algo: CryptoAlgo,
+        key_len: usize,
+        payload: &[u8],
+        out: &mut [u8],
+    ) -> Result<usize, CryptoError> {
+        let (key, data) = payload
+            .split_at_checked(key_len)
+            .ok_or(CryptoError::BufferTooSmall)?;
+        let (index, backend) = self.take_free()?;
+        let (result, backend) = match algo {
+            CryptoAlgo::Sha256 => one_shot_mac(backend, HmacSha2_256, key, data, out),
+            CryptoAlgo::Sha384 => one_shot_mac(backend, HmacSha2_384, key, data, out),
+            CryptoAlgo::Sha512 => one_shot_mac(backend, HmacSha2_512, key, data, out),
+            _ => (Err(CryptoError::UnsupportedAlgorithm), Some(backend)),
+        };
+        if let Some(backend) = backend {
+            self.slots[index] = Slot::Free(backend);
+        }
+        result
+    }
+
+    fn ecdsa_verify(&mut self, algo: CryptoAlgo, payload: &[u8]) -> Result<(), CryptoError> {
+        let backend = self
+            .slots
+            .iter_mut()
+            .find_map(|slot| match slot {
+                Slot::Free(backend) => Some(backend),
+                _ => None,
+            })
+            .ok_or(CryptoError::Busy)?;
+        match algo {
+            CryptoAlgo::P256 => verify::<P256, C>(backend, payload),
+            CryptoAlgo::P384 => verify::<P384, C>(backend, payload),
+            _ => Err(CryptoError::UnsupportedAlgorithm),
+        }
+    }
+
+    /// Next nonzero session id not currently in use.
+    fn allocate_session_id(&mut self) -> u16 {
+        loop {
+            self.next_session = self.next_session.wrapping_add(1);
+            let id = self.next_session;
+            if id != 0 && !self.slots.iter().any(|slot| slot.session_id() == Some(id)) {
+                return id;
+            }
+        }
+    }
+}
+
+/// Copy a backend output into the response payload.
+fn write_output(bytes: &[u8], out: &mut [u8]) -> Result<usize, CryptoError> {
+    out.get_mut(..bytes.len())
+        .ok_or(CryptoError::BufferTooSmall)?
+        .copy_from_slice(bytes);
+    Ok(bytes.len())
+}
+
+fn finish_hash<X>(ctx: X, out: &mut [u8]) -> (Result<usize, CryptoError>, Option<X::Controller>)
+where
+    X: DigestOp,
+    X::Output: Immutable,
+{
+    match ctx.finalize() {
+        Ok((digest, backend)) => (write_output(digest.as_bytes(), out), Some(backend)),
+        Err(e) => (Err(digest_error(e.kind())), None),
+    }
+}
+
+/// Hash `data` in one go, handing the backend back unless it was consumed.
+fn one_shot_hash<A, C>(
+    backend: C,
+    algo: A,
+    data: &[u8],
+    out: &mut [u8],
+) -> (Result<usize, CryptoError>, Option<C>)
+where
+    A: DigestAlgorithm,
+    C: DigestInit<A>,
+    C::Output: Immutable,
+{
+    let ctx = match backend.init(algo) {
+        Ok(ctx) => ctx,
+        Err(e) => return (Err(digest_error(e.kind())), None),
+    };
+    match ctx.update(data) {
+        Ok(ctx) => finish_hash(ctx, out),
+        Err(e) => (Err(digest_error(e.kind())), None),
+    }
+}
+
+/// MAC `data` under `key` in one go, handing the backend back unless it
+/// was consumed.
+fn one_shot_mac<A, C>(
+    backend: C,
+    algo: A,
+    key: &[u8],
+    data: &[u8],
+    out: &mut [u8],
+) -> (Result<usize, CryptoError>, Option<C>)
+where
+    A: MacAlgorithm,
+    C: MacInit<A, Key: for<'a> TryFrom<&'a [u8]>, Output: Immutable>,
+{
+    let Ok(key) = C::Key::try_from(key) else {
+        return (Err(CryptoError::InvalidKey), Some(backend));
+    };
+    let ctx = match backend.init(algo, key) {
+        Ok(ctx) => ctx,
+        Err(e) => return (Err(mac_error(e.kind())), None),
+    };
+    let ctx = match ctx.update(data) {
+        Ok(ctx) => ctx,
+        Err(e) => return (Err(mac_error(e.kind())), None),
+    };
+    match ctx.finalize() {
+        Ok((mac, backend)) => (write_output(mac.as_bytes(), out), Some(backend)),
+        Err(e) => (Err(mac_error(e.kind())), None),
+    }
+}
+
+/// Decode `Qx ‖ Qy ‖ r ‖ s ‖ digest` and verify it on `backend`.
+fn verify<K, B>(backend: &mut B, payload: &[u8]) -> Result<(), CryptoError>
+where
+    K: Curve,
+    B: EcdsaVerify<K>,
+    <K::DigestType as DigestAlgorithm>::Digest: FromBytes,
+{
+    let scalar = core::mem::size_of::<K::Scalar>();
+    let digest_len = K::DigestType::OUTPUT_BITS / 8;
+    if payload.len() != 4 * scalar + digest_len {
+        return Err(CryptoError::BufferTooSmall);
+    }
+    let field = |i: usize| {
+        K::Scalar::read_from_bytes(&payload[i * scalar..(i + 1) * scalar])
+            .map_err(|_| CryptoError::BufferTooSmall)
+    };
+    let public_key = B::PublicKey::from_coordinates(field(0)?, field(1)?)
+        .map_err(|_| CryptoError::InvalidKey)?;
+    let signature = B::Signature::from_coordinates(field(2)?, field(3)?).map_err(ecdsa_error)?;
+    let digest =
+        <K::DigestType as DigestAlgorithm>::Digest::read_from_bytes(&payload[4 * scalar..])
+            .map_err(|_| CryptoError::BufferTooSmall)?;
+    backend
+        .verify(&public_key, digest, &signature)
+        .map_err(|e| ecdsa_error(e.kind()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openprot_hal_blocking::digest::{self, Digest};
+    use openprot_hal_blocking::ecdsa::P384Signature;
+    use openprot_hal_blocking::ecdsa::{self, P256PublicKey, P256Signature, P384PublicKey};
+    use openprot_hal_blocking::mac::{self, KeyHandle};
+    use openprot_platform_mock::rng::{self, CountingDrbg};
+
+    /// Input that makes the fake backend fail mid-operation, and the DRBG
+    /// fail to reseed.
+    const POISON: &[u8] = rng::POISON;
+
+    /// Non-cryptographic stand-in for a hash engine: a rolling checksum
+    /// spread across the output words.
+    fn fold<const N: usize>(state: u32, data: &[u8]) -> Digest<N> {
+        let state = data
+            .iter()
+            .fold(state, |s, &b| s.rotate_left(5) ^ u32::from(b));
+        Digest::new(core::array::from_fn(|i| state.wrapping_add(i as u32)))
+    }
+
+    fn checksum(state: u32, data: &[u8]) -> u32 {
+        fold::<1>(state, data).value[0]
+    }
+
+    #[derive(Debug)]
+    enum FakeError {
+        Hardware,
+        BadSignature,
+    }
+
+    impl digest::Error for FakeError {
+        fn kind(&self) -> digest::ErrorKind {
+            digest::ErrorKind::HardwareFailure
+        }
+    }
+
+    impl mac::Error for FakeError {
+        fn kind(&self) -> mac::ErrorKind {
+            mac::ErrorKind::HardwareFailure
+        }
+    }
+
+    impl ecdsa::Error for FakeError {
+        fn kind(&self) -> ecdsa::ErrorKind {
+            match self {
+                Self::Hardware => ecdsa::ErrorKind::Other,
+                Self::BadSignature => ecdsa::ErrorKind::InvalidSignature,
+            }
+        }
+    }
+
+    struct FakeBackend;
+
+    struct FakeHash<const N: usize> {
+        state: u32,
+    }
+
+    struct FakeMac<const N: usize> {
+        state: u32,
+    }
+
+    struct FakeKey(u32);
+
+    impl KeyHandle for FakeKey {}
+
+    impl TryFrom<&[u8]> for FakeKey {
+        type Error = ();
+
+        fn try_from(key: &[u8]) -> Result<Self, ()> {
+            if key.is_empty() {
+                Err(())
+            } else {
+                Ok(Self(checksum(0, key)))
+            }
+        }
+    }
+
+    impl digest::ErrorType for FakeBackend {
+        type Error = FakeError;
+    }
+
+    impl mac::ErrorType for FakeBackend {
+        type Error = FakeError;
+    }
+
+    impl ecdsa::ErrorType for FakeBackend {
+        type Error = FakeError;
+    }
+
+    impl<const N: usize> digest::ErrorType for FakeHash<N> {
+        type Error = FakeError;
+    }
+
+    impl<const N: usize> mac::ErrorType for FakeMac<N> {
+        type Error = FakeError;
+    }
+
+    impl<const N: usize> DigestOp for FakeHash<N> {
+        type Output = Digest<N>;
+        type Controller = FakeBackend;
+
+        fn update(self, data: &[u8]) -> Result<Self, FakeError> {
+            if data == POISON {
+                return Err(FakeError::Hardware);
+            }
+            Ok(Self {
+                state: checksum(self.state, data),
+            })
+        }
+
+        fn finalize(self) -> Result<(Digest<N>, FakeBackend), FakeError> {
+            Ok((fold(self.state, &[]), FakeBackend))
+        }
+
+        fn cancel(self) -> FakeBackend {
+            FakeBackend
+        }
+    }
+
+    impl<const N: usize> MacOp for FakeMac<N> {
+        type Output = Digest<N>;
+        type Controller = FakeBackend;
+
+        fn update(self, data: &[u8]) -> Result<Self, FakeError> {
+            Ok(Self {
+                state: checksum(self.state, data),
+            })
+        }
+
+        fn finalize(self) -> Result<(Digest<N>, FakeBackend), FakeError> {
+            Ok((fold(self.state, &[]), FakeBackend))
+        }
+
+        fn cancel(self) -> FakeBackend {
+            FakeBackend
+        }
+    }
+
+    macro_rules! fake_algos {
+        ($($hash:ty, $hmac:ty, $n:literal, $seed:literal;)*) => {$(
+            impl DigestInit<$hash> for FakeBackend {
+                type Context = FakeHash<$n>;
+                type Output = Digest<$n>;
+
+                fn init(self, _: $hash) -> Result<FakeHash<$n>, FakeError> {
+                    Ok(FakeHash { state: $seed })
+                }
+            }
+
+            impl MacInit<$hmac> for FakeBackend {
+                type Key = FakeKey;
+                type Context = FakeMac<$n>;
+                type Output = Digest<$n>;
+
+                fn init(self, _: $hmac, key: FakeKey) -> Result<FakeMac<$n>, FakeError> {
+                    Ok(FakeMac { state: key.0 ^ $seed })
+                }
+            }
+        )*};
+    }
+
+    fake_algos! {
+        Sha2_256, HmacSha2_256, 8, 256;
+        Sha2_384, HmacSha2_384, 12, 384;
+        Sha2_512, HmacSha2_512, 16, 512;
+    }
+
+    /// Accepts a signature iff `r == Qx`.
+    macro_rules! fake_verify {
+        ($($curve:ty, $key:ty, $sig:ty, $len:literal;)*) => {$(
+            impl EcdsaVerify<$curve> for FakeBackend {
+                type PublicKey = $key;
+                type Signature = $sig;
+
+                fn verify(
+                    &mut self,
+                    key: &$key,
+                    _digest: <<$curve as Curve>::DigestType as DigestAlgorithm>::Digest,
+                    signature: &$sig,
+                ) -> Result<(), FakeError> {
+                    let [mut x, mut y, mut r, mut s] = [[0u8; $len]; 4];
+                    PublicKey::<$curve>::coordinates(key, &mut x, &mut y);
+                    Signature::<$curve>::coordinates(signature, &mut r, &mut s);
+                    if r == x {
+                        Ok(())
+                    } else {
+                        Err(FakeError::BadSignature)
+                    }
+                }
+            }
+        )*};
+    }
+
+    fake_verify! {
+        P256, P256PublicKey, P256Signature, 32;
+        P384, P384PublicKey, P384Signature, 48;
+    }
+
+    /// Counting DRBG that demands a reseed after `remaining` bytes.
+    fn server() -> CryptoServer<FakeBackend, CountingDrbg, 2> {
+        CryptoServer::new([FakeBackend, FakeBackend], CountingDrbg::new(64))
+    }
+
+    /// Send one request; returns the status and the response payload.
+    fn call<'a>(
+        server: &mut CryptoServer<FakeBackend, CountingDrbg, 2>,
+        op: CryptoOp,
+        algo: CryptoAlgo,
+        arg: u16,
+        payload: &[u8],
+        resp: &'a mut [u8; MAX_BUF_SIZE],
+    ) -> Result<&'a [u8], CryptoError> {
+        let mut req = [0u8; MAX_BUF_SIZE];
+        let hdr = CryptoRequestHeader::new(op, algo, arg, payload.len() as u16);
+        req[..CryptoRequestHeader::SIZE].copy_from_slice(hdr.as_bytes());
+        req[CryptoRequestHeader::SIZE..][..payload.len()].copy_from_slice(payload);
+        let n = server.dispatch(&req[..CryptoRequestHeader::SIZE + payload.len()], resp);
+        let hdr =
+            CryptoResponseHeader::read_from_bytes(&resp[..CryptoResponseHeader::SIZE]).unwrap();
+        assert_eq!(n, CryptoResponseHeader::SIZE + hdr.payload_length());
+        match hdr.error_code() {
+            None => Ok(&resp[CryptoResponseHeader::SIZE..n]),
+            Some(e) => Err(e),
+        }
+    }
+
+    fn begin(server: &mut CryptoServer<FakeBackend, CountingDrbg, 2>, algo: CryptoAlgo) -> u16 {
+        let mut resp = [0u8; MAX_BUF_SIZE];
+        let id = call(server, CryptoOp::HashBegin, algo, 0, &[], &mut resp).unwrap();
+        u16::from_le_bytes([id[0], id[1]])
+    }
+
+    #[test]
+    fn one_shot_hash_per_algorithm() {
+        let mut s = server();
+        let mut resp = [0u8; MAX_BUF_SIZE];
+        for (algo, seed, len) in [
+            (CryptoAlgo::Sha256, 256, 32),
+            (CryptoAlgo::Sha384, 384, 48),
+            (CryptoAlgo::Sha512, 512, 64),
+        ] {
+            let digest = call(&mut s, CryptoOp::Hash, algo, 0, b"abc", &mut resp).unwrap();
+            assert_eq!(digest.len(), len);
+            assert_eq!(&digest[..4], &checksum(seed, b"abc").to_ne_bytes());
+        }
+        assert_eq!(
+            call(
+                &mut s,
+                CryptoOp::Hash,
+                CryptoAlgo::P256,
+                0,
+                b"abc",
+                &mut resp
+            ),
+            Err(CryptoError::UnsupportedAlgorithm)
+        );
+    }
+
+    #[test]
+    fn streaming_session_matches_one_shot() {
+        let mut s = server();
+        let mut resp = [0u8; MAX_BUF_SIZE];
+        let id = begin(&mut s, CryptoAlgo::Sha384);
+        assert_ne!(id, 0);
+        assert_eq!(s.open_sessions(), 1);
+        for chunk in [b"ab".as_slice(), b"c"] {
+            call(
+                &mut s,
+                CryptoOp::HashUpdate,
+                CryptoAlgo::None,
+                id,
+                chunk,
+                &mut resp,
+            )
+            .unwrap();
+        }
+        let mut streamed = [0u8; 48];
+        streamed.copy_from_slice(
+            call(
+                &mut s,
+                CryptoOp::HashFinish,
+                CryptoAlgo::None,
+                id,
+                &[],
+                &mut resp,
+            )
+            .unwrap(),
+        );
+        assert_eq!(s.open_sessions(), 0);
+
+        let one_shot = call(
+            &mut s,
+            CryptoOp::Hash,
+            CryptoAlgo::Sha384,
+            0,
+            b"abc",
+            &mut resp,
+        );
+        assert_eq!(one_shot, Ok(streamed.as_slice()));
+        assert_eq!(
+            call(
+                &mut s,
+                CryptoOp::HashFinish,
+                CryptoAlgo::None,
+                id,
+                &[],
+                &mut resp
+            ),
+            Err(CryptoError::InvalidSession)
+        );
+    }
+
+    #[test]
+    fn sessions_exhaust_the_backend_pool() {
+        let mut s = server();
+        let mut resp = [0u8; MAX_BUF_SIZE];
+        let first = begin(&mut s, CryptoAlgo::Sha256);
+        let second = begin(&mut s, CryptoAlgo::Sha512);
+        assert_ne!(first, second);
+        assert_eq!(
+            call(
+                &mut s,
+                CryptoOp::HashBegin,
+                CryptoAlgo::Sha256,
+                0,
+                &[],
+                &mut resp
+            ),
+            Err(CryptoError::Busy)
+        );
+        assert_eq!(
+            call(
+                &mut s,
+                CryptoOp::Hash,
+                CryptoAlgo::Sha256,
+                0,
+                b"x",
+                &mut resp
+            ),
+            Err(CryptoError::Busy)
+        );
+
+        call(
+            &mut s,
+            CryptoOp::HashCancel,
+            CryptoAlgo::None,
+            first,
+            &[],
+            &mut resp,
+        )
+        .unwrap();
+        assert_eq!(s.open_sessions(), 1);
+        call(
+            &mut s,
+            CryptoOp::Hash,
+            CryptoAlgo::Sha256,
+            0,
+            b"x",
+            &mut resp,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn backend_failure_retires_its_slot() {
+        let mut s = server();
+        let mut resp = [0u8; MAX_BUF_SIZE];
+        let id = begin(&mut s, CryptoAlgo::Sha256);
+        assert_eq!(
+            call(
+                &mut s,
+                CryptoOp::HashUpdate,
+                CryptoAlgo::None,
+                id,
+                POISON,
+                &mut resp
+            ),
+            Err(CryptoError::InternalError)
+        );
+        assert_eq!(s.open_sessions(), 0);
+
+        // One backend left: a session takes it, and the pool is then empty.
+        let _ = begin(&mut s, CryptoAlgo::Sha256);
+        assert_eq!(
+            call(
+                &mut s,
+                CryptoOp::Hash,
+                CryptoAlgo::Sha256,
+                0,
+                b"x",
+                &mut resp
+            ),
+            Err(CryptoError::Busy)
+        );
+    }
+
+    #[test]
+    fn hmac_splits_key_and_data() {
+        let mut s = server();
+        let mut resp = [0u8; MAX_BUF_SIZE];
+        let mac = call(
+            &mut s,
+            CryptoOp::Hmac,
+            CryptoAlgo::Sha512,
+            3,
+            b"keydata",
+            &mut resp,
+        )
+        .unwrap();
+        assert_eq!(mac.len(), 64);
+        let expected = checksum(checksum(0, b"key") ^ 512, b"data");
+        assert_eq!(&mac[..4], &expected.to_ne_bytes());
+
+        assert_eq!(
+            call(
+                &mut s,
+                CryptoOp::Hmac,
+                CryptoAlgo::Sha256,
+                0,
+                b"data",
+                &mut resp
+            ),
+            Err(CryptoError::InvalidKey)
+        );
+        assert_eq!(
+            call(
+                &mut s,
+                CryptoOp::Hmac,
+                CryptoAlgo::Sha256,
+                9,
+                b"data",
+                &mut resp
+            ),
+            Err(CryptoError::BufferTooSmall)
+        );
+    }
+
+    #[test]
+    fn random_and_reseed() {
+        let mut s = server();
+        let mut resp = [0u8; MAX_BUF_SIZE];
+        let bytes = call(
+            &mut s,
+            CryptoOp::Random,
+            CryptoAlgo::None,
+            4,
+            &[],
+            &mut resp,
+        )
+        .unwrap();
+        assert_eq!(bytes, &[0, 1, 2, 3]);
+        assert_eq!(
+            call(
+                &mut s,
+                CryptoOp::Random,
+                CryptoAlgo::None,
+                61,
+                &[],
+                &mut resp
+            ),
+            Err(CryptoError::ReseedRequired)
+        );
+        assert_eq!(
+            call(
+                &mut s,
+                CryptoOp::Reseed,
+                CryptoAlgo::None,
+                0,
+                POISON,
+                &mut resp
+            ),
+            Err(CryptoError::EntropyFailure)
+        );
+        call(
+            &mut s,
+            CryptoOp::Reseed,
+            CryptoAlgo::None,
+            0,
+            b"fresh",
+            &mut resp,
+        )
+        .unwrap();
+        let bytes = call(
+            &mut s,
+            CryptoOp::Random,
+            CryptoAlgo::None,
+            61,
+            &[],
+            &mut resp,
+        )
+        .unwrap();
+        assert_eq!(bytes.len(), 61);
+    }
+
+    #[test]
+    fn ecdsa_verify_decodes_the_payload() {
+        let mut s = server();
+        let mut resp = [0u8; MAX_BUF_SIZE];
+        // Qx ‖ Qy ‖ r ‖ s ‖ digest with r == Qx.
+        let mut payload = [0x11u8; 4 * 48 + 48];
+        assert_eq!(
+            call(
+                &mut s,
+                CryptoOp::EcdsaVerify,
+                CryptoAlgo::P384,
+                0,
+                &payload,
+                &mut resp
+            ),
+            Ok([].as_slice())
+        );
+        payload[2 * 48] = 0x22;
+        assert_eq!(
+            call(
+                &mut s,
+                CryptoOp::EcdsaVerify,
+                CryptoAlgo::P384,
+                0,
+                &payload,
+                &mut resp
+            ),
+            Err(CryptoError::VerificationFailed)
+        );
+        assert_eq!(
+            call(
+                &mut s,
+                CryptoOp::EcdsaVerify,
+                CryptoAlgo::P256,
+                0,
+                &payload,
+                &mut resp
+            ),
+            Err(CryptoError::BufferTooSmall)
+        );
+
+        // s = 0 is out of range and rejected before reaching the backend.
+        let mut payload = [0x11u8; 4 * 32 + 32];
+        payload[3 * 32..4 * 32].fill(0);
+        assert_eq!(
+            call(
+                &mut s,
+                CryptoOp::EcdsaVerify,
+                CryptoAlgo::P256,
+                0,
+                &payload,
+                &mut resp
+            ),
+            Err(CryptoError::VerificationFailed)
+        );
+    }
+
+    #[test]
+    fn malformed_requests_are_answered_with_errors() {
+        let mut s = server();
+        let mut resp = [0u8; MAX_BUF_SIZE];
+
+        assert_eq!(
+            s.dispatch(&[0x01, 0x01], &mut resp),
+            CryptoResponseHeader::SIZE
+        );
+        assert_eq!(resp[0], CryptoError::InvalidOperation as u8);
+
+        let hdr = CryptoRequestHeader::new(CryptoOp::Hash, CryptoAlgo::Sha256, 0, 8);
+        assert_eq!(
+            s.dispatch(hdr.as_bytes(), &mut resp),
+            CryptoResponseHeader::SIZE
+        );
+        assert_eq!(resp[0], CryptoError::BufferTooSmall as u8);
+
+        let mut req = [0u8; CryptoRequestHeader::SIZE];
+        req.copy_from_slice(
+            CryptoRequestHeader::new(CryptoOp::Hash, CryptoAlgo::Sha256, 0, 0).as_bytes(),
+        );
+        req[0] = 0x7F;
+        assert_eq!(s.dispatch(&req, &mut resp), CryptoResponseHeader::SIZE);
+        assert_eq!(resp[0], CryptoError::InvalidOperation as u8);
+
+        assert_eq!(s.dispatch(&req, &mut [0u8; 2]), 0);
+    }
+}
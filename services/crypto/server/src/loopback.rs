@@ -0,0 +1,43 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! In-process transport: client marshalling → [`CryptoServer::dispatch`] →
+//! real HAL backends, no kernel.
+//!
+//! Like the I2C loopback, this is the host-test path (the SPDM crates run
+//! end-to-end against `CryptoClient::new(LoopbackTransport::new(server))`)
+//! and the early-boot path before IPC exists. Swapping it for
+//! `crypto_client_ipc::IpcTransport` is a wiring choice, not a code fork.
+
+use crypto_api::{Transport, TransportError};
+use openprot_hal_blocking::rng::Drbg;
+
+use crate::backend::CryptoBackend;
+use crate::CryptoServer;
+
+/// A [`Transport`] that runs [`CryptoServer::dispatch`] on an owned server.
+pub struct LoopbackTransport<C: CryptoBackend, R, const N: usize> {
+    server: CryptoServer<C, R, N>,
+}
+
+impl<C: CryptoBackend, R: Drbg, const N: usize> LoopbackTransport<C, R, N> {
+    pub const fn new(server: CryptoServer<C, R, N>) -> Self {
+        Self { server }
+    }
+
+    /// The server behind the transport, e.g. to inspect open sessions.
+    pub fn server(&self) -> &CryptoServer<C, R, N> {
+        &self.server
+    }
+}
+
+impl<C: CryptoBackend, R: Drbg, const N: usize> Transport for LoopbackTransport<C, R, N> {
+    fn transact(&mut self, req: &[u8], resp: &mut [u8]) -> Result<usize, TransportError> {
+        // `dispatch` encodes every crypto error into the response header, so
+        // the transport itself only fails on a buffer that cannot hold one.
+        match self.server.dispatch(req, resp) {
+            0 => Err(TransportError::Failed),
+            n => Ok(n),
+        }
+    }
+}
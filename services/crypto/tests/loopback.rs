@@ -0,0 +1,151 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! End-to-end host test, no kernel: `CryptoClient` marshals every request,
+//! `LoopbackTransport` routes it into `CryptoServer::dispatch`, and the
+//! server runs it on the RustCrypto controller and HMAC_DRBG. This exercises
+//! the exact encoders/decoders the IPC client uses.
+
+use crypto_api::{CryptoAlgo, CryptoError, MAX_PAYLOAD_SIZE};
+use crypto_client::{ClientError, CryptoClient};
+use crypto_loopback::CountingEntropy;
+use crypto_server::loopback::LoopbackTransport;
+use crypto_server::CryptoServer;
+use hex_literal::hex;
+use openprot_hal_blocking::digest::Digest;
+use openprot_hal_blocking::ecdsa::{EcdsaKeyGen, EcdsaSign, PublicKey, Signature, P256};
+use openprot_hal_blocking::rng::{Drbg, DrbgRng};
+use platform_rustcrypto_impl::{HmacDrbgSha256, RustCryptoController};
+use rand_core::TryRngCore;
+use zerocopy::FromBytes;
+
+type Transport = LoopbackTransport<RustCryptoController, HmacDrbgSha256<CountingEntropy>, 2>;
+
+fn client() -> CryptoClient<Transport> {
+    let drbg = HmacDrbgSha256::new(CountingEntropy::new(0), b"crypto loopback").unwrap();
+    let server = CryptoServer::new(
+        [RustCryptoController::new(), RustCryptoController::new()],
+        drbg,
+    );
+    CryptoClient::new(LoopbackTransport::new(server))
+}
+
+const SHA256_ABC: [u8; 32] =
+    hex!("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+const SHA384_ABC: [u8; 48] = hex!(
+    "cb00753f45a35e8bb5a03d699ac65007272c32ab0eded1631a8b605a43ff5bed"
+    "8086072ba1e7cc2358baeca134c825a7"
+);
+const SHA512_ABC: [u8; 64] = hex!(
+    "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a"
+    "2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f"
+);
+
+#[test]
+fn one_shot_hashes_match_fips_180_vectors() {
+    let mut client = client();
+    assert_eq!(client.sha256(b"abc").unwrap(), SHA256_ABC);
+    assert_eq!(client.sha384(b"abc").unwrap(), SHA384_ABC);
+    assert_eq!(client.sha512(b"abc").unwrap(), SHA512_ABC);
+}
+
+#[test]
+fn streaming_and_oversized_hashes_agree() {
+    let mut client = client();
+    let mut session = client.sha384_begin().unwrap();
+    session.update(&mut client, b"a").unwrap();
+    session.update(&mut client, b"bc").unwrap();
+    assert_eq!(session.finalize(&mut client).unwrap(), SHA384_ABC);
+
+    // More than one payload: the one-shot call streams through a session,
+    // and the session update is split across several requests.
+    let data = [0x5au8; 3 * MAX_PAYLOAD_SIZE + 7];
+    let one_shot = client.sha512(&data).unwrap();
+    let mut session = client.sha512_begin().unwrap();
+    session.update(&mut client, &data[..10]).unwrap();
+    session.update(&mut client, &data[10..]).unwrap();
+    assert_eq!(session.finalize(&mut client).unwrap(), one_shot);
+}
+
+#[test]
+fn sessions_hold_backends_until_closed() {
+    let mut client = client();
+    let first = client.sha256_begin().unwrap();
+    let second = client.sha256_begin().unwrap();
+    assert_eq!(
+        client.sha256(b"abc"),
+        Err(ClientError::ServerError(CryptoError::Busy))
+    );
+
+    first.cancel(&mut client).unwrap();
+    assert_eq!(client.sha256(b"abc").unwrap(), SHA256_ABC);
+    second.finalize(&mut client).unwrap();
+}
+
+#[test]
+fn hmac_matches_rfc_4231_case_2() {
+    let mut client = client();
+    let mut mac = [0u8; 64];
+    let len = client
+        .hmac(
+            CryptoAlgo::Sha256,
+            b"Jefe",
+            b"what do ya want for nothing?",
+            &mut mac,
+        )
+        .unwrap();
+    assert_eq!(
+        mac[..len],
+        hex!("5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843")
+    );
+}
+
+#[test]
+fn random_bytes_come_from_the_service_drbg() {
+    let mut client = client();
+    let mut ours = HmacDrbgSha256::new(CountingEntropy::new(0), b"crypto loopback").unwrap();
+
+    // Spans two payloads, so the client issues two generate requests.
+    let mut remote = [0u8; MAX_PAYLOAD_SIZE + 16];
+    client.get_random_bytes(&mut remote).unwrap();
+    let mut local = [0u8; MAX_PAYLOAD_SIZE + 16];
+    let (head, tail) = local.split_at_mut(MAX_PAYLOAD_SIZE);
+    ours.generate(head, &[]).unwrap();
+    ours.generate(tail, &[]).unwrap();
+    assert_eq!(remote, local);
+
+    client.reseed(b"more").unwrap();
+    ours.reseed(b"more").unwrap();
+    let mut remote = [0u8; 32];
+    Drbg::generate(&mut client, &mut remote, b"extra").unwrap();
+    let mut local = [0u8; 32];
+    ours.generate(&mut local, b"extra").unwrap();
+    assert_eq!(remote, local);
+}
+
+#[test]
+fn ecdsa_p256_signature_verifies_over_the_wire() {
+    let mut signer = RustCryptoController::new();
+    let mut rng =
+        DrbgRng::new(HmacDrbgSha256::new(CountingEntropy::new(0x80), &[]).unwrap()).unwrap_err();
+    let (private_key, public_key) =
+        EcdsaKeyGen::<P256>::generate_keypair(&mut signer, &mut rng).unwrap();
+    let digest = Digest::<8>::read_from_bytes(&SHA256_ABC).unwrap();
+    let signature = EcdsaSign::<P256>::sign(&mut signer, &private_key, digest, &mut rng).unwrap();
+
+    let (mut qx, mut qy, mut r, mut s) = ([0u8; 32], [0u8; 32], [0u8; 32], [0u8; 32]);
+    PublicKey::<P256>::coordinates(&public_key, &mut qx, &mut qy);
+    Signature::<P256>::coordinates(&signature, &mut r, &mut s);
+
+    let mut client = client();
+    client
+        .ecdsa_verify_p256(&qx, &qy, &r, &s, &SHA256_ABC)
+        .unwrap();
+
+    let mut tampered = SHA256_ABC;
+    tampered[0] ^= 1;
+    assert_eq!(
+        client.ecdsa_verify_p256(&qx, &qy, &r, &s, &tampered),
+        Err(ClientError::ServerError(CryptoError::VerificationFailed))
+    );
+}
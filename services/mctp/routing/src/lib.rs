@@ -0,0 +1,436 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! # MCTP Routing Table
+//!
+//! Maps destination EIDs to the transport binding and physical address that
+//! reach them, so one endpoint can talk to several peers (BMC, host CPU,
+//! downstream devices) over one or more buses.
+//!
+//! ## Entries
+//!
+//! Each [`RouteEntry`] covers a single EID or an inclusive EID range and
+//! names a [`Route`]: the local [`BindingId`] (which bus / transport binding
+//! instance) and the peer's physical address on it.
+//!
+//! - **Static** entries come from board configuration via
+//!   [`RoutingTable::add_static`]. They are authoritative.
+//! - **Learned** entries are recorded from inbound packets via
+//!   [`RoutingTable::learn`]: the packet's source EID is reachable at the
+//!   physical source address it arrived from. Learning never overrides an
+//!   EID covered by a static entry. When the table is full, the oldest
+//!   learned entry is evicted.
+//!
+//! [`RoutingTable::lookup`] picks the most specific entry (smallest EID
+//! range) covering the EID; static entries win ties.
+//!
+//! ## Physical addresses
+//!
+//! The address is a single byte whose meaning depends on the binding: the
+//! 7-bit target address for I2C/SMBus and I3C, or 0 for point-to-point
+//! bindings such as serial.
+
+#![no_std]
+#![warn(missing_docs)]
+
+use core::ops::RangeInclusive;
+
+use heapless::Vec;
+
+/// Maximum number of entries in a [`RoutingTable`].
+pub const MAX_ROUTES: usize = 16;
+
+/// Null EID; never routable.
+const EID_NULL: u8 = 0x00;
+/// Broadcast EID; never routable.
+const EID_BROADCAST: u8 = 0xFF;
+
+/// Identifies one transport binding instance (e.g. one I2C bus) on this
+/// endpoint. Values are assigned by the platform.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct BindingId(pub u8);
+
+/// Where to send packets for an EID: a binding and a physical address on it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Route {
+    /// Binding instance the peer is attached to.
+    pub binding: BindingId,
+    /// Binding-specific physical address of the peer.
+    pub addr: u8,
+}
+
+impl Route {
+    /// Create a route to `addr` on `binding`.
+    pub const fn new(binding: BindingId, addr: u8) -> Self {
+        Self { binding, addr }
+    }
+}
+
+/// How a [`RouteEntry`] was added.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RouteOrigin {
+    /// Added from configuration.
+    Static,
+    /// Learned from an inbound packet.
+    Learned,
+}
+
+/// One routing table entry covering the EIDs `first..=last`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RouteEntry {
+    /// First EID covered.
+    pub first: u8,
+    /// Last EID covered (inclusive).
+    pub last: u8,
+    /// Route for every EID in the range.
+    pub route: Route,
+    /// How the entry was added.
+    pub origin: RouteOrigin,
+}
+
+impl RouteEntry {
+    /// Whether `eid` falls in this entry's range.
+    pub fn contains(&self, eid: u8) -> bool {
+        (self.first..=self.last).contains(&eid)
+    }
+
+    /// Number of EIDs covered.
+    pub fn eid_count(&self) -> usize {
+        usize::from(self.last - self.first) + 1
+    }
+}
+
+/// Errors returned when adding routing table entries.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RouteError {
+    /// The range is empty or includes the null (0x00) or broadcast (0xFF) EID.
+    InvalidEid,
+    /// A static entry for exactly this range already exists.
+    Duplicate,
+    /// The table has no free entry.
+    Full,
+}
+
+/// EID-to-physical-address routing table.
+///
+/// Holds up to [`MAX_ROUTES`] entries. See the [crate docs](crate) for the
+/// static/learned precedence rules.
+#[derive(Clone, Debug, Default)]
+pub struct RoutingTable {
+    /// Entries; learned entries are kept in age order, oldest first.
+    entries: Vec<RouteEntry, MAX_ROUTES>,
+}
+
+impl RoutingTable {
+    /// Create an empty routing table.
+    pub const fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    /// Add a static route for `eids`.
+    ///
+    /// Overlapping ranges are allowed; lookups use the most specific one.
+    /// Learned entries that fall inside `eids` are dropped, as static
+    /// entries are authoritative.
+    pub fn add_static(&mut self, eids: RangeInclusive<u8>, route: Route) -> Result<(), RouteError> {
+        let (first, last) = (*eids.start(), *eids.end());
+        if first > last || first == EID_NULL || last == EID_BROADCAST {
+            return Err(RouteError::InvalidEid);
+        }
+        if self
+            .entries
+            .iter()
+            .any(|e| e.origin == RouteOrigin::Static && e.first == first && e.last == last)
+        {
+            return Err(RouteError::Duplicate);
+        }
+
+        self.entries
+            .retain(|e| e.origin == RouteOrigin::Static || !(first..=last).contains(&e.first));
+        self.entries
+            .push(RouteEntry {
+                first,
+                last,
+                route,
+                origin: RouteOrigin::Static,
+            })
+            .map_err(|_| RouteError::Full)
+    }
+
+    /// Remove the static entry for exactly `eids`.
+    ///
+    /// Returns `true` if an entry was removed.
+    pub fn remove_static(&mut self, eids: RangeInclusive<u8>) -> bool {
+        let before = self.entries.len();
+        self.entries.retain(|e| {
+            !(e.origin == RouteOrigin::Static && e.first == *eids.start() && e.last == *eids.end())
+        });
+        self.entries.len() != before
+    }
+
+    /// Record that `eid` is reachable via `route`.
+    ///
+    /// Call this for each inbound packet with its source EID and the
+    /// physical address it arrived from. Re-learning an EID refreshes its
+    /// entry. Returns `true` if the route was recorded, and `false` if the
+    /// EID is null/broadcast, covered by a static entry, or the table is
+    /// full of static entries.
+    pub fn learn(&mut self, eid: u8, route: Route) -> bool {
+        if eid == EID_NULL || eid == EID_BROADCAST {
+            return false;
+        }
+        if self
+            .entries
+            .iter()
+            .any(|e| e.origin == RouteOrigin::Static && e.contains(eid))
+        {
+            return false;
+        }
+
+        // Refresh: drop the old entry so the new one is the youngest
+        self.entries
+            .retain(|e| !(e.origin == RouteOrigin::Learned && e.first == eid));
+        if self.entries.is_full() {
+            let Some(oldest) = self
+                .entries
+                .iter()
+                .position(|e| e.origin == RouteOrigin::Learned)
+            else {
+                return false;
+            };
+            self.entries.remove(oldest);
+        }
+
+        self.entries
+            .push(RouteEntry {
+                first: eid,
+                last: eid,
+                route,
+                origin: RouteOrigin::Learned,
+            })
+            .is_ok()
+    }
+
+    /// Learn the source EID of an inbound MCTP packet.
+    ///
+    /// `pkt` is a raw MCTP packet (transport header first), as passed to
+    /// `Server::inbound`. See [`learn`](Self::learn).
+    pub fn learn_from_packet(&mut self, pkt: &[u8], route: Route) -> bool {
+        match source_eid(pkt) {
+            Some(eid) => self.learn(eid, route),
+            None => false,
+        }
+    }
+
+    /// Drop all learned entries.
+    pub fn clear_learned(&mut self) {
+        self.entries.retain(|e| e.origin == RouteOrigin::Static);
+    }
+
+    /// Find the route for `eid`.
+    pub fn lookup(&self, eid: u8) -> Option<Route> {
+        self.entries
+            .iter()
+            .filter(|e| e.contains(eid))
+            .min_by_key(|e| (e.eid_count(), e.origin == RouteOrigin::Learned))
+            .map(|e| e.route)
+    }
+
+    /// All entries: static entries in insertion order, then learned entries
+    /// oldest first.
+    pub fn entries(&self) -> impl Iterator<Item = &RouteEntry> {
+        self.entries
+            .iter()
+            .filter(|e| e.origin == RouteOrigin::Static)
+            .chain(
+                self.entries
+                    .iter()
+                    .filter(|e| e.origin == RouteOrigin::Learned),
+            )
+    }
+
+    /// Number of entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the table has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// Destination EID of a raw MCTP packet.
+pub fn dest_eid(pkt: &[u8]) -> Option<u8> {
+    pkt.get(1).copied()
+}
+
+/// Source EID of a raw MCTP packet.
+pub fn source_eid(pkt: &[u8]) -> Option<u8> {
+    pkt.get(2).copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BUS0: BindingId = BindingId(0);
+    const BUS1: BindingId = BindingId(1);
+
+    #[test]
+    fn empty_table_has_no_routes() {
+        let table = RoutingTable::new();
+        assert!(table.is_empty());
+        assert_eq!(table.lookup(8), None);
+    }
+
+    #[test]
+    fn static_single_and_range() {
+        let mut table = RoutingTable::new();
+        table.add_static(8..=8, Route::new(BUS0, 0x20)).unwrap();
+        table
+            .add_static(0x30..=0x3F, Route::new(BUS1, 0x52))
+            .unwrap();
+
+        assert_eq!(table.lookup(8), Some(Route::new(BUS0, 0x20)));
+        assert_eq!(table.lookup(0x30), Some(Route::new(BUS1, 0x52)));
+        assert_eq!(table.lookup(0x3F), Some(Route::new(BUS1, 0x52)));
+        assert_eq!(table.lookup(0x40), None);
+    }
+
+    #[test]
+    fn most_specific_entry_wins() {
+        let mut table = RoutingTable::new();
+        // Default route through a bridge, with one device reached directly
+        table
+            .add_static(0x08..=0xFE, Route::new(BUS0, 0x10))
+            .unwrap();
+        table
+            .add_static(0x42..=0x42, Route::new(BUS1, 0x42))
+            .unwrap();
+
+        assert_eq!(table.lookup(0x41), Some(Route::new(BUS0, 0x10)));
+        assert_eq!(table.lookup(0x42), Some(Route::new(BUS1, 0x42)));
+    }
+
+    #[test]
+    fn invalid_and_duplicate_static() {
+        let mut table = RoutingTable::new();
+        let route = Route::new(BUS0, 0x20);
+        assert_eq!(table.add_static(0..=4, route), Err(RouteError::InvalidEid));
+        assert_eq!(
+            table.add_static(9..=0xFF, route),
+            Err(RouteError::InvalidEid)
+        );
+        #[allow(clippy::reversed_empty_ranges)]
+        let reversed = 9..=8;
+        assert_eq!(
+            table.add_static(reversed, route),
+            Err(RouteError::InvalidEid)
+        );
+
+        table.add_static(8..=9, route).unwrap();
+        assert_eq!(table.add_static(8..=9, route), Err(RouteError::Duplicate));
+        assert!(table.remove_static(8..=9));
+        assert!(!table.remove_static(8..=9));
+        assert_eq!(table.lookup(8), None);
+    }
+
+    #[test]
+    fn learned_routes() {
+        let mut table = RoutingTable::new();
+        assert!(table.learn(0x1D, Route::new(BUS0, 0x30)));
+        assert_eq!(table.lookup(0x1D), Some(Route::new(BUS0, 0x30)));
+
+        // Re-learning moves the peer
+        assert!(table.learn(0x1D, Route::new(BUS1, 0x31)));
+        assert_eq!(table.lookup(0x1D), Some(Route::new(BUS1, 0x31)));
+        assert_eq!(table.len(), 1);
+
+        assert!(!table.learn(0x00, Route::new(BUS0, 0x30)));
+        assert!(!table.learn(0xFF, Route::new(BUS0, 0x30)));
+
+        table.clear_learned();
+        assert!(table.is_empty());
+    }
+
+    #[test]
+    fn static_routes_are_authoritative() {
+        let mut table = RoutingTable::new();
+        assert!(table.learn(0x22, Route::new(BUS1, 0x99)));
+
+        table
+            .add_static(0x20..=0x2F, Route::new(BUS0, 0x10))
+            .unwrap();
+        // The learned entry inside the static range was dropped
+        assert_eq!(table.lookup(0x22), Some(Route::new(BUS0, 0x10)));
+        assert!(!table.learn(0x23, Route::new(BUS1, 0x99)));
+        assert_eq!(table.len(), 1);
+    }
+
+    #[test]
+    fn learning_evicts_oldest_learned() {
+        let mut table = RoutingTable::new();
+        table.add_static(8..=8, Route::new(BUS0, 0x20)).unwrap();
+        for eid in 0..(MAX_ROUTES as u8 - 1) {
+            assert!(table.learn(0x40 + eid, Route::new(BUS1, eid)));
+        }
+        assert_eq!(table.len(), MAX_ROUTES);
+
+        // Refresh the oldest, then overflow: the second oldest goes
+        assert!(table.learn(0x40, Route::new(BUS1, 0x70)));
+        assert!(table.learn(0x60, Route::new(BUS1, 0x71)));
+        assert_eq!(table.len(), MAX_ROUTES);
+        assert_eq!(table.lookup(0x40), Some(Route::new(BUS1, 0x70)));
+        assert_eq!(table.lookup(0x41), None);
+        assert_eq!(table.lookup(0x60), Some(Route::new(BUS1, 0x71)));
+        assert_eq!(table.lookup(8), Some(Route::new(BUS0, 0x20)));
+    }
+
+    #[test]
+    fn static_table_full() {
+        let mut table = RoutingTable::new();
+        for eid in 0..MAX_ROUTES as u8 {
+            table
+                .add_static(0x10 + eid..=0x10 + eid, Route::new(BUS0, eid))
+                .unwrap();
+        }
+        assert_eq!(
+            table.add_static(0x80..=0x80, Route::new(BUS0, 0)),
+            Err(RouteError::Full)
+        );
+        assert!(!table.learn(0x90, Route::new(BUS0, 0)));
+    }
+
+    #[test]
+    fn learn_from_packet_header() {
+        // [hdr ver][dest EID][source EID][flags][msg type]
+        let pkt = [0x01, 0x08, 0x30, 0xC8, 0x05];
+        assert_eq!(dest_eid(&pkt), Some(0x08));
+        assert_eq!(source_eid(&pkt), Some(0x30));
+
+        let mut table = RoutingTable::new();
+        assert!(table.learn_from_packet(&pkt, Route::new(BUS0, 0x42)));
+        assert_eq!(table.lookup(0x30), Some(Route::new(BUS0, 0x42)));
+        assert!(!table.learn_from_packet(&pkt[..2], Route::new(BUS0, 0x42)));
+    }
+
+    #[test]
+    fn entries_lists_static_first() {
+        let mut table = RoutingTable::new();
+        table.learn(0x50, Route::new(BUS1, 0x50));
+        table.add_static(8..=8, Route::new(BUS0, 0x20)).unwrap();
+
+        let mut entries = table.entries();
+        assert_eq!(entries.next().unwrap().origin, RouteOrigin::Static);
+        let learned = entries.next().unwrap();
+        assert_eq!(learned.origin, RouteOrigin::Learned);
+        assert_eq!(
+            (learned.first, learned.last, learned.eid_count()),
+            (0x50, 0x50, 1)
+        );
+        assert!(entries.next().is_none());
+    }
+}
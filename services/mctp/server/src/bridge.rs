@@ -0,0 +1,222 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! MCTP bridging between transport bindings.
+//!
+//! A PRoT often sits between a BMC and downstream devices on separate buses.
+//! In bridge mode, [`Server::inbound_bridged`](crate::Server::inbound_bridged)
+//! consumes packets addressed to the local EID as usual and forwards every
+//! other packet, unchanged and without reassembly, to the binding and
+//! physical address the shared [`RoutingTable`] selects.
+//!
+//! Each binding has its own MTU ([`BindingConfig::mtu`]). A forwarded packet
+//! whose payload exceeds the MTU of the outbound binding is dropped, as
+//! bridges do not re-fragment. Locally originated messages are sent through
+//! [`RoutedSender`], which fragments at the smallest configured MTU so every
+//! packet fits whichever binding it is routed to.
+//!
+//! With the control responder enabled, a bridge also answers Routing
+//! Information Update and Get Routing Table Entries (see
+//! [`control`](crate::control)).
+
+use core::cell::RefCell;
+
+use heapless::LinearMap;
+use mctp::Tag;
+use mctp_lib::fragment::{Fragmenter, SendOutput};
+use mctp_lib::Sender;
+use openprot_mctp_routing::{dest_eid, BindingId, Route, RoutingTable};
+
+/// Maximum number of bindings a [`Bridge`] connects.
+pub const MAX_BINDINGS: usize = 4;
+
+/// Baseline transmission unit every MCTP binding supports (DSP0236).
+pub const BASELINE_MTU: usize = 64;
+
+/// Largest binding MTU used for fragmentation by [`RoutedSender`].
+pub const MAX_BRIDGE_MTU: usize = 255;
+
+/// Size of the MCTP transport header.
+pub(crate) const MCTP_HEADER_SIZE: usize = 4;
+
+/// Physical transport binding identifiers (DSP0239), reported by Get
+/// Routing Table Entries.
+pub mod transport_type {
+    /// MCTP over SMBus/I2C.
+    pub const SMBUS: u8 = 0x01;
+    /// MCTP over serial.
+    pub const SERIAL: u8 = 0x05;
+    /// MCTP over I3C.
+    pub const I3C: u8 = 0x06;
+    /// Vendor defined.
+    pub const VENDOR: u8 = 0xFF;
+}
+
+/// Properties of one binding connected to a [`Bridge`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BindingConfig {
+    /// Largest packet payload (excluding the MCTP header) the binding carries.
+    pub mtu: usize,
+    /// Physical transport binding identifier (see [`transport_type`]).
+    pub transport_type: u8,
+    /// Physical media identifier (DSP0239).
+    pub media_type: u8,
+}
+
+/// Transmits raw MCTP packets on a binding.
+///
+/// Implemented by the platform over its transport drivers. Packets are sent
+/// unchanged; the implementation adds only its own transport framing.
+pub trait PacketForwarder {
+    /// Send `pkt` (MCTP header first) to `route.addr` on `route.binding`.
+    fn forward(&mut self, route: Route, pkt: &[u8]) -> mctp::Result<()>;
+}
+
+/// Why a bridge dropped a packet instead of forwarding it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DropReason {
+    /// No route for the destination EID.
+    NoRoute,
+    /// The route names a binding the bridge does not know.
+    UnknownBinding,
+    /// The route leads back out of the binding the packet arrived on.
+    SameBinding,
+    /// The payload exceeds the MTU of the outbound binding.
+    TooLarge,
+}
+
+/// What [`Server::inbound_bridged`](crate::Server::inbound_bridged) did with
+/// a packet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Delivery {
+    /// Handed to the local router.
+    Local,
+    /// Forwarded unchanged to the route.
+    Forwarded(Route),
+    /// Dropped.
+    Dropped(DropReason),
+}
+
+/// Bridge configuration: the shared routing table and the bindings it
+/// forwards between.
+#[derive(Debug)]
+pub struct Bridge<'r> {
+    /// Routing table, shared with the transport receivers that learn routes.
+    routes: &'r RefCell<RoutingTable>,
+    /// Connected bindings, keyed by [`BindingId`].
+    bindings: LinearMap<u8, BindingConfig, MAX_BINDINGS>,
+}
+
+impl<'r> Bridge<'r> {
+    /// Create a bridge over `routes` with no bindings.
+    pub fn new(routes: &'r RefCell<RoutingTable>) -> Self {
+        Self {
+            routes,
+            bindings: LinearMap::new(),
+        }
+    }
+
+    /// Connect `binding`, or update its configuration.
+    ///
+    /// Returns the rejected configuration if [`MAX_BINDINGS`] bindings are
+    /// already connected.
+    pub fn add_binding(
+        &mut self,
+        binding: BindingId,
+        config: BindingConfig,
+    ) -> Result<(), BindingConfig> {
+        self.bindings
+            .insert(binding.0, config)
+            .map(|_| ())
+            .map_err(|(_, config)| config)
+    }
+
+    /// Configuration of `binding`, if connected.
+    pub fn binding(&self, binding: BindingId) -> Option<&BindingConfig> {
+        self.bindings.get(&binding.0)
+    }
+
+    /// The shared routing table.
+    pub fn routes(&self) -> &'r RefCell<RoutingTable> {
+        self.routes
+    }
+
+    /// Smallest MTU across the connected bindings, or [`BASELINE_MTU`] if
+    /// none are connected.
+    pub fn min_mtu(&self) -> usize {
+        self.bindings
+            .values()
+            .map(|config| config.mtu)
+            .min()
+            .unwrap_or(BASELINE_MTU)
+    }
+
+    /// Select the route for a packet that arrived on `ingress` and is not
+    /// addressed to this endpoint.
+    pub fn select(&self, pkt: &[u8], ingress: BindingId) -> Result<Route, DropReason> {
+        let eid = dest_eid(pkt).ok_or(DropReason::NoRoute)?;
+        let route = self
+            .routes
+            .borrow()
+            .lookup(eid)
+            .ok_or(DropReason::NoRoute)?;
+        if route.binding == ingress {
+            return Err(DropReason::SameBinding);
+        }
+        let config = self
+            .binding(route.binding)
+            .ok_or(DropReason::UnknownBinding)?;
+        if pkt.len().saturating_sub(MCTP_HEADER_SIZE) > config.mtu {
+            return Err(DropReason::TooLarge);
+        }
+        Ok(route)
+    }
+}
+
+/// [`Sender`] for a bridge's own messages.
+///
+/// Fragments at the bridge's smallest binding MTU and sends each packet
+/// through `forwarder` to the route of its destination EID. Sending fails
+/// with `Unreachable` if the EID has no route to a connected binding.
+pub struct RoutedSender<'r, F: PacketForwarder> {
+    bridge: &'r Bridge<'r>,
+    forwarder: F,
+}
+
+impl<'r, F: PacketForwarder> RoutedSender<'r, F> {
+    /// Create a sender that routes through `bridge`.
+    pub fn new(bridge: &'r Bridge<'r>, forwarder: F) -> Self {
+        Self { bridge, forwarder }
+    }
+}
+
+impl<F: PacketForwarder> Sender for RoutedSender<'_, F> {
+    fn send_vectored(
+        &mut self,
+        mut fragmenter: Fragmenter,
+        payload: &[&[u8]],
+    ) -> mctp::Result<Tag> {
+        loop {
+            let mut buf = [0u8; MAX_BRIDGE_MTU + MCTP_HEADER_SIZE];
+            match fragmenter.fragment_vectored(payload, &mut buf) {
+                SendOutput::Packet(p) => {
+                    let eid = dest_eid(p).ok_or(mctp::Error::InvalidInput)?;
+                    let route = self
+                        .bridge
+                        .routes
+                        .borrow()
+                        .lookup(eid)
+                        .filter(|route| self.bridge.binding(route.binding).is_some())
+                        .ok_or(mctp::Error::Unreachable)?;
+                    self.forwarder.forward(route, p)?;
+                }
+                SendOutput::Complete { tag, .. } => return Ok(tag),
+                SendOutput::Error { err, .. } => return Err(err),
+            }
+        }
+    }
+
+    fn get_mtu(&self) -> usize {
+        self.bridge.min_mtu().min(MAX_BRIDGE_MTU)
+    }
+}
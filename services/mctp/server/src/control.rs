@@ -0,0 +1,473 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! MCTP control protocol responder (message type 0x00, DSP0236).
+//!
+//! [`ControlResponder`] answers the control commands that the OpenPRoT MCTP
+//! specification marks as required:
+//!
+//! - Set Endpoint ID
+//! - Get Endpoint ID
+//! - Get MCTP Version Support
+//! - Get Message Type Support
+//! - Get Vendor Defined Message Support
+//!
+//! In bridge mode (see [`bridge`](crate::bridge)) it also answers the bridge
+//! commands:
+//!
+//! - Routing Information Update
+//! - Get Routing Table Entries
+//!
+//! Any other command, including the transport-specific range `0xF0..=0xFF`,
+//! is answered with `ERROR_UNSUPPORTED_CMD`.
+//!
+//! The responder only builds responses. The [`Server`](crate::Server) feeds
+//! it control requests from the bus, sends the responses, and applies any
+//! EID assignment the responder accepted. See
+//! [`Server::enable_control`](crate::Server::enable_control).
+
+use heapless::Vec;
+use openprot_mctp_routing::{BindingId, Route, RouteOrigin, MAX_ROUTES};
+
+use crate::bridge::Bridge;
+
+/// MCTP message type of control messages.
+pub const MCTP_TYPE_CONTROL: u8 = 0x00;
+
+/// Size of the control message header (Rq/D/Instance ID, command code).
+pub const CONTROL_HEADER_SIZE: usize = 2;
+
+/// Largest control request the server copies out of the router.
+///
+/// Longer requests are truncated. The longest supported request, a Routing
+/// Information Update, fits 15 entries.
+pub const MAX_CONTROL_REQUEST: usize = 64;
+
+/// Largest control response the responder builds.
+pub const MAX_CONTROL_RESPONSE: usize = 32;
+
+/// Maximum number of vendor ID sets reported by Get Vendor Defined Message
+/// Support.
+pub const MAX_VENDOR_SETS: usize = 4;
+
+/// MCTP specification version reported for the base specification and the
+/// control protocol (1.3.1, BCD-encoded with the `0xF` "no field" nibble).
+pub const MCTP_VERSION_1_3_1: [u8; 4] = [0xF1, 0xF3, 0xF1, 0x00];
+
+/// Control command codes.
+pub mod command {
+    /// Set Endpoint ID.
+    pub const SET_ENDPOINT_ID: u8 = 0x01;
+    /// Get Endpoint ID.
+    pub const GET_ENDPOINT_ID: u8 = 0x02;
+    /// Get MCTP Version Support.
+    pub const GET_VERSION_SUPPORT: u8 = 0x04;
+    /// Get Message Type Support.
+    pub const GET_MESSAGE_TYPE_SUPPORT: u8 = 0x05;
+    /// Get Vendor Defined Message Support.
+    pub const GET_VENDOR_MESSAGE_SUPPORT: u8 = 0x06;
+    /// Routing Information Update (bridges only).
+    pub const ROUTING_INFORMATION_UPDATE: u8 = 0x09;
+    /// Get Routing Table Entries (bridges only).
+    pub const GET_ROUTING_TABLE_ENTRIES: u8 = 0x0A;
+}
+
+/// Control completion codes.
+pub mod completion {
+    /// Request completed normally.
+    pub const SUCCESS: u8 = 0x00;
+    /// Generic failure.
+    pub const ERROR: u8 = 0x01;
+    /// Invalid request data.
+    pub const ERROR_INVALID_DATA: u8 = 0x02;
+    /// Request data is too short.
+    pub const ERROR_INVALID_LENGTH: u8 = 0x03;
+    /// Command is not supported.
+    pub const ERROR_UNSUPPORTED_CMD: u8 = 0x05;
+    /// Get MCTP Version Support: message type number not supported.
+    pub const MESSAGE_TYPE_NOT_SUPPORTED: u8 = 0x80;
+}
+
+/// Request bit in the first control header byte.
+const RQ_BIT: u8 = 0x80;
+/// Instance ID field in the first control header byte.
+const INSTANCE_ID_MASK: u8 = 0x1F;
+/// "Get version of the base specification" message type number.
+const VERSION_BASE_SPEC: u8 = 0xFF;
+/// Vendor ID set selector meaning "no more sets".
+const LAST_VENDOR_SET: u8 = 0xFF;
+
+/// Set Endpoint ID operation field.
+const SET_EID_OPERATION_MASK: u8 = 0x03;
+/// Set Endpoint ID "Set EID" operation.
+const SET_EID_OP_SET: u8 = 0x00;
+/// Set Endpoint ID "Force EID" operation.
+const SET_EID_OP_FORCE: u8 = 0x01;
+/// Set Endpoint ID response: EID assignment rejected.
+const SET_EID_REJECTED: u8 = 0x10;
+
+/// Size of one Routing Information Update entry with a one-byte physical
+/// address: entry type, EID range size, starting EID, address.
+const ROUTING_UPDATE_ENTRY_SIZE: usize = 4;
+/// Size of one Get Routing Table Entries entry with a one-byte physical
+/// address.
+const ROUTING_TABLE_ENTRY_SIZE: usize = 7;
+/// Entry handle meaning "no more entries".
+const LAST_ROUTING_ENTRY: u8 = 0xFF;
+/// Routing table entry type: single endpoint.
+const ENTRY_TYPE_ENDPOINT: u8 = 0x00;
+/// Routing table entry type: EID range of a bridge and its downstream
+/// endpoints.
+const ENTRY_TYPE_BRIDGE_RANGE: u8 = 0x40;
+/// Routing table entry flag: statically configured.
+const ENTRY_STATIC: u8 = 0x20;
+/// Routing table entry port number field.
+const ENTRY_PORT_MASK: u8 = 0x1F;
+
+/// Vendor identifier reported by Get Vendor Defined Message Support.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VendorId {
+    /// PCI vendor ID (format 0x00), used with message type 0x7E.
+    Pci(u16),
+    /// IANA enterprise number (format 0x01), used with message type 0x7F.
+    Iana(u32),
+}
+
+/// One vendor ID set: a vendor and the command set version it supports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VendorSupport {
+    /// Vendor identifier.
+    pub vendor: VendorId,
+    /// Vendor-defined command set type/version.
+    pub command_set: u16,
+}
+
+/// Response built by [`ControlResponder::respond`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ControlResponse {
+    /// Length of the response written to the output buffer.
+    pub len: usize,
+    /// EID the caller must assign once the response has been sent.
+    pub new_eid: Option<u8>,
+}
+
+/// MCTP control protocol responder.
+///
+/// Tracks the bus owner that assigned the local EID and the vendor-defined
+/// message sets to report. Everything else (the current EID and the message
+/// types with a registered listener) is passed in per request.
+#[derive(Debug, Clone, Default)]
+pub struct ControlResponder {
+    /// EID of the bus owner whose assignment was last accepted.
+    bus_owner: Option<u8>,
+    /// Vendor ID sets, reported in order.
+    vendor_sets: Vec<VendorSupport, MAX_VENDOR_SETS>,
+}
+
+impl ControlResponder {
+    /// Create a responder with no bus owner and no vendor ID sets.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Report `support` in Get Vendor Defined Message Support responses.
+    ///
+    /// Returns the rejected entry if [`MAX_VENDOR_SETS`] are already
+    /// registered.
+    pub fn add_vendor_support(&mut self, support: VendorSupport) -> Result<(), VendorSupport> {
+        self.vendor_sets.push(support)
+    }
+
+    /// EID of the bus owner whose EID assignment was last accepted.
+    pub fn bus_owner(&self) -> Option<u8> {
+        self.bus_owner
+    }
+
+    /// Build the response to one control message.
+    ///
+    /// * `request` - control message payload, starting at the control header.
+    /// * `response` - output buffer for the response payload.
+    /// * `source_eid` - EID the request came from.
+    /// * `own_eid` - currently configured local EID.
+    /// * `message_types` - message types with a registered listener.
+    /// * `bridge` - in bridge mode, the bridge and the binding the request
+    ///   arrived on; the bridge commands are unsupported otherwise.
+    ///
+    /// Returns `None` when nothing should be sent: the message is a response
+    /// or datagram, is shorter than the control header, or the response does
+    /// not fit in `response`.
+    ///
+    /// A Set Endpoint ID that is accepted reports the new EID in the response
+    /// and returns it in [`ControlResponse::new_eid`]. The caller sends the
+    /// response from the current EID first, so the bus owner can match it to
+    /// its request, and then assigns the new EID.
+    pub fn respond(
+        &mut self,
+        request: &[u8],
+        response: &mut [u8],
+        source_eid: u8,
+        own_eid: u8,
+        message_types: &[u8],
+        bridge: Option<(&Bridge<'_>, BindingId)>,
+    ) -> Option<ControlResponse> {
+        let (&flags, rest) = request.split_first()?;
+        let (&cmd, data) = rest.split_first()?;
+        if flags & RQ_BIT == 0 {
+            return None;
+        }
+
+        let mut out = ResponseWriter::new(response);
+        out.push(flags & INSTANCE_ID_MASK)?;
+        out.push(cmd)?;
+
+        let mut new_eid = None;
+        match cmd {
+            command::SET_ENDPOINT_ID => {
+                new_eid = self.set_endpoint_id(&mut out, data, source_eid, own_eid)?;
+            }
+            command::GET_ENDPOINT_ID => {
+                // Simple endpoint, dynamic EID; no medium-specific data
+                out.extend(&[completion::SUCCESS, own_eid, 0x00, 0x00])?;
+            }
+            command::GET_VERSION_SUPPORT => match data.first() {
+                None => out.push(completion::ERROR_INVALID_LENGTH)?,
+                Some(&(VERSION_BASE_SPEC | MCTP_TYPE_CONTROL)) => {
+                    out.extend(&[completion::SUCCESS, 1])?;
+                    out.extend(&MCTP_VERSION_1_3_1)?;
+                }
+                // Other message types report their own versions in-protocol
+                Some(_) => out.push(completion::MESSAGE_TYPE_NOT_SUPPORTED)?,
+            },
+            command::GET_MESSAGE_TYPE_SUPPORT => {
+                let mut types: Vec<u8, 256> = Vec::new();
+                for &typ in message_types {
+                    if typ != MCTP_TYPE_CONTROL && !types.contains(&typ) {
+                        types.push(typ).ok()?;
+                    }
+                }
+                types.sort_unstable();
+                out.extend(&[completion::SUCCESS, types.len() as u8])?;
+                out.extend(&types)?;
+            }
+            command::GET_VENDOR_MESSAGE_SUPPORT => {
+                self.vendor_message_support(&mut out, data)?;
+            }
+            command::ROUTING_INFORMATION_UPDATE if bridge.is_some() => {
+                let (bridge, ingress) = bridge?;
+                self.routing_information_update(&mut out, data, source_eid, bridge, ingress)?;
+            }
+            command::GET_ROUTING_TABLE_ENTRIES if bridge.is_some() => {
+                let (bridge, _) = bridge?;
+                get_routing_table_entries(&mut out, data, bridge)?;
+            }
+            _ => out.push(completion::ERROR_UNSUPPORTED_CMD)?,
+        }
+
+        Some(ControlResponse {
+            len: out.len,
+            new_eid,
+        })
+    }
+
+    /// Set Endpoint ID. Returns the EID to assign, if any.
+    fn set_endpoint_id(
+        &mut self,
+        out: &mut ResponseWriter<'_>,
+        data: &[u8],
+        source_eid: u8,
+        own_eid: u8,
+    ) -> Option<Option<u8>> {
+        let [operation, eid, ..] = *data else {
+            out.push(completion::ERROR_INVALID_LENGTH)?;
+            return Some(None);
+        };
+
+        // 0x00 (null) and 0xFF (broadcast) cannot be assigned. Reset EID
+        // needs a static EID and Set Discovered Flag is PCIe-only, neither of
+        // which this endpoint has.
+        let operation = operation & SET_EID_OPERATION_MASK;
+        let valid_op = operation == SET_EID_OP_SET || operation == SET_EID_OP_FORCE;
+        if eid == 0x00 || eid == 0xFF || !valid_op {
+            out.push(completion::ERROR_INVALID_DATA)?;
+            return Some(None);
+        }
+
+        // A plain Set from a different bus owner does not override an
+        // existing assignment; Force always does.
+        let rejected =
+            operation == SET_EID_OP_SET && self.bus_owner.is_some_and(|owner| owner != source_eid);
+        if rejected {
+            out.extend(&[completion::SUCCESS, SET_EID_REJECTED, own_eid, 0x00])?;
+            return Some(None);
+        }
+
+        out.extend(&[completion::SUCCESS, 0x00, eid, 0x00])?;
+        self.bus_owner = Some(source_eid);
+        Some(Some(eid))
+    }
+
+    /// Get Vendor Defined Message Support.
+    fn vendor_message_support(&self, out: &mut ResponseWriter<'_>, data: &[u8]) -> Option<()> {
+        let Some(&selector) = data.first() else {
+            return out.push(completion::ERROR_INVALID_LENGTH);
+        };
+        let Some(set) = self.vendor_sets.get(selector as usize) else {
+            return out.push(completion::ERROR_INVALID_DATA);
+        };
+
+        let next = selector as usize + 1;
+        let next = if next < self.vendor_sets.len() {
+            next as u8
+        } else {
+            LAST_VENDOR_SET
+        };
+        out.extend(&[completion::SUCCESS, next])?;
+        match set.vendor {
+            VendorId::Pci(id) => {
+                out.push(0x00)?;
+                out.extend(&id.to_be_bytes())?;
+            }
+            VendorId::Iana(id) => {
+                out.push(0x01)?;
+                out.extend(&id.to_be_bytes())?;
+            }
+        }
+        out.extend(&set.command_set.to_be_bytes())
+    }
+
+    /// Routing Information Update.
+    ///
+    /// Each entry makes an EID range reachable through a physical address on
+    /// the binding the update arrived on. Entries replace any static entry
+    /// for the same range. The update is validated and staged as a whole, so
+    /// a rejected update leaves the routing table unchanged.
+    fn routing_information_update(
+        &self,
+        out: &mut ResponseWriter<'_>,
+        data: &[u8],
+        source_eid: u8,
+        bridge: &Bridge<'_>,
+        ingress: BindingId,
+    ) -> Option<()> {
+        // Only the bus owner that assigned our EID may update routes
+        if self.bus_owner.is_some_and(|owner| owner != source_eid) {
+            return out.push(completion::ERROR);
+        }
+        let Some((&count, entries)) = data.split_first() else {
+            return out.push(completion::ERROR_INVALID_LENGTH);
+        };
+        let Some(entries) = entries.get(..usize::from(count) * ROUTING_UPDATE_ENTRY_SIZE) else {
+            return out.push(completion::ERROR_INVALID_LENGTH);
+        };
+
+        let mut ranges: Vec<(u8, u8, u8), MAX_ROUTES> = Vec::new();
+        for entry in entries.chunks_exact(ROUTING_UPDATE_ENTRY_SIZE) {
+            let [_entry_type, size, first, addr] = *entry else {
+                return out.push(completion::ERROR_INVALID_LENGTH);
+            };
+            let last = size.checked_sub(1).and_then(|span| first.checked_add(span));
+            match last {
+                Some(last) if first != 0x00 && last != 0xFF => {
+                    if ranges.push((first, last, addr)).is_err() {
+                        return out.push(completion::ERROR);
+                    }
+                }
+                _ => return out.push(completion::ERROR_INVALID_DATA),
+            }
+        }
+
+        let mut routes = bridge.routes().borrow().clone();
+        for &(first, last, addr) in &ranges {
+            routes.remove_static(first..=last);
+            if routes
+                .add_static(first..=last, Route::new(ingress, addr))
+                .is_err()
+            {
+                return out.push(completion::ERROR);
+            }
+        }
+        *bridge.routes().borrow_mut() = routes;
+        out.push(completion::SUCCESS)
+    }
+}
+
+/// Get Routing Table Entries, starting at the entry handle in `data`.
+fn get_routing_table_entries(
+    out: &mut ResponseWriter<'_>,
+    data: &[u8],
+    bridge: &Bridge<'_>,
+) -> Option<()> {
+    let Some(&handle) = data.first() else {
+        return out.push(completion::ERROR_INVALID_LENGTH);
+    };
+    let routes = bridge.routes().borrow();
+    let total = routes.len();
+    if usize::from(handle) >= total.max(1) {
+        return out.push(completion::ERROR_INVALID_DATA);
+    }
+
+    // As many entries as fit after the completion code, next handle and count
+    let room = out.remaining().saturating_sub(3) / ROUTING_TABLE_ENTRY_SIZE;
+    let count = room.min(total - usize::from(handle));
+    let next = usize::from(handle) + count;
+    let next = if next < total {
+        next as u8
+    } else {
+        LAST_ROUTING_ENTRY
+    };
+    out.extend(&[completion::SUCCESS, next, count as u8])?;
+
+    for entry in routes.entries().skip(usize::from(handle)).take(count) {
+        let mut entry_type = if entry.eid_count() == 1 {
+            ENTRY_TYPE_ENDPOINT
+        } else {
+            ENTRY_TYPE_BRIDGE_RANGE
+        };
+        if entry.origin == RouteOrigin::Static {
+            entry_type |= ENTRY_STATIC;
+        }
+        entry_type |= entry.route.binding.0 & ENTRY_PORT_MASK;
+        let (transport, media) = bridge
+            .binding(entry.route.binding)
+            .map_or((0x00, 0x00), |config| {
+                (config.transport_type, config.media_type)
+            });
+        out.extend(&[
+            entry.eid_count() as u8,
+            entry.first,
+            entry_type,
+            transport,
+            media,
+            1,
+            entry.route.addr,
+        ])?;
+    }
+    Some(())
+}
+
+/// Bounds-checked writer for response payloads.
+struct ResponseWriter<'a> {
+    buf: &'a mut [u8],
+    len: usize,
+}
+
+impl<'a> ResponseWriter<'a> {
+    fn new(buf: &'a mut [u8]) -> Self {
+        Self { buf, len: 0 }
+    }
+
+    fn remaining(&self) -> usize {
+        self.buf.len() - self.len
+    }
+
+    fn push(&mut self, byte: u8) -> Option<()> {
+        self.extend(&[byte])
+    }
+
+    fn extend(&mut self, bytes: &[u8]) -> Option<()> {
+        let end = self.len.checked_add(bytes.len())?;
+        self.buf.get_mut(self.len..end)?.copy_from_slice(bytes);
+        self.len = end;
+        Some(())
+    }
+}
@@ -10,6 +10,8 @@
 //! - Inbound message routing to registered listeners
 //! - Outbound message fragmentation and sending
 //! - Timeout management for pending receive calls
+//! - Optional MCTP control protocol responder (see [`control`])
+//! - Optional bridging between transport bindings (see [`bridge`])
 //!
 //! ## Transport Bindings
 //!
@@ -28,8 +30,12 @@
 #![no_std]
 #![warn(missing_docs)]
 
+pub mod bridge;
+pub mod control;
 pub mod dispatch;
 mod server;
 
+pub use bridge::{Bridge, Delivery};
+pub use control::ControlResponder;
 pub use mctp_lib::Sender;
 pub use server::{RecvResult, Server, ServerConfig};
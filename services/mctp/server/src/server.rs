@@ -13,12 +13,23 @@ use heapless::LinearMap;
 use mctp::{Eid, MsgIC, MsgType, Tag, TagValue};
 use mctp_lib::{AppCookie, Router, Sender};
 use openprot_mctp_api::{Handle, MctpError, RecvMetadata, ResponseCode};
+use openprot_mctp_routing::{dest_eid, BindingId};
+
+use crate::bridge::{Bridge, Delivery, PacketForwarder, MCTP_HEADER_SIZE};
+use crate::control::{
+    ControlResponder, MAX_CONTROL_REQUEST, MAX_CONTROL_RESPONSE, MCTP_TYPE_CONTROL,
+};
 
 /// Maximum payload size in bytes.
 // TODO: Use configuration from mctp-lib (mctp-estack)
 //       see https://github.com/OpenPRoT/mctp-lib/issues/4
 const MAX_PAYLOAD: usize = 1023;
 
+/// Null EID, used by endpoints that have not been assigned an EID yet.
+const EID_NULL: u8 = 0x00;
+/// Broadcast EID.
+const EID_BROADCAST: u8 = 0xFF;
+
 /// Configuration constants for the MCTP server.
 pub struct ServerConfig;
 
@@ -58,6 +69,10 @@ pub struct Server<S: Sender, const OUTSTANDING: usize> {
     /// Maps the handle to a deadline. The platform layer is responsible
     /// for storing any additional per-recv state (e.g., reply channels).
     outstanding: LinearMap<u32, PendingRecv, OUTSTANDING>,
+    /// Message type of each listener registered through [`listener`](Self::listener).
+    listener_types: LinearMap<u32, u8, { ServerConfig::MAX_LISTENERS }>,
+    /// Control protocol responder and its internal listener, once enabled.
+    control: Option<(Handle, ControlResponder)>,
 }
 
 impl<S: Sender, const OUTSTANDING: usize> Server<S, OUTSTANDING> {
@@ -67,7 +82,38 @@ impl<S: Sender, const OUTSTANDING: usize> Server<S, OUTSTANDING> {
         Self {
             stack,
             outstanding: LinearMap::new(),
+            listener_types: LinearMap::new(),
+            control: None,
+        }
+    }
+
+    /// Answer MCTP control messages (type 0x00) from the bus.
+    ///
+    /// Registers an internal listener for the control message type, which
+    /// takes one of the [`ServerConfig::MAX_LISTENERS`] slots. From then on,
+    /// [`inbound`](Self::inbound) hands every control request to `responder`
+    /// and sends its response. Get Message Type Support reports the message
+    /// types of the listeners registered at the time of the request, and an
+    /// accepted Set Endpoint ID updates the local EID after the response
+    /// has been sent.
+    ///
+    /// Returns `AddrInUse` if a client already listens for control messages
+    /// or the responder is already enabled.
+    pub fn enable_control(&mut self, responder: ControlResponder) -> Result<(), MctpError> {
+        if self.control.is_some() {
+            return Err(MctpError::from_code(ResponseCode::AddrInUse));
         }
+        let cookie = self
+            .stack
+            .listener(MsgType(MCTP_TYPE_CONTROL))
+            .map_err(mctp_error_to_server_error)?;
+        self.control = Some((Handle(cookie.0 as u32), responder));
+        Ok(())
+    }
+
+    /// The control protocol responder, if enabled.
+    pub fn control(&self) -> Option<&ControlResponder> {
+        self.control.as_ref().map(|(_, responder)| responder)
     }
 
     /// Allocate a request handle for sending messages to the given EID.
@@ -81,7 +127,12 @@ impl<S: Sender, const OUTSTANDING: usize> Server<S, OUTSTANDING> {
     /// Register a listener for incoming messages of the given type.
     pub fn listener(&mut self, typ: u8) -> Result<Handle, MctpError> {
         match self.stack.listener(MsgType(typ)) {
-            Ok(cookie) => Ok(Handle(cookie.0 as u32)),
+            Ok(cookie) => {
+                let handle = Handle(cookie.0 as u32);
+                // Bounded by the router's own listener table
+                let _ = self.listener_types.insert(handle.0, typ);
+                Ok(handle)
+            }
             Err(e) => Err(mctp_error_to_server_error(e)),
         }
     }
@@ -241,8 +292,12 @@ impl<S: Sender, const OUTSTANDING: usize> Server<S, OUTSTANDING> {
     /// Unbind a handle previously allocated by `req` or `listener`.
     pub fn unbind(&mut self, handle: Handle) -> Result<(), MctpError> {
         let cookie = AppCookie(handle.0 as usize);
+        if matches!(self.control, Some((control, _)) if control == handle) {
+            return Err(MctpError::from_code(ResponseCode::BadArgument));
+        }
         let _ = self.stack.unbind(cookie);
         self.outstanding.remove(&handle.0);
+        self.listener_types.remove(&handle.0);
         Ok(())
     }
 
@@ -251,8 +306,116 @@ impl<S: Sender, const OUTSTANDING: usize> Server<S, OUTSTANDING> {
     /// The platform layer calls this when data arrives from a transport
     /// binding. The packet should be a raw MCTP packet without transport
     /// headers (the transport binding strips those).
+    ///
+    /// If the control responder is enabled, any completed control request
+    /// is answered before this returns.
     pub fn inbound(&mut self, pkt: &[u8]) -> Result<(), MctpError> {
-        self.stack.inbound(pkt).map_err(mctp_error_to_server_error)
+        self.stack
+            .inbound(pkt)
+            .map_err(mctp_error_to_server_error)?;
+        self.service_control(None);
+        Ok(())
+    }
+
+    /// Feed an inbound MCTP packet that arrived on `ingress`, in bridge mode.
+    ///
+    /// Packets addressed to the local EID, the null EID or the broadcast EID
+    /// go to the router as with [`inbound`](Self::inbound). Any other packet
+    /// is forwarded unchanged through `forwarder` to the route `bridge`
+    /// selects, or dropped if there is none (see
+    /// [`DropReason`](crate::bridge::DropReason)). Forwarding does not
+    /// reassemble: each packet of a message is forwarded as it arrives.
+    ///
+    /// Control requests handled here may also use the bridge commands, which
+    /// update or report `bridge`'s routing table.
+    pub fn inbound_bridged<F: PacketForwarder>(
+        &mut self,
+        pkt: &[u8],
+        ingress: BindingId,
+        bridge: &Bridge<'_>,
+        forwarder: &mut F,
+    ) -> Result<Delivery, MctpError> {
+        let own_eid = self.get_eid();
+        let local = match dest_eid(pkt) {
+            Some(eid) if pkt.len() >= MCTP_HEADER_SIZE => {
+                eid == own_eid || eid == EID_NULL || eid == EID_BROADCAST
+            }
+            // Too short to route; the router rejects it
+            _ => true,
+        };
+        if local {
+            self.stack
+                .inbound(pkt)
+                .map_err(mctp_error_to_server_error)?;
+            self.service_control(Some((bridge, ingress)));
+            return Ok(Delivery::Local);
+        }
+
+        match bridge.select(pkt, ingress) {
+            Ok(route) => {
+                forwarder
+                    .forward(route, pkt)
+                    .map_err(mctp_error_to_server_error)?;
+                Ok(Delivery::Forwarded(route))
+            }
+            Err(reason) => Ok(Delivery::Dropped(reason)),
+        }
+    }
+
+    /// Answer queued control requests.
+    ///
+    /// Control responses are best effort: a failed send is dropped, as the
+    /// requester will retry.
+    fn service_control(&mut self, bridge: Option<(&Bridge<'_>, BindingId)>) {
+        let Some((handle, responder)) = self.control.as_mut() else {
+            return;
+        };
+        let cookie = handle.0 as usize;
+
+        loop {
+            let mut request = [0u8; MAX_CONTROL_REQUEST];
+            let (source, tag, len) = match self.stack.recv(AppCookie(cookie)) {
+                // Control messages never carry an integrity check
+                Some(msg) if msg.ic.0 => continue,
+                Some(msg) => {
+                    let len = msg.payload.len().min(MAX_CONTROL_REQUEST);
+                    request[..len].copy_from_slice(&msg.payload[..len]);
+                    (msg.source.0, msg.tag.tag().0, len)
+                }
+                None => return,
+            };
+
+            let mut message_types: heapless::Vec<u8, { ServerConfig::MAX_LISTENERS }> =
+                heapless::Vec::new();
+            for (_, typ) in self.listener_types.iter() {
+                let _ = message_types.push(*typ);
+            }
+
+            let mut response = [0u8; MAX_CONTROL_RESPONSE];
+            let Some(reply) = responder.respond(
+                &request[..len],
+                &mut response,
+                source,
+                self.stack.get_eid().0,
+                &message_types,
+                bridge,
+            ) else {
+                continue;
+            };
+
+            let _ = self.stack.send(
+                Some(Eid(source)),
+                MsgType(MCTP_TYPE_CONTROL),
+                Some(Tag::Unowned(TagValue(tag))),
+                MsgIC(false),
+                // Responses need no handle, use 255 as dummy
+                AppCookie(255),
+                &response[..reply.len],
+            );
+            if let Some(eid) = reply.new_eid {
+                let _ = self.stack.set_eid(Eid(eid));
+            }
+        }
     }
 }
 
@@ -0,0 +1,636 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! MCTP bridging tests.
+//!
+//! A bridge server (EID 8) connects a BMC (EID 0x1D) on one binding to a
+//! device (EID 0x42) on another. The endpoints use mock senders that capture
+//! their packets; the bridge sends through a `RoutedSender` and forwards
+//! through a mock `PacketForwarder` that records each packet with its route.
+
+mod common;
+
+use std::cell::RefCell;
+
+use mctp::Eid;
+use openprot_mctp_routing::{BindingId, Route, RoutingTable, MAX_ROUTES};
+use openprot_mctp_server::bridge::{
+    transport_type, BindingConfig, Bridge, Delivery, DropReason, PacketForwarder, RoutedSender,
+};
+use openprot_mctp_server::control::{command, completion, ControlResponder, MCTP_TYPE_CONTROL};
+use openprot_mctp_server::{Sender, Server};
+
+use common::SmallMtuBufferSender;
+
+const BUS_BMC: BindingId = BindingId(0);
+const BUS_DEV: BindingId = BindingId(1);
+const ADDR_BMC: u8 = 0x20;
+const ADDR_DEV: u8 = 0x30;
+const BRIDGE_EID: u8 = 8;
+const BMC_EID: u8 = 0x1D;
+const DEV_EID: u8 = 0x42;
+const MSG_TYPE: u8 = 0x05; // SPDM
+const MTU: usize = 64;
+
+/// Control header byte for a request with instance ID 3.
+const RQ: u8 = 0x80 | 3;
+/// Control header byte of the matching response.
+const RSP: u8 = 3;
+
+type Packets = RefCell<Vec<Vec<u8>>>;
+type Forwarded = RefCell<Vec<(Route, Vec<u8>)>>;
+
+// ---------------------------------------------------------------------------
+// Fixtures
+// ---------------------------------------------------------------------------
+
+/// A mock [`PacketForwarder`] that records every packet with its route.
+struct CaptureForwarder<'a> {
+    out: &'a Forwarded,
+}
+
+impl PacketForwarder for CaptureForwarder<'_> {
+    fn forward(&mut self, route: Route, pkt: &[u8]) -> mctp::Result<()> {
+        self.out.borrow_mut().push((route, pkt.to_vec()));
+        Ok(())
+    }
+}
+
+/// Routing table with the BMC and the device on their own bindings.
+fn routes() -> RefCell<RoutingTable> {
+    let mut routes = RoutingTable::new();
+    routes
+        .add_static(BMC_EID..=BMC_EID, Route::new(BUS_BMC, ADDR_BMC))
+        .unwrap();
+    routes
+        .add_static(DEV_EID..=DEV_EID, Route::new(BUS_DEV, ADDR_DEV))
+        .unwrap();
+    RefCell::new(routes)
+}
+
+/// A bridge over `routes` connecting two SMBus bindings with the given MTUs.
+fn bridge(routes: &RefCell<RoutingTable>, bmc_mtu: usize, dev_mtu: usize) -> Bridge<'_> {
+    let mut bridge = Bridge::new(routes);
+    for (binding, mtu) in [(BUS_BMC, bmc_mtu), (BUS_DEV, dev_mtu)] {
+        bridge
+            .add_binding(
+                binding,
+                BindingConfig {
+                    mtu,
+                    transport_type: transport_type::SMBUS,
+                    media_type: 0x01,
+                },
+            )
+            .unwrap();
+    }
+    bridge
+}
+
+/// An endpoint server whose packets are captured in `packets`.
+fn endpoint(eid: u8, packets: &Packets) -> Server<SmallMtuBufferSender<'_>, 16> {
+    Server::new(Eid(eid), 0, SmallMtuBufferSender { packets, mtu: MTU })
+}
+
+/// Drain an endpoint's packets into the bridge, as received on `ingress`.
+fn to_bridge<S: Sender>(
+    packets: &Packets,
+    server: &mut Server<S, 16>,
+    ingress: BindingId,
+    bridge: &Bridge<'_>,
+    forwarded: &Forwarded,
+) -> Vec<Delivery> {
+    let mut forwarder = CaptureForwarder { out: forwarded };
+    packets
+        .take()
+        .iter()
+        .map(|pkt| {
+            server
+                .inbound_bridged(pkt, ingress, bridge, &mut forwarder)
+                .unwrap()
+        })
+        .collect()
+}
+
+/// Deliver the packets the bridge sent on `binding` to `server`.
+///
+/// Returns the delivered packets.
+fn from_bridge<S: Sender>(
+    forwarded: &Forwarded,
+    binding: BindingId,
+    server: &mut Server<S, 16>,
+) -> Vec<Vec<u8>> {
+    let (out, rest): (Vec<_>, Vec<_>) = forwarded
+        .take()
+        .into_iter()
+        .partition(|(route, _)| route.binding == binding);
+    *forwarded.borrow_mut() = rest;
+    out.into_iter()
+        .map(|(_, pkt)| {
+            server.inbound(&pkt).unwrap();
+            pkt
+        })
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Forwarding
+// ---------------------------------------------------------------------------
+
+/// A request and its response cross the bridge unchanged.
+#[test]
+fn forwards_request_and_response() {
+    let routes = routes();
+    let bridge = bridge(&routes, MTU, MTU);
+    let bmc_pkts = RefCell::new(Vec::new());
+    let dev_pkts = RefCell::new(Vec::new());
+    let forwarded = RefCell::new(Vec::new());
+    let mut bmc = endpoint(BMC_EID, &bmc_pkts);
+    let mut dev = endpoint(DEV_EID, &dev_pkts);
+    let mut prot: Server<_, 16> = Server::new(
+        Eid(BRIDGE_EID),
+        0,
+        RoutedSender::new(&bridge, CaptureForwarder { out: &forwarded }),
+    );
+    let listener = dev.listener(MSG_TYPE).unwrap();
+
+    // BMC -> device
+    let req = bmc.req(DEV_EID).unwrap();
+    bmc.send(Some(req), MSG_TYPE, None, None, false, b"GET_VERSION")
+        .unwrap();
+    let sent = bmc_pkts.borrow().clone();
+    assert_eq!(
+        to_bridge(&bmc_pkts, &mut prot, BUS_BMC, &bridge, &forwarded),
+        [Delivery::Forwarded(Route::new(BUS_DEV, ADDR_DEV))]
+    );
+    assert_eq!(from_bridge(&forwarded, BUS_DEV, &mut dev), sent);
+
+    let mut buf = [0u8; 64];
+    let meta = dev
+        .try_recv(listener, &mut buf)
+        .expect("device received nothing");
+    assert_eq!(meta.remote_eid, BMC_EID);
+    assert_eq!(&buf[..meta.payload_size], b"GET_VERSION");
+
+    // Device -> BMC
+    dev.send(
+        None,
+        MSG_TYPE,
+        Some(BMC_EID),
+        Some(meta.msg_tag),
+        false,
+        b"VERSION",
+    )
+    .unwrap();
+    assert_eq!(
+        to_bridge(&dev_pkts, &mut prot, BUS_DEV, &bridge, &forwarded),
+        [Delivery::Forwarded(Route::new(BUS_BMC, ADDR_BMC))]
+    );
+    from_bridge(&forwarded, BUS_BMC, &mut bmc);
+
+    let meta = bmc.try_recv(req, &mut buf).expect("BMC received nothing");
+    assert_eq!(meta.remote_eid, DEV_EID);
+    assert_eq!(&buf[..meta.payload_size], b"VERSION");
+}
+
+/// Each packet of a multi-packet message is forwarded as it arrives; only
+/// the destination reassembles.
+#[test]
+fn forwards_packets_without_reassembly() {
+    let routes = routes();
+    let bridge = bridge(&routes, MTU, MTU);
+    let bmc_pkts = RefCell::new(Vec::new());
+    let dev_pkts = RefCell::new(Vec::new());
+    let forwarded = RefCell::new(Vec::new());
+    let mut bmc = endpoint(BMC_EID, &bmc_pkts);
+    let mut dev = endpoint(DEV_EID, &dev_pkts);
+    let mut prot: Server<_, 16> = Server::new(
+        Eid(BRIDGE_EID),
+        0,
+        RoutedSender::new(&bridge, CaptureForwarder { out: &forwarded }),
+    );
+    let listener = dev.listener(MSG_TYPE).unwrap();
+
+    let payload: Vec<u8> = (0..150u8).collect();
+    let req = bmc.req(DEV_EID).unwrap();
+    bmc.send(Some(req), MSG_TYPE, None, None, false, &payload)
+        .unwrap();
+    let sent = bmc_pkts.borrow().clone();
+    assert_eq!(sent.len(), 3);
+
+    // Nothing reaches the bridge's own router
+    let bridge_listener = prot.listener(MSG_TYPE).unwrap();
+    for pkt in &sent {
+        bmc_pkts.borrow_mut().push(pkt.clone());
+        assert_eq!(
+            to_bridge(&bmc_pkts, &mut prot, BUS_BMC, &bridge, &forwarded),
+            [Delivery::Forwarded(Route::new(BUS_DEV, ADDR_DEV))]
+        );
+        assert_eq!(forwarded.borrow().last().unwrap().1, *pkt);
+    }
+    let mut buf = [0u8; 256];
+    assert!(prot.try_recv(bridge_listener, &mut buf).is_none());
+
+    assert_eq!(from_bridge(&forwarded, BUS_DEV, &mut dev), sent);
+    let meta = dev
+        .try_recv(listener, &mut buf)
+        .expect("device received nothing");
+    assert_eq!(&buf[..meta.payload_size], &payload[..]);
+}
+
+/// Packets larger than the outbound binding's MTU are dropped, and the
+/// bridge's own messages fit the smallest binding MTU.
+#[test]
+fn per_binding_mtu() {
+    const DEV_MTU: usize = 32;
+
+    let routes = routes();
+    let bridge = bridge(&routes, MTU, DEV_MTU);
+    let bmc_pkts = RefCell::new(Vec::new());
+    let forwarded = RefCell::new(Vec::new());
+    let mut bmc = endpoint(BMC_EID, &bmc_pkts);
+    let mut prot: Server<_, 16> = Server::new(
+        Eid(BRIDGE_EID),
+        0,
+        RoutedSender::new(&bridge, CaptureForwarder { out: &forwarded }),
+    );
+
+    // Fits the BMC's binding, but not the device's (the message type byte
+    // counts towards the packet payload)
+    let req = bmc.req(DEV_EID).unwrap();
+    bmc.send(Some(req), MSG_TYPE, None, None, false, &[0xA5; DEV_MTU])
+        .unwrap();
+    assert_eq!(
+        to_bridge(&bmc_pkts, &mut prot, BUS_BMC, &bridge, &forwarded),
+        [Delivery::Dropped(DropReason::TooLarge)]
+    );
+    assert!(forwarded.borrow().is_empty());
+
+    bmc.send(Some(req), MSG_TYPE, None, None, false, &[0xA5; DEV_MTU - 1])
+        .unwrap();
+    assert_eq!(
+        to_bridge(&bmc_pkts, &mut prot, BUS_BMC, &bridge, &forwarded),
+        [Delivery::Forwarded(Route::new(BUS_DEV, ADDR_DEV))]
+    );
+    forwarded.borrow_mut().clear();
+
+    // The bridge fragments its own messages at the smallest MTU
+    let prot_req = prot.req(BMC_EID).unwrap();
+    prot.send(
+        Some(prot_req),
+        MSG_TYPE,
+        None,
+        None,
+        false,
+        &[0x5A; MTU - 1],
+    )
+    .unwrap();
+    let pkts = from_bridge(&forwarded, BUS_BMC, &mut bmc);
+    assert!(pkts.len() >= 2);
+    assert!(pkts.iter().all(|pkt| pkt.len() - 4 <= DEV_MTU));
+}
+
+/// Packets for the bridge itself are consumed locally; packets without a
+/// usable route are dropped.
+#[test]
+fn local_and_unroutable_packets() {
+    const UNKNOWN_EID: u8 = 0x55;
+    const BMC_SIDE_EID: u8 = 0x1E;
+
+    let routes = routes();
+    routes
+        .borrow_mut()
+        .add_static(BMC_SIDE_EID..=BMC_SIDE_EID, Route::new(BUS_BMC, 0x21))
+        .unwrap();
+    let bridge = bridge(&routes, MTU, MTU);
+    let bmc_pkts = RefCell::new(Vec::new());
+    let forwarded = RefCell::new(Vec::new());
+    let mut bmc = endpoint(BMC_EID, &bmc_pkts);
+    let mut prot: Server<_, 16> = Server::new(
+        Eid(BRIDGE_EID),
+        0,
+        RoutedSender::new(&bridge, CaptureForwarder { out: &forwarded }),
+    );
+    let listener = prot.listener(MSG_TYPE).unwrap();
+
+    let req = bmc.req(BRIDGE_EID).unwrap();
+    bmc.send(Some(req), MSG_TYPE, None, None, false, b"for the bridge")
+        .unwrap();
+    assert_eq!(
+        to_bridge(&bmc_pkts, &mut prot, BUS_BMC, &bridge, &forwarded),
+        [Delivery::Local]
+    );
+    let mut buf = [0u8; 64];
+    let meta = prot
+        .try_recv(listener, &mut buf)
+        .expect("bridge received nothing");
+    assert_eq!(&buf[..meta.payload_size], b"for the bridge");
+
+    for (eid, reason) in [
+        (UNKNOWN_EID, DropReason::NoRoute),
+        // Routed back out of the binding it came in on
+        (BMC_SIDE_EID, DropReason::SameBinding),
+    ] {
+        let req = bmc.req(eid).unwrap();
+        bmc.send(Some(req), MSG_TYPE, None, None, false, b"nowhere")
+            .unwrap();
+        assert_eq!(
+            to_bridge(&bmc_pkts, &mut prot, BUS_BMC, &bridge, &forwarded),
+            [Delivery::Dropped(reason)]
+        );
+    }
+    assert!(forwarded.borrow().is_empty());
+}
+
+// ---------------------------------------------------------------------------
+// Bridge control commands
+// ---------------------------------------------------------------------------
+
+/// Send a control request from the BMC to the bridge and return the
+/// control response payload.
+fn control_request<S: Sender, T: Sender>(
+    bmc: &mut Server<S, 16>,
+    bmc_pkts: &Packets,
+    prot: &mut Server<T, 16>,
+    bridge: &Bridge<'_>,
+    forwarded: &Forwarded,
+    request: &[u8],
+) -> Vec<u8> {
+    let handle = bmc.req(BRIDGE_EID).unwrap();
+    bmc.send(Some(handle), MCTP_TYPE_CONTROL, None, None, false, request)
+        .unwrap();
+    assert!(to_bridge(bmc_pkts, prot, BUS_BMC, bridge, forwarded)
+        .iter()
+        .all(|delivery| *delivery == Delivery::Local));
+    from_bridge(forwarded, BUS_BMC, bmc);
+
+    let mut buf = [0u8; 64];
+    let meta = bmc
+        .try_recv(handle, &mut buf)
+        .expect("bridge should answer the control request");
+    bmc.unbind(handle).unwrap();
+    assert_eq!(meta.msg_type, MCTP_TYPE_CONTROL);
+    buf[..meta.payload_size].to_vec()
+}
+
+/// Routing Information Update adds routes on the binding it arrived on, and
+/// Get Routing Table Entries reports them.
+#[test]
+fn routing_information_update_and_table_entries() {
+    let routes = routes();
+    let bridge = bridge(&routes, MTU, MTU);
+    let bmc_pkts = RefCell::new(Vec::new());
+    let forwarded = RefCell::new(Vec::new());
+    let mut bmc = endpoint(BMC_EID, &bmc_pkts);
+    let mut prot: Server<_, 16> = Server::new(
+        Eid(BRIDGE_EID),
+        0,
+        RoutedSender::new(&bridge, CaptureForwarder { out: &forwarded }),
+    );
+    prot.enable_control(ControlResponder::new()).unwrap();
+
+    // One endpoint at 0x21 and a downstream bridge range at 0x22
+    let rsp = control_request(
+        &mut bmc,
+        &bmc_pkts,
+        &mut prot,
+        &bridge,
+        &forwarded,
+        &[
+            RQ,
+            command::ROUTING_INFORMATION_UPDATE,
+            2,
+            0x00,
+            1,
+            0x1E,
+            0x21,
+            0x40,
+            8,
+            0x60,
+            0x22,
+        ],
+    );
+    assert_eq!(
+        rsp,
+        [
+            RSP,
+            command::ROUTING_INFORMATION_UPDATE,
+            completion::SUCCESS
+        ]
+    );
+    assert_eq!(
+        routes.borrow().lookup(0x1E),
+        Some(Route::new(BUS_BMC, 0x21))
+    );
+    assert_eq!(
+        routes.borrow().lookup(0x67),
+        Some(Route::new(BUS_BMC, 0x22))
+    );
+
+    // Entry count says two, data holds one
+    let rsp = control_request(
+        &mut bmc,
+        &bmc_pkts,
+        &mut prot,
+        &bridge,
+        &forwarded,
+        &[
+            RQ,
+            command::ROUTING_INFORMATION_UPDATE,
+            2,
+            0x00,
+            1,
+            0x1E,
+            0x21,
+        ],
+    );
+    assert_eq!(
+        rsp,
+        [
+            RSP,
+            command::ROUTING_INFORMATION_UPDATE,
+            completion::ERROR_INVALID_LENGTH
+        ]
+    );
+
+    // Four entries: three fit in the first response
+    let rsp = control_request(
+        &mut bmc,
+        &bmc_pkts,
+        &mut prot,
+        &bridge,
+        &forwarded,
+        &[RQ, command::GET_ROUTING_TABLE_ENTRIES, 0],
+    );
+    assert_eq!(
+        rsp,
+        [
+            RSP,
+            command::GET_ROUTING_TABLE_ENTRIES,
+            completion::SUCCESS,
+            3,
+            3,
+            // BMC, static single endpoint on port 0
+            1,
+            BMC_EID,
+            0x20,
+            transport_type::SMBUS,
+            0x01,
+            1,
+            ADDR_BMC,
+            // Device, static single endpoint on port 1
+            1,
+            DEV_EID,
+            0x21,
+            transport_type::SMBUS,
+            0x01,
+            1,
+            ADDR_DEV,
+            // From the update
+            1,
+            0x1E,
+            0x20,
+            transport_type::SMBUS,
+            0x01,
+            1,
+            0x21,
+        ]
+    );
+
+    let rsp = control_request(
+        &mut bmc,
+        &bmc_pkts,
+        &mut prot,
+        &bridge,
+        &forwarded,
+        &[RQ, command::GET_ROUTING_TABLE_ENTRIES, 3],
+    );
+    assert_eq!(
+        rsp,
+        [
+            RSP,
+            command::GET_ROUTING_TABLE_ENTRIES,
+            completion::SUCCESS,
+            0xFF,
+            1,
+            // Static bridge range on port 0
+            8,
+            0x60,
+            0x60,
+            transport_type::SMBUS,
+            0x01,
+            1,
+            0x22,
+        ]
+    );
+
+    let rsp = control_request(
+        &mut bmc,
+        &bmc_pkts,
+        &mut prot,
+        &bridge,
+        &forwarded,
+        &[RQ, command::GET_ROUTING_TABLE_ENTRIES, 4],
+    );
+    assert_eq!(
+        rsp,
+        [
+            RSP,
+            command::GET_ROUTING_TABLE_ENTRIES,
+            completion::ERROR_INVALID_DATA
+        ]
+    );
+}
+
+/// A Routing Information Update whose last entry does not fit in the routing
+/// table is rejected without applying any of its entries.
+#[test]
+fn routing_information_update_overflow_is_atomic() {
+    let routes = routes();
+    let bridge = bridge(&routes, MTU, MTU);
+    let bmc_pkts = RefCell::new(Vec::new());
+    let forwarded = RefCell::new(Vec::new());
+    let mut bmc = endpoint(BMC_EID, &bmc_pkts);
+    let mut prot: Server<_, 16> = Server::new(
+        Eid(BRIDGE_EID),
+        0,
+        RoutedSender::new(&bridge, CaptureForwarder { out: &forwarded }),
+    );
+    prot.enable_control(ControlResponder::new()).unwrap();
+
+    // One endpoint entry more than the table has room for
+    let before = routes.borrow().len();
+    let count = MAX_ROUTES - before + 1;
+    let mut request = vec![RQ, command::ROUTING_INFORMATION_UPDATE, count as u8];
+    for eid in (0x50..).take(count) {
+        request.extend_from_slice(&[0x00, 1, eid, 0x21]);
+    }
+    let rsp = control_request(
+        &mut bmc, &bmc_pkts, &mut prot, &bridge, &forwarded, &request,
+    );
+    assert_eq!(
+        rsp,
+        [RSP, command::ROUTING_INFORMATION_UPDATE, completion::ERROR]
+    );
+    assert_eq!(routes.borrow().len(), before);
+    assert_eq!(routes.borrow().lookup(0x50), None);
+}
+
+/// Routing Information Update is only accepted from the bus owner that
+/// assigned the bridge's EID.
+#[test]
+fn routing_information_update_from_bus_owner_only() {
+    let routes = routes();
+    let bridge = bridge(&routes, MTU, MTU);
+    let bmc_pkts = RefCell::new(Vec::new());
+    let forwarded = RefCell::new(Vec::new());
+    let mut bmc = endpoint(BMC_EID, &bmc_pkts);
+    let mut prot: Server<_, 16> = Server::new(
+        Eid(BRIDGE_EID),
+        0,
+        RoutedSender::new(&bridge, CaptureForwarder { out: &forwarded }),
+    );
+    prot.enable_control(ControlResponder::new()).unwrap();
+
+    // The BMC assigns the bridge's EID...
+    let rsp = control_request(
+        &mut bmc,
+        &bmc_pkts,
+        &mut prot,
+        &bridge,
+        &forwarded,
+        &[RQ, command::SET_ENDPOINT_ID, 0x00, BRIDGE_EID],
+    );
+    assert_eq!(rsp[2], completion::SUCCESS);
+
+    // ...so an update from the device is refused
+    let dev_pkts = RefCell::new(Vec::new());
+    let mut dev = endpoint(DEV_EID, &dev_pkts);
+    let handle = dev.req(BRIDGE_EID).unwrap();
+    dev.send(
+        Some(handle),
+        MCTP_TYPE_CONTROL,
+        None,
+        None,
+        false,
+        &[
+            RQ,
+            command::ROUTING_INFORMATION_UPDATE,
+            1,
+            0x00,
+            1,
+            0x1E,
+            0x21,
+        ],
+    )
+    .unwrap();
+    to_bridge(&dev_pkts, &mut prot, BUS_DEV, &bridge, &forwarded);
+    from_bridge(&forwarded, BUS_DEV, &mut dev);
+
+    let mut buf = [0u8; 64];
+    let meta = dev.try_recv(handle, &mut buf).unwrap();
+    assert_eq!(
+        &buf[..meta.payload_size],
+        [RSP, command::ROUTING_INFORMATION_UPDATE, completion::ERROR]
+    );
+    assert_eq!(routes.borrow().lookup(0x1E), None);
+}
@@ -0,0 +1,543 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! MCTP control protocol responder tests.
+//!
+//! A bus owner server (EID 8) sends control requests through a
+//! `DirectClient` to an endpoint server (EID 9) with the control responder
+//! enabled, and decodes the responses the endpoint sends back.
+
+mod common;
+
+use std::cell::RefCell;
+
+use openprot_mctp_api::{MctpClient, ResponseCode};
+use openprot_mctp_server::control::{
+    command, completion, ControlResponder, VendorId, VendorSupport, MCTP_TYPE_CONTROL,
+};
+use openprot_mctp_server::{Sender, Server};
+
+use common::{make_server, transfer, DirectClient};
+
+const OWNER_EID: u8 = 8;
+const ENDPOINT_EID: u8 = 9;
+
+/// Control header byte for a request with instance ID 3.
+const RQ: u8 = 0x80 | 3;
+/// Control header byte of the matching response.
+const RSP: u8 = 3;
+
+// ---------------------------------------------------------------------------
+// Helpers
+// ---------------------------------------------------------------------------
+
+/// Send one control request from `owner` to `endpoint_eid` and return the
+/// control response payload.
+///
+/// Panics if the endpoint does not answer.
+fn control_request<S: Sender, T: Sender>(
+    owner: &DirectClient<'_, S, 16>,
+    owner_pkts: &RefCell<Vec<Vec<u8>>>,
+    endpoint: &RefCell<Server<T, 16>>,
+    endpoint_pkts: &RefCell<Vec<Vec<u8>>>,
+    endpoint_eid: u8,
+    request: &[u8],
+) -> Vec<u8> {
+    let handle = owner.req(endpoint_eid).unwrap();
+    owner
+        .send(Some(handle), MCTP_TYPE_CONTROL, None, None, false, request)
+        .unwrap();
+
+    transfer(owner_pkts, &mut endpoint.borrow_mut());
+    owner_pkts.borrow_mut().clear();
+    transfer(endpoint_pkts, &mut owner.server.borrow_mut());
+    endpoint_pkts.borrow_mut().clear();
+
+    let mut buf = [0u8; 64];
+    let meta = owner
+        .recv(handle, 0, &mut buf)
+        .expect("endpoint should answer the control request");
+    owner.drop_handle(handle);
+
+    assert_eq!(meta.msg_type, MCTP_TYPE_CONTROL);
+    assert_eq!(meta.remote_eid, endpoint_eid);
+    buf[..meta.payload_size].to_vec()
+}
+
+// ---------------------------------------------------------------------------
+// Enablement
+// ---------------------------------------------------------------------------
+
+/// Without `enable_control`, control requests go unanswered.
+#[test]
+fn control_disabled_by_default() {
+    let owner_pkts = RefCell::new(Vec::new());
+    let endpoint_pkts = RefCell::new(Vec::new());
+    let owner = RefCell::new(make_server(OWNER_EID, &owner_pkts));
+    let endpoint = RefCell::new(make_server(ENDPOINT_EID, &endpoint_pkts));
+    let client = DirectClient::new(&owner);
+
+    let handle = client.req(ENDPOINT_EID).unwrap();
+    client
+        .send(
+            Some(handle),
+            MCTP_TYPE_CONTROL,
+            None,
+            None,
+            false,
+            &[RQ, command::GET_ENDPOINT_ID],
+        )
+        .unwrap();
+    transfer(&owner_pkts, &mut endpoint.borrow_mut());
+
+    assert!(endpoint_pkts.borrow().is_empty());
+    assert!(endpoint.borrow().control().is_none());
+}
+
+/// The responder owns the control message type once enabled.
+#[test]
+fn enable_control_claims_control_type() {
+    let endpoint_pkts = RefCell::new(Vec::new());
+    let endpoint = RefCell::new(make_server(ENDPOINT_EID, &endpoint_pkts));
+    let client = DirectClient::new(&endpoint);
+
+    endpoint
+        .borrow_mut()
+        .enable_control(ControlResponder::new())
+        .unwrap();
+
+    let err = endpoint
+        .borrow_mut()
+        .enable_control(ControlResponder::new())
+        .expect_err("second enable should fail");
+    assert_eq!(err.code, ResponseCode::AddrInUse);
+
+    let err = client
+        .listener(MCTP_TYPE_CONTROL)
+        .expect_err("client listener for control type should fail");
+    assert_eq!(err.code, ResponseCode::AddrInUse);
+}
+
+// ---------------------------------------------------------------------------
+// Commands
+// ---------------------------------------------------------------------------
+
+/// Get Endpoint ID reports the configured EID.
+#[test]
+fn get_endpoint_id() {
+    let owner_pkts = RefCell::new(Vec::new());
+    let endpoint_pkts = RefCell::new(Vec::new());
+    let owner = RefCell::new(make_server(OWNER_EID, &owner_pkts));
+    let endpoint = RefCell::new(make_server(ENDPOINT_EID, &endpoint_pkts));
+    endpoint
+        .borrow_mut()
+        .enable_control(ControlResponder::new())
+        .unwrap();
+    let client = DirectClient::new(&owner);
+
+    let rsp = control_request(
+        &client,
+        &owner_pkts,
+        &endpoint,
+        &endpoint_pkts,
+        ENDPOINT_EID,
+        &[RQ, command::GET_ENDPOINT_ID],
+    );
+    assert_eq!(
+        rsp,
+        [
+            RSP,
+            command::GET_ENDPOINT_ID,
+            completion::SUCCESS,
+            ENDPOINT_EID,
+            0,
+            0
+        ]
+    );
+}
+
+/// Set Endpoint ID from the bus owner assigns the new EID after the
+/// response, and a later Set from another bus owner is rejected.
+#[test]
+fn set_endpoint_id_from_bus_owner() {
+    let owner_pkts = RefCell::new(Vec::new());
+    let endpoint_pkts = RefCell::new(Vec::new());
+    let owner = RefCell::new(make_server(OWNER_EID, &owner_pkts));
+    let endpoint = RefCell::new(make_server(ENDPOINT_EID, &endpoint_pkts));
+    endpoint
+        .borrow_mut()
+        .enable_control(ControlResponder::new())
+        .unwrap();
+    let client = DirectClient::new(&owner);
+
+    let rsp = control_request(
+        &client,
+        &owner_pkts,
+        &endpoint,
+        &endpoint_pkts,
+        ENDPOINT_EID,
+        &[RQ, command::SET_ENDPOINT_ID, 0x00, 20],
+    );
+    assert_eq!(
+        rsp,
+        [
+            RSP,
+            command::SET_ENDPOINT_ID,
+            completion::SUCCESS,
+            0x00,
+            20,
+            0
+        ]
+    );
+    assert_eq!(endpoint.borrow().get_eid(), 20);
+    assert_eq!(
+        endpoint.borrow().control().unwrap().bus_owner(),
+        Some(OWNER_EID)
+    );
+
+    // A second bus owner cannot take over with a plain Set
+    owner.borrow_mut().set_eid(10).unwrap();
+    let rsp = control_request(
+        &client,
+        &owner_pkts,
+        &endpoint,
+        &endpoint_pkts,
+        20,
+        &[RQ, command::SET_ENDPOINT_ID, 0x00, 30],
+    );
+    assert_eq!(
+        rsp,
+        [
+            RSP,
+            command::SET_ENDPOINT_ID,
+            completion::SUCCESS,
+            0x10,
+            20,
+            0
+        ]
+    );
+    assert_eq!(endpoint.borrow().get_eid(), 20);
+
+    // ...but a Force is accepted
+    let rsp = control_request(
+        &client,
+        &owner_pkts,
+        &endpoint,
+        &endpoint_pkts,
+        20,
+        &[RQ, command::SET_ENDPOINT_ID, 0x01, 30],
+    );
+    assert_eq!(
+        rsp,
+        [
+            RSP,
+            command::SET_ENDPOINT_ID,
+            completion::SUCCESS,
+            0x00,
+            30,
+            0
+        ]
+    );
+    assert_eq!(endpoint.borrow().get_eid(), 30);
+}
+
+/// Set Endpoint ID rejects reserved EIDs and unsupported operations.
+#[test]
+fn set_endpoint_id_invalid_requests() {
+    let owner_pkts = RefCell::new(Vec::new());
+    let endpoint_pkts = RefCell::new(Vec::new());
+    let owner = RefCell::new(make_server(OWNER_EID, &owner_pkts));
+    let endpoint = RefCell::new(make_server(ENDPOINT_EID, &endpoint_pkts));
+    endpoint
+        .borrow_mut()
+        .enable_control(ControlResponder::new())
+        .unwrap();
+    let client = DirectClient::new(&owner);
+
+    for (request, code) in [
+        // Null and broadcast EIDs
+        (
+            &[RQ, command::SET_ENDPOINT_ID, 0x00, 0x00][..],
+            completion::ERROR_INVALID_DATA,
+        ),
+        (
+            &[RQ, command::SET_ENDPOINT_ID, 0x00, 0xFF][..],
+            completion::ERROR_INVALID_DATA,
+        ),
+        // Reset EID and Set Discovered Flag
+        (
+            &[RQ, command::SET_ENDPOINT_ID, 0x02, 20][..],
+            completion::ERROR_INVALID_DATA,
+        ),
+        (
+            &[RQ, command::SET_ENDPOINT_ID, 0x03, 20][..],
+            completion::ERROR_INVALID_DATA,
+        ),
+        // Missing EID byte
+        (
+            &[RQ, command::SET_ENDPOINT_ID, 0x00][..],
+            completion::ERROR_INVALID_LENGTH,
+        ),
+    ] {
+        let rsp = control_request(
+            &client,
+            &owner_pkts,
+            &endpoint,
+            &endpoint_pkts,
+            ENDPOINT_EID,
+            request,
+        );
+        assert_eq!(rsp, [RSP, command::SET_ENDPOINT_ID, code]);
+    }
+    assert_eq!(endpoint.borrow().get_eid(), ENDPOINT_EID);
+    assert_eq!(endpoint.borrow().control().unwrap().bus_owner(), None);
+}
+
+/// Get MCTP Version Support reports 1.3.1 for the base specification and
+/// the control protocol only.
+#[test]
+fn get_version_support() {
+    let owner_pkts = RefCell::new(Vec::new());
+    let endpoint_pkts = RefCell::new(Vec::new());
+    let owner = RefCell::new(make_server(OWNER_EID, &owner_pkts));
+    let endpoint = RefCell::new(make_server(ENDPOINT_EID, &endpoint_pkts));
+    endpoint
+        .borrow_mut()
+        .enable_control(ControlResponder::new())
+        .unwrap();
+    let client = DirectClient::new(&owner);
+
+    for typ in [0xFF, MCTP_TYPE_CONTROL] {
+        let rsp = control_request(
+            &client,
+            &owner_pkts,
+            &endpoint,
+            &endpoint_pkts,
+            ENDPOINT_EID,
+            &[RQ, command::GET_VERSION_SUPPORT, typ],
+        );
+        assert_eq!(
+            rsp,
+            [
+                RSP,
+                command::GET_VERSION_SUPPORT,
+                completion::SUCCESS,
+                1,
+                0xF1,
+                0xF3,
+                0xF1,
+                0x00
+            ]
+        );
+    }
+
+    let rsp = control_request(
+        &client,
+        &owner_pkts,
+        &endpoint,
+        &endpoint_pkts,
+        ENDPOINT_EID,
+        &[RQ, command::GET_VERSION_SUPPORT, 0x05],
+    );
+    assert_eq!(
+        rsp,
+        [
+            RSP,
+            command::GET_VERSION_SUPPORT,
+            completion::MESSAGE_TYPE_NOT_SUPPORTED
+        ]
+    );
+}
+
+/// Get Message Type Support follows the listeners registered by clients.
+#[test]
+fn get_message_type_support_tracks_listeners() {
+    let owner_pkts = RefCell::new(Vec::new());
+    let endpoint_pkts = RefCell::new(Vec::new());
+    let owner = RefCell::new(make_server(OWNER_EID, &owner_pkts));
+    let endpoint = RefCell::new(make_server(ENDPOINT_EID, &endpoint_pkts));
+    endpoint
+        .borrow_mut()
+        .enable_control(ControlResponder::new())
+        .unwrap();
+    let client = DirectClient::new(&owner);
+    let endpoint_client = DirectClient::new(&endpoint);
+
+    let rsp = control_request(
+        &client,
+        &owner_pkts,
+        &endpoint,
+        &endpoint_pkts,
+        ENDPOINT_EID,
+        &[RQ, command::GET_MESSAGE_TYPE_SUPPORT],
+    );
+    assert_eq!(
+        rsp,
+        [
+            RSP,
+            command::GET_MESSAGE_TYPE_SUPPORT,
+            completion::SUCCESS,
+            0
+        ]
+    );
+
+    // SPDM (5) and PLDM (1) listeners, registered out of order
+    let spdm = endpoint_client.listener(0x05).unwrap();
+    endpoint_client.listener(0x01).unwrap();
+    let rsp = control_request(
+        &client,
+        &owner_pkts,
+        &endpoint,
+        &endpoint_pkts,
+        ENDPOINT_EID,
+        &[RQ, command::GET_MESSAGE_TYPE_SUPPORT],
+    );
+    assert_eq!(
+        rsp,
+        [
+            RSP,
+            command::GET_MESSAGE_TYPE_SUPPORT,
+            completion::SUCCESS,
+            2,
+            0x01,
+            0x05
+        ]
+    );
+
+    endpoint_client.drop_handle(spdm);
+    let rsp = control_request(
+        &client,
+        &owner_pkts,
+        &endpoint,
+        &endpoint_pkts,
+        ENDPOINT_EID,
+        &[RQ, command::GET_MESSAGE_TYPE_SUPPORT],
+    );
+    assert_eq!(
+        rsp,
+        [
+            RSP,
+            command::GET_MESSAGE_TYPE_SUPPORT,
+            completion::SUCCESS,
+            1,
+            0x01
+        ]
+    );
+}
+
+/// Get Vendor Defined Message Support walks the registered vendor ID sets.
+#[test]
+fn get_vendor_message_support() {
+    let owner_pkts = RefCell::new(Vec::new());
+    let endpoint_pkts = RefCell::new(Vec::new());
+    let owner = RefCell::new(make_server(OWNER_EID, &owner_pkts));
+    let endpoint = RefCell::new(make_server(ENDPOINT_EID, &endpoint_pkts));
+
+    let mut responder = ControlResponder::new();
+    responder
+        .add_vendor_support(VendorSupport {
+            vendor: VendorId::Pci(0x1AB4),
+            command_set: 0x0001,
+        })
+        .unwrap();
+    responder
+        .add_vendor_support(VendorSupport {
+            vendor: VendorId::Iana(0x0000_A015),
+            command_set: 0x0102,
+        })
+        .unwrap();
+    endpoint.borrow_mut().enable_control(responder).unwrap();
+    let client = DirectClient::new(&owner);
+
+    let rsp = control_request(
+        &client,
+        &owner_pkts,
+        &endpoint,
+        &endpoint_pkts,
+        ENDPOINT_EID,
+        &[RQ, command::GET_VENDOR_MESSAGE_SUPPORT, 0],
+    );
+    assert_eq!(
+        rsp,
+        [
+            RSP,
+            command::GET_VENDOR_MESSAGE_SUPPORT,
+            completion::SUCCESS,
+            1,
+            0x00,
+            0x1A,
+            0xB4,
+            0x00,
+            0x01
+        ]
+    );
+
+    let rsp = control_request(
+        &client,
+        &owner_pkts,
+        &endpoint,
+        &endpoint_pkts,
+        ENDPOINT_EID,
+        &[RQ, command::GET_VENDOR_MESSAGE_SUPPORT, 1],
+    );
+    assert_eq!(
+        rsp,
+        [
+            RSP,
+            command::GET_VENDOR_MESSAGE_SUPPORT,
+            completion::SUCCESS,
+            0xFF,
+            0x01,
+            0x00,
+            0x00,
+            0xA0,
+            0x15,
+            0x01,
+            0x02
+        ]
+    );
+
+    let rsp = control_request(
+        &client,
+        &owner_pkts,
+        &endpoint,
+        &endpoint_pkts,
+        ENDPOINT_EID,
+        &[RQ, command::GET_VENDOR_MESSAGE_SUPPORT, 2],
+    );
+    assert_eq!(
+        rsp,
+        [
+            RSP,
+            command::GET_VENDOR_MESSAGE_SUPPORT,
+            completion::ERROR_INVALID_DATA
+        ]
+    );
+}
+
+/// Commands without a handler, including the transport-specific range, get
+/// `ERROR_UNSUPPORTED_CMD`.
+#[test]
+fn unsupported_commands() {
+    let owner_pkts = RefCell::new(Vec::new());
+    let endpoint_pkts = RefCell::new(Vec::new());
+    let owner = RefCell::new(make_server(OWNER_EID, &owner_pkts));
+    let endpoint = RefCell::new(make_server(ENDPOINT_EID, &endpoint_pkts));
+    endpoint
+        .borrow_mut()
+        .enable_control(ControlResponder::new())
+        .unwrap();
+    let client = DirectClient::new(&owner);
+
+    for cmd in [0x03, 0x0A, 0xF0, 0xFF] {
+        let rsp = control_request(
+            &client,
+            &owner_pkts,
+            &endpoint,
+            &endpoint_pkts,
+            ENDPOINT_EID,
+            &[RQ, cmd],
+        );
+        assert_eq!(rsp, [RSP, cmd, completion::ERROR_UNSUPPORTED_CMD]);
+    }
+}
@@ -15,6 +15,14 @@
 //! - Inbound target-mode data comes from the i2c userspace driver
 //!   notification + `SlaveReceive` flow.
 //! - MCTP framing/PEC logic stays in `mctp_lib::i2c::MctpI2cEncap`.
+//!
+//! ## Addressing
+//!
+//! [`I2cSender::new`] sends every packet to one peer. For several peers,
+//! share an `openprot_mctp_routing::RoutingTable` between
+//! [`I2cSender::with_routes`] and [`MctpI2cReceiver::decode_and_learn`]:
+//! static routes come from board configuration and inbound frames teach the
+//! table the I2C address of each source EID.
 
 #![no_std]
 #![warn(missing_docs)]
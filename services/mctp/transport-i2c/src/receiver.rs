@@ -11,6 +11,7 @@
 //! for decoding (same as Hubris).
 
 use mctp_lib::i2c::{MctpI2cEncap, MctpI2cHeader};
+use openprot_mctp_routing::{BindingId, Route, RoutingTable};
 
 /// Decodes I2C target frames into raw MCTP packets.
 ///
@@ -41,6 +42,22 @@ impl MctpI2cReceiver {
         // and returns the raw MCTP packet + source I2C address.
         self.encap.decode(data, true)
     }
+
+    /// Decode an I2C target frame and learn the sender's route.
+    ///
+    /// Same as [`decode`](Self::decode), and on success records that the
+    /// packet's source EID is reachable at the frame's I2C source address
+    /// on `binding`. Static routes in `routes` are left untouched.
+    pub fn decode_and_learn<'a>(
+        &self,
+        data: &'a [u8],
+        binding: BindingId,
+        routes: &mut RoutingTable,
+    ) -> Result<(&'a [u8], MctpI2cHeader), mctp::Error> {
+        let (pkt, header) = self.decode(data)?;
+        routes.learn_from_packet(pkt, Route::new(binding, header.source));
+        Ok((pkt, header))
+    }
 }
 
 #[cfg(test)]
@@ -6,9 +6,12 @@
 //! Direct port of Hubris `mctp-server/src/i2c.rs` `I2cSender`.
 //! Only the I2C driver API is replaced with `embedded_hal::i2c::I2c`.
 
+use core::cell::RefCell;
+
 use embedded_hal::i2c::I2c;
 use mctp::Result;
 use mctp_lib::i2c::{MctpI2cEncap, MCTP_I2C_MAXMTU};
+use openprot_mctp_routing::{dest_eid, BindingId, RoutingTable};
 
 /// I2C MCTP sender.
 ///
@@ -19,17 +22,21 @@ use mctp_lib::i2c::{MctpI2cEncap, MCTP_I2C_MAXMTU};
 /// loop, I2C encoding via `MctpI2cEncap`, and error mapping are preserved
 /// as-is. Only the I2C write call is changed from `drv_i2c_api::I2cDevice::write`
 /// to `embedded_hal::i2c::I2c::write`.
-pub struct I2cSender<C: I2c<u8>> {
+///
+/// The destination I2C address comes either from a single static peer
+/// ([`new`](Self::new)) or from a shared [`RoutingTable`]
+/// ([`with_routes`](Self::with_routes)).
+pub struct I2cSender<'r, C: I2c<u8>> {
     i2c: C,
     own_addr: u8,
-    // Simple static remote address. Full neighbor table (EID → I2C address mapping)
-    // will be implemented later per https://github.com/OpenPRoT/mctp-lib/issues/4.
-    // For now, this supports single-peer communication (requester ↔ responder).
+    /// Destination of every packet when no routing table is attached.
     remote_addr: u8,
+    /// Routing table and the binding this sender drives.
+    routes: Option<(&'r RefCell<RoutingTable>, BindingId)>,
 }
 
-impl<C: I2c<u8>> I2cSender<C> {
-    /// Create a new I2C sender.
+impl<C: I2c<u8>> I2cSender<'static, C> {
+    /// Create a new I2C sender for a single peer.
     ///
     /// * `i2c` - I2C client for bus writes
     /// * `own_addr` - Own I2C address (7-bit, used in MCTP-I2C header)
@@ -39,23 +46,58 @@ impl<C: I2c<u8>> I2cSender<C> {
             i2c,
             own_addr,
             remote_addr,
+            routes: None,
+        }
+    }
+}
+
+impl<'r, C: I2c<u8>> I2cSender<'r, C> {
+    /// Create a new I2C sender that resolves destinations through `routes`.
+    ///
+    /// * `i2c` - I2C client for bus writes
+    /// * `own_addr` - Own I2C address (7-bit, used in MCTP-I2C header)
+    /// * `binding` - Binding ID of this bus in `routes`
+    /// * `routes` - Routing table shared with the inbound path
+    ///
+    /// Each packet goes to the I2C address routed for its destination EID.
+    /// Sending fails with `Unreachable` if the EID has no route or is routed
+    /// over a different binding.
+    pub fn with_routes(
+        i2c: C,
+        own_addr: u8,
+        binding: BindingId,
+        routes: &'r RefCell<RoutingTable>,
+    ) -> Self {
+        Self {
+            i2c,
+            own_addr,
+            remote_addr: 0,
+            routes: Some((routes, binding)),
+        }
+    }
+
+    /// Resolve the I2C address for a fragmented MCTP packet.
+    fn resolve(&self, pkt: &[u8]) -> Result<u8> {
+        let Some((routes, binding)) = self.routes else {
+            return Ok(self.remote_addr);
+        };
+        let eid = dest_eid(pkt).ok_or(mctp::Error::InvalidInput)?;
+        match routes.borrow().lookup(eid) {
+            Some(route) if route.binding == binding => Ok(route.addr),
+            _ => {
+                pw_log::error!("no I2C route to EID 0x{:02x}", eid as u32);
+                Err(mctp::Error::Unreachable)
+            }
         }
     }
 }
 
-impl<C: I2c<u8>> mctp_lib::Sender for I2cSender<C> {
+impl<C: I2c<u8>> mctp_lib::Sender for I2cSender<'_, C> {
     fn send_vectored(
         &mut self,
         mut fragmenter: mctp_lib::fragment::Fragmenter,
         payload: &[&[u8]],
     ) -> Result<mctp::Tag> {
-        // Use the configured remote address. In a full implementation, this would
-        // look up the destination EID in a neighbor table to find the corresponding
-        // I2C address. For now, we support single-peer communication with a static
-        // remote address configured at construction time.
-        // TODO: Implement full EID → I2C address neighbor table
-        //       (see https://github.com/OpenPRoT/mctp-lib/issues/4)
-        let addr = self.remote_addr;
         let encoder = MctpI2cEncap::new(self.own_addr);
         let mtu = self.get_mtu();
         pw_log::info!(
@@ -74,6 +116,7 @@ impl<C: I2c<u8>> mctp_lib::Sender for I2cSender<C> {
 
             match r {
                 mctp_lib::fragment::SendOutput::Packet(p) => {
+                    let addr = self.resolve(p)?;
                     pw_log::info!("packet sending to 0x{:02x}...", addr as u32);
                     let mut out = [0; MCTP_I2C_MAXMTU + 8]; // max MTU + I2C header size
                     let packet = encoder.encode(addr, p, &mut out, true)?;
@@ -155,11 +198,16 @@ mod tests {
     use std::vec::Vec;
 
     use mctp::Eid;
+    use openprot_mctp_routing::{BindingId, Route, RoutingTable};
 
-    use i2c_api::seam::{ErrorKind, ErrorType, I2c, I2cBusError, Operation, SevenBitAddress};
+    use i2c_api::seam::{
+        ErrorKind, ErrorType, I2c, I2cBusError, I2cSlaveBuffer, Operation, SevenBitAddress,
+    };
     use i2c_client::I2cClient;
     use i2c_server::loopback::LoopbackTransport;
     use openprot_mctp_server::Server;
+    use openprot_platform_mock::i2c_bus::{VirtualI2cBus, VirtualI2cPort};
+    use openprot_platform_mock::i2c_hardware::MockI2cHardware;
 
     use super::I2cSender;
     use crate::MctpI2cReceiver;
@@ -280,4 +328,211 @@ mod tests {
             "expected exactly one I2C write for a short payload"
         );
     }
+
+    // Hand the frame the virtual bus left in `port`'s slave buffer to
+    // `server`, as the i2c server's slave-receive path would. The bus
+    // delivers the bytes after the address, so the dest byte is restored
+    // before decoding. Returns the I2C source address of the frame.
+    fn deliver<S: mctp_lib::Sender, const N: usize, const B: usize>(
+        port: &mut VirtualI2cPort<'_, B>,
+        own_addr: u8,
+        server: &mut Server<S, N>,
+    ) -> u8 {
+        deliver_learning(port, own_addr, server, None)
+    }
+
+    // As `deliver`, optionally teaching `routes` the frame's source EID.
+    fn deliver_learning<S: mctp_lib::Sender, const N: usize, const B: usize>(
+        port: &mut VirtualI2cPort<'_, B>,
+        own_addr: u8,
+        server: &mut Server<S, N>,
+        routes: Option<(&RefCell<RoutingTable>, BindingId)>,
+    ) -> u8 {
+        let mut frame = [0u8; 65];
+        frame[0] = own_addr << 1;
+        let len = port
+            .read_slave_buffer(&mut frame[1..])
+            .expect("slave read failed");
+        assert!(len > 0, "nothing delivered to 0x{own_addr:02x}");
+
+        let receiver = MctpI2cReceiver::new(own_addr);
+        let (pkt, hdr) = match routes {
+            Some((routes, binding)) => {
+                receiver.decode_and_learn(&frame[..=len], binding, &mut routes.borrow_mut())
+            }
+            None => receiver.decode(&frame[..=len]),
+        }
+        .expect("decode failed");
+        server.inbound(pkt).expect("inbound failed");
+        hdr.source
+    }
+
+    // Two servers on one virtual I2C bus: each I2cSender writes real
+    // MCTP-over-SMBus frames (with PEC) to the other's slave buffer, and
+    // MctpI2cReceiver decodes them on the far side.
+    #[test]
+    fn virtual_bus_roundtrip() {
+        const ADDR_A: u8 = 0x10;
+        const ADDR_B: u8 = 0x42;
+        const EID_A: u8 = 8;
+        const EID_B: u8 = 42;
+        const MSG_TYPE: u8 = 0x05; // SPDM
+
+        let bus: VirtualI2cBus<2> = VirtualI2cBus::new();
+        let mut port_a = bus.attach(MockI2cHardware::new(), ADDR_A).unwrap();
+        let mut port_b = bus.attach(MockI2cHardware::new(), ADDR_B).unwrap();
+
+        let mut server_a: Server<_, 16> = Server::new(
+            Eid(EID_A),
+            0,
+            I2cSender::new(port_a.clone(), ADDR_A, ADDR_B),
+        );
+        let mut server_b: Server<_, 16> = Server::new(
+            Eid(EID_B),
+            0,
+            I2cSender::new(port_b.clone(), ADDR_B, ADDR_A),
+        );
+        let listener_a = server_a.listener(MSG_TYPE).unwrap();
+        let listener_b = server_b.listener(MSG_TYPE).unwrap();
+
+        // A -> B
+        let req_a = server_a.req(EID_B).unwrap();
+        server_a
+            .send(Some(req_a), MSG_TYPE, None, None, false, b"hello from A")
+            .unwrap();
+        assert_eq!(deliver(&mut port_b, ADDR_B, &mut server_b), ADDR_A);
+
+        let mut buf = [0u8; 64];
+        let meta = server_b
+            .try_recv(listener_b, &mut buf)
+            .expect("B received nothing");
+        assert_eq!(meta.msg_type, MSG_TYPE);
+        assert_eq!(meta.remote_eid, EID_A);
+        assert_eq!(&buf[..meta.payload_size], b"hello from A");
+
+        // B -> A
+        let req_b = server_b.req(EID_A).unwrap();
+        server_b
+            .send(Some(req_b), MSG_TYPE, None, None, false, b"hello from B")
+            .unwrap();
+        assert_eq!(deliver(&mut port_a, ADDR_A, &mut server_a), ADDR_B);
+
+        let meta = server_a
+            .try_recv(listener_a, &mut buf)
+            .expect("A received nothing");
+        assert_eq!(meta.remote_eid, EID_B);
+        assert_eq!(&buf[..meta.payload_size], b"hello from B");
+    }
+
+    // A target that has left the bus NACKs, which the sender reports as a
+    // transmit failure.
+    #[test]
+    fn virtual_bus_missing_peer() {
+        let bus: VirtualI2cBus<2> = VirtualI2cBus::new();
+        let port = bus.attach(MockI2cHardware::new(), 0x10).unwrap();
+        let mut server: Server<_, 16> = Server::new(Eid(8), 0, I2cSender::new(port, 0x10, 0x42));
+
+        let req = server.req(42).unwrap();
+        assert!(server
+            .send(Some(req), 1, None, None, false, b"short")
+            .is_err());
+    }
+
+    // A PRoT on one bus with a BMC (static route) and a device (learned
+    // route): one routed I2cSender reaches both, and EIDs without a route on
+    // this binding are unreachable.
+    #[test]
+    fn routing_table_multi_peer() {
+        const BUS: BindingId = BindingId(0);
+        const ADDR_PROT: u8 = 0x10;
+        const ADDR_BMC: u8 = 0x20;
+        const ADDR_DEV: u8 = 0x30;
+        const EID_PROT: u8 = 8;
+        const EID_BMC: u8 = 0x1D;
+        const EID_DEV: u8 = 0x42;
+        const EID_OTHER_BUS: u8 = 0x50;
+        const MSG_TYPE: u8 = 0x05; // SPDM
+
+        let routes = RefCell::new(RoutingTable::new());
+        routes
+            .borrow_mut()
+            .add_static(EID_BMC..=EID_BMC, Route::new(BUS, ADDR_BMC))
+            .unwrap();
+        routes
+            .borrow_mut()
+            .add_static(
+                EID_OTHER_BUS..=EID_OTHER_BUS,
+                Route::new(BindingId(1), 0x60),
+            )
+            .unwrap();
+
+        let bus: VirtualI2cBus<3> = VirtualI2cBus::new();
+        let mut port_prot = bus.attach(MockI2cHardware::new(), ADDR_PROT).unwrap();
+        let mut port_bmc = bus.attach(MockI2cHardware::new(), ADDR_BMC).unwrap();
+        let mut port_dev = bus.attach(MockI2cHardware::new(), ADDR_DEV).unwrap();
+
+        let mut prot: Server<_, 16> = Server::new(
+            Eid(EID_PROT),
+            0,
+            I2cSender::with_routes(port_prot.clone(), ADDR_PROT, BUS, &routes),
+        );
+        let mut bmc: Server<_, 16> = Server::new(
+            Eid(EID_BMC),
+            0,
+            I2cSender::new(port_bmc.clone(), ADDR_BMC, ADDR_PROT),
+        );
+        let mut dev: Server<_, 16> = Server::new(
+            Eid(EID_DEV),
+            0,
+            I2cSender::new(port_dev.clone(), ADDR_DEV, ADDR_PROT),
+        );
+        let listener_prot = prot.listener(MSG_TYPE).unwrap();
+        let listener_bmc = bmc.listener(MSG_TYPE).unwrap();
+        let listener_dev = dev.listener(MSG_TYPE).unwrap();
+        let mut buf = [0u8; 64];
+
+        // The device is not known yet
+        let req_dev = prot.req(EID_DEV).unwrap();
+        assert!(prot
+            .send(Some(req_dev), MSG_TYPE, None, None, false, b"too early")
+            .is_err());
+
+        // Device -> PRoT teaches the PRoT the device's address
+        let req = dev.req(EID_PROT).unwrap();
+        dev.send(Some(req), MSG_TYPE, None, None, false, b"hello from dev")
+            .unwrap();
+        let source = deliver_learning(&mut port_prot, ADDR_PROT, &mut prot, Some((&routes, BUS)));
+        assert_eq!(source, ADDR_DEV);
+        assert_eq!(
+            routes.borrow().lookup(EID_DEV),
+            Some(Route::new(BUS, ADDR_DEV))
+        );
+        let meta = prot.try_recv(listener_prot, &mut buf).unwrap();
+        assert_eq!(meta.remote_eid, EID_DEV);
+
+        // PRoT -> device over the learned route
+        prot.send(Some(req_dev), MSG_TYPE, None, None, false, b"to dev")
+            .unwrap();
+        assert_eq!(deliver(&mut port_dev, ADDR_DEV, &mut dev), ADDR_PROT);
+        let meta = dev.try_recv(listener_dev, &mut buf).unwrap();
+        assert_eq!(&buf[..meta.payload_size], b"to dev");
+
+        // PRoT -> BMC over the static route
+        let req_bmc = prot.req(EID_BMC).unwrap();
+        prot.send(Some(req_bmc), MSG_TYPE, None, None, false, b"to bmc")
+            .unwrap();
+        assert_eq!(deliver(&mut port_bmc, ADDR_BMC, &mut bmc), ADDR_PROT);
+        let meta = bmc.try_recv(listener_bmc, &mut buf).unwrap();
+        assert_eq!(meta.remote_eid, EID_PROT);
+        assert_eq!(&buf[..meta.payload_size], b"to bmc");
+
+        // Nothing leaked onto the device's buffer
+        assert_eq!(port_dev.read_slave_buffer(&mut buf).unwrap(), 0);
+
+        // Routed over another binding: not this sender's bus
+        let req_other = prot.req(EID_OTHER_BUS).unwrap();
+        assert!(prot
+            .send(Some(req_other), MSG_TYPE, None, None, false, b"wrong bus")
+            .is_err());
+    }
 }
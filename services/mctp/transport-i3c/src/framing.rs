@@ -0,0 +1,139 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! DSP0233 private write framing.
+//!
+//! An MCTP packet travels as one I3C private write, followed by a PEC byte:
+//!
+//! ```text
+//! MCTP packet (header + payload) | PEC
+//! ```
+//!
+//! The PEC is the SMBus CRC-8 over the target address byte (dynamic address
+//! with the R/W bit clear) and the packet.
+
+/// Size of the MCTP transport header.
+pub const MCTP_HEADER_SIZE: usize = 4;
+/// Baseline MCTP transmission unit every I3C endpoint must accept.
+pub const MCTP_I3C_BASELINE_MTU: usize = 64;
+/// Largest MCTP payload per packet this binding sends.
+pub const MCTP_I3C_MAXMTU: usize = 251;
+/// Largest MCTP packet.
+pub const MAX_PACKET_SIZE: usize = MCTP_I3C_MAXMTU + MCTP_HEADER_SIZE;
+/// Largest private write: a packet plus its PEC.
+pub const MAX_FRAME_SIZE: usize = MAX_PACKET_SIZE + 1;
+
+/// SMBus CRC-8 polynomial (x^8 + x^2 + x + 1).
+const PEC_POLY: u8 = 0x07;
+
+/// PEC of a private write of `pkt` to dynamic address `addr`.
+pub fn pec(addr: u8, pkt: &[u8]) -> u8 {
+    let mut crc = 0u8;
+    for &byte in [addr << 1].iter().chain(pkt) {
+        crc ^= byte;
+        for _ in 0..8 {
+            crc = if crc & 0x80 != 0 {
+                (crc << 1) ^ PEC_POLY
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+/// Encode `pkt` for a private write to `addr` in `out`.
+///
+/// Returns the frame. Fails with `BadArgument` if `pkt` is shorter than an
+/// MCTP header or longer than [`MAX_PACKET_SIZE`], and `NoSpace` if `out` is
+/// too small.
+pub fn encode_frame<'a>(addr: u8, pkt: &[u8], out: &'a mut [u8]) -> Result<&'a [u8], mctp::Error> {
+    if !(MCTP_HEADER_SIZE..=MAX_PACKET_SIZE).contains(&pkt.len()) {
+        return Err(mctp::Error::BadArgument);
+    }
+    let frame = out.get_mut(..pkt.len() + 1).ok_or(mctp::Error::NoSpace)?;
+    let (body, pec_byte) = frame.split_at_mut(pkt.len());
+    body.copy_from_slice(pkt);
+    pec_byte[0] = pec(addr, pkt);
+    Ok(frame)
+}
+
+/// Check the PEC of a private write received at `own_addr` and strip it.
+///
+/// Returns the MCTP packet, or `InvalidInput` if the frame is too short to
+/// hold a header and PEC or the PEC does not match.
+pub fn decode_frame(own_addr: u8, frame: &[u8]) -> Result<&[u8], mctp::Error> {
+    if frame.len() <= MCTP_HEADER_SIZE {
+        return Err(mctp::Error::InvalidInput);
+    }
+    let (pkt, pec_byte) = frame.split_at(frame.len() - 1);
+    if pec(own_addr, pkt) != pec_byte[0] {
+        return Err(mctp::Error::InvalidInput);
+    }
+    Ok(pkt)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pec_check_value() {
+        // CRC-8/SMBUS check value over "123456789"; the address byte is
+        // folded in first, so address 0 with a leading zero byte leaves the
+        // CRC unchanged
+        assert_eq!(pec(0, b"123456789"), 0xF4);
+    }
+
+    #[test]
+    fn encode_decode_roundtrip() {
+        let pkt = [0x01, 0x21, 0x08, 0xC8, 0x05, 0x10];
+        let mut out = [0u8; MAX_FRAME_SIZE];
+        let frame = encode_frame(0x21, &pkt, &mut out).unwrap();
+
+        assert_eq!(frame.len(), pkt.len() + 1);
+        assert_eq!(&frame[..pkt.len()], pkt);
+        assert_eq!(decode_frame(0x21, frame).unwrap(), pkt);
+    }
+
+    #[test]
+    fn decode_rejects_bad_frames() {
+        let pkt = [0x01, 0x21, 0x08, 0xC8, 0x05];
+        let mut out = [0u8; MAX_FRAME_SIZE];
+        let len = encode_frame(0x21, &pkt, &mut out).unwrap().len();
+
+        // Addressed to a different target
+        assert!(matches!(
+            decode_frame(0x22, &out[..len]),
+            Err(mctp::Error::InvalidInput)
+        ));
+        // Corrupted byte
+        out[2] ^= 0x01;
+        assert!(matches!(
+            decode_frame(0x21, &out[..len]),
+            Err(mctp::Error::InvalidInput)
+        ));
+        // Header only, no PEC
+        assert!(matches!(
+            decode_frame(0x21, &pkt[..4]),
+            Err(mctp::Error::InvalidInput)
+        ));
+    }
+
+    #[test]
+    fn encode_rejects_bad_lengths() {
+        let mut out = [0u8; MAX_FRAME_SIZE];
+        assert!(matches!(
+            encode_frame(0x21, &[0x01, 0x21, 0x08], &mut out),
+            Err(mctp::Error::BadArgument)
+        ));
+        assert!(matches!(
+            encode_frame(0x21, &[0u8; MAX_PACKET_SIZE + 1], &mut out),
+            Err(mctp::Error::BadArgument)
+        ));
+        assert!(matches!(
+            encode_frame(0x21, &[0u8; 8], &mut out[..8]),
+            Err(mctp::Error::NoSpace)
+        ));
+    }
+}
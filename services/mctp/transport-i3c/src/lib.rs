@@ -0,0 +1,36 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! # MCTP over I3C Transport Binding
+//!
+//! This crate provides the DSP0233 I3C transport binding for the MCTP
+//! server.
+//!
+//! It implements [`mctp_lib::Sender`] for outbound packets, sent as private
+//! writes with a PEC, and provides [`MctpI3cReceiver`] for decoding private
+//! writes received in the target role into MCTP packets.
+//!
+//! ## Current I3C seam
+//!
+//! - Both directions are built on the `openprot_hal_blocking::i3c`
+//!   controller and target traits; `openprot_platform_mock::i3c` provides a
+//!   virtual bus for host tests.
+//! - The sender acts as the active controller. Targets sending back through
+//!   in-band interrupts and private reads are not supported yet.
+//! - Framing and PEC live in [`framing`].
+//!
+//! ## Addressing
+//!
+//! [`I3cSender::new`] sends every packet to one target. For several targets,
+//! give [`I3cSender::with_routes`] an `openprot_mctp_routing::RoutingTable`
+//! mapping each EID to its target's dynamic address.
+
+#![no_std]
+#![warn(missing_docs)]
+
+pub mod framing;
+mod receiver;
+mod sender;
+
+pub use receiver::MctpI3cReceiver;
+pub use sender::I3cSender;
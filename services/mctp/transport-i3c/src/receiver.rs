@@ -0,0 +1,147 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! I3C MCTP receiver — inbound transport binding.
+//!
+//! Turns private writes received in the target role into raw MCTP packets
+//! that can be fed to `Server::inbound()`.
+
+use openprot_hal_blocking::i3c::I3cTarget;
+
+use crate::framing::{decode_frame, MAX_FRAME_SIZE};
+
+/// Decodes I3C private writes into raw MCTP packets.
+///
+/// Writes with a bad PEC, writes too short to hold a packet and writes that
+/// arrive before a dynamic address is assigned are dropped and counted in
+/// [`dropped`](Self::dropped). One instance should exist per I3C target
+/// carrying MCTP traffic.
+#[derive(Debug)]
+pub struct MctpI3cReceiver {
+    frame: [u8; MAX_FRAME_SIZE],
+    dropped: u32,
+}
+
+impl MctpI3cReceiver {
+    /// Create a new receiver.
+    pub const fn new() -> Self {
+        Self {
+            frame: [0; MAX_FRAME_SIZE],
+            dropped: 0,
+        }
+    }
+
+    /// Number of private writes dropped so far.
+    pub fn dropped(&self) -> u32 {
+        self.dropped
+    }
+
+    /// Decode one private write received at dynamic address `own_addr`.
+    ///
+    /// Validates and strips the PEC, returning the raw MCTP packet
+    /// (suitable for `Server::inbound()`).
+    pub fn decode(own_addr: u8, frame: &[u8]) -> Result<&[u8], mctp::Error> {
+        decode_frame(own_addr, frame)
+    }
+
+    /// Drain the private writes `target` has received, calling `on_packet`
+    /// for each valid MCTP packet.
+    ///
+    /// Returns the number of packets delivered. The caller polls again when
+    /// the target signals a new private write.
+    pub fn poll<T: I3cTarget>(
+        &mut self,
+        target: &mut T,
+        mut on_packet: impl FnMut(&[u8]),
+    ) -> Result<usize, T::Error> {
+        let mut delivered = 0;
+        while let Some(len) = target.take_private_write(&mut self.frame)? {
+            let decoded = target
+                .dynamic_address()
+                .ok_or(mctp::Error::InvalidInput)
+                .and_then(|addr| decode_frame(addr, &self.frame[..len]));
+            match decoded {
+                Ok(pkt) => {
+                    on_packet(pkt);
+                    delivered += 1;
+                }
+                Err(_) => self.dropped += 1,
+            }
+        }
+        Ok(delivered)
+    }
+}
+
+impl Default for MctpI3cReceiver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use openprot_platform_mock::i3c::MockI3cDevice;
+
+    use super::*;
+    use crate::framing::encode_frame;
+
+    const OWN_ADDR: u8 = 0x21;
+    const PKT: [u8; 6] = [0x01, 0x09, 0x08, 0xC8, 0x05, 0x10];
+
+    fn frame(addr: u8, pkt: &[u8]) -> ([u8; MAX_FRAME_SIZE], usize) {
+        let mut out = [0u8; MAX_FRAME_SIZE];
+        let len = encode_frame(addr, pkt, &mut out).unwrap().len();
+        (out, len)
+    }
+
+    #[test]
+    fn poll_delivers_valid_writes() {
+        let mut target = MockI3cDevice::with_dynamic_address(OWN_ADDR);
+        let (good, len) = frame(OWN_ADDR, &PKT);
+        let (wrong_addr, wrong_len) = frame(0x22, &PKT);
+        target.inject_private_write(&good[..len]);
+        target.inject_private_write(&wrong_addr[..wrong_len]);
+        target.inject_private_write(&PKT[..4]);
+        target.inject_private_write(&good[..len]);
+
+        let mut rx = MctpI3cReceiver::new();
+        let mut packets = 0;
+        let delivered = rx
+            .poll(&mut target, |pkt| {
+                assert_eq!(pkt, PKT);
+                packets += 1;
+            })
+            .unwrap();
+        assert_eq!((delivered, packets), (2, 2));
+        assert_eq!(rx.dropped(), 2);
+        assert_eq!(target.pending_writes(), 0);
+    }
+
+    #[test]
+    fn poll_drops_writes_without_dynamic_address() {
+        let mut target = MockI3cDevice::new();
+        let (good, len) = frame(OWN_ADDR, &PKT);
+        target.inject_private_write(&good[..len]);
+
+        let mut rx = MctpI3cReceiver::new();
+        assert_eq!(rx.poll(&mut target, |_| panic!("unexpected packet")), Ok(0));
+        assert_eq!(rx.dropped(), 1);
+    }
+
+    #[test]
+    fn poll_reports_target_errors() {
+        let mut target = MockI3cDevice::new_failing();
+        let mut rx = MctpI3cReceiver::new();
+        assert!(rx.poll(&mut target, |_| {}).is_err());
+    }
+
+    #[test]
+    fn decode_strips_pec() {
+        let (good, len) = frame(OWN_ADDR, &PKT);
+        assert_eq!(
+            MctpI3cReceiver::decode(OWN_ADDR, &good[..len]).unwrap(),
+            PKT
+        );
+        assert!(MctpI3cReceiver::decode(OWN_ADDR, &good[..len - 1]).is_err());
+    }
+}
@@ -0,0 +1,315 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! I3C MCTP sender — outbound transport binding.
+//!
+//! Fragments outbound messages to the I3C MTU and sends each packet, with
+//! its PEC, as one private write from the controller role.
+
+use core::cell::RefCell;
+
+use mctp::Result;
+use openprot_hal_blocking::i3c::{I3cController, I3cError, I3cErrorKind};
+use openprot_mctp_routing::{dest_eid, BindingId, RoutingTable};
+
+use crate::framing::{
+    encode_frame, MAX_FRAME_SIZE, MAX_PACKET_SIZE, MCTP_I3C_BASELINE_MTU, MCTP_I3C_MAXMTU,
+};
+
+/// I3C MCTP sender.
+///
+/// Implements `mctp_lib::Sender` over an `openprot_hal_blocking`
+/// [`I3cController`]. Packets carry [`MCTP_I3C_BASELINE_MTU`] bytes of
+/// payload until a larger MTU is agreed with the targets and set with
+/// [`set_mtu`](Self::set_mtu).
+///
+/// The destination dynamic address comes either from a single static peer
+/// ([`new`](Self::new)) or from a shared [`RoutingTable`]
+/// ([`with_routes`](Self::with_routes)).
+pub struct I3cSender<'r, C: I3cController> {
+    i3c: C,
+    mtu: usize,
+    /// Destination of every packet when no routing table is attached.
+    target_addr: u8,
+    /// Routing table and the binding this sender drives.
+    routes: Option<(&'r RefCell<RoutingTable>, BindingId)>,
+}
+
+impl<C: I3cController> I3cSender<'static, C> {
+    /// Create a new I3C sender for a single target.
+    ///
+    /// * `i3c` - I3C controller for private writes
+    /// * `target_addr` - Dynamic address of the target (7-bit)
+    pub fn new(i3c: C, target_addr: u8) -> Self {
+        Self {
+            i3c,
+            mtu: MCTP_I3C_BASELINE_MTU,
+            target_addr,
+            routes: None,
+        }
+    }
+}
+
+impl<'r, C: I3cController> I3cSender<'r, C> {
+    /// Create a new I3C sender that resolves destinations through `routes`.
+    ///
+    /// * `i3c` - I3C controller for private writes
+    /// * `binding` - Binding ID of this bus in `routes`
+    /// * `routes` - Routing table mapping EIDs to dynamic addresses
+    ///
+    /// Each packet goes to the dynamic address routed for its destination
+    /// EID. Sending fails with `Unreachable` if the EID has no route or is
+    /// routed over a different binding.
+    pub fn with_routes(i3c: C, binding: BindingId, routes: &'r RefCell<RoutingTable>) -> Self {
+        Self {
+            i3c,
+            mtu: MCTP_I3C_BASELINE_MTU,
+            target_addr: 0,
+            routes: Some((routes, binding)),
+        }
+    }
+
+    /// Set the MTU used to fragment outbound messages.
+    ///
+    /// Every target must accept private writes of `mtu` plus the MCTP header
+    /// and PEC, i.e. its maximum write length must be at least `mtu + 5`.
+    /// Fails with `BadArgument` outside [`MCTP_I3C_BASELINE_MTU`]..=
+    /// [`MCTP_I3C_MAXMTU`].
+    pub fn set_mtu(&mut self, mtu: usize) -> Result<()> {
+        if !(MCTP_I3C_BASELINE_MTU..=MCTP_I3C_MAXMTU).contains(&mtu) {
+            return Err(mctp::Error::BadArgument);
+        }
+        self.mtu = mtu;
+        Ok(())
+    }
+
+    /// Resolve the dynamic address for a fragmented MCTP packet.
+    fn resolve(&self, pkt: &[u8]) -> Result<u8> {
+        let Some((routes, binding)) = self.routes else {
+            return Ok(self.target_addr);
+        };
+        let eid = dest_eid(pkt).ok_or(mctp::Error::InvalidInput)?;
+        match routes.borrow().lookup(eid) {
+            Some(route) if route.binding == binding => Ok(route.addr),
+            _ => {
+                pw_log::error!("no I3C route to EID 0x{:02x}", eid as u32);
+                Err(mctp::Error::Unreachable)
+            }
+        }
+    }
+}
+
+impl<C: I3cController> mctp_lib::Sender for I3cSender<'_, C> {
+    fn send_vectored(
+        &mut self,
+        mut fragmenter: mctp_lib::fragment::Fragmenter,
+        payload: &[&[u8]],
+    ) -> Result<mctp::Tag> {
+        loop {
+            let mut pkt = [0u8; MAX_PACKET_SIZE];
+            match fragmenter.fragment_vectored(payload, &mut pkt) {
+                mctp_lib::fragment::SendOutput::Packet(p) => {
+                    let addr = self.resolve(p)?;
+                    let mut out = [0u8; MAX_FRAME_SIZE];
+                    let frame = encode_frame(addr, p, &mut out)?;
+                    if let Err(err) = self.i3c.private_write(addr, frame) {
+                        let kind_code: u8 = match err.kind() {
+                            I3cErrorKind::AddressNack => 0,
+                            I3cErrorKind::Overrun => 1,
+                            I3cErrorKind::Parity => 2,
+                            I3cErrorKind::NoDynamicAddress => 3,
+                            I3cErrorKind::Bus => 4,
+                            I3cErrorKind::Other => 5,
+                            _ => 0xFF,
+                        };
+                        pw_log::error!(
+                            "I3C write to 0x{:02x} failed: kind=0x{:02x}",
+                            addr as u32,
+                            kind_code as u32
+                        );
+                        return Err(mctp::Error::TxFailure);
+                    }
+                }
+                mctp_lib::fragment::SendOutput::Complete { tag, .. } => break Ok(tag),
+                mctp_lib::fragment::SendOutput::Error { err, .. } => break Err(err),
+            }
+        }
+    }
+
+    fn get_mtu(&self) -> usize {
+        self.mtu
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use std::cell::RefCell;
+    use std::vec::Vec;
+
+    use mctp::Eid;
+    use openprot_mctp_routing::{BindingId, Route, RoutingTable};
+    use openprot_mctp_server::Server;
+    use openprot_platform_mock::i3c::{MockI3cDevice, VirtualI3cBus, VirtualI3cPort};
+
+    use super::I3cSender;
+    use crate::framing::{MCTP_I3C_BASELINE_MTU, MCTP_I3C_MAXMTU};
+    use crate::MctpI3cReceiver;
+
+    const MSG_TYPE: u8 = 0x05; // SPDM
+
+    // Hand the private writes the virtual bus left at `port` to `server`,
+    // as the platform's target-mode interrupt handler would. Returns the
+    // number of packets delivered.
+    fn deliver<S: mctp_lib::Sender, const N: usize, const B: usize>(
+        port: &mut VirtualI3cPort<'_, B>,
+        server: &mut Server<S, N>,
+    ) -> usize {
+        let mut rx = MctpI3cReceiver::new();
+        let delivered = rx
+            .poll(port, |pkt| server.inbound(pkt).expect("inbound failed"))
+            .expect("target read failed");
+        assert_eq!(rx.dropped(), 0);
+        delivered
+    }
+
+    // A message longer than the baseline MTU crosses the virtual bus as
+    // several private writes and is reassembled by the target's server.
+    #[test]
+    fn virtual_bus_roundtrip() {
+        const EID_CTRL: u8 = 8;
+        const EID_TARGET: u8 = 42;
+        const TARGET_ADDR: u8 = 0x21;
+
+        let bus: VirtualI3cBus<2> = VirtualI3cBus::new();
+        let port_ctrl = bus.attach(MockI3cDevice::new()).unwrap();
+        let mut port_target = bus
+            .attach(MockI3cDevice::with_dynamic_address(TARGET_ADDR))
+            .unwrap();
+
+        let mut ctrl: Server<_, 16> =
+            Server::new(Eid(EID_CTRL), 0, I3cSender::new(port_ctrl, TARGET_ADDR));
+        let mut target: Server<_, 16> =
+            Server::new(Eid(EID_TARGET), 0, I3cSender::new(port_target.clone(), 0));
+        let listener = target.listener(MSG_TYPE).unwrap();
+
+        // 150 payload bytes plus the message type byte: 64 + 64 + 23
+        let payload: Vec<u8> = (0..150u8).collect();
+        let req = ctrl.req(EID_TARGET).unwrap();
+        ctrl.send(Some(req), MSG_TYPE, None, None, false, &payload)
+            .unwrap();
+        assert_eq!(port_target.device().pending_writes(), 3);
+        assert_eq!(deliver(&mut port_target, &mut target), 3);
+
+        let mut buf = [0u8; 256];
+        let meta = target
+            .try_recv(listener, &mut buf)
+            .expect("target received nothing");
+        assert_eq!(meta.msg_type, MSG_TYPE);
+        assert_eq!(meta.remote_eid, EID_CTRL);
+        assert_eq!(&buf[..meta.payload_size], &payload[..]);
+    }
+
+    // A larger MTU produces fewer, longer writes; a target whose maximum
+    // write length is too small rejects them.
+    #[test]
+    fn mtu_and_max_write_length() {
+        const TARGET_ADDR: u8 = 0x21;
+
+        let bus: VirtualI3cBus<2> = VirtualI3cBus::new();
+        let port_ctrl = bus.attach(MockI3cDevice::new()).unwrap();
+        let mut port_target = bus
+            .attach(MockI3cDevice::with_dynamic_address(TARGET_ADDR))
+            .unwrap();
+
+        let mut sender = I3cSender::new(port_ctrl, TARGET_ADDR);
+        assert!(sender.set_mtu(MCTP_I3C_BASELINE_MTU - 1).is_err());
+        assert!(sender.set_mtu(MCTP_I3C_MAXMTU + 1).is_err());
+        sender.set_mtu(128).unwrap();
+        let mut ctrl: Server<_, 16> = Server::new(Eid(8), 0, sender);
+        let mut target: Server<_, 16> =
+            Server::new(Eid(42), 0, I3cSender::new(port_target.clone(), 0));
+        let listener = target.listener(MSG_TYPE).unwrap();
+
+        let payload = [0x5A; 150];
+        let req = ctrl.req(42).unwrap();
+        ctrl.send(Some(req), MSG_TYPE, None, None, false, &payload)
+            .unwrap();
+        assert_eq!(deliver(&mut port_target, &mut target), 2);
+        let mut buf = [0u8; 256];
+        let meta = target.try_recv(listener, &mut buf).unwrap();
+        assert_eq!(&buf[..meta.payload_size], &payload[..]);
+
+        // 128 + 4 + 1 bytes no longer fit
+        port_target.device().set_max_write_length(128);
+        let req = ctrl.req(42).unwrap();
+        assert!(ctrl
+            .send(Some(req), MSG_TYPE, None, None, false, &payload)
+            .is_err());
+        assert_eq!(port_target.device().pending_writes(), 0);
+    }
+
+    // With a routing table, each EID is sent to its own target's dynamic
+    // address; unknown EIDs and absent targets fail to send.
+    #[test]
+    fn routing_table_multi_target() {
+        const BUS: BindingId = BindingId(0);
+        const ADDR_A: u8 = 0x21;
+        const ADDR_B: u8 = 0x22;
+        const EID_A: u8 = 0x20;
+        const EID_B: u8 = 0x30;
+        const EID_GONE: u8 = 0x40;
+
+        let routes = RefCell::new(RoutingTable::new());
+        for (eid, addr) in [(EID_A, ADDR_A), (EID_B, ADDR_B), (EID_GONE, 0x23)] {
+            routes
+                .borrow_mut()
+                .add_static(eid..=eid, Route::new(BUS, addr))
+                .unwrap();
+        }
+
+        let bus: VirtualI3cBus<3> = VirtualI3cBus::new();
+        let port_ctrl = bus.attach(MockI3cDevice::new()).unwrap();
+        let mut port_a = bus
+            .attach(MockI3cDevice::with_dynamic_address(ADDR_A))
+            .unwrap();
+        let mut port_b = bus
+            .attach(MockI3cDevice::with_dynamic_address(ADDR_B))
+            .unwrap();
+
+        let mut ctrl: Server<_, 16> =
+            Server::new(Eid(8), 0, I3cSender::with_routes(port_ctrl, BUS, &routes));
+        let mut target_a: Server<_, 16> =
+            Server::new(Eid(EID_A), 0, I3cSender::new(port_a.clone(), 0));
+        let mut target_b: Server<_, 16> =
+            Server::new(Eid(EID_B), 0, I3cSender::new(port_b.clone(), 0));
+        let listener_a = target_a.listener(MSG_TYPE).unwrap();
+        let listener_b = target_b.listener(MSG_TYPE).unwrap();
+        let mut buf = [0u8; 64];
+
+        let req = ctrl.req(EID_B).unwrap();
+        ctrl.send(Some(req), MSG_TYPE, None, None, false, b"to B")
+            .unwrap();
+        let req = ctrl.req(EID_A).unwrap();
+        ctrl.send(Some(req), MSG_TYPE, None, None, false, b"to A")
+            .unwrap();
+
+        assert_eq!(deliver(&mut port_a, &mut target_a), 1);
+        let meta = target_a.try_recv(listener_a, &mut buf).unwrap();
+        assert_eq!(&buf[..meta.payload_size], b"to A");
+        assert_eq!(deliver(&mut port_b, &mut target_b), 1);
+        let meta = target_b.try_recv(listener_b, &mut buf).unwrap();
+        assert_eq!(&buf[..meta.payload_size], b"to B");
+
+        // No route
+        let req = ctrl.req(0x50).unwrap();
+        assert!(ctrl
+            .send(Some(req), MSG_TYPE, None, None, false, b"lost")
+            .is_err());
+        // Routed, but no target answers at 0x23
+        let req = ctrl.req(EID_GONE).unwrap();
+        assert!(ctrl
+            .send(Some(req), MSG_TYPE, None, None, false, b"lost")
+            .is_err());
+    }
+}
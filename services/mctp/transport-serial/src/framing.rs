@@ -0,0 +1,180 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! DSP0253 serial framing.
+//!
+//! A frame carries one MCTP packet:
+//!
+//! ```text
+//! 0x7E | revision (0x01) | byte count | packet (escaped) | FCS high | FCS low | 0x7E
+//! ```
+//!
+//! Inside the packet, `0x7E` and `0x7D` are sent as `0x7D` followed by the
+//! byte XOR `0x20`. The byte count is the unescaped packet length. The FCS is
+//! the RFC 1662 16-bit FCS over the revision, the byte count and the
+//! unescaped packet.
+
+/// Frame delimiter.
+pub const FRAME_FLAG: u8 = 0x7E;
+/// Escape byte.
+pub const ESCAPE: u8 = 0x7D;
+/// Value XORed into an escaped byte.
+const ESCAPE_XOR: u8 = 0x20;
+/// Serial binding header revision.
+pub const SERIAL_REVISION: u8 = 0x01;
+
+/// Size of the MCTP transport header.
+pub const MCTP_HEADER_SIZE: usize = 4;
+/// Largest MCTP packet: the byte count field is one byte.
+pub const MAX_PACKET_SIZE: usize = 0xFF;
+/// Maximum MCTP payload per packet over serial.
+pub const MCTP_SERIAL_MAXMTU: usize = MAX_PACKET_SIZE - MCTP_HEADER_SIZE;
+/// Largest encoded frame: every packet byte escaped.
+pub const MAX_FRAME_SIZE: usize = 3 + 2 * MAX_PACKET_SIZE + 3;
+
+/// RFC 1662 FCS initial value.
+const FCS_INIT: u16 = 0xFFFF;
+/// RFC 1662 FCS polynomial, bit-reversed.
+const FCS_POLY: u16 = 0x8408;
+
+/// Running RFC 1662 16-bit frame check sequence.
+#[derive(Debug, Clone, Copy)]
+pub struct Fcs(u16);
+
+impl Fcs {
+    /// Start a new FCS.
+    pub const fn new() -> Self {
+        Self(FCS_INIT)
+    }
+
+    /// Add `data` to the FCS.
+    pub fn update(&mut self, data: &[u8]) {
+        for &byte in data {
+            self.0 ^= u16::from(byte);
+            for _ in 0..8 {
+                self.0 = if self.0 & 1 != 0 {
+                    (self.0 >> 1) ^ FCS_POLY
+                } else {
+                    self.0 >> 1
+                };
+            }
+        }
+    }
+
+    /// Final FCS value.
+    pub const fn finish(self) -> u16 {
+        !self.0
+    }
+}
+
+impl Default for Fcs {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// FCS of a frame carrying `pkt`.
+pub fn frame_fcs(pkt: &[u8]) -> u16 {
+    let mut fcs = Fcs::new();
+    fcs.update(&[SERIAL_REVISION, pkt.len() as u8]);
+    fcs.update(pkt);
+    fcs.finish()
+}
+
+/// Encode `pkt` into a serial frame in `out`.
+///
+/// Returns the frame length. Fails with `BadArgument` if `pkt` is shorter
+/// than an MCTP header or longer than [`MAX_PACKET_SIZE`], and `NoSpace` if
+/// `out` is too small.
+pub fn encode_frame(pkt: &[u8], out: &mut [u8]) -> Result<usize, mctp::Error> {
+    if !(MCTP_HEADER_SIZE..=MAX_PACKET_SIZE).contains(&pkt.len()) {
+        return Err(mctp::Error::BadArgument);
+    }
+
+    let mut len = 0;
+    let mut put = |byte: u8| -> Result<(), mctp::Error> {
+        *out.get_mut(len).ok_or(mctp::Error::NoSpace)? = byte;
+        len += 1;
+        Ok(())
+    };
+
+    put(FRAME_FLAG)?;
+    put(SERIAL_REVISION)?;
+    put(pkt.len() as u8)?;
+    for &byte in pkt {
+        if byte == FRAME_FLAG || byte == ESCAPE {
+            put(ESCAPE)?;
+            put(byte ^ ESCAPE_XOR)?;
+        } else {
+            put(byte)?;
+        }
+    }
+    let [fcs_high, fcs_low] = frame_fcs(pkt).to_be_bytes();
+    put(fcs_high)?;
+    put(fcs_low)?;
+    put(FRAME_FLAG)?;
+    Ok(len)
+}
+
+/// Undo the escaping of one packet byte that followed [`ESCAPE`].
+pub(crate) const fn unescape(byte: u8) -> u8 {
+    byte ^ ESCAPE_XOR
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fcs_check_value() {
+        // CRC-16/IBM-SDLC (the RFC 1662 FCS) check value
+        let mut fcs = Fcs::new();
+        fcs.update(b"123456789");
+        assert_eq!(fcs.finish(), 0x906E);
+    }
+
+    #[test]
+    fn encode_plain_packet() {
+        let pkt = [0x01, 0x09, 0x08, 0xC8, 0x05, 0x10];
+        let mut out = [0u8; MAX_FRAME_SIZE];
+        let len = encode_frame(&pkt, &mut out).unwrap();
+
+        let [fcs_high, fcs_low] = frame_fcs(&pkt).to_be_bytes();
+        assert_eq!(
+            &out[..len],
+            [0x7E, 0x01, 0x06, 0x01, 0x09, 0x08, 0xC8, 0x05, 0x10, fcs_high, fcs_low, 0x7E]
+        );
+    }
+
+    #[test]
+    fn encode_escapes_flag_and_escape_bytes() {
+        let pkt = [0x01, 0x09, 0x08, 0xC8, 0x7E, 0x7D];
+        let mut out = [0u8; MAX_FRAME_SIZE];
+        let len = encode_frame(&pkt, &mut out).unwrap();
+
+        assert_eq!(len, pkt.len() + 2 + 6);
+        assert_eq!(
+            &out[3..11],
+            [0x01, 0x09, 0x08, 0xC8, 0x7D, 0x5E, 0x7D, 0x5D]
+        );
+        // The byte count is the unescaped length
+        assert_eq!(out[2], 6);
+    }
+
+    #[test]
+    fn encode_rejects_bad_lengths() {
+        let mut out = [0u8; MAX_FRAME_SIZE];
+        assert!(matches!(
+            encode_frame(&[0x01, 0x09, 0x08], &mut out),
+            Err(mctp::Error::BadArgument)
+        ));
+        assert!(matches!(
+            encode_frame(&[0u8; MAX_PACKET_SIZE + 1], &mut out),
+            Err(mctp::Error::BadArgument)
+        ));
+        assert!(matches!(
+            encode_frame(&[0u8; 8], &mut out[..10]),
+            Err(mctp::Error::NoSpace)
+        ));
+    }
+}
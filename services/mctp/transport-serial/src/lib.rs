@@ -0,0 +1,31 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! # MCTP over Serial Transport Binding
+//!
+//! This crate provides the DSP0253 serial transport binding for the MCTP
+//! server.
+//!
+//! It implements [`mctp_lib::Sender`] for outbound packets and provides
+//! [`MctpSerialReceiver`] for decoding an inbound UART byte stream into
+//! MCTP packets for `Server::inbound()`.
+//!
+//! ## Current USART seam
+//!
+//! - Both directions are built on `usart_api::backend::UsartBackend`, so
+//!   the binding runs over the AST10x0 UART backend on target and
+//!   `LinuxUsart` on the host.
+//! - Inbound bytes are drained with `try_read`; the platform re-arms the
+//!   `RX_DATA_AVAILABLE` interrupt and calls [`MctpSerialReceiver::poll`]
+//!   again when it fires.
+//! - Framing, escaping and FCS live in [`framing`].
+
+#![no_std]
+#![warn(missing_docs)]
+
+pub mod framing;
+mod receiver;
+mod sender;
+
+pub use receiver::MctpSerialReceiver;
+pub use sender::SerialSender;
@@ -0,0 +1,281 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! Serial MCTP receiver — inbound transport binding.
+//!
+//! Reassembles DSP0253 frames from a UART byte stream into raw MCTP packets
+//! that can be fed to `Server::inbound()`.
+
+use usart_api::backend::{BackendError, UsartBackend};
+
+use crate::framing::{
+    frame_fcs, unescape, ESCAPE, FRAME_FLAG, MAX_PACKET_SIZE, MCTP_HEADER_SIZE, SERIAL_REVISION,
+};
+
+/// Bytes drained from the UART per `try_read` in [`MctpSerialReceiver::poll`].
+const READ_CHUNK: usize = 64;
+
+/// Frame decoder state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    /// Waiting for an opening flag.
+    Idle,
+    /// After a flag; expecting the revision (or another flag).
+    Revision,
+    /// Expecting the byte count.
+    Count,
+    /// Receiving packet bytes.
+    Data,
+    /// Receiving a packet byte after an escape.
+    Escaped,
+    /// Expecting the FCS high byte.
+    FcsHigh,
+    /// Expecting the FCS low byte.
+    FcsLow(u8),
+    /// Expecting the closing flag.
+    End(u16),
+}
+
+/// Decodes a serial byte stream into raw MCTP packets.
+///
+/// Bytes may arrive in any chunking. Frames with a bad revision, length or
+/// FCS are dropped and counted in [`dropped`](Self::dropped); the decoder
+/// resynchronises on the next flag. One instance should exist per UART
+/// carrying MCTP traffic.
+#[derive(Debug)]
+pub struct MctpSerialReceiver {
+    state: State,
+    /// Unescaped packet length from the byte count field.
+    expected: usize,
+    /// Packet bytes received so far.
+    len: usize,
+    packet: [u8; MAX_PACKET_SIZE],
+    dropped: u32,
+}
+
+impl MctpSerialReceiver {
+    /// Create a receiver waiting for the first frame.
+    pub const fn new() -> Self {
+        Self {
+            state: State::Idle,
+            expected: 0,
+            len: 0,
+            packet: [0; MAX_PACKET_SIZE],
+            dropped: 0,
+        }
+    }
+
+    /// Number of frames dropped so far.
+    pub fn dropped(&self) -> u32 {
+        self.dropped
+    }
+
+    /// Feed one byte.
+    ///
+    /// Returns the raw MCTP packet (suitable for `Server::inbound()`) when
+    /// `byte` completes a valid frame.
+    pub fn push(&mut self, byte: u8) -> Option<&[u8]> {
+        match self.state {
+            State::Idle => {
+                if byte == FRAME_FLAG {
+                    self.state = State::Revision;
+                }
+            }
+            State::Revision => match byte {
+                // Back-to-back flags: idle line or a closing flag reused
+                FRAME_FLAG => {}
+                SERIAL_REVISION => self.state = State::Count,
+                _ => self.drop_frame(State::Idle),
+            },
+            State::Count => {
+                let count = usize::from(byte);
+                if count < MCTP_HEADER_SIZE {
+                    self.drop_frame(State::Idle);
+                } else {
+                    self.expected = count;
+                    self.len = 0;
+                    self.state = State::Data;
+                }
+            }
+            State::Data => match byte {
+                // An unescaped flag ends the frame early; treat it as the
+                // start of the next one
+                FRAME_FLAG => self.drop_frame(State::Revision),
+                ESCAPE => self.state = State::Escaped,
+                _ => self.store(byte),
+            },
+            State::Escaped => {
+                if byte == FRAME_FLAG {
+                    self.drop_frame(State::Revision);
+                } else {
+                    self.state = State::Data;
+                    self.store(unescape(byte));
+                }
+            }
+            State::FcsHigh => self.state = State::FcsLow(byte),
+            State::FcsLow(high) => self.state = State::End(u16::from_be_bytes([high, byte])),
+            State::End(fcs) => {
+                if byte != FRAME_FLAG {
+                    self.drop_frame(State::Idle);
+                    return None;
+                }
+                // The closing flag may also open the next frame
+                self.state = State::Revision;
+                let packet = &self.packet[..self.len];
+                if frame_fcs(packet) != fcs {
+                    self.dropped += 1;
+                    return None;
+                }
+                return Some(packet);
+            }
+        }
+        None
+    }
+
+    /// Feed a chunk of bytes, calling `on_packet` for each complete packet.
+    pub fn receive(&mut self, bytes: &[u8], mut on_packet: impl FnMut(&[u8])) {
+        for &byte in bytes {
+            if let Some(packet) = self.push(byte) {
+                on_packet(packet);
+            }
+        }
+    }
+
+    /// Drain the bytes `usart` has received, calling `on_packet` for each
+    /// complete packet.
+    ///
+    /// Reads until the UART reports `WouldBlock` and returns the number of
+    /// bytes consumed. The caller re-arms the RX interrupt and polls again
+    /// when it fires.
+    pub fn poll<B: UsartBackend>(
+        &mut self,
+        usart: &mut B,
+        mut on_packet: impl FnMut(&[u8]),
+    ) -> Result<usize, BackendError> {
+        let mut total = 0;
+        loop {
+            let mut chunk = [0u8; READ_CHUNK];
+            match usart.try_read(&mut chunk) {
+                Ok(0) | Err(BackendError::WouldBlock) => return Ok(total),
+                Ok(n) => {
+                    self.receive(&chunk[..n], &mut on_packet);
+                    total += n;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    fn store(&mut self, byte: u8) {
+        self.packet[self.len] = byte;
+        self.len += 1;
+        if self.len == self.expected {
+            self.state = State::FcsHigh;
+        }
+    }
+
+    fn drop_frame(&mut self, next: State) {
+        self.dropped += 1;
+        self.state = next;
+    }
+}
+
+impl Default for MctpSerialReceiver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use std::vec::Vec;
+
+    use super::*;
+    use crate::framing::{encode_frame, MAX_FRAME_SIZE};
+
+    fn frame(pkt: &[u8]) -> Vec<u8> {
+        let mut out = [0u8; MAX_FRAME_SIZE];
+        let len = encode_frame(pkt, &mut out).unwrap();
+        out[..len].to_vec()
+    }
+
+    fn decode_all(rx: &mut MctpSerialReceiver, bytes: &[u8]) -> Vec<Vec<u8>> {
+        let mut packets = Vec::new();
+        rx.receive(bytes, |pkt| packets.push(pkt.to_vec()));
+        packets
+    }
+
+    const PKT: [u8; 8] = [0x01, 0x09, 0x08, 0xC8, 0x05, 0x7E, 0x7D, 0x10];
+
+    #[test]
+    fn decodes_escaped_frame_byte_by_byte() {
+        let mut rx = MctpSerialReceiver::new();
+        let bytes = frame(&PKT);
+        let mut packets = Vec::new();
+        for &byte in &bytes {
+            if let Some(pkt) = rx.push(byte) {
+                packets.push(pkt.to_vec());
+            }
+        }
+        assert_eq!(packets, [PKT.to_vec()]);
+        assert_eq!(rx.dropped(), 0);
+    }
+
+    #[test]
+    fn back_to_back_frames_share_flags() {
+        let mut rx = MctpSerialReceiver::new();
+        let first = frame(&PKT);
+        let second = frame(&[0x01, 0x09, 0x08, 0xC8, 0x05]);
+
+        // Closing flag of the first frame doubles as the second's opener
+        let mut bytes = first.clone();
+        bytes.extend_from_slice(&second[1..]);
+        // Idle flags between frames are ignored
+        bytes.extend_from_slice(&[0x7E, 0x7E]);
+        bytes.extend_from_slice(&first);
+
+        let packets = decode_all(&mut rx, &bytes);
+        assert_eq!(packets.len(), 3);
+        assert_eq!(packets[1], [0x01, 0x09, 0x08, 0xC8, 0x05]);
+        assert_eq!(rx.dropped(), 0);
+    }
+
+    #[test]
+    fn bad_fcs_is_dropped() {
+        let mut rx = MctpSerialReceiver::new();
+        let mut bytes = frame(&PKT);
+        let fcs_low = bytes.len() - 2;
+        bytes[fcs_low] ^= 0x01;
+
+        assert!(decode_all(&mut rx, &bytes).is_empty());
+        assert_eq!(rx.dropped(), 1);
+
+        // The next good frame still decodes
+        assert_eq!(decode_all(&mut rx, &frame(&PKT)), [PKT.to_vec()]);
+    }
+
+    #[test]
+    fn resynchronises_after_garbage_and_truncation() {
+        let mut rx = MctpSerialReceiver::new();
+        let good = frame(&PKT);
+
+        let mut bytes = std::vec![0x00, 0x55, 0xAA];
+        // Truncated frame: cut off mid-packet by the next frame's flag
+        bytes.extend_from_slice(&good[..6]);
+        bytes.extend_from_slice(&good);
+        // Wrong revision
+        bytes.extend_from_slice(&[0x7E, 0x02, 0x05]);
+        bytes.extend_from_slice(&good);
+
+        assert_eq!(decode_all(&mut rx, &bytes), [PKT.to_vec(), PKT.to_vec()]);
+        assert_eq!(rx.dropped(), 2);
+    }
+
+    #[test]
+    fn short_byte_count_is_dropped() {
+        let mut rx = MctpSerialReceiver::new();
+        assert!(decode_all(&mut rx, &[0x7E, 0x01, 0x03, 0x01, 0x02, 0x03]).is_empty());
+        assert_eq!(rx.dropped(), 1);
+    }
+}
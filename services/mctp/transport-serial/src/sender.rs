@@ -0,0 +1,264 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! Serial MCTP sender — outbound transport binding.
+//!
+//! Fragments outbound messages and writes each packet to the UART as a
+//! DSP0253 frame.
+
+use mctp::Result;
+use usart_api::backend::UsartBackend;
+
+use crate::framing::{encode_frame, MAX_FRAME_SIZE, MAX_PACKET_SIZE, MCTP_SERIAL_MAXMTU};
+
+/// Serial MCTP sender.
+///
+/// Implements `mctp_lib::Sender` over any `usart_api` backend, such as the
+/// AST10x0 UART backend or `LinuxUsart` on the host. Serial is
+/// point-to-point, so there is no destination address to resolve.
+pub struct SerialSender<B: UsartBackend> {
+    usart: B,
+}
+
+impl<B: UsartBackend> SerialSender<B> {
+    /// Create a new serial sender writing to `usart`.
+    pub fn new(usart: B) -> Self {
+        Self { usart }
+    }
+
+    /// Write a whole frame, retrying partial writes.
+    fn write_frame(&mut self, mut frame: &[u8]) -> Result<()> {
+        while !frame.is_empty() {
+            match self.usart.write(frame) {
+                Ok(n) if n > 0 => frame = &frame[n.min(frame.len())..],
+                _ => {
+                    pw_log::error!(
+                        "UART write failed with 0x{:04x} bytes left",
+                        frame.len() as u32
+                    );
+                    return Err(mctp::Error::TxFailure);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<B: UsartBackend> mctp_lib::Sender for SerialSender<B> {
+    fn send_vectored(
+        &mut self,
+        mut fragmenter: mctp_lib::fragment::Fragmenter,
+        payload: &[&[u8]],
+    ) -> Result<mctp::Tag> {
+        loop {
+            let mut pkt = [0u8; MAX_PACKET_SIZE];
+            match fragmenter.fragment_vectored(payload, &mut pkt) {
+                mctp_lib::fragment::SendOutput::Packet(p) => {
+                    let mut frame = [0u8; MAX_FRAME_SIZE];
+                    let len = encode_frame(p, &mut frame)?;
+                    self.write_frame(&frame[..len])?;
+                }
+                mctp_lib::fragment::SendOutput::Complete { tag, .. } => break Ok(tag),
+                mctp_lib::fragment::SendOutput::Error { err, .. } => break Err(err),
+            }
+        }
+    }
+
+    fn get_mtu(&self) -> usize {
+        MCTP_SERIAL_MAXMTU
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use std::cell::RefCell;
+    use std::collections::VecDeque;
+    use std::rc::Rc;
+    use std::vec::Vec;
+
+    use mctp::Eid;
+    use openprot_mctp_server::Server;
+    use usart_api::backend::{BackendError, IrqMask, LineStatus, UsartBackend, UsartConfig};
+
+    use super::SerialSender;
+    use crate::framing::{FRAME_FLAG, MCTP_SERIAL_MAXMTU};
+    use crate::MctpSerialReceiver;
+
+    type Pipe = Rc<RefCell<VecDeque<u8>>>;
+
+    // One end of an in-memory null-modem cable: writes go to the peer's RX
+    // pipe, reads drain this end's RX pipe. `max_write` limits how much one
+    // write() accepts, to exercise partial writes.
+    #[derive(Clone)]
+    struct LoopbackUsart {
+        rx: Pipe,
+        tx: Pipe,
+        max_write: usize,
+        fail_writes: bool,
+    }
+
+    fn loopback_pair() -> (LoopbackUsart, LoopbackUsart) {
+        let a: Pipe = Rc::default();
+        let b: Pipe = Rc::default();
+        let end = |rx: &Pipe, tx: &Pipe| LoopbackUsart {
+            rx: rx.clone(),
+            tx: tx.clone(),
+            max_write: usize::MAX,
+            fail_writes: false,
+        };
+        (end(&a, &b), end(&b, &a))
+    }
+
+    impl UsartBackend for LoopbackUsart {
+        fn configure(&mut self, _config: UsartConfig) -> Result<(), BackendError> {
+            Ok(())
+        }
+
+        fn write(&mut self, data: &[u8]) -> Result<usize, BackendError> {
+            if self.fail_writes {
+                return Err(BackendError::Timeout);
+            }
+            let n = data.len().min(self.max_write);
+            self.tx.borrow_mut().extend(&data[..n]);
+            Ok(n)
+        }
+
+        fn read(&mut self, out: &mut [u8]) -> Result<usize, BackendError> {
+            self.try_read(out)
+        }
+
+        fn try_read(&mut self, out: &mut [u8]) -> Result<usize, BackendError> {
+            let mut rx = self.rx.borrow_mut();
+            if rx.is_empty() {
+                return Err(BackendError::WouldBlock);
+            }
+            let n = out.len().min(rx.len());
+            for (dst, src) in out.iter_mut().zip(rx.drain(..n)) {
+                *dst = src;
+            }
+            Ok(n)
+        }
+
+        fn line_status(&self) -> Result<LineStatus, BackendError> {
+            Ok(LineStatus(u8::from(!self.rx.borrow().is_empty())))
+        }
+
+        fn enable_interrupts(&mut self, _mask: IrqMask) -> Result<(), BackendError> {
+            Ok(())
+        }
+
+        fn disable_interrupts(&mut self, _mask: IrqMask) -> Result<(), BackendError> {
+            Ok(())
+        }
+    }
+
+    // Drain `port` through `rx` into `server`, as the platform's UART RX
+    // handler would. Returns the number of packets delivered.
+    fn deliver<S: mctp_lib::Sender, const N: usize>(
+        port: &mut LoopbackUsart,
+        rx: &mut MctpSerialReceiver,
+        server: &mut Server<S, N>,
+    ) -> usize {
+        let mut packets = 0;
+        rx.poll(port, |pkt| {
+            server.inbound(pkt).expect("inbound failed");
+            packets += 1;
+        })
+        .expect("UART read failed");
+        packets
+    }
+
+    // Two servers joined by a loopback UART: requests and responses cross
+    // as DSP0253 frames, including payload bytes that need escaping.
+    #[test]
+    fn loopback_roundtrip() {
+        const EID_A: u8 = 8;
+        const EID_B: u8 = 42;
+        const MSG_TYPE: u8 = 0x05; // SPDM
+
+        let (mut port_a, mut port_b) = loopback_pair();
+        let mut server_a: Server<_, 16> =
+            Server::new(Eid(EID_A), 0, SerialSender::new(port_a.clone()));
+        let mut server_b: Server<_, 16> =
+            Server::new(Eid(EID_B), 0, SerialSender::new(port_b.clone()));
+        let mut rx_a = MctpSerialReceiver::new();
+        let mut rx_b = MctpSerialReceiver::new();
+        let listener_b = server_b.listener(MSG_TYPE).unwrap();
+
+        // A -> B
+        let request = [0x10, 0x84, FRAME_FLAG, 0x7D, 0x00];
+        let req = server_a.req(EID_B).unwrap();
+        server_a
+            .send(Some(req), MSG_TYPE, None, None, false, &request)
+            .unwrap();
+        assert_eq!(deliver(&mut port_b, &mut rx_b, &mut server_b), 1);
+
+        let mut buf = [0u8; 64];
+        let meta = server_b
+            .try_recv(listener_b, &mut buf)
+            .expect("B received nothing");
+        assert_eq!(meta.remote_eid, EID_A);
+        assert_eq!(&buf[..meta.payload_size], request);
+
+        // B -> A
+        server_b
+            .send(
+                None,
+                MSG_TYPE,
+                Some(EID_A),
+                Some(meta.msg_tag),
+                false,
+                b"response",
+            )
+            .unwrap();
+        assert_eq!(deliver(&mut port_a, &mut rx_a, &mut server_a), 1);
+
+        let meta = server_a
+            .try_recv(req, &mut buf)
+            .expect("A received nothing");
+        assert_eq!(meta.remote_eid, EID_B);
+        assert_eq!(&buf[..meta.payload_size], b"response");
+        assert_eq!(rx_a.dropped() + rx_b.dropped(), 0);
+    }
+
+    // A message larger than the serial MTU goes out as several frames,
+    // written a few bytes at a time, and is reassembled by the far server.
+    #[test]
+    fn loopback_multi_packet_partial_writes() {
+        const MSG_TYPE: u8 = 0x7E;
+
+        let (mut port_a, mut slow_b) = loopback_pair();
+        slow_b.max_write = 7;
+        let mut server_a: Server<_, 16> = Server::new(Eid(8), 0, SerialSender::new(port_a.clone()));
+        let mut server_b: Server<_, 16> = Server::new(Eid(42), 0, SerialSender::new(slow_b));
+        let mut rx_a = MctpSerialReceiver::new();
+        let listener_a = server_a.listener(MSG_TYPE).unwrap();
+
+        let payload: Vec<u8> = (0..=255u8).cycle().take(MCTP_SERIAL_MAXMTU * 2).collect();
+        let req = server_b.req(8).unwrap();
+        server_b
+            .send(Some(req), MSG_TYPE, None, None, false, &payload)
+            .unwrap();
+        assert_eq!(deliver(&mut port_a, &mut rx_a, &mut server_a), 3);
+
+        let mut buf = [0u8; 1024];
+        let meta = server_a
+            .try_recv(listener_a, &mut buf)
+            .expect("A received nothing");
+        assert_eq!(&buf[..meta.payload_size], &payload[..]);
+    }
+
+    // A UART that rejects writes is reported as a transmit failure.
+    #[test]
+    fn write_failure() {
+        let (mut broken, _port_b) = loopback_pair();
+        broken.fail_writes = true;
+        let mut server: Server<_, 16> = Server::new(Eid(8), 0, SerialSender::new(broken));
+
+        let req = server.req(42).unwrap();
+        assert!(server
+            .send(Some(req), 1, None, None, false, b"short")
+            .is_err());
+    }
+}
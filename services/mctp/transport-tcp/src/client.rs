@@ -0,0 +1,211 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! In-process MCTP client running a server over a socket link.
+
+use std::cell::RefCell;
+use std::io;
+use std::time::{Duration, Instant};
+
+use mctp::Eid;
+use openprot_mctp_api::{Handle, MctpClient, MctpError, RecvMetadata, ResponseCode};
+use openprot_mctp_server::Server;
+
+use crate::link::Link;
+use crate::pump::TcpPump;
+use crate::sender::TcpSender;
+
+/// [`MctpClient`] backed by an `openprot_mctp_server::Server` whose only
+/// transport is a [`Link`].
+///
+/// The server runs in the caller's thread: `recv` pumps the socket until a
+/// message for the handle arrives or the timeout expires, so a `Stack`
+/// built on this client behaves like one talking to the MCTP server over
+/// IPC on target. `N` is the server's handle capacity.
+pub struct HostClient<const N: usize = 16> {
+    server: RefCell<Server<TcpSender, N>>,
+    pump: RefCell<TcpPump>,
+    start: Instant,
+}
+
+impl<const N: usize> HostClient<N> {
+    /// Run a server with endpoint ID `eid` over `link`.
+    pub fn new(eid: u8, link: Link) -> io::Result<Self> {
+        let sender = TcpSender::new(link.try_clone()?);
+        Ok(Self {
+            server: RefCell::new(Server::new(Eid(eid), 0, sender)),
+            pump: RefCell::new(TcpPump::new(link)),
+            start: Instant::now(),
+        })
+    }
+
+    /// Direct access to the server, e.g. to enable the control responder.
+    pub fn server(&self) -> &RefCell<Server<TcpSender, N>> {
+        &self.server
+    }
+
+    /// Wait up to `timeout` for inbound packets and deliver them to the
+    /// server.
+    ///
+    /// `None` waits forever. Returns the number of packets delivered.
+    /// Packets the server rejects are dropped. Fails if the link fails or
+    /// the peer closed it.
+    pub fn poll(&self, timeout: Option<Duration>) -> io::Result<usize> {
+        let mut server = self.server.borrow_mut();
+        let packets = self.pump.borrow_mut().wait(timeout, |pkt| {
+            let _ = server.inbound(pkt);
+        })?;
+        server.update(self.now_millis(), &mut []);
+        Ok(packets)
+    }
+
+    fn now_millis(&self) -> u64 {
+        self.start.elapsed().as_millis() as u64
+    }
+}
+
+impl<const N: usize> MctpClient for HostClient<N> {
+    fn req(&self, eid: u8) -> Result<Handle, MctpError> {
+        self.server.borrow_mut().req(eid)
+    }
+
+    fn listener(&self, msg_type: u8) -> Result<Handle, MctpError> {
+        self.server.borrow_mut().listener(msg_type)
+    }
+
+    fn get_eid(&self) -> u8 {
+        self.server.borrow().get_eid()
+    }
+
+    fn set_eid(&self, eid: u8) -> Result<(), MctpError> {
+        self.server.borrow_mut().set_eid(eid)
+    }
+
+    fn recv(
+        &self,
+        handle: Handle,
+        timeout_millis: u32,
+        buf: &mut [u8],
+    ) -> Result<RecvMetadata, MctpError> {
+        let deadline = (timeout_millis != 0)
+            .then(|| Instant::now() + Duration::from_millis(timeout_millis.into()));
+        loop {
+            if let Some(meta) = self.server.borrow_mut().try_recv(handle, buf) {
+                return Ok(meta);
+            }
+            // Wake up at least once a second so reassembly timeouts run
+            let mut wait = Duration::from_secs(1);
+            if let Some(deadline) = deadline {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                if remaining.is_zero() {
+                    return Err(MctpError::from_code(ResponseCode::TimedOut));
+                }
+                wait = wait.min(remaining);
+            }
+            self.poll(Some(wait)).map_err(link_error)?;
+        }
+    }
+
+    fn send(
+        &self,
+        handle: Option<Handle>,
+        msg_type: u8,
+        eid: Option<u8>,
+        tag: Option<u8>,
+        integrity_check: bool,
+        buf: &[u8],
+    ) -> Result<u8, MctpError> {
+        self.server
+            .borrow_mut()
+            .send(handle, msg_type, eid, tag, integrity_check, buf)
+    }
+
+    fn drop_handle(&self, handle: Handle) {
+        let _ = self.server.borrow_mut().unbind(handle);
+    }
+}
+
+/// A failed or closed link leaves nothing to retry.
+fn link_error(_: io::Error) -> MctpError {
+    MctpError::from_code(ResponseCode::InternalError)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{TcpListener, UdpSocket};
+    use std::thread;
+
+    use openprot_mctp_api::{MctpReqChannel, Stack};
+    use openprot_mctp_echo::{echo_once, prepare_listener, ECHO_EID, ECHO_MSG_TYPE};
+
+    use super::*;
+    use crate::link::MCTP_TCP_MTU;
+
+    const PEER_EID: u8 = 42;
+
+    // Run an echo responder on `link` in its own thread until one request
+    // has been answered.
+    fn spawn_echo(link: Link) -> thread::JoinHandle<()> {
+        thread::spawn(move || {
+            let stack = Stack::new(HostClient::<16>::new(ECHO_EID, link).unwrap());
+            let mut listener = prepare_listener(&stack).unwrap();
+            let mut buf = [0u8; 1024];
+            echo_once(&mut listener, &mut buf).unwrap();
+        })
+    }
+
+    fn round_trip(link: Link, payload: &[u8]) {
+        let stack = Stack::new(HostClient::<16>::new(PEER_EID, link).unwrap());
+        let mut req = stack.req(ECHO_EID, 5000).unwrap();
+        req.send(ECHO_MSG_TYPE, payload).unwrap();
+
+        let mut buf = [0u8; 1024];
+        let (meta, response) = req.recv(&mut buf).unwrap();
+        assert_eq!(meta.msg_type, ECHO_MSG_TYPE);
+        assert_eq!(meta.remote_eid, ECHO_EID);
+        assert_eq!(response, payload);
+    }
+
+    // Two clients joined over TCP: a multi-packet request and its echo
+    // cross the socket through the echo helpers.
+    #[test]
+    fn tcp_echo_round_trip() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let responder = spawn_echo(Link::connect(addr).unwrap());
+
+        let payload: Vec<u8> = (0..=255u8).cycle().take(MCTP_TCP_MTU * 3).collect();
+        round_trip(Link::accept(&listener).unwrap(), &payload);
+        responder.join().unwrap();
+    }
+
+    #[test]
+    fn udp_echo_round_trip() {
+        let a = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let b = UdpSocket::bind("127.0.0.1:0").unwrap();
+        a.connect(b.local_addr().unwrap()).unwrap();
+        b.connect(a.local_addr().unwrap()).unwrap();
+        let responder = spawn_echo(Link::Udp(a));
+
+        round_trip(Link::Udp(b), b"echo over udp");
+        responder.join().unwrap();
+    }
+
+    // With no peer traffic, recv times out; once the peer goes away it
+    // fails instead of waiting forever.
+    #[test]
+    fn recv_timeout_and_closed_link() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let peer = Link::connect(listener.local_addr().unwrap()).unwrap();
+        let client = HostClient::<16>::new(PEER_EID, Link::accept(&listener).unwrap()).unwrap();
+        let handle = client.listener(ECHO_MSG_TYPE).unwrap();
+
+        let mut buf = [0u8; 64];
+        let err = client.recv(handle, 20, &mut buf).unwrap_err();
+        assert!(err.is_timeout());
+
+        drop(peer);
+        let err = client.recv(handle, 0, &mut buf).unwrap_err();
+        assert_eq!(err.code, ResponseCode::InternalError);
+    }
+}
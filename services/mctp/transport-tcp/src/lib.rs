@@ -0,0 +1,34 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! # Development MCTP over TCP/UDP Transport Binding
+//!
+//! This crate provides a host-only transport binding that carries MCTP
+//! packets between two processes over localhost sockets. It is meant for
+//! development and testing without hardware and is not a DMTF binding.
+//!
+//! It implements [`mctp_lib::Sender`] for outbound packets, provides
+//! [`TcpPump`] for feeding inbound packets to `Server::inbound()`, and
+//! [`HostClient`], an [`MctpClient`](openprot_mctp_api::MctpClient) that
+//! runs an `openprot_mctp_server::Server` over a [`Link`] so that `Stack`
+//! users (echo, SPDM over MCTP) work unchanged on the host.
+//!
+//! ## Wire format
+//!
+//! - **TCP**: each packet is preceded by its length as a big-endian `u16`.
+//! - **UDP**: each datagram carries exactly one packet.
+//!
+//! Packets are raw MCTP packets, starting with the 4-byte transport header.
+//! The link is point-to-point, so there is no physical addressing.
+
+#![warn(missing_docs)]
+
+mod client;
+mod link;
+mod pump;
+mod sender;
+
+pub use client::HostClient;
+pub use link::{Link, MAX_PACKET_SIZE, MCTP_HEADER_SIZE, MCTP_TCP_MTU};
+pub use pump::TcpPump;
+pub use sender::TcpSender;
@@ -0,0 +1,105 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! Socket links between two host processes.
+
+use std::io::{self, Read, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs, UdpSocket};
+use std::time::Duration;
+
+/// Size of the MCTP transport header.
+pub const MCTP_HEADER_SIZE: usize = 4;
+/// Maximum MCTP payload per packet over this binding.
+pub const MCTP_TCP_MTU: usize = 255;
+/// Largest MCTP packet carried over this binding.
+pub const MAX_PACKET_SIZE: usize = MCTP_TCP_MTU + MCTP_HEADER_SIZE;
+/// Size of the length prefix in front of each packet on a TCP stream.
+pub(crate) const LENGTH_PREFIX_SIZE: usize = 2;
+
+/// A point-to-point socket carrying MCTP packets.
+#[derive(Debug)]
+pub enum Link {
+    /// A connected TCP stream; packets are length-prefixed.
+    Tcp(TcpStream),
+    /// A connected UDP socket; one packet per datagram.
+    Udp(UdpSocket),
+}
+
+impl Link {
+    /// Connect to a peer listening on `addr`.
+    pub fn connect(addr: impl ToSocketAddrs) -> io::Result<Self> {
+        Self::tcp(TcpStream::connect(addr)?)
+    }
+
+    /// Accept one peer connection on `listener`.
+    pub fn accept(listener: &TcpListener) -> io::Result<Self> {
+        let (stream, _) = listener.accept()?;
+        Self::tcp(stream)
+    }
+
+    /// Bind a UDP socket to `local` and send to and receive from `peer`
+    /// only.
+    pub fn udp(local: impl ToSocketAddrs, peer: impl ToSocketAddrs) -> io::Result<Self> {
+        let socket = UdpSocket::bind(local)?;
+        socket.connect(peer)?;
+        Ok(Self::Udp(socket))
+    }
+
+    /// Create an independent handle to the same socket.
+    ///
+    /// Used to give the sender and the inbound pump their own handle.
+    pub fn try_clone(&self) -> io::Result<Self> {
+        match self {
+            Self::Tcp(stream) => stream.try_clone().map(Self::Tcp),
+            Self::Udp(socket) => socket.try_clone().map(Self::Udp),
+        }
+    }
+
+    /// Send one MCTP packet to the peer.
+    pub fn send_packet(&mut self, pkt: &[u8]) -> io::Result<()> {
+        if !(MCTP_HEADER_SIZE..=MAX_PACKET_SIZE).contains(&pkt.len()) {
+            return Err(io::ErrorKind::InvalidInput.into());
+        }
+        match self {
+            Self::Tcp(stream) => {
+                let mut frame = [0u8; LENGTH_PREFIX_SIZE + MAX_PACKET_SIZE];
+                frame[..LENGTH_PREFIX_SIZE].copy_from_slice(&(pkt.len() as u16).to_be_bytes());
+                frame[LENGTH_PREFIX_SIZE..][..pkt.len()].copy_from_slice(pkt);
+                stream.write_all(&frame[..LENGTH_PREFIX_SIZE + pkt.len()])
+            }
+            Self::Udp(socket) => {
+                if socket.send(pkt)? != pkt.len() {
+                    return Err(io::ErrorKind::WriteZero.into());
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Set how long [`read`](Self::read) blocks; `None` blocks forever.
+    pub(crate) fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        match self {
+            Self::Tcp(stream) => stream.set_read_timeout(timeout),
+            Self::Udp(socket) => socket.set_read_timeout(timeout),
+        }
+    }
+
+    /// Read stream bytes (TCP) or one datagram (UDP) into `buf`.
+    ///
+    /// A TCP peer closing the connection is reported as `UnexpectedEof`.
+    pub(crate) fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Self::Tcp(stream) => match stream.read(buf)? {
+                0 => Err(io::ErrorKind::UnexpectedEof.into()),
+                n => Ok(n),
+            },
+            Self::Udp(socket) => socket.recv(buf),
+        }
+    }
+
+    fn tcp(stream: TcpStream) -> io::Result<Self> {
+        // Packets are small and latency matters more than throughput
+        stream.set_nodelay(true)?;
+        Ok(Self::Tcp(stream))
+    }
+}
@@ -0,0 +1,178 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! Host MCTP endpoint over the development TCP/UDP binding.
+//!
+//! Runs an MCTP server in this process, joined to one peer process over a
+//! socket, and either answers echo requests or sends them:
+//!
+//! ```text
+//! mctp_tcp_host --eid 8 --listen 127.0.0.1:7300 echo
+//! mctp_tcp_host --eid 42 --connect 127.0.0.1:7300 ping 8 5
+//! ```
+
+use std::io;
+use std::net::TcpListener;
+use std::process::ExitCode;
+use std::str::FromStr;
+use std::time::Instant;
+
+use openprot_mctp_api::{MctpReqChannel, Stack};
+use openprot_mctp_echo::{echo_once, prepare_listener_with_eid_and_timeout, ECHO_MSG_TYPE};
+use openprot_mctp_transport_tcp::{HostClient, Link};
+
+const USAGE: &str = "\
+usage: mctp_tcp_host --eid EID LINK COMMAND
+
+LINK:
+  --listen ADDR       accept one TCP connection on ADDR
+  --connect ADDR      connect to a peer listening on ADDR
+  --udp LOCAL PEER    exchange UDP datagrams between LOCAL and PEER
+
+COMMAND:
+  echo                answer echo requests until the link closes
+  ping EID [COUNT]    send COUNT (default 1) echo requests to EID";
+
+/// Timeout for each ping round trip.
+const PING_TIMEOUT_MS: u32 = 5000;
+
+enum Command {
+    Echo,
+    Ping { peer: u8, count: u32 },
+}
+
+enum LinkSpec {
+    Listen(String),
+    Connect(String),
+    Udp(String, String),
+}
+
+impl LinkSpec {
+    fn open(&self) -> io::Result<Link> {
+        match self {
+            Self::Listen(addr) => {
+                let listener = TcpListener::bind(addr)?;
+                println!("waiting for a peer on {addr}");
+                Link::accept(&listener)
+            }
+            Self::Connect(addr) => Link::connect(addr),
+            Self::Udp(local, peer) => Link::udp(local, peer),
+        }
+    }
+}
+
+struct Args {
+    eid: u8,
+    link: LinkSpec,
+    command: Command,
+}
+
+fn value(args: &mut impl Iterator<Item = String>, flag: &str) -> Result<String, String> {
+    args.next().ok_or(format!("{flag} needs a value"))
+}
+
+fn number<T: FromStr>(s: String) -> Result<T, String> {
+    s.parse().map_err(|_| format!("invalid number: {s}"))
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Args, String> {
+    let mut eid = None;
+    let mut link = None;
+    let mut command = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--eid" => eid = Some(number(value(&mut args, "--eid")?)?),
+            "--listen" => link = Some(LinkSpec::Listen(value(&mut args, "--listen")?)),
+            "--connect" => link = Some(LinkSpec::Connect(value(&mut args, "--connect")?)),
+            "--udp" => {
+                let local = value(&mut args, "--udp")?;
+                link = Some(LinkSpec::Udp(local, value(&mut args, "--udp")?));
+            }
+            "echo" => command = Some(Command::Echo),
+            "ping" => {
+                let peer = number(value(&mut args, "ping")?)?;
+                let count = args.next().map(number).transpose()?.unwrap_or(1);
+                command = Some(Command::Ping { peer, count });
+            }
+            _ => return Err(format!("unexpected argument: {arg}")),
+        }
+    }
+
+    Ok(Args {
+        eid: eid.ok_or("missing --eid")?,
+        link: link.ok_or("missing --listen, --connect or --udp")?,
+        command: command.ok_or("missing command")?,
+    })
+}
+
+fn echo(stack: &Stack<HostClient>, eid: u8) -> Result<(), String> {
+    let mut listener = prepare_listener_with_eid_and_timeout(stack, eid, 0)
+        .map_err(|e| format!("listener: {:?}", e.code))?;
+    let mut buf = [0u8; 1024];
+    loop {
+        echo_once(&mut listener, &mut buf).map_err(|e| format!("echo stopped: {:?}", e.code))?;
+    }
+}
+
+fn ping(stack: &Stack<HostClient>, peer: u8, count: u32) -> Result<(), String> {
+    let mut buf = [0u8; 1024];
+    for seq in 0..count {
+        let msg = format!("ping {seq}");
+        let start = Instant::now();
+        let mut req = stack
+            .req(peer, PING_TIMEOUT_MS)
+            .map_err(|e| format!("req: {:?}", e.code))?;
+        req.send(ECHO_MSG_TYPE, msg.as_bytes())
+            .map_err(|e| format!("send: {:?}", e.code))?;
+        let (meta, reply) = req
+            .recv(&mut buf)
+            .map_err(|e| format!("recv: {:?}", e.code))?;
+        if reply != msg.as_bytes() {
+            return Err(format!(
+                "EID {} replied with a different payload",
+                meta.remote_eid
+            ));
+        }
+        println!(
+            "reply from EID {}: seq={seq} time={:?}",
+            meta.remote_eid,
+            start.elapsed()
+        );
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{e}\n\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+
+    let client = match args
+        .link
+        .open()
+        .and_then(|link| HostClient::new(args.eid, link))
+    {
+        Ok(client) => client,
+        Err(e) => {
+            eprintln!("link: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let stack = Stack::new(client);
+    let result = match args.command {
+        Command::Echo => echo(&stack, args.eid),
+        Command::Ping { peer, count } => ping(&stack, peer, count),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
+}
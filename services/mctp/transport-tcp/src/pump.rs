@@ -0,0 +1,208 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! Inbound pump — reads MCTP packets from a [`Link`].
+//!
+//! Waits for data on the socket and hands each complete packet to a
+//! callback, typically `Server::inbound()`.
+
+use std::io;
+use std::time::Duration;
+
+use crate::link::{Link, LENGTH_PREFIX_SIZE, MAX_PACKET_SIZE, MCTP_HEADER_SIZE};
+
+/// Largest TCP frame: length prefix and packet.
+const MAX_FRAME_SIZE: usize = LENGTH_PREFIX_SIZE + MAX_PACKET_SIZE;
+
+/// Reads MCTP packets from a [`Link`].
+///
+/// On TCP, packets may arrive split across or merged within reads; the pump
+/// buffers partial frames between calls. A length prefix outside the valid
+/// packet range means the stream has lost framing and is reported as
+/// `InvalidData`. On UDP, datagrams that are not a valid packet are dropped
+/// and counted in [`dropped`](Self::dropped).
+#[derive(Debug)]
+pub struct TcpPump {
+    link: Link,
+    /// Buffered TCP stream bytes not yet forming a whole frame.
+    stream: [u8; MAX_FRAME_SIZE],
+    len: usize,
+    dropped: u32,
+}
+
+impl TcpPump {
+    /// Create a pump reading from `link`.
+    pub fn new(link: Link) -> Self {
+        Self {
+            link,
+            stream: [0; MAX_FRAME_SIZE],
+            len: 0,
+            dropped: 0,
+        }
+    }
+
+    /// Number of UDP datagrams dropped so far.
+    pub fn dropped(&self) -> u32 {
+        self.dropped
+    }
+
+    /// Wait up to `timeout` for data, calling `on_packet` for each complete
+    /// packet.
+    ///
+    /// `None` waits forever. Returns the number of packets delivered, which
+    /// is 0 if the timeout expired or only part of a frame arrived. Fails
+    /// if the socket fails or the TCP peer closed the connection.
+    pub fn wait(
+        &mut self,
+        timeout: Option<Duration>,
+        mut on_packet: impl FnMut(&[u8]),
+    ) -> io::Result<usize> {
+        // A zero read timeout is rejected by the socket
+        self.link
+            .set_read_timeout(timeout.map(|t| t.max(Duration::from_millis(1))))?;
+
+        match self.link {
+            Link::Tcp(_) => {
+                let n = match self.link.read(&mut self.stream[self.len..]) {
+                    Ok(n) => n,
+                    Err(e) if is_timeout(&e) => return Ok(0),
+                    Err(e) => return Err(e),
+                };
+                self.len += n;
+                self.drain(&mut on_packet)
+            }
+            Link::Udp(_) => {
+                // One byte of slack to detect oversized datagrams
+                let mut datagram = [0u8; MAX_PACKET_SIZE + 1];
+                let n = match self.link.read(&mut datagram) {
+                    Ok(n) => n,
+                    Err(e) if is_timeout(&e) => return Ok(0),
+                    Err(e) => return Err(e),
+                };
+                if !(MCTP_HEADER_SIZE..=MAX_PACKET_SIZE).contains(&n) {
+                    self.dropped += 1;
+                    return Ok(0);
+                }
+                on_packet(&datagram[..n]);
+                Ok(1)
+            }
+        }
+    }
+
+    /// Deliver every whole frame in the stream buffer.
+    fn drain(&mut self, on_packet: &mut impl FnMut(&[u8])) -> io::Result<usize> {
+        let mut packets = 0;
+        let mut start = 0;
+        while self.len - start >= LENGTH_PREFIX_SIZE {
+            let frame = &self.stream[start..self.len];
+            let pkt_len = usize::from(u16::from_be_bytes([frame[0], frame[1]]));
+            if !(MCTP_HEADER_SIZE..=MAX_PACKET_SIZE).contains(&pkt_len) {
+                return Err(io::ErrorKind::InvalidData.into());
+            }
+            let Some(pkt) = frame[LENGTH_PREFIX_SIZE..].get(..pkt_len) else {
+                break;
+            };
+            on_packet(pkt);
+            packets += 1;
+            start += LENGTH_PREFIX_SIZE + pkt_len;
+        }
+        self.stream.copy_within(start..self.len, 0);
+        self.len -= start;
+        Ok(packets)
+    }
+}
+
+fn is_timeout(e: &io::Error) -> bool {
+    matches!(
+        e.kind(),
+        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+    use std::net::{TcpListener, TcpStream, UdpSocket};
+    use std::time::Duration;
+
+    use super::*;
+
+    const PKT: [u8; 6] = [0x01, 0x09, 0x08, 0xC8, 0x05, 0x10];
+    const SHORT: Option<Duration> = Some(Duration::from_millis(50));
+
+    fn tcp_pair() -> (Link, TcpStream) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let peer = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        (Link::accept(&listener).unwrap(), peer)
+    }
+
+    fn collect(pump: &mut TcpPump) -> Vec<Vec<u8>> {
+        let mut packets = Vec::new();
+        pump.wait(SHORT, |pkt| packets.push(pkt.to_vec())).unwrap();
+        packets
+    }
+
+    #[test]
+    fn tcp_frames_split_and_merged() {
+        let (link, mut peer) = tcp_pair();
+        let mut pump = TcpPump::new(link);
+
+        let mut frame = vec![0x00, PKT.len() as u8];
+        frame.extend_from_slice(&PKT);
+
+        // Half a frame delivers nothing until the rest arrives
+        peer.write_all(&frame[..4]).unwrap();
+        assert!(collect(&mut pump).is_empty());
+        peer.write_all(&frame[4..]).unwrap();
+        assert_eq!(collect(&mut pump), [PKT.to_vec()]);
+
+        // Two frames in one write
+        let both = [frame.as_slice(), frame.as_slice()].concat();
+        peer.write_all(&both).unwrap();
+        let mut packets = Vec::new();
+        while packets.len() < 2 {
+            pump.wait(SHORT, |pkt| packets.push(pkt.to_vec())).unwrap();
+        }
+        assert_eq!(packets, [PKT.to_vec(), PKT.to_vec()]);
+    }
+
+    #[test]
+    fn tcp_timeout_bad_length_and_close() {
+        let (link, mut peer) = tcp_pair();
+        let mut pump = TcpPump::new(link);
+
+        assert_eq!(pump.wait(SHORT, |_| panic!("no data sent")).unwrap(), 0);
+        assert_eq!(pump.wait(Some(Duration::ZERO), |_| ()).unwrap(), 0);
+
+        peer.write_all(&[0x00, 0x02, 0x01, 0x09]).unwrap();
+        let err = pump.wait(SHORT, |_| ()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        let (link, peer) = tcp_pair();
+        let mut pump = TcpPump::new(link);
+        drop(peer);
+        let err = pump.wait(SHORT, |_| ()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn udp_datagrams() {
+        let peer = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let link = Link::udp("127.0.0.1:0", peer.local_addr().unwrap()).unwrap();
+        let Link::Udp(socket) = &link else {
+            unreachable!()
+        };
+        peer.connect(socket.local_addr().unwrap()).unwrap();
+        let mut pump = TcpPump::new(link);
+
+        peer.send(&PKT).unwrap();
+        assert_eq!(collect(&mut pump), [PKT.to_vec()]);
+
+        // Runt and oversized datagrams are dropped
+        peer.send(&PKT[..3]).unwrap();
+        peer.send(&[0u8; MAX_PACKET_SIZE + 1]).unwrap();
+        assert!(collect(&mut pump).is_empty());
+        assert!(collect(&mut pump).is_empty());
+        assert_eq!(pump.dropped(), 2);
+    }
+}
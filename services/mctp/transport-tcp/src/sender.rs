@@ -0,0 +1,51 @@
+// Licensed under the Apache-2.0 license
+// SPDX-License-Identifier: Apache-2.0
+
+//! Socket MCTP sender — outbound transport binding.
+//!
+//! Fragments outbound messages and writes each packet to a [`Link`].
+
+use mctp::Result;
+
+use crate::link::{Link, MAX_PACKET_SIZE, MCTP_TCP_MTU};
+
+/// Socket MCTP sender.
+///
+/// Implements `mctp_lib::Sender` over a [`Link`]. The link is
+/// point-to-point, so there is no destination address to resolve.
+#[derive(Debug)]
+pub struct TcpSender {
+    link: Link,
+}
+
+impl TcpSender {
+    /// Create a new sender writing to `link`.
+    pub fn new(link: Link) -> Self {
+        Self { link }
+    }
+}
+
+impl mctp_lib::Sender for TcpSender {
+    fn send_vectored(
+        &mut self,
+        mut fragmenter: mctp_lib::fragment::Fragmenter,
+        payload: &[&[u8]],
+    ) -> Result<mctp::Tag> {
+        loop {
+            let mut pkt = [0u8; MAX_PACKET_SIZE];
+            match fragmenter.fragment_vectored(payload, &mut pkt) {
+                mctp_lib::fragment::SendOutput::Packet(p) => {
+                    self.link
+                        .send_packet(p)
+                        .map_err(|_| mctp::Error::TxFailure)?;
+                }
+                mctp_lib::fragment::SendOutput::Complete { tag, .. } => break Ok(tag),
+                mctp_lib::fragment::SendOutput::Error { err, .. } => break Err(err),
+            }
+        }
+    }
+
+    fn get_mtu(&self) -> usize {
+        MCTP_TCP_MTU
+    }
+}
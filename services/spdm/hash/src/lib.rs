@@ -12,6 +12,10 @@
 //! the `CryptoClient` and calling its hash methods. It supports both
 //! stateless one-shot hashing and stateful streaming operations.
 //!
+//! The client is generic over `crypto_api::Transport`: on target it talks to
+//! the crypto server through `crypto_client_ipc::IpcTransport`; on the host
+//! `crypto_server::loopback::LoopbackTransport` runs the server in-process.
+//!
 //! ## Supported Algorithms
 //!
 //! - **SHA-384** (48-byte output) — Default per SPDM spec
@@ -22,10 +26,12 @@
 //! ### Stateless (One-Shot)
 //!
 //! ```rust,no_run
+//! use crypto_client::CryptoClient;
+//! use crypto_client_ipc::IpcTransport;
 //! use openprot_spdm_hash::SpdmCryptoHash;
 //! use spdm_lib::platform::hash::{SpdmHash, SpdmHashAlgoType};
 //!
-//! let mut hasher = SpdmCryptoHash::new(crypto_handle);
+//! let mut hasher = SpdmCryptoHash::new(CryptoClient::new(IpcTransport::new(crypto_handle)));
 //! let mut output = [0u8; 48];
 //! hasher.hash(SpdmHashAlgoType::SHA384, b"data", &mut output).unwrap();
 //! ```
@@ -33,10 +39,12 @@
 //! ### Stateful (Streaming)
 //!
 //! ```rust,no_run
+//! use crypto_client::CryptoClient;
+//! use crypto_client_ipc::IpcTransport;
 //! use openprot_spdm_hash::SpdmCryptoHash;
 //! use spdm_lib::platform::hash::{SpdmHash, SpdmHashAlgoType};
 //!
-//! let mut hasher = SpdmCryptoHash::new(crypto_handle);
+//! let mut hasher = SpdmCryptoHash::new(CryptoClient::new(IpcTransport::new(crypto_handle)));
 //!
 //! // Initialize
 //! hasher.init(SpdmHashAlgoType::SHA384, None).unwrap();
@@ -56,15 +64,16 @@
 #![no_std]
 #![warn(missing_docs)]
 
+use crypto_api::Transport;
 use crypto_client::{CryptoClient, Sha384Session, Sha512Session};
 use spdm_lib::platform::hash::{SpdmHash, SpdmHashAlgoType, SpdmHashError, SpdmHashResult};
 
 /// SPDM hash implementation using OpenPRoT crypto service.
 ///
-/// This struct wraps a `CryptoClient` handle and maintains internal state
+/// This struct wraps a `CryptoClient` and maintains internal state
 /// to support both stateless one-shot hashing and stateful streaming operations.
-pub struct SpdmCryptoHash {
-    crypto: CryptoClient,
+pub struct SpdmCryptoHash<T: Transport> {
+    crypto: CryptoClient<T>,
     state: HashState,
 }
 
@@ -78,22 +87,30 @@ enum HashState {
     Sha512(Sha512Session),
 }
 
-impl SpdmCryptoHash {
+impl<T: Transport> SpdmCryptoHash<T> {
     /// Create a new SPDM hash implementation using the crypto service.
     ///
     /// # Arguments
     ///
-    /// * `crypto_handle` — IPC channel handle for the crypto service
-    ///   (typically from your app's generated handle module, e.g., `handle::CRYPTO`)
-    pub const fn new(crypto_handle: u32) -> Self {
+    /// * `crypto` — client bound to the crypto service, e.g. over
+    ///   `IpcTransport::new(handle::CRYPTO)`
+    pub const fn new(crypto: CryptoClient<T>) -> Self {
         Self {
-            crypto: CryptoClient::new(crypto_handle),
+            crypto,
             state: HashState::Idle,
         }
     }
+
+    /// Unwrap the hasher, returning the client.
+    ///
+    /// An open streaming session is cancelled first.
+    pub fn into_inner(mut self) -> CryptoClient<T> {
+        self.reset();
+        self.crypto
+    }
 }
 
-impl SpdmHash for SpdmCryptoHash {
+impl<T: Transport> SpdmHash for SpdmCryptoHash<T> {
     fn hash(
         &mut self,
         hash_algo: SpdmHashAlgoType,
@@ -162,36 +179,38 @@ impl SpdmHash for SpdmCryptoHash {
         match &mut self.state {
             HashState::Idle => Err(SpdmHashError::PlatformError),
             HashState::Sha384(session) => session
-                .update(data)
+                .update(&mut self.crypto, data)
                 .map_err(|_| SpdmHashError::PlatformError),
             HashState::Sha512(session) => session
-                .update(data)
+                .update(&mut self.crypto, data)
                 .map_err(|_| SpdmHashError::PlatformError),
         }
     }
 
     fn finalize(&mut self, hash: &mut [u8]) -> SpdmHashResult<()> {
-        // Take the session out of state, replacing with Idle
-        let state = core::mem::replace(&mut self.state, HashState::Idle);
+        let required = match self.state {
+            HashState::Idle => return Err(SpdmHashError::PlatformError),
+            HashState::Sha384(_) => 48,
+            HashState::Sha512(_) => 64,
+        };
+        // Check before closing the session so a short buffer leaves it open.
+        if hash.len() < required {
+            return Err(SpdmHashError::BufferTooSmall);
+        }
 
-        match state {
+        // Take the session out of state, replacing with Idle
+        match core::mem::replace(&mut self.state, HashState::Idle) {
             HashState::Idle => Err(SpdmHashError::PlatformError),
             HashState::Sha384(session) => {
-                if hash.len() < 48 {
-                    return Err(SpdmHashError::BufferTooSmall);
-                }
                 let result = session
-                    .finalize()
+                    .finalize(&mut self.crypto)
                     .map_err(|_| SpdmHashError::PlatformError)?;
                 hash[..48].copy_from_slice(&result);
                 Ok(())
             }
             HashState::Sha512(session) => {
-                if hash.len() < 64 {
-                    return Err(SpdmHashError::BufferTooSmall);
-                }
                 let result = session
-                    .finalize()
+                    .finalize(&mut self.crypto)
                     .map_err(|_| SpdmHashError::PlatformError)?;
                 hash[..64].copy_from_slice(&result);
                 Ok(())
@@ -200,8 +219,13 @@ impl SpdmHash for SpdmCryptoHash {
     }
 
     fn reset(&mut self) {
-        // Simply drop the current session and return to Idle
-        self.state = HashState::Idle;
+        // Cancel the current session so the server releases its backend.
+        // Best effort: there is nothing useful to do if the cancel fails.
+        let _ = match core::mem::replace(&mut self.state, HashState::Idle) {
+            HashState::Idle => Ok(()),
+            HashState::Sha384(session) => session.cancel(&mut self.crypto),
+            HashState::Sha512(session) => session.cancel(&mut self.crypto),
+        };
     }
 
     fn algo(&self) -> SpdmHashAlgoType {
@@ -216,23 +240,173 @@ impl SpdmHash for SpdmCryptoHash {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crypto_api::{TransportError, MAX_PAYLOAD_SIZE};
+    use crypto_loopback::{rustcrypto_loopback, CountingEntropy, RustCryptoLoopback, SESSIONS};
+    use hex_literal::hex;
+
+    /// FIPS 180-2 two-block message (896 bits)
+    const MSG_896: &[u8] = b"abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmn\
+                             hijklmnoijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu";
+    const SHA384_ABC: [u8; 48] = hex!(
+        "cb00753f45a35e8bb5a03d699ac65007272c32ab0eded1631a8b605a43ff5bed"
+        "8086072ba1e7cc2358baeca134c825a7"
+    );
+    const SHA512_ABC: [u8; 64] = hex!(
+        "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a"
+        "2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f"
+    );
+    const SHA384_896: [u8; 48] = hex!(
+        "09330c33f71147e83d192fc782cd1b4753111b173b3b05d22fa08086e3b0f712"
+        "fcc7c71a557e2db966c3e9fa91746039"
+    );
+    const SHA512_896: [u8; 64] = hex!(
+        "8e959b75dae313da8cf4f72814fc143f8f7779c6eb9f7fa17299aeadb6889018"
+        "501d289e4900f7e4331b99dec4b5433ac7d329eeb6dd26545e96e55b874be909"
+    );
+
+    /// Transport with no server behind it
+    struct Disconnected;
+
+    impl Transport for Disconnected {
+        fn transact(&mut self, _req: &[u8], _resp: &mut [u8]) -> Result<usize, TransportError> {
+            Err(TransportError::Failed)
+        }
+    }
+
+    fn hasher() -> SpdmCryptoHash<Disconnected> {
+        SpdmCryptoHash::new(CryptoClient::new(Disconnected))
+    }
+
+    /// Hasher backed by an in-process RustCrypto crypto server
+    fn loopback_hasher() -> SpdmCryptoHash<RustCryptoLoopback<CountingEntropy>> {
+        let transport = rustcrypto_loopback(CountingEntropy::new(0), b"spdm hash").unwrap();
+        SpdmCryptoHash::new(CryptoClient::new(transport))
+    }
 
     #[test]
     fn test_struct_creation() {
-        let _hasher = SpdmCryptoHash::new(42);
+        let _hasher = hasher();
     }
 
     #[test]
     fn test_default_algo() {
-        let hasher = SpdmCryptoHash::new(42);
+        let hasher = hasher();
         assert_eq!(hasher.algo(), SpdmHashAlgoType::SHA384);
     }
 
     #[test]
     fn test_reset_idempotent() {
-        let mut hasher = SpdmCryptoHash::new(42);
+        let mut hasher = hasher();
         hasher.reset();
         hasher.reset();
         assert_eq!(hasher.algo(), SpdmHashAlgoType::SHA384);
     }
+
+    #[test]
+    fn test_errors_without_server() {
+        let mut hasher = hasher();
+        let mut out = [0u8; 64];
+        assert!(hasher
+            .hash(SpdmHashAlgoType::SHA512, b"abc", &mut out)
+            .is_err());
+        assert!(hasher.init(SpdmHashAlgoType::SHA384, None).is_err());
+        assert!(hasher.update(b"abc").is_err());
+        assert!(hasher.finalize(&mut out).is_err());
+    }
+
+    #[test]
+    fn test_one_shot_sha384_sha512() {
+        let mut hasher = loopback_hasher();
+        let mut out = [0u8; 64];
+        hasher
+            .hash(SpdmHashAlgoType::SHA384, b"abc", &mut out)
+            .unwrap();
+        assert_eq!(out[..48], SHA384_ABC);
+        hasher
+            .hash(SpdmHashAlgoType::SHA512, b"abc", &mut out)
+            .unwrap();
+        assert_eq!(out, SHA512_ABC);
+
+        let mut short = [0u8; 48];
+        assert_eq!(
+            hasher.hash(SpdmHashAlgoType::SHA512, b"abc", &mut short),
+            Err(SpdmHashError::BufferTooSmall)
+        );
+    }
+
+    #[test]
+    fn test_streaming_sha384_sha512() {
+        let mut hasher = loopback_hasher();
+        let (head, tail) = MSG_896.split_at(40);
+
+        hasher.init(SpdmHashAlgoType::SHA384, Some(head)).unwrap();
+        assert_eq!(hasher.algo(), SpdmHashAlgoType::SHA384);
+        hasher.update(tail).unwrap();
+        let mut out = [0u8; 48];
+        hasher.finalize(&mut out).unwrap();
+        assert_eq!(out, SHA384_896);
+
+        hasher.init(SpdmHashAlgoType::SHA512, None).unwrap();
+        assert_eq!(hasher.algo(), SpdmHashAlgoType::SHA512);
+        for chunk in MSG_896.chunks(7) {
+            hasher.update(chunk).unwrap();
+        }
+        let mut out = [0u8; 64];
+        hasher.finalize(&mut out).unwrap();
+        assert_eq!(out, SHA512_896);
+    }
+
+    #[test]
+    fn test_streaming_rejects_misuse() {
+        let mut hasher = loopback_hasher();
+        assert!(hasher.update(b"abc").is_err());
+
+        hasher.init(SpdmHashAlgoType::SHA512, Some(b"abc")).unwrap();
+        assert!(hasher.init(SpdmHashAlgoType::SHA384, None).is_err());
+
+        // A short buffer leaves the session open for a retry.
+        let mut short = [0u8; 48];
+        assert_eq!(
+            hasher.finalize(&mut short),
+            Err(SpdmHashError::BufferTooSmall)
+        );
+        let mut out = [0u8; 64];
+        hasher.finalize(&mut out).unwrap();
+        assert_eq!(out, SHA512_ABC);
+        assert!(hasher.finalize(&mut out).is_err());
+    }
+
+    #[test]
+    fn test_reset_releases_server_session() {
+        let mut hasher = loopback_hasher();
+        // More sessions than the server has backends: each reset must
+        // cancel its session or the server runs out.
+        for _ in 0..=SESSIONS {
+            hasher
+                .init(SpdmHashAlgoType::SHA384, Some(b"partial"))
+                .unwrap();
+            hasher.reset();
+        }
+        let mut out = [0u8; 48];
+        hasher
+            .hash(SpdmHashAlgoType::SHA384, b"abc", &mut out)
+            .unwrap();
+        assert_eq!(out, SHA384_ABC);
+    }
+
+    #[test]
+    fn test_large_one_shot_matches_streaming() {
+        let mut hasher = loopback_hasher();
+        let data = [0xa5u8; 2 * MAX_PAYLOAD_SIZE + 100];
+        let mut one_shot = [0u8; 48];
+        hasher
+            .hash(SpdmHashAlgoType::SHA384, &data, &mut one_shot)
+            .unwrap();
+
+        hasher.init(SpdmHashAlgoType::SHA384, None).unwrap();
+        hasher.update(&data).unwrap();
+        let mut streamed = [0u8; 48];
+        hasher.finalize(&mut streamed).unwrap();
+        assert_eq!(one_shot, streamed);
+    }
 }
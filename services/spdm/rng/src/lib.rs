@@ -3,78 +3,149 @@
 
 //! SPDM RNG Implementation
 //!
-//! Provides random number generation for SPDM protocol operations by
-//! delegating to the OpenPRoT crypto service via IPC.
+//! Provides random number generation for SPDM protocol operations on top of
+//! any `openprot_hal_blocking::rng::Drbg` implementation, typically the
+//! OpenPRoT crypto service client.
 //!
 //! ## Architecture
 //!
 //! This crate implements the `SpdmRng` trait from spdm-lib by wrapping
-//! the `CryptoClient` and calling its `get_random_bytes()` method, which
-//! uses ChaCha20 CSPRNG seeded from system entropy.
+//! a DRBG and calling its `fill_bytes()` method. The usual DRBG is a
+//! `CryptoClient`, which forwards to the crypto server's DRBG over any
+//! `crypto_api::Transport`; a local DRBG such as the RustCrypto `HmacDrbg`
+//! can be used instead.
 //!
 //! ## Usage
 //!
 //! ```rust,no_run
+//! use crypto_client::CryptoClient;
+//! use crypto_client_ipc::IpcTransport;
 //! use openprot_spdm_rng::SpdmCryptoRng;
 //! use spdm_lib::platform::rng::SpdmRng;
 //!
-//! let mut rng = SpdmCryptoRng::new(crypto_handle);
+//! let mut rng = SpdmCryptoRng::new(CryptoClient::new(IpcTransport::new(crypto_handle)));
 //! let mut buffer = [0u8; 32];
 //! rng.get_random_bytes(&mut buffer).unwrap();
 //! ```
+//!
+//! With a local DRBG:
+//!
+//! ```rust,no_run
+//! use openprot_spdm_rng::SpdmCryptoRng;
+//!
+//! let drbg = HmacDrbgSha256::new(trng, b"spdm").unwrap();
+//! let mut rng = SpdmCryptoRng::from_drbg(drbg);
+//! ```
 
 #![no_std]
 #![warn(missing_docs)]
 
+use crypto_api::Transport;
 use crypto_client::CryptoClient;
-use spdm_lib::platform::rng::{SpdmRng, SpdmRngResult};
+use openprot_hal_blocking::rng::Drbg;
+use spdm_lib::platform::rng::{SpdmRng, SpdmRngError, SpdmRngResult};
 
-/// SPDM RNG implementation using OpenPRoT crypto service.
+/// SPDM RNG implementation on top of a DRBG.
 ///
-/// This struct wraps a `CryptoClient` handle and delegates all RNG
-/// operations to the centralized crypto service via IPC.
-pub struct SpdmCryptoRng {
-    crypto: CryptoClient,
+/// Usually this wraps a `CryptoClient` and delegates all RNG operations to
+/// the centralized crypto service.
+pub struct SpdmCryptoRng<R> {
+    drbg: R,
 }
 
-impl SpdmCryptoRng {
+impl<T: Transport> SpdmCryptoRng<CryptoClient<T>> {
     /// Create a new SPDM RNG using the crypto service.
     ///
     /// # Arguments
     ///
-    /// * `crypto_handle` — IPC channel handle for the crypto service
-    ///   (typically from your app's generated handle module, e.g., `handle::CRYPTO`)
-    pub const fn new(crypto_handle: u32) -> Self {
-        Self {
-            crypto: CryptoClient::new(crypto_handle),
-        }
+    /// * `crypto` — client bound to the crypto service, e.g. over
+    ///   `IpcTransport::new(handle::CRYPTO)`
+    pub const fn new(crypto: CryptoClient<T>) -> Self {
+        Self { drbg: crypto }
+    }
+}
+
+impl<R: Drbg> SpdmCryptoRng<R> {
+    /// Create a new SPDM RNG on top of an instantiated DRBG.
+    pub const fn from_drbg(drbg: R) -> Self {
+        Self { drbg }
+    }
+
+    /// Unwrap the SPDM RNG, returning the DRBG.
+    pub fn into_inner(self) -> R {
+        self.drbg
     }
 }
 
-impl SpdmRng for SpdmCryptoRng {
+impl<R: Drbg> SpdmRng for SpdmCryptoRng<R> {
     fn get_random_bytes(&mut self, buf: &mut [u8]) -> SpdmRngResult<()> {
-        self.crypto
-            .get_random_bytes(buf)
-            .map_err(|_| spdm_lib::platform::rng::SpdmRngError::InvalidSize)
+        self.drbg
+            .fill_bytes(buf)
+            .map_err(|_| SpdmRngError::InvalidSize)
     }
 
     fn generate_random_number(&mut self, random_number: &mut [u8]) -> SpdmRngResult<()> {
         // Both methods are identical in spdm-lib: fill a buffer with random bytes
-        self.crypto
-            .get_random_bytes(random_number)
-            .map_err(|_| spdm_lib::platform::rng::SpdmRngError::InvalidSize)
+        self.drbg
+            .fill_bytes(random_number)
+            .map_err(|_| SpdmRngError::InvalidSize)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crypto_api::{TransportError, MAX_PAYLOAD_SIZE};
+    use crypto_loopback::{rustcrypto_loopback, CountingEntropy};
+    use openprot_platform_mock::rng::CountingDrbg;
+    use platform_rustcrypto_impl::HmacDrbgSha256;
 
-    // Note: These tests require a running crypto service, so they're
-    // integration tests that would run in a QEMU environment
+    /// Transport with no server behind it
+    struct Disconnected;
+
+    impl Transport for Disconnected {
+        fn transact(&mut self, _req: &[u8], _resp: &mut [u8]) -> Result<usize, TransportError> {
+            Err(TransportError::Failed)
+        }
+    }
 
     #[test]
     fn test_struct_creation() {
-        let _rng = SpdmCryptoRng::new(42);
+        let mut rng = SpdmCryptoRng::new(CryptoClient::new(Disconnected));
+        let mut buf = [0u8; 4];
+        assert!(rng.get_random_bytes(&mut buf).is_err());
+    }
+
+    #[test]
+    fn test_rng_over_local_drbg() {
+        let mut rng = SpdmCryptoRng::from_drbg(CountingDrbg::new(8));
+        let mut buf = [0u8; 4];
+        assert!(rng.get_random_bytes(&mut buf).is_ok());
+        assert_eq!(buf, [0, 1, 2, 3]);
+        assert!(rng.generate_random_number(&mut buf).is_ok());
+        assert_eq!(buf, [4, 5, 6, 7]);
+        assert!(rng.get_random_bytes(&mut buf).is_err());
+    }
+
+    #[test]
+    fn test_rng_over_loopback_service() {
+        let transport = rustcrypto_loopback(CountingEntropy::new(7), b"spdm rng").unwrap();
+        let mut rng = SpdmCryptoRng::new(CryptoClient::new(transport));
+        let mut reference = HmacDrbgSha256::new(CountingEntropy::new(7), b"spdm rng").unwrap();
+
+        let mut remote = [0u8; 48];
+        rng.get_random_bytes(&mut remote).unwrap();
+        let mut local = [0u8; 48];
+        reference.generate(&mut local, &[]).unwrap();
+        assert_eq!(remote, local);
+
+        // Larger than one payload: the client splits it into two requests.
+        let mut remote = [0u8; MAX_PAYLOAD_SIZE + 32];
+        rng.generate_random_number(&mut remote).unwrap();
+        let mut local = [0u8; MAX_PAYLOAD_SIZE + 32];
+        let (head, tail) = local.split_at_mut(MAX_PAYLOAD_SIZE);
+        reference.generate(head, &[]).unwrap();
+        reference.generate(tail, &[]).unwrap();
+        assert_eq!(remote, local);
     }
 }